@@ -0,0 +1,83 @@
+//! Board hot path benchmarks: move generation, make/unmake, attack
+//! detection and evaluation, measured directly on the `ChessBoard` so the
+//! UCI string handling of `GameState` doesn't hide regressions.
+
+use divan::{Bencher, black_box};
+use enrust::game_state::ChessBoard;
+use enrust::game_state::Color;
+use enrust::game_state::GameState;
+
+fn main() {
+    divan::main();
+}
+
+const POSITIONS: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", // Initial position
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", // Kiwipete
+    "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1", // Position 4
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",                // Rook endgame
+    "8/8/8/4k3/8/8/4P3/4K3 w - - 0 1",                          // King and pawn
+    "8/8/8/8/8/2k5/8/K6R w - - 0 1",                            // King and rook
+];
+
+fn setup_board(fen: &str) -> (ChessBoard, Color) {
+    let mut game = GameState::new(Some(16));
+    assert!(game.set_fen_position(fen), "Failed to set FEN: {}", fen);
+    (game.get_chess_board().clone(), game.side_to_move())
+}
+
+#[divan::bench(args = POSITIONS)]
+fn generate_moves(bencher: Bencher, fen: &str) {
+    let (mut board, side) = setup_board(fen);
+
+    bencher.bench_local(|| black_box(board.generate_moves(side)));
+}
+
+#[divan::bench(args = POSITIONS)]
+fn make_unmake_move(bencher: Bencher, fen: &str) {
+    let (mut board, side) = setup_board(fen);
+    let moves = board.generate_moves(side);
+
+    bencher
+        .counter(divan::counter::ItemsCount::new(moves.len()))
+        .bench_local(|| {
+            for mv in &moves {
+                board.make_move(mv);
+                board.unmake_move(mv);
+            }
+        });
+}
+
+#[divan::bench(args = POSITIONS)]
+fn copy_make(bencher: Bencher, fen: &str) {
+    let (board, side) = setup_board(fen);
+    let moves = board.clone().generate_moves(side);
+
+    bencher
+        .counter(divan::counter::ItemsCount::new(moves.len()))
+        .bench_local(|| {
+            for mv in &moves {
+                black_box(board.copy_make(mv));
+            }
+        });
+}
+
+#[divan::bench(args = POSITIONS)]
+fn is_square_attacked(bencher: Bencher, fen: &str) {
+    let (board, side) = setup_board(fen);
+
+    bencher
+        .counter(divan::counter::ItemsCount::new(64usize))
+        .bench_local(|| {
+            for square in 0..64 {
+                black_box(board.is_square_attacked(square, side.opposite()));
+            }
+        });
+}
+
+#[divan::bench(args = POSITIONS)]
+fn evaluate(bencher: Bencher, fen: &str) {
+    let (board, _) = setup_board(fen);
+
+    bencher.bench_local(|| black_box(board.evaluate()));
+}
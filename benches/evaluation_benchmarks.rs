@@ -0,0 +1,105 @@
+//! Evaluation hot path benchmarks.
+//!
+//! Compare the scalar reference against the dispatched implementations,
+//! which use AVX2 when built with `--features simd`:
+//!
+//! ```text
+//! cargo bench --bench evaluation_benchmarks
+//! cargo bench --bench evaluation_benchmarks --features simd
+//! ```
+
+use std::sync::Arc;
+
+use divan::{Bencher, black_box};
+use enrust::game_state::ChessBoard;
+use enrust::game_state::Color;
+use enrust::game_state::GameState;
+use enrust::game_state::board::evaluation::nnue::{FEATURES, Network, simd};
+
+fn main() {
+    divan::main();
+}
+
+fn pseudo_random_values(count: usize, seed: u64) -> Vec<i16> {
+    let mut state = seed | 1;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 512) as i16 - 256
+        })
+        .collect()
+}
+
+fn setup_game(fen: &str, hidden_size: usize) -> ChessBoard {
+    let network = Network::new(
+        hidden_size,
+        pseudo_random_values(FEATURES * hidden_size, 1),
+        pseudo_random_values(hidden_size, 2),
+        pseudo_random_values(2 * hidden_size, 3),
+        0,
+    )
+    .unwrap();
+
+    let mut game = GameState::new(Some(16));
+    assert!(game.set_fen_position(fen), "Failed to set FEN: {}", fen);
+    let mut board = game.get_chess_board().clone();
+    board.set_network(Some(Arc::new(network)));
+    board
+}
+
+#[divan::bench(args = [256, 512, 1024])]
+fn add_weights_scalar(bencher: Bencher, size: usize) {
+    let mut values = pseudo_random_values(size, 4);
+    let weights = pseudo_random_values(size, 5);
+
+    bencher.bench_local(|| simd::scalar::add_weights(black_box(&mut values), &weights));
+}
+
+#[divan::bench(args = [256, 512, 1024])]
+fn add_weights_dispatched(bencher: Bencher, size: usize) {
+    let mut values = pseudo_random_values(size, 4);
+    let weights = pseudo_random_values(size, 5);
+
+    bencher.bench_local(|| simd::add_weights(black_box(&mut values), &weights));
+}
+
+#[divan::bench(args = [256, 512, 1024])]
+fn clipped_dot_scalar(bencher: Bencher, size: usize) {
+    let values = pseudo_random_values(size, 6);
+    let weights: Vec<i16> = pseudo_random_values(size, 7)
+        .iter()
+        .map(|w| w / 8)
+        .collect();
+
+    bencher.bench_local(|| simd::scalar::clipped_dot(black_box(&values), &weights));
+}
+
+#[divan::bench(args = [256, 512, 1024])]
+fn clipped_dot_dispatched(bencher: Bencher, size: usize) {
+    let values = pseudo_random_values(size, 6);
+    let weights: Vec<i16> = pseudo_random_values(size, 7)
+        .iter()
+        .map(|w| w / 8)
+        .collect();
+
+    bencher.bench_local(|| simd::clipped_dot(black_box(&values), &weights));
+}
+
+#[divan::bench]
+fn nnue_make_unmake_evaluate(bencher: Bencher) {
+    let mut board = setup_game(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        256,
+    );
+    let moves = board.generate_moves(Color::White);
+
+    bencher.bench_local(|| {
+        for mv in &moves {
+            board.make_move(mv);
+            black_box(board.evaluate());
+            board.unmake_move(mv);
+        }
+    });
+}
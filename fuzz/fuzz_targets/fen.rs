@@ -0,0 +1,25 @@
+//! Loads arbitrary text as a FEN, then looks at the position the way the
+//! UCI commands do.
+
+#![no_main]
+
+use enrust::game_state::GameState;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(fen) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let mut game = GameState::new(None);
+    if game.set_fen_position(fen) {
+        let moves = game.generate_moves();
+        game.to_fen();
+        game.position_dump(false);
+        game.evaluation_report();
+        if let Some(mv) = moves.first() {
+            game.make_move(&mv.to_string());
+            game.undo_last_move();
+        }
+    }
+});
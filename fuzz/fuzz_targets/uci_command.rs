@@ -0,0 +1,39 @@
+//! Feeds arbitrary lines to the UCI command dispatcher.
+//!
+//! Commands touching files or taking too long are skipped: `bench`, `tt`,
+//! `quit`, the file and learning options and hash resizes. Every line is
+//! followed by `stop`, so searches end right away.
+
+#![no_main]
+
+use std::sync::Mutex;
+
+use enrust::game_state::{GameState, UciSession};
+use libfuzzer_sys::fuzz_target;
+
+static SESSION: Mutex<Option<UciSession>> = Mutex::new(None);
+
+/// Whether a command line may run inside the fuzzer.
+fn is_allowed(line: &str) -> bool {
+    let mut tokens = line.split_whitespace();
+    match tokens.next() {
+        Some("bench" | "tt" | "quit") => false,
+        Some("setoption") => !["File", "Learning", "Hash"]
+            .iter()
+            .any(|name| line.contains(name)),
+        _ => true,
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let mut session = SESSION.lock().unwrap();
+    let session = session.get_or_insert_with(|| UciSession::new(GameState::new(Some(1))));
+    for line in input.lines().filter(|line| is_allowed(line)) {
+        session.handle_command(line);
+        session.handle_command("stop");
+    }
+});
@@ -0,0 +1,38 @@
+//! Parses arbitrary text as a UCI move in positions with castling, en
+//! passant and promotions available.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use enrust::game_state::GameState;
+use enrust::game_state::board::moves::Move;
+use libfuzzer_sys::fuzz_target;
+
+const POSITIONS: [&str; 3] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    "4k3/1P6/8/8/8/8/6p1/4K3 b - - 0 1",
+];
+
+fuzz_target!(|data: &[u8]| {
+    static GAMES: OnceLock<Vec<GameState>> = OnceLock::new();
+    let games = GAMES.get_or_init(|| {
+        POSITIONS
+            .iter()
+            .map(|fen| {
+                let mut game = GameState::new(None);
+                game.set_fen_position(fen);
+                game
+            })
+            .collect()
+    });
+
+    let Ok(notation) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    for game in games {
+        Move::parse_algebraic_move(game.get_chess_board(), notation);
+    }
+});
@@ -5,24 +5,84 @@
 //! integration for chess engine communication.
 
 use std::io::{self, Write};
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+pub mod affinity;
+pub mod analysis;
+pub mod annotate;
+pub mod bench;
 pub mod board;
+pub mod book;
+pub mod eco;
+pub mod experience;
+#[cfg(feature = "interop")]
+pub mod interop;
+pub mod mate_solver;
+pub mod options;
+pub mod permanent_brain;
+pub mod personality;
+pub mod pgn;
+pub mod puzzles;
+pub mod readiness;
+pub mod replay;
+pub mod search_handle;
+pub mod selfplay;
+pub mod test_suites;
+pub mod topology;
 pub mod uci;
+pub mod version;
 pub use board::CastlingRights;
 pub use board::ChessBoard;
-pub use board::moves::Move;
-pub use board::piece::{Color, Piece};
-pub use board::search::{DepthFirst, Search};
+pub use board::moves::{Move, MoveRecord};
+pub use board::piece::{Color, Piece, PieceType};
+pub use board::score::Score;
+pub use board::search::{DepthFirst, Search, SearchParams};
 pub use board::transposition_table::{TranspositionTable, Zobrist};
 
-use board::search::MinimaxAlphaBeta;
+use analysis::{AnalysisConfig, AnalysisResult};
+use board::evaluation::kpk;
+use board::evaluation::nnue::{self, Network};
+use board::evaluation::params::EvalParams;
+use board::evaluation::{CompositeEvaluator, EvaluationTerms, GamePhase, TOTAL_PHASE};
+use board::rules::Outcome;
+use board::search::trace::DEFAULT_TRACE_DEPTH;
+use board::search::{InfoReporter, MinimaxAlphaBeta, SearchClock, SearchTrace};
+use eco::Opening;
+use experience::Experience;
+use options::EngineOptions;
+use permanent_brain::PermanentBrain;
+use personality::Personality;
+use pgn::GameResult;
+use readiness::ReadinessGate;
+use search_handle::{SearchHandle, SearchResult};
 
 use crate::game_state::board::search::IterativeDeepening;
 
+/// Default time, in milliseconds, kept aside for each move to cover the
+/// communication delay with the GUI.
+pub const DEFAULT_MOVE_OVERHEAD: u64 = 10;
+
+/// Default depth, in plies, the search is capped at.
+pub const DEFAULT_MAX_DEPTH: u8 = 5;
+
+/// Default number of moves, from the start of the game, played with
+/// opening variety when it is enabled.
+pub const DEFAULT_VARIETY_MOVES: u64 = 10;
+
+/// Number of moves the remaining time is split over in sudden death.
+const DEFAULT_MOVES_TO_GO: u64 = 20;
+
+/// Largest fraction of the remaining time spent on a single move.
+const MAX_TIME_FRACTION: f64 = 0.8;
+
+/// Shortest time spent on a move, even with the clock almost out.
+const MIN_THINK_TIME: u64 = 10;
+
 /// Configuration for search parameters and time control.
 ///
 /// Used to configure the engine's search behavior according to UCI protocol
@@ -41,7 +101,7 @@ pub struct SearchConfiguration {
     pub movestogo: Option<u64>,
     /// Time limit for this move in milliseconds
     pub movetime: Option<u64>,
-    /// Maximum search depth
+    /// Maximum search depth, capped by the `MaxDepth` option
     pub depth: Option<u64>,
     /// Maximum number of nodes to search
     pub nodes: Option<u64>,
@@ -49,10 +109,16 @@ pub struct SearchConfiguration {
     pub infinite: bool,
     /// Restrict search to specific moves
     pub searchmoves: Option<Vec<Move>>,
+    /// Root moves left out of the search, to find the best move other than
+    /// them
+    pub avoidmoves: Vec<Move>,
     /// Enable pondering (thinking during opponent's time)
     pub ponder: bool,
     /// Search for a mate in specified number of moves
     pub mate: Option<u32>,
+    /// Time in milliseconds kept aside for each move to cover the
+    /// communication delay with the GUI
+    pub move_overhead: u64,
 }
 
 impl Default for SearchConfiguration {
@@ -77,8 +143,10 @@ impl SearchConfiguration {
             nodes: None,
             infinite: false,
             searchmoves: None,
+            avoidmoves: Vec::new(),
             ponder: false,
             mate: None,
+            move_overhead: DEFAULT_MOVE_OVERHEAD,
         }
     }
 
@@ -86,24 +154,33 @@ impl SearchConfiguration {
     ///
     /// Implements basic time management strategy:
     /// - Uses `movetime` if specified directly
-    /// - Otherwise divides remaining time by moves to go
+    /// - Otherwise divides remaining time by moves to go, spending up to a
+    ///   quarter more in middlegames and up to a quarter less in endgames.
+    ///   Without `movestogo` (or with `movestogo 0`) the game is sudden
+    ///   death and the time is split over [`DEFAULT_MOVES_TO_GO`] moves
     /// - Adds increment if available
+    /// - Keeps the move overhead aside and never spends more than
+    ///   [`MAX_TIME_FRACTION`] of what is left, but always at least
+    ///   [`MIN_THINK_TIME`], relying on the increment when the clock is
+    ///   almost out
     /// - Returns `None` for infinite search
     ///
     /// # Arguments
     ///
     /// * `side_to_move` - Color to calculate time for
+    /// * `phase` - Game phase of the position to search
     ///
     /// # Returns
     ///
     /// Recommended time in milliseconds, or `None` for infinite search
-    fn time_for_move(&self, side_to_move: Color) -> Option<Duration> {
+    pub fn time_for_move(&self, side_to_move: Color, phase: GamePhase) -> Option<Duration> {
         if self.infinite {
             return None;
         }
 
         if let Some(movetime) = self.movetime {
-            return Some(Duration::from_millis(movetime));
+            let movetime = movetime.saturating_sub(self.move_overhead);
+            return Some(Duration::from_millis(movetime.max(MIN_THINK_TIME)));
         }
 
         let (time_left, increment) = match side_to_move {
@@ -111,11 +188,49 @@ impl SearchConfiguration {
             Color::Black => (self.btime?, self.binc.unwrap_or(0)),
         };
 
-        // Simple time management: use time_left / movestogo or a fraction
-        let moves_to_go = self.movestogo.unwrap_or(20) as f64;
-        let allocated_time = (time_left as f64 / moves_to_go).min(time_left as f64 * 0.9) as u64;
+        let usable_time = time_left.saturating_sub(self.move_overhead) as f64;
+        let moves_to_go = match self.movestogo {
+            Some(moves) if moves > 0 => moves,
+            _ => DEFAULT_MOVES_TO_GO,
+        } as f64;
+        // Complex middlegames get more time than simplified endgames
+        let complexity = 0.75 + 0.5 * phase.value() as f64 / TOTAL_PHASE as f64;
+        let allocated_time = (usable_time / moves_to_go * complexity + increment as f64)
+            .min(usable_time * MAX_TIME_FRACTION) as u64;
+
+        Some(Duration::from_millis(allocated_time.max(MIN_THINK_TIME)))
+    }
 
-        Some(Duration::from_millis(allocated_time + increment))
+    /// Calculates the time left for the move when the opponent plays the
+    /// move the engine was pondering on.
+    ///
+    /// The time allocation is computed from the clocks sent with `go ponder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `side_to_move` - Color to calculate time for
+    /// * `phase` - Game phase of the position to search
+    /// * `pondered` - Time spent pondering before the `ponderhit`
+    /// * `ponder_time_counts` - Whether the pondering time is taken from the
+    ///   allocation, or the whole allocation starts at the `ponderhit`
+    ///
+    /// # Returns
+    ///
+    /// Time left in milliseconds, or `None` for infinite search
+    pub fn time_after_ponderhit(
+        &self,
+        side_to_move: Color,
+        phase: GamePhase,
+        pondered: Duration,
+        ponder_time_counts: bool,
+    ) -> Option<Duration> {
+        let time_to_think = self.time_for_move(side_to_move, phase)?;
+
+        if ponder_time_counts {
+            Some(time_to_think.saturating_sub(pondered))
+        } else {
+            Some(time_to_think)
+        }
     }
 }
 
@@ -124,18 +239,88 @@ impl SearchConfiguration {
 /// Handles position setup, move execution, move generation, and search operations.
 /// Integrates with the UCI protocol for engine communication.
 pub struct GameState {
-    /// Total ply moves made in the game
-    ply_moves: u64,
+    /// Half moves since the last capture or pawn move
+    halfmove_clock: u64,
+    /// Full move number, incremented after each black move
+    fullmove_number: u64,
     /// Current side to move
     side_to_move: Color,
     /// Search configuration and time control settings
     search_control: Option<SearchConfiguration>,
     /// Search interrupt
     stop_flag: Arc<AtomicBool>,
+    /// Flag stopping the search on the opponent's time
+    brain_stop_flag: Arc<AtomicBool>,
     /// The chess board with current position
     board: ChessBoard,
     /// The search algorithm to use
     search_algorithm: Arc<dyn Search + Send + Sync>,
+    /// Learned moves, shared with the search thread
+    experience: Arc<Mutex<Experience>>,
+    /// Whether the NNUE network should replace the handcrafted evaluation
+    use_nnue: bool,
+    /// Path of the NNUE network file
+    eval_file: PathBuf,
+    /// When the current ponder search started, until the `ponderhit`
+    ponder_start: Option<Instant>,
+    /// Clock of the last search started, which holds its time limits
+    search_clock: Option<Arc<SearchClock>>,
+    /// Receiver of the `info` lines of the searches, set by the UCI loop
+    info_reporter: Option<InfoReporter>,
+    /// Whether searches record their tree, enabled with `debug on`
+    debug: bool,
+    /// Number of plies recorded when tracing searches
+    trace_depth: usize,
+    /// Tree recorded by the last traced search
+    last_trace: Arc<Mutex<Option<SearchTrace>>>,
+    /// Where the last analysis stopped, kept for the lifetime of the game
+    resume_point: Arc<Mutex<Option<ResumePoint>>>,
+    /// Options shared with the UCI loop and the searches
+    options: Arc<EngineOptions>,
+    /// Size in megabytes of the current transposition table
+    hash_mb: usize,
+    /// Margin within which a random move is played in the opening, zero to
+    /// always play the best move
+    variety: Score,
+    /// Number of moves of the game played with opening variety
+    variety_moves: u64,
+    /// Optional terms of the handcrafted evaluation
+    evaluation_terms: EvaluationTerms,
+    /// Weights of the handcrafted evaluation
+    eval_params: EvalParams,
+    /// Playing style the weights and search parameters were last set from
+    personality: Personality,
+    /// Deepest known opening reached by the game
+    opening: Option<Opening>,
+    /// Opening before each move made, restored when the move is unmade
+    previous_openings: Vec<Option<Opening>>,
+    /// Moves played since the position was set up, for taking them back
+    history: Vec<PlayedMove>,
+    /// Moves taken back, most recent last, until another move is made
+    undone_moves: Vec<Move>,
+}
+
+/// Where an analysis (`go infinite`) stopped, for the analysis of the next
+/// position to pick it up.
+struct ResumePoint {
+    /// Polyglot key of the position analyzed
+    key: u64,
+    /// Principal variation found, in UCI format
+    pv: Vec<String>,
+    /// Depth of the last completed iteration
+    depth: u8,
+}
+
+/// A move played in the game with the position it was played from.
+struct PlayedMove {
+    /// The move as it was made on the board
+    mv: Move,
+    /// Halfmove clock before the move
+    halfmove_clock: u64,
+    /// Fullmove number before the move
+    fullmove_number: u64,
+    /// Polyglot key of the position before the move
+    key: u64,
 }
 
 impl GameState {
@@ -153,13 +338,21 @@ impl GameState {
     ///
     /// * `sc` - Search configuration to apply
     pub fn set_time_control(&mut self, sc: &SearchConfiguration) {
-        self.search_control = Some(sc.clone());
+        self.search_control = Some(SearchConfiguration {
+            move_overhead: self.options.move_overhead(),
+            ..sc.clone()
+        });
     }
 
     /// Sets up the board position from a FEN string.
     ///
     /// FEN format: `<position> <side> <castling> <en passant> <halfmove> <fullmove>`
     ///
+    /// Only the piece placement and side to move are required. Missing
+    /// castling and en passant fields default to `-`, and missing clocks
+    /// default to `0 1`, so the 4-field FENs sent by many GUIs are accepted.
+    /// The current position is left untouched if the FEN is rejected.
+    ///
     /// # Arguments
     ///
     /// * `fen_str` - FEN string representing the position
@@ -168,6 +361,22 @@ impl GameState {
     ///
     /// `true` if FEN was parsed successfully, `false` otherwise
     pub fn set_fen_position(&mut self, fen_str: &str) -> bool {
+        if !self.load_fen(fen_str) {
+            return false;
+        }
+
+        self.opening = eco::classify(self.polyglot_key());
+        self.previous_openings.clear();
+        self.history.clear();
+        self.undone_moves.clear();
+        true
+    }
+
+    /// Sets up the board position from a FEN string, without classifying
+    /// the opening.
+    ///
+    /// See [`set_fen_position`](Self::set_fen_position).
+    fn load_fen(&mut self, fen_str: &str) -> bool {
         // FEN: <position> <side to move> <castling rights> <en passant square> <half move number> <full move number>
         let mut fen = fen_str.split_whitespace();
 
@@ -177,6 +386,10 @@ impl GameState {
         if let Some(fen_position) = fen.next() {
             let rank_strings: Vec<&str> = fen_position.split('/').collect();
             // FEN has 8 ranks, from rank 8 (black side) to rank 1 (white side)
+            if rank_strings.len() != 8 {
+                return false;
+            }
+
             for (rank_index, rank_str) in rank_strings.iter().enumerate() {
                 let mut file_index = 0;
 
@@ -193,23 +406,9 @@ impl GameState {
                         continue;
                     }
 
-                    let piece = match c {
-                        'P' => Piece::WhitePawn,
-                        'R' => Piece::WhiteRook,
-                        'N' => Piece::WhiteKnight,
-                        'B' => Piece::WhiteBishop,
-                        'Q' => Piece::WhiteQueen,
-                        'K' => Piece::WhiteKing,
-                        'p' => Piece::BlackPawn,
-                        'r' => Piece::BlackRook,
-                        'n' => Piece::BlackKnight,
-                        'b' => Piece::BlackBishop,
-                        'q' => Piece::BlackQueen,
-                        'k' => Piece::BlackKing,
-                        _ => {
-                            println!("Invalid FEN character {}\n", c);
-                            return false;
-                        }
+                    let Some(piece) = Piece::from_char(c) else {
+                        println!("Invalid FEN character {}\n", c);
+                        return false;
                     };
 
                     let board_index = (7 - rank_index) * 8 + file_index;
@@ -222,93 +421,135 @@ impl GameState {
         }
 
         // Side to move
-        if let Some(side_to_move) = fen.next() {
-            match side_to_move {
-                "w" => self.side_to_move = Color::White,
-                "b" => self.side_to_move = Color::Black,
-                _ => return false,
-            }
-        } else {
+        let side_to_move = match fen.next() {
+            Some("w") => Color::White,
+            Some("b") => Color::Black,
+            _ => return false,
+        };
+
+        // Castling rights, "-" when absent
+        let Some(castling_rights) = Self::parse_castling_rights(fen.next(), &board_8x8) else {
             return false;
-        }
+        };
 
-        let mut white_queenside = false;
-        let mut white_kingside = false;
-        let mut black_queenside = false;
-        let mut black_kingside = false;
-
-        // Castling rights
-        if let Some(castling_rights) = fen.next() {
-            for c in castling_rights.chars() {
-                match c {
-                    '-' => break,
-                    'K' => white_kingside = true,
-                    'Q' => white_queenside = true,
-                    'k' => black_kingside = true,
-                    'q' => black_queenside = true,
-                    _ => return false,
-                }
+        // En passant square, "-" when absent
+        let mut en_passant_square = None;
+        if let Some(en_passant) = fen.next()
+            && en_passant != "-"
+        {
+            match Move::notation_to_square(en_passant) {
+                Some(square) => en_passant_square = Some(square),
+                None => return false,
             }
         }
 
-        let castling_rights = CastlingRights {
-            white_queenside,
-            white_kingside,
-            black_queenside,
-            black_kingside,
+        // Half move clock, 0 when absent
+        let halfmove_clock = match fen.next() {
+            Some(half_moves_str) => match half_moves_str.parse::<u64>() {
+                Ok(half_moves) => half_moves,
+                Err(_) => return false,
+            },
+            None => 0,
+        };
+
+        // Full move number, 1 when absent
+        let fullmove_number = match fen.next() {
+            Some(full_moves_str) => match full_moves_str.parse::<u64>() {
+                Ok(full_moves) => full_moves.max(1),
+                Err(_) => return false,
+            },
+            None => 1,
         };
+
         self.board.set_castling_rights(&castling_rights);
 
-        if let Some(en_passant) = fen.next() {
-            if en_passant != "-" {
-                if en_passant.len() == 2 {
-                    // 0 for 'a', 1 for 'b', …, 7 for 'h'
-                    let file = (en_passant.as_bytes()[0] - b'a') as i16;
-
-                    // 0 for rank 1, …, 7 for rank 8
-                    let rank = (en_passant.as_bytes()[1] - b'1') as i16;
-                    if file < 8 && rank < 8 {
-                        let en_passant_square = rank * 8 + file;
-                        self.board.set_en_passant_square(en_passant_square);
-                    } else {
-                        return false;
-                    }
-                } else {
-                    return false;
-                }
-            }
-        } else {
-            return false;
+        match en_passant_square {
+            Some(square) => self.board.set_en_passant_square(square),
+            None => self.board.clear_en_passant_square(),
         }
 
-        let mut total_moves;
-        // Half move
-        if let Some(half_moves_str) = fen.next() {
-            if let Ok(half_moves) = half_moves_str.parse::<u64>() {
-                total_moves = half_moves;
-            } else {
-                return false;
+        self.side_to_move = side_to_move;
+        self.halfmove_clock = halfmove_clock;
+        self.fullmove_number = fullmove_number;
+
+        self.board.set_board(&board_8x8, self.side_to_move);
+
+        true
+    }
+
+    /// Parses the castling field of a FEN.
+    ///
+    /// Accepts the standard `KQkq` letters, read as in X-FEN as the
+    /// outermost rook on each side of the king, and the Shredder-FEN rook
+    /// files (`HAha`, or any file letter). Each right is mapped to the start
+    /// square of its rook, which may stand on any file as in Chess960.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The castling field, `None` when the FEN stops before it
+    /// * `board_8x8` - Pieces of the position in standard square order
+    ///
+    /// # Returns
+    ///
+    /// The castling rights, or None if the field is invalid
+    fn parse_castling_rights(
+        field: Option<&str>,
+        board_8x8: &[Piece; 64],
+    ) -> Option<CastlingRights> {
+        let mut rights = CastlingRights {
+            white_queenside: false,
+            white_kingside: false,
+            black_queenside: false,
+            black_kingside: false,
+            rook_files: CastlingRights::STANDARD_ROOK_FILES,
+        };
+
+        for c in field.unwrap_or("-").chars() {
+            if c == '-' {
+                break;
             }
-        } else {
-            return false;
-        }
 
-        // Full move
-        if let Some(full_moves_str) = fen.next() {
-            if let Ok(full_moves) = full_moves_str.parse::<u64>() {
-                total_moves += full_moves;
+            let (color, back_rank) = if c.is_ascii_uppercase() {
+                (Color::White, 0)
             } else {
-                return false;
-            }
-        } else {
-            return false;
-        }
+                (Color::Black, 56)
+            };
+            let (rook, king) = match color {
+                Color::White => (Piece::WhiteRook, Piece::WhiteKing),
+                Color::Black => (Piece::BlackRook, Piece::BlackKing),
+            };
+            let rank = &board_8x8[back_rank..back_rank + 8];
+            let king_file = rank.iter().position(|&piece| piece == king);
+
+            let rook_file = match c.to_ascii_lowercase() {
+                // The outermost rook, in the corner if it's missing
+                'k' => king_file
+                    .and_then(|king_file| (king_file + 1..8).rev().find(|&file| rank[file] == rook))
+                    .unwrap_or(7),
+                'q' => king_file
+                    .and_then(|king_file| (0..king_file).find(|&file| rank[file] == rook))
+                    .unwrap_or(0),
+                file @ 'a'..='h' => file as usize - 'a' as usize,
+                _ => return None,
+            };
 
-        self.ply_moves = total_moves;
+            // Shredder-FEN letters name the side through the king's file
+            let kingside = match c.to_ascii_lowercase() {
+                'k' => true,
+                'q' => false,
+                _ => rook_file > king_file?,
+            };
 
-        self.board.set_board(&board_8x8, self.side_to_move);
+            // The rook must stand on the side of the king it castles to
+            if king_file.is_some_and(|king_file| (rook_file > king_file) != kingside) {
+                return None;
+            }
 
-        true
+            rights.set(color, kingside, true);
+            rights.rook_files[2 * color as usize + kingside as usize] = rook_file as u8;
+        }
+
+        Some(rights)
     }
 
     /// Creates a move object from algebraic notation.
@@ -324,343 +565,1711 @@ impl GameState {
         self.board.from_uci(algebraic_notation)
     }
 
-    /// Executes a move on the board.
+    /// Creates a move object from Standard Algebraic Notation (SAN).
     ///
     /// # Arguments
     ///
-    /// * `algebraic_notation` - Move in UCI format to execute
-    pub fn make_move(&mut self, algebraic_notation: &str) {
-        if let Some(mv) = self.create_move(algebraic_notation) {
-            self.board.make_move(&mv);
-            self.side_to_move = self.side_to_move.opposite();
-        }
+    /// * `san` - Move in SAN (e.g., "Nf3", "O-O")
+    ///
+    /// # Returns
+    ///
+    /// `Some(Move)` if the notation matches a legal move, `None` otherwise
+    pub fn create_move_from_san(&mut self, san: &str) -> Option<Move> {
+        self.board.from_san(san, self.side_to_move)
     }
 
-    /// Reverts a move on the board.
+    /// Executes a move on the board.
+    ///
+    /// Thin wrapper around [`make_move_obj`](Self::make_move_obj) for moves
+    /// in UCI format.
     ///
     /// # Arguments
     ///
-    /// * `algebraic_notation` - Move in UCI format to undo
-    pub fn unmake_move(&mut self, algebraic_notation: &str) {
-        if let Some(mv) = self.create_move(algebraic_notation) {
-            self.board.unmake_move(&mv);
-            self.side_to_move = self.side_to_move.opposite();
-        }
-    }
-
-    /// Generates all legal moves for the current position.
+    /// * `algebraic_notation` - Move in UCI format to execute
     ///
     /// # Returns
     ///
-    /// Vector of moves in UCI string format
-    pub fn generate_moves(&mut self) -> Vec<String> {
-        let moves = self.board.generate_moves(self.side_to_move);
-
-        let move_ucis: Vec<String> = moves.iter().map(|mv| self.board.move_to_uci(mv)).collect();
-        move_ucis
+    /// `true` if the notation was understood and the move was made
+    pub fn make_move(&mut self, algebraic_notation: &str) -> bool {
+        self.create_move(algebraic_notation)
+            .is_some_and(|mv| self.make_move_obj(&mv).is_ok())
     }
 
-    /// Performs a search to find the best move for the current position.
+    /// Executes a move, as generated or created for the current position.
     ///
-    /// Uses the configured time control and search parameters.
+    /// Keeps the side to move, the hash, the clocks and the game history in
+    /// step with the board; the move can be taken back with
+    /// [`unmake_last`](Self::unmake_last).
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - Move of the side to move
     ///
     /// # Returns
     ///
-    /// Best move in UCI format, or "0000" if no move found
-    pub fn search(&mut self) {
-        // The time parameters were set with the time requirements from the go command.
-        // This method will then, spawn a thread that will interrupt the search after a calculated time
-        self.time_manager();
+    /// An error, with the position left untouched, if the move doesn't
+    /// belong to the current position
+    pub fn make_move_obj(&mut self, mv: &Move) -> Result<(), String> {
+        self.board.validate_move(mv, self.side_to_move)?;
+
+        // A new move replaces the moves that could be redone
+        self.undone_moves.clear();
+        self.play(mv.clone());
+        Ok(())
+    }
 
-        let mut board_copy = self.board.clone();
-        let side_to_move = self.side_to_move;
-        self.stop_flag.store(false, Ordering::Release);
-        let stop_flag_clone = Arc::clone(&self.stop_flag);
-        let algorithm = Arc::clone(&self.search_algorithm);
+    /// Plays a move on the board and records it in the game history.
+    fn play(&mut self, mv: Move) {
+        let key = self.polyglot_key();
+        self.board.make_move(&mv);
 
-        thread::spawn(move || {
-            let (_, best_move) = algorithm.search(&mut board_copy, side_to_move, stop_flag_clone);
-            match best_move {
-                Some(mv) => {
-                    println!("bestmove {}", board_copy.move_to_uci(&mv));
-                }
-                None => {
-                    println!("bestmove 0000");
-                }
-            }
+        self.history.push(PlayedMove {
+            mv: mv.clone(),
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            key,
         });
-    }
 
-    pub fn stop_search(&self) {
-        // Force the search thread to stop and return the best move found up to this point
-        self.stop_flag.store(true, Ordering::Release);
+        let is_pawn_move = matches!(mv.piece, Piece::WhitePawn | Piece::BlackPawn);
+        if is_pawn_move || mv.is_capture() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+
+        if self.side_to_move == Color::Black {
+            self.fullmove_number += 1;
+        }
+
+        self.side_to_move = self.side_to_move.opposite();
+
+        // Keep the last known opening once the game leaves the table
+        self.previous_openings.push(self.opening);
+        if let Some(opening) = eco::classify(self.polyglot_key()) {
+            self.opening = Some(opening);
+        }
     }
 
-    /// Manages search time by spawning a timer thread that will interrupt the search
-    /// after the allocated time period has elapsed.
+    /// Takes back the last move played since the position was set up.
     ///
-    /// This function calculates the appropriate time allocation for the current move
-    /// based on the game state and search configuration, then spawns a background
-    /// thread that will call the `stop_search` method when the time expires.
+    /// Thin wrapper around [`unmake_last`](Self::unmake_last) giving the
+    /// move in UCI format.
     ///
-    /// # Arguments
+    /// # Returns
     ///
-    /// * `game_state` - Reference to the current game state containing side to move.
+    /// The move taken back in UCI format, or `None` if no move was played
+    pub fn undo_last_move(&mut self) -> Option<String> {
+        let mv = self.unmake_last()?;
+        Some(self.board.move_to_uci(&mv))
+    }
+
+    /// Takes back the last move played since the position was set up,
+    /// restoring the side to move, the hash, the clocks and the opening.
     ///
-    /// # Behavior
+    /// The move can be played again with [`redo_move`](Self::redo_move)
+    /// until another move is made.
     ///
-    /// - Calculates time allocation using `time_for_move()` based on the current
-    ///   player's time remaining, increment, and moves until next time control
-    /// - If time allocation is determined (`Some(Duration)`), spawns a timer thread
-    /// - The timer thread sleeps for the allocated duration, then calls the
-    ///   `game_state.stop_search()`
-    /// - If no time allocation is calculated (`None`), no timer is started,
-    ///   allowing for infinite search (when `infinite` flag is set in configuration)
-    fn time_manager(&self) {
-        if let Some(search_control) = &self.search_control
-            && let Some(time_to_think) = search_control.time_for_move(self.side_to_move)
-        {
-            // Here we spawn a new thread that will interrupt the search
-            // after the calculated time period.
-            let stop_flag = self.stop_flag.clone();
-            thread::spawn(move || {
-                thread::sleep(time_to_think);
-                stop_flag.store(true, Ordering::Release);
-            });
+    /// # Returns
+    ///
+    /// The move taken back, or `None` if no move was played
+    pub fn unmake_last(&mut self) -> Option<Move> {
+        let played = self.history.pop()?;
+
+        self.board.unmake_move(&played.mv);
+        self.side_to_move = self.side_to_move.opposite();
+        self.halfmove_clock = played.halfmove_clock;
+        self.fullmove_number = played.fullmove_number;
+        if let Some(opening) = self.previous_openings.pop() {
+            self.opening = opening;
         }
+
+        self.undone_moves.push(played.mv.clone());
+        Some(played.mv)
     }
 
-    /// Performs a perft (performance test) for debugging move generation.
+    /// Gets the moves played since the position was set up.
     ///
-    /// Counts the number of leaf nodes at a given depth for testing move generation correctness.
+    /// # Returns
+    ///
+    /// Vector of moves in UCI string format, oldest first
+    pub fn moves_played(&self) -> Vec<String> {
+        self.history
+            .iter()
+            .map(|played| self.board.move_to_uci(&played.mv))
+            .collect()
+    }
+
+    /// Gets the last move played since the position was set up.
+    ///
+    /// # Returns
+    ///
+    /// The move in UCI format, or `None` if no move was played
+    pub fn last_move(&self) -> Option<String> {
+        self.history
+            .last()
+            .map(|played| self.board.move_to_uci(&played.mv))
+    }
+
+    /// Gets the Polyglot keys of the positions reached in the game, from
+    /// the position set up to the current one, as needed to detect
+    /// repetitions.
+    pub fn position_keys(&self) -> Vec<u64> {
+        self.history
+            .iter()
+            .map(|played| played.key)
+            .chain(std::iter::once(self.polyglot_key()))
+            .collect()
+    }
+
+    /// Gets the position reached after some of the moves played.
     ///
     /// # Arguments
     ///
-    /// * `depth` - Depth to search (0 returns immediate count)
-    /// * `print` - Whether to print move counts for each branch
+    /// * `moves` - Number of moves played from the position set up, 0 for
+    ///   that position itself
     ///
     /// # Returns
     ///
-    /// Total number of leaf nodes at the specified depth
-    pub fn perft_debug(&mut self, depth: u64, print: bool) -> u64 {
-        if depth == 0 {
-            return 1;
-        }
-
-        let color = self.side_to_move;
-        let moves = self.board.generate_moves(color);
+    /// The position in FEN, or `None` if fewer moves were played
+    pub fn position_after(&self, moves: usize) -> Option<String> {
+        let Some(played) = self.history.get(moves) else {
+            return (moves == self.history.len()).then(|| self.to_fen());
+        };
 
-        if print {
-            println!("Depth {}: {} moves", depth, moves.len());
+        let mut board = self.board.clone();
+        for later in self.history[moves..].iter().rev() {
+            board.unmake_move(&later.mv);
         }
 
-        let mut total_nodes = 0;
-
-        for mv in moves {
-            self.board.make_move(&mv);
-            self.side_to_move = self.side_to_move.opposite();
-
-            let nodes = if depth == 1 {
-                1
-            } else {
-                self.perft_debug(depth - 1, false)
-            };
-
-            if print {
-                println!("{}: {}", self.board.move_to_uci(&mv), nodes);
-            }
-
-            total_nodes += nodes;
-
-            self.side_to_move = self.side_to_move.opposite();
-            self.board.unmake_move(&mv);
-        }
+        Some(board.to_fen(
+            played.mv.piece.get_color(),
+            played.halfmove_clock,
+            played.fullmove_number,
+        ))
+    }
 
-        if print {
-            println!("Nodes searched: {}", total_nodes);
-        }
+    /// Plays again the last move taken back with
+    /// [`undo_last_move`](Self::undo_last_move).
+    ///
+    /// # Returns
+    ///
+    /// The move played in UCI format, or `None` if there is nothing to redo
+    pub fn redo_move(&mut self) -> Option<String> {
+        let mv = self.undone_moves.pop()?;
+        let notation = self.board.move_to_uci(&mv);
 
-        total_nodes
+        self.play(mv);
+        Some(notation)
     }
 
-    /// Prints the current board state to stdout.
-    pub fn print_board(&self) {
-        self.board.print_board();
+    /// Checks whether a move in UCI format is legal in the current position.
+    ///
+    /// # Arguments
+    ///
+    /// * `algebraic_notation` - Move in UCI format (e.g., "e2e4")
+    pub fn is_legal_move(&mut self, algebraic_notation: &str) -> bool {
+        self.generate_moves()
+            .iter()
+            .any(|mv| mv == algebraic_notation)
     }
 
-    /// Gets a reference to the underlying chess board.
+    /// Reverts the last move played, given in UCI format.
     ///
-    /// # Returns
+    /// Does nothing unless the move is the last one played, see
+    /// [`unmake_last`](Self::unmake_last).
     ///
-    /// Reference to the ChessBoard instance
-    pub fn get_chess_board(&self) -> &ChessBoard {
-        &self.board
+    /// # Arguments
+    ///
+    /// * `algebraic_notation` - Move in UCI format to undo
+    pub fn unmake_move(&mut self, algebraic_notation: &str) {
+        if self.last_move().as_deref() == Some(algebraic_notation) {
+            self.unmake_last();
+        }
     }
 
-    pub fn resize_hash_table(&mut self, new_size_mb: usize) {
-        let transposition_table = Arc::new(TranspositionTable::new(new_size_mb));
+    /// Generates all legal moves for the current position.
+    ///
+    /// # Returns
+    ///
+    /// Vector of moves in UCI string format
+    pub fn generate_moves(&mut self) -> Vec<String> {
+        let moves = self.board.generate_moves(self.side_to_move);
 
-        self.board.set_transposition_table(transposition_table);
+        let move_ucis: Vec<String> = moves.iter().map(|mv| self.board.move_to_uci(mv)).collect();
+        move_ucis
     }
 
-    /// Sets the search algorithm to use for future searches.
+    /// Generates all legal moves for the current position, with their
+    /// notations and properties.
     ///
-    /// # Arguments
+    /// # Returns
     ///
-    /// * `algorithm` - The search algorithm implementation to use
-    pub fn set_search_algorithm(&mut self, algorithm: Arc<dyn Search + Send + Sync>) {
-        self.search_algorithm = algorithm;
+    /// Vector of move records, in move generation order
+    pub fn legal_moves(&mut self) -> Vec<MoveRecord> {
+        let moves = self.board.generate_moves(self.side_to_move);
+
+        moves
+            .iter()
+            .map(|mv| MoveRecord::new(&mut self.board, mv))
+            .collect()
     }
 
-    /// Gets a reference to the current search algorithm.
+    /// Starts a search for the best move of the current position.
+    ///
+    /// Uses the configured time control and search parameters. The search
+    /// runs on its own thread and prints its progress as UCI `info` lines,
+    /// the best move is collected from the returned handle.
     ///
     /// # Returns
     ///
-    /// Reference to the current search algorithm implementation
-    pub fn get_search_algorithm(&self) -> &Arc<dyn Search + Send + Sync> {
-        &self.search_algorithm
-    }
+    /// Handle to wait for the result of the search or to stop it
+    pub fn search(&mut self) -> SearchHandle {
+        // The table is the real search's again
+        self.stop_permanent_brain();
+        // Hash size changes made through the shared options apply here
+        self.apply_hash_size();
 
-    /// Creates a default game state passing the zobrist keys and transposition table structure to be used
-    pub fn new(table_size_mb: Option<usize>) -> Self {
-        // 1. Create the zobrist keys once.
-        // Wrap it in Arc<> to enable shared read access
-        let zobrist_keys = Arc::new(Zobrist::new());
+        // The time parameters were set with the time requirements from the go command.
+        // This method will then, spawn a thread that will interrupt the search after a calculated time
+        let key = self.polyglot_key();
+        let side_to_move = self.side_to_move;
 
-        // 2. Create the transposition table once
-        let table_size = table_size_mb.unwrap_or(0);
-        let transposition_table = Arc::new(TranspositionTable::new(table_size));
+        let pondering = self
+            .search_control
+            .as_ref()
+            .is_some_and(|search_control| search_control.ponder);
+        let analyzing = self.search_control.as_ref().is_some_and(|sc| sc.infinite);
+
+        // A move that did well in previous games is searched first and
+        // played when the search finds it about as good as the best move.
+        // While pondering the move can't be sent and an analysis asks for the
+        // best move itself, so neither is biased
+        let learned_move = self
+            .experience
+            .lock()
+            .unwrap()
+            .best_move(key)
+            .map(str::to_string);
+        let learned_move = learned_move
+            .filter(|mv| !pondering && !analyzing && self.is_legal_move(mv))
+            .and_then(|mv| self.board.from_uci(&mv));
 
-        GameState {
-            ply_moves: 0,
-            side_to_move: Color::White,
-            search_control: None,
-            stop_flag: Arc::new(AtomicBool::new(false)),
-            search_algorithm: Arc::new(IterativeDeepening::new(MinimaxAlphaBeta, 5)),
+        self.stop_flag.store(false, Ordering::Release);
+        let clock = Arc::new(SearchClock::new(Arc::clone(&self.stop_flag)));
+        self.search_clock = Some(Arc::clone(&clock));
+
+        // The clock only starts running when the opponent plays the
+        // pondered move, see `ponderhit`
+        if pondering {
+            self.ponder_start = Some(Instant::now());
+        } else {
+            self.ponder_start = None;
+            self.time_manager();
+        }
+        // The lower of the `go nodes` limit and the cap of every move
+        let go_nodes = self.search_control.as_ref().and_then(|sc| sc.nodes);
+        if let Some(nodes) = go_nodes
+            .into_iter()
+            .chain(self.options.nodes_per_move())
+            .min()
+        {
+            clock.set_node_limit(nodes);
+        }
+        // `go depth` can only lower the cap of every search
+        let max_depth = self.options.max_depth();
+        let go_depth = self.search_control.as_ref().and_then(|sc| sc.depth);
+        let depth = go_depth.map_or(max_depth, |depth| depth.clamp(1, max_depth.into()) as u8);
+        clock.set_depth_limit(depth);
+
+        let mut board_copy = self.board.clone();
+        board_copy.set_clock(Some(clock));
+        board_copy.set_info_reporter(self.info_reporter.clone());
+        let in_opening = self.fullmove_number <= self.variety_moves;
+        board_copy.set_variety((self.variety > Score::ZERO && in_opening).then_some(self.variety));
+        board_copy.set_learned_move(learned_move);
+        // Searches waiting for `stop` or `ponderhit` have nothing to save
+        // time for
+        board_copy.set_instant_moves(self.options.instant_moves() && !pondering && !analyzing);
+        board_copy.set_quiescence_checks(self.options.quiescence_checks());
+        if let Some(search_control) = &self.search_control {
+            board_copy.set_search_moves(search_control.searchmoves.clone());
+            board_copy.set_avoid_moves(search_control.avoidmoves.clone());
+        }
+        board_copy.set_last_capture(
+            self.history
+                .last()
+                .filter(|played| played.mv.is_capture())
+                .map(|played| played.mv.to),
+        );
+        board_copy.set_resume_depth(self.resume_depth());
+        if self.debug {
+            board_copy.set_trace(Some(SearchTrace::new(self.trace_depth)));
+        }
+        let stop_flag = Arc::clone(&self.stop_flag);
+        let stop_flag_clone = Arc::clone(&self.stop_flag);
+        let algorithm = Arc::clone(&self.search_algorithm);
+        let experience = Arc::clone(&self.experience);
+        let last_trace = Arc::clone(&self.last_trace);
+        let options = Arc::clone(&self.options);
+        let resume_point = Arc::clone(&self.resume_point);
+
+        let thread = thread::spawn(move || {
+            if options.thread_affinity() {
+                affinity::pin_current_thread(0);
+            }
+            let nodes_before = board_copy.nodes();
+            let (score, best_move) =
+                algorithm.search(&mut board_copy, side_to_move, stop_flag_clone);
+            let nodes = board_copy.nodes() - nodes_before;
+            if let Some(trace) = board_copy.take_trace() {
+                *last_trace.lock().unwrap() = Some(trace);
+            }
+
+            *resume_point.lock().unwrap() = None;
+            let Some(mv) = best_move else {
+                // No legal move: the rules decide the game
+                let score = board_copy
+                    .rules()
+                    .outcome_without_moves(&board_copy, side_to_move)
+                    .score();
+                return SearchResult {
+                    best_move: None,
+                    ponder_move: None,
+                    score,
+                    depth: 0,
+                    nodes,
+                    pv: Vec::new(),
+                };
+            };
+
+            let uci = board_copy.move_to_uci(&mv);
+            let score = score.relative_to(side_to_move);
+            experience
+                .lock()
+                .unwrap()
+                .record(key, &uci, score, side_to_move);
+
+            let pv: Vec<String> = board_copy
+                .principal_variation(&mv, side_to_move, analysis::MAX_PV_LENGTH)
+                .iter()
+                .map(|mv| board_copy.move_to_uci(mv))
+                .collect();
+            if analyzing {
+                *resume_point.lock().unwrap() = Some(ResumePoint {
+                    key,
+                    pv: pv.clone(),
+                    depth: board_copy.completed_depth(),
+                });
+            }
+            SearchResult {
+                best_move: Some(uci),
+                ponder_move: pv.get(1).cloned(),
+                score,
+                depth: board_copy.completed_depth(),
+                nodes,
+                pv,
+            }
+        });
+
+        SearchHandle::running(thread, stop_flag)
+    }
+
+    /// Gets the depth the next search resumes the last analysis at, if it
+    /// does.
+    ///
+    /// An analysis (`go infinite`) of a position reached by playing the
+    /// first move of the principal variation of the last analysis continues
+    /// it: the transposition table entries are kept as entries of the new
+    /// search, and with the `ResumeAnalysis` option deepening starts one
+    /// iteration below the depth reached, the root being a ply deeper.
+    ///
+    /// # Returns
+    ///
+    /// The first iteration of the next search, None if it starts afresh
+    pub fn resume_depth(&self) -> Option<u8> {
+        if !self.search_control.as_ref().is_some_and(|sc| sc.infinite) {
+            return None;
+        }
+
+        let resume_point = self.resume_point.lock().unwrap();
+        let resume_point = resume_point.as_ref()?;
+        let played = self.history.last()?;
+        if played.key != resume_point.key
+            || resume_point.pv.first() != Some(&self.board.move_to_uci(&played.mv))
+        {
+            return None;
+        }
+
+        if self.options.resume_analysis() {
+            Some(resume_point.depth.saturating_sub(1).max(1))
+        } else {
+            Some(1)
+        }
+    }
+
+    /// Sets whether an analysis moving along its principal variation
+    /// continues deepening from the depth reached, see
+    /// [`resume_depth`](Self::resume_depth).
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` to continue deepening, `false` to start the
+    ///   next analysis at depth 1
+    pub fn set_resume_analysis(&mut self, enabled: bool) {
+        self.options.set_resume_analysis(enabled);
+    }
+
+    /// Switches a ponder search to a normal search after the opponent played
+    /// the expected move.
+    ///
+    /// The search keeps running and its clock is limited to the time left
+    /// for the move. Does nothing if the engine isn't pondering.
+    pub fn ponderhit(&mut self) {
+        let Some(ponder_start) = self.ponder_start.take() else {
+            return;
+        };
+
+        if let Some(search_control) = &self.search_control
+            && let Some(time_to_think) = search_control.time_after_ponderhit(
+                self.side_to_move,
+                self.board.game_phase(),
+                ponder_start.elapsed(),
+                self.options.ponder_time_counts(),
+            )
+        {
+            self.limit_search_time(time_to_think);
+        }
+    }
+
+    /// Enables or disables Chess960 mode (`UCI_Chess960`).
+    ///
+    /// FENs are then written with Shredder-FEN castling rights; both forms
+    /// are always accepted when reading them.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess960` - Whether Chess960 is played
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.board.set_chess960(chess960);
+    }
+
+    /// Sets whether time spent pondering counts toward the move's time
+    /// budget after a `ponderhit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `counts` - `true` to take the pondering time from the budget,
+    ///   `false` to start the whole budget at the `ponderhit`
+    pub fn set_ponder_time_counts(&mut self, counts: bool) {
+        self.options.set_ponder_time_counts(counts);
+    }
+
+    /// Sets whether forced moves are played without searching: the only
+    /// legal move, or in timed searches the recapture of the piece just
+    /// taken when no other capture wins material. They are reported as a
+    /// depth 1 search.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` to play forced moves at once, `false` to search
+    ///   every position
+    pub fn set_instant_moves(&mut self, enabled: bool) {
+        self.options.set_instant_moves(enabled);
+    }
+
+    /// Whether forced moves are played without searching.
+    pub fn instant_moves(&self) -> bool {
+        self.options.instant_moves()
+    }
+
+    /// Sets whether quiescence also searches quiet checking moves at its
+    /// first ply, on top of captures and queen promotions. It finds short
+    /// tactics starting with a check at the cost of a larger tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` to search quiet checks
+    pub fn set_quiescence_checks(&mut self, enabled: bool) {
+        self.options.set_quiescence_checks(enabled);
+    }
+
+    /// Whether quiescence searches quiet checking moves.
+    pub fn quiescence_checks(&self) -> bool {
+        self.options.quiescence_checks()
+    }
+
+    /// Enables or disables debug mode (`debug on` / `debug off`).
+    ///
+    /// In debug mode every search records its tree, see [`last_trace`](Self::last_trace).
+    ///
+    /// # Arguments
+    ///
+    /// * `debug` - Whether to trace searches
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    /// Sets the number of plies recorded when tracing searches.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Plies to record, at least one
+    pub fn set_trace_depth(&mut self, depth: usize) {
+        self.trace_depth = depth.max(1);
+    }
+
+    /// Gets the tree recorded by the last search run in debug mode.
+    pub fn last_trace(&self) -> Option<SearchTrace> {
+        self.last_trace.lock().unwrap().clone()
+    }
+
+    /// Sets the time kept aside for each move to cover the communication
+    /// delay with the GUI. Applies from the next `go` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `move_overhead` - Overhead in milliseconds
+    pub fn set_move_overhead(&mut self, move_overhead: u64) {
+        self.options.set_move_overhead(move_overhead);
+    }
+
+    /// Caps the nodes searched for each move, whatever the time control.
+    ///
+    /// The engine then plays at a steady speed and, the node count being
+    /// independent of the hardware, gives testers an opponent of
+    /// reproducible strength. A lower `go nodes` limit still applies. The
+    /// search checks the count every
+    /// [`NODES_PER_TIME_CHECK`](board::search::clock::NODES_PER_TIME_CHECK)
+    /// nodes, so it may go over by up to that many.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Nodes per move, None for no cap
+    pub fn set_nodes_per_move(&mut self, nodes: Option<u64>) {
+        self.options.set_nodes_per_move(nodes);
+    }
+
+    /// Gets the cap on the nodes searched for each move, see
+    /// [`set_nodes_per_move`](Self::set_nodes_per_move).
+    pub fn nodes_per_move(&self) -> Option<u64> {
+        self.options.nodes_per_move()
+    }
+
+    /// Caps the depth of every search, whatever the time control.
+    ///
+    /// A lower `go depth` still applies, a deeper one is cut to the cap.
+    /// The search algorithm's own maximum depth, see
+    /// [`set_search_algorithm`](Self::set_search_algorithm), is kept when it
+    /// is lower.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Depth in plies, at least 1
+    pub fn set_max_depth(&mut self, depth: u8) {
+        self.options.set_max_depth(depth);
+    }
+
+    /// Gets the depth every search is capped at, see
+    /// [`set_max_depth`](Self::set_max_depth).
+    pub fn max_depth(&self) -> u8 {
+        self.options.max_depth()
+    }
+
+    /// Pins search threads to CPUs, see [`affinity`].
+    ///
+    /// Applies to the thread of [`search`](Self::search) and to the
+    /// threads of [`analyze_batch`](Self::analyze_batch).
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Pin the threads when true, let them migrate otherwise
+    pub fn set_thread_affinity(&mut self, enabled: bool) {
+        self.options.set_thread_affinity(enabled);
+    }
+
+    /// Whether search threads are pinned to CPUs.
+    pub fn thread_affinity(&self) -> bool {
+        self.options.thread_affinity()
+    }
+
+    /// Sets the opening variety: in the first moves of the game, any move
+    /// scored within `margin` centipawns of the best one may be played, so
+    /// games don't all repeat the same opening. Applies from the next `go`
+    /// command.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin` - Largest loss accepted in centipawns, 0 to disable
+    /// * `moves` - Number of moves, counted from the start of the game,
+    ///   played with variety
+    pub fn set_variety(&mut self, margin: i32, moves: u64) {
+        self.variety = Score::cp(margin.max(0));
+        self.variety_moves = moves;
+    }
+
+    /// Gets the opening variety margin and the number of moves it applies
+    /// to, see [`set_variety`](Self::set_variety).
+    pub fn variety(&self) -> (Score, u64) {
+        (self.variety, self.variety_moves)
+    }
+
+    /// Sets a tunable search parameter by its option name. Applies from the
+    /// next `go` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Option name of the parameter
+    /// * `value` - New value of the parameter
+    ///
+    /// # Returns
+    ///
+    /// `true` if the parameter exists and the value is in range
+    pub fn set_search_param(&mut self, name: &str, value: i32) -> bool {
+        let mut search_params = *self.board.search_params();
+        if !search_params.set(name, value) {
+            return false;
+        }
+
+        self.board.set_search_params(search_params);
+        true
+    }
+
+    /// Gets the margins and depths used by the search.
+    pub fn search_params(&self) -> &SearchParams {
+        self.board.search_params()
+    }
+
+    /// Prepares the search on the opponent's time that follows the next
+    /// search, see [`permanent_brain`](permanent_brain).
+    ///
+    /// Called after [`search`](Self::search) started, which stops the
+    /// former search on the opponent's time, on the position it searches.
+    ///
+    /// # Returns
+    ///
+    /// The search to run once the best move is sent, None when the
+    /// `PermanentBrain` option is off, the GUI ponders, or the next search
+    /// is itself a ponder search or an analysis
+    pub fn permanent_brain(&mut self) -> Option<PermanentBrain> {
+        let thinking_search = self
+            .search_control
+            .as_ref()
+            .is_some_and(|sc| sc.ponder || sc.infinite);
+        if !self.options.permanent_brain() || self.options.ponder() || thinking_search {
+            return None;
+        }
+
+        // A flag of its own, so stopping a former search doesn't stop it
+        self.brain_stop_flag = Arc::new(AtomicBool::new(false));
+        Some(PermanentBrain::new(
+            self.board.clone(),
+            self.side_to_move,
+            self.options.max_depth(),
+            Arc::clone(&self.brain_stop_flag),
+        ))
+    }
+
+    /// Stops the search on the opponent's time, when the next command
+    /// arrives.
+    pub fn stop_permanent_brain(&self) {
+        self.brain_stop_flag.store(true, Ordering::Release);
+    }
+
+    /// Sets whether the engine thinks on the opponent's time when the GUI
+    /// doesn't ponder.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - `true` to search on after sending the best move
+    pub fn set_permanent_brain(&mut self, enabled: bool) {
+        self.options.set_permanent_brain(enabled);
+    }
+
+    /// Records whether the GUI enabled pondering, which replaces the
+    /// search on the opponent's time.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Value of the `Ponder` option
+    pub fn set_ponder(&mut self, enabled: bool) {
+        self.options.set_ponder(enabled);
+    }
+
+    /// Sets where the searches started with [`search`](Self::search) send
+    /// their `info` lines. Searches are silent without a reporter.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - Receiver of the lines, or `None` to search silently
+    pub fn set_info_reporter(&mut self, reporter: Option<InfoReporter>) {
+        self.info_reporter = reporter;
+    }
+
+    /// Gets the clock of the last search started, with its limits and stop
+    /// flag.
+    pub fn search_clock(&self) -> Option<Arc<SearchClock>> {
+        self.search_clock.clone()
+    }
+
+    pub fn stop_search(&self) {
+        // Force the search thread to stop and return the best move found up to this point
+        self.stop_flag.store(true, Ordering::Release);
+    }
+
+    /// Manages search time by limiting the clock of the running search.
+    ///
+    /// This function calculates the appropriate time allocation for the current move
+    /// based on the game state and search configuration, then sets it on the
+    /// search clock so the search stops itself when the time expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_state` - Reference to the current game state containing side to move.
+    ///
+    /// # Behavior
+    ///
+    /// - Calculates time allocation using `time_for_move()` based on the current
+    ///   player's time remaining, increment, moves until next time control and
+    ///   the game phase of the position
+    /// - If time allocation is determined (`Some(Duration)`), sets it as the
+    ///   limit of the search clock, which the search checks every few nodes
+    ///   and stops itself once the time is up
+    /// - If no time allocation is calculated (`None`), the clock has no limit,
+    ///   allowing for infinite search (when `infinite` flag is set in configuration)
+    fn time_manager(&self) {
+        if let Some(search_control) = &self.search_control
+            && let Some(time_to_think) =
+                search_control.time_for_move(self.side_to_move, self.board.game_phase())
+        {
+            self.limit_search_time(time_to_think);
+        }
+    }
+
+    /// Limits the running search to `time_to_think` from now.
+    fn limit_search_time(&self, time_to_think: Duration) {
+        if let Some(clock) = &self.search_clock {
+            clock.set_time_to_think(time_to_think);
+        }
+    }
+
+    /// Analyzes a list of positions.
+    ///
+    /// The positions are searched with the board settings of this game
+    /// (transposition table, evaluation and search parameters), one after
+    /// another or across `config.threads` threads. The current position is
+    /// left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - FEN strings of the positions to analyze
+    /// * `config` - Search limits and number of threads
+    ///
+    /// # Returns
+    ///
+    /// Best move, score and principal variation of each position, in the
+    /// order of `positions`
+    pub fn analyze_batch<S: AsRef<str> + Sync>(
+        &self,
+        positions: &[S],
+        config: &AnalysisConfig,
+    ) -> Vec<AnalysisResult> {
+        analysis::analyze_batch(self, positions, config)
+    }
+
+    /// Creates a game that searches with a copy of this game's board.
+    fn analysis_worker(&self) -> GameState {
+        let mut worker = GameState::new(None);
+        worker.board = self.board.clone();
+        worker
+    }
+
+    /// Performs a perft (performance test) for debugging move generation.
+    ///
+    /// Counts the number of leaf nodes at a given depth for testing move generation correctness.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Depth to search (0 returns immediate count)
+    /// * `print` - Whether to print move counts for each branch
+    ///
+    /// # Returns
+    ///
+    /// Total number of leaf nodes at the specified depth
+    pub fn perft_debug(&mut self, depth: u64, print: bool) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let color = self.side_to_move;
+        let moves = self.board.generate_moves(color);
+
+        if print {
+            println!("Depth {}: {} moves", depth, moves.len());
+        }
+
+        let mut total_nodes = 0;
+
+        for mv in moves {
+            self.board.make_move(&mv);
+            self.side_to_move = self.side_to_move.opposite();
+
+            let nodes = if depth == 1 {
+                1
+            } else {
+                self.perft_debug(depth - 1, false)
+            };
+
+            if print {
+                println!("{}: {}", self.board.move_to_uci(&mv), nodes);
+            }
+
+            total_nodes += nodes;
+
+            self.side_to_move = self.side_to_move.opposite();
+            self.board.unmake_move(&mv);
+        }
+
+        if print {
+            println!("Nodes searched: {}", total_nodes);
+        }
+
+        total_nodes
+    }
+
+    /// Prints the current board state to stdout.
+    pub fn print_board(&self) {
+        self.board.print_board();
+    }
+
+    /// Enables or disables the NNUE evaluation.
+    ///
+    /// When the network file can't be loaded the handcrafted evaluation
+    /// stays in use and the error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `use_nnue` - Whether to evaluate with the network
+    pub fn set_use_nnue(&mut self, use_nnue: bool) -> io::Result<()> {
+        self.use_nnue = use_nnue;
+        self.load_network()
+    }
+
+    /// Enables or disables optional terms of the handcrafted evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `terms` - Optional terms to evaluate
+    pub fn set_evaluation_terms(&mut self, terms: EvaluationTerms) {
+        self.evaluation_terms = terms;
+        self.update_evaluator();
+    }
+
+    /// Gets the optional terms of the handcrafted evaluation in use.
+    pub fn evaluation_terms(&self) -> EvaluationTerms {
+        self.evaluation_terms
+    }
+
+    /// Sets the weights of the handcrafted evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Weights of the heuristics
+    pub fn set_eval_params(&mut self, params: EvalParams) {
+        self.eval_params = params;
+        self.update_evaluator();
+    }
+
+    /// Gets the weights of the handcrafted evaluation in use.
+    pub fn eval_params(&self) -> &EvalParams {
+        &self.eval_params
+    }
+
+    /// Loads the weights of the handcrafted evaluation from a parameter
+    /// file, see [`EvalParams::load`].
+    ///
+    /// The weights in use are kept when the file can't be loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the TOML or JSON file, `None` for the built-in
+    ///   weights
+    pub fn set_eval_params_file(&mut self, path: Option<&Path>) -> io::Result<()> {
+        let params = match path {
+            Some(path) => EvalParams::load(path)?,
+            None => EvalParams::default(),
+        };
+        self.set_eval_params(params);
+        Ok(())
+    }
+
+    /// Sets the playing style, replacing the evaluation weights and the
+    /// search parameters with its presets.
+    ///
+    /// # Arguments
+    ///
+    /// * `personality` - Playing style
+    pub fn set_personality(&mut self, personality: Personality) {
+        self.personality = personality;
+        self.set_eval_params(personality.eval_params());
+        self.board.set_search_params(personality.search_params());
+    }
+
+    /// Gets the playing style last selected.
+    pub fn personality(&self) -> Personality {
+        self.personality
+    }
+
+    /// Rebuilds the handcrafted evaluator from its terms and weights.
+    fn update_evaluator(&mut self) {
+        self.board
+            .set_evaluator(Arc::new(CompositeEvaluator::with_params(
+                self.evaluation_terms,
+                &self.eval_params,
+            )));
+    }
+
+    /// Sets the NNUE network file, loading it if NNUE is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the network file
+    pub fn set_eval_file(&mut self, path: &Path) -> io::Result<()> {
+        self.eval_file = path.to_path_buf();
+        self.load_network()
+    }
+
+    /// Checks whether positions are evaluated by the NNUE network.
+    pub fn is_nnue_active(&self) -> bool {
+        self.board.nnue().is_some()
+    }
+
+    /// Selects the chess variant played, see [`board::rules`].
+    ///
+    /// The transposition table is cleared, since its scores follow the rules
+    /// of the previous variant. The NNUE network is only used in standard
+    /// chess, so it is reloaded when coming back to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the variant, as given to the `UCI_Variant` option
+    ///
+    /// # Returns
+    ///
+    /// `false` if the variant isn't compiled in, the rules are then unchanged
+    pub fn set_variant(&mut self, name: &str) -> bool {
+        let Some(rules) = board::rules::find_variant(name) else {
+            return false;
+        };
+
+        self.board.set_rules(rules);
+        self.clear_hash_table();
+        // A network that can't be loaded was reported when NNUE was enabled
+        let _ = self.load_network();
+        true
+    }
+
+    /// Gets the name of the chess variant played.
+    pub fn variant(&self) -> &'static str {
+        self.board.rules().name()
+    }
+
+    /// Loads the network file into the board, or falls back to the
+    /// handcrafted evaluation.
+    fn load_network(&mut self) -> io::Result<()> {
+        if !self.use_nnue {
+            self.board.set_network(None);
+            return Ok(());
+        }
+
+        match Network::load(&self.eval_file) {
+            Ok(network) => {
+                self.board.set_network(Some(Arc::new(network)));
+                Ok(())
+            }
+            Err(err) => {
+                self.board.set_network(None);
+                Err(err)
+            }
+        }
+    }
+
+    /// Enables or disables learning from played games.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to record games and replay learned moves
+    pub fn set_learning_enabled(&mut self, enabled: bool) -> io::Result<()> {
+        self.experience.lock().unwrap().set_enabled(enabled)
+    }
+
+    /// Sets the file where learned moves are stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the experience file
+    pub fn set_experience_file(&mut self, path: &Path) -> io::Result<()> {
+        self.experience.lock().unwrap().set_path(path)
+    }
+
+    /// Gets the experience shared with the search thread.
+    pub fn experience(&self) -> &Arc<Mutex<Experience>> {
+        &self.experience
+    }
+
+    /// Ends the current game and saves its moves to the experience file.
+    ///
+    /// A position without legal moves, checkmate or stalemate in standard
+    /// chess, or without the material to checkmate decides the result;
+    /// otherwise the result is adjudicated from the last search score.
+    pub fn finish_game(&mut self) -> io::Result<()> {
+        let result = self.game_result();
+        self.experience.lock().unwrap().finish_game(result)
+    }
+
+    /// Gets the result decided by the rules in the current position.
+    ///
+    /// # Returns
+    ///
+    /// The result if the side to move has no legal move, checkmate or
+    /// stalemate in standard chess, a draw if neither side can checkmate,
+    /// see [`ChessBoard::has_insufficient_material`],
+    /// [`GameResult::Unknown`] otherwise
+    pub fn game_result(&mut self) -> GameResult {
+        if self.board.count_legal_moves(self.side_to_move) > 0 {
+            if self.board.has_insufficient_material() {
+                GameResult::Draw
+            } else {
+                GameResult::Unknown
+            }
+        } else {
+            let outcome = self
+                .board
+                .rules()
+                .outcome_without_moves(&self.board, self.side_to_move);
+            match (outcome, self.side_to_move) {
+                (Outcome::Draw, _) => GameResult::Draw,
+                (Outcome::Win, Color::White) | (Outcome::Loss, Color::Black) => {
+                    GameResult::WhiteWins
+                }
+                (Outcome::Win, Color::Black) | (Outcome::Loss, Color::White) => {
+                    GameResult::BlackWins
+                }
+            }
+        }
+    }
+
+    /// Gets the Polyglot opening book key of the current position.
+    pub fn polyglot_key(&self) -> u64 {
+        self.board.polyglot_key(self.side_to_move)
+    }
+
+    /// Switches between the engine's random Zobrist keys and the Polyglot keys.
+    ///
+    /// With Polyglot keys the position hash matches other Polyglot aware
+    /// tools and [`GameState::polyglot_key`] no longer scans the board.
+    /// The transposition table is cleared since its entries were stored
+    /// under the previous keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Use the Polyglot keys when true, the default seeded keys otherwise
+    pub fn set_polyglot_keys(&mut self, enabled: bool) {
+        let zobrist_keys = if enabled {
+            Zobrist::polyglot()
+        } else {
+            Zobrist::new()
+        };
+
+        self.board
+            .set_zobrist_keys(Arc::new(zobrist_keys), self.side_to_move);
+        self.board.clear_transposition_table();
+    }
+
+    /// Writes the current position in Forsyth-Edwards Notation (FEN).
+    pub fn to_fen(&self) -> String {
+        self.board
+            .to_fen(self.side_to_move, self.halfmove_clock, self.fullmove_number)
+    }
+
+    /// Describes the current position for debugging: the board diagram,
+    /// its FEN, Polyglot key and the pieces giving check.
+    ///
+    /// # Arguments
+    ///
+    /// * `flipped` - Whether to draw the board from Black's side
+    pub fn position_dump(&self, flipped: bool) -> String {
+        let checkers: Vec<String> = self
+            .board
+            .checkers(self.side_to_move)
+            .into_iter()
+            .map(ChessBoard::square_name)
+            .collect();
+
+        format!(
+            "\n{}\nFen: {}\nKey: {:016X}\nCheckers: {}\n",
+            self.board.to_diagram(flipped),
+            self.to_fen(),
+            self.polyglot_key(),
+            checkers.join(" ")
+        )
+    }
+
+    /// Gets the static evaluation of the current position, without searching.
+    ///
+    /// # Returns
+    ///
+    /// Evaluation in centipawns from the point of view of the side to move,
+    /// tempo bonus included, see [`ChessBoard::evaluate_for`]
+    pub fn evaluate_cp(&self) -> i32 {
+        self.board.evaluate_for(self.side_to_move).centipawns()
+    }
+
+    /// Describes the static evaluation of the current position: each term
+    /// from White's point of view, their total, the final evaluation for
+    /// the side to move and how many pieces of each side attack each square.
+    pub fn evaluation_report(&self) -> String {
+        let breakdown = self.board.evaluation_breakdown();
+        // The term column fits the longest name
+        let width = breakdown
+            .iter()
+            .map(|(term, _)| term.len())
+            .fold("Total".len(), usize::max);
+        let rule = format!("{}+--------\n", "-".repeat(width + 1));
+
+        let mut report = format!("\n{:^width$} |  White\n{}", "Term", rule);
+        for (term, score) in breakdown {
+            report.push_str(&format!("{:>width$} | {:>6}\n", term, score.centipawns()));
+        }
+
+        let total = self.board.evaluate();
+        report.push_str(&format!(
+            "{}{:>width$} | {:>6}\n\nPhase: {}/{}\nEvaluation: {} cp ({} to move)\n",
+            rule,
+            "Total",
+            total.centipawns(),
+            self.board.game_phase().value(),
+            TOTAL_PHASE,
+            self.evaluate_cp(),
+            match self.side_to_move {
+                Color::White => "White",
+                Color::Black => "Black",
+            }
+        ));
+
+        let attacks = self.board.attack_map();
+        report.push_str("\nAttacks:  White            Black\n");
+        for (white, black) in attacks
+            .diagram(Color::White)
+            .lines()
+            .zip(attacks.diagram(Color::Black).lines())
+        {
+            report.push_str(&format!("          {}  {}\n", white, black));
+        }
+        report
+    }
+
+    /// Gets the opening of the game.
+    ///
+    /// The opening is the one of the last position found in the ECO table,
+    /// so it is kept once the game leaves the known lines.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Opening)` with the ECO code and name, `None` if no position of
+    /// the game is in the table
+    pub fn opening(&self) -> Option<Opening> {
+        self.opening
+    }
+
+    /// Gets the current side to move.
+    pub fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    /// Gets the number of half moves since the last capture or pawn move.
+    pub fn halfmove_clock(&self) -> u64 {
+        self.halfmove_clock
+    }
+
+    /// Gets the full move number, starting at 1.
+    pub fn fullmove_number(&self) -> u64 {
+        self.fullmove_number
+    }
+
+    /// Gets a reference to the underlying chess board.
+    ///
+    /// # Returns
+    ///
+    /// Reference to the ChessBoard instance
+    pub fn get_chess_board(&self) -> &ChessBoard {
+        &self.board
+    }
+
+    /// Replaces the transposition table with an empty one of another size.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_size_mb` - Size in megabytes, zero for no table
+    pub fn resize_hash_table(&mut self, new_size_mb: usize) {
+        self.options.set_hash_mb(new_size_mb);
+        self.apply_hash_size();
+    }
+
+    /// Gets the size in megabytes of the transposition table.
+    pub fn hash_size_mb(&self) -> usize {
+        self.hash_mb
+    }
+
+    /// Resizes the transposition table if the size in the options changed.
+    fn apply_hash_size(&mut self) {
+        let size_mb = self.options.hash_mb();
+        if size_mb != self.hash_mb {
+            let transposition_table = Arc::new(TranspositionTable::new(size_mb));
+            self.board.set_transposition_table(transposition_table);
+            self.hash_mb = size_mb;
+        }
+    }
+
+    /// Gets the options of the engine, shared with the searches.
+    ///
+    /// Options changed through the returned handle, from any thread, apply
+    /// from the next search.
+    pub fn options(&self) -> &Arc<EngineOptions> {
+        &self.options
+    }
+
+    /// Empties the transposition table, keeping its size.
+    pub fn clear_hash_table(&self) {
+        self.board.clear_transposition_table();
+        // Nothing of the last analysis is left to resume
+        *self.resume_point.lock().unwrap() = None;
+    }
+
+    /// Saves the transposition table to a hash file, so a long analysis can
+    /// be resumed in a later session with
+    /// [`load_hash_file`](Self::load_hash_file).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the hash file, replaced if it exists
+    ///
+    /// # Returns
+    ///
+    /// The number of entries saved
+    pub fn save_hash_file(&self, path: &Path) -> io::Result<usize> {
+        self.board.save_transposition_table(path)
+    }
+
+    /// Replaces the transposition table entries with those of a hash file
+    /// written by [`save_hash_file`](Self::save_hash_file).
+    ///
+    /// The file must have been saved with the same Zobrist keys, the table
+    /// is left untouched when it is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the hash file
+    ///
+    /// # Returns
+    ///
+    /// The number of entries read from the file
+    pub fn load_hash_file(&self, path: &Path) -> io::Result<usize> {
+        self.board.load_transposition_table(path)
+    }
+
+    /// Sets the search algorithm to use for future searches.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The search algorithm implementation to use
+    pub fn set_search_algorithm(&mut self, algorithm: Arc<dyn Search + Send + Sync>) {
+        self.search_algorithm = algorithm;
+    }
+
+    /// Gets a reference to the current search algorithm.
+    ///
+    /// # Returns
+    ///
+    /// Reference to the current search algorithm implementation
+    pub fn get_search_algorithm(&self) -> &Arc<dyn Search + Send + Sync> {
+        &self.search_algorithm
+    }
+
+    /// Creates a default game state with its own transposition table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_size_mb` - Size of the transposition table in megabytes,
+    ///   None for no table
+    pub fn new(table_size_mb: Option<usize>) -> Self {
+        Self::with_hash_size(table_size_mb.unwrap_or(0))
+    }
+
+    /// Creates a default game state with its own zobrist keys and a
+    /// transposition table of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `size_mb` - Size of the transposition table in megabytes, zero for
+    ///   no table
+    pub fn with_hash_size(size_mb: usize) -> Self {
+        let options = EngineOptions::new();
+        options.set_hash_mb(size_mb);
+        Self::with_options(Arc::new(options))
+    }
+
+    /// Creates a default game state configured by shared options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Options of the engine, the transposition table is
+    ///   created with their hash size
+    pub fn with_options(options: Arc<EngineOptions>) -> Self {
+        let transposition_table = Arc::new(TranspositionTable::new(options.hash_mb()));
+
+        Self::with_shared_tables(options, Arc::new(Zobrist::new()), transposition_table)
+    }
+
+    /// Creates a default game state using zobrist keys and a
+    /// transposition table shared with other games, for instance to let
+    /// several games fill the same table.
+    ///
+    /// The table is taken to be of the hash size of the options: changing
+    /// that size later gives this game a new table of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Options of the engine
+    /// * `zobrist_keys` - Zobrist keys hashing the positions stored in the table
+    /// * `transposition_table` - Transposition table searched by this game
+    pub fn with_shared_tables(
+        options: Arc<EngineOptions>,
+        zobrist_keys: Arc<Zobrist>,
+        transposition_table: Arc<TranspositionTable>,
+    ) -> Self {
+        let hash_mb = options.hash_mb();
+
+        GameState {
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            side_to_move: Color::White,
+            search_control: None,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            brain_stop_flag: Arc::new(AtomicBool::new(false)),
+            // The depth is limited by the clock of each search, see `max_depth`
+            search_algorithm: Arc::new(IterativeDeepening::new(MinimaxAlphaBeta, u8::MAX)),
+            experience: Arc::new(Mutex::new(Experience::new())),
+            use_nnue: false,
+            eval_file: PathBuf::from(nnue::DEFAULT_EVAL_FILE),
+            ponder_start: None,
+            search_clock: None,
+            info_reporter: None,
+            debug: false,
+            trace_depth: DEFAULT_TRACE_DEPTH,
+            last_trace: Arc::new(Mutex::new(None)),
+            resume_point: Arc::new(Mutex::new(None)),
+            options,
+            hash_mb,
+            variety: Score::ZERO,
+            variety_moves: DEFAULT_VARIETY_MOVES,
+            evaluation_terms: EvaluationTerms::default(),
+            eval_params: EvalParams::default(),
+            personality: Personality::Default,
+            opening: None,
+            previous_openings: Vec::new(),
+            history: Vec::new(),
+            undone_moves: Vec::new(),
             board: ChessBoard::new(
                 zobrist_keys,
                 transposition_table,
-                Arc::new(board::evaluation::CompositeEvaluator::default()),
+                Arc::new(CompositeEvaluator::default()),
             ),
         }
     }
 }
 
-/// Main UCI protocol loop for handling commands from chess GUIs.
-///
-/// Implements the UCI protocol state machine that processes commands from
-/// standard input and sends responses to standard output. The loop continues
-/// until receiving the "quit" command.
-///
-/// # Supported Commands
-///
-/// - `uci`: Engine identification
-/// - `isready`: Engine readiness check
-/// - `ucinewgame`: Start new game
-/// - `position`: Set up board position
-/// - `go`: Start search with parameters
-/// - `quit`: Exit the engine
-/// - `print`: Debug command to display board state
-///
-/// # Protocol Flow
+impl Default for GameState {
+    /// Creates a game state with the default options, owning its zobrist
+    /// keys and a transposition table of [`DEFAULT_HASH_MB`](options::DEFAULT_HASH_MB).
+    fn default() -> Self {
+        Self::with_options(Arc::new(EngineOptions::new()))
+    }
+}
+
+/// State of the UCI loop between commands: the game and the debugging
+/// options of the protocol.
 ///
-/// 1. GUI sends `uci` to initialize
-/// 2. Engine responds with identification and `uciok`
-/// 3. GUI sends `isready` to check engine status
-/// 4. Engine responds with `readyok`
-/// 5. GUI sends `position` to set up the board
-/// 6. GUI sends `go` to start search
-/// 7. Engine responds with `bestmove` when search completes
-/// 8. Process repeats until `quit` command
-pub fn uci_main() {
-    let mut game_state = GameState::new(Some(256));
+/// [`uci_main`] feeds it the lines read from standard input, tests and fuzz
+/// targets call [`UciSession::handle_command`] directly.
+pub struct UciSession {
+    game_state: GameState,
+    /// Background initialization `isready` waits for
+    readiness: ReadinessGate,
+    /// Whether `d` draws the board from Black's side
+    flipped: bool,
+    /// Whether the calibrated speed was reported
+    nps_reported: bool,
+    /// Releases the `bestmove` of the running `go infinite` or `go ponder`
+    /// search, see [`uci::handle_go_command`]
+    answer_release: Option<Sender<()>>,
+}
 
-    // Main UCI protocol loop
-    loop {
-        // Read from stdin
-        let mut cli_cmd = String::new();
-        io::stdin()
-            .read_line(&mut cli_cmd)
-            .expect("Failed to read command");
+impl Default for UciSession {
+    /// Creates a session playing a game with the default options.
+    fn default() -> Self {
+        Self::new(GameState::default())
+    }
+}
+
+impl UciSession {
+    /// Creates a session, starting the background initialization.
+    ///
+    /// The game is set to the starting position, so `d` and `go` sent before
+    /// any `position` command see a valid position.
+    ///
+    /// # Arguments
+    ///
+    /// * `game_state` - Game the commands apply to
+    pub fn new(mut game_state: GameState) -> Self {
+        game_state.start_position();
+        game_state.set_info_reporter(Some(Arc::new(|line: &str| println!("{}", line))));
+
+        // Ready before the first search, without delaying the GUI handshake,
+        // but `isready` waits for it
+        let readiness = ReadinessGate::new();
+        readiness.spawn(kpk::init);
+
+        UciSession {
+            game_state,
+            readiness,
+            flipped: false,
+            nps_reported: false,
+            answer_release: None,
+        }
+    }
+
+    /// Measures the single-thread speed of the engine in the background,
+    /// see [`bench::calibrate`].
+    ///
+    /// `isready` waits for the measure, and the first `isready` after it
+    /// reports the speed as an `info string`.
+    pub fn start_calibration(&self) {
+        self.readiness.spawn(|| {
+            // Measured alone, once the bitbase is generated
+            kpk::init();
+            bench::calibrate(bench::CALIBRATION_TIME);
+        });
+    }
 
-        let cmd = cli_cmd.trim();
-        let mut uci_cmd = cmd.split_whitespace();
+    /// Gets the game the commands apply to.
+    pub fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    /// Lets the running `go infinite` or `go ponder` search send its
+    /// `bestmove` once it is over.
+    fn release_answer(&mut self) {
+        if let Some(release) = self.answer_release.take() {
+            // The answering thread may already be gone
+            let _ = release.send(());
+        }
+    }
+
+    /// Handles one command line, writing the responses to standard output.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - Line received from the GUI
+    ///
+    /// # Returns
+    ///
+    /// false after `quit`, true otherwise
+    pub fn handle_command(&mut self, command: &str) -> bool {
+        let cmd = command.trim();
+        let mut tokens = cmd.split_whitespace();
 
         // Get command keyword and dispatch to appropriate handler
-        if let Some(keyword) = uci_cmd.next() {
+        if let Some(keyword) = tokens.next() {
             match keyword {
                 "uci" => {
                     uci::handle_uci_command();
                 }
                 "isready" => {
-                    // Confirm engine is ready to receive commands
+                    // Confirm engine is ready to receive commands, once the
+                    // background initialization is over
+                    if self.readiness.wait() > 0 {
+                        uci::report_error("background initialization failed");
+                    }
+                    if !self.nps_reported
+                        && let Some(nps) = bench::calibrated_nps()
+                    {
+                        println!("info string calibrated nps {} on one thread", nps);
+                        self.nps_reported = true;
+                    }
                     println!("readyok");
                 }
                 "ucinewgame" => {
-                    // Reset to standard starting position
-                    game_state.start_position();
+                    // Learn from the previous game, then reset to standard starting position
+                    self.game_state.stop_permanent_brain();
+                    if let Err(err) = self.game_state.finish_game() {
+                        println!("info string Could not save experience: {}", err);
+                    }
+                    self.game_state.start_position();
                 }
                 "quit" => {
-                    // Exit the UCI protocol loop
-                    break;
+                    // Save what was learned and exit the UCI protocol loop
+                    self.game_state.stop_search();
+                    self.game_state.stop_permanent_brain();
+                    if let Err(err) = self.game_state.finish_game() {
+                        println!("info string Could not save experience: {}", err);
+                    }
+                    return false;
                 }
                 "position" => {
-                    let args: Vec<&str> = uci_cmd.collect();
-
-                    if args.is_empty() {
-                        println!("info string No position args");
-                    } else if args[0] == "startpos" {
-                        // Set up standard starting position
-                        game_state.start_position();
-                        // Apply move sequence if provided
-                        if args.len() > 1 && args[1] == "moves" {
-                            for mv in &args[2..] {
-                                game_state.make_move(mv);
-                            }
-                        }
-                    } else if args[0] == "fen" {
-                        // Set up custom position from FEN string
-                        if let Some(idx) = args.iter().position(|&x| x == "moves") {
-                            // FEN followed by move sequence
-                            let fen = args[1..idx].join(" ");
-                            game_state.set_fen_position(&fen);
-                            for mv in &args[idx + 1..] {
-                                game_state.make_move(mv);
-                            }
-                        } else {
-                            // FEN without additional moves
-                            let fen = args[1..].join(" ");
-                            game_state.set_fen_position(&fen);
-                        }
-                    }
+                    // Set up the board from startpos or a FEN plus optional moves,
+                    // the opponent has moved
+                    self.game_state.stop_permanent_brain();
+                    uci::handle_position_command(&mut self.game_state, &mut tokens);
                 }
                 "go" => {
                     // Start search with parsed parameters
-                    uci::handle_go_command(&mut game_state, &mut uci_cmd);
+                    self.answer_release = uci::handle_go_command(&mut self.game_state, &mut tokens);
                 }
 
                 "stop" => {
-                    game_state.stop_search();
+                    self.game_state.stop_search();
+                    self.release_answer();
+                }
+
+                "ponderhit" => {
+                    // The opponent played the pondered move, start the clock
+                    self.game_state.ponderhit();
+                    self.release_answer();
                 }
 
                 "setoption" => {
                     // Configure engine based on the GUI parameters
-                    uci::handle_setoption_command(&mut game_state, &mut uci_cmd);
+                    uci::handle_setoption_command(&mut self.game_state, &mut tokens);
                 }
 
+                "debug" => match tokens.next() {
+                    Some("on") => self.game_state.set_debug(true),
+                    Some("off") => self.game_state.set_debug(false),
+                    _ => uci::report_error("expected 'debug on' or 'debug off'"),
+                },
+
+                // Not a uci command, dumps the tree of the last search made
+                // in debug mode as text or JSON
+                "trace" => match self.game_state.last_trace() {
+                    Some(trace) if tokens.next() == Some("json") => {
+                        println!("{}", trace.to_json())
+                    }
+                    Some(trace) => print!("{}", trace.to_text()),
+                    None => println!("info string No trace, search with 'debug on' first"),
+                },
+
                 // This is not a uci command, is my way of printing the board
                 "print" => {
                     // Debug command to display current board state
-                    game_state.print_board();
+                    self.game_state.print_board();
+                }
+
+                // Not uci commands, Stockfish-style position dump and the
+                // side the board is drawn from
+                "d" => print!("{}", self.game_state.position_dump(self.flipped)),
+
+                // Not a uci command, static evaluation with its terms
+                "eval" => print!("{}", self.game_state.evaluation_report()),
+
+                // Not a uci command, searches the benchmark positions
+                "bench" => {
+                    let args: Vec<&str> = tokens.collect();
+                    match bench::BenchConfig::from_args(&args) {
+                        Ok(config) => print!("{}", bench::run_bench_with(&config)),
+                        Err(error) => uci::report_error(error),
+                    }
+                }
+                // Not a uci command, saves or loads the transposition table
+                "tt" => uci::handle_tt_command(&self.game_state, &mut tokens),
+                // Not uci commands, take back the last move and play it again
+                "undo" | "takeback" => match self.game_state.undo_last_move() {
+                    Some(mv) => println!("info string Took back {}", mv),
+                    None => println!("info string No move to take back"),
+                },
+                "redo" => match self.game_state.redo_move() {
+                    Some(mv) => println!("info string Played {}", mv),
+                    None => println!("info string No move to redo"),
+                },
+                "flip" => {
+                    self.flipped = !self.flipped;
+                    print!(
+                        "{}",
+                        self.game_state.get_chess_board().to_diagram(self.flipped)
+                    );
                 }
                 _ => {
                     // Handle unrecognized commands gracefully
-                    println!("info string Unhandled command: {}", cmd);
+                    uci::report_error(format!("unknown command: '{}'", cmd));
                 }
             }
         }
 
+        true
+    }
+}
+
+/// Main UCI protocol loop for handling commands from chess GUIs.
+///
+/// Implements the UCI protocol state machine that processes commands from
+/// standard input and sends responses to standard output. The loop continues
+/// until receiving the "quit" command.
+///
+/// # Supported Commands
+///
+/// - `uci`: Engine identification
+/// - `isready`: Engine readiness check
+/// - `ucinewgame`: Start new game
+/// - `position`: Set up board position
+/// - `go`: Start search with parameters
+/// - `quit`: Exit the engine
+/// - `print`: Debug command to display board state
+/// - `d`: Debug command to display the board, FEN, key and checkers
+/// - `flip`: Debug command to switch the side the board is drawn from
+/// - `eval`: Debug command to display the static evaluation and its terms
+/// - `bench [ttSize] [threads] [depth] [fenfile]`: Searches the benchmark
+///   positions, see [`bench`]
+/// - `tt save <path>`, `tt load <path>`: Saves the transposition table to a
+///   file or loads it back
+///
+/// # Protocol Flow
+///
+/// 1. GUI sends `uci` to initialize
+/// 2. Engine responds with identification and `uciok`
+/// 3. GUI sends `isready` to check engine status
+/// 4. Engine responds with `readyok`
+/// 5. GUI sends `position` to set up the board
+/// 6. GUI sends `go` to start search
+/// 7. Engine responds with `bestmove` when search completes
+/// 8. Process repeats until `quit` command
+pub fn uci_main() {
+    let mut session = UciSession::default();
+    session.start_calibration();
+
+    // Main UCI protocol loop
+    loop {
+        // Read from stdin
+        let mut cli_cmd = String::new();
+        io::stdin()
+            .read_line(&mut cli_cmd)
+            .expect("Failed to read command");
+
+        let running = session.handle_command(&cli_cmd);
+
         // Flush stdout after every response (important for UCI protocol)
         io::stdout().flush().unwrap();
+
+        if !running {
+            break;
+        }
     }
 }
@@ -0,0 +1,73 @@
+//! Pinning of search threads to CPUs.
+//!
+//! On machines with many cores the scheduler moves threads from CPU to CPU,
+//! and from one NUMA node to another, and each move costs the thread its
+//! caches. With the `ThreadAffinity` option every search thread is pinned to
+//! a CPU of its own. Analysis threads pin themselves before they build their
+//! per-thread data, their copy of the board with its piece lists and undo
+//! stack, and the kernel places memory on the node of the CPU that first
+//! touches it, so that data ends up local to the thread. The transposition
+//! table is shared by every thread and isn't placed on any particular node.
+//!
+//! Pinning is only implemented on Linux. Elsewhere threads run unpinned.
+
+/// Pins the calling thread to one of the CPUs the process may run on.
+///
+/// Threads are spread over the allowed CPUs in order, so with no more
+/// threads than CPUs each one gets a CPU of its own.
+///
+/// # Arguments
+///
+/// * `index` - Index of the thread among the search threads
+///
+/// # Returns
+///
+/// `true` if the thread was pinned
+pub fn pin_current_thread(index: usize) -> bool {
+    #[cfg(target_os = "linux")]
+    return linux::pin_current_thread(index);
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = index;
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::os::raw::{c_int, c_ulong};
+
+    /// Words of a CPU mask, as large as the `cpu_set_t` of the C library.
+    const MASK_WORDS: usize = 1024 / c_ulong::BITS as usize;
+
+    type CpuMask = [c_ulong; MASK_WORDS];
+
+    unsafe extern "C" {
+        fn sched_getaffinity(pid: c_int, cpusetsize: usize, mask: *mut c_ulong) -> c_int;
+        fn sched_setaffinity(pid: c_int, cpusetsize: usize, mask: *const c_ulong) -> c_int;
+    }
+
+    pub(super) fn pin_current_thread(index: usize) -> bool {
+        let bits = c_ulong::BITS as usize;
+        let mut allowed: CpuMask = [0; MASK_WORDS];
+        // SAFETY: the mask is as large as the size given, and pid 0 is the
+        // calling thread
+        if unsafe { sched_getaffinity(0, size_of::<CpuMask>(), allowed.as_mut_ptr()) } != 0 {
+            return false;
+        }
+
+        let cpus: Vec<usize> = (0..MASK_WORDS * bits)
+            .filter(|&cpu| allowed[cpu / bits] >> (cpu % bits) & 1 == 1)
+            .collect();
+        if cpus.is_empty() {
+            return false;
+        }
+
+        let cpu = cpus[index % cpus.len()];
+        let mut mask: CpuMask = [0; MASK_WORDS];
+        mask[cpu / bits] = 1 << (cpu % bits);
+        // SAFETY: as above, the kernel only reads the mask
+        unsafe { sched_setaffinity(0, size_of::<CpuMask>(), mask.as_ptr()) == 0 }
+    }
+}
@@ -0,0 +1,176 @@
+//! Batch analysis of positions.
+//!
+//! Searches a list of FEN positions one after another, or spread over
+//! several threads sharing the transposition table, and collects the best
+//! move, score and principal variation of each one. Useful to annotate
+//! datasets or to look for puzzles.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::game_state::GameState;
+use crate::game_state::affinity;
+use crate::game_state::board::score::Score;
+use crate::game_state::board::search::{IterativeDeepening, MinimaxAlphaBeta, Search, SearchClock};
+
+/// Longest principal variation reported.
+pub(super) const MAX_PV_LENGTH: usize = 32;
+
+/// Limits and resources of a batch analysis.
+#[derive(Clone, Debug)]
+pub struct AnalysisConfig {
+    /// Search depth in plies
+    pub depth: u8,
+    /// Time limit for each position
+    pub movetime: Option<Duration>,
+    /// Number of positions searched at the same time
+    pub threads: usize,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            depth: 5,
+            movetime: None,
+            threads: 1,
+        }
+    }
+}
+
+/// Outcome of the analysis of one position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnalysisResult {
+    /// The analyzed position
+    pub fen: String,
+    /// Best move in UCI format, None if the FEN was rejected or there is no
+    /// legal move
+    pub best_move: Option<String>,
+    /// Score from the point of view of the side to move, mated or drawn
+    /// when there is no legal move
+    pub score: Score,
+    /// Principal variation in UCI format, starting with the best move
+    pub pv: Vec<String>,
+    /// Nodes searched
+    pub nodes: u64,
+}
+
+impl AnalysisResult {
+    /// Creates the result of a position that couldn't be searched.
+    fn unsearched(fen: &str) -> Self {
+        AnalysisResult {
+            fen: fen.to_string(),
+            best_move: None,
+            score: Score::ZERO,
+            pv: Vec::new(),
+            nodes: 0,
+        }
+    }
+}
+
+/// Searches every position with its own copy of `game`'s board.
+///
+/// # Arguments
+///
+/// * `game` - Game whose board settings (keys, table, evaluation, search
+///   parameters) are used
+/// * `positions` - FEN strings of the positions to analyze
+/// * `config` - Search limits and number of threads
+///
+/// # Returns
+///
+/// One result per position, in the same order
+pub(super) fn analyze_batch<S: AsRef<str> + Sync>(
+    game: &GameState,
+    positions: &[S],
+    config: &AnalysisConfig,
+) -> Vec<AnalysisResult> {
+    let next_position = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; positions.len()]);
+    let threads = config.threads.clamp(1, positions.len().max(1));
+
+    thread::scope(|scope| {
+        for index in 0..threads {
+            let next_position = &next_position;
+            let results = &results;
+
+            scope.spawn(move || {
+                // Pinned first, so the worker's board is allocated on the
+                // NUMA node of its CPU
+                if game.thread_affinity() {
+                    affinity::pin_current_thread(index);
+                }
+                let mut worker = game.analysis_worker();
+
+                loop {
+                    let index = next_position.fetch_add(1, Ordering::Relaxed);
+                    let Some(fen) = positions.get(index) else {
+                        break;
+                    };
+
+                    let result = analyze_position(&mut worker, fen.as_ref(), config);
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every position is analyzed"))
+        .collect()
+}
+
+/// Searches a single position.
+fn analyze_position(game: &mut GameState, fen: &str, config: &AnalysisConfig) -> AnalysisResult {
+    if !game.set_fen_position(fen) {
+        return AnalysisResult::unsearched(fen);
+    }
+
+    let side_to_move = game.side_to_move();
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let clock = Arc::new(SearchClock::new(Arc::clone(&stop_flag)));
+    if let Some(movetime) = config.movetime {
+        clock.set_time_to_think(movetime);
+    }
+
+    let mut board = game.get_chess_board().clone();
+    board.set_clock(Some(clock));
+    let nodes_before = board.nodes();
+
+    let search = IterativeDeepening::new(MinimaxAlphaBeta, config.depth.max(1));
+    let (score, best_move) = search.search(&mut board, side_to_move, stop_flag);
+    let nodes = board.nodes() - nodes_before;
+
+    let Some(best_move) = best_move else {
+        // No legal move: the rules decide the game, checkmate or stalemate
+        // in standard chess
+        let score = board
+            .rules()
+            .outcome_without_moves(&board, side_to_move)
+            .score();
+        return AnalysisResult {
+            score,
+            nodes,
+            ..AnalysisResult::unsearched(fen)
+        };
+    };
+
+    let pv = board
+        .principal_variation(&best_move, side_to_move, MAX_PV_LENGTH)
+        .iter()
+        .map(|mv| board.move_to_uci(mv))
+        .collect();
+
+    AnalysisResult {
+        fen: fen.to_string(),
+        best_move: Some(board.move_to_uci(&best_move)),
+        score: score.relative_to(side_to_move),
+        pv,
+        nodes,
+    }
+}
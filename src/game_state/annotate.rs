@@ -0,0 +1,440 @@
+//! Game annotation.
+//!
+//! Every position of a game is analyzed with the batch analysis, the
+//! position after the last move included. The score of the move played is
+//! the score of the next position from the opponent's point of view, so its
+//! difference with the score of the best move is what the move lost. Losses
+//! above the thresholds of the configuration mark the move as an inaccuracy
+//! (`?!`), a mistake (`?`) or a blunder (`??`), followed by the best line as
+//! a variation.
+//!
+//! Each move is followed by a comment with the evaluation after it, in pawns
+//! from White's point of view as usual in PGN: `{+0.35}`, or `{#-3}` when
+//! Black mates in three.
+
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::game_state::analysis::{AnalysisConfig, AnalysisResult};
+use crate::game_state::board::score::Score;
+use crate::game_state::pgn::{self, GameResult, PgnGame, parse_pgn};
+use crate::game_state::{Color, GameState, version};
+
+/// Time spent on each position by default.
+pub const DEFAULT_ANNOTATE_MOVETIME: Duration = Duration::from_millis(1000);
+
+/// Longest best line given as a variation, in plies.
+const MAX_VARIATION_LENGTH: usize = 8;
+
+/// Settings of the annotation.
+#[derive(Clone, Debug)]
+pub struct AnnotateConfig {
+    /// Smallest loss of an inaccuracy
+    pub inaccuracy: Score,
+    /// Smallest loss of a mistake
+    pub mistake: Score,
+    /// Smallest loss of a blunder
+    pub blunder: Score,
+    /// Limits of the analysis of each position
+    pub analysis: AnalysisConfig,
+}
+
+impl Default for AnnotateConfig {
+    fn default() -> Self {
+        AnnotateConfig {
+            inaccuracy: Score::cp(50),
+            mistake: Score::cp(100),
+            blunder: Score::cp(300),
+            // Without a depth limit, the movetime alone stops the search
+            analysis: AnalysisConfig {
+                depth: u8::MAX,
+                movetime: Some(DEFAULT_ANNOTATE_MOVETIME),
+                ..AnalysisConfig::default()
+            },
+        }
+    }
+}
+
+/// How bad a move is, from the score it lost.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Judgement {
+    /// Lost at least the inaccuracy threshold (`?!`)
+    Inaccuracy,
+    /// Lost at least the mistake threshold (`?`)
+    Mistake,
+    /// Lost at least the blunder threshold (`??`)
+    Blunder,
+}
+
+impl Judgement {
+    /// Judges a move from the score it lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `loss` - Score lost against the best move
+    /// * `config` - Thresholds of each judgement
+    ///
+    /// # Returns
+    ///
+    /// `Some(Judgement)` for the worst threshold reached, `None` for a good
+    /// move
+    pub fn from_loss(loss: Score, config: &AnnotateConfig) -> Option<Self> {
+        if loss >= config.blunder {
+            Some(Judgement::Blunder)
+        } else if loss >= config.mistake {
+            Some(Judgement::Mistake)
+        } else if loss >= config.inaccuracy {
+            Some(Judgement::Inaccuracy)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the PGN suffix annotation of the judgement.
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "?!",
+            Judgement::Mistake => "?",
+            Judgement::Blunder => "??",
+        }
+    }
+}
+
+/// A move of a game with the analysis of the engine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotatedMove {
+    /// Move played, in SAN
+    pub san: String,
+    /// Evaluation after the move, from White's point of view
+    pub evaluation: Score,
+    /// Evaluation after the best move, from White's point of view
+    pub best_evaluation: Score,
+    /// Score lost against the best move, from the point of view of the side
+    /// that played it
+    pub loss: Score,
+    /// Judgement of the move, `None` for a good move
+    pub judgement: Option<Judgement>,
+    /// Best line in SAN for judged moves, empty otherwise
+    pub best_line: Vec<String>,
+}
+
+/// A game with its annotated moves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotatedGame {
+    /// Tag pairs of the original game
+    pub tags: Vec<(String, String)>,
+    /// Position the game starts from
+    pub start_fen: String,
+    /// Moves replayed and analyzed
+    pub moves: Vec<AnnotatedMove>,
+    /// Moves from the first one that couldn't be replayed, kept as they are
+    pub unannotated: Vec<String>,
+    /// Result of the game
+    pub result: GameResult,
+}
+
+impl AnnotatedGame {
+    /// Gets the tag pairs of the game, the engine being recorded as the
+    /// annotator.
+    pub fn export_tags(&self) -> Vec<(String, String)> {
+        let mut tags: Vec<(String, String)> = self
+            .tags
+            .iter()
+            .filter(|(name, _)| name != "Annotator")
+            .cloned()
+            .collect();
+        tags.push(("Annotator".to_string(), version::engine_name()));
+        tags
+    }
+
+    /// Gets the game in PGN.
+    pub fn to_pgn(&self) -> String {
+        pgn::format_game(&self.export_tags(), &self.movetext(), self.result)
+    }
+
+    /// Gets the moves of the game in PGN movetext, with their judgements,
+    /// evaluations and variations. The result is left out.
+    pub fn movetext(&self) -> String {
+        let fields: Vec<&str> = self.start_fen.split_whitespace().collect();
+        let mut side = match fields.get(1) {
+            Some(&"b") => Color::Black,
+            _ => Color::White,
+        };
+        let mut move_number: u64 = fields.get(5).and_then(|n| n.parse().ok()).unwrap_or(1);
+
+        let mut movetext = String::new();
+        // Black moves are numbered again after a comment or a variation
+        let mut interrupted = true;
+        for annotated in &self.moves {
+            write_move_number(&mut movetext, side, move_number, interrupted);
+            movetext.push_str(&annotated.san);
+            movetext.push_str(annotated.judgement.map_or("", Judgement::glyph));
+            write_evaluation(&mut movetext, annotated.evaluation, side.opposite());
+            movetext.push(' ');
+
+            if !annotated.best_line.is_empty() {
+                movetext.push('(');
+                let mut variation_side = side;
+                let mut variation_number = move_number;
+                for (index, san) in annotated.best_line.iter().enumerate() {
+                    write_move_number(&mut movetext, variation_side, variation_number, index < 2);
+                    movetext.push_str(san);
+                    if index == 0 {
+                        write_evaluation(&mut movetext, annotated.best_evaluation, side.opposite());
+                    }
+                    movetext.push(' ');
+
+                    if variation_side == Color::Black {
+                        variation_number += 1;
+                    }
+                    variation_side = variation_side.opposite();
+                }
+                movetext.truncate(movetext.trim_end().len());
+                movetext.push_str(") ");
+            }
+
+            if side == Color::Black {
+                move_number += 1;
+            }
+            side = side.opposite();
+        }
+
+        for san in &self.unannotated {
+            write_move_number(&mut movetext, side, move_number, interrupted);
+            write!(movetext, "{} ", san).unwrap();
+            interrupted = false;
+
+            if side == Color::Black {
+                move_number += 1;
+            }
+            side = side.opposite();
+        }
+
+        movetext.truncate(movetext.trim_end().len());
+        movetext
+    }
+}
+
+/// Writes the number of a move: always before White's moves, before
+/// Black's only when they don't follow White's directly.
+fn write_move_number(movetext: &mut String, side: Color, move_number: u64, interrupted: bool) {
+    if side == Color::White {
+        write!(movetext, "{}. ", move_number).unwrap();
+    } else if interrupted {
+        write!(movetext, "{}... ", move_number).unwrap();
+    }
+}
+
+/// Writes the evaluation after a move as a PGN comment. A mate on the board
+/// is left out, the SAN of the move already ends with `#`.
+fn write_evaluation(movetext: &mut String, score: Score, side_to_move: Color) {
+    if score.relative_to(side_to_move).mate_moves() != Some(0) {
+        write!(movetext, " {{{}}}", format_evaluation(score, side_to_move)).unwrap();
+    }
+}
+
+/// Formats the evaluation of a position for a PGN comment, e.g. `+0.35`,
+/// `-1.20` or `#-3`.
+///
+/// # Arguments
+///
+/// * `score` - Evaluation from White's point of view
+/// * `side_to_move` - Side to move in the position, which mates are counted
+///   from
+pub fn format_evaluation(score: Score, side_to_move: Color) -> String {
+    match score.relative_to(side_to_move).mate_moves() {
+        Some(moves) if score < Score::ZERO => format!("#-{}", moves.abs()),
+        Some(moves) => format!("#{}", moves.abs()),
+        None => format!("{:+.2}", f64::from(score.centipawns()) / 100.0),
+    }
+}
+
+/// A game replayed up to its first illegal move.
+struct Replay {
+    /// Position the game starts from
+    start_fen: String,
+    /// FEN before each replayed move, then after the last one
+    positions: Vec<String>,
+    /// Replayed moves in SAN and UCI formats
+    played: Vec<(String, String)>,
+    /// Moves that couldn't be replayed
+    unannotated: Vec<String>,
+}
+
+/// Annotates games with the analysis of their positions.
+///
+/// Games are analyzed up to their first illegal move, the moves after it
+/// are kept without annotations.
+///
+/// # Arguments
+///
+/// * `games` - Parsed PGN games
+/// * `config` - Thresholds and analysis limits
+///
+/// # Returns
+///
+/// Annotated games, in the same order
+pub fn annotate_games(games: &[PgnGame], config: &AnnotateConfig) -> Vec<AnnotatedGame> {
+    let mut game_state = GameState::new(Some(64));
+
+    let replays: Vec<Replay> = games
+        .iter()
+        .map(|game| replay_game(&mut game_state, game))
+        .collect();
+    let positions: Vec<&String> = replays
+        .iter()
+        .flat_map(|replay| &replay.positions)
+        .collect();
+    let mut results = game_state
+        .analyze_batch(&positions, &config.analysis)
+        .into_iter();
+
+    games
+        .iter()
+        .zip(replays)
+        .map(|(game, replay)| {
+            let analyses: Vec<AnalysisResult> =
+                results.by_ref().take(replay.positions.len()).collect();
+            let moves = replay
+                .played
+                .iter()
+                .zip(analyses.windows(2))
+                .map(|((san, uci), pair)| {
+                    annotate_move(&mut game_state, san, uci, &pair[0], &pair[1], config)
+                })
+                .collect();
+
+            AnnotatedGame {
+                tags: game.tags.clone(),
+                start_fen: replay.start_fen,
+                moves,
+                unannotated: replay.unannotated,
+                result: game.result,
+            }
+        })
+        .collect()
+}
+
+/// Annotates the games of a PGN file.
+///
+/// # Arguments
+///
+/// * `pgn_path` - Path of the PGN database
+/// * `config` - Thresholds and analysis limits
+///
+/// # Returns
+///
+/// Annotated games, in file order
+pub fn annotate_pgn(pgn_path: &Path, config: &AnnotateConfig) -> io::Result<Vec<AnnotatedGame>> {
+    let pgn = fs::read_to_string(pgn_path)?;
+    Ok(annotate_games(&parse_pgn(&pgn), config))
+}
+
+/// Replays a game, collecting its positions and its moves.
+fn replay_game(game_state: &mut GameState, game: &PgnGame) -> Replay {
+    let started = match game.tag("FEN") {
+        Some(fen) => game_state.set_fen_position(fen),
+        None => {
+            game_state.start_position();
+            true
+        }
+    };
+    if !started {
+        return Replay {
+            start_fen: game.tag("FEN").unwrap_or_default().to_string(),
+            positions: Vec::new(),
+            played: Vec::new(),
+            unannotated: game.moves.clone(),
+        };
+    }
+
+    let mut replay = Replay {
+        start_fen: game_state.to_fen(),
+        positions: vec![game_state.to_fen()],
+        played: Vec::new(),
+        unannotated: Vec::new(),
+    };
+    for (index, san) in game.moves.iter().enumerate() {
+        let record = game_state.create_move_from_san(san).and_then(|mv| {
+            let uci = game_state.get_chess_board().move_to_uci(&mv);
+            game_state
+                .legal_moves()
+                .into_iter()
+                .find(|record| record.uci == uci)
+        });
+        let Some(record) = record else {
+            replay.unannotated = game.moves[index..].to_vec();
+            break;
+        };
+
+        game_state.make_move(&record.uci);
+        replay.positions.push(game_state.to_fen());
+        replay.played.push((record.san, record.uci));
+    }
+
+    replay
+}
+
+/// Annotates a move from the analyses of the positions before and after
+/// it.
+fn annotate_move(
+    game_state: &mut GameState,
+    san: &str,
+    uci: &str,
+    before: &AnalysisResult,
+    after: &AnalysisResult,
+    config: &AnnotateConfig,
+) -> AnnotatedMove {
+    let side = if before.fen.split_whitespace().nth(1) == Some("b") {
+        Color::Black
+    } else {
+        Color::White
+    };
+    // The score after the move is from the opponent's point of view
+    let played = -after.score;
+    let best = before.score.max(played);
+
+    let loss = if before.best_move.as_deref() == Some(uci) {
+        Score::ZERO
+    } else {
+        best - played
+    };
+    let judgement = Judgement::from_loss(loss, config);
+    let best_line = match judgement {
+        Some(_) => san_line(game_state, &before.fen, &before.pv),
+        None => Vec::new(),
+    };
+
+    AnnotatedMove {
+        san: san.to_string(),
+        evaluation: played.relative_to(side),
+        best_evaluation: best.relative_to(side),
+        loss,
+        judgement,
+        best_line,
+    }
+}
+
+/// Converts the start of a line of UCI moves to SAN.
+fn san_line(game_state: &mut GameState, fen: &str, pv: &[String]) -> Vec<String> {
+    game_state.set_fen_position(fen);
+
+    let mut line = Vec::new();
+    for uci in pv.iter().take(MAX_VARIATION_LENGTH) {
+        let Some(record) = game_state
+            .legal_moves()
+            .into_iter()
+            .find(|record| &record.uci == uci)
+        else {
+            break;
+        };
+
+        line.push(record.san);
+        game_state.make_move(uci);
+    }
+
+    line
+}
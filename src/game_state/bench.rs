@@ -0,0 +1,365 @@
+//! Search benchmark.
+//!
+//! Searches a fixed set of positions to a fixed depth and reports the total
+//! node count and speed, in the `<nodes> nodes <nps> nps` format expected by
+//! distributed testing frameworks such as OpenBench. The node count only
+//! depends on the search itself, so it works as a signature of the engine's
+//! behaviour: a change that isn't meant to alter the search must keep it.
+//!
+//! Like other engines, the benchmark takes `[ttSize] [threads] [depth]
+//! [fenfile]` arguments so testers can compare hardware with the same
+//! settings. Each thread searches its own positions with its own
+//! transposition table, so the node count doesn't depend on the threads.
+//!
+//! The engine also measures its single-thread speed when the UCI loop
+//! starts, with a short [`calibrate`] run, or with the first benchmark run
+//! on one thread. The speed is kept as [`calibrated_nps`].
+
+use std::fmt;
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::game_state::GameState;
+use crate::game_state::board::search::clock::SearchClock;
+use crate::game_state::board::search::{
+    Depth, IterativeDeepening, MinimaxAlphaBeta, Search, SearchAlgorithm,
+};
+use crate::game_state::topology;
+
+/// Depth searched when no depth is given.
+pub const DEFAULT_BENCH_DEPTH: u8 = 4;
+
+/// Transposition table size used by the benchmark, in megabytes.
+pub const BENCH_HASH_SIZE: usize = 16;
+
+/// Largest transposition table size accepted, in megabytes, as for the
+/// `Hash` option.
+const MAX_BENCH_HASH_SIZE: usize = 2048;
+
+/// Time spent measuring the speed of the engine when the UCI loop starts.
+pub const CALIBRATION_TIME: Duration = Duration::from_millis(250);
+
+/// Depth of the searches measuring the speed of the engine.
+const CALIBRATION_DEPTH: Depth = 2;
+
+/// Single-thread speed measured first, by [`calibrate`] or a benchmark run.
+static CALIBRATED_NPS: OnceLock<u64> = OnceLock::new();
+
+/// Positions searched by the benchmark, covering openings, middlegames with
+/// tactics and endgames.
+pub const BENCH_POSITIONS: [&str; 10] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "r1bq1rk1/pp2ppbp/2np1np1/8/3NP3/2N1BP2/PPPQ2PP/R3KB1R w KQ - 3 9",
+    "2r3k1/pp3ppp/4p3/3n4/3P4/P4N2/1P3PPP/2R3K1 b - - 0 25",
+    "8/8/4k3/3p4/3P4/4K3/8/8 w - - 0 1",
+    "6k1/5p2/6p1/8/7P/8/5PK1/3R4 w - - 0 40",
+];
+
+/// Settings of a benchmark run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Transposition table size of each thread, in megabytes
+    pub hash_size: usize,
+    /// Number of positions searched at the same time
+    pub threads: usize,
+    /// Search depth in plies
+    pub depth: u8,
+    /// Positions to search, as FENs
+    pub positions: Vec<String>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            hash_size: BENCH_HASH_SIZE,
+            threads: 1,
+            depth: DEFAULT_BENCH_DEPTH,
+            positions: BENCH_POSITIONS.iter().map(|fen| fen.to_string()).collect(),
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Reads the `[ttSize] [threads] [depth] [fenfile]` arguments.
+    ///
+    /// Missing arguments keep their default, and 0 threads stands for one
+    /// thread per physical core. The FEN file has one position
+    /// per line, skipping empty lines and lines starting with `#`, and
+    /// `default` stands for the built-in positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - Arguments following the `bench` keyword
+    ///
+    /// # Returns
+    ///
+    /// The settings, or a message naming the invalid argument
+    pub fn from_args<S: AsRef<str>>(args: &[S]) -> Result<Self, String> {
+        let mut config = BenchConfig::default();
+        let mut args = args.iter().map(AsRef::as_ref);
+
+        if let Some(hash_size) = args.next() {
+            config.hash_size = match hash_size.parse() {
+                Ok(size) if (1..=MAX_BENCH_HASH_SIZE).contains(&size) => size,
+                _ => return Err(format!("invalid bench ttSize: '{}'", hash_size)),
+            };
+        }
+        if let Some(threads) = args.next() {
+            config.threads = match threads.parse() {
+                Ok(threads) => topology::resolve_threads(threads),
+                _ => return Err(format!("invalid bench threads: '{}'", threads)),
+            };
+        }
+        if let Some(depth) = args.next() {
+            config.depth = match depth.parse() {
+                Ok(depth) if depth > 0 => depth,
+                _ => return Err(format!("invalid bench depth: '{}'", depth)),
+            };
+        }
+        if let Some(file) = args.next().filter(|&file| file != "default") {
+            let text = fs::read_to_string(file)
+                .map_err(|error| format!("failed to read {}: {}", file, error))?;
+            config.positions = text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+
+            let mut game = GameState::new(None);
+            if let Some(fen) = config
+                .positions
+                .iter()
+                .find(|fen| !game.set_fen_position(fen))
+            {
+                return Err(format!("invalid FEN in {}: '{}'", file, fen));
+            }
+        }
+        if let Some(extra) = args.next() {
+            return Err(format!("unexpected bench argument: '{}'", extra));
+        }
+        Ok(config)
+    }
+}
+
+/// Nodes searched in one benchmark position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PositionBench {
+    /// The searched position
+    pub fen: String,
+    /// Nodes searched
+    pub nodes: u64,
+}
+
+/// Per-position node counts and totals of a benchmark run.
+#[derive(Clone, Debug)]
+pub struct BenchReport {
+    /// One entry per position, in the order of the configuration
+    pub positions: Vec<PositionBench>,
+    /// Nodes over all the positions and time the run took
+    pub total: BenchResult,
+}
+
+/// Outcome of a benchmark run.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchResult {
+    /// Nodes searched over all the positions
+    pub nodes: u64,
+    /// Time spent searching
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    /// Nodes searched per second.
+    pub fn nps(&self) -> u64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            (self.nodes as f64 / seconds) as u64
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for BenchResult {
+    /// Formats the result as `<nodes> nodes <nps> nps`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} nodes {} nps", self.nodes, self.nps())
+    }
+}
+
+impl fmt::Display for BenchReport {
+    /// Formats one `position <n>/<count> <nodes> nodes <fen>` line per
+    /// position, then the totals on the last line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, position) in self.positions.iter().enumerate() {
+            writeln!(
+                f,
+                "position {}/{} {} nodes {}",
+                index + 1,
+                self.positions.len(),
+                position.nodes,
+                position.fen
+            )?;
+        }
+        writeln!(f, "{}", self.total)
+    }
+}
+
+/// Searches every benchmark position to the given depth.
+///
+/// Each position starts from an empty transposition table, so the node count
+/// is the same on every run.
+///
+/// # Arguments
+///
+/// * `depth` - Search depth in plies
+///
+/// # Returns
+///
+/// Total node count and time spent
+pub fn run_bench(depth: u8) -> BenchResult {
+    let config = BenchConfig {
+        depth,
+        ..Default::default()
+    };
+    run_bench_with(&config).total
+}
+
+/// Searches the positions of a benchmark configuration.
+///
+/// Each position starts from an empty transposition table, so the node
+/// counts are the same on every run whatever the number of threads.
+///
+/// # Arguments
+///
+/// * `config` - Positions, depth, table size and threads of the run
+///
+/// # Returns
+///
+/// The node count of each position, and the totals with the wall-clock
+/// time of the run
+pub fn run_bench_with(config: &BenchConfig) -> BenchReport {
+    let next_position = AtomicUsize::new(0);
+    let nodes = Mutex::new(vec![0; config.positions.len()]);
+    let threads = config.threads.clamp(1, config.positions.len().max(1));
+    let start = Instant::now();
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let next_position = &next_position;
+            let nodes = &nodes;
+
+            scope.spawn(move || {
+                let mut game = GameState::new(Some(config.hash_size));
+                let search = IterativeDeepening::new(MinimaxAlphaBeta, config.depth);
+
+                loop {
+                    let index = next_position.fetch_add(1, Ordering::Relaxed);
+                    let Some(fen) = config.positions.get(index) else {
+                        break;
+                    };
+
+                    game.set_fen_position(fen);
+                    game.clear_hash_table();
+
+                    let mut board = game.get_chess_board().clone();
+                    let nodes_before = board.nodes();
+                    search.search(
+                        &mut board,
+                        game.side_to_move(),
+                        Arc::new(AtomicBool::new(false)),
+                    );
+                    nodes.lock().unwrap()[index] = board.nodes() - nodes_before;
+                }
+            });
+        }
+    });
+
+    let positions: Vec<PositionBench> = config
+        .positions
+        .iter()
+        .zip(nodes.into_inner().unwrap())
+        .map(|(fen, nodes)| PositionBench {
+            fen: fen.clone(),
+            nodes,
+        })
+        .collect();
+
+    let total = BenchResult {
+        nodes: positions.iter().map(|position| position.nodes).sum(),
+        elapsed: start.elapsed(),
+    };
+    if threads == 1 {
+        CALIBRATED_NPS.get_or_init(|| total.nps());
+    }
+    BenchReport { total, positions }
+}
+
+/// Measures the single-thread speed of the engine.
+///
+/// The benchmark positions are searched in turn, to a low depth and without
+/// printing anything, until the time is over. Each search is stopped by a
+/// clock at the end of the time, so the run doesn't wait for a search to
+/// finish. The first speed measured is kept as [`calibrated_nps`].
+///
+/// # Arguments
+///
+/// * `time` - Time to spend searching
+///
+/// # Returns
+///
+/// Nodes searched per second
+pub fn calibrate(time: Duration) -> u64 {
+    let start = Instant::now();
+    let mut game = GameState::new(Some(BENCH_HASH_SIZE));
+    let mut nodes = 0;
+    for fen in BENCH_POSITIONS.iter().cycle() {
+        game.set_fen_position(fen);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let clock = SearchClock::new(stop_flag.clone());
+        clock.set_time_to_think(time.saturating_sub(start.elapsed()));
+
+        let mut board = game.get_chess_board().clone();
+        board.set_clock(Some(Arc::new(clock)));
+        let nodes_before = board.nodes();
+        MinimaxAlphaBeta.search(
+            &mut board,
+            CALIBRATION_DEPTH,
+            game.side_to_move(),
+            stop_flag.clone(),
+        );
+        nodes += board.nodes() - nodes_before;
+
+        if stop_flag.load(Ordering::Acquire) || start.elapsed() >= time {
+            break;
+        }
+    }
+
+    let nps = BenchResult {
+        nodes,
+        elapsed: start.elapsed(),
+    }
+    .nps();
+    CALIBRATED_NPS.get_or_init(|| nps);
+    nps
+}
+
+/// Gets the single-thread speed measured first, by [`calibrate`] or by a
+/// benchmark run on one thread.
+///
+/// # Returns
+///
+/// Nodes searched per second, None before any measure
+pub fn calibrated_nps() -> Option<u64> {
+    CALIBRATED_NPS.get().copied()
+}
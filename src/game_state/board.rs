@@ -5,29 +5,47 @@
 //! game states. The board uses a 12x10 mailbox representation with sentinel
 //! squares for efficient move generation and validation.
 
+use std::io;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
+pub mod directions;
 pub mod evaluation;
 pub mod moves;
 pub mod piece;
 pub mod piece_list;
+pub mod polyglot;
+pub mod rules;
+pub mod score;
 pub mod search;
+pub mod see;
 pub mod transposition_table;
 
+use crate::game_state::board::search::InfoReporter;
 use crate::game_state::board::search::Search;
-
-use evaluation::Evaluator;
-use moves::Move;
+use crate::game_state::board::search::SearchParams;
+use crate::game_state::board::search::clock::{NODES_PER_TIME_CHECK, SearchClock};
+use crate::game_state::board::search::trace::{SearchTrace, TraceEvent};
+
+use directions::{BOARD_HEIGHT, BOARD_SQUARES, BOARD_WIDTH, Direction, named_square};
+use evaluation::attacks::AttackMap;
+use evaluation::endgame::{self, MaterialSignature};
+use evaluation::nnue::{FeatureChanges, Network, NnueState};
+use evaluation::{Evaluator, GamePhase, PHASE_WEIGHTS, TEMPO_BONUS};
+use moves::{Move, MoveList};
 use piece::{Color, Piece, PieceType};
 use piece_list::PieceList;
+use rules::Rules;
+use score::Score;
 use transposition_table::{TranspositionTable, Zobrist};
 
 /// Represents the castling rights for both players.
 ///
 /// Tracks which castling moves are still available for white and black,
-/// both kingside and queenside. Castling rights are updated automatically
-/// when pieces move or are captured.
+/// both kingside and queenside, and the files the castling rooks start
+/// from. Castling rights are updated automatically when pieces move or are
+/// captured.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct CastlingRights {
     /// Whether white can still castle queenside
@@ -38,6 +56,58 @@ pub struct CastlingRights {
     pub black_queenside: bool,
     /// Whether black can still castle kingside
     pub black_kingside: bool,
+    /// Files the castling rooks start from, 0 being the a-file: White's
+    /// queenside and kingside rooks, then Black's. The corners in standard
+    /// chess, any file on either side of the king in Chess960.
+    pub rook_files: [u8; 4],
+}
+
+impl CastlingRights {
+    /// Files of the castling rooks in standard chess.
+    pub const STANDARD_ROOK_FILES: [u8; 4] = [0, 7, 0, 7];
+
+    /// Checks if a side can still castle on one side of the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side castling
+    /// * `kingside` - `true` for kingside castling, `false` for queenside
+    pub fn allows(&self, color: Color, kingside: bool) -> bool {
+        match (color, kingside) {
+            (Color::White, false) => self.white_queenside,
+            (Color::White, true) => self.white_kingside,
+            (Color::Black, false) => self.black_queenside,
+            (Color::Black, true) => self.black_kingside,
+        }
+    }
+
+    /// Grants or removes the right of a side to castle on one side of the
+    /// board.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side castling
+    /// * `kingside` - `true` for kingside castling, `false` for queenside
+    /// * `allowed` - Whether the castling move is allowed
+    pub fn set(&mut self, color: Color, kingside: bool, allowed: bool) {
+        match (color, kingside) {
+            (Color::White, false) => self.white_queenside = allowed,
+            (Color::White, true) => self.white_kingside = allowed,
+            (Color::Black, false) => self.black_queenside = allowed,
+            (Color::Black, true) => self.black_kingside = allowed,
+        }
+    }
+
+    /// Gets the file a castling rook starts from, 0 being the a-file.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side castling
+    /// * `kingside` - `true` for the kingside rook, `false` for the
+    ///   queenside one
+    pub fn rook_file(&self, color: Color, kingside: bool) -> u8 {
+        self.rook_files[2 * color as usize + usize::from(kingside)]
+    }
 }
 
 /// Contains information needed to execute a castling move.
@@ -53,6 +123,24 @@ pub struct CastlingInfo {
     pub rook_piece: Piece,
 }
 
+/// Irreversible state saved by `make_move` and restored by `unmake_move`.
+#[derive(Clone, Debug)]
+struct UndoState {
+    /// En passant target before the move
+    en_passant_target: Option<i16>,
+    /// Castling rights before the move
+    castling_rights: CastlingRights,
+    /// Position hash before the move
+    hash: u64,
+    /// Reversible plies played before the move
+    reversible_plies: u16,
+    /// Pieces removed by the explosion of a capture, with their squares
+    exploded: Vec<(i16, Piece)>,
+}
+
+/// Number of undo states preallocated on a new board, deeper than any search.
+const MAX_UNDO_DEPTH: usize = 256;
+
 /// Main chess board representation using a mailbox system.
 ///
 /// The board uses a 12x10 array with sentinel squares around the edges
@@ -65,7 +153,7 @@ pub struct ChessBoard {
     /// Height of the internal board representation (including sentinels)
     board_height: i16,
     /// Array of pieces representing the board state with sentinel borders
-    board_squares: [Piece; 12 * 10],
+    board_squares: [Piece; BOARD_SQUARES],
 
     /// The en passant target square, if applicable
     en_passant_target: Option<i16>,
@@ -87,19 +175,441 @@ pub struct ChessBoard {
 
     /// Positional evaluator (material, PST, etc.)
     evaluator: Arc<dyn Evaluator>,
+
+    /// Number of moves made on this board, used as the search node count
+    nodes: u64,
+
+    /// Nodes searched since they were last added to the clock's count
+    unreported_nodes: u64,
+
+    /// Transposition table lookups of the searches on this board
+    tt_probes: u64,
+
+    /// Lookups that found the position in the transposition table
+    tt_hits: u64,
+
+    /// Number of quiescence search nodes visited on this board
+    qnodes: u64,
+
+    /// Length of the undo stack at the root of the current search iteration
+    root_ply: usize,
+
+    /// Deepest ply below the root reached by the current search iteration
+    seldepth: usize,
+
+    /// NNUE accumulators, replacing the evaluator when a network is loaded
+    nnue: Option<NnueState>,
+
+    /// Clock of the search running on this board, checked every few nodes
+    clock: Option<Arc<SearchClock>>,
+
+    /// Receiver of the `info` lines of the searches on this board
+    info_reporter: Option<InfoReporter>,
+
+    /// Recorder of the search tree, only set while debugging
+    trace: Option<SearchTrace>,
+
+    /// Margins and depths used by the searches on this board
+    search_params: SearchParams,
+
+    /// Side the last search on this board played for, whose draws are
+    /// scored with the contempt of the [`SearchParams`]
+    search_side: Color,
+
+    /// Rules of the variant played on this board
+    rules: &'static dyn Rules,
+
+    /// Whether castling rights are written in the Shredder-FEN form used by
+    /// Chess960
+    chess960: bool,
+
+    /// Margin within which the search plays a random root move instead of
+    /// the best one, set for the opening moves of a game
+    variety: Option<Score>,
+
+    /// Move that did well in earlier games, searched first and preferred
+    /// when it scores close to the best move
+    learned_move: Option<Move>,
+
+    /// Whether the searches on this board play forced moves without
+    /// searching
+    instant_moves: bool,
+
+    /// Whether quiescence searches quiet checking moves at its first ply
+    quiescence_checks: bool,
+
+    /// Root moves the searches on this board are restricted to, every legal
+    /// move when None
+    search_moves: Option<Vec<Move>>,
+
+    /// Root moves the searches on this board leave out
+    avoid_moves: Vec<Move>,
+
+    /// Depth the next search on this board starts at, when it resumes the
+    /// analysis of the previous position
+    resume_depth: Option<u8>,
+
+    /// Square the last move of the game captured on, for the searches to
+    /// recognize recaptures
+    last_capture: Option<i16>,
+
+    /// Depth of the last iteration completed by the search on this board
+    completed_depth: u8,
+
+    /// State to restore when unmaking the moves made on this board. The
+    /// hashes saved in it are the positions along the current game and
+    /// search path, used to detect repetitions.
+    undo_stack: Vec<UndoState>,
+
+    /// Plies played since the last capture, pawn move or castling rights
+    /// change; earlier positions can't be repeated
+    reversible_plies: u16,
 }
 
 impl ChessBoard {
     /// Evaluates the current board position using the configured evaluator.
     ///
-    /// Delegates to the internal [`Evaluator`] which aggregates heuristic
-    /// components (material, PST, etc.).
+    /// Uses the NNUE network when one is loaded, otherwise delegates to the
+    /// internal [`Evaluator`] which aggregates heuristic components
+    /// (material, PST, etc.).
+    ///
+    /// # Returns
+    ///
+    /// Score from white's perspective (positive if white is winning), kept
+    /// below the mate scores
+    pub fn evaluate(&self) -> Score {
+        if let Some(score) = self.endgame_evaluation(None) {
+            return score;
+        }
+
+        let score = match &self.nnue {
+            Some(nnue) => Score::from(nnue.evaluate()),
+            None => self.evaluator.evaluate(self),
+        };
+        score.clamp(-Score::MAX_EVAL, Score::MAX_EVAL)
+    }
+
+    /// Evaluates the current board position for the side to move.
+    ///
+    /// Unlike [`evaluate`](Self::evaluate), the side to move gets a
+    /// [`TEMPO_BONUS`], and the score is the same whichever side the search
+    /// started from. Exact endgame results get no bonus: a drawn position
+    /// stays a draw.
+    ///
+    /// # Arguments
+    ///
+    /// * `side_to_move` - Side to move in the position
+    ///
+    /// # Returns
+    ///
+    /// Score from the point of view of the side to move, kept below the mate
+    /// scores
+    pub fn evaluate_for(&self, side_to_move: Color) -> Score {
+        if let Some(score) = self.endgame_evaluation(Some(side_to_move)) {
+            return score.relative_to(side_to_move);
+        }
+
+        let score = match &self.nnue {
+            Some(nnue) => Score::from(nnue.evaluate()),
+            None => self.evaluator.evaluate(self),
+        };
+        (score.relative_to(side_to_move) + TEMPO_BONUS).clamp(-Score::MAX_EVAL, Score::MAX_EVAL)
+    }
+
+    /// Splits the evaluation of the position into named terms, from White's
+    /// perspective.
+    ///
+    /// # Returns
+    ///
+    /// The terms of the handcrafted evaluation, or a single `NNUE` term when
+    /// a network is loaded
+    pub fn evaluation_breakdown(&self) -> Vec<(&'static str, Score)> {
+        if let Some(score) = self.endgame_evaluation(None) {
+            return vec![("Endgame", score)];
+        }
+
+        match &self.nnue {
+            Some(nnue) => vec![("NNUE", Score::from(nnue.evaluate()))],
+            None => self.evaluator.breakdown(self),
+        }
+    }
+
+    /// Gets the number of pieces of each kind on the board.
+    pub fn material_signature(&self) -> MaterialSignature {
+        self.piece_list.material_signature()
+    }
+
+    /// Evaluates known endgames of standard chess, see [`endgame`].
+    fn endgame_evaluation(&self, side_to_move: Option<Color>) -> Option<Score> {
+        endgame::evaluate(self, side_to_move).filter(|_| self.is_standard_chess())
+    }
+
+    /// Gets the game phase from the non-pawn material left on the board.
+    ///
+    /// The phase goes continuously from [`TOTAL_PHASE`](evaluation::TOTAL_PHASE)
+    /// with all pieces on the board (opening and middlegame) down to 0 with
+    /// only kings and pawns (endgame). Promoted pieces can push it past the
+    /// maximum, so it is clamped.
+    ///
+    /// # Returns
+    ///
+    /// The current game phase
+    pub fn game_phase(&self) -> GamePhase {
+        let phase = PHASE_WEIGHTS
+            .iter()
+            .map(|&(piece, weight)| {
+                self.piece_list.get_number_of_pieces(piece).unwrap_or(0) * weight
+            })
+            .sum();
+
+        GamePhase::new(phase)
+    }
+
+    /// Sets the NNUE network used for evaluation.
+    ///
+    /// Networks are trained on standard chess positions, so they are ignored
+    /// while a variant is played.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - Network to use, or `None` for the handcrafted evaluation
+    pub fn set_network(&mut self, network: Option<Arc<Network>>) {
+        let network = network.filter(|_| self.is_standard_chess());
+        self.nnue = network.map(|network| NnueState::new(network, &self.standard_board()));
+    }
+
+    /// Sets the evaluator used when no NNUE network is loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` - Evaluation function (composite or custom)
+    pub fn set_evaluator(&mut self, evaluator: Arc<dyn Evaluator>) {
+        self.evaluator = evaluator;
+    }
+
+    /// Sets the rules of the variant played on this board.
+    ///
+    /// A loaded NNUE network is dropped when switching to a variant, see
+    /// [`set_network`](Self::set_network).
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - Rules of the variant, see [`rules::find_variant`]
+    pub fn set_rules(&mut self, rules: &'static dyn Rules) {
+        self.rules = rules;
+        if !self.is_standard_chess() {
+            self.nnue = None;
+        }
+    }
+
+    /// Gets the rules of the variant played on this board.
+    pub fn rules(&self) -> &'static dyn Rules {
+        self.rules
+    }
+
+    /// Checks if standard chess is played on this board.
+    fn is_standard_chess(&self) -> bool {
+        self.rules.name() == rules::STANDARD.name()
+    }
+
+    /// Gets the NNUE state, if a network is loaded.
+    pub fn nnue(&self) -> Option<&NnueState> {
+        self.nnue.as_ref()
+    }
+
+    /// Gets the pieces of the board in standard 0-63 square order.
+    fn standard_board(&self) -> [Piece; 64] {
+        std::array::from_fn(|square| {
+            self.get_piece_on_square(self.map_inner_to_outer_board(square as i16))
+        })
+    }
+
+    /// Updates the NNUE accumulators after a move has been made.
+    fn push_nnue_accumulator(&mut self, mv: &Move) {
+        if self.nnue.is_none() {
+            return;
+        }
+
+        let mut removed = FeatureChanges::new();
+        let mut added = FeatureChanges::new();
+
+        removed.push((mv.piece, self.map_to_standard_chess_board(mv.from)));
+        added.push((
+            mv.promotion.unwrap_or(mv.piece),
+            self.map_to_standard_chess_board(mv.to),
+        ));
+
+        if let Some((capture_square, captured_pawn)) = mv.en_passant_capture(self.board_width) {
+            removed.push((
+                captured_pawn,
+                self.map_to_standard_chess_board(capture_square),
+            ));
+        } else if mv.captured_piece.is_valid_piece() {
+            removed.push((mv.captured_piece, self.map_to_standard_chess_board(mv.to)));
+        }
+
+        if let Some(castling) = &mv.castling {
+            removed.push((
+                castling.rook_piece,
+                self.map_to_standard_chess_board(castling.rook_from),
+            ));
+            added.push((
+                castling.rook_piece,
+                self.map_to_standard_chess_board(castling.rook_to),
+            ));
+        }
+
+        // Only a king move needs the whole board to refresh its perspective
+        let pieces = (mv.piece.get_type() == PieceType::King).then(|| self.standard_board());
+        if let Some(nnue) = &mut self.nnue {
+            nnue.push(&removed, &added, pieces.as_ref());
+        }
+    }
+
+    /// Checks if neither side has the material to checkmate.
+    ///
+    /// Covers bare kings, a single knight or bishop against a bare king and
+    /// one bishop each on squares of the same color, where no sequence of
+    /// legal moves leads to checkmate. Other material, however weak, is left
+    /// to the search. Only standard chess is drawn this way.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the position is a draw by insufficient material
+    pub fn has_insufficient_material(&self) -> bool {
+        let signature = self.material_signature();
+        let count = |piece| signature.count(piece);
+
+        let heavy_or_pawns = [
+            Piece::WhitePawn,
+            Piece::WhiteRook,
+            Piece::WhiteQueen,
+            Piece::BlackPawn,
+            Piece::BlackRook,
+            Piece::BlackQueen,
+        ];
+        if !self.is_standard_chess() || heavy_or_pawns.into_iter().any(|piece| count(piece) > 0) {
+            return false;
+        }
+
+        let knights = count(Piece::WhiteKnight) + count(Piece::BlackKnight);
+        match (
+            knights,
+            count(Piece::WhiteBishop),
+            count(Piece::BlackBishop),
+        ) {
+            (0, 0, 0) | (1, 0, 0) | (0, 1, 0) | (0, 0, 1) => true,
+            (0, 1, 1) => {
+                let mut light_squares = [false; 2];
+                let mut bishops = 0;
+                self.piece_list.for_each_piece(|piece, square| {
+                    if piece.get_type() == PieceType::Bishop {
+                        let square = self.map_to_standard_chess_board(square);
+                        light_squares[bishops] = (square / 8 + square % 8) % 2 == 1;
+                        bishops += 1;
+                    }
+                });
+                light_squares[0] == light_squares[1]
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if the current position already occurred in the game or along
+    /// the search path.
+    ///
+    /// A single earlier occurrence is enough: if a line repeats once, the
+    /// side that could avoid the repetition had no better option, so the
+    /// position is scored as a draw.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the position occurred before with the same side to move
+    pub fn is_repetition(&self) -> bool {
+        // The last state is the position before the last move, with the
+        // other side to move, so compare every second position
+        self.undo_stack
+            .iter()
+            .rev()
+            .take(self.reversible_plies as usize)
+            .skip(1)
+            .step_by(2)
+            .any(|undo| undo.hash == self.hash)
+    }
+
+    /// Follows the best moves stored in the transposition table from a root
+    /// move to build the principal variation.
+    ///
+    /// Table moves are only followed while they are legal, and the line
+    /// stops at a repeated position. The board is left as it was.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_move` - Best move found at the root
+    /// * `side_to_move` - Color of the player to move
+    /// * `max_length` - Maximum number of moves in the line
+    ///
+    /// # Returns
+    ///
+    /// The principal variation, starting with `root_move`
+    pub fn principal_variation(
+        &mut self,
+        root_move: &Move,
+        side_to_move: Color,
+        max_length: usize,
+    ) -> Vec<Move> {
+        let mut line = vec![root_move.clone()];
+        self.make_move(root_move);
+        let mut color = side_to_move.opposite();
+
+        while line.len() < max_length && !self.is_repetition() {
+            let Some(mv) = self
+                .transposition_table
+                .retrieve_position(self.hash)
+                .and_then(|position| self.transposition_move(position.best_move, color))
+            else {
+                break;
+            };
+
+            let encoded = mv.encode(self);
+            let legal_moves = self.generate_moves(color);
+            if !legal_moves
+                .iter()
+                .any(|legal| legal.encode(self) == encoded)
+            {
+                break;
+            }
+            self.make_move(&mv);
+            line.push(mv);
+            color = color.opposite();
+        }
+
+        for mv in line.iter().rev() {
+            self.unmake_move(mv);
+        }
+        line
+    }
+
+    /// Decodes a best move stored in the transposition table.
+    ///
+    /// The entry may belong to another position whose key collides with
+    /// this one, so the move is only returned if it is pseudo-legal here.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded` - Move encoded by [`Move::encode`], 0 for no move
+    /// * `side_to_move` - Color of the player to move
     ///
     /// # Returns
     ///
-    /// Score from white's perspective (positive if white is winning)
-    pub fn evaluate(&self) -> i16 {
-        self.evaluator.evaluate(self)
+    /// The move, or None if there is no move or it can't be played
+    pub fn transposition_move(&self, encoded: u16, side_to_move: Color) -> Option<Move> {
+        if encoded == 0 {
+            return None;
+        }
+
+        Move::decode(encoded, self)
+            .filter(|mv| mv.piece.is_friend(side_to_move) && self.is_pseudo_legal(mv))
     }
 
     /// Checks if the given color is in checkmate.
@@ -112,8 +622,7 @@ impl ChessBoard {
     ///
     /// `true` if the king is in check and no legal moves exist
     pub fn is_checkmate(&mut self, color: Color) -> bool {
-        let moves = self.generate_moves(color);
-        moves.is_empty() && self.is_in_check(color)
+        self.is_in_check(color) && self.count_legal_moves(color) == 0
     }
 
     /// Checks if the given color's king is in check.
@@ -126,7 +635,385 @@ impl ChessBoard {
     ///
     /// `true` if the king is under attack
     pub fn is_in_check(&self, color: Color) -> bool {
-        !self.piece_list.is_king_in_check(self, color).is_empty()
+        self.rules.is_in_check(self, color)
+    }
+
+    /// Gets the pieces giving check to a king.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Color of the king in check
+    ///
+    /// # Returns
+    ///
+    /// Standard chess squares (0-63) of the checking pieces, in ascending
+    /// order, empty if the king isn't in check
+    pub fn checkers(&self, color: Color) -> Vec<usize> {
+        let mut checkers: Vec<usize> = self
+            .piece_list
+            .is_king_in_check(self, color)
+            .into_iter()
+            .map(|(_, square)| self.map_to_standard_chess_board(square))
+            .collect();
+        checkers.sort_unstable();
+        checkers
+    }
+
+    /// Gets the pieces pinned to their king.
+    ///
+    /// A pinned piece can only move along the line between its king and the
+    /// enemy slider pinning it.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Color of the king and of the pinned pieces
+    ///
+    /// # Returns
+    ///
+    /// Standard chess squares (0-63) of the pinned pieces, in ascending order
+    pub fn pinned(&self, color: Color) -> Vec<usize> {
+        let mut pinned: Vec<usize> = self
+            .piece_list
+            .detect_pinned_pieces(self, color)
+            .into_keys()
+            .map(|square| self.map_to_standard_chess_board(square))
+            .collect();
+        pinned.sort_unstable();
+        pinned
+    }
+
+    /// Checks if a square is attacked by any piece of the given color.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - Standard chess square index (0-63)
+    /// * `by_color` - Color of the attacking pieces
+    ///
+    /// # Returns
+    ///
+    /// `true` if the square is attacked
+    pub fn is_square_attacked(&self, square: usize, by_color: Color) -> bool {
+        let square = self.map_inner_to_outer_board(square as i16);
+        self.piece_list.is_square_attacked(self, square, by_color)
+    }
+
+    /// Checks if a pawn is passed: no enemy pawn stands in front of it on its
+    /// own file or on the adjacent files.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - Standard chess square index (0-63) of the pawn
+    /// * `color` - Color of the pawn
+    ///
+    /// # Returns
+    ///
+    /// `true` if no enemy pawn can block or capture the pawn on its way
+    pub fn is_passed_pawn(&self, square: usize, color: Color) -> bool {
+        let (rank, file) = ((square / 8) as i16, (square % 8) as i16);
+        let (enemy_pawn, ranks_ahead) = match color {
+            Color::White => (Piece::BlackPawn, rank + 1..8),
+            Color::Black => (Piece::WhitePawn, 0..rank),
+        };
+
+        !ranks_ahead.into_iter().any(|rank_ahead| {
+            (file - 1..=file + 1)
+                .filter(|file| (0..8).contains(file))
+                .any(|file| {
+                    let square = self.map_inner_to_outer_board(rank_ahead * 8 + file);
+                    self.get_piece_on_square(square) == enemy_pawn
+                })
+        })
+    }
+
+    /// Checks if a move gives check, without making it on the board.
+    ///
+    /// Covers direct and discovered checks. Castling and en passant are rare
+    /// enough that they are tested on a copy of the board instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - A legal move for the side to move
+    ///
+    /// # Returns
+    ///
+    /// `true` if the opponent's king is in check after the move
+    pub fn gives_check(&self, mv: &Move) -> bool {
+        if mv.castling.is_some() || mv.en_passant {
+            return self
+                .copy_make(mv)
+                .is_in_check(mv.piece.get_color().opposite());
+        }
+
+        self.piece_list.gives_check(self, mv)
+    }
+
+    /// Computes the static exchange evaluation of a move: the material it
+    /// wins once both sides have made every profitable capture on its
+    /// destination square.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - A legal move for the side to move
+    ///
+    /// # Returns
+    ///
+    /// Material won in centipawns, negative if the move loses material
+    pub fn see(&self, mv: &Move) -> i16 {
+        see::static_exchange(self, mv)
+    }
+
+    /// Checks if a move can be played in the position, ignoring whether it
+    /// leaves its own king in check.
+    ///
+    /// Moves taken from the transposition table may belong to another
+    /// position whose key collides with this one, so they are validated with
+    /// this test before being used. It is much cheaper than generating every
+    /// move.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - Move to test, for the color of its piece
+    ///
+    /// # Returns
+    ///
+    /// `true` if the piece can make the move in the position
+    pub fn is_pseudo_legal(&self, mv: &Move) -> bool {
+        let Some(castling) = &mv.castling else {
+            return self.piece_list.is_pseudo_legal(self, mv);
+        };
+
+        if !matches!(mv.piece, Piece::WhiteKing | Piece::BlackKing) || mv.promotion.is_some() {
+            return false;
+        }
+
+        let color = mv.piece.get_color();
+        if castling.rook_from > mv.from {
+            mv.to == castling.rook_to + 1
+                && self.can_castle_kingside(&self.piece_list, color, mv.from, castling.rook_from)
+        } else {
+            mv.to == castling.rook_to - 1
+                && self.can_castle_queenside(&self.piece_list, color, mv.from, castling.rook_from)
+        }
+    }
+
+    /// Gets all squares attacked by the given color.
+    ///
+    /// A square counts as attacked even when it holds a piece of the
+    /// attacking color, i.e. when that piece is defended.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Color of the attacking pieces
+    ///
+    /// # Returns
+    ///
+    /// Bitmask with bit `rank * 8 + file` set for each attacked square,
+    /// so a1 is bit 0 and h8 is bit 63
+    pub fn attacked_squares(&self, color: Color) -> u64 {
+        self.piece_list.attacked_squares(self, color)
+    }
+
+    /// Counts how many pieces of each side attack each square, as the
+    /// evaluation does for king tropism and weak squares.
+    pub fn attack_map(&self) -> AttackMap {
+        AttackMap::new(self)
+    }
+
+    /// Computes the Polyglot opening book key of the position.
+    ///
+    /// When the board is hashed with [`Zobrist::polyglot`] keys the key is
+    /// derived from the incrementally updated hash, otherwise it is computed
+    /// from scratch.
+    ///
+    /// # Arguments
+    ///
+    /// * `side_to_move` - Color of the side to move
+    ///
+    /// # Returns
+    ///
+    /// 64-bit key compatible with Polyglot books
+    pub fn polyglot_key(&self, side_to_move: Color) -> u64 {
+        if !self.zobrist.is_polyglot() {
+            return polyglot::polyglot_key(self, side_to_move);
+        }
+
+        // Polyglot hashes White to move instead of Black, so the side key
+        // always flips
+        self.hash ^ self.zobrist.side_to_move
+    }
+
+    /// Replaces the Zobrist keys used to hash the board.
+    ///
+    /// The hash is recomputed with the new keys, so moves made before can't
+    /// be unmade anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `zobrist_keys` - Shared zobrist random numbers
+    /// * `side_to_move` - Color of the side to move
+    pub fn set_zobrist_keys(&mut self, zobrist_keys: Arc<Zobrist>, side_to_move: Color) {
+        self.zobrist = zobrist_keys;
+        self.undo_stack.clear();
+        self.reversible_plies = 0;
+        self.hash = self.zobrist_hash(side_to_move);
+    }
+
+    /// Whether the board is hashed with the Polyglot keys.
+    pub fn uses_polyglot_keys(&self) -> bool {
+        self.zobrist.is_polyglot()
+    }
+
+    /// Writes the position in Forsyth-Edwards Notation (FEN).
+    ///
+    /// Castling rights are written as `KQkq`, or in Chess960 mode with the
+    /// files of the castling rooks as in Shredder-FEN (`HAha`, or any other
+    /// rook files).
+    ///
+    /// # Arguments
+    ///
+    /// * `side_to_move` - Color of the side to move
+    /// * `halfmove_clock` - Half moves since the last capture or pawn move
+    /// * `fullmove_number` - Full move number
+    ///
+    /// # Returns
+    ///
+    /// FEN string with all six fields
+    pub fn to_fen(&self, side_to_move: Color, halfmove_clock: u64, fullmove_number: u64) -> String {
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty_squares = 0;
+            for file in 0..8 {
+                let piece =
+                    self.get_piece_on_square(self.map_inner_to_outer_board(rank * 8 + file));
+                if piece.is_empty() {
+                    empty_squares += 1;
+                    continue;
+                }
+
+                if empty_squares > 0 {
+                    placement.push_str(&empty_squares.to_string());
+                    empty_squares = 0;
+                }
+                placement.push_str(piece.print_piece());
+            }
+
+            if empty_squares > 0 {
+                placement.push_str(&empty_squares.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+
+        let side = match side_to_move {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+
+        let rights = &self.castling_rights;
+        let mut castling: String = [
+            (Color::White, true, 'K'),
+            (Color::White, false, 'Q'),
+            (Color::Black, true, 'k'),
+            (Color::Black, false, 'q'),
+        ]
+        .iter()
+        .filter(|(color, kingside, _)| rights.allows(*color, *kingside))
+        .map(|&(color, kingside, symbol)| {
+            if !self.chess960 {
+                return symbol;
+            }
+            let file = (b'a' + rights.rook_file(color, kingside)) as char;
+            match color {
+                Color::White => file.to_ascii_uppercase(),
+                Color::Black => file,
+            }
+        })
+        .collect();
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = self
+            .get_en_passant_target()
+            .map_or("-".to_string(), |square| {
+                Self::square_name(self.map_to_standard_chess_board(square))
+            });
+
+        format!(
+            "{} {} {} {} {} {}",
+            placement, side, castling, en_passant, halfmove_clock, fullmove_number
+        )
+    }
+
+    /// Draws the board as text, with the rank and file labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `flipped` - Whether to draw the board from Black's side
+    ///
+    /// # Returns
+    ///
+    /// Board diagram, one line per rank and border
+    pub fn to_diagram(&self, flipped: bool) -> String {
+        let separator = " +---+---+---+---+---+---+---+---+\n";
+        let mut ranks: Vec<i16> = (0..8).rev().collect();
+        let mut files: Vec<i16> = (0..8).collect();
+        if flipped {
+            ranks.reverse();
+            files.reverse();
+        }
+
+        let mut diagram = String::from(separator);
+        for &rank in &ranks {
+            for &file in &files {
+                let piece =
+                    self.get_piece_on_square(self.map_inner_to_outer_board(rank * 8 + file));
+                let symbol = if piece.is_empty() {
+                    " "
+                } else {
+                    piece.print_piece()
+                };
+                diagram.push_str(&format!(" | {}", symbol));
+            }
+            diagram.push_str(&format!(" | {}\n", rank + 1));
+            diagram.push_str(separator);
+        }
+
+        for &file in &files {
+            diagram.push_str(&format!("   {}", (b'a' + file as u8) as char));
+        }
+        diagram.push('\n');
+
+        diagram
+    }
+
+    /// Gets the name of a square in algebraic notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - Standard chess square index (0-63)
+    ///
+    /// # Returns
+    ///
+    /// Square name, e.g. "e4"
+    pub fn square_name(square: usize) -> String {
+        let file = (b'a' + (square % 8) as u8) as char;
+        let rank = (b'1' + (square / 8) as u8) as char;
+        format!("{}{}", file, rank)
+    }
+
+    /// Encodes a move in the Polyglot opening book format.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - The move to encode
+    ///
+    /// # Returns
+    ///
+    /// 16-bit Polyglot move
+    pub fn move_to_polyglot(&self, mv: &Move) -> u16 {
+        polyglot::encode_move(self, mv)
     }
 
     /// Parses a move from UCI algebraic notation.
@@ -142,6 +1029,47 @@ impl ChessBoard {
         Move::parse_algebraic_move(self, uci_notation)
     }
 
+    /// Checks that a move belongs to the position: the piece moved is one
+    /// of the side to move, on the square the move starts from, and the
+    /// destination holds the piece the move captures.
+    ///
+    /// Legality isn't checked, nor the rest of the move consistent with its
+    /// squares, as for moves generated in the position.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - The move to check
+    /// * `color` - Color of the side to move
+    ///
+    /// # Returns
+    ///
+    /// An error describing the mismatch, if any
+    pub fn validate_move(&self, mv: &Move, color: Color) -> Result<(), String> {
+        let notation = mv.to_uci(self);
+
+        if !mv.piece.is_valid_piece() || mv.piece.get_color() != color {
+            return Err(format!("{} is not a move of {:?}", notation, color));
+        }
+        if self.get_piece_on_square(mv.from) != mv.piece {
+            return Err(format!("{} doesn't move the piece on its square", notation));
+        }
+        // Castling in Chess960 notation lands on the rook, and en passant
+        // captures a pawn beside the destination
+        let on_target = if mv.en_passant {
+            Piece::EmptySquare
+        } else {
+            mv.captured_piece
+        };
+        if mv.castling.is_none() && self.get_piece_on_square(mv.to) != on_target {
+            return Err(format!(
+                "{} doesn't capture the piece on its square",
+                notation
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Converts a move to UCI algebraic notation.
     ///
     /// # Arguments
@@ -155,6 +1083,33 @@ impl ChessBoard {
         mv.to_uci(self)
     }
 
+    /// Parses a move from Standard Algebraic Notation (SAN).
+    ///
+    /// # Arguments
+    ///
+    /// * `san` - Move in SAN (e.g., "Nf3", "exd5", "O-O")
+    /// * `color` - Color of the side to move
+    ///
+    /// # Returns
+    ///
+    /// `Some(Move)` if the notation matches a legal move, `None` otherwise
+    pub fn from_san(&mut self, san: &str, color: Color) -> Option<Move> {
+        Move::parse_san(self, san, color)
+    }
+
+    /// Converts a move to Standard Algebraic Notation (SAN).
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - A legal move in the current position
+    ///
+    /// # Returns
+    ///
+    /// SAN string representation of the move
+    pub fn move_to_san(&mut self, mv: &Move) -> String {
+        mv.to_san(self)
+    }
+
     /// Converts algebraic notation to internal board coordinates.
     ///
     /// # Arguments
@@ -163,12 +1118,10 @@ impl ChessBoard {
     ///
     /// # Returns
     ///
-    /// Internal board index, or -1 if invalid
-    fn algebraic_to_internal(&self, algebraic_notation: &str) -> i16 {
-        if let Some(square) = Move::notation_to_square(algebraic_notation) {
-            return self.map_inner_to_outer_board(square);
-        }
-        -1
+    /// Internal board index, None if the notation isn't a square
+    fn algebraic_to_internal(&self, algebraic_notation: &str) -> Option<i16> {
+        Move::notation_to_square(algebraic_notation)
+            .map(|square| self.map_inner_to_outer_board(square))
     }
 
     /// Gets the piece on a given square.
@@ -179,9 +1132,17 @@ impl ChessBoard {
     ///
     /// # Returns
     ///
-    /// Piece at the specified square
+    /// Piece at the specified square, a sentinel for a square outside of
+    /// the internal board
     fn get_piece_on_square(&self, square: i16) -> Piece {
-        self.board_squares[square as usize]
+        // Like the sentinel ranks and files, whatever lies beyond them is off
+        // the board: stepping past the edge from a corrupted square can't
+        // read out of bounds
+        usize::try_from(square)
+            .ok()
+            .and_then(|square| self.board_squares.get(square))
+            .copied()
+            .unwrap_or(Piece::SentinelSquare)
     }
 
     /// Sets a piece on a given square.
@@ -189,8 +1150,13 @@ impl ChessBoard {
     /// # Arguments
     ///
     /// * `piece` - Piece to place
-    /// * `square` - Internal board coordinate
+    /// * `square` - Internal board coordinate, on the chess board
     fn set_piece_on_square(&mut self, piece: Piece, square: i16) {
+        debug_assert!(
+            directions::is_on_board(square),
+            "{} is off the board",
+            square
+        );
         self.board_squares[square as usize] = piece;
     }
 
@@ -296,12 +1262,12 @@ impl ChessBoard {
     ///
     /// # Returns
     ///
-    /// Direction that should be taked to reach end square or 0 if there's not
-    /// a valid straight line between `from` and `to` squares
-    fn get_rank_or_file_direction(&self, from: i16, to: i16) -> i16 {
+    /// Direction that should be taked to reach end square or `None` if
+    /// there's not a valid straight line between `from` and `to` squares
+    fn get_rank_or_file_direction(&self, from: i16, to: i16) -> Option<Direction> {
         // Sanity check, the squares can't be the same
         if from == to {
-            return 0;
+            return None;
         }
 
         // Check if the squares are in the same file or in the same rank.
@@ -310,20 +1276,24 @@ impl ChessBoard {
         if !same_file && !same_rank {
             // If they aren't in the same rank or in the same file,
             // the rook can't move there.
-            return 0;
+            return None;
         }
 
         // We now know that the squares are in the same file or in the
         // same rank, we need to get in which direction the rook should
         // move.
         let distance = to - from;
-        if same_rank {
-            if distance > 0 { 1 } else { -1 }
+        Some(if same_rank {
+            if distance > 0 {
+                Direction::EAST
+            } else {
+                Direction::WEST
+            }
         } else if distance > 0 {
-            self.board_width
+            Direction::NORTH
         } else {
-            -self.board_width
-        }
+            Direction::SOUTH
+        })
     }
 
     /// Get the direction that if a square can reach another in diagonal lines.
@@ -337,19 +1307,19 @@ impl ChessBoard {
     ///
     /// # Returns
     ///
-    /// Direction it should be taked to reach end square or 0 if there's not
-    /// a valid diagonal line between `from` and `to` squares
-    fn get_diagonal_direction(&self, from: i16, to: i16) -> i16 {
+    /// Direction it should be taked to reach end square or `None` if there's
+    /// not a valid diagonal line between `from` and `to` squares
+    fn get_diagonal_direction(&self, from: i16, to: i16) -> Option<Direction> {
         // Sanity check, the squares can't be the same
         if from == to {
-            return 0;
+            return None;
         }
 
         // Check if the squares are in the same diagonal.
         let same_diagonal = self.are_on_the_same_diagonal(from, to);
         if !same_diagonal {
             // If they aren't in the same diagonal the bishop can't move there
-            return 0;
+            return None;
         }
 
         // The squares are in the same diagonal, now we need to get in which
@@ -362,7 +1332,7 @@ impl ChessBoard {
         let col2 = self.square_file(to);
         let col_dir: i16 = if col2 > col1 { 1 } else { -1 };
 
-        row_dir * self.board_width + col_dir
+        Some(Direction::new(row_dir, col_dir))
     }
 
     /// Gets the current en passant target square.
@@ -425,6 +1395,12 @@ impl ChessBoard {
         // This function converts a standard 0-63 chess square to its position
         // in our internal board representation.
 
+        debug_assert!(
+            (0..64).contains(&square),
+            "{} is not a chess square",
+            square
+        );
+
         // Calculate the starting position of the inner 8×8 board within our larger board
         let vertical_padding = (self.board_height - 8) / 2; // Rows below the chess board
         let horizontal_padding = (self.board_width - 8) / 2; // Columns to the left
@@ -480,47 +1456,16 @@ impl ChessBoard {
             }
         }
 
-        let white_rook_queenside = self.algebraic_to_internal("a1");
-        let white_rook_kingside = self.algebraic_to_internal("h1");
-
-        let black_rook_queenside = self.algebraic_to_internal("a8");
-        let black_rook_kingside = self.algebraic_to_internal("h8");
-
-        // If rook moves from its starting square, lose corresponding castling right
-        match (color, mv.from) {
-            (Color::White, square) if square == white_rook_queenside => {
-                self.castling_rights.white_queenside = false
-            }
-            (Color::White, square) if square == white_rook_kingside => {
-                self.castling_rights.white_kingside = false
-            }
-            (Color::Black, square) if square == black_rook_queenside => {
-                self.castling_rights.black_queenside = false
+        // If a castling rook moves from its starting square, or is captured
+        // on it, lose the corresponding castling right
+        let captured_rook =
+            mv.captured_piece.is_valid_piece() && mv.captured_piece.get_type() == PieceType::Rook;
+        for kingside in [false, true] {
+            if mv.from == self.castling_rook_square(color, kingside) {
+                self.castling_rights.set(color, kingside, false);
             }
-            (Color::Black, square) if square == black_rook_kingside => {
-                self.castling_rights.black_kingside = false
-            }
-            _ => {}
-        }
-
-        // If a rook is captured, lose corresponding castling right
-        if (mv.captured_piece != Piece::EmptySquare)
-            && (mv.captured_piece.get_type() == PieceType::Rook)
-        {
-            match (mv.captured_piece.get_color(), mv.to) {
-                (Color::White, square) if square == white_rook_queenside => {
-                    self.castling_rights.white_queenside = false
-                }
-                (Color::White, square) if square == white_rook_kingside => {
-                    self.castling_rights.white_kingside = false
-                }
-                (Color::Black, square) if square == black_rook_queenside => {
-                    self.castling_rights.black_queenside = false
-                }
-                (Color::Black, square) if square == black_rook_kingside => {
-                    self.castling_rights.black_kingside = false
-                }
-                _ => {}
+            if captured_rook && mv.to == self.castling_rook_square(color.opposite(), kingside) {
+                self.castling_rights.set(color.opposite(), kingside, false);
             }
         }
 
@@ -538,11 +1483,12 @@ impl ChessBoard {
 
     /// Checks if kingside castling is legal for the given color.
     ///
-    /// Verifies all castling conditions: rights, piece positions, empty squares, and safety.
+    /// Verifies all castling conditions: rights, the rook being the castling
+    /// rook on the king's side, empty squares, and safety.
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the chess board
+    /// * `piece_list` - Piece lists used to find attackers of the king's path
     /// * `color` - Color attempting to castle
     /// * `king_square` - Expected king starting square
     /// * `rook_square` - Expected rook starting square
@@ -550,79 +1496,36 @@ impl ChessBoard {
     /// # Returns
     ///
     /// `true` if kingside castling is legal
-    fn can_castle_kingside(&self, color: Color, king_square: i16, rook_square: i16) -> bool {
-        // 0. Check if castling privileges are valid
-        if (color == Color::White) && (!self.castling_rights.white_kingside) {
-            return false;
-        }
-
-        if (color == Color::Black) && (!self.castling_rights.black_kingside) {
-            return false;
-        }
-
-        // 1. Check if king and rook are in starting positions
-        if self.get_piece_on_square(king_square)
-            != if color == Color::White {
-                Piece::WhiteKing
-            } else {
-                Piece::BlackKing
-            }
-        {
-            return false;
-        }
-
-        if self.get_piece_on_square(rook_square)
-            != if color == Color::White {
-                Piece::WhiteRook
-            } else {
-                Piece::BlackRook
-            }
-        {
-            return false;
-        }
-
-        // 2. Check if squares between king and rook are empty
-        let squares_between = match color {
-            Color::White => vec![26, 27], // f1, g1
-            Color::Black => vec![96, 97], // f8, g8
-        };
-
-        for square in squares_between {
-            if self.get_piece_on_square(square) != Piece::EmptySquare {
-                return false;
-            }
-        }
-
-        // 3. Check if king is not in check and doesn't move through check
-        let check_squares = match color {
-            Color::White => vec![25, 26, 27], // e1, f1, g1
-            Color::Black => vec![95, 96, 97], // e8, f8, g8
-        };
-
-        for square in check_squares {
-            let opposite_color = if color == Color::White {
-                Color::Black
-            } else {
-                Color::White
-            };
-            if self
-                .piece_list
-                .is_square_attacked(self, square, opposite_color)
-            {
-                return false;
-            }
-        }
-
-        true
+    fn can_castle_kingside(
+        &self,
+        piece_list: &PieceList,
+        color: Color,
+        king_square: i16,
+        rook_square: i16,
+    ) -> bool {
+        // The king lands on the g-file and the rook on the f-file
+        const G1: i16 = named_square("g1");
+        const F1: i16 = named_square("f1");
+        self.castling_rights.allows(color, true)
+            && rook_square == self.castling_rook_square(color, true)
+            && self.square_rank(king_square) == self.square_rank(rook_square)
+            && king_square < rook_square
+            && self.can_castle(
+                piece_list,
+                color,
+                (king_square, self.on_rank_of(king_square, G1)),
+                (rook_square, self.on_rank_of(king_square, F1)),
+            )
     }
 
     /// Checks if queenside castling is legal for the given color.
     ///
-    /// Verifies all castling conditions: rights, piece positions, empty squares, and safety.
+    /// Verifies all castling conditions: rights, the rook being the castling
+    /// rook on the queen's side, empty squares, and safety.
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the chess board
+    /// * `piece_list` - Piece lists used to find attackers of the king's path
     /// * `color` - Color attempting to castle
     /// * `king_square` - Expected king starting square
     /// * `rook_square` - Expected rook starting square
@@ -630,70 +1533,128 @@ impl ChessBoard {
     /// # Returns
     ///
     /// `true` if queenside castling is legal
-    fn can_castle_queenside(&self, color: Color, king_square: i16, rook_square: i16) -> bool {
-        // 0. Check if castling privileges are valid
-        if (color == Color::White) && (!self.castling_rights.white_queenside) {
-            return false;
-        }
-
-        if (color == Color::Black) && (!self.castling_rights.black_queenside) {
-            return false;
-        }
+    fn can_castle_queenside(
+        &self,
+        piece_list: &PieceList,
+        color: Color,
+        king_square: i16,
+        rook_square: i16,
+    ) -> bool {
+        // The king lands on the c-file and the rook on the d-file
+        const C1: i16 = named_square("c1");
+        const D1: i16 = named_square("d1");
+        self.castling_rights.allows(color, false)
+            && rook_square == self.castling_rook_square(color, false)
+            && self.square_rank(king_square) == self.square_rank(rook_square)
+            && king_square > rook_square
+            && self.can_castle(
+                piece_list,
+                color,
+                (king_square, self.on_rank_of(king_square, C1)),
+                (rook_square, self.on_rank_of(king_square, D1)),
+            )
+    }
+
+    /// Gets the square a castling rook starts from.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side castling
+    /// * `kingside` - `true` for the kingside rook, `false` for the
+    ///   queenside one
+    fn castling_rook_square(&self, color: Color, kingside: bool) -> i16 {
+        let back_rank = match color {
+            Color::White => 0,
+            Color::Black => 56,
+        };
+        let file = self.castling_rights.rook_file(color, kingside);
+        self.map_inner_to_outer_board(back_rank + i16::from(file))
+    }
 
+    /// Checks the board allows a castling move, the rights aside.
+    ///
+    /// The king and the rook must stand on their squares, every square
+    /// either of them goes through or lands on must be empty but for the
+    /// two of them, and the king must not be in check, pass through or land
+    /// on an attacked square. The squares follow from where the pieces
+    /// start and land, as in Chess960.
+    ///
+    /// # Arguments
+    ///
+    /// * `piece_list` - Piece lists used to find attackers of the king's path
+    /// * `color` - Color attempting to castle
+    /// * `(king_from, king_to)` - Squares the king starts from and lands on
+    /// * `(rook_from, rook_to)` - Squares the rook starts from and lands on
+    fn can_castle(
+        &self,
+        piece_list: &PieceList,
+        color: Color,
+        (king_from, king_to): (i16, i16),
+        (rook_from, rook_to): (i16, i16),
+    ) -> bool {
         // 1. Check if king and rook are in starting positions
-        if self.get_piece_on_square(king_square)
-            != if color == Color::White {
-                Piece::WhiteKing
-            } else {
-                Piece::BlackKing
-            }
+        let (king, rook) = match color {
+            Color::White => (Piece::WhiteKing, Piece::WhiteRook),
+            Color::Black => (Piece::BlackKing, Piece::BlackRook),
+        };
+        if self.get_piece_on_square(king_from) != king
+            || self.get_piece_on_square(rook_from) != rook
         {
             return false;
         }
 
-        if self.get_piece_on_square(rook_square)
-            != if color == Color::White {
-                Piece::WhiteRook
-            } else {
-                Piece::BlackRook
-            }
-        {
+        // 2. Check if the squares the pieces go through are empty. They are
+        // on one rank, where the squares follow each other
+        let span = |from: i16, to: i16| from.min(to)..=from.max(to);
+        let blocked = span(king_from, king_to)
+            .chain(span(rook_from, rook_to))
+            .filter(|&square| square != king_from && square != rook_from)
+            .any(|square| self.get_piece_on_square(square) != Piece::EmptySquare);
+        if blocked {
             return false;
         }
 
-        // 2. Check if squares between king and rook are empty
-        let squares_between = match color {
-            Color::White => vec![22, 23, 24], // b1, c1, d1
-            Color::Black => vec![92, 93, 94], // b8, c8, d8
-        };
-
-        for square in squares_between {
-            if self.get_piece_on_square(square) != Piece::EmptySquare {
+        // 3. Check if king is not in check and doesn't move through check
+        let step = if king_to > king_from { 1 } else { -1 };
+        let mut square = king_from;
+        loop {
+            if piece_list.is_square_attacked(self, square, color.opposite()) {
                 return false;
             }
+            if square == king_to {
+                break;
+            }
+            square += step;
         }
 
-        // 3. Check if king is not in check and doesn't move through check
-        let check_squares = match color {
-            Color::White => vec![25, 24, 23], // e1, d1, c1
-            Color::Black => vec![95, 94, 93], // e8, d8, c8
-        };
-
-        for square in check_squares {
-            let opposite_color = if color == Color::White {
-                Color::Black
-            } else {
-                Color::White
-            };
-            if self
-                .piece_list
-                .is_square_attacked(self, square, opposite_color)
-            {
-                return false;
-            }
+        // 4. In Chess960 the rook may stand between the king's destination
+        // and a rook or queen on the back rank, which attack the king once
+        // the rook has left
+        let step = (rook_from - king_to).signum();
+        if step == 0 {
+            return true;
+        }
+        let mut square = king_to + step;
+        while square == king_from
+            || square == rook_from
+            || self.get_piece_on_square(square) == Piece::EmptySquare
+        {
+            square += step;
         }
+        let piece = self.get_piece_on_square(square);
+        !(piece.is_valid_piece()
+            && piece.get_color() != color
+            && matches!(piece.get_type(), PieceType::Rook | PieceType::Queen))
+    }
 
-        true
+    /// Gets the square of a file on the rank of another square.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - Square giving the rank
+    /// * `file_square` - Square giving the file
+    fn on_rank_of(&self, square: i16, file_square: i16) -> i16 {
+        square - self.square_file(square) + self.square_file(file_square)
     }
 
     fn zobrist_hash(&self, side_to_move: Color) -> u64 {
@@ -702,7 +1663,7 @@ impl ChessBoard {
         // Hash pieces
         for square_idx in 0..64 {
             let piece = self.get_piece_on_square(self.map_inner_to_outer_board(square_idx));
-            if !piece.is_empty() {
+            if piece.is_valid_piece() {
                 hash ^= self.zobrist.pieces[square_idx as usize][piece as usize];
             }
         }
@@ -727,160 +1688,582 @@ impl ChessBoard {
         }
 
         // Hash en passant file
-        if let Some(square) = self.get_en_passant_target() {
-            let file = self.square_file(square) - (self.board_width - 8) / 2;
-            hash ^= self.zobrist.en_passant[file as usize];
+        if let Some(file) = self.hashed_en_passant_file() {
+            hash ^= self.zobrist.en_passant[file];
         }
 
         hash
     }
 
-    fn update_hash(&mut self, mv: &Move) {
+    /// Gets the file of the en passant target if it is part of the hash.
+    ///
+    /// Like Polyglot, the target is only hashed when a pawn can capture on
+    /// it, otherwise the same position reached by different move orders
+    /// would get different hashes. The capturing side is deduced from the
+    /// rank of the target.
+    ///
+    /// # Returns
+    ///
+    /// File index (0 = a ... 7 = h), or None when no en passant key applies
+    fn hashed_en_passant_file(&self) -> Option<usize> {
+        let target = self.get_en_passant_target()?;
+        let capturing_side = if self.map_to_standard_chess_board(target) / 8 == 5 {
+            Color::White
+        } else {
+            Color::Black
+        };
+
+        polyglot::en_passant_file(self, capturing_side)
+    }
+
+    fn update_hash(
+        &mut self,
+        mv: &Move,
+        previous_en_passant_file: Option<usize>,
+        previous_castling_rights: &CastlingRights,
+    ) {
         let from_square = self.map_to_standard_chess_board(mv.from);
         let to_square = self.map_to_standard_chess_board(mv.to);
 
         // 1. Hash out the piece from its original square
         self.hash ^= self.zobrist.pieces[from_square][mv.piece as usize];
 
-        // 2. Hash out the captured piece from its square (if any)
-        if mv.captured_piece.is_valid_piece() {
+        // 2. Hash out the captured piece from its square (if any), en
+        // passant captures are hashed out below
+        if mv.captured_piece.is_valid_piece() && !mv.en_passant {
             self.hash ^= self.zobrist.pieces[to_square][mv.captured_piece as usize];
         }
 
-        // 3. Hash in the moved piece to its new square
-        self.hash ^= self.zobrist.pieces[to_square][mv.piece as usize];
+        // 3. Hash in the moved piece to its new square
+        self.hash ^= self.zobrist.pieces[to_square][mv.piece as usize];
+
+        // 4. Hash out the old side to move
+        self.hash ^= self.zobrist.side_to_move;
+
+        // 5. Hash out castling move
+        if let Some(castling) = &mv.castling {
+            let rook_from = self.map_to_standard_chess_board(castling.rook_from);
+            let rook_to = self.map_to_standard_chess_board(castling.rook_to);
+            self.hash ^= self.zobrist.pieces[rook_from][castling.rook_piece as usize];
+            self.hash ^= self.zobrist.pieces[rook_to][castling.rook_piece as usize];
+        }
+
+        // 6. Hash out en passant squares, the ones no pawn can capture on
+        // are not part of the hash
+        if let Some(file) = self.hashed_en_passant_file() {
+            self.hash ^= self.zobrist.en_passant[file];
+        }
+
+        if let Some(file) = previous_en_passant_file {
+            self.hash ^= self.zobrist.en_passant[file];
+        }
+
+        // 7. Hash out en passant moves
+        if let Some((capture_square, captured_pawn)) = mv.en_passant_capture(self.board_width) {
+            let capture_square = self.map_to_standard_chess_board(capture_square);
+            self.hash ^= self.zobrist.pieces[capture_square][captured_pawn as usize];
+        }
+
+        // 8. Promotion: Hash out the pawn and hash in the new piece on the same square.
+        if let Some(promoted_piece) = mv.promotion {
+            // hash out the pawn
+            self.hash ^= self.zobrist.pieces[to_square][mv.piece as usize];
+            // hash in the promoted piece
+            self.hash ^= self.zobrist.pieces[to_square][promoted_piece as usize];
+        }
+
+        // 9. Handle castling rights changes
+        let old_rights = previous_castling_rights;
+        let new_rights = &self.castling_rights;
+
+        // Only update hash for rights that actually changed
+        if old_rights.white_queenside != new_rights.white_queenside {
+            self.hash ^= self.zobrist.castling_rights[0];
+        }
+        if old_rights.white_kingside != new_rights.white_kingside {
+            self.hash ^= self.zobrist.castling_rights[1];
+        }
+        if old_rights.black_queenside != new_rights.black_queenside {
+            self.hash ^= self.zobrist.castling_rights[2];
+        }
+        if old_rights.black_kingside != new_rights.black_kingside {
+            self.hash ^= self.zobrist.castling_rights[3];
+        }
+    }
+
+    /// Sets up the board from an 8x8 array of pieces.
+    ///
+    /// # Arguments
+    ///
+    /// * `board_position` - Array of 64 pieces representing the chess board
+    pub fn set_board(&mut self, board_position: &[Piece; 64], side_to_move: Color) {
+        // Set all squares to invalid
+        for square in self.board_squares.iter_mut() {
+            *square = Piece::SentinelSquare;
+        }
+
+        for (square, &piece) in board_position.iter().enumerate() {
+            let inner_square = self.map_inner_to_outer_board(square as i16);
+            self.set_piece_on_square(piece, inner_square);
+        }
+
+        // When the board is set all at once we have to update the piece-lists
+        self.piece_list.update_lists(&self.board_squares);
+
+        // Moves made before the new position can't be unmade anymore
+        self.undo_stack.clear();
+        self.reversible_plies = 0;
+
+        if let Some(nnue) = &mut self.nnue {
+            nnue.refresh(board_position);
+        }
+
+        // Calculate hash for this board position
+        self.hash = self.zobrist_hash(side_to_move);
+    }
+
+    /// Sets the en passant target square from a standard chess coordinate.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - Standard chess square index (0-63)
+    pub fn set_en_passant_square(&mut self, square: i16) {
+        self.en_passant_target = Some(self.map_inner_to_outer_board(square));
+    }
+
+    /// Clears the en passant target square.
+    pub fn clear_en_passant_square(&mut self) {
+        self.en_passant_target = None;
+    }
+
+    /// Sets whether the board follows Chess960 conventions.
+    ///
+    /// Only the castling field of [`to_fen`](Self::to_fen) depends on it:
+    /// castling follows the rook squares of the castling rights either way.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess960` - `true` to write castling rights in Shredder-FEN form
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+    }
+
+    /// Whether the board follows Chess960 conventions, see
+    /// [`set_chess960`](Self::set_chess960).
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    /// Sets the castling rights from a CastlingRights struct.
+    ///
+    /// # Arguments
+    ///
+    /// * `castling_rights` - New castling rights to set
+    pub fn set_castling_rights(&mut self, castling_rights: &CastlingRights) {
+        self.castling_rights = *castling_rights;
+    }
+
+    /// Gets the number of moves made on this board since the last reset.
+    ///
+    /// Every node visited by the search is entered with a `make_move`, so
+    /// this doubles as the search node counter.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Resets the node counters to zero.
+    pub fn reset_nodes(&mut self) {
+        self.nodes = 0;
+        self.qnodes = 0;
+    }
+
+    /// Gets the number of quiescence search nodes visited since the last
+    /// reset, whose moves are counted by [`nodes`](Self::nodes) as well.
+    pub fn qnodes(&self) -> u64 {
+        self.qnodes
+    }
+
+    /// Gets the number of transposition table lookups made by the searches
+    /// on this board.
+    pub fn tt_probes(&self) -> u64 {
+        self.tt_probes
+    }
+
+    /// Gets the number of transposition table lookups that found their
+    /// position, usable or not at the depth searched. Divided by
+    /// [`tt_probes`](Self::tt_probes), it measures how well the table keeps
+    /// the positions the search comes back to.
+    pub fn tt_hits(&self) -> u64 {
+        self.tt_hits
+    }
+
+    /// Gets the selective search depth: the deepest ply below the root
+    /// reached since the start of the search iteration, extensions and
+    /// quiescence included.
+    pub fn seldepth(&self) -> usize {
+        self.seldepth
+    }
+
+    /// Makes the current position the root of a search iteration for the
+    /// selective depth.
+    fn start_seldepth(&mut self) {
+        self.root_ply = self.undo_stack.len();
+        self.seldepth = 0;
+    }
+
+    /// Gets the distance of the position from the root of the search
+    /// iteration, in plies.
+    fn search_ply(&self) -> u32 {
+        self.undo_stack.len().saturating_sub(self.root_ply) as u32
+    }
+
+    /// Counts a transposition table lookup of the search.
+    fn count_tt_probe(&mut self, hit: bool) {
+        self.tt_probes += 1;
+        self.tt_hits += hit as u64;
+    }
+
+    /// Sets the clock of the search running on this board.
+    ///
+    /// While a clock is set, [`make_move`](Self::make_move) checks it every
+    /// [`NODES_PER_TIME_CHECK`] nodes and stops the search once its hard
+    /// limit is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - Clock of the search, or `None` to search without limits
+    pub fn set_clock(&mut self, clock: Option<Arc<SearchClock>>) {
+        self.clock = clock;
+        self.unreported_nodes = 0;
+    }
+
+    /// Adds the nodes searched since the last batch to the clock's count,
+    /// so that the count reported is exact.
+    pub fn report_nodes(&mut self) {
+        if let Some(clock) = &self.clock {
+            clock.add_nodes(self.unreported_nodes);
+            self.unreported_nodes = 0;
+        }
+    }
+
+    /// Gets the clock of the search running on this board.
+    pub fn clock(&self) -> Option<&SearchClock> {
+        self.clock.as_deref()
+    }
+
+    /// Sets where the searches on this board send their `info` lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - Receiver of the lines, or `None` to search silently
+    pub fn set_info_reporter(&mut self, reporter: Option<InfoReporter>) {
+        self.info_reporter = reporter;
+    }
+
+    /// Sends an `info` line to the reporter, formatting it only when there
+    /// is one.
+    fn report_info(&self, line: impl FnOnce() -> String) {
+        if let Some(reporter) = &self.info_reporter {
+            reporter(&line());
+        }
+    }
+
+    /// Sets the margins and depths used by the searches on this board.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_params` - Search parameters
+    pub fn set_search_params(&mut self, search_params: SearchParams) {
+        self.search_params = search_params;
+    }
+
+    /// Gets the margins and depths used by the searches on this board.
+    pub fn search_params(&self) -> &SearchParams {
+        &self.search_params
+    }
+
+    /// Scores a draw by repetition or insufficient material for the side to
+    /// move.
+    ///
+    /// With contempt, the side the search plays for takes a draw as a small
+    /// loss and its opponent as a small win, so the search avoids
+    /// repetitions when the position is about equal.
+    ///
+    /// # Arguments
+    ///
+    /// * `side_to_move` - Color of the player to move
+    ///
+    /// # Returns
+    ///
+    /// The side-relative score of the draw
+    pub fn draw_score(&self, side_to_move: Color) -> Score {
+        let contempt = self.search_params.contempt;
+        if side_to_move == self.search_side {
+            -contempt
+        } else {
+            contempt
+        }
+    }
+
+    /// Lets the searches on this board play any root move scored within a
+    /// margin of the best one, picked at random.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin` - Largest score loss accepted, None to always play the
+    ///   best move
+    pub fn set_variety(&mut self, margin: Option<Score>) {
+        self.variety = margin;
+    }
+
+    /// Gets the margin set with [`set_variety`](Self::set_variety).
+    pub fn variety(&self) -> Option<Score> {
+        self.variety
+    }
 
-        // 4. Hash out the old side to move
-        self.hash ^= self.zobrist.side_to_move;
+    /// Makes the searches on this board search a root move first and play
+    /// it when it scores within
+    /// [`LEARNED_MOVE_MARGIN`](search::LEARNED_MOVE_MARGIN) of the best
+    /// move, see [`Experience`](crate::game_state::experience::Experience).
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - Move learned from earlier games, None to play the best move
+    pub fn set_learned_move(&mut self, mv: Option<Move>) {
+        self.learned_move = mv;
+    }
 
-        // 5. Hash out castling move
-        if let Some(castling) = &mv.castling {
-            let rook_from = self.map_to_standard_chess_board(castling.rook_from);
-            let rook_to = self.map_to_standard_chess_board(castling.rook_to);
-            self.hash ^= self.zobrist.pieces[rook_from][castling.rook_piece as usize];
-            self.hash ^= self.zobrist.pieces[rook_to][castling.rook_piece as usize];
-        }
+    /// Gets the move set with [`set_learned_move`](Self::set_learned_move).
+    pub fn learned_move(&self) -> Option<&Move> {
+        self.learned_move.as_ref()
+    }
 
-        // 6. Hash out en passant squares
-        if let Some(square) = mv.en_passant_square {
-            let file = self.square_file(square) - (self.board_width - 8) / 2;
-            self.hash ^= self.zobrist.en_passant[file as usize];
-        }
+    /// Lets the searches on this board play forced moves without searching:
+    /// the only legal move, or in timed searches an obvious recapture.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether forced moves are played at once
+    pub fn set_instant_moves(&mut self, enabled: bool) {
+        self.instant_moves = enabled;
+    }
 
-        if let Some(square) = mv.previous_en_passant {
-            let file = self.square_file(square) - (self.board_width - 8) / 2;
-            self.hash ^= self.zobrist.en_passant[file as usize];
-        }
+    /// Whether the searches on this board play forced moves without
+    /// searching, see [`set_instant_moves`](Self::set_instant_moves).
+    pub fn instant_moves(&self) -> bool {
+        self.instant_moves
+    }
 
-        // 7. Hash out en passant moves
-        if mv.en_passant {
-            let capture_square = if mv.piece.is_white() {
-                self.map_to_standard_chess_board(mv.to - self.board_width)
-            } else {
-                self.map_to_standard_chess_board(mv.to + self.board_width)
-            };
-            let captured_pawn = if mv.piece.is_white() {
-                Piece::BlackPawn
-            } else {
-                Piece::WhitePawn
-            };
-            self.hash ^= self.zobrist.pieces[capture_square][captured_pawn as usize];
-        }
+    /// Lets the quiescence searches at the horizon of the searches on this
+    /// board play quiet checking moves at their first ply.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether quiet checks are searched
+    pub fn set_quiescence_checks(&mut self, enabled: bool) {
+        self.quiescence_checks = enabled;
+    }
 
-        // 8. Promotion: Hash out the pawn and hash in the new piece on the same square.
-        if let Some(promoted_piece) = mv.promotion {
-            // hash out the pawn
-            self.hash ^= self.zobrist.pieces[to_square][mv.piece as usize];
-            // hash in the promoted piece
-            self.hash ^= self.zobrist.pieces[to_square][promoted_piece as usize];
-        }
+    /// Whether quiescence searches quiet checks on this board, see
+    /// [`set_quiescence_checks`](Self::set_quiescence_checks).
+    pub fn quiescence_checks(&self) -> bool {
+        self.quiescence_checks
+    }
 
-        // 9. Handle castling rights changes
-        if let Some(old_rights) = &mv.previous_castling_rights {
-            let new_rights = &self.castling_rights;
+    /// Restricts the searches on this board to some root moves, as `go
+    /// searchmoves`.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` - Moves to search, None to search every legal move
+    pub fn set_search_moves(&mut self, moves: Option<Vec<Move>>) {
+        self.search_moves = moves;
+    }
 
-            // Only update hash for rights that actually changed
-            if old_rights.white_queenside != new_rights.white_queenside {
-                self.hash ^= self.zobrist.castling_rights[0];
-            }
-            if old_rights.white_kingside != new_rights.white_kingside {
-                self.hash ^= self.zobrist.castling_rights[1];
-            }
-            if old_rights.black_queenside != new_rights.black_queenside {
-                self.hash ^= self.zobrist.castling_rights[2];
-            }
-            if old_rights.black_kingside != new_rights.black_kingside {
-                self.hash ^= self.zobrist.castling_rights[3];
-            }
-        }
+    /// Leaves root moves out of the searches on this board, as `go
+    /// avoidmoves`: the search finds the best move among the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` - Moves not to search, empty to search every legal move
+    pub fn set_avoid_moves(&mut self, moves: Vec<Move>) {
+        self.avoid_moves = moves;
     }
 
-    /// Sets up the board from an 8x8 array of pieces.
+    /// Generates the root moves the searches on this board may play: the
+    /// moves of [`set_search_moves`](Self::set_search_moves) less those of
+    /// [`set_avoid_moves`](Self::set_avoid_moves).
+    ///
+    /// When no legal move is left, every legal move is returned, as a move
+    /// must still be played.
     ///
     /// # Arguments
     ///
-    /// * `board_position` - Array of 64 pieces representing the chess board
-    pub fn set_board(&mut self, board_position: &[Piece; 64], side_to_move: Color) {
-        // Set all squares to invalid
-        for square in self.board_squares.iter_mut() {
-            *square = Piece::SentinelSquare;
-        }
+    /// * `color` - Color to generate moves for
+    pub fn generate_root_moves(&mut self, color: Color) -> MoveList {
+        let moves = self.generate_moves(color);
+        let listed = |list: &[Move], mv: &Move| {
+            let encoded = mv.encode(self);
+            list.iter().any(|listed| listed.encode(self) == encoded)
+        };
 
-        for (square, &piece) in board_position.iter().enumerate() {
-            let inner_square = self.map_inner_to_outer_board(square as i16);
-            self.set_piece_on_square(piece, inner_square);
-        }
+        let allowed: MoveList = moves
+            .iter()
+            .filter(|mv| {
+                self.search_moves
+                    .as_deref()
+                    .is_none_or(|list| listed(list, mv))
+                    && !listed(&self.avoid_moves, mv)
+            })
+            .cloned()
+            .collect();
 
-        // When the board is set all at once we have to update the piece-lists
-        self.piece_list.update_lists(&self.board_squares);
+        if allowed.is_empty() { moves } else { allowed }
+    }
 
-        // Calculate hash for this board position
-        self.hash = self.zobrist_hash(side_to_move);
+    /// Makes the searches on this board resume the analysis of the previous
+    /// position: the transposition table entries of that search are kept
+    /// as entries of the new one, and iterative deepening starts at a depth
+    /// above 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - First iteration to search, None for a new search
+    pub fn set_resume_depth(&mut self, depth: Option<u8>) {
+        self.resume_depth = depth;
     }
 
-    /// Sets the en passant target square from a standard chess coordinate.
+    /// Gets the depth set with [`set_resume_depth`](Self::set_resume_depth).
+    pub fn resume_depth(&self) -> Option<u8> {
+        self.resume_depth
+    }
+
+    /// Sets the square the last move of the game captured on, so the
+    /// searches can recapture without searching.
     ///
     /// # Arguments
     ///
-    /// * `square` - Standard chess square index (0-63)
-    pub fn set_en_passant_square(&mut self, square: i16) {
-        self.en_passant_target = Some(self.map_inner_to_outer_board(square));
+    /// * `square` - Destination square of the capture, None if the last
+    ///   move wasn't a capture
+    pub fn set_last_capture(&mut self, square: Option<i16>) {
+        self.last_capture = square;
     }
 
-    /// Sets the castling rights from a CastlingRights struct.
+    /// Gets the square set with [`set_last_capture`](Self::set_last_capture).
+    pub fn last_capture(&self) -> Option<i16> {
+        self.last_capture
+    }
+
+    /// Gets the depth of the last iteration completed by the search on this
+    /// board, 0 before the first one.
+    pub fn completed_depth(&self) -> u8 {
+        self.completed_depth
+    }
+
+    /// Sets the recorder of the search tree of the searches on this board.
     ///
     /// # Arguments
     ///
-    /// * `castling_rights` - New castling rights to set
-    pub fn set_castling_rights(&mut self, castling_rights: &CastlingRights) {
-        self.castling_rights.white_queenside = castling_rights.white_queenside;
-        self.castling_rights.white_kingside = castling_rights.white_kingside;
-        self.castling_rights.black_queenside = castling_rights.black_queenside;
-        self.castling_rights.black_kingside = castling_rights.black_kingside;
+    /// * `trace` - Trace to record into, or `None` to stop tracing
+    pub fn set_trace(&mut self, trace: Option<SearchTrace>) {
+        self.trace = trace;
+    }
+
+    /// Removes the search trace from the board, with what it recorded.
+    pub fn take_trace(&mut self) -> Option<SearchTrace> {
+        self.trace.take()
+    }
+
+    /// Starts recording a new search iteration, if tracing.
+    fn trace_start_iteration(&mut self) {
+        if let Some(trace) = &mut self.trace {
+            trace.start_iteration();
+        }
+    }
+
+    /// Keeps the iteration recorded so far as the last completed one, if
+    /// tracing.
+    fn trace_complete_iteration(&mut self) {
+        if let Some(trace) = &mut self.trace {
+            trace.complete_iteration();
+        }
+    }
+
+    /// Records that the search starts searching `mv`, if tracing.
+    fn trace_enter(&mut self, mv: &Move, alpha: Score, beta: Score) {
+        if self.trace.is_some() {
+            let uci = self.move_to_uci(mv);
+            if let Some(trace) = &mut self.trace {
+                trace.enter(uci, alpha, beta);
+            }
+        }
+    }
+
+    /// Records an event on the move being searched, if tracing.
+    fn trace_event(&mut self, event: TraceEvent) {
+        if let Some(trace) = &mut self.trace {
+            trace.event(event);
+        }
+    }
+
+    /// Records the score of the move being searched, if tracing.
+    fn trace_exit(&mut self, score: Score) {
+        if let Some(trace) = &mut self.trace {
+            trace.exit(score);
+        }
     }
 
     /// Executes a move on the board.
     ///
-    /// Updates the board state, castling rights, and piece lists.
+    /// Updates the board state, castling rights, and piece lists. The en
+    /// passant target, castling rights and hash before the move are saved on
+    /// the board, so the move carries nothing needed to unmake it.
     ///
     /// # Arguments
     ///
     /// * `mv` - The move to execute
     pub fn make_move(&mut self, mv: &Move) {
+        self.nodes += 1;
+        self.unreported_nodes += 1;
+        if self.unreported_nodes >= NODES_PER_TIME_CHECK
+            && let Some(clock) = &self.clock
+        {
+            clock.add_nodes(self.unreported_nodes);
+            self.unreported_nodes = 0;
+            clock.check();
+        }
+        let mut undo = UndoState {
+            en_passant_target: self.en_passant_target,
+            castling_rights: self.castling_rights,
+            hash: self.hash,
+            reversible_plies: self.reversible_plies,
+            exploded: Vec::new(),
+        };
+        let previous_en_passant_file = self.hashed_en_passant_file();
+
         self.update_castling_rights(mv);
 
+        let is_irreversible = mv.piece.get_type() == PieceType::Pawn
+            || mv.is_capture()
+            || self.castling_rights != undo.castling_rights;
+        self.reversible_plies = if is_irreversible {
+            0
+        } else {
+            self.reversible_plies.saturating_add(1)
+        };
+
         let piece = mv.piece;
 
         // If this was an en passant capture
-        if mv.en_passant {
-            let capture_square = if mv.piece.is_white() {
-                mv.to - self.board_width
-            } else {
-                mv.to + self.board_width
-            };
+        if let Some((capture_square, _)) = mv.en_passant_capture(self.board_width) {
             self.set_piece_on_square(Piece::EmptySquare, capture_square);
         }
 
+        // When a move is made, the previous square of the piece is cleared.
+        // In Chess960 the king and the rook may land on each other's
+        // squares, so both leave before either lands
+        self.set_piece_on_square(Piece::EmptySquare, mv.from);
+
         if let Some(castling) = &mv.castling {
             self.set_piece_on_square(Piece::EmptySquare, castling.rook_from);
             self.set_piece_on_square(castling.rook_piece, castling.rook_to);
@@ -892,47 +2275,128 @@ impl ChessBoard {
             self.set_piece_on_square(piece, mv.to);
         }
 
-        // When a move is made, the previous square of the piece is cleared
-        self.set_piece_on_square(Piece::EmptySquare, mv.from);
-
         // When pawn moves two squares we update the en passant square
         self.set_en_passant_target(mv.en_passant_square);
 
         // Update piece list
-        self.piece_list.make_move(mv);
+        self.piece_list.make_move(mv, self.board_width);
+
+        if mv.is_capture() {
+            undo.exploded = self.explode(mv);
+        }
 
         // Update hash AFTER changing board state
         // so we can see what was changed after applying this move
-        self.update_hash(mv);
+        self.update_hash(mv, previous_en_passant_file, &undo.castling_rights);
+        for &(square, piece) in &undo.exploded {
+            let square = self.map_to_standard_chess_board(square);
+            self.hash ^= self.zobrist.pieces[square][piece as usize];
+        }
+        // The bucket of the new position loads while the accumulator is
+        // updated, the search probes it next
+        self.transposition_table.prefetch(self.hash);
+
+        self.push_nnue_accumulator(mv);
+
+        self.undo_stack.push(undo);
+        self.seldepth = self
+            .seldepth
+            .max(self.undo_stack.len().saturating_sub(self.root_ply));
+    }
+
+    /// Returns a copy of the board with a move applied (copy-make).
+    ///
+    /// The original board is left untouched, so no unmake is needed. Copying
+    /// the board costs more than a make/unmake pair (see the board
+    /// benchmarks), so the search keeps using [`make_move`](Self::make_move)
+    /// and [`unmake_move`](Self::unmake_move); copy-make suits one-off
+    /// lookahead from a shared reference, such as testing whether a rare move
+    /// gives check.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - The move to apply
+    pub fn copy_make(&self, mv: &Move) -> ChessBoard {
+        let mut board = self.clone();
+        board.make_move(mv);
+        board
+    }
+
+    /// Removes the pieces blown up by a capture, as decided by the rules.
+    ///
+    /// Castling rights are lost with the king or rook that held them.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - The capture, already made on the board
+    ///
+    /// # Returns
+    ///
+    /// The removed pieces with their squares, in removal order
+    fn explode(&mut self, mv: &Move) -> Vec<(i16, Piece)> {
+        let exploded: Vec<(i16, Piece)> = self
+            .rules
+            .exploded_squares(self, mv)
+            .into_iter()
+            .map(|square| (square, self.get_piece_on_square(square)))
+            .filter(|(_, piece)| piece.is_valid_piece())
+            .collect();
+
+        for &(square, piece) in &exploded {
+            self.set_piece_on_square(Piece::EmptySquare, square);
+            self.piece_list.remove_piece(piece, square);
+
+            // Standard squares of a1, e1, h1, a8, e8 and h8
+            match self.map_to_standard_chess_board(square) {
+                0 => self.castling_rights.white_queenside = false,
+                4 => {
+                    self.castling_rights.white_queenside = false;
+                    self.castling_rights.white_kingside = false;
+                }
+                7 => self.castling_rights.white_kingside = false,
+                56 => self.castling_rights.black_queenside = false,
+                60 => {
+                    self.castling_rights.black_queenside = false;
+                    self.castling_rights.black_kingside = false;
+                }
+                63 => self.castling_rights.black_kingside = false,
+                _ => {}
+            }
+        }
+
+        exploded
     }
 
     /// Reverts a move on the board.
     ///
-    /// Restores the board state to before the move was made.
+    /// Restores the board state to before the move was made. Moves must be
+    /// unmade in the reverse order they were made.
     ///
     /// # Arguments
     ///
     /// * `mv` - The move to undo
+    ///
+    /// # Panics
+    ///
+    /// Panics if no move was made on the board.
     pub fn unmake_move(&mut self, mv: &Move) {
-        // Update hash BEFORE restoring board state
-        // so that we can see what WILL change when this revoked
-        self.update_hash(mv);
+        let undo = self
+            .undo_stack
+            .pop()
+            .expect("unmake_move called without a move to undo");
+
+        // Put back the exploded pieces first, the moved piece among them
+        for &(square, piece) in undo.exploded.iter().rev() {
+            self.set_piece_on_square(piece, square);
+            self.piece_list.add_piece(piece, square);
+        }
 
         // Restaure captured piece
-        self.set_piece_on_square(mv.captured_piece, mv.to);
-
-        if mv.en_passant {
-            let capture_square = if mv.piece.is_white() {
-                mv.to - self.board_width
-            } else {
-                mv.to + self.board_width
-            };
-            let captured_pawn = if mv.piece.is_white() {
-                Piece::BlackPawn
-            } else {
-                Piece::WhitePawn
-            };
+        if let Some((capture_square, captured_pawn)) = mv.en_passant_capture(self.board_width) {
+            self.set_piece_on_square(Piece::EmptySquare, mv.to);
             self.set_piece_on_square(captured_pawn, capture_square);
+        } else {
+            self.set_piece_on_square(mv.captured_piece, mv.to);
         }
 
         if let Some(castling) = &mv.castling {
@@ -940,17 +2404,94 @@ impl ChessBoard {
             self.set_piece_on_square(Piece::EmptySquare, castling.rook_to);
         }
 
-        if let Some(previous_castling_rights) = mv.previous_castling_rights {
-            self.castling_rights = previous_castling_rights;
-        }
+        self.castling_rights = undo.castling_rights;
 
         // Promotion is undone automatically
         self.set_piece_on_square(mv.piece, mv.from);
 
-        // Restore en passant square to previous state
-        self.set_en_passant_target(mv.previous_en_passant);
+        // Restore en passant square and hash to previous state
+        self.set_en_passant_target(undo.en_passant_target);
+        self.hash = undo.hash;
+        self.reversible_plies = undo.reversible_plies;
 
-        self.piece_list.unmake_move(mv);
+        self.piece_list.unmake_move(mv, self.board_width);
+
+        if let Some(nnue) = &mut self.nnue {
+            nnue.pop();
+        }
+    }
+
+    /// Passes the turn without moving a piece (null move).
+    ///
+    /// Only the side to move and the en passant target change. The null move
+    /// is irreversible for repetition detection, so positions before it are
+    /// never compared with positions after it.
+    pub fn make_null_move(&mut self) {
+        let undo = UndoState {
+            en_passant_target: self.en_passant_target,
+            castling_rights: self.castling_rights,
+            hash: self.hash,
+            reversible_plies: self.reversible_plies,
+            exploded: Vec::new(),
+        };
+
+        self.hash ^= self.zobrist.side_to_move;
+        if let Some(file) = self.hashed_en_passant_file() {
+            self.hash ^= self.zobrist.en_passant[file];
+        }
+        self.set_en_passant_target(None);
+        self.reversible_plies = 0;
+
+        self.undo_stack.push(undo);
+    }
+
+    /// Reverts a null move made with [`make_null_move`](Self::make_null_move).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no move was made on the board.
+    pub fn unmake_null_move(&mut self) {
+        let undo = self
+            .undo_stack
+            .pop()
+            .expect("unmake_null_move called without a move to undo");
+
+        self.set_en_passant_target(undo.en_passant_target);
+        self.hash = undo.hash;
+        self.reversible_plies = undo.reversible_plies;
+    }
+
+    /// Checks if a color has any piece other than pawns and its king.
+    ///
+    /// Positions where the side to move only has pawns are prone to
+    /// zugzwang, where passing would be better than any move.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Color whose pieces are counted
+    ///
+    /// # Returns
+    ///
+    /// `true` if the color has a knight, bishop, rook or queen
+    pub fn has_non_pawn_material(&self, color: Color) -> bool {
+        let pieces = match color {
+            Color::White => [
+                Piece::WhiteKnight,
+                Piece::WhiteBishop,
+                Piece::WhiteRook,
+                Piece::WhiteQueen,
+            ],
+            Color::Black => [
+                Piece::BlackKnight,
+                Piece::BlackBishop,
+                Piece::BlackRook,
+                Piece::BlackQueen,
+            ],
+        };
+
+        pieces
+            .into_iter()
+            .any(|piece| self.piece_list.get_number_of_pieces(piece).unwrap_or(0) > 0)
     }
 
     /// Searches for the best move using minimax with alpha-beta pruning.
@@ -1023,16 +2564,84 @@ impl ChessBoard {
     ///
     /// # Returns
     ///
-    /// Vector of legal moves
-    pub fn generate_moves(&mut self, color: Color) -> Vec<Move> {
-        let mut board_copy = self.clone();
-        self.piece_list.generate_legal_moves(&mut board_copy, color)
+    /// List of legal moves
+    pub fn generate_moves(&mut self, color: Color) -> MoveList {
+        self.rules.generate_moves(self, color)
+    }
+
+    /// Generates the legal moves of standard chess for the given color.
+    fn generate_standard_moves(&mut self, color: Color) -> MoveList {
+        // The generator lifts pieces off the board squares while it reads the
+        // piece lists, so the lists are moved out for the duration of the call.
+        // Taking them leaves empty lists behind, which doesn't allocate.
+        let piece_list = std::mem::take(&mut self.piece_list);
+        let moves = piece_list.generate_legal_moves(self, color);
+        self.piece_list = piece_list;
+        moves
+    }
+
+    /// Counts the legal moves for the given color without building them.
+    ///
+    /// Cheaper than [`generate_moves`](Self::generate_moves) when only the
+    /// number of moves matters, as for mobility or mate and stalemate checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Color to count moves for
+    ///
+    /// # Returns
+    ///
+    /// Number of legal moves
+    pub fn count_legal_moves(&mut self, color: Color) -> usize {
+        self.rules.count_moves(self, color)
+    }
+
+    /// Counts the legal moves of standard chess for the given color.
+    fn count_standard_moves(&mut self, color: Color) -> usize {
+        // See generate_standard_moves for why the lists are taken
+        let piece_list = std::mem::take(&mut self.piece_list);
+        let count = piece_list.count_legal_moves(self, color);
+        self.piece_list = piece_list;
+        count
+    }
+
+    /// Generates the moves for the given color without testing whether they
+    /// leave its king in check, for the variants deciding it themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Color to generate moves for
+    /// * `castling` - Whether castling moves are generated
+    #[cfg(any(feature = "antichess", feature = "atomic"))]
+    fn generate_pseudo_legal_moves(&mut self, color: Color, castling: bool) -> MoveList {
+        // See generate_standard_moves for why the lists are taken
+        let piece_list = std::mem::take(&mut self.piece_list);
+        let moves = piece_list.generate_pseudo_legal_moves(self, color, castling);
+        self.piece_list = piece_list;
+        moves
     }
 
     pub fn set_transposition_table(&mut self, transposition_table: Arc<TranspositionTable>) {
         self.transposition_table = transposition_table;
     }
 
+    /// Empties the transposition table shared by this board.
+    pub fn clear_transposition_table(&self) {
+        self.transposition_table.clear();
+    }
+
+    /// Saves the transposition table shared by this board to a hash file,
+    /// see [`TranspositionTable::save`].
+    pub fn save_transposition_table(&self, path: &Path) -> io::Result<usize> {
+        self.transposition_table.save(path, &self.zobrist)
+    }
+
+    /// Loads the transposition table shared by this board from a hash file,
+    /// see [`TranspositionTable::load`].
+    pub fn load_transposition_table(&self, path: &Path) -> io::Result<usize> {
+        self.transposition_table.load(path, &self.zobrist)
+    }
+
     /// Create board passing the zobrist keys to be used and the transposition table structure
     /// Creates a new chess board with the given zobrist keys and
     /// transposition table. The evaluator defaults to a composite
@@ -1049,9 +2658,9 @@ impl ChessBoard {
         evaluator: Arc<dyn Evaluator>,
     ) -> Self {
         ChessBoard {
-            board_width: 10,
-            board_height: 12,
-            board_squares: [Piece::SentinelSquare; 10 * 12],
+            board_width: BOARD_WIDTH,
+            board_height: BOARD_HEIGHT,
+            board_squares: [Piece::SentinelSquare; BOARD_SQUARES],
             en_passant_target: None,
 
             castling_rights: CastlingRights {
@@ -1059,6 +2668,7 @@ impl ChessBoard {
                 white_queenside: false,
                 black_kingside: false,
                 black_queenside: false,
+                rook_files: CastlingRights::STANDARD_ROOK_FILES,
             },
 
             piece_list: PieceList::default(),
@@ -1070,6 +2680,38 @@ impl ChessBoard {
             transposition_table,
 
             evaluator,
+
+            nodes: 0,
+            unreported_nodes: 0,
+            tt_probes: 0,
+            tt_hits: 0,
+            qnodes: 0,
+            root_ply: 0,
+            seldepth: 0,
+
+            nnue: None,
+
+            clock: None,
+            info_reporter: None,
+            search_params: SearchParams::default(),
+            search_side: Color::White,
+            rules: &rules::STANDARD,
+            chess960: false,
+            variety: None,
+            learned_move: None,
+            instant_moves: true,
+            quiescence_checks: false,
+            search_moves: None,
+            avoid_moves: Vec::new(),
+            resume_depth: None,
+            last_capture: None,
+            completed_depth: 0,
+
+            trace: None,
+
+            undo_stack: Vec::with_capacity(MAX_UNDO_DEPTH),
+
+            reversible_plies: 0,
         }
     }
 }
@@ -1093,11 +2735,38 @@ mod chess_board_tests {
     fn algebraic_to_internal_convertion() {
         let board = setup_game().board;
 
-        assert_eq!(board.algebraic_to_internal("e4"), 55);
-        assert_eq!(board.algebraic_to_internal("a1"), 21);
-        assert_eq!(board.algebraic_to_internal("a8"), 91);
-        assert_eq!(board.algebraic_to_internal("h1"), 28);
-        assert_eq!(board.algebraic_to_internal("h8"), 98);
+        assert_eq!(board.algebraic_to_internal("e4").unwrap(), 55);
+        assert_eq!(board.algebraic_to_internal("a1").unwrap(), 21);
+        assert_eq!(board.algebraic_to_internal("a8").unwrap(), 91);
+        assert_eq!(board.algebraic_to_internal("h1").unwrap(), 28);
+        assert_eq!(board.algebraic_to_internal("h8").unwrap(), 98);
+    }
+
+    #[test]
+    fn invalid_squares_are_rejected() {
+        let board = setup_game().board;
+
+        assert_eq!(board.algebraic_to_internal("i1"), None);
+        assert_eq!(board.algebraic_to_internal("a9"), None);
+        assert_eq!(board.algebraic_to_internal("e"), None);
+        assert_eq!(board.algebraic_to_internal("e44"), None);
+        assert_eq!(
+            board.algebraic_to_internal("e1"),
+            Some(directions::named_square("e1"))
+        );
+    }
+
+    #[test]
+    fn squares_off_the_internal_board_read_as_sentinels() {
+        let board = setup_game().board;
+
+        for square in [-21, -1, BOARD_SQUARES as i16, i16::MAX, i16::MIN] {
+            assert_eq!(board.get_piece_on_square(square), Piece::SentinelSquare);
+        }
+        // Knight jumps from the corners stay on the internal board
+        assert_eq!(board.get_piece_on_square(21 - 21), Piece::SentinelSquare);
+        assert_eq!(board.get_piece_on_square(98 + 21), Piece::SentinelSquare);
+        assert_eq!(board.get_piece_on_square(21), Piece::WhiteRook);
     }
 
     fn assert_board_states_equal(b1: &ChessBoard, b2: &ChessBoard, msg: &str) {
@@ -1158,6 +2827,128 @@ mod castling_tests {
         game
     }
 
+    /// Castling test with the squares of the standard start position
+    /// written out, as the board checked them before deriving the squares
+    /// from where the king and the rook start and land.
+    fn can_castle_reference(board: &ChessBoard, color: Color, kingside: bool) -> bool {
+        let rights = &board.castling_rights;
+        let (allowed, king, rook, between, path) = match (color, kingside) {
+            (Color::White, true) => (rights.white_kingside, 25, 28, &[26, 27][..], [25, 26, 27]),
+            (Color::White, false) => (
+                rights.white_queenside,
+                25,
+                21,
+                &[22, 23, 24][..],
+                [25, 24, 23],
+            ),
+            (Color::Black, true) => (rights.black_kingside, 95, 98, &[96, 97][..], [95, 96, 97]),
+            (Color::Black, false) => (
+                rights.black_queenside,
+                95,
+                91,
+                &[92, 93, 94][..],
+                [95, 94, 93],
+            ),
+        };
+        let (king_piece, rook_piece) = match color {
+            Color::White => (Piece::WhiteKing, Piece::WhiteRook),
+            Color::Black => (Piece::BlackKing, Piece::BlackRook),
+        };
+
+        allowed
+            && board.board_squares[king as usize] == king_piece
+            && board.board_squares[rook as usize] == rook_piece
+            && between
+                .iter()
+                .all(|&square| board.board_squares[square as usize] == Piece::EmptySquare)
+            && path.iter().all(|&square| {
+                !board
+                    .piece_list
+                    .is_square_attacked(board, square, color.opposite())
+            })
+    }
+
+    #[test]
+    fn test_castling_squares_match_the_start_position_squares() {
+        let blockers = ["b", "c", "d", "f", "g"];
+        let attacker_files = ["", "a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut allowed = 0;
+
+        for mask in 0..1 << blockers.len() {
+            for attacker_file in attacker_files {
+                // Knights block the back ranks, a rook of the other color
+                // attacks a file of each back rank
+                let mut game = setup_game_with_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+                for (index, file) in blockers.iter().enumerate() {
+                    if mask & (1 << index) != 0 {
+                        for (rank, knight) in [("1", Piece::WhiteKnight), ("8", Piece::BlackKnight)]
+                        {
+                            let square = game
+                                .board
+                                .algebraic_to_internal(&format!("{}{}", file, rank))
+                                .unwrap();
+                            game.board.set_piece_on_square(knight, square);
+                        }
+                    }
+                }
+                if !attacker_file.is_empty() {
+                    for (rank, rook) in [("4", Piece::BlackRook), ("5", Piece::WhiteRook)] {
+                        let square = game
+                            .board
+                            .algebraic_to_internal(&format!("{}{}", attacker_file, rank))
+                            .unwrap();
+                        game.board.set_piece_on_square(rook, square);
+                    }
+                }
+                let board = &mut game.board;
+                let pieces: [Piece; 64] = std::array::from_fn(|square| {
+                    board.get_piece_on_square(board.map_inner_to_outer_board(square as i16))
+                });
+                board.set_board(&pieces, Color::White);
+
+                for color in [Color::White, Color::Black] {
+                    let (king, kingside_rook, queenside_rook) = match color {
+                        Color::White => ("e1", "h1", "a1"),
+                        Color::Black => ("e8", "h8", "a8"),
+                    };
+                    let square = |name: &str| board.algebraic_to_internal(name).unwrap();
+
+                    allowed += usize::from(can_castle_reference(board, color, true))
+                        + usize::from(can_castle_reference(board, color, false));
+                    assert_eq!(
+                        board.can_castle_kingside(
+                            &board.piece_list,
+                            color,
+                            square(king),
+                            square(kingside_rook)
+                        ),
+                        can_castle_reference(board, color, true),
+                        "{:?} kingside, blockers {:b}, attacker on the {}-file",
+                        color,
+                        mask,
+                        attacker_file
+                    );
+                    assert_eq!(
+                        board.can_castle_queenside(
+                            &board.piece_list,
+                            color,
+                            square(king),
+                            square(queenside_rook)
+                        ),
+                        can_castle_reference(board, color, false),
+                        "{:?} queenside, blockers {:b}, attacker on the {}-file",
+                        color,
+                        mask,
+                        attacker_file
+                    );
+                }
+            }
+        }
+
+        // Castling is allowed in some of the positions only
+        assert!(allowed > 0 && allowed < 2 * 2 * 32 * 9);
+    }
+
     #[test]
     fn test_castling_move_execution() {
         let mut game = setup_game_with_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1");
@@ -1167,8 +2958,8 @@ mod castling_tests {
         game.board.print_board();
 
         // Verify board state after castling
-        let king_square = game.board.algebraic_to_internal("g1");
-        let rook_square = game.board.algebraic_to_internal("f1");
+        let king_square = game.board.algebraic_to_internal("g1").unwrap();
+        let rook_square = game.board.algebraic_to_internal("f1").unwrap();
 
         assert_eq!(
             game.board.get_piece_on_square(king_square),
@@ -1180,8 +2971,8 @@ mod castling_tests {
         );
 
         // Original squares should be empty
-        let original_king = game.board.algebraic_to_internal("e1");
-        let original_rook = game.board.algebraic_to_internal("h1");
+        let original_king = game.board.algebraic_to_internal("e1").unwrap();
+        let original_rook = game.board.algebraic_to_internal("h1").unwrap();
 
         assert_eq!(
             game.board.get_piece_on_square(original_king),
@@ -1223,10 +3014,10 @@ mod castling_tests {
         game.make_move("e8c8");
 
         // Verify final position
-        let white_king = game.board.algebraic_to_internal("g1");
-        let white_rook = game.board.algebraic_to_internal("f1");
-        let black_king = game.board.algebraic_to_internal("c8");
-        let black_rook = game.board.algebraic_to_internal("d8");
+        let white_king = game.board.algebraic_to_internal("g1").unwrap();
+        let white_rook = game.board.algebraic_to_internal("f1").unwrap();
+        let black_king = game.board.algebraic_to_internal("c8").unwrap();
+        let black_rook = game.board.algebraic_to_internal("d8").unwrap();
 
         assert_eq!(game.board.get_piece_on_square(white_king), Piece::WhiteKing);
         assert_eq!(game.board.get_piece_on_square(white_rook), Piece::WhiteRook);
@@ -1258,16 +3049,18 @@ mod can_castle_queenside_tests {
 
         // White should be able to castle queenside
         assert!(game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
 
         // Black should be able to castle queenside
         assert!(game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::Black,
-            game.board.algebraic_to_internal("e8"),
-            game.board.algebraic_to_internal("a8")
+            game.board.algebraic_to_internal("e8").unwrap(),
+            game.board.algebraic_to_internal("a8").unwrap()
         ));
     }
 
@@ -1279,9 +3072,10 @@ mod can_castle_queenside_tests {
         game.board.castling_rights.white_queenside = false;
 
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
     }
 
@@ -1293,9 +3087,10 @@ mod can_castle_queenside_tests {
         game.board.castling_rights.white_queenside = false;
 
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
     }
 
@@ -1305,9 +3100,10 @@ mod can_castle_queenside_tests {
 
         // Bishop on c1 blocks queenside castling
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
     }
 
@@ -1317,9 +3113,10 @@ mod can_castle_queenside_tests {
 
         // Black knight attacks d1, which king moves through
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
     }
 
@@ -1329,9 +3126,10 @@ mod can_castle_queenside_tests {
 
         // Black bishop attacks e1 (king is in check)
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
     }
 
@@ -1341,9 +3139,10 @@ mod can_castle_queenside_tests {
 
         // No rook on a1
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"),
-            game.board.algebraic_to_internal("a1")
+            game.board.algebraic_to_internal("e1").unwrap(),
+            game.board.algebraic_to_internal("a1").unwrap()
         ));
     }
 
@@ -1353,9 +3152,10 @@ mod can_castle_queenside_tests {
 
         // Black pieces on white squares shouldn't allow white to castle
         assert!(!game.board.can_castle_queenside(
+            &game.board.piece_list,
             Color::White,
-            game.board.algebraic_to_internal("e1"), // white king
-            game.board.algebraic_to_internal("a8")  // black rook - WRONG ROOK!
+            game.board.algebraic_to_internal("e1").unwrap(), // white king
+            game.board.algebraic_to_internal("a8").unwrap()  // black rook - WRONG ROOK!
         ));
     }
 }
@@ -1363,7 +3163,7 @@ mod can_castle_queenside_tests {
 #[cfg(test)]
 mod zobrist_tests {
     use super::*;
-    use crate::GameState;
+    use crate::game_state::GameState;
 
     fn setup_game_with_fen(fen: &str) -> GameState {
         let mut game = GameState::new(None);
@@ -1413,19 +3213,18 @@ mod zobrist_tests {
 
         // Create a pawn move (e2 to e4)
         let mv = Move {
-            from: board.algebraic_to_internal("e2"),
-            to: board.algebraic_to_internal("e4"),
+            from: board.algebraic_to_internal("e2").unwrap(),
+            to: board.algebraic_to_internal("e4").unwrap(),
             piece: Piece::WhitePawn,
             captured_piece: Piece::EmptySquare,
             promotion: None,
             castling: None,
             en_passant: false,
-            en_passant_square: Some(board.algebraic_to_internal("e3")),
-            previous_en_passant: None,
-            previous_castling_rights: Some(board.castling_rights),
+            en_passant_square: Some(board.algebraic_to_internal("e3").unwrap()),
         };
 
-        board.update_hash(&mv);
+        let castling_rights = board.castling_rights;
+        board.update_hash(&mv, None, &castling_rights);
         let after_move_hash = board.hash;
 
         assert_ne!(
@@ -1434,7 +3233,7 @@ mod zobrist_tests {
         );
 
         // Test unmake
-        board.update_hash(&mv);
+        board.update_hash(&mv, None, &castling_rights);
         assert_eq!(initial_hash, board.hash, "Hash should restore after unmake");
     }
 
@@ -1605,7 +3404,7 @@ mod zobrist_tests {
         let initial_hash = board.hash;
 
         // Test that setting en passant target file affects hash
-        let target_square = board.algebraic_to_internal("e3");
+        let target_square = board.algebraic_to_internal("e3").unwrap();
         let file = board.square_file(target_square) - (board.board_width - 8) / 2;
 
         // XOR in the en passant file
@@ -1625,6 +3424,51 @@ mod zobrist_tests {
         );
     }
 
+    #[test]
+    fn test_transposed_move_orders_share_hash() {
+        let mut first =
+            setup_game_with_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut second =
+            setup_game_with_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        for mv in ["e2e4", "g8f6", "g1f3"] {
+            assert!(first.make_move(mv));
+        }
+        // The double push comes last and leaves an en passant target no
+        // black pawn can capture on
+        for mv in ["g1f3", "g8f6", "e2e4"] {
+            assert!(second.make_move(mv));
+        }
+
+        assert!(second.board.get_en_passant_target().is_some());
+        assert_eq!(first.board.hash, second.board.hash);
+    }
+
+    #[test]
+    fn test_capturable_en_passant_is_hashed() {
+        let mut game = setup_game_with_fen("4k3/8/8/8/3p4/8/4P3/4K3 w - - 0 1");
+        assert!(game.make_move("e2e4"));
+
+        let with_target = setup_game_with_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1");
+        let without_target = setup_game_with_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1");
+
+        assert_eq!(game.board.hash, with_target.board.hash);
+        assert_ne!(game.board.hash, without_target.board.hash);
+        assert_eq!(game.board.hash, game.board.zobrist_hash(Color::Black));
+
+        // Whatever the pawn plays, the en passant key is hashed out
+        for mv in ["d4e3", "d4d3", "e8d7"] {
+            let mut board = game.board.clone();
+            let mv = board.from_uci(mv).unwrap();
+            board.make_move(&mv);
+            assert_eq!(board.hash, board.zobrist_hash(Color::White));
+        }
+
+        let mut board = game.board.clone();
+        board.make_null_move();
+        assert_eq!(board.hash, board.zobrist_hash(Color::White));
+    }
+
     #[test]
     fn test_multiple_moves_hash_consistency() {
         let game = setup_game_with_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
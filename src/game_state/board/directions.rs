@@ -0,0 +1,330 @@
+//! Directions on the internal board and precomputed rays.
+//!
+//! The board is a mailbox of [`BOARD_WIDTH`] by [`BOARD_HEIGHT`] squares,
+//! with the 8x8 chess board in the middle and sentinel squares around it.
+//! Moving in a [`Direction`] adds its offset to the square index, so every
+//! direction is derived from the board width here instead of in each move
+//! generator.
+//!
+//! The squares a sliding piece goes through are computed once per square
+//! and direction, up to the first sentinel. Move generation and attack
+//! detection walk these rays and only have to look at the pieces on them.
+//! The squares reached by the king, the knight and the pawn captures are
+//! tabulated the same way, with the sentinels left out.
+
+use std::ops::{Add, AddAssign};
+use std::sync::LazyLock;
+
+use crate::game_state::board::piece::Color;
+
+/// Width of the internal board, sentinel files included.
+pub const BOARD_WIDTH: i16 = 10;
+
+/// Height of the internal board, sentinel ranks included.
+pub const BOARD_HEIGHT: i16 = 12;
+
+/// Number of squares of the internal board.
+pub const BOARD_SQUARES: usize = (BOARD_WIDTH * BOARD_HEIGHT) as usize;
+
+/// Sentinel files on each side of the chess board.
+const FILE_PADDING: i16 = (BOARD_WIDTH - 8) / 2;
+
+/// Sentinel ranks below and above the chess board.
+const RANK_PADDING: i16 = (BOARD_HEIGHT - 8) / 2;
+
+/// Step between two squares of the internal board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Direction(i16);
+
+impl Direction {
+    pub const NORTH: Direction = Direction::new(1, 0);
+    pub const SOUTH: Direction = Direction::new(-1, 0);
+    pub const EAST: Direction = Direction::new(0, 1);
+    pub const WEST: Direction = Direction::new(0, -1);
+    pub const NORTH_EAST: Direction = Direction::new(1, 1);
+    pub const NORTH_WEST: Direction = Direction::new(1, -1);
+    pub const SOUTH_EAST: Direction = Direction::new(-1, 1);
+    pub const SOUTH_WEST: Direction = Direction::new(-1, -1);
+
+    /// Creates the direction moving by a number of ranks and files.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranks` - Ranks moved up the board, negative to move down
+    /// * `files` - Files moved towards the h-file, negative towards the a-file
+    pub const fn new(ranks: i16, files: i16) -> Self {
+        Direction(ranks * BOARD_WIDTH + files)
+    }
+
+    /// Gets the direction a pawn of the given color moves forward.
+    pub const fn pawn_push(color: Color) -> Self {
+        match color {
+            Color::White => Direction::NORTH,
+            Color::Black => Direction::SOUTH,
+        }
+    }
+
+    /// Gets the difference of the square indices of a step.
+    pub const fn offset(self) -> i16 {
+        self.0
+    }
+
+    /// Gets the opposite direction.
+    pub const fn reverse(self) -> Self {
+        Direction(-self.0)
+    }
+
+    /// Checks if two directions follow the same line, either way.
+    pub fn is_parallel(self, other: Direction) -> bool {
+        self == other || self == other.reverse()
+    }
+
+    /// Checks if the direction follows a rank or a file.
+    pub fn is_orthogonal(self) -> bool {
+        ORTHOGONAL.contains(&self)
+    }
+
+    /// Checks if the direction follows a diagonal.
+    pub fn is_diagonal(self) -> bool {
+        DIAGONAL.contains(&self)
+    }
+
+    /// Position of a sliding direction in [`ALL`].
+    fn slide_index(self) -> usize {
+        match self {
+            Direction::WEST => 0,
+            Direction::EAST => 1,
+            Direction::NORTH => 2,
+            Direction::SOUTH => 3,
+            Direction::NORTH_EAST => 4,
+            Direction::SOUTH_EAST => 5,
+            Direction::NORTH_WEST => 6,
+            Direction::SOUTH_WEST => 7,
+            _ => panic!("{:?} is not a sliding direction", self),
+        }
+    }
+}
+
+impl Add for Direction {
+    type Output = Direction;
+
+    fn add(self, other: Direction) -> Direction {
+        Direction(self.0 + other.0)
+    }
+}
+
+impl Add<Direction> for i16 {
+    type Output = i16;
+
+    fn add(self, direction: Direction) -> i16 {
+        self + direction.0
+    }
+}
+
+impl AddAssign<Direction> for i16 {
+    fn add_assign(&mut self, direction: Direction) {
+        *self += direction.0;
+    }
+}
+
+/// Directions of the rook.
+pub const ORTHOGONAL: [Direction; 4] = [
+    Direction::EAST,
+    Direction::WEST,
+    Direction::SOUTH,
+    Direction::NORTH,
+];
+
+/// Directions of the bishop.
+pub const DIAGONAL: [Direction; 4] = [
+    Direction::NORTH_EAST,
+    Direction::NORTH_WEST,
+    Direction::SOUTH_EAST,
+    Direction::SOUTH_WEST,
+];
+
+/// Directions of the queen and the king, orthogonal ones first.
+pub const ALL: [Direction; 8] = [
+    Direction::WEST,
+    Direction::EAST,
+    Direction::NORTH,
+    Direction::SOUTH,
+    Direction::NORTH_EAST,
+    Direction::SOUTH_EAST,
+    Direction::NORTH_WEST,
+    Direction::SOUTH_WEST,
+];
+
+/// Jumps of the knight.
+pub const KNIGHT_JUMPS: [Direction; 8] = [
+    Direction::new(2, 1),
+    Direction::new(2, -1),
+    Direction::new(-2, 1),
+    Direction::new(-2, -1),
+    Direction::new(1, 2),
+    Direction::new(1, -2),
+    Direction::new(-1, 2),
+    Direction::new(-1, -2),
+];
+
+/// Checks if a square of the internal board is on the chess board rather
+/// than a sentinel.
+pub const fn is_on_board(square: i16) -> bool {
+    let rank = square.div_euclid(BOARD_WIDTH);
+    let file = square.rem_euclid(BOARD_WIDTH);
+
+    rank >= RANK_PADDING
+        && rank < RANK_PADDING + 8
+        && file >= FILE_PADDING
+        && file < FILE_PADDING + 8
+}
+
+/// Gets the internal board square of a square name like `e4`.
+///
+/// Meant for the squares written in the code: evaluated in a constant, a
+/// misspelled name fails the build instead of giving a square off the board.
+///
+/// # Panics
+///
+/// If the name isn't a file from `a` to `h` followed by a rank from `1` to `8`
+pub const fn named_square(name: &str) -> i16 {
+    let bytes = name.as_bytes();
+    assert!(
+        bytes.len() == 2
+            && bytes[0] >= b'a'
+            && bytes[0] <= b'h'
+            && bytes[1] >= b'1'
+            && bytes[1] <= b'8',
+        "invalid square name"
+    );
+
+    let file = (bytes[0] - b'a') as i16;
+    let rank = (bytes[1] - b'1') as i16;
+    (rank + RANK_PADDING) * BOARD_WIDTH + file + FILE_PADDING
+}
+
+/// Squares reached from every square in every sliding direction.
+struct Rays {
+    /// Squares of each ray, closest first
+    squares: [[[i16; 7]; 8]; BOARD_SQUARES],
+    /// Number of squares of each ray
+    lengths: [[u8; 8]; BOARD_SQUARES],
+}
+
+impl Rays {
+    fn new() -> Self {
+        let mut rays = Rays {
+            squares: [[[0; 7]; 8]; BOARD_SQUARES],
+            lengths: [[0; 8]; BOARD_SQUARES],
+        };
+
+        for square in 0..BOARD_SQUARES as i16 {
+            if !is_on_board(square) {
+                continue;
+            }
+
+            for (index, direction) in ALL.into_iter().enumerate() {
+                let mut length = 0;
+                let mut current = square + direction;
+                while is_on_board(current) {
+                    rays.squares[square as usize][index][length] = current;
+                    length += 1;
+                    current += direction;
+                }
+                rays.lengths[square as usize][index] = length as u8;
+            }
+        }
+
+        rays
+    }
+}
+
+static RAYS: LazyLock<Rays> = LazyLock::new(Rays::new);
+
+/// Gets the squares from a square to the edge of the board in a direction.
+///
+/// # Arguments
+///
+/// * `square` - Internal board square the ray starts from, not included
+/// * `direction` - One of the directions in [`ALL`]
+///
+/// # Returns
+///
+/// Squares of the ray, closest first, empty from a sentinel square
+pub fn ray(square: i16, direction: Direction) -> &'static [i16] {
+    let index = direction.slide_index();
+    let length = RAYS.lengths[square as usize][index] as usize;
+
+    &RAYS.squares[square as usize][index][..length]
+}
+
+/// Squares reached in one step from every square, sentinels left out.
+struct Steps {
+    /// Squares reached from each square, in the order of the steps
+    squares: [[i16; 8]; BOARD_SQUARES],
+    /// Number of squares reached from each square
+    lengths: [u8; BOARD_SQUARES],
+}
+
+impl Steps {
+    fn new(steps: &[Direction]) -> Self {
+        let mut table = Steps {
+            squares: [[0; 8]; BOARD_SQUARES],
+            lengths: [0; BOARD_SQUARES],
+        };
+
+        for square in 0..BOARD_SQUARES as i16 {
+            if !is_on_board(square) {
+                continue;
+            }
+
+            let mut length = 0;
+            for &step in steps {
+                if is_on_board(square + step) {
+                    table.squares[square as usize][length] = square + step;
+                    length += 1;
+                }
+            }
+            table.lengths[square as usize] = length as u8;
+        }
+
+        table
+    }
+
+    fn from(&self, square: i16) -> &[i16] {
+        &self.squares[square as usize][..self.lengths[square as usize] as usize]
+    }
+}
+
+static KING_STEPS: LazyLock<Steps> = LazyLock::new(|| Steps::new(&ALL));
+
+static KNIGHT_STEPS: LazyLock<Steps> = LazyLock::new(|| Steps::new(&KNIGHT_JUMPS));
+
+static WHITE_PAWN_CAPTURES: LazyLock<Steps> =
+    LazyLock::new(|| Steps::new(&[Direction::NORTH_EAST, Direction::NORTH_WEST]));
+
+static BLACK_PAWN_CAPTURES: LazyLock<Steps> =
+    LazyLock::new(|| Steps::new(&[Direction::SOUTH_EAST, Direction::SOUTH_WEST]));
+
+/// Gets the squares a king attacks from a square.
+pub fn king_targets(square: i16) -> &'static [i16] {
+    KING_STEPS.from(square)
+}
+
+/// Gets the squares a knight attacks from a square.
+pub fn knight_targets(square: i16) -> &'static [i16] {
+    KNIGHT_STEPS.from(square)
+}
+
+/// Gets the squares a pawn attacks from a square.
+///
+/// # Arguments
+///
+/// * `color` - Color of the pawn
+/// * `square` - Internal board square of the pawn
+pub fn pawn_attacks(color: Color, square: i16) -> &'static [i16] {
+    match color {
+        Color::White => WHITE_PAWN_CAPTURES.from(square),
+        Color::Black => BLACK_PAWN_CAPTURES.from(square),
+    }
+}
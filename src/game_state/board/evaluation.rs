@@ -9,14 +9,45 @@
 //! and endgame values based on the amount of material remaining on the board.
 
 use crate::game_state::ChessBoard;
+use crate::game_state::Piece;
 use crate::game_state::board::Move;
+use crate::game_state::board::score::Score;
 
+use attacks::AttackMap;
+use params::EvalParams;
+
+pub mod attacks;
+pub mod endgame;
+pub mod kpk;
 pub mod material;
+pub mod nnue;
+pub mod params;
+pub mod passed_pawn;
 pub mod piece_square;
+pub mod space;
+pub mod trapped;
 
 /// Maximum possible phase value (all pieces present).
 pub const TOTAL_PHASE: i16 = 256;
 
+/// Bonus for the side to move of the handcrafted evaluation: it can improve
+/// its position before the opponent does, which a symmetric evaluation
+/// doesn't see.
+pub const TEMPO_BONUS: Score = Score::cp(15);
+
+/// Contribution of each non-pawn piece to the game phase. The starting
+/// material adds up to [`TOTAL_PHASE`].
+pub const PHASE_WEIGHTS: [(Piece, i16); 8] = [
+    (Piece::WhiteKnight, 12),
+    (Piece::BlackKnight, 12),
+    (Piece::WhiteBishop, 12),
+    (Piece::BlackBishop, 12),
+    (Piece::WhiteRook, 20),
+    (Piece::BlackRook, 20),
+    (Piece::WhiteQueen, 40),
+    (Piece::BlackQueen, 40),
+];
+
 /// Represents the current game phase as a value between 0 and [`TOTAL_PHASE`].
 ///
 /// 0 = pure endgame, [`TOTAL_PHASE`] = pure midgame.
@@ -41,7 +72,7 @@ impl GamePhase {
 
 /// A pair of midgame and endgame scores that can be interpolated
 /// based on the current game phase.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TaperedScore {
     pub mg: i16,
     pub eg: i16,
@@ -77,7 +108,7 @@ impl TaperedScore {
 /// [`HeuristicComponent`]s into a final score.
 pub trait Evaluator: Send + Sync {
     /// Evaluates the board from white's perspective.
-    fn evaluate(&self, board: &ChessBoard) -> i16;
+    fn evaluate(&self, board: &ChessBoard) -> Score;
 
     /// Incremental update after a move has been applied.
     ///
@@ -88,13 +119,23 @@ pub trait Evaluator: Send + Sync {
     /// * `board` - Board state after the move
     /// * `mv` - The move that was just applied
     /// * `prev_score` - Evaluation score before the move
-    fn evaluate_incremental(&self, board: &ChessBoard, _mv: &Move, _prev_score: i16) -> i16 {
+    fn evaluate_incremental(&self, board: &ChessBoard, _mv: &Move, _prev_score: Score) -> Score {
         self.evaluate(board)
     }
+
+    /// Splits the evaluation into named terms, from white's perspective.
+    ///
+    /// Default implementation reports the whole evaluation as one term.
+    fn breakdown(&self, board: &ChessBoard) -> Vec<(&'static str, Score)> {
+        vec![("Evaluation", self.evaluate(board))]
+    }
 }
 
 /// A single heuristic component contributing to the evaluation.
 pub trait HeuristicComponent: Send + Sync {
+    /// Name of the component in evaluation breakdowns.
+    fn name(&self) -> &'static str;
+
     /// Returns the component's score from white's perspective.
     ///
     /// # Arguments
@@ -103,6 +144,27 @@ pub trait HeuristicComponent: Send + Sync {
     /// * `phase` - Current game phase for tapered interpolation
     fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16;
 
+    /// Whether the component reads the attack map, which the aggregator
+    /// then builds once for all the components that do.
+    fn uses_attacks(&self) -> bool {
+        false
+    }
+
+    /// Returns the component's score from white's perspective, given the
+    /// attack map of the board.
+    ///
+    /// Only called when [`uses_attacks`](Self::uses_attacks) is true;
+    /// defaults to `score()`.
+    #[allow(unused_variables)]
+    fn score_with_attacks(
+        &self,
+        board: &ChessBoard,
+        phase: &GamePhase,
+        attacks: &AttackMap,
+    ) -> i16 {
+        self.score(board, phase)
+    }
+
     /// Returns the incremental delta for this component after a move,
     /// or `None` if incremental update is not supported.
     ///
@@ -113,93 +175,134 @@ pub trait HeuristicComponent: Send + Sync {
     }
 }
 
+/// Optional terms of the handcrafted evaluation, all enabled by default.
+///
+/// They can be switched off to measure what each one is worth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvaluationTerms {
+    /// Space behind the pawns, see [`space::SpaceHeuristic`]
+    pub space: bool,
+    /// Center control in the opening, see [`space::CenterControlHeuristic`]
+    pub center_control: bool,
+    /// Attacks on the king zone, see [`attacks::KingTropismHeuristic`]
+    pub king_tropism: bool,
+    /// Weak squares, see [`attacks::WeakSquaresHeuristic`]
+    pub weak_squares: bool,
+}
+
+impl Default for EvaluationTerms {
+    fn default() -> Self {
+        EvaluationTerms {
+            space: true,
+            center_control: true,
+            king_tropism: true,
+            weak_squares: true,
+        }
+    }
+}
+
 /// Aggregates multiple [`HeuristicComponent`]s into a single evaluation.
 ///
 /// Iterates through components, summing their contributions. The game
-/// phase is computed once and shared across all components.
+/// phase is computed once with [`ChessBoard::game_phase`] and shared across
+/// all components, and so is the [`AttackMap`] when a component uses it.
 pub struct CompositeEvaluator {
     components: Vec<Box<dyn HeuristicComponent>>,
+    /// Whether a component reads the attack map
+    uses_attacks: bool,
 }
 
 impl CompositeEvaluator {
     /// Creates a new composite evaluator from a list of heuristic components.
     pub fn new(components: Vec<Box<dyn HeuristicComponent>>) -> Self {
-        Self { components }
-    }
-
-    /// Computes the game phase from the current board position.
-    fn compute_phase(&self, board: &ChessBoard) -> GamePhase {
-        let piece_list = &board.piece_list;
-        let mut phase = 0i16;
-
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::WhiteQueen)
-            .unwrap_or(0)
-            * 40;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::BlackQueen)
-            .unwrap_or(0)
-            * 40;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::WhiteRook)
-            .unwrap_or(0)
-            * 20;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::BlackRook)
-            .unwrap_or(0)
-            * 20;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::WhiteBishop)
-            .unwrap_or(0)
-            * 12;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::BlackBishop)
-            .unwrap_or(0)
-            * 12;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::WhiteKnight)
-            .unwrap_or(0)
-            * 12;
-        phase += piece_list
-            .get_number_of_pieces(crate::game_state::Piece::BlackKnight)
-            .unwrap_or(0)
-            * 12;
-
-        GamePhase::new(phase)
+        let uses_attacks = components.iter().any(|component| component.uses_attacks());
+        Self {
+            components,
+            uses_attacks,
+        }
+    }
+
+    /// Scores the components of a board, in order.
+    fn component_scores<'a>(
+        &'a self,
+        board: &'a ChessBoard,
+    ) -> impl Iterator<Item = (&'static str, Score)> + 'a {
+        let phase = board.game_phase();
+        let attacks = self.uses_attacks.then(|| AttackMap::new(board));
+
+        self.components.iter().map(move |component| {
+            let score = match &attacks {
+                Some(attacks) if component.uses_attacks() => {
+                    component.score_with_attacks(board, &phase, attacks)
+                }
+                _ => component.score(board, &phase),
+            };
+            (component.name(), Score::from(score))
+        })
+    }
+
+    /// Creates the standard evaluator with some of its optional terms.
+    ///
+    /// # Arguments
+    ///
+    /// * `terms` - Optional terms to include
+    pub fn with_terms(terms: EvaluationTerms) -> Self {
+        Self::with_params(terms, &EvalParams::default())
+    }
+
+    /// Creates the standard evaluator with its own weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `terms` - Optional terms to include
+    /// * `params` - Weights of the heuristics
+    pub fn with_params(terms: EvaluationTerms, params: &EvalParams) -> Self {
+        let mut components: Vec<Box<dyn HeuristicComponent>> = vec![
+            Box::new(material::MaterialHeuristic::new(params)),
+            Box::new(piece_square::PieceSquareHeuristic::new(params)),
+            Box::new(passed_pawn::UnstoppablePasserHeuristic::new(params)),
+            Box::new(trapped::TrappedPiecesHeuristic::new(params)),
+        ];
+        if terms.space {
+            components.push(Box::new(space::SpaceHeuristic::new(params)));
+        }
+        if terms.center_control {
+            components.push(Box::new(space::CenterControlHeuristic::new(params)));
+        }
+        if terms.king_tropism {
+            components.push(Box::new(attacks::KingTropismHeuristic::new(params)));
+        }
+        if terms.weak_squares {
+            components.push(Box::new(attacks::WeakSquaresHeuristic::new(params)));
+        }
+
+        Self::new(components)
     }
 }
 
 impl Default for CompositeEvaluator {
     /// Creates the default evaluator with standard heuristics:
-    /// material counting and piece-square tables (PesTO).
+    /// material counting, piece-square tables (PesTO), unstoppable
+    /// passed pawns, trapped pieces, space, center control, king tropism
+    /// and weak squares.
     fn default() -> Self {
-        Self {
-            components: vec![
-                Box::new(material::MaterialHeuristic),
-                Box::new(piece_square::PieceSquareHeuristic),
-            ],
-        }
+        Self::with_terms(EvaluationTerms::default())
     }
 }
 
 impl Evaluator for CompositeEvaluator {
-    fn evaluate(&self, board: &ChessBoard) -> i16 {
-        let phase = self.compute_phase(board);
-        let mut total = 0i16;
-
-        for component in &self.components {
-            total += component.score(board, &phase);
-        }
-
-        total
+    fn evaluate(&self, board: &ChessBoard) -> Score {
+        // Components are summed as scores, so large terms can't overflow
+        self.component_scores(board)
+            .fold(Score::ZERO, |total, (_, score)| total + score)
     }
 
-    fn evaluate_incremental(&self, board: &ChessBoard, mv: &Move, prev_score: i16) -> i16 {
+    fn evaluate_incremental(&self, board: &ChessBoard, mv: &Move, prev_score: Score) -> Score {
         let mut total = prev_score;
 
         for component in &self.components {
             if let Some(delta) = component.delta(board, mv) {
-                total += delta;
+                total += Score::from(delta);
             } else {
                 total = self.evaluate(board);
                 break;
@@ -208,4 +311,8 @@ impl Evaluator for CompositeEvaluator {
 
         total
     }
+
+    fn breakdown(&self, board: &ChessBoard) -> Vec<(&'static str, Score)> {
+        self.component_scores(board).collect()
+    }
 }
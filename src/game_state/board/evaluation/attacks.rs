@@ -0,0 +1,257 @@
+//! Attack maps, king tropism and weak squares.
+//!
+//! The attack map counts, for each square, how many pieces of each side
+//! attack it. It is built once per evaluation and shared by the components
+//! that read it, see [`HeuristicComponent::uses_attacks`].
+//!
+//! King tropism measures how the enemy pieces converge on the king: each
+//! attack on the king zone, the king square and its neighbours, is
+//! penalized. It only matters with pieces on the board, so it fades out
+//! with the game phase.
+//!
+//! A weak square is a square of a side's own half its pawns don't cover
+//! and the enemy attacks more often than the side defends it, a post for
+//! the enemy pieces.
+//!
+//! Squares are standard squares (0 = a1, 63 = h8).
+
+use crate::game_state::ChessBoard;
+use crate::game_state::Color;
+use crate::game_state::PieceType;
+use crate::game_state::board::directions::{self, DIAGONAL, Direction, ORTHOGONAL};
+
+use super::params::EvalParams;
+use super::{GamePhase, HeuristicComponent, TaperedScore};
+
+/// Ranks 1 to 4, White's half of the board.
+const WHITE_HALF: u64 = 0x0000_0000_ffff_ffff;
+
+/// Ranks 5 to 8, Black's half of the board.
+const BLACK_HALF: u64 = !WHITE_HALF;
+
+/// Penalty for each attack on the king zone.
+pub(super) const KING_ZONE_ATTACK: TaperedScore = TaperedScore::new(8, 0);
+
+/// Penalty for each weak square.
+pub(super) const WEAK_SQUARE: TaperedScore = TaperedScore::new(4, 2);
+
+/// Number of pieces of each side attacking each square.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttackMap {
+    /// Attack counts by color and square, White's first
+    counts: [[u8; 64]; 2],
+    /// Squares attacked by the pawns of each side, White's first
+    pawn_attacks: [u64; 2],
+}
+
+impl AttackMap {
+    /// Counts the attacks of the pieces of a board.
+    ///
+    /// A sliding piece attacks the squares of its rays up to the first
+    /// occupied one, included. Pieces lined up behind each other aren't
+    /// seen through.
+    pub fn new(board: &ChessBoard) -> Self {
+        let mut map = AttackMap {
+            counts: [[0; 64]; 2],
+            pawn_attacks: [0; 2],
+        };
+
+        board.piece_list.for_each_piece(|piece, square| {
+            let color = piece.get_color();
+            let counts = &mut map.counts[color as usize];
+            let mut mark = |target: i16| counts[board.map_to_standard_chess_board(target)] += 1;
+            let mut slide = |piece_directions: &[Direction]| {
+                for &direction in piece_directions {
+                    for &target in directions::ray(square, direction) {
+                        mark(target);
+                        if !board.get_piece_on_square(target).is_empty() {
+                            break;
+                        }
+                    }
+                }
+            };
+
+            match piece.get_type() {
+                PieceType::Pawn => {
+                    for &target in directions::pawn_attacks(color, square) {
+                        mark(target);
+                        map.pawn_attacks[color as usize] |=
+                            1 << board.map_to_standard_chess_board(target);
+                    }
+                }
+                PieceType::Knight => directions::knight_targets(square)
+                    .iter()
+                    .for_each(|&target| mark(target)),
+                PieceType::King => directions::king_targets(square)
+                    .iter()
+                    .for_each(|&target| mark(target)),
+                PieceType::Bishop => slide(&DIAGONAL),
+                PieceType::Rook => slide(&ORTHOGONAL),
+                PieceType::Queen => slide(&directions::ALL),
+            }
+        });
+        map
+    }
+
+    /// Gets the number of pieces of a side attacking a square.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side of the attacking pieces
+    /// * `square` - Standard square, 0 being a1 and 63 being h8
+    pub fn count(&self, color: Color, square: usize) -> u8 {
+        self.counts[color as usize][square]
+    }
+
+    /// Gets the squares attacked by a side, bit 0 being a1 and bit 63 being
+    /// h8.
+    pub fn attacked(&self, color: Color) -> u64 {
+        self.counts[color as usize]
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .fold(0, |attacked, (square, _)| attacked | 1 << square)
+    }
+
+    /// Gets the squares attacked by the pawns of a side.
+    pub fn pawn_attacks(&self, color: Color) -> u64 {
+        self.pawn_attacks[color as usize]
+    }
+
+    /// Draws the attack counts of a side, rank 8 first, with a dot for the
+    /// squares it doesn't attack.
+    ///
+    /// # Returns
+    ///
+    /// Eight lines of eight counts each, without a trailing newline
+    pub fn diagram(&self, color: Color) -> String {
+        (0..8)
+            .rev()
+            .map(|rank| {
+                (0..8)
+                    .map(|file| match self.count(color, rank * 8 + file) {
+                        0 => ".".to_string(),
+                        count => count.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Heuristic component that penalizes the attacks on the king zone.
+pub struct KingTropismHeuristic {
+    zone_attack: TaperedScore,
+}
+
+impl KingTropismHeuristic {
+    /// Creates the heuristic with the weight of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            zone_attack: params.king_zone_attack,
+        }
+    }
+
+    /// Counts the enemy attacks on the squares around the king of a side.
+    fn zone_attacks(board: &ChessBoard, attacks: &AttackMap, color: Color) -> i16 {
+        let Some(king) = board.piece_list.get_king_square(color) else {
+            return 0;
+        };
+
+        std::iter::once(king)
+            .chain(directions::king_targets(king).iter().copied())
+            .map(|square| {
+                attacks.count(color.opposite(), board.map_to_standard_chess_board(square))
+            })
+            .map(i16::from)
+            .sum()
+    }
+}
+
+impl HeuristicComponent for KingTropismHeuristic {
+    fn name(&self) -> &'static str {
+        "King tropism"
+    }
+
+    fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
+        self.score_with_attacks(board, phase, &AttackMap::new(board))
+    }
+
+    fn uses_attacks(&self) -> bool {
+        true
+    }
+
+    fn score_with_attacks(
+        &self,
+        board: &ChessBoard,
+        phase: &GamePhase,
+        attacks: &AttackMap,
+    ) -> i16 {
+        let white = Self::zone_attacks(board, attacks, Color::White);
+        let black = Self::zone_attacks(board, attacks, Color::Black);
+
+        let penalty = black - white;
+        TaperedScore::new(self.zone_attack.mg * penalty, self.zone_attack.eg * penalty)
+            .interpolate(phase)
+    }
+}
+
+/// Heuristic component that penalizes the weak squares of each side.
+pub struct WeakSquaresHeuristic {
+    weak_square: TaperedScore,
+}
+
+impl WeakSquaresHeuristic {
+    /// Creates the heuristic with the weight of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            weak_square: params.weak_square,
+        }
+    }
+
+    /// Counts the weak squares in the half of a side.
+    fn weak_squares(attacks: &AttackMap, color: Color) -> i16 {
+        let half = match color {
+            Color::White => WHITE_HALF,
+            Color::Black => BLACK_HALF,
+        };
+        let candidates = half & attacks.attacked(color.opposite()) & !attacks.pawn_attacks(color);
+
+        (0..64)
+            .filter(|&square| candidates & 1 << square != 0)
+            .filter(|&square| {
+                attacks.count(color.opposite(), square) > attacks.count(color, square)
+            })
+            .count() as i16
+    }
+}
+
+impl HeuristicComponent for WeakSquaresHeuristic {
+    fn name(&self) -> &'static str {
+        "Weak squares"
+    }
+
+    fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
+        self.score_with_attacks(board, phase, &AttackMap::new(board))
+    }
+
+    fn uses_attacks(&self) -> bool {
+        true
+    }
+
+    fn score_with_attacks(
+        &self,
+        _board: &ChessBoard,
+        phase: &GamePhase,
+        attacks: &AttackMap,
+    ) -> i16 {
+        let white = Self::weak_squares(attacks, Color::White);
+        let black = Self::weak_squares(attacks, Color::Black);
+
+        let penalty = black - white;
+        TaperedScore::new(self.weak_square.mg * penalty, self.weak_square.eg * penalty)
+            .interpolate(phase)
+    }
+}
@@ -0,0 +1,328 @@
+//! Specialized evaluation of known endgames.
+//!
+//! Some endings are won by technique rather than by material: with a queen, a
+//! rook or a bishop and a knight against a bare king, the generic evaluation
+//! sees a stable advantage but gives the search no idea how to deliver mate.
+//! Others are decided exactly, like king and pawn against king with the
+//! [`kpk`](super::kpk) bitbase. The endgames are recognized by their
+//! [`MaterialSignature`], looked up in a table of recognizers, and their
+//! evaluation replaces the generic one.
+
+use crate::game_state::ChessBoard;
+use crate::game_state::Color;
+use crate::game_state::Piece;
+use crate::game_state::PieceType;
+use crate::game_state::board::rules::Outcome;
+use crate::game_state::board::score::Score;
+
+use super::kpk;
+use super::material::values;
+
+/// Score of a position won by known technique, above any material balance
+/// the generic evaluation can reach but below the mate scores. The material
+/// of the strong side is added, so the search still prefers the easier wins,
+/// e.g. promoting to a queen rather than to a rook.
+pub const KNOWN_WIN: Score = Score::cp(10_000);
+
+/// Number of pieces of each kind on the board, packed four bits per piece in
+/// the order of the [`Piece`] discriminants.
+///
+/// The signature is kept up to date by the piece lists as pieces are added
+/// and removed, so recognizing an endgame is a single comparison.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MaterialSignature(u64);
+
+impl MaterialSignature {
+    /// Bits used by the count of each piece.
+    const BITS_PER_PIECE: u32 = 4;
+
+    /// Builds the signature of an endgame from its usual name.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Pieces of the strong side then of the weak side, each
+    ///   starting with its king, e.g. `KBNK`
+    /// * `strong` - Color of the side listed first
+    pub const fn from_code(code: &str, strong: Color) -> Self {
+        let bytes = code.as_bytes();
+        let mut signature = 0;
+        let mut color_offset = match strong {
+            Color::White => 0,
+            Color::Black => 6,
+        };
+
+        let mut index = 0;
+        while index < bytes.len() {
+            let kind = match bytes[index] {
+                b'P' => 0,
+                b'N' => 1,
+                b'B' => 2,
+                b'R' => 3,
+                b'Q' => 4,
+                b'K' => 5,
+                _ => panic!("invalid piece in endgame code"),
+            };
+            // The second king starts the pieces of the weak side
+            if kind == 5 && index > 0 {
+                color_offset = 6 - color_offset;
+            }
+            signature += 1 << ((color_offset + kind) * Self::BITS_PER_PIECE);
+            index += 1;
+        }
+
+        MaterialSignature(signature)
+    }
+
+    /// Counts one more piece.
+    pub fn add(&mut self, piece: Piece) {
+        self.0 += 1 << Self::shift(piece);
+    }
+
+    /// Counts one piece less.
+    pub fn remove(&mut self, piece: Piece) {
+        self.0 -= 1 << Self::shift(piece);
+    }
+
+    /// Gets the number of pieces of a kind.
+    pub fn count(self, piece: Piece) -> u32 {
+        ((self.0 >> Self::shift(piece)) & 0xF) as u32
+    }
+
+    fn shift(piece: Piece) -> u32 {
+        piece as u32 * Self::BITS_PER_PIECE
+    }
+}
+
+/// Evaluation of an endgame from the point of view of its strong side, given
+/// the side to move when it is known. None to fall back to the generic
+/// evaluation.
+type EndgameEvaluation = fn(&ChessBoard, Color, Option<Color>) -> Option<Score>;
+
+/// An endgame with the evaluation replacing the generic one.
+struct Recognizer {
+    /// Material of the endgame
+    signature: MaterialSignature,
+    /// Side playing for the win
+    strong: Color,
+    /// Evaluation of the endgame
+    evaluate: EndgameEvaluation,
+}
+
+impl Recognizer {
+    const fn new(code: &str, strong: Color, evaluate: EndgameEvaluation) -> Self {
+        Recognizer {
+            signature: MaterialSignature::from_code(code, strong),
+            strong,
+            evaluate,
+        }
+    }
+}
+
+/// Recognized endgames, for both colors.
+static RECOGNIZERS: [Recognizer; 8] = [
+    Recognizer::new("KQK", Color::White, evaluate_kqk),
+    Recognizer::new("KQK", Color::Black, evaluate_kqk),
+    Recognizer::new("KRK", Color::White, evaluate_krk),
+    Recognizer::new("KRK", Color::Black, evaluate_krk),
+    Recognizer::new("KBNK", Color::White, evaluate_kbnk),
+    Recognizer::new("KBNK", Color::Black, evaluate_kbnk),
+    Recognizer::new("KPK", Color::White, evaluate_kpk),
+    Recognizer::new("KPK", Color::Black, evaluate_kpk),
+];
+
+/// Evaluates the position with a specialized endgame evaluation.
+///
+/// # Arguments
+///
+/// * `board` - Board to evaluate
+/// * `side_to_move` - Side to move, if known
+///
+/// # Returns
+///
+/// Score from White's perspective, or `None` if the material on the board is
+/// not a recognized endgame
+pub fn evaluate(board: &ChessBoard, side_to_move: Option<Color>) -> Option<Score> {
+    let signature = board.material_signature();
+
+    let recognizer = RECOGNIZERS
+        .iter()
+        .find(|recognizer| recognizer.signature == signature)?;
+
+    (recognizer.evaluate)(board, recognizer.strong, side_to_move)
+        .map(|score| score.relative_to(recognizer.strong))
+}
+
+/// Queen against a bare king, see [`drive_to_edge`].
+fn evaluate_kqk(board: &ChessBoard, strong: Color, _: Option<Color>) -> Option<Score> {
+    Some(drive_to_edge(board, strong) + Score::from(values::QUEEN_EG))
+}
+
+/// Rook against a bare king, see [`drive_to_edge`].
+fn evaluate_krk(board: &ChessBoard, strong: Color, _: Option<Color>) -> Option<Score> {
+    Some(drive_to_edge(board, strong) + Score::from(values::ROOK_EG))
+}
+
+/// Mate with a major piece: drives the weak king to the edge, where it can
+/// be mated, and brings the strong king closer to help.
+fn drive_to_edge(board: &ChessBoard, strong: Color) -> Score {
+    let (strong_king, weak_king) = king_squares(board, strong);
+
+    KNOWN_WIN
+        + Score::cp(20 * center_distance(weak_king))
+        + Score::cp(10 * (7 - distance(strong_king, weak_king)))
+}
+
+/// Bishop and knight against a bare king: mate is only possible in a corner
+/// of the bishop's color, so the weak king is driven there.
+fn evaluate_kbnk(board: &ChessBoard, strong: Color, _: Option<Color>) -> Option<Score> {
+    let (strong_king, weak_king) = king_squares(board, strong);
+
+    let mut bishop = 0;
+    board.piece_list.for_each_piece(|piece, square| {
+        if piece.get_type() == PieceType::Bishop {
+            bishop = board.map_to_standard_chess_board(square);
+        }
+    });
+    // a1 and h8 are dark, a8 and h1 are light
+    let corners = if is_light_square(bishop) {
+        [56, 7]
+    } else {
+        [0, 63]
+    };
+    let corner_distance = corners
+        .iter()
+        .map(|&corner| distance(weak_king, corner))
+        .min()
+        .unwrap_or(0);
+
+    Some(
+        KNOWN_WIN
+            + Score::from(values::BISHOP_EG + values::KNIGHT_EG)
+            + Score::cp(40 * (7 - corner_distance))
+            + Score::cp(10 * (7 - distance(strong_king, weak_king))),
+    )
+}
+
+/// King and pawn against king, looked up in the bitbase.
+///
+/// The position is probed with the side to move. Without one, it is only
+/// scored when its result is the same whoever moves. A won position gets a
+/// bonus for the advance of the pawn, so the search pushes it to promotion.
+fn evaluate_kpk(board: &ChessBoard, strong: Color, side_to_move: Option<Color>) -> Option<Score> {
+    let wins = match side_to_move {
+        Some(side_to_move) => probe_kpk(board, strong, side_to_move)?,
+        None => {
+            // A side to move that would leave a king in check is skipped
+            let mut results = [strong, strong.opposite()]
+                .into_iter()
+                .filter_map(|side_to_move| probe_kpk(board, strong, side_to_move));
+            let first = results.next()?;
+            if results.any(|result| result != first) {
+                return None;
+            }
+            first
+        }
+    };
+
+    if !wins {
+        return Some(Score::DRAW);
+    }
+
+    let pawn = pawn_square(board);
+    let advance = match strong {
+        Color::White => pawn / 8,
+        Color::Black => 7 - pawn / 8,
+    } as i32;
+    Some(KNOWN_WIN + Score::from(values::PAWN_EG) + Score::cp(10 * advance))
+}
+
+/// Looks up a king and pawn against king position in the bitbase.
+///
+/// # Returns
+///
+/// Whether the side with the pawn wins, `None` if the position is illegal
+/// with this side to move
+fn probe_kpk(board: &ChessBoard, strong: Color, side_to_move: Color) -> Option<bool> {
+    let (strong_king, weak_king) = king_squares(board, strong);
+
+    kpk::probe(
+        strong,
+        strong_king,
+        pawn_square(board),
+        weak_king,
+        side_to_move,
+    )
+}
+
+/// Gets the exact result of an endgame covered by a bitbase.
+///
+/// Bare kings are a draw and king and pawn against king is looked up in the
+/// [`kpk`] bitbase. Other endgames, even won ones like [`evaluate_kqk`], are
+/// only evaluated and not covered.
+///
+/// # Arguments
+///
+/// * `board` - Board of the position
+/// * `side_to_move` - Side to move
+///
+/// # Returns
+///
+/// Result for the side to move, or `None` if the endgame isn't covered
+pub fn probe(board: &ChessBoard, side_to_move: Color) -> Option<Outcome> {
+    let signature = board.material_signature();
+    if signature == MaterialSignature::from_code("KK", Color::White) {
+        return Some(Outcome::Draw);
+    }
+
+    let strong = [Color::White, Color::Black]
+        .into_iter()
+        .find(|&strong| signature == MaterialSignature::from_code("KPK", strong))?;
+
+    Some(match probe_kpk(board, strong, side_to_move)? {
+        false => Outcome::Draw,
+        true if strong == side_to_move => Outcome::Win,
+        true => Outcome::Loss,
+    })
+}
+
+/// Gets the standard square of the pawn, the last one found if there are
+/// several.
+fn pawn_square(board: &ChessBoard) -> usize {
+    let mut pawn = 0;
+    board.piece_list.for_each_piece(|piece, square| {
+        if piece.get_type() == PieceType::Pawn {
+            pawn = board.map_to_standard_chess_board(square);
+        }
+    });
+    pawn
+}
+
+/// Gets the standard squares of the strong and the weak king.
+fn king_squares(board: &ChessBoard, strong: Color) -> (usize, usize) {
+    let king = |color| {
+        board
+            .piece_list
+            .get_king_square(color)
+            .map_or(0, |square| board.map_to_standard_chess_board(square))
+    };
+
+    (king(strong), king(strong.opposite()))
+}
+
+/// Number of king moves between two standard squares.
+fn distance(from: usize, to: usize) -> i32 {
+    let ranks = (from / 8).abs_diff(to / 8);
+    let files = (from % 8).abs_diff(to % 8);
+    ranks.max(files) as i32
+}
+
+/// Distance of a standard square from the four central squares, 0 in the
+/// center and 6 in the corners.
+fn center_distance(square: usize) -> i32 {
+    let from_center = |line: usize| if line < 4 { 3 - line } else { line - 4 };
+    (from_center(square / 8) + from_center(square % 8)) as i32
+}
+
+fn is_light_square(square: usize) -> bool {
+    (square / 8 + square % 8) % 2 == 1
+}
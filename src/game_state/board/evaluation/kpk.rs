@@ -0,0 +1,290 @@
+//! King and pawn against king bitbase.
+//!
+//! Every king and pawn against king position is classified as won or drawn
+//! by retrograde analysis, which takes about a tenth of a second in release
+//! builds. The engine does it in the background at startup with [`init`],
+//! otherwise it is done the first time the bitbase is probed.
+//!
+//! Positions are stored from the point of view of the side with the pawn,
+//! playing White, with the pawn on the queen side; the other positions are
+//! mirrored onto them.
+//!
+//! The classification starts from the positions whose result is obvious:
+//! the pawn promotes safely, the defending king captures the pawn or is
+//! stalemated. It is then propagated backward until nothing changes: the
+//! attacker wins if one of its moves wins, the defender draws if one of its
+//! moves draws. Positions still undecided at the end are draws.
+
+use std::sync::OnceLock;
+
+use crate::game_state::Color;
+
+/// Number of positions: side to move, pawn on files a to d and ranks 2 to
+/// 7, and both kings anywhere.
+const POSITIONS: usize = 2 * 24 * 64 * 64;
+
+/// Result of a position during the classification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Classification {
+    /// Not a legal position
+    Invalid,
+    /// Not decided yet
+    Unknown,
+    Draw,
+    Win,
+}
+
+/// Position with the attacker playing White and the pawn on files a to d.
+#[derive(Clone, Copy)]
+struct Position {
+    white_to_move: bool,
+    white_king: usize,
+    black_king: usize,
+    pawn: usize,
+}
+
+impl Position {
+    fn index(self) -> usize {
+        let pawn = (self.pawn / 8 - 1) * 4 + self.pawn % 8;
+        ((pawn * 64 + self.white_king) * 64 + self.black_king) * 2 + self.white_to_move as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        let pawn = index / (2 * 64 * 64);
+        Position {
+            white_to_move: index % 2 == 1,
+            black_king: index / 2 % 64,
+            white_king: index / (2 * 64) % 64,
+            pawn: (pawn / 4 + 1) * 8 + pawn % 4,
+        }
+    }
+
+    /// Whether the position can't happen: the pawn on the first or last
+    /// rank, pieces on the same square, kings touching, or the defending
+    /// king in check with the attacker to move.
+    fn is_invalid(self) -> bool {
+        !(8..56).contains(&self.pawn)
+            || self.white_king == self.black_king
+            || self.white_king == self.pawn
+            || self.black_king == self.pawn
+            || distance(self.white_king, self.black_king) <= 1
+            || (self.white_to_move && pawn_attacks(self.pawn, self.black_king))
+    }
+
+    /// Classifies the positions decided without looking at the moves.
+    fn initial_classification(self) -> Classification {
+        if self.is_invalid() {
+            return Classification::Invalid;
+        }
+
+        if self.white_to_move {
+            // The pawn promotes and the new queen can't be taken
+            let promotion = self.pawn + 8;
+            if self.pawn / 8 == 6
+                && self.white_king != promotion
+                && self.black_king != promotion
+                && (distance(self.black_king, promotion) > 1
+                    || distance(self.white_king, promotion) == 1)
+            {
+                return Classification::Win;
+            }
+        } else {
+            let stalemate = king_moves(self.black_king).all(|square| {
+                distance(square, self.white_king) <= 1 || pawn_attacks(self.pawn, square)
+            });
+            let takes_pawn = distance(self.black_king, self.pawn) == 1
+                && distance(self.white_king, self.pawn) > 1;
+            if stalemate || takes_pawn {
+                return Classification::Draw;
+            }
+        }
+
+        Classification::Unknown
+    }
+
+    /// Classifies the position from the classification of the positions
+    /// reached by its moves.
+    fn classify(self, classifications: &[Classification]) -> Classification {
+        let successors = self
+            .successors()
+            .into_iter()
+            .map(|successor| classifications[successor.index()])
+            .filter(|&classification| classification != Classification::Invalid);
+
+        let (good, bad) = if self.white_to_move {
+            (Classification::Win, Classification::Draw)
+        } else {
+            (Classification::Draw, Classification::Win)
+        };
+
+        let mut all_bad = true;
+        for classification in successors {
+            if classification == good {
+                return good;
+            }
+            all_bad &= classification == bad;
+        }
+
+        if all_bad {
+            bad
+        } else {
+            Classification::Unknown
+        }
+    }
+
+    /// Gets the positions reached by the moves of the side to move, illegal
+    /// ones included. Promotions are already classified and left out.
+    fn successors(self) -> Vec<Position> {
+        let mut successors = Vec::with_capacity(10);
+        let after = Position {
+            white_to_move: !self.white_to_move,
+            ..self
+        };
+
+        if !self.white_to_move {
+            for black_king in king_moves(self.black_king) {
+                successors.push(Position {
+                    black_king,
+                    ..after
+                });
+            }
+            return successors;
+        }
+
+        for white_king in king_moves(self.white_king) {
+            successors.push(Position {
+                white_king,
+                ..after
+            });
+        }
+
+        let is_free = |square| square != self.white_king && square != self.black_king;
+        let rank = self.pawn / 8;
+        if rank < 6 && is_free(self.pawn + 8) {
+            successors.push(Position {
+                pawn: self.pawn + 8,
+                ..after
+            });
+            if rank == 1 && is_free(self.pawn + 16) {
+                successors.push(Position {
+                    pawn: self.pawn + 16,
+                    ..after
+                });
+            }
+        }
+
+        successors
+    }
+}
+
+/// Won positions, one bit per position index.
+static BITBASE: OnceLock<Vec<u64>> = OnceLock::new();
+
+/// Classifies every position and keeps the won ones.
+fn generate() -> Vec<u64> {
+    let mut classifications: Vec<Classification> = (0..POSITIONS)
+        .map(|index| Position::from_index(index).initial_classification())
+        .collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for index in 0..POSITIONS {
+            if classifications[index] != Classification::Unknown {
+                continue;
+            }
+
+            let classification = Position::from_index(index).classify(&classifications);
+            if classification != Classification::Unknown {
+                classifications[index] = classification;
+                changed = true;
+            }
+        }
+    }
+
+    let mut bitbase = vec![0; POSITIONS / 64];
+    for (index, &classification) in classifications.iter().enumerate() {
+        if classification == Classification::Win {
+            bitbase[index / 64] |= 1 << (index % 64);
+        }
+    }
+    bitbase
+}
+
+/// Generates the bitbase if it wasn't generated yet.
+pub fn init() {
+    BITBASE.get_or_init(generate);
+}
+
+/// Looks up a king and pawn against king position.
+///
+/// # Arguments
+///
+/// * `strong` - Color of the side with the pawn
+/// * `strong_king` - Standard square of the king of the side with the pawn
+/// * `pawn` - Standard square of the pawn
+/// * `weak_king` - Standard square of the defending king
+/// * `side_to_move` - Side to move
+///
+/// # Returns
+///
+/// `Some(true)` if the side with the pawn wins, `Some(false)` if the
+/// position is a draw, `None` if the position is illegal with this side to
+/// move
+pub fn probe(
+    strong: Color,
+    strong_king: usize,
+    pawn: usize,
+    weak_king: usize,
+    side_to_move: Color,
+) -> Option<bool> {
+    // Black's pawn goes down the board: flip the ranks
+    let flip_ranks = if strong == Color::Black { 56 } else { 0 };
+    // Pawns on the king side are mirrored onto the queen side
+    let flip_files = if pawn % 8 >= 4 { 7 } else { 0 };
+    let normalize = |square: usize| square ^ flip_ranks ^ flip_files;
+
+    let position = Position {
+        white_to_move: side_to_move == strong,
+        white_king: normalize(strong_king),
+        black_king: normalize(weak_king),
+        pawn: normalize(pawn),
+    };
+    if position.is_invalid() {
+        return None;
+    }
+
+    let index = position.index();
+    let bitbase = BITBASE.get_or_init(generate);
+    Some(bitbase[index / 64] & (1 << (index % 64)) != 0)
+}
+
+/// Number of king moves between two standard squares.
+fn distance(from: usize, to: usize) -> usize {
+    (from / 8).abs_diff(to / 8).max((from % 8).abs_diff(to % 8))
+}
+
+/// Whether a white pawn attacks a square.
+fn pawn_attacks(pawn: usize, square: usize) -> bool {
+    square / 8 == pawn / 8 + 1 && (square % 8).abs_diff(pawn % 8) == 1
+}
+
+/// Gets the squares a king can step to from a square.
+fn king_moves(square: usize) -> impl Iterator<Item = usize> {
+    let (rank, file) = ((square / 8) as isize, (square % 8) as isize);
+
+    [
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, -1),
+        (0, 1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+    ]
+    .into_iter()
+    .map(move |(ranks, files)| (rank + ranks, file + files))
+    .filter(|&(rank, file)| (0..8).contains(&rank) && (0..8).contains(&file))
+    .map(|(rank, file)| (rank * 8 + file) as usize)
+}
@@ -5,23 +5,27 @@
 
 use crate::game_state::ChessBoard;
 use crate::game_state::Piece;
+use crate::game_state::PieceType;
 
-use super::{GamePhase, HeuristicComponent};
+use super::params::EvalParams;
+use super::{GamePhase, HeuristicComponent, TaperedScore};
 
 /// Piece values in centipawns for midgame and endgame.
-mod values {
-    pub const PAWN_MG: i16 = 100;
-    pub const PAWN_EG: i16 = 100;
-    pub const KNIGHT_MG: i16 = 300;
-    pub const KNIGHT_EG: i16 = 300;
-    pub const BISHOP_MG: i16 = 300;
-    pub const BISHOP_EG: i16 = 300;
-    pub const ROOK_MG: i16 = 500;
-    pub const ROOK_EG: i16 = 500;
-    pub const QUEEN_MG: i16 = 900;
-    pub const QUEEN_EG: i16 = 900;
-    pub const KING_MG: i16 = 20000;
-    pub const KING_EG: i16 = 20000;
+pub(crate) mod values {
+    use super::PieceType;
+
+    pub const PAWN_MG: i16 = PieceType::Pawn.value();
+    pub const PAWN_EG: i16 = PieceType::Pawn.value();
+    pub const KNIGHT_MG: i16 = PieceType::Knight.value();
+    pub const KNIGHT_EG: i16 = PieceType::Knight.value();
+    pub const BISHOP_MG: i16 = PieceType::Bishop.value();
+    pub const BISHOP_EG: i16 = PieceType::Bishop.value();
+    pub const ROOK_MG: i16 = PieceType::Rook.value();
+    pub const ROOK_EG: i16 = PieceType::Rook.value();
+    pub const QUEEN_MG: i16 = PieceType::Queen.value();
+    pub const QUEEN_EG: i16 = PieceType::Queen.value();
+    pub const KING_MG: i16 = PieceType::King.value();
+    pub const KING_EG: i16 = PieceType::King.value();
     pub const BISHOP_PAIR_MG: i16 = 30;
     pub const BISHOP_PAIR_EG: i16 = 50;
 }
@@ -30,9 +34,27 @@ mod values {
 ///
 /// Counts pieces and weights them by standard chess piece values.
 /// Applies a tapered bishop pair bonus.
-pub struct MaterialHeuristic;
+pub struct MaterialHeuristic {
+    /// Piece values, pawn to queen
+    piece_values: [TaperedScore; 5],
+    bishop_pair: TaperedScore,
+}
+
+impl MaterialHeuristic {
+    /// Creates the heuristic with the piece values of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            piece_values: params.piece_values,
+            bishop_pair: params.bishop_pair,
+        }
+    }
+}
 
 impl HeuristicComponent for MaterialHeuristic {
+    fn name(&self) -> &'static str {
+        "Material"
+    }
+
     fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
         let piece_list = &board.piece_list;
 
@@ -73,45 +95,46 @@ impl HeuristicComponent for MaterialHeuristic {
             .get_number_of_pieces(Piece::BlackKing)
             .unwrap_or(0);
 
-        let material_mg = values::PAWN_MG * (w_pawn - b_pawn)
-            + values::KNIGHT_MG * (w_knight - b_knight)
-            + values::BISHOP_MG * (w_bishop - b_bishop)
-            + values::ROOK_MG * (w_rook - b_rook)
-            + values::QUEEN_MG * (w_queen - b_queen)
+        let [pawn, knight, bishop, rook, queen] = self.piece_values;
+        let material_mg = pawn.mg * (w_pawn - b_pawn)
+            + knight.mg * (w_knight - b_knight)
+            + bishop.mg * (w_bishop - b_bishop)
+            + rook.mg * (w_rook - b_rook)
+            + queen.mg * (w_queen - b_queen)
             + values::KING_MG * (w_king - b_king);
 
-        let material_eg = values::PAWN_EG * (w_pawn - b_pawn)
-            + values::KNIGHT_EG * (w_knight - b_knight)
-            + values::BISHOP_EG * (w_bishop - b_bishop)
-            + values::ROOK_EG * (w_rook - b_rook)
-            + values::QUEEN_EG * (w_queen - b_queen)
+        let material_eg = pawn.eg * (w_pawn - b_pawn)
+            + knight.eg * (w_knight - b_knight)
+            + bishop.eg * (w_bishop - b_bishop)
+            + rook.eg * (w_rook - b_rook)
+            + queen.eg * (w_queen - b_queen)
             + values::KING_EG * (w_king - b_king);
 
         let w_bishop_pair = if w_bishop >= 2 {
-            values::BISHOP_PAIR_MG
+            self.bishop_pair.mg
         } else {
             0
         };
         let b_bishop_pair = if b_bishop >= 2 {
-            values::BISHOP_PAIR_MG
+            self.bishop_pair.mg
         } else {
             0
         };
         let pair_mg = w_bishop_pair - b_bishop_pair;
 
         let w_bishop_pair_eg = if w_bishop >= 2 {
-            values::BISHOP_PAIR_EG
+            self.bishop_pair.eg
         } else {
             0
         };
         let b_bishop_pair_eg = if b_bishop >= 2 {
-            values::BISHOP_PAIR_EG
+            self.bishop_pair.eg
         } else {
             0
         };
         let pair_eg = w_bishop_pair_eg - b_bishop_pair_eg;
 
-        let tapered = super::TaperedScore::new(material_mg + pair_mg, material_eg + pair_eg);
+        let tapered = TaperedScore::new(material_mg + pair_mg, material_eg + pair_eg);
 
         tapered.interpolate(phase)
     }
@@ -0,0 +1,489 @@
+//! NNUE (efficiently updatable neural network) evaluation.
+//!
+//! The network uses HalfKP-style inputs: for each perspective, a feature is
+//! the combination of that side's king square with the piece type, colour
+//! and square of every other non-king piece. Both perspectives share the
+//! same feature transformer, with Black's board mirrored vertically.
+//!
+//! The feature transformer output (the *accumulator*) is kept up to date
+//! incrementally: a move only adds and removes a handful of features, so
+//! [`NnueState`] copies the accumulator into the buffer of the next ply on
+//! each move and updates it, and goes back a ply on unmake. The buffers are
+//! kept once a ply has been reached, so the search doesn't allocate. A king
+//! move changes every feature of its own perspective, which is then
+//! refreshed from scratch.
+//!
+//! The accumulators go through a clipped ReLU into a single output neuron:
+//!
+//! ```text
+//! eval = (sum(clamp(white_acc) * w[..H]) + sum(clamp(black_acc) * w[H..]) + bias)
+//!        * OUTPUT_SCALE / (QA * QB)
+//! ```
+//!
+//! The evaluation is from White's perspective, like the rest of the
+//! evaluation framework.
+//!
+//! # File format
+//!
+//! All values are little-endian:
+//!
+//! | Field                | Type  | Count            |
+//! |----------------------|-------|------------------|
+//! | magic `"ENN1"`       | `u32` | 1                |
+//! | hidden size `H`      | `u32` | 1                |
+//! | feature weights      | `i16` | `FEATURES * H`   |
+//! | feature biases       | `i16` | `H`              |
+//! | output weights       | `i16` | `2 * H`          |
+//! | output bias          | `i32` | 1                |
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use smallvec::SmallVec;
+
+use crate::game_state::board::piece::{Color, Piece, PieceType};
+
+pub mod simd;
+
+use simd::{add_weights, clipped_dot, sub_weights};
+
+/// Magic number at the start of a network file (`"ENN1"`).
+pub const NNUE_MAGIC: u32 = u32::from_le_bytes(*b"ENN1");
+
+/// Default name of the network file.
+pub const DEFAULT_EVAL_FILE: &str = "enrust.nnue";
+
+/// Number of non-king piece kinds (5 piece types for each colour).
+const PIECE_KINDS: usize = 10;
+
+/// Number of input features per perspective.
+pub const FEATURES: usize = 64 * PIECE_KINDS * 64;
+
+/// Clipped ReLU ceiling, the quantization scale of the accumulator.
+pub const QA: i32 = 255;
+
+/// Quantization scale of the output weights.
+pub const QB: i32 = 64;
+
+/// Scale from network output to centipawns.
+pub const OUTPUT_SCALE: i32 = 400;
+
+/// Pieces that change with a move, as `(piece, square)` pairs.
+pub type FeatureChanges = SmallVec<[(Piece, usize); 3]>;
+
+/// A quantized HalfKP-style network.
+pub struct Network {
+    /// Number of neurons in the hidden layer
+    hidden_size: usize,
+    /// Feature transformer weights, `hidden_size` values per feature
+    feature_weights: Vec<i16>,
+    /// Feature transformer biases
+    feature_biases: Vec<i16>,
+    /// Output weights, White's accumulator first
+    output_weights: Vec<i16>,
+    /// Output bias
+    output_bias: i32,
+}
+
+impl Network {
+    /// Creates a network from its parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `hidden_size` - Number of neurons in the hidden layer
+    /// * `feature_weights` - `FEATURES * hidden_size` weights
+    /// * `feature_biases` - `hidden_size` biases
+    /// * `output_weights` - `2 * hidden_size` weights
+    /// * `output_bias` - Output bias
+    ///
+    /// # Returns
+    ///
+    /// An error if a layer doesn't match the hidden size
+    pub fn new(
+        hidden_size: usize,
+        feature_weights: Vec<i16>,
+        feature_biases: Vec<i16>,
+        output_weights: Vec<i16>,
+        output_bias: i32,
+    ) -> io::Result<Self> {
+        if hidden_size == 0
+            || feature_weights.len() != FEATURES * hidden_size
+            || feature_biases.len() != hidden_size
+            || output_weights.len() != 2 * hidden_size
+        {
+            return Err(invalid_data("network layer sizes don't match"));
+        }
+
+        Ok(Network {
+            hidden_size,
+            feature_weights,
+            feature_biases,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    /// Loads a network file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the network file
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Parses a network from the bytes of a network file.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = ByteReader { bytes, position: 0 };
+
+        if reader.read_u32()? != NNUE_MAGIC {
+            return Err(invalid_data("not an EnRust network file"));
+        }
+
+        let hidden_size = reader.read_u32()? as usize;
+        if hidden_size == 0 || hidden_size > 4096 {
+            return Err(invalid_data("invalid hidden layer size"));
+        }
+
+        let feature_weights = reader.read_i16s(FEATURES * hidden_size)?;
+        let feature_biases = reader.read_i16s(hidden_size)?;
+        let output_weights = reader.read_i16s(2 * hidden_size)?;
+        let output_bias = reader.read_i32()?;
+
+        if reader.position != bytes.len() {
+            return Err(invalid_data("trailing data after network"));
+        }
+
+        Self::new(
+            hidden_size,
+            feature_weights,
+            feature_biases,
+            output_weights,
+            output_bias,
+        )
+    }
+
+    /// Serializes the network in the network file format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + 2 * self.feature_weights.len());
+        bytes.extend_from_slice(&NNUE_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&(self.hidden_size as u32).to_le_bytes());
+        for value in self
+            .feature_weights
+            .iter()
+            .chain(&self.feature_biases)
+            .chain(&self.output_weights)
+        {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.output_bias.to_le_bytes());
+        bytes
+    }
+
+    /// Gets the number of neurons in the hidden layer.
+    pub fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    /// Gets the feature transformer weights of a feature.
+    fn weights(&self, feature: usize) -> &[i16] {
+        let start = feature * self.hidden_size;
+        &self.feature_weights[start..start + self.hidden_size]
+    }
+
+    /// Computes the accumulator of one perspective from scratch.
+    ///
+    /// # Arguments
+    ///
+    /// * `pieces` - Board in standard 0-63 square order
+    /// * `perspective` - Side whose king anchors the features
+    pub fn refresh(&self, pieces: &[Piece; 64], perspective: Color) -> Vec<i16> {
+        let mut values = vec![0; self.hidden_size];
+        self.refresh_into(pieces, perspective, &mut values);
+        values
+    }
+
+    /// Computes the accumulator of one perspective from scratch into an
+    /// existing buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `pieces` - Board in standard 0-63 square order
+    /// * `perspective` - Side whose king anchors the features
+    /// * `values` - Buffer of `hidden_size` values to overwrite
+    fn refresh_into(&self, pieces: &[Piece; 64], perspective: Color, values: &mut [i16]) {
+        values.copy_from_slice(&self.feature_biases);
+
+        let Some(king_square) = find_king(pieces, perspective) else {
+            return;
+        };
+
+        for (square, &piece) in pieces.iter().enumerate() {
+            if let Some(feature) = feature_index(perspective, king_square, piece, square) {
+                add_weights(values, self.weights(feature));
+            }
+        }
+    }
+
+    /// Evaluates an accumulator.
+    ///
+    /// # Returns
+    ///
+    /// Score in centipawns from White's perspective
+    pub fn evaluate(&self, accumulator: &Accumulator) -> i16 {
+        let (white_weights, black_weights) = self.output_weights.split_at(self.hidden_size);
+
+        let output = self.output_bias
+            + clipped_dot(&accumulator.white, white_weights)
+            + clipped_dot(&accumulator.black, black_weights);
+
+        let score = output as i64 * OUTPUT_SCALE as i64 / (QA * QB) as i64;
+        score.clamp(-(i16::MAX as i64) / 2, i16::MAX as i64 / 2) as i16
+    }
+}
+
+/// Feature transformer output for both perspectives.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Accumulator {
+    /// Hidden layer values from White's perspective
+    pub white: Vec<i16>,
+    /// Hidden layer values from Black's perspective
+    pub black: Vec<i16>,
+}
+
+impl Accumulator {
+    /// Computes both perspectives from scratch.
+    pub fn new(network: &Network, pieces: &[Piece; 64]) -> Self {
+        Accumulator {
+            white: network.refresh(pieces, Color::White),
+            black: network.refresh(pieces, Color::Black),
+        }
+    }
+
+    /// Overwrites the values with those of another accumulator of the same
+    /// network, reusing the buffers.
+    fn copy_from(&mut self, other: &Accumulator) {
+        self.white.copy_from_slice(&other.white);
+        self.black.copy_from_slice(&other.black);
+    }
+
+    /// Gets the values of one perspective.
+    fn perspective_mut(&mut self, perspective: Color) -> &mut [i16] {
+        match perspective {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black,
+        }
+    }
+}
+
+/// Accumulator stack following the moves made on a board.
+#[derive(Clone)]
+pub struct NnueState {
+    /// Network used for evaluation
+    network: Arc<Network>,
+    /// One accumulator per ply, the root first. Buffers past the current
+    /// position are kept for the next moves
+    stack: Vec<Accumulator>,
+    /// King squares of each position up to the current one, in standard
+    /// 0-63 order. Its length gives the number of accumulators in use
+    kings: Vec<[Option<usize>; 2]>,
+}
+
+impl NnueState {
+    /// Creates the accumulator of a position.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - Network used for evaluation
+    /// * `pieces` - Board in standard 0-63 square order
+    pub fn new(network: Arc<Network>, pieces: &[Piece; 64]) -> Self {
+        let mut state = NnueState {
+            network,
+            stack: Vec::with_capacity(64),
+            kings: Vec::with_capacity(64),
+        };
+        state.refresh(pieces);
+        state
+    }
+
+    /// Discards the move history and recomputes the accumulator.
+    pub fn refresh(&mut self, pieces: &[Piece; 64]) {
+        self.stack.clear();
+        self.stack.push(Accumulator::new(&self.network, pieces));
+        self.kings.clear();
+        self.kings.push([
+            find_king(pieces, Color::White),
+            find_king(pieces, Color::Black),
+        ]);
+    }
+
+    /// Pushes the accumulator of the position after a move.
+    ///
+    /// # Arguments
+    ///
+    /// * `removed` - Pieces taken off their squares by the move
+    /// * `added` - Pieces put on their squares by the move
+    /// * `pieces` - Board after the move, required when a king moved
+    pub fn push(
+        &mut self,
+        removed: &FeatureChanges,
+        added: &FeatureChanges,
+        pieces: Option<&[Piece; 64]>,
+    ) {
+        let mut kings = *self.kings.last().expect("king squares");
+        let ply = self.kings.len();
+
+        // The buffer of the ply is only allocated the first time it's reached
+        if ply == self.stack.len() {
+            self.stack.push(self.stack[ply - 1].clone());
+        } else {
+            let (previous, next) = self.stack.split_at_mut(ply);
+            next[0].copy_from(&previous[ply - 1]);
+        }
+        let accumulator = &mut self.stack[ply];
+
+        for (index, perspective) in [Color::White, Color::Black].into_iter().enumerate() {
+            let king_moved = added
+                .iter()
+                .find(|&&(piece, _)| is_king(piece, perspective));
+
+            if let Some(&(_, king_square)) = king_moved {
+                kings[index] = Some(king_square);
+                let pieces = pieces.expect("board after a king move");
+                self.network.refresh_into(
+                    pieces,
+                    perspective,
+                    accumulator.perspective_mut(perspective),
+                );
+                continue;
+            }
+
+            let Some(king_square) = kings[index] else {
+                continue;
+            };
+            let values = accumulator.perspective_mut(perspective);
+
+            for &(piece, square) in removed {
+                if let Some(feature) = feature_index(perspective, king_square, piece, square) {
+                    sub_weights(values, self.network.weights(feature));
+                }
+            }
+            for &(piece, square) in added {
+                if let Some(feature) = feature_index(perspective, king_square, piece, square) {
+                    add_weights(values, self.network.weights(feature));
+                }
+            }
+        }
+
+        self.kings.push(kings);
+    }
+
+    /// Pops the accumulator of the last move.
+    pub fn pop(&mut self) {
+        if self.kings.len() > 1 {
+            self.kings.pop();
+        }
+    }
+
+    /// Gets the accumulator of the current position.
+    pub fn accumulator(&self) -> &Accumulator {
+        &self.stack[self.kings.len() - 1]
+    }
+
+    /// Gets the network used for evaluation.
+    pub fn network(&self) -> &Arc<Network> {
+        &self.network
+    }
+
+    /// Evaluates the current position from White's perspective.
+    pub fn evaluate(&self) -> i16 {
+        self.network.evaluate(self.accumulator())
+    }
+}
+
+/// Computes the input feature of a piece.
+///
+/// # Arguments
+///
+/// * `perspective` - Side whose king anchors the feature
+/// * `king_square` - Square of that king, in standard 0-63 order
+/// * `piece` - Piece on the square
+/// * `square` - Square of the piece, in standard 0-63 order
+///
+/// # Returns
+///
+/// `None` for kings and empty squares, which are not features
+pub fn feature_index(
+    perspective: Color,
+    king_square: usize,
+    piece: Piece,
+    square: usize,
+) -> Option<usize> {
+    if !piece.is_valid_piece() || piece.get_type() == PieceType::King {
+        return None;
+    }
+
+    // Black sees the board upside down, so both sides share the weights
+    let orient = |square: usize| match perspective {
+        Color::White => square,
+        Color::Black => square ^ 56,
+    };
+
+    let kind = piece as usize % 6;
+    let relation = if piece.get_color() == perspective {
+        0
+    } else {
+        1
+    };
+    let piece_index = kind * 2 + relation;
+
+    Some((orient(king_square) * PIECE_KINDS + piece_index) * 64 + orient(square))
+}
+
+fn is_king(piece: Piece, color: Color) -> bool {
+    piece.is_valid_piece() && piece.get_type() == PieceType::King && piece.get_color() == color
+}
+
+fn find_king(pieces: &[Piece; 64], color: Color) -> Option<usize> {
+    pieces.iter().position(|&piece| is_king(piece, color))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Little-endian reader over the bytes of a network file.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ByteReader<'_> {
+    fn take(&mut self, count: usize) -> io::Result<&[u8]> {
+        let end = self.position + count;
+        if end > self.bytes.len() {
+            return Err(invalid_data("network file is truncated"));
+        }
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_i16s(&mut self, count: usize) -> io::Result<Vec<i16>> {
+        Ok(self
+            .take(count * 2)?
+            .chunks_exact(2)
+            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect())
+    }
+}
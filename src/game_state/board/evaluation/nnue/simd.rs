@@ -0,0 +1,151 @@
+//! Vectorized accumulator updates and output dot products.
+//!
+//! With the `simd` feature enabled on x86-64, these functions use AVX2 when
+//! the CPU supports it, detected at runtime. Otherwise, and for the elements
+//! left over after the last full vector, the scalar versions in [`scalar`]
+//! are used. Both paths produce identical results, including the wrapping
+//! of accumulator values on overflow.
+
+use super::QA;
+
+/// Adds feature weights to accumulator values.
+pub fn add_weights(values: &mut [i16], weights: &[i16]) {
+    assert!(weights.len() >= values.len());
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was checked above
+        unsafe { avx2::add_weights(values, weights) };
+        return;
+    }
+
+    scalar::add_weights(values, weights);
+}
+
+/// Subtracts feature weights from accumulator values.
+pub fn sub_weights(values: &mut [i16], weights: &[i16]) {
+    assert!(weights.len() >= values.len());
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was checked above
+        unsafe { avx2::sub_weights(values, weights) };
+        return;
+    }
+
+    scalar::sub_weights(values, weights);
+}
+
+/// Computes the dot product of clipped ReLU activations with output weights.
+pub fn clipped_dot(values: &[i16], weights: &[i16]) -> i32 {
+    assert!(weights.len() >= values.len());
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was checked above
+        return unsafe { avx2::clipped_dot(values, weights) };
+    }
+
+    scalar::clipped_dot(values, weights)
+}
+
+/// Portable implementations, used as fallback and as reference.
+pub mod scalar {
+    use super::QA;
+
+    /// Adds feature weights to accumulator values.
+    pub fn add_weights(values: &mut [i16], weights: &[i16]) {
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value = value.wrapping_add(*weight);
+        }
+    }
+
+    /// Subtracts feature weights from accumulator values.
+    pub fn sub_weights(values: &mut [i16], weights: &[i16]) {
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value = value.wrapping_sub(*weight);
+        }
+    }
+
+    /// Computes the dot product of clipped ReLU activations with output weights.
+    pub fn clipped_dot(values: &[i16], weights: &[i16]) -> i32 {
+        values
+            .iter()
+            .zip(weights)
+            .map(|(&value, &weight)| (value as i32).clamp(0, QA) * weight as i32)
+            .sum()
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    use super::{QA, scalar};
+
+    /// Number of `i16` lanes in an AVX2 register.
+    const LANES: usize = 16;
+
+    #[target_feature(enable = "avx2")]
+    pub fn add_weights(values: &mut [i16], weights: &[i16]) {
+        let vectorized = values.len() / LANES * LANES;
+
+        for start in (0..vectorized).step_by(LANES) {
+            // SAFETY: `start + LANES` is within both slices
+            unsafe {
+                let value = _mm256_loadu_si256(values.as_ptr().add(start).cast());
+                let weight = _mm256_loadu_si256(weights.as_ptr().add(start).cast());
+                let sum = _mm256_add_epi16(value, weight);
+                _mm256_storeu_si256(values.as_mut_ptr().add(start).cast(), sum);
+            }
+        }
+
+        scalar::add_weights(&mut values[vectorized..], &weights[vectorized..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub fn sub_weights(values: &mut [i16], weights: &[i16]) {
+        let vectorized = values.len() / LANES * LANES;
+
+        for start in (0..vectorized).step_by(LANES) {
+            // SAFETY: `start + LANES` is within both slices
+            unsafe {
+                let value = _mm256_loadu_si256(values.as_ptr().add(start).cast());
+                let weight = _mm256_loadu_si256(weights.as_ptr().add(start).cast());
+                let difference = _mm256_sub_epi16(value, weight);
+                _mm256_storeu_si256(values.as_mut_ptr().add(start).cast(), difference);
+            }
+        }
+
+        scalar::sub_weights(&mut values[vectorized..], &weights[vectorized..]);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub fn clipped_dot(values: &[i16], weights: &[i16]) -> i32 {
+        let vectorized = values.len() / LANES * LANES;
+
+        let zero = _mm256_setzero_si256();
+        let ceiling = _mm256_set1_epi16(QA as i16);
+        let mut sums = _mm256_setzero_si256();
+
+        for start in (0..vectorized).step_by(LANES) {
+            // SAFETY: `start + LANES` is within both slices
+            let (value, weight) = unsafe {
+                (
+                    _mm256_loadu_si256(values.as_ptr().add(start).cast()),
+                    _mm256_loadu_si256(weights.as_ptr().add(start).cast()),
+                )
+            };
+            let clipped = _mm256_min_epi16(_mm256_max_epi16(value, zero), ceiling);
+            // Multiplies the 16-bit lanes and adds neighbouring pairs into 32 bits
+            sums = _mm256_add_epi32(sums, _mm256_madd_epi16(clipped, weight));
+        }
+
+        let mut lanes = [0i32; 8];
+        // SAFETY: `lanes` holds exactly one 256-bit vector
+        unsafe { _mm256_storeu_si256(lanes.as_mut_ptr().cast(), sums) };
+
+        lanes.iter().sum::<i32>()
+            + scalar::clipped_dot(&values[vectorized..], &weights[vectorized..])
+    }
+}
@@ -0,0 +1,493 @@
+//! Evaluation parameters.
+//!
+//! Every weight of the handcrafted evaluation is gathered in [`EvalParams`],
+//! whose defaults are the constants the heuristics are written with. A
+//! parameter file replaces some or all of them without rebuilding the
+//! engine, for tuning experiments or to ship alternative playing styles.
+//!
+//! Files are TOML or JSON, told apart by their first character. Only the
+//! subset the parameters need is understood: tables (objects in JSON)
+//! holding integers and arrays of integers. A parameter is named after its
+//! table and key, tapered weights are `[mg, eg]` pairs and piece-square
+//! tables list 64 values from a8 to h1. Parameters missing from the file
+//! keep their defaults, and unknown ones are reported as errors so typos
+//! don't go unnoticed.
+//!
+//! ```toml
+//! [material]
+//! knight = [320, 300]
+//!
+//! [passed_pawns]
+//! unstoppable = 650
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::TaperedScore;
+use super::material::values;
+use super::piece_square::{self, Pst};
+use super::{attacks, passed_pawn, space, trapped};
+
+/// Names of the pieces in parameter names, pawn to king.
+const PIECE_NAMES: [&str; 6] = ["pawn", "knight", "bishop", "rook", "queen", "king"];
+
+/// Weights of the handcrafted evaluation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalParams {
+    /// Piece values, pawn to queen
+    pub piece_values: [TaperedScore; 5],
+    /// Bonus for the bishop pair
+    pub bishop_pair: TaperedScore,
+    /// Midgame piece-square tables, pawn to king, a8 first
+    pub piece_square_mg: [Pst; 6],
+    /// Endgame piece-square tables, pawn to king, a8 first
+    pub piece_square_eg: [Pst; 6],
+    /// Bonus for a passed pawn the enemy king can't catch
+    pub unstoppable_passer: i16,
+    /// Penalty for a bishop shut in on the seventh rank
+    pub trapped_bishop: TaperedScore,
+    /// Penalty for a knight in a corner of the enemy side
+    pub cornered_knight: TaperedScore,
+    /// Penalty for a rook boxed in by its own king
+    pub boxed_rook: TaperedScore,
+    /// Bonus for each safe space square and minor piece
+    pub space_weight: i16,
+    /// Bonus for a pawn on a central square
+    pub center_pawn: i16,
+    /// Bonus for each attacked central square
+    pub center_attack: i16,
+    /// Bonus for each attacked square around the center
+    pub extended_center_attack: i16,
+    /// Penalty for each attack on the king zone
+    pub king_zone_attack: TaperedScore,
+    /// Penalty for each weak square
+    pub weak_square: TaperedScore,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        EvalParams {
+            piece_values: [
+                TaperedScore::new(values::PAWN_MG, values::PAWN_EG),
+                TaperedScore::new(values::KNIGHT_MG, values::KNIGHT_EG),
+                TaperedScore::new(values::BISHOP_MG, values::BISHOP_EG),
+                TaperedScore::new(values::ROOK_MG, values::ROOK_EG),
+                TaperedScore::new(values::QUEEN_MG, values::QUEEN_EG),
+            ],
+            bishop_pair: TaperedScore::new(values::BISHOP_PAIR_MG, values::BISHOP_PAIR_EG),
+            piece_square_mg: piece_square::MG_TABLES,
+            piece_square_eg: piece_square::EG_TABLES,
+            unstoppable_passer: passed_pawn::UNSTOPPABLE_PASSER_BONUS,
+            trapped_bishop: trapped::TRAPPED_BISHOP,
+            cornered_knight: trapped::CORNERED_KNIGHT,
+            boxed_rook: trapped::BOXED_ROOK,
+            space_weight: space::SPACE_WEIGHT,
+            center_pawn: space::CENTER_PAWN,
+            center_attack: space::CENTER_ATTACK,
+            extended_center_attack: space::EXTENDED_CENTER_ATTACK,
+            king_zone_attack: attacks::KING_ZONE_ATTACK,
+            weak_square: attacks::WEAK_SQUARE,
+        }
+    }
+}
+
+impl EvalParams {
+    /// Loads parameters from a TOML or JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the parameter file
+    ///
+    /// # Returns
+    ///
+    /// The defaults with the parameters of the file applied, or an
+    /// `InvalidData` error naming the offending line
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses parameters from the text of a TOML or JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Content of the parameter file
+    ///
+    /// # Returns
+    ///
+    /// The defaults with the parameters of the text applied
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let entries = if text.trim_start().starts_with('{') {
+            parse_json(text)
+        } else {
+            parse_toml(text)
+        }
+        .map_err(invalid_data)?;
+
+        let mut params = EvalParams::default();
+        for entry in entries {
+            params
+                .set(&entry.name, &entry.values)
+                .map_err(|err| invalid_data(format!("line {}: {}", entry.line, err)))?;
+        }
+        Ok(params)
+    }
+
+    /// Sets a parameter by its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Parameter name, such as `material.knight`
+    /// * `values` - One value per weight of the parameter
+    ///
+    /// # Returns
+    ///
+    /// An error message for an unknown name, a wrong number of values or a
+    /// value that doesn't fit a weight
+    pub fn set(&mut self, name: &str, values: &[i32]) -> Result<(), String> {
+        let mut fields = self.fields();
+        let Some((_, weights)) = fields.iter_mut().find(|(field, _)| field == name) else {
+            return Err(format!("unknown parameter '{}'", name));
+        };
+        if weights.len() != values.len() {
+            return Err(format!(
+                "'{}' expects {} values, found {}",
+                name,
+                weights.len(),
+                values.len()
+            ));
+        }
+
+        for (weight, &value) in weights.iter_mut().zip(values) {
+            **weight = i16::try_from(value)
+                .map_err(|_| format!("'{}' value {} is out of range", name, value))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the parameters as a TOML file that [`EvalParams::parse`]
+    /// reads back.
+    pub fn to_toml(&self) -> String {
+        let mut params = self.clone();
+        let mut toml = String::new();
+        let mut table = String::new();
+
+        for (name, weights) in params.fields() {
+            let (field_table, key) = name.split_once('.').unwrap_or(("", &name));
+            if field_table != table {
+                if !toml.is_empty() {
+                    toml.push('\n');
+                }
+                toml.push_str(&format!("[{}]\n", field_table));
+                table = field_table.to_string();
+            }
+
+            let weights: Vec<String> = weights.iter().map(|weight| weight.to_string()).collect();
+            match weights.len() {
+                1 => toml.push_str(&format!("{} = {}\n", key, weights[0])),
+                2 => toml.push_str(&format!("{} = [{}]\n", key, weights.join(", "))),
+                // Piece-square tables, a rank per line
+                _ => {
+                    toml.push_str(&format!("{} = [\n", key));
+                    for rank in weights.chunks(8) {
+                        toml.push_str(&format!("    {},\n", rank.join(", ")));
+                    }
+                    toml.push_str("]\n");
+                }
+            }
+        }
+        toml
+    }
+
+    /// Lists the parameters by name, with the weights they are made of.
+    fn fields(&mut self) -> Vec<(String, Vec<&mut i16>)> {
+        fn tapered(score: &mut TaperedScore) -> Vec<&mut i16> {
+            vec![&mut score.mg, &mut score.eg]
+        }
+
+        let EvalParams {
+            piece_values,
+            bishop_pair,
+            piece_square_mg,
+            piece_square_eg,
+            unstoppable_passer,
+            trapped_bishop,
+            cornered_knight,
+            boxed_rook,
+            space_weight,
+            center_pawn,
+            center_attack,
+            extended_center_attack,
+            king_zone_attack,
+            weak_square,
+        } = self;
+
+        let mut fields = Vec::new();
+        for (piece, value) in PIECE_NAMES.iter().zip(piece_values) {
+            fields.push((format!("material.{}", piece), tapered(value)));
+        }
+        fields.push(("material.bishop_pair".to_string(), tapered(bishop_pair)));
+        for ((piece, mg), eg) in PIECE_NAMES.iter().zip(piece_square_mg).zip(piece_square_eg) {
+            fields.push((
+                format!("piece_square.{}_mg", piece),
+                mg.iter_mut().collect(),
+            ));
+            fields.push((
+                format!("piece_square.{}_eg", piece),
+                eg.iter_mut().collect(),
+            ));
+        }
+        fields.push((
+            "passed_pawns.unstoppable".to_string(),
+            vec![unstoppable_passer],
+        ));
+        fields.push(("trapped.bishop".to_string(), tapered(trapped_bishop)));
+        fields.push(("trapped.knight".to_string(), tapered(cornered_knight)));
+        fields.push(("trapped.rook".to_string(), tapered(boxed_rook)));
+        fields.push(("space.weight".to_string(), vec![space_weight]));
+        fields.push(("space.center_pawn".to_string(), vec![center_pawn]));
+        fields.push(("space.center_attack".to_string(), vec![center_attack]));
+        fields.push((
+            "space.extended_center_attack".to_string(),
+            vec![extended_center_attack],
+        ));
+        fields.push((
+            "king_safety.zone_attack".to_string(),
+            tapered(king_zone_attack),
+        ));
+        fields.push(("weak_squares.penalty".to_string(), tapered(weak_square)));
+        fields
+    }
+}
+
+/// A parameter read from a file.
+struct Entry {
+    /// Line the parameter was found on
+    line: usize,
+    /// Parameter name, the table and the key joined by a dot
+    name: String,
+    /// Values, a single one for scalars
+    values: Vec<i32>,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads the entries of a TOML file.
+fn parse_toml(text: &str) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    let mut table = String::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let Some(name) = header.strip_suffix(']') else {
+                return Err(format!(
+                    "line {}: expected ']' after the table name",
+                    line_number
+                ));
+            };
+            table = name.trim().to_string();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected 'name = value'", line_number));
+        };
+        let mut value = value.trim().to_string();
+        // Arrays may span several lines
+        if value.starts_with('[') {
+            while !value.contains(']') {
+                let Some((_, next)) = lines.next() else {
+                    return Err(format!("line {}: unterminated array", line_number));
+                };
+                value.push(' ');
+                value.push_str(strip_comment(next));
+            }
+        }
+
+        let key = key.trim();
+        entries.push(Entry {
+            line: line_number,
+            name: if table.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", table, key)
+            },
+            values: parse_values(&value).map_err(|err| format!("line {}: {}", line_number, err))?,
+        });
+    }
+    Ok(entries)
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split_once('#').map_or(line, |(before, _)| before)
+}
+
+/// Parses a TOML value, an integer or an array of integers.
+fn parse_values(value: &str) -> Result<Vec<i32>, String> {
+    let value = value.trim();
+    let Some(array) = value.strip_prefix('[') else {
+        return parse_integer(value).map(|value| vec![value]);
+    };
+    let Some(array) = array.trim_end().strip_suffix(']') else {
+        return Err("expected ']' at the end of the array".to_string());
+    };
+
+    let mut items: Vec<&str> = array.split(',').map(str::trim).collect();
+    // A trailing comma is allowed
+    if items.last() == Some(&"") {
+        items.pop();
+    }
+    items.into_iter().map(parse_integer).collect()
+}
+
+fn parse_integer(token: &str) -> Result<i32, String> {
+    token
+        .parse()
+        .map_err(|_| format!("invalid integer '{}'", token))
+}
+
+/// Reads the entries of a JSON file, nested objects giving the tables.
+fn parse_json(text: &str) -> Result<Vec<Entry>, String> {
+    let mut reader = JsonReader {
+        bytes: text.as_bytes(),
+        position: 0,
+        line: 1,
+    };
+    let mut entries = Vec::new();
+    reader.object("", &mut entries)?;
+    if reader.peek().is_some() {
+        return Err(reader.error("unexpected content after the object"));
+    }
+    Ok(entries)
+}
+
+/// Reader for the JSON subset of parameter files: objects whose values are
+/// objects, integers or arrays of integers.
+struct JsonReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+}
+
+impl JsonReader<'_> {
+    /// Skips whitespace and gets the next character without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(&byte) = self.bytes.get(self.position) {
+            if !byte.is_ascii_whitespace() {
+                return Some(byte);
+            }
+            if byte == b'\n' {
+                self.line += 1;
+            }
+            self.position += 1;
+        }
+        None
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("line {}: {}", self.line, message)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected '{}'", expected as char)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Reads a string, which can't hold escapes in parameter names.
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let start = self.position;
+        while let Some(&byte) = self.bytes.get(self.position) {
+            self.position += 1;
+            if byte == b'"' {
+                let string = &self.bytes[start..self.position - 1];
+                return Ok(String::from_utf8_lossy(string).into_owned());
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn integer(&mut self) -> Result<i32, String> {
+        self.peek();
+        let start = self.position;
+        while let Some(b'-' | b'0'..=b'9') = self.bytes.get(self.position) {
+            self.position += 1;
+        }
+        let token = String::from_utf8_lossy(&self.bytes[start..self.position]);
+        parse_integer(&token).map_err(|err| self.error(&err))
+    }
+
+    fn array(&mut self) -> Result<Vec<i32>, String> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(values);
+        }
+
+        loop {
+            values.push(self.integer()?);
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(values);
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Reads an object, adding its entries under a name prefix.
+    fn object(&mut self, prefix: &str, entries: &mut Vec<Entry>) -> Result<(), String> {
+        self.expect(b'{')?;
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(());
+        }
+
+        loop {
+            let key = self.string()?;
+            let line = self.line;
+            let name = if prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            self.expect(b':')?;
+
+            match self.peek() {
+                Some(b'{') => self.object(&name, entries)?,
+                Some(b'[') => {
+                    let values = self.array()?;
+                    entries.push(Entry { line, name, values });
+                }
+                _ => {
+                    let values = vec![self.integer()?];
+                    entries.push(Entry { line, name, values });
+                }
+            }
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
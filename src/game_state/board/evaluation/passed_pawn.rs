@@ -0,0 +1,144 @@
+//! Unstoppable passed pawns in pawn endgames.
+//!
+//! Once only kings and pawns are left, a passed pawn that the enemy king
+//! can't catch will promote, whatever the rest of the position looks like.
+//! The rule of the square decides the race: the king catches the pawn if it
+//! is no farther from the promotion square than the pawn is.
+
+use crate::game_state::ChessBoard;
+use crate::game_state::Color;
+use crate::game_state::Piece;
+
+use super::params::EvalParams;
+use super::{GamePhase, HeuristicComponent};
+
+/// Bonus for a pawn that can't be stopped, a bit less than a queen so the
+/// search still prefers actually promoting.
+pub(super) const UNSTOPPABLE_PASSER_BONUS: i16 = 700;
+
+/// Heuristic component that scores pawn races in pawn endgames.
+///
+/// The evaluation doesn't know the side to move, so the defending king is
+/// always given the tempo. When both sides have an unstoppable pawn, only
+/// the one that promotes first gets the bonus.
+pub struct UnstoppablePasserHeuristic {
+    bonus: i16,
+}
+
+impl UnstoppablePasserHeuristic {
+    /// Creates the heuristic with the bonus of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            bonus: params.unstoppable_passer,
+        }
+    }
+}
+
+impl HeuristicComponent for UnstoppablePasserHeuristic {
+    fn name(&self) -> &'static str {
+        "Passed pawns"
+    }
+
+    fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
+        if phase.value() != 0 {
+            return 0;
+        }
+
+        let mut pawns = Vec::new();
+        let mut white_king = None;
+        let mut black_king = None;
+        board.piece_list.for_each_piece(|piece, square| {
+            let square = board.map_to_standard_chess_board(square);
+            match piece {
+                Piece::WhitePawn => pawns.push((square, Color::White)),
+                Piece::BlackPawn => pawns.push((square, Color::Black)),
+                Piece::WhiteKing => white_king = Some(square),
+                Piece::BlackKing => black_king = Some(square),
+                _ => {}
+            }
+        });
+
+        let (Some(white_king), Some(black_king)) = (white_king, black_king) else {
+            return 0;
+        };
+
+        // Fewest moves to promote an unstoppable pawn, for each side
+        let mut white_race: Option<usize> = None;
+        let mut black_race: Option<usize> = None;
+        for (square, color) in pawns {
+            let (defending_king, race) = match color {
+                Color::White => (black_king, &mut white_race),
+                Color::Black => (white_king, &mut black_race),
+            };
+
+            if let Some(moves) = unstoppable_pawn_moves(board, square, color, defending_king) {
+                *race = Some(race.map_or(moves, |best| best.min(moves)));
+            }
+        }
+
+        match (white_race, black_race) {
+            (Some(_), None) => self.bonus,
+            (None, Some(_)) => -self.bonus,
+            (Some(white), Some(black)) if white < black => self.bonus,
+            (Some(white), Some(black)) if black < white => -self.bonus,
+            _ => 0,
+        }
+    }
+
+    fn delta(&self, _board: &ChessBoard, _mv: &crate::game_state::board::Move) -> Option<i16> {
+        None
+    }
+}
+
+/// Checks a pawn against the rule of the square.
+///
+/// # Arguments
+///
+/// * `board` - The current board state
+/// * `square` - Standard chess square of the pawn
+/// * `color` - Color of the pawn
+/// * `defending_king` - Standard chess square of the enemy king
+///
+/// # Returns
+///
+/// `Some(moves)` with the moves the pawn needs to promote if it can't be
+/// stopped, `None` otherwise
+fn unstoppable_pawn_moves(
+    board: &ChessBoard,
+    square: usize,
+    color: Color,
+    defending_king: usize,
+) -> Option<usize> {
+    if !board.is_passed_pawn(square, color) {
+        return None;
+    }
+
+    let (rank, file) = (square / 8, square % 8);
+    let (promotion_rank, start_rank, path) = match color {
+        Color::White => (7, 1, rank + 1..8),
+        Color::Black => (0, 6, 0..rank),
+    };
+
+    // Any piece in the way, the own king included, slows the pawn down
+    if path.into_iter().any(|rank| {
+        !board
+            .get_piece_on_square(board.map_inner_to_outer_board((rank * 8 + file) as i16))
+            .is_empty()
+    }) {
+        return None;
+    }
+
+    let mut moves = rank.abs_diff(promotion_rank);
+    // The first move can push the pawn two squares
+    if rank == start_rank {
+        moves -= 1;
+    }
+
+    let promotion_square = promotion_rank * 8 + file;
+    let king_distance = (defending_king / 8)
+        .abs_diff(promotion_square / 8)
+        .max((defending_king % 8).abs_diff(promotion_square % 8));
+
+    // The defending king may move first
+    (king_distance > moves + 1).then_some(moves)
+}
@@ -9,9 +9,10 @@
 use crate::game_state::ChessBoard;
 use crate::game_state::Piece;
 
+use super::params::EvalParams;
 use super::{GamePhase, HeuristicComponent, TaperedScore};
 
-type Pst = [i16; 64];
+pub type Pst = [i16; 64];
 
 const PAWN_MG: Pst = build_pawn_mg();
 const PAWN_EG: Pst = build_pawn_eg();
@@ -26,6 +27,12 @@ const QUEEN_EG: Pst = build_queen_eg();
 const KING_MG: Pst = build_king_mg();
 const KING_EG: Pst = build_king_eg();
 
+/// Midgame tables, pawn to king.
+pub(super) const MG_TABLES: [Pst; 6] = [PAWN_MG, KNIGHT_MG, BISHOP_MG, ROOK_MG, QUEEN_MG, KING_MG];
+
+/// Endgame tables, pawn to king.
+pub(super) const EG_TABLES: [Pst; 6] = [PAWN_EG, KNIGHT_EG, BISHOP_EG, ROOK_EG, QUEEN_EG, KING_EG];
+
 /// Looks up a PST value for a piece at the given standard chess square.
 ///
 /// The PST tables are stored in PesTO published format (index 0 = a8).
@@ -55,15 +62,44 @@ fn to_standard(board: &ChessBoard, internal_sq: i16) -> i16 {
 ///
 /// For each piece on the board, looks up its midgame and endgame PST
 /// values and interpolates them based on the current game phase.
-pub struct PieceSquareHeuristic;
+pub struct PieceSquareHeuristic {
+    /// Midgame tables, pawn to king
+    mg: [Pst; 6],
+    /// Endgame tables, pawn to king
+    eg: [Pst; 6],
+}
+
+impl PieceSquareHeuristic {
+    /// Creates the heuristic with the tables of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            mg: params.piece_square_mg,
+            eg: params.piece_square_eg,
+        }
+    }
+
+    /// Looks up the midgame and endgame values of a piece on a square.
+    fn pst_value(&self, piece: Piece, sq: i16) -> (i16, i16) {
+        let is_white = piece.is_white();
+        let table = piece.get_type() as usize;
+        (
+            pst_lookup(&self.mg[table], sq, is_white),
+            pst_lookup(&self.eg[table], sq, is_white),
+        )
+    }
+}
 
 impl HeuristicComponent for PieceSquareHeuristic {
+    fn name(&self) -> &'static str {
+        "Piece-square"
+    }
+
     fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
         let mut total = 0i16;
 
         board.piece_list.for_each_piece(|piece, sq| {
             let std_sq = to_standard(board, sq);
-            let (mg, eg) = pst_value(piece, std_sq);
+            let (mg, eg) = self.pst_value(piece, std_sq);
 
             total += if piece.is_white() { 1 } else { -1 }
                 * TaperedScore::new(mg, eg).interpolate(phase);
@@ -77,37 +113,6 @@ impl HeuristicComponent for PieceSquareHeuristic {
     }
 }
 
-fn pst_value(piece: Piece, sq: i16) -> (i16, i16) {
-    let is_white = piece.is_white();
-    match piece {
-        Piece::WhitePawn | Piece::BlackPawn => (
-            pst_lookup(&PAWN_MG, sq, is_white),
-            pst_lookup(&PAWN_EG, sq, is_white),
-        ),
-        Piece::WhiteKnight | Piece::BlackKnight => (
-            pst_lookup(&KNIGHT_MG, sq, is_white),
-            pst_lookup(&KNIGHT_EG, sq, is_white),
-        ),
-        Piece::WhiteBishop | Piece::BlackBishop => (
-            pst_lookup(&BISHOP_MG, sq, is_white),
-            pst_lookup(&BISHOP_EG, sq, is_white),
-        ),
-        Piece::WhiteRook | Piece::BlackRook => (
-            pst_lookup(&ROOK_MG, sq, is_white),
-            pst_lookup(&ROOK_EG, sq, is_white),
-        ),
-        Piece::WhiteQueen | Piece::BlackQueen => (
-            pst_lookup(&QUEEN_MG, sq, is_white),
-            pst_lookup(&QUEEN_EG, sq, is_white),
-        ),
-        Piece::WhiteKing | Piece::BlackKing => (
-            pst_lookup(&KING_MG, sq, is_white),
-            pst_lookup(&KING_EG, sq, is_white),
-        ),
-        _ => (0, 0),
-    }
-}
-
 const fn build_pawn_mg() -> Pst {
     [
         0, 0, 0, 0, 0, 0, 0, 0, 98, 134, 61, 95, 68, 126, 34, -11, -6, 7, 26, 31, 65, 56, 25, -20,
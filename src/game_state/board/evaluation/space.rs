@@ -0,0 +1,219 @@
+//! Space and center control.
+//!
+//! Space counts the safe squares a side controls in the middle of its own
+//! half, behind and around its pawns, where its pieces can maneuver. It
+//! only matters with pieces on the board, so it is weighted by the number
+//! of minor pieces and fades out with the game phase.
+//!
+//! Center control rewards the central squares held by pawns and attacked
+//! by pawns and minor pieces. It guides development in the opening and is
+//! switched off before the middlegame, when the piece-square tables take
+//! over.
+//!
+//! Squares are standard squares (0 = a1, 63 = h8) kept in bitboards.
+
+use crate::game_state::ChessBoard;
+use crate::game_state::Color;
+use crate::game_state::Piece;
+use crate::game_state::PieceType;
+use crate::game_state::board::directions::{self, DIAGONAL};
+
+use super::params::EvalParams;
+use super::{GamePhase, HeuristicComponent, TOTAL_PHASE, TaperedScore};
+
+/// Files c to f.
+const CENTER_FILES: u64 = 0x3c3c_3c3c_3c3c_3c3c;
+
+/// Squares of the central files on ranks 2 to 4, where White counts space.
+const WHITE_SPACE: u64 = CENTER_FILES & 0x0000_0000_ffff_ff00;
+
+/// Squares of the central files on ranks 5 to 7, where Black counts space.
+const BLACK_SPACE: u64 = CENTER_FILES & 0x00ff_ffff_0000_0000;
+
+/// Squares d4, e4, d5 and e5.
+const CENTER: u64 = 0x0000_0018_1800_0000;
+
+/// Squares c3 to f6 around the center.
+const EXTENDED_CENTER: u64 = 0x0000_3c3c_3c3c_0000 & !CENTER;
+
+/// File a, masked out of the pawn captures towards the a-file.
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+/// File h, masked out of the pawn captures towards the h-file.
+const FILE_H: u64 = 0x8080_8080_8080_8080;
+
+/// Bonus for each safe space square and minor piece.
+pub(super) const SPACE_WEIGHT: i16 = 1;
+
+/// Bonus for a pawn on a central square.
+pub(super) const CENTER_PAWN: i16 = 10;
+
+/// Bonus for each attacked central square.
+pub(super) const CENTER_ATTACK: i16 = 6;
+
+/// Bonus for each attacked square around the center.
+pub(super) const EXTENDED_CENTER_ATTACK: i16 = 2;
+
+/// Squares attacked by the pawns of a side.
+fn pawn_attacks(pawns: u64, color: Color) -> u64 {
+    match color {
+        Color::White => ((pawns & !FILE_A) << 7) | ((pawns & !FILE_H) << 9),
+        Color::Black => ((pawns & !FILE_H) >> 7) | ((pawns & !FILE_A) >> 9),
+    }
+}
+
+/// Gets the pawns of both sides, White's first.
+fn pawns(board: &ChessBoard) -> [u64; 2] {
+    let mut pawns = [0; 2];
+    board.piece_list.for_each_piece(|piece, square| {
+        let bit = 1 << board.map_to_standard_chess_board(square);
+        match piece {
+            Piece::WhitePawn => pawns[0] |= bit,
+            Piece::BlackPawn => pawns[1] |= bit,
+            _ => {}
+        }
+    });
+    pawns
+}
+
+/// Heuristic component that rewards the space behind the pawns.
+///
+/// A square counts when it isn't occupied by an own pawn nor attacked by an
+/// enemy pawn, and twice when it is behind an own pawn, out of reach of the
+/// enemy pieces.
+pub struct SpaceHeuristic {
+    weight: i16,
+}
+
+impl SpaceHeuristic {
+    /// Creates the heuristic with the weight of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            weight: params.space_weight,
+        }
+    }
+
+    /// Counts the safe space squares of a side.
+    fn space(own_pawns: u64, enemy_pawns: u64, color: Color) -> i16 {
+        let (area, behind) = match color {
+            Color::White => (
+                WHITE_SPACE,
+                (own_pawns >> 8) | (own_pawns >> 16) | (own_pawns >> 24),
+            ),
+            Color::Black => (
+                BLACK_SPACE,
+                (own_pawns << 8) | (own_pawns << 16) | (own_pawns << 24),
+            ),
+        };
+        let safe = area & !own_pawns & !pawn_attacks(enemy_pawns, color.opposite());
+
+        (safe.count_ones() + (safe & behind).count_ones()) as i16
+    }
+}
+
+impl HeuristicComponent for SpaceHeuristic {
+    fn name(&self) -> &'static str {
+        "Space"
+    }
+
+    fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
+        if phase.value() == 0 {
+            return 0;
+        }
+
+        let [white_pawns, black_pawns] = pawns(board);
+        let signature = board.material_signature();
+        let minors = |knight, bishop| (signature.count(knight) + signature.count(bishop)) as i16;
+
+        let white = Self::space(white_pawns, black_pawns, Color::White)
+            * minors(Piece::WhiteKnight, Piece::WhiteBishop);
+        let black = Self::space(black_pawns, white_pawns, Color::Black)
+            * minors(Piece::BlackKnight, Piece::BlackBishop);
+
+        TaperedScore::new(self.weight * (white - black), 0).interpolate(phase)
+    }
+}
+
+/// Heuristic component that rewards control of the center in the opening.
+///
+/// Central pawns and the central squares attacked by pawns and minor pieces
+/// are counted; the heavy pieces and the king aren't meant to fight for the
+/// center this early. The bonus is full with all the pieces on the board
+/// and fades out until half of the material is traded.
+pub struct CenterControlHeuristic {
+    pawn: i16,
+    attack: i16,
+    extended_attack: i16,
+}
+
+impl CenterControlHeuristic {
+    /// Creates the heuristic with the bonuses of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            pawn: params.center_pawn,
+            attack: params.center_attack,
+            extended_attack: params.extended_center_attack,
+        }
+    }
+
+    /// Gets the squares attacked by the minor pieces of both sides, White's
+    /// first.
+    fn minor_attacks(board: &ChessBoard) -> [u64; 2] {
+        let mut attacks = [0; 2];
+        board.piece_list.for_each_piece(|piece, square| {
+            let targets = &mut attacks[piece.get_color() as usize];
+            let mut mark = |target: i16| *targets |= 1 << board.map_to_standard_chess_board(target);
+
+            match piece.get_type() {
+                PieceType::Knight => directions::knight_targets(square)
+                    .iter()
+                    .for_each(|&target| mark(target)),
+                PieceType::Bishop => {
+                    for direction in DIAGONAL {
+                        for &target in directions::ray(square, direction) {
+                            mark(target);
+                            if !board.get_piece_on_square(target).is_empty() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        });
+        attacks
+    }
+
+    /// Scores the central pawns and attacks of a side.
+    fn center_control(&self, pawns: u64, attacked: u64) -> i16 {
+        (pawns & CENTER).count_ones() as i16 * self.pawn
+            + (attacked & CENTER).count_ones() as i16 * self.attack
+            + (attacked & EXTENDED_CENTER).count_ones() as i16 * self.extended_attack
+    }
+}
+
+impl HeuristicComponent for CenterControlHeuristic {
+    fn name(&self) -> &'static str {
+        "Center control"
+    }
+
+    fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
+        let opening = 2 * (phase.value() - TOTAL_PHASE / 2);
+        if opening <= 0 {
+            return 0;
+        }
+
+        let [white_pawns, black_pawns] = pawns(board);
+        let [white_minors, black_minors] = Self::minor_attacks(board);
+        let white = self.center_control(
+            white_pawns,
+            white_minors | pawn_attacks(white_pawns, Color::White),
+        );
+        let black = self.center_control(
+            black_pawns,
+            black_minors | pawn_attacks(black_pawns, Color::Black),
+        );
+
+        TaperedScore::new(white - black, 0).interpolate(&GamePhase::new(opening))
+    }
+}
@@ -0,0 +1,133 @@
+//! Trapped pieces.
+//!
+//! Some piece placements look fine to the piece-square tables but lose the
+//! piece or its activity in practice:
+//!
+//! - A bishop taking a pawn on a7 or h7 is shut in by the pawn pushed to b6
+//!   or g6, and is usually won after a7 or h7 is attacked again.
+//! - A knight on a8 or h8 has two squares to go to and is easily lost.
+//! - A rook next to its king in the corner, with the king unable to castle
+//!   anymore, stays out of play until the king walks away.
+//!
+//! The patterns are looked up from the squares of the pieces, so the cost is
+//! a handful of board lookups. Squares are standard squares given from
+//! White's side and mirrored for Black.
+
+use crate::game_state::ChessBoard;
+use crate::game_state::Color;
+use crate::game_state::Piece;
+use crate::game_state::PieceType;
+
+use super::params::EvalParams;
+use super::{GamePhase, HeuristicComponent, TaperedScore};
+
+/// Penalty for a bishop shut in on the seventh rank.
+pub(super) const TRAPPED_BISHOP: TaperedScore = TaperedScore::new(120, 100);
+
+/// Penalty for a knight in a corner of the enemy side.
+pub(super) const CORNERED_KNIGHT: TaperedScore = TaperedScore::new(50, 30);
+
+/// Penalty for a rook boxed in by its own king.
+pub(super) const BOXED_ROOK: TaperedScore = TaperedScore::new(50, 0);
+
+/// Trapped bishop patterns for White: the bishop square and the enemy pawn
+/// square shutting it in.
+const TRAPPED_BISHOP_SQUARES: [(usize, usize); 2] = [
+    (48, 41), // a7, pawn on b6
+    (55, 46), // h7, pawn on g6
+];
+
+/// Corners where a white knight is cornered, a8 and h8.
+const CORNER_SQUARES: [usize; 2] = [56, 63];
+
+/// Boxed rook patterns for White: the king squares, the rook squares and
+/// whether it is on the king side.
+const BOXED_ROOK_SQUARES: [(&[usize], &[usize], bool); 2] = [
+    (&[5, 6], &[6, 7], true),  // king on f1 or g1, rook on g1 or h1
+    (&[1, 2], &[0, 1], false), // king on b1 or c1, rook on a1 or b1
+];
+
+/// Heuristic component that penalizes trapped pieces.
+pub struct TrappedPiecesHeuristic {
+    trapped_bishop: TaperedScore,
+    cornered_knight: TaperedScore,
+    boxed_rook: TaperedScore,
+}
+
+impl TrappedPiecesHeuristic {
+    /// Creates the heuristic with the penalties of a parameter set.
+    pub fn new(params: &EvalParams) -> Self {
+        Self {
+            trapped_bishop: params.trapped_bishop,
+            cornered_knight: params.cornered_knight,
+            boxed_rook: params.boxed_rook,
+        }
+    }
+
+    /// Sums the penalties of the trapped pieces of a side.
+    fn penalties(&self, board: &ChessBoard, color: Color) -> TaperedScore {
+        // Squares are given from White's side
+        let square = |square: usize| match color {
+            Color::White => square,
+            Color::Black => square ^ 56,
+        };
+        let piece_on = |square: usize| {
+            board.get_piece_on_square(board.map_inner_to_outer_board(square as i16))
+        };
+        let is = |square: usize, piece_type| piece_on(square) == Piece::make(piece_type, color);
+        let is_enemy_pawn =
+            |square: usize| piece_on(square) == Piece::make(PieceType::Pawn, color.opposite());
+
+        let mut penalty = TaperedScore::new(0, 0);
+        let mut add = |term: TaperedScore| {
+            penalty.mg += term.mg;
+            penalty.eg += term.eg;
+        };
+
+        for (bishop, pawn) in TRAPPED_BISHOP_SQUARES {
+            if is(square(bishop), PieceType::Bishop) && is_enemy_pawn(square(pawn)) {
+                add(self.trapped_bishop);
+            }
+        }
+
+        for corner in CORNER_SQUARES {
+            if is(square(corner), PieceType::Knight) {
+                add(self.cornered_knight);
+            }
+        }
+
+        let rights = &board.castling_rights;
+        let (can_castle_kingside, can_castle_queenside) = match color {
+            Color::White => (rights.white_kingside, rights.white_queenside),
+            Color::Black => (rights.black_kingside, rights.black_queenside),
+        };
+        for (kings, rooks, kingside) in BOXED_ROOK_SQUARES {
+            let can_castle = if kingside {
+                can_castle_kingside
+            } else {
+                can_castle_queenside
+            };
+            if !can_castle
+                && kings.iter().any(|&king| is(square(king), PieceType::King))
+                && rooks.iter().any(|&rook| is(square(rook), PieceType::Rook))
+            {
+                add(self.boxed_rook);
+            }
+        }
+
+        penalty
+    }
+}
+
+impl HeuristicComponent for TrappedPiecesHeuristic {
+    fn name(&self) -> &'static str {
+        "Trapped pieces"
+    }
+
+    fn score(&self, board: &ChessBoard, phase: &GamePhase) -> i16 {
+        let white = self.penalties(board, Color::White);
+        let black = self.penalties(board, Color::Black);
+
+        TaperedScore::new(black.mg - white.mg, black.eg - white.eg).interpolate(phase)
+    }
+}
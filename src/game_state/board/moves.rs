@@ -3,15 +3,25 @@
 //! This module provides the Move struct for representing chess moves and
 //! conversion functions between different move notations (UCI, algebraic).
 
+use smallvec::SmallVec;
+
 use super::piece::{Color, Piece, PieceType};
 use crate::game_state::ChessBoard;
 use crate::game_state::board::CastlingInfo;
-use crate::game_state::board::CastlingRights;
+use crate::game_state::board::directions::named_square;
+
+// Squares the kings and rooks land on when castling
+const C1: i16 = named_square("c1");
+const D1: i16 = named_square("d1");
+const F1: i16 = named_square("f1");
+const G1: i16 = named_square("g1");
 
 /// Represents a chess move with all associated metadata.
 ///
-/// Stores information about the move itself, captured pieces, special moves
-/// (castling, en passant, promotion), and state needed for move unmaking.
+/// Stores information about the move itself, captured pieces and special
+/// moves (castling, en passant, promotion). The state needed to unmake it
+/// is kept by the board, so the same move can be replayed in any position
+/// it is legal in.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Move {
     /// Starting square of the moving piece
@@ -20,7 +30,9 @@ pub struct Move {
     pub to: i16,
     /// The piece being moved
     pub piece: Piece,
-    /// Piece captured by this move (EmptySquare if no capture)
+    /// Piece captured by this move (EmptySquare if no capture), the pawn
+    /// taken by an en passant capture although it isn't on the destination
+    /// square
     pub captured_piece: Piece,
     /// Promotion piece if this move promotes a pawn
     pub promotion: Option<Piece>,
@@ -30,10 +42,117 @@ pub struct Move {
     pub en_passant: bool,
     /// En passant target square set by double pawn moves
     pub en_passant_square: Option<i16>,
-    /// Previous en passant target for move unmaking
-    pub previous_en_passant: Option<i16>,
-    /// Previous castling rights for move unmaking
-    pub previous_castling_rights: Option<CastlingRights>,
+}
+
+/// Move packed into 32 bits for the search internals.
+///
+/// Holds only what identifies the move and what move ordering needs; the
+/// board keeps everything else needed to make and unmake it. Converting back
+/// to a [`Move`] requires the position the move was created in.
+///
+/// Bit layout:
+/// - Bits 0-5:   from square (0-63)
+/// - Bits 6-11:  to square (0-63)
+/// - Bits 12-15: promotion piece flags (queen=0x1, rook=0x2, bishop=0x4, knight=0x8)
+/// - Bit 16:     capture
+/// - Bit 17:     en passant
+/// - Bit 18:     castling
+/// - Bit 19:     double pawn push
+///
+/// The low 16 bits are the encoding stored in the transposition table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CompactMove(u32);
+
+impl CompactMove {
+    const CAPTURE: u32 = 1 << 16;
+    const EN_PASSANT: u32 = 1 << 17;
+    const CASTLING: u32 = 1 << 18;
+    const DOUBLE_PUSH: u32 = 1 << 19;
+
+    /// Starting square in standard 0-63 order.
+    pub fn from_square(self) -> usize {
+        (self.0 & 0b11_1111) as usize
+    }
+
+    /// Destination square in standard 0-63 order.
+    pub fn to_square(self) -> usize {
+        ((self.0 >> 6) & 0b11_1111) as usize
+    }
+
+    /// Whether the move captures a piece, en passant included.
+    pub fn is_capture(self) -> bool {
+        self.0 & (Self::CAPTURE | Self::EN_PASSANT) != 0
+    }
+
+    /// Whether the move is an en passant capture.
+    pub fn is_en_passant(self) -> bool {
+        self.0 & Self::EN_PASSANT != 0
+    }
+
+    /// Whether the move is a castling move.
+    pub fn is_castling(self) -> bool {
+        self.0 & Self::CASTLING != 0
+    }
+
+    /// Whether the move is a pawn moving two squares.
+    pub fn is_double_push(self) -> bool {
+        self.0 & Self::DOUBLE_PUSH != 0
+    }
+
+    /// Whether the move promotes a pawn.
+    pub fn is_promotion(self) -> bool {
+        self.encoded() >> 12 != 0
+    }
+
+    /// 16-bit encoding used by the transposition table, see [`Move::encode`].
+    pub fn encoded(self) -> u16 {
+        self.0 as u16
+    }
+}
+
+/// List of moves generated for a position.
+///
+/// Positions rarely have more than 64 legal moves, so the list lives on the
+/// stack and move generation doesn't allocate in the search.
+pub type MoveList = SmallVec<[Move; 64]>;
+
+/// Legal move described for library users.
+///
+/// Carries the notations and flags a frontend or bot usually needs, so they
+/// don't have to be derived again from the board internals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveRecord {
+    /// Move in UCI notation (e.g. "e7e8q")
+    pub uci: String,
+    /// Move in Standard Algebraic Notation (e.g. "e8=Q+")
+    pub san: String,
+    /// Kind of the piece being moved
+    pub piece: PieceType,
+    /// Whether the move captures a piece, en passant included
+    pub is_capture: bool,
+    /// Whether the move gives check, mates included
+    pub is_check: bool,
+    /// Whether the move promotes a pawn
+    pub is_promotion: bool,
+}
+
+impl MoveRecord {
+    /// Describes a legal move of the current position.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Mutable reference to the board before the move
+    /// * `mv` - A legal move in the current position
+    pub fn new(chess_board: &mut ChessBoard, mv: &Move) -> Self {
+        Self {
+            uci: mv.to_uci(chess_board),
+            san: mv.to_san(chess_board),
+            piece: mv.piece.get_type(),
+            is_capture: mv.is_capture(),
+            is_check: chess_board.gives_check(mv),
+            is_promotion: mv.promotion.is_some(),
+        }
+    }
 }
 
 /// Configuration for pawn moves
@@ -58,7 +177,6 @@ impl Move {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the current board state
     /// * `from` - Starting square
     /// * `to` - Destination square
     /// * `piece` - The pawn being moved
@@ -71,7 +189,6 @@ impl Move {
     ///
     /// A new Move instance configured for pawn movement
     pub fn create_pawn_move(
-        chess_board: &ChessBoard,
         from: i16,
         to: i16,
         piece: Piece,
@@ -87,8 +204,6 @@ impl Move {
             castling: None,
             en_passant: pawn_move_config.en_passant,
             en_passant_square: pawn_move_config.en_passant_square,
-            previous_en_passant: chess_board.get_en_passant_target(),
-            previous_castling_rights: Some(chess_board.castling_rights),
         }
     }
 
@@ -96,7 +211,6 @@ impl Move {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the current board state
     /// * `from` - Starting square
     /// * `to` - Destination square
     /// * `piece` - The piece being moved
@@ -105,13 +219,7 @@ impl Move {
     /// # Returns
     ///
     /// A new Move instance for standard piece movement
-    pub fn create_move(
-        chess_board: &ChessBoard,
-        from: i16,
-        to: i16,
-        piece: Piece,
-        captured: Piece,
-    ) -> Self {
+    pub fn create_move(from: i16, to: i16, piece: Piece, captured: Piece) -> Self {
         Self {
             from,
             to,
@@ -121,8 +229,6 @@ impl Move {
             castling: None,
             en_passant: false,
             en_passant_square: None,
-            previous_en_passant: chess_board.get_en_passant_target(),
-            previous_castling_rights: Some(chess_board.castling_rights),
         }
     }
 
@@ -130,7 +236,6 @@ impl Move {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the current board state
     /// * `king_from` - King's starting square
     /// * `king_to` - King's destination square
     /// * `king_piece` - The king piece
@@ -141,18 +246,12 @@ impl Move {
     ///
     /// A new Move instance configured for castling
     pub fn create_castling_move(
-        chess_board: &ChessBoard,
         king_from: i16,
         king_to: i16,
         king_piece: Piece,
         rook_from: i16,
         rook_to: i16,
     ) -> Self {
-        let color = if king_from == chess_board.algebraic_to_internal("e1") {
-            Color::White
-        } else {
-            Color::Black
-        };
         Self {
             from: king_from,
             to: king_to,
@@ -162,102 +261,65 @@ impl Move {
             castling: Some(CastlingInfo {
                 rook_from,
                 rook_to,
-                rook_piece: if color == Color::White {
-                    Piece::WhiteRook
-                } else {
-                    Piece::BlackRook
-                },
+                rook_piece: Piece::make(PieceType::Rook, king_piece.get_color()),
             }),
             en_passant: false,
             en_passant_square: None,
-            previous_en_passant: chess_board.get_en_passant_target(),
-            previous_castling_rights: Some(chess_board.castling_rights),
         }
     }
 
-    /// Detects if a move is a castling move based on piece type and squares.
+    /// Detects if a king move is a castling move.
+    ///
+    /// Castling is written either as the king moving two files to the g- or
+    /// c-file, or as the king capturing its own rook, as in Chess960 and in
+    /// Polyglot books. The rook is the castling rook of that side.
     ///
     /// # Arguments
     ///
     /// * `chess_board` - Reference to the current board state
     /// * `piece` - The piece being moved (must be a king)
     /// * `from` - Starting square
-    /// * `to` - Destination square
+    /// * `to` - Destination square, or the square of the rook
     ///
     /// # Returns
     ///
-    /// `Some(CastlingInfo)` if the move is castling, `None` otherwise
+    /// `Some((king_to, CastlingInfo))` with the square the king lands on if
+    /// the move is castling, `None` otherwise
     fn detect_castling(
         chess_board: &ChessBoard,
         piece: Piece,
         from: i16,
         to: i16,
-    ) -> Option<CastlingInfo> {
-        if piece.get_type() == PieceType::King {
-            // Kingside castling: e1-g1 or e8-g8
-            let white_king_from = chess_board.algebraic_to_internal("e1");
-            let white_king_to = chess_board.algebraic_to_internal("g1");
-
-            let black_king_from = chess_board.algebraic_to_internal("e8");
-            let black_king_to = chess_board.algebraic_to_internal("g8");
-
-            if (from == white_king_from && to == white_king_to)
-                || (from == black_king_from && to == black_king_to)
-            {
-                let (rook_from, rook_to) = if from == white_king_from {
-                    (
-                        chess_board.algebraic_to_internal("h1"),
-                        chess_board.algebraic_to_internal("f1"),
-                    )
-                } else {
-                    (
-                        chess_board.algebraic_to_internal("h8"),
-                        chess_board.algebraic_to_internal("f8"),
-                    )
-                };
-                let rook_piece = if piece.is_white() {
-                    Piece::WhiteRook
-                } else {
-                    Piece::BlackRook
-                };
-                return Some(CastlingInfo {
-                    rook_from,
-                    rook_to,
-                    rook_piece,
-                });
-            }
-
-            // Queenside castling: e1-c1 or e8-c8
-            let white_king_to = chess_board.algebraic_to_internal("c1");
-            let black_king_to = chess_board.algebraic_to_internal("c8");
-
-            if (from == white_king_from && to == white_king_to)
-                || (from == black_king_from && to == black_king_to)
-            {
-                let (rook_from, rook_to) = if from == white_king_from {
-                    (
-                        chess_board.algebraic_to_internal("a1"),
-                        chess_board.algebraic_to_internal("d1"),
-                    )
-                } else {
-                    (
-                        chess_board.algebraic_to_internal("a8"),
-                        chess_board.algebraic_to_internal("d8"),
-                    )
-                };
-                let rook_piece = if piece.is_white() {
-                    Piece::WhiteRook
-                } else {
-                    Piece::BlackRook
-                };
-                return Some(CastlingInfo {
-                    rook_from,
-                    rook_to,
-                    rook_piece,
-                });
-            }
+    ) -> Option<(i16, CastlingInfo)> {
+        if !piece.is_valid_piece()
+            || piece.get_type() != PieceType::King
+            || chess_board.square_rank(from) != chess_board.square_rank(to)
+        {
+            return None;
         }
-        None
+
+        let color = piece.get_color();
+        let rook_piece = Piece::make(PieceType::Rook, color);
+        let (kingside, rook_from) = if chess_board.get_piece_on_square(to) == rook_piece {
+            (to > from, to)
+        } else {
+            let kingside = match to - from {
+                2 if chess_board.square_file(to) == chess_board.square_file(G1) => true,
+                -2 if chess_board.square_file(to) == chess_board.square_file(C1) => false,
+                _ => return None,
+            };
+            (kingside, chess_board.castling_rook_square(color, kingside))
+        };
+
+        let (king_to, rook_to) = if kingside { (G1, F1) } else { (C1, D1) };
+        Some((
+            chess_board.on_rank_of(from, king_to),
+            CastlingInfo {
+                rook_from,
+                rook_to: chess_board.on_rank_of(from, rook_to),
+                rook_piece,
+            },
+        ))
     }
 
     /// Detects if a move is an en passant capture.
@@ -285,14 +347,17 @@ impl Move {
             && captured == Piece::EmptySquare
             && let Some(ep_target) = chess_board.get_en_passant_target()
         {
-            // Check if this is an en passant capture
-            let expected_from = if piece.is_white() {
+            // The capturing pawn stands on the rank behind the target, on
+            // either file next to it
+            let behind_target = if piece.is_white() {
                 ep_target - chess_board.board_width // White pawn was one rank below
             } else {
                 ep_target + chess_board.board_width // Black pawn was one rank above
             };
 
-            return from == expected_from && to == ep_target;
+            return to == ep_target
+                && chess_board.square_rank(from) == chess_board.square_rank(behind_target)
+                && (chess_board.square_file(from) - chess_board.square_file(to)).abs() == 1;
         }
         false
     }
@@ -329,12 +394,10 @@ impl Move {
     ///
     /// `Some(i16)` with 0-63 square index if valid, `None` otherwise
     pub fn notation_to_square(square_notation: &str) -> Option<i16> {
-        if square_notation.len() != 2 {
+        let mut chars = square_notation.chars();
+        let (Some(file), Some(rank), None) = (chars.next(), chars.next(), chars.next()) else {
             return None;
-        }
-
-        let file = square_notation.chars().nth(0).unwrap();
-        let rank = square_notation.chars().nth(1).unwrap();
+        };
 
         if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
             return None;
@@ -354,13 +417,26 @@ impl Move {
     ) -> Option<Self> {
         let moving_piece = chess_board.get_piece_on_square(from);
 
-        // Get captured piece
-        let captured_piece = chess_board.get_piece_on_square(to);
-
+        // Castling written as the king capturing its rook lands elsewhere
         let castling = Self::detect_castling(chess_board, moving_piece, from, to);
+        let (to, castling) = match castling {
+            Some((king_to, castling)) => (king_to, Some(castling)),
+            None => (to, None),
+        };
+        let target = if castling.is_some() {
+            Piece::EmptySquare
+        } else {
+            chess_board.get_piece_on_square(to)
+        };
 
-        let en_passant =
-            Self::detect_en_passant(chess_board, moving_piece, from, to, captured_piece);
+        let en_passant = Self::detect_en_passant(chess_board, moving_piece, from, to, target);
+
+        // The pawn taken en passant stands next to the capturing pawn
+        let captured_piece = if en_passant {
+            Piece::make(PieceType::Pawn, moving_piece.get_color().opposite())
+        } else {
+            target
+        };
 
         // Detect en passant target square for double pawn moves
         let en_passant_square = if moving_piece.get_type() == PieceType::Pawn {
@@ -392,8 +468,6 @@ impl Move {
             castling,
             en_passant,
             en_passant_square,
-            previous_en_passant: chess_board.get_en_passant_target(),
-            previous_castling_rights: Some(chess_board.castling_rights),
         })
     }
 
@@ -411,14 +485,9 @@ impl Move {
     ///
     /// `Some(Move)` if the notation is valid, `None` otherwise
     pub fn parse_algebraic_move(chess_board: &ChessBoard, uci_notation: &str) -> Option<Self> {
-        if uci_notation.len() < 4 {
-            return None;
-        }
-
-        let from =
-            chess_board.map_inner_to_outer_board(Self::notation_to_square(&uci_notation[0..2])?);
-        let to =
-            chess_board.map_inner_to_outer_board(Self::notation_to_square(&uci_notation[2..4])?);
+        // Slicing by bytes, `get` rejects text cut inside a character
+        let from = chess_board.algebraic_to_internal(uci_notation.get(0..2)?)?;
+        let to = chess_board.algebraic_to_internal(uci_notation.get(2..4)?)?;
 
         // Get the moving piece from the board
         let moving_piece = chess_board.get_piece_on_square(from);
@@ -427,7 +496,7 @@ impl Move {
         }
 
         let promotion = if uci_notation.len() == 5 {
-            match &uci_notation[4..5] {
+            match uci_notation.get(4..5)? {
                 "q" => Some(if moving_piece.is_white() {
                     Piece::WhiteQueen
                 } else {
@@ -459,6 +528,8 @@ impl Move {
 
     /// Converts the move to UCI algebraic notation.
     ///
+    /// In Chess960 mode castling is written as the king capturing its rook.
+    ///
     /// # Arguments
     ///
     /// * `chess_board` - Reference to the current board state
@@ -468,24 +539,186 @@ impl Move {
     /// UCI string representation of the move
     pub fn to_uci(&self, chess_board: &ChessBoard) -> String {
         let from_square = Self::square_to_notation(chess_board, self.from);
-        let to_square = Self::square_to_notation(chess_board, self.to);
-
-        let promotion_suffix = if let Some(promo_piece) = self.promotion {
-            match promo_piece.get_type() {
-                PieceType::Queen => "q",
-                PieceType::Rook => "r",
-                PieceType::Bishop => "b",
-                PieceType::Knight => "n",
-                _ => "",
-            }
-        } else {
-            ""
+        let to = match &self.castling {
+            Some(castling) if chess_board.is_chess960() => castling.rook_from,
+            _ => self.to,
         };
+        let to_square = Self::square_to_notation(chess_board, to);
+
+        let promotion_suffix = self
+            .promotion
+            .map(|promo_piece| promo_piece.get_type().to_char().to_string())
+            .unwrap_or_default();
 
         format!("{}{}{}", from_square, to_square, promotion_suffix)
     }
 
-    /// Checks if this move is a capture.
+    /// Parses a move in Standard Algebraic Notation (SAN).
+    ///
+    /// Accepts the usual SAN forms (`e4`, `Nbd7`, `exd5`, `e8=Q`, `O-O`),
+    /// with or without check and annotation suffixes. Castling may also be
+    /// written with zeros (`0-0`).
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Mutable reference to the current board state
+    /// * `san` - Move in SAN
+    /// * `color` - Color of the side to move
+    ///
+    /// # Returns
+    ///
+    /// `Some(Move)` if the notation matches exactly one legal move, `None` otherwise
+    pub fn parse_san(chess_board: &mut ChessBoard, san: &str, color: Color) -> Option<Self> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        let legal_moves = chess_board.generate_moves(color);
+
+        // Castling is matched on the king's destination file
+        let castling_file = match san {
+            "O-O" | "0-0" => Some(6),
+            "O-O-O" | "0-0-0" => Some(2),
+            _ => None,
+        };
+        if let Some(file) = castling_file {
+            return legal_moves.into_iter().find(|mv| {
+                mv.castling.is_some()
+                    && chess_board.square_file(mv.to) - (chess_board.board_width - 8) / 2 == file
+            });
+        }
+
+        let mut chars: Vec<char> = san.chars().filter(|&c| c != 'x' && c != '=').collect();
+
+        // Piece letters are uppercase, a lowercase `b` is a file
+        let first = *chars.first()?;
+        let piece_type = Some(first)
+            .filter(char::is_ascii_uppercase)
+            .and_then(PieceType::from_char)
+            .unwrap_or(PieceType::Pawn);
+        if piece_type != PieceType::Pawn {
+            chars.remove(0);
+        }
+
+        let promotion = PieceType::from_char(*chars.last()?)
+            .filter(|piece_type| !matches!(piece_type, PieceType::Pawn | PieceType::King));
+        if promotion.is_some() {
+            chars.pop();
+        }
+
+        if chars.len() < 2 {
+            return None;
+        }
+        let destination: String = chars[chars.len() - 2..].iter().collect();
+        let to = chess_board.map_inner_to_outer_board(Self::notation_to_square(&destination)?);
+
+        // Whatever is left in front of the destination disambiguates the origin
+        let disambiguation = &chars[..chars.len() - 2];
+        let from_file = disambiguation.iter().find(|c| ('a'..='h').contains(c));
+        let from_rank = disambiguation.iter().find(|c| ('1'..='8').contains(c));
+
+        let mut candidates = legal_moves.into_iter().filter(|mv| {
+            let from_notation = Self::square_to_notation(chess_board, mv.from);
+            let mut from_chars = from_notation.chars();
+            let file = from_chars.next();
+            let rank = from_chars.next();
+
+            mv.to == to
+                && mv.castling.is_none()
+                && mv.piece.get_type() == piece_type
+                && mv.promotion.map(|piece| piece.get_type()) == promotion
+                && from_file.is_none_or(|&c| file == Some(c))
+                && from_rank.is_none_or(|&c| rank == Some(c))
+        });
+
+        let mv = candidates.next()?;
+        if candidates.next().is_some() {
+            // Ambiguous notation
+            return None;
+        }
+
+        Some(mv)
+    }
+
+    /// Converts the move to Standard Algebraic Notation (SAN).
+    ///
+    /// Includes the disambiguation needed among the legal moves, and a `+`
+    /// or `#` suffix when the move gives check or mate.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Mutable reference to the board before the move
+    ///
+    /// # Returns
+    ///
+    /// SAN string representation of the move
+    pub fn to_san(&self, chess_board: &mut ChessBoard) -> String {
+        let color = self.piece.get_color();
+        let mut san = String::new();
+
+        if let Some(castling) = &self.castling {
+            if castling.rook_to > self.to {
+                san.push_str("O-O-O");
+            } else {
+                san.push_str("O-O");
+            }
+        } else {
+            let to_notation = Self::square_to_notation(chess_board, self.to);
+            let from_notation = Self::square_to_notation(chess_board, self.from);
+            let is_capture = self.is_capture();
+
+            if self.piece.get_type() == PieceType::Pawn {
+                if is_capture {
+                    san.push_str(&from_notation[0..1]);
+                }
+            } else {
+                san.push(self.piece.get_type().to_char().to_ascii_uppercase());
+
+                // Other pieces of the same kind that can reach the same square
+                let others: MoveList = chess_board
+                    .generate_moves(color)
+                    .into_iter()
+                    .filter(|mv| mv.piece == self.piece && mv.to == self.to && mv.from != self.from)
+                    .collect();
+
+                if !others.is_empty() {
+                    let same_file = others.iter().any(|mv| {
+                        chess_board.square_file(mv.from) == chess_board.square_file(self.from)
+                    });
+                    let same_rank = others.iter().any(|mv| {
+                        chess_board.square_rank(mv.from) == chess_board.square_rank(self.from)
+                    });
+
+                    if !same_file {
+                        san.push_str(&from_notation[0..1]);
+                    } else if !same_rank {
+                        san.push_str(&from_notation[1..2]);
+                    } else {
+                        san.push_str(&from_notation);
+                    }
+                }
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&to_notation);
+
+            if let Some(promotion) = self.promotion {
+                san.push('=');
+                san.push(promotion.get_type().to_char().to_ascii_uppercase());
+            }
+        }
+
+        if chess_board.gives_check(self) {
+            chess_board.make_move(self);
+            let is_mate = chess_board.count_legal_moves(color.opposite()) == 0;
+            chess_board.unmake_move(self);
+
+            san.push(if is_mate { '#' } else { '+' });
+        }
+
+        san
+    }
+
+    /// Checks if this move is a capture, en passant included.
     ///
     /// # Returns
     ///
@@ -494,8 +727,52 @@ impl Move {
         self.captured_piece.is_valid_piece()
     }
 
+    /// Checks if this move promotes a pawn to a queen.
+    pub fn is_queen_promotion(&self) -> bool {
+        matches!(self.promotion, Some(Piece::WhiteQueen | Piece::BlackQueen))
+    }
+
+    /// Checks if this move changes the material on the board, as captures
+    /// and queen promotions do.
+    ///
+    /// These are the moves quiescence plays out. Underpromotions are left
+    /// out: they are only worth more than a queen promotion in the rare
+    /// positions the main search is there to find.
+    pub fn is_tactical(&self) -> bool {
+        self.is_capture() || self.is_queen_promotion()
+    }
+
+    /// Gets the pawn taken by an en passant capture and the square it stands on.
+    ///
+    /// The captured pawn sits one rank behind the destination square, from the
+    /// point of view of the capturing side, so the offset depends on the width
+    /// of the board representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `board_width` - Number of squares in a rank of the internal board
+    ///
+    /// # Returns
+    ///
+    /// `Some((square, pawn))` for en passant captures, `None` otherwise
+    pub fn en_passant_capture(&self, board_width: i16) -> Option<(i16, Piece)> {
+        if !self.en_passant {
+            return None;
+        }
+
+        Some(if self.piece.is_white() {
+            (self.to - board_width, Piece::BlackPawn)
+        } else {
+            (self.to + board_width, Piece::WhitePawn)
+        })
+    }
+
     /// Compress move to be more efficient on the transposition table
     ///
+    /// Castling is encoded as the king capturing its rook: in Chess960 the
+    /// king may land one square away, or on its own square, where a king
+    /// move would read the same.
+    ///
     /// # Returns
     /// u16 comprision of |from square 6 bits|to square 6 bits|captured piece 4 bits|
     pub fn encode(&self, chess_board: &ChessBoard) -> u16 {
@@ -504,7 +781,11 @@ impl Move {
         let from_square = chess_board.map_to_standard_chess_board(self.from) as u16;
         encoded_move |= from_square;
 
-        let to_square = chess_board.map_to_standard_chess_board(self.to) as u16;
+        let to = self
+            .castling
+            .as_ref()
+            .map_or(self.to, |castling| castling.rook_from);
+        let to_square = chess_board.map_to_standard_chess_board(to) as u16;
         encoded_move |= to_square << 6;
 
         if let Some(promotion) = self.promotion {
@@ -522,6 +803,16 @@ impl Move {
         encoded_move
     }
 
+    /// Decodes a move from its transposition table encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded_move` - Move encoded by [`Move::encode`]
+    /// * `chess_board` - Position the move was encoded in
+    ///
+    /// # Returns
+    ///
+    /// `Some(Move)` if there is a piece on the starting square, `None` otherwise
     pub fn decode(encoded_move: u16, chess_board: &ChessBoard) -> Option<Move> {
         let from_8x8 = (encoded_move & 0b11_1111) as i16;
         let to_8x8 = ((encoded_move >> 6) & 0b011_1111) as i16;
@@ -549,5 +840,49 @@ impl Move {
 
         let to = chess_board.map_inner_to_outer_board(to_8x8);
         Self::get_move_from_to_promotion(chess_board, from, to, promotion)
+            // Castling is only stored as the king capturing its rook
+            .filter(|mv| {
+                mv.castling
+                    .as_ref()
+                    .is_none_or(|castling| castling.rook_from == to)
+            })
+    }
+
+    /// Packs the move into its compact search representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Reference to the current board state
+    pub fn compact(&self, chess_board: &ChessBoard) -> CompactMove {
+        let mut compact = self.encode(chess_board) as u32;
+
+        if self.is_capture() {
+            compact |= CompactMove::CAPTURE;
+        }
+        if self.en_passant {
+            compact |= CompactMove::EN_PASSANT;
+        }
+        if self.castling.is_some() {
+            compact |= CompactMove::CASTLING;
+        }
+        if self.en_passant_square.is_some() {
+            compact |= CompactMove::DOUBLE_PUSH;
+        }
+
+        CompactMove(compact)
+    }
+
+    /// Unpacks a compact move in the position it was created in.
+    ///
+    /// # Arguments
+    ///
+    /// * `compact` - Compact move to unpack
+    /// * `chess_board` - Position the move was packed in
+    ///
+    /// # Returns
+    ///
+    /// `Some(Move)` if there is a piece on the starting square, `None` otherwise
+    pub fn from_compact(compact: CompactMove, chess_board: &ChessBoard) -> Option<Move> {
+        Self::decode(compact.encoded(), chess_board)
     }
 }
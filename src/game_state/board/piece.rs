@@ -10,7 +10,7 @@
 //!   (white or black), including helper methods like [`Color::opposite`].
 //! - The [`Piece`] enum, representing the concrete pieces (e.g. `WhitePawn`,
 //!   `BlackQueen`) plus sentinel and empty squares, with methods for
-//!   color/type queries, printing symbols, and checking relationships
+//!   color/type queries, FEN letters, and checking relationships
 //!   (friend, opponent, empty, sentinel).
 //!
 //! In short, this module is the foundation for representing pieces on the
@@ -20,15 +20,58 @@
 /// Represents the type of a chess piece, without its color.
 ///
 /// Used to differentiate between different movement patterns
-/// (e.g., `PieceType::Rook` vs `PieceType::Bishop`).
-#[derive(PartialEq)]
+/// (e.g., `PieceType::Rook` vs `PieceType::Bishop`). The types are in the
+/// same order as the pieces of each color in [`Piece`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PieceType {
-    King,
-    Queen,
-    Rook,
-    Bishop,
-    Knight,
     Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl PieceType {
+    /// Every piece type, from the pawn to the king.
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Pawn,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ];
+
+    /// Gets the piece type of a FEN letter, in either case.
+    ///
+    /// # Examples
+    /// ```
+    /// use enrust::game_state::PieceType;
+    /// assert_eq!(PieceType::from_char('n'), Some(PieceType::Knight));
+    /// assert_eq!(PieceType::from_char('x'), None);
+    /// ```
+    pub fn from_char(letter: char) -> Option<PieceType> {
+        let index = b"pnbrqk"
+            .iter()
+            .position(|&c| c as char == letter.to_ascii_lowercase())?;
+        Some(PieceType::ALL[index])
+    }
+
+    /// Returns the lowercase FEN letter of the piece type, as used for
+    /// promotions in UCI moves.
+    pub fn to_char(self) -> char {
+        b"pnbrqk"[self as usize] as char
+    }
+
+    /// Returns the material value of the piece type in centipawns.
+    ///
+    /// These are the nominal values, also the midgame values of the
+    /// evaluation.
+    pub const fn value(self) -> i16 {
+        [100, 300, 300, 500, 900, 20000][self as usize]
+    }
 }
 
 /// Represents the color of a piece or side to move.
@@ -83,6 +126,76 @@ pub enum Piece {
 }
 
 impl Piece {
+    /// Every piece, the white ones first, in the order of their
+    /// discriminants.
+    pub const ALL: [Piece; 12] = [
+        Piece::WhitePawn,
+        Piece::WhiteKnight,
+        Piece::WhiteBishop,
+        Piece::WhiteRook,
+        Piece::WhiteQueen,
+        Piece::WhiteKing,
+        Piece::BlackPawn,
+        Piece::BlackKnight,
+        Piece::BlackBishop,
+        Piece::BlackRook,
+        Piece::BlackQueen,
+        Piece::BlackKing,
+    ];
+
+    /// Returns the piece of a type and a color.
+    ///
+    /// # Examples
+    /// ```
+    /// use enrust::game_state::{Color, Piece, PieceType};
+    /// assert_eq!(Piece::make(PieceType::Rook, Color::Black), Piece::BlackRook);
+    /// ```
+    pub fn make(piece_type: PieceType, color: Color) -> Piece {
+        Piece::ALL[color as usize * 6 + piece_type as usize]
+    }
+
+    /// Returns the pieces of a color, from the pawn to the king.
+    pub fn of_color(color: Color) -> impl Iterator<Item = Piece> {
+        Piece::ALL[color as usize * 6..][..6].iter().copied()
+    }
+
+    /// Gets the piece of a FEN letter: uppercase for white, lowercase for
+    /// black.
+    ///
+    /// # Examples
+    /// ```
+    /// use enrust::game_state::Piece;
+    /// assert_eq!(Piece::from_char('Q'), Some(Piece::WhiteQueen));
+    /// assert_eq!(Piece::from_char('q'), Some(Piece::BlackQueen));
+    /// ```
+    pub fn from_char(letter: char) -> Option<Piece> {
+        let color = if letter.is_ascii_uppercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+
+        PieceType::from_char(letter).map(|piece_type| Piece::make(piece_type, color))
+    }
+
+    /// Returns the FEN letter of the piece, `.` for an empty square and `X`
+    /// for a sentinel square.
+    pub fn to_char(self) -> char {
+        match self {
+            Piece::EmptySquare => '.',
+            Piece::SentinelSquare => 'X',
+            _ => b"PNBRQKpnbrqk"[self as usize] as char,
+        }
+    }
+
+    /// Returns the material value of the piece in centipawns.
+    ///
+    /// # Panics
+    /// Panics if called on an empty or sentinel square.
+    pub fn value(self) -> i16 {
+        self.get_type().value()
+    }
+
     /// Returns the color of the piece.
     ///
     /// # Panics
@@ -100,36 +213,20 @@ impl Piece {
     /// # Panics
     /// Panics if called on an empty or sentinel square.
     pub fn get_type(self) -> PieceType {
-        match self {
-            Piece::WhitePawn | Piece::BlackPawn => PieceType::Pawn,
-            Piece::WhiteKnight | Piece::BlackKnight => PieceType::Knight,
-            Piece::WhiteBishop | Piece::BlackBishop => PieceType::Bishop,
-            Piece::WhiteRook | Piece::BlackRook => PieceType::Rook,
-            Piece::WhiteQueen | Piece::BlackQueen => PieceType::Queen,
-            Piece::WhiteKing | Piece::BlackKing => PieceType::King,
-            _ => panic!("Invalid piece"),
-        }
+        assert!(self.is_valid_piece(), "Invalid piece");
+        PieceType::ALL[self as usize % 6]
     }
 
     /// Returns the single-character string used to print the piece on a board.
     ///
     /// Empty squares are `"."`, sentinel squares are `"X"`.
     pub fn print_piece(&self) -> &str {
+        const SYMBOLS: &str = "PNBRQKpnbrqk";
+
         match self {
             Piece::EmptySquare => ".",
             Piece::SentinelSquare => "X",
-            Piece::WhitePawn => "P",
-            Piece::WhiteRook => "R",
-            Piece::WhiteKnight => "N",
-            Piece::WhiteBishop => "B",
-            Piece::WhiteQueen => "Q",
-            Piece::WhiteKing => "K",
-            Piece::BlackPawn => "p",
-            Piece::BlackRook => "r",
-            Piece::BlackKnight => "n",
-            Piece::BlackBishop => "b",
-            Piece::BlackQueen => "q",
-            Piece::BlackKing => "k",
+            _ => &SYMBOLS[*self as usize..][..1],
         }
     }
 
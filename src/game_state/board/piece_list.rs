@@ -4,7 +4,6 @@
 //! for each piece type and color, enabling efficient piece tracking and
 //! move generation without scanning the entire board.
 
-use smallvec::{SmallVec, smallvec};
 use std::collections::HashMap;
 
 use crate::game_state::board::ChessBoard;
@@ -12,7 +11,19 @@ use crate::game_state::board::Color;
 use crate::game_state::board::Move;
 use crate::game_state::board::Piece;
 use crate::game_state::board::PieceType;
-use crate::game_state::board::moves::PawnMoveConfig;
+use crate::game_state::board::directions::{self, DIAGONAL, Direction, ORTHOGONAL, named_square};
+use crate::game_state::board::evaluation::endgame::MaterialSignature;
+use crate::game_state::board::moves::{MoveList, PawnMoveConfig};
+
+// Squares on the pawn start and promotion ranks, and where castling lands
+const C1: i16 = named_square("c1");
+const D1: i16 = named_square("d1");
+const E1: i16 = named_square("e1");
+const F1: i16 = named_square("f1");
+const G1: i16 = named_square("g1");
+const E2: i16 = named_square("e2");
+const E7: i16 = named_square("e7");
+const E8: i16 = named_square("e8");
 
 /// Maintains separate lists of squares for each piece type and color.
 ///
@@ -46,6 +57,79 @@ pub struct PieceList {
     black_knight_list: Vec<i16>,
     /// Black pawn positions
     black_pawn_list: Vec<i16>,
+
+    /// Number of pieces of each kind, kept in step with the lists
+    material: MaterialSignature,
+}
+
+/// Destination of the generated moves.
+///
+/// Generators report each move with its destination square and a closure
+/// building it, so sinks that only count moves never construct them.
+trait MoveSink {
+    /// Adds a move landing on `to`, built by `create` if the sink keeps it.
+    fn add(&mut self, to: i16, create: impl FnOnce() -> Move);
+
+    /// Adds an en passant capture already checked to leave the king safe.
+    fn add_en_passant(&mut self, mv: Move) {
+        self.add(mv.to, || mv);
+    }
+
+    /// Whether the generators may skip the tests keeping the king safe.
+    fn ignores_king_safety(&self) -> bool {
+        false
+    }
+}
+
+impl MoveSink for MoveList {
+    fn add(&mut self, _to: i16, create: impl FnOnce() -> Move) {
+        self.push(create());
+    }
+}
+
+/// Counts moves without building them.
+#[derive(Default)]
+struct MoveCounter(usize);
+
+impl MoveSink for MoveCounter {
+    fn add(&mut self, _to: i16, _create: impl FnOnce() -> Move) {
+        self.0 += 1;
+    }
+}
+
+/// Collects moves whether or not they leave the king in check, for variants
+/// where the king can be captured or blown up.
+struct PseudoLegal<'a>(&'a mut MoveList);
+
+impl MoveSink for PseudoLegal<'_> {
+    fn add(&mut self, _to: i16, create: impl FnOnce() -> Move) {
+        self.0.push(create());
+    }
+
+    fn ignores_king_safety(&self) -> bool {
+        true
+    }
+}
+
+/// Keeps the moves that capture a checking piece or block its check.
+struct Evasions<'a, S: MoveSink> {
+    sink: &'a mut S,
+    /// Square of the checking piece and squares between it and the king
+    blocking_squares: &'a [i16],
+}
+
+impl<S: MoveSink> MoveSink for Evasions<'_, S> {
+    fn add(&mut self, to: i16, create: impl FnOnce() -> Move) {
+        if self.blocking_squares.contains(&to) {
+            self.sink.add(to, create);
+        }
+    }
+
+    // En passant captures land behind the pawn they take, and the pawn
+    // generation already checked that they leave the king safe
+    fn add_en_passant(&mut self, mv: Move) {
+        self.sink.add_en_passant(mv);
+    }
 }
 
 impl PieceList {
@@ -132,22 +216,86 @@ impl PieceList {
     ///
     /// # Returns
     ///
-    /// Vector of legal moves
-    pub fn generate_legal_moves(
-        &mut self,
+    /// List of legal moves
+    pub fn generate_legal_moves(&self, chess_board: &mut ChessBoard, color: Color) -> MoveList {
+        let mut moves = MoveList::new();
+        self.generate_legal(chess_board, color, &mut moves);
+        moves
+    }
+
+    /// Counts the legal moves for the given color without building them.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Mutable reference to the chess board
+    /// * `color` - Color to count moves for
+    ///
+    /// # Returns
+    ///
+    /// Number of legal moves, the length of
+    /// [`generate_legal_moves`](Self::generate_legal_moves)
+    pub fn count_legal_moves(&self, chess_board: &mut ChessBoard, color: Color) -> usize {
+        let mut counter = MoveCounter::default();
+        self.generate_legal(chess_board, color, &mut counter);
+        counter.0
+    }
+
+    /// Adds the legal moves for the given color to a sink, see
+    /// [`generate_legal_moves`](Self::generate_legal_moves).
+    fn generate_legal(
+        &self,
         chess_board: &mut ChessBoard,
         color: Color,
-    ) -> Vec<Move> {
+        moves: &mut impl MoveSink,
+    ) {
         let king_attackers = self.is_king_in_check(chess_board, color);
 
         if king_attackers.is_empty() {
-            self.generate_moves(chess_board, color)
+            self.generate_moves(chess_board, color, moves);
         } else if king_attackers.len() == 1 {
-            self.generate_attacker_captures(chess_board, king_attackers, color)
+            self.generate_attacker_captures(chess_board, king_attackers, color, moves);
         } else {
             // If multiple attackers, only king moves are possible
-            self.generate_king_moves(chess_board, color)
+            self.generate_king_moves(chess_board, color, moves);
+        }
+    }
+
+    /// Generates the moves of the given color without testing whether they
+    /// leave its king in check.
+    ///
+    /// Variants whose kings can be captured or blown up decide themselves
+    /// which of these moves are legal.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Mutable reference to the chess board
+    /// * `color` - Color to generate moves for
+    /// * `castling` - Whether castling moves are generated
+    ///
+    /// # Returns
+    ///
+    /// List of pseudo-legal moves
+    pub fn generate_pseudo_legal_moves(
+        &self,
+        chess_board: &mut ChessBoard,
+        color: Color,
+        castling: bool,
+    ) -> MoveList {
+        let mut moves = MoveList::new();
+        let mut sink = PseudoLegal(&mut moves);
+        let no_pins = HashMap::new();
+
+        self.generate_king_moves(chess_board, color, &mut sink);
+        if castling {
+            self.generate_castling_moves(chess_board, color, &mut sink);
         }
+        self.generate_queen_moves(chess_board, &no_pins, color, &mut sink);
+        self.generate_rook_moves(chess_board, &no_pins, color, &mut sink);
+        self.generate_bishop_moves(chess_board, &no_pins, color, &mut sink);
+        self.generate_knight_moves(chess_board, &no_pins, color, &mut sink);
+        self.generate_pawn_moves(chess_board, &no_pins, color, &mut sink);
+
+        moves
     }
 
     /// Generates moves when the king is in single check.
@@ -162,21 +310,17 @@ impl PieceList {
     /// * `chess_board` - Mutable reference to the chess board
     /// * `king_attackers` - Information about the checking piece
     /// * `color` - Color to generate moves for
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal evasion moves
+    /// * `moves` - Sink the evasions are added to
     fn generate_attacker_captures(
-        &mut self,
+        &self,
         chess_board: &mut ChessBoard,
         king_attackers: Vec<(Piece, i16)>,
         color: Color,
-    ) -> Vec<Move> {
-        let mut valid_moves = Vec::new();
-
+        moves: &mut impl MoveSink,
+    ) {
         let Some(king_square) = self.get_king_square(color) else {
-            // If there's no king than return empty move list
-            return valid_moves;
+            // If there's no king there are no moves to add
+            return;
         };
 
         let (attacker_piece, attacker_square) = &king_attackers[0];
@@ -194,18 +338,18 @@ impl PieceList {
 
         let pinned_pieces = self.detect_pinned_pieces(chess_board, color);
 
-        // Generate moves for all piece types
-        valid_moves = self.generate_queen_moves(chess_board, &pinned_pieces, color);
-        valid_moves.append(&mut self.generate_rook_moves(chess_board, &pinned_pieces, color));
-        valid_moves.append(&mut self.generate_bishop_moves(chess_board, &pinned_pieces, color));
-        valid_moves.append(&mut self.generate_knight_moves(chess_board, &pinned_pieces, color));
-        valid_moves.append(&mut self.generate_pawn_moves(chess_board, &pinned_pieces, color));
-
         // Only consider moves that block the attacker or capture it
-        valid_moves.retain(|mv| blocking_squares.contains(&mv.to));
-        valid_moves.append(&mut self.generate_king_moves(chess_board, color));
+        let mut evasions = Evasions {
+            sink: moves,
+            blocking_squares: &blocking_squares,
+        };
+        self.generate_queen_moves(chess_board, &pinned_pieces, color, &mut evasions);
+        self.generate_rook_moves(chess_board, &pinned_pieces, color, &mut evasions);
+        self.generate_bishop_moves(chess_board, &pinned_pieces, color, &mut evasions);
+        self.generate_knight_moves(chess_board, &pinned_pieces, color, &mut evasions);
+        self.generate_pawn_moves(chess_board, &pinned_pieces, color, &mut evasions);
 
-        valid_moves
+        self.generate_king_moves(chess_board, color, moves);
     }
 
     /// Generates all legal moves for the given color.
@@ -217,22 +361,22 @@ impl PieceList {
     ///
     /// * `chess_board` - Mutable reference to the chess board
     /// * `color` - Color to generate moves for
-    ///
-    /// # Returns
-    ///
-    /// Vector of pseudo-legal moves
-    fn generate_moves(&mut self, chess_board: &mut ChessBoard, color: Color) -> Vec<Move> {
+    /// * `moves` - Sink the moves are added to
+    fn generate_moves(
+        &self,
+        chess_board: &mut ChessBoard,
+        color: Color,
+        moves: &mut impl MoveSink,
+    ) {
         let pinned_pieces = self.detect_pinned_pieces(chess_board, color);
 
-        let mut all_moves = self.generate_king_moves(chess_board, color);
-        all_moves.append(&mut self.generate_castling_moves(chess_board, color));
-        all_moves.append(&mut self.generate_queen_moves(chess_board, &pinned_pieces, color));
-        all_moves.append(&mut self.generate_rook_moves(chess_board, &pinned_pieces, color));
-        all_moves.append(&mut self.generate_bishop_moves(chess_board, &pinned_pieces, color));
-        all_moves.append(&mut self.generate_knight_moves(chess_board, &pinned_pieces, color));
-        all_moves.append(&mut self.generate_pawn_moves(chess_board, &pinned_pieces, color));
-
-        all_moves
+        self.generate_king_moves(chess_board, color, moves);
+        self.generate_castling_moves(chess_board, color, moves);
+        self.generate_queen_moves(chess_board, &pinned_pieces, color, moves);
+        self.generate_rook_moves(chess_board, &pinned_pieces, color, moves);
+        self.generate_bishop_moves(chess_board, &pinned_pieces, color, moves);
+        self.generate_knight_moves(chess_board, &pinned_pieces, color, moves);
+        self.generate_pawn_moves(chess_board, &pinned_pieces, color, moves);
     }
 
     /// Generates king moves with safety checks.
@@ -244,57 +388,41 @@ impl PieceList {
     ///
     /// * `chess_board` - Mutable reference to the chess board
     /// * `color` - Color of the king to move
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal king moves
-    fn generate_king_moves(&mut self, chess_board: &mut ChessBoard, color: Color) -> Vec<Move> {
-        let mut moves = Vec::new();
+    /// * `moves` - Sink the king moves are added to
+    fn generate_king_moves(
+        &self,
+        chess_board: &mut ChessBoard,
+        color: Color,
+        moves: &mut impl MoveSink,
+    ) {
         let (king, king_list) = match color {
             Color::White => (Piece::WhiteKing, &self.white_king_list),
             Color::Black => (Piece::BlackKing, &self.black_king_list),
         };
 
-        let king_rays: [i16; 8] = [
-            -1,
-            1,
-            chess_board.board_width,
-            -chess_board.board_width,
-            chess_board.board_width + 1,
-            -chess_board.board_width + 1,
-            chess_board.board_width - 1,
-            -chess_board.board_width - 1,
-        ];
-
         for &square in king_list {
-            for ray in king_rays {
-                let position = square + ray;
-
-                // Remove the king to not have the king blocking a square that would otherwise being attacked
-                chess_board.set_piece_on_square(Piece::EmptySquare, square);
-                // If king will be in check in this position, don't add to possible moves
-                if self.is_square_attacked(chess_board, position, color.opposite()) {
+            for &position in directions::king_targets(square) {
+                if !moves.ignores_king_safety() {
+                    // Remove the king to not have the king blocking a square that would otherwise being attacked
+                    chess_board.set_piece_on_square(Piece::EmptySquare, square);
+                    // If king will be in check in this position, don't add to possible moves
+                    if self.is_square_attacked(chess_board, position, color.opposite()) {
+                        // Restore king on the board
+                        chess_board.set_piece_on_square(king, square);
+                        continue;
+                    }
                     // Restore king on the board
                     chess_board.set_piece_on_square(king, square);
-                    continue;
                 }
-                // Restore king on the board
-                chess_board.set_piece_on_square(king, square);
 
                 let target = chess_board.get_piece_on_square(position);
                 if target.is_empty() || target.is_opponent(color) {
-                    moves.push(Move::create_move(
-                        chess_board,
-                        square,
-                        position,
-                        king,
-                        target,
-                    ));
+                    moves.add(position, || {
+                        Move::create_move(square, position, king, target)
+                    });
                 }
             }
         }
-
-        moves
     }
 
     /// Generates queen moves considering pin constraints.
@@ -304,73 +432,26 @@ impl PieceList {
     /// * `chess_board` - Mutable reference to the chess board
     /// * `pinned_pieces` - Map of pinned pieces and their pin directions
     /// * `color` - Color of the queens to move
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal queen moves
+    /// * `moves` - Sink the queen moves are added to
     fn generate_queen_moves(
-        &mut self,
+        &self,
         chess_board: &mut ChessBoard,
-        pinned_pieces: &HashMap<i16, i16>,
+        pinned_pieces: &HashMap<i16, Direction>,
         color: Color,
-    ) -> Vec<Move> {
-        let mut moves = Vec::new();
+        moves: &mut impl MoveSink,
+    ) {
         let (queen, queen_list) = match color {
             Color::White => (Piece::WhiteQueen, &self.white_queen_list),
             Color::Black => (Piece::BlackQueen, &self.black_queen_list),
         };
 
-        let queen_possible_rays: SmallVec<[i16; 4]> = smallvec![
-            -1,
-            1,
-            chess_board.board_width,
-            -chess_board.board_width,
-            chess_board.board_width + 1,
-            -chess_board.board_width + 1,
-            chess_board.board_width - 1,
-            -chess_board.board_width - 1,
-        ];
-
-        for &square in queen_list {
-            let mut queen_rays = queen_possible_rays.clone();
-
-            if let Some(pin_direction) = pinned_pieces.get(&square) {
-                queen_rays.retain(|dir| (*dir == *pin_direction) || (*dir == -*pin_direction));
-            }
-
-            for ray in &queen_rays {
-                let mut position = square + ray;
-                loop {
-                    let target = chess_board.get_piece_on_square(position);
-                    if target.is_empty() {
-                        moves.push(Move::create_move(
-                            chess_board,
-                            square,
-                            position,
-                            queen,
-                            target,
-                        ));
-                    } else if target.is_opponent(color) {
-                        moves.push(Move::create_move(
-                            chess_board,
-                            square,
-                            position,
-                            queen,
-                            target,
-                        ));
-                        break;
-                    }
-
-                    if target.is_sentinel() || target.is_friend(color) {
-                        break;
-                    }
-
-                    position += ray;
-                }
-            }
-        }
-
-        moves
+        self.generate_sliding_moves(
+            chess_board,
+            pinned_pieces,
+            (queen, queen_list),
+            &directions::ALL,
+            moves,
+        );
     }
 
     /// Generates rook moves considering pin constraints.
@@ -380,67 +461,26 @@ impl PieceList {
     /// * `chess_board` - Mutable reference to the chess board
     /// * `pinned_pieces` - Map of pinned pieces and their pin directions
     /// * `color` - Color of the rooks to move
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal rook moves
+    /// * `moves` - Sink the rook moves are added to
     fn generate_rook_moves(
-        &mut self,
+        &self,
         chess_board: &mut ChessBoard,
-        pinned_pieces: &HashMap<i16, i16>,
+        pinned_pieces: &HashMap<i16, Direction>,
         color: Color,
-    ) -> Vec<Move> {
-        let mut moves = Vec::new();
-
+        moves: &mut impl MoveSink,
+    ) {
         let (rook, rook_list) = match color {
             Color::White => (Piece::WhiteRook, &self.white_rook_list),
             Color::Black => (Piece::BlackRook, &self.black_rook_list),
         };
 
-        let rook_possible_rays: SmallVec<[i16; 4]> =
-            smallvec![1, -1, -chess_board.board_width, chess_board.board_width];
-
-        for &square in rook_list {
-            let mut rook_rays = rook_possible_rays.clone();
-
-            if let Some(pin_direction) = pinned_pieces.get(&square) {
-                rook_rays.retain(|dir| (*dir == *pin_direction) || (*dir == -*pin_direction));
-            }
-
-            for ray in &rook_rays {
-                let mut position = square + ray;
-                loop {
-                    let target = chess_board.get_piece_on_square(position);
-                    if target.is_empty() {
-                        moves.push(Move::create_move(
-                            chess_board,
-                            square,
-                            position,
-                            rook,
-                            target,
-                        ));
-                    } else if target.is_opponent(color) {
-                        moves.push(Move::create_move(
-                            chess_board,
-                            square,
-                            position,
-                            rook,
-                            target,
-                        ));
-                        // If there is an enemy in this square, the rook can't go further
-                        break;
-                    }
-
-                    if target.is_sentinel() || target.is_friend(color) {
-                        break;
-                    }
-
-                    position += ray;
-                }
-            }
-        }
-
-        moves
+        self.generate_sliding_moves(
+            chess_board,
+            pinned_pieces,
+            (rook, rook_list),
+            &ORTHOGONAL,
+            moves,
+        );
     }
 
     /// Generates bishop moves considering pin constraints.
@@ -450,71 +490,74 @@ impl PieceList {
     /// * `chess_board` - Mutable reference to the chess board
     /// * `pinned_pieces` - Map of pinned pieces and their pin directions
     /// * `color` - Color of the bishops to move
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal bishop moves
+    /// * `moves` - Sink the bishop moves are added to
     fn generate_bishop_moves(
-        &mut self,
+        &self,
         chess_board: &mut ChessBoard,
-        pinned_pieces: &HashMap<i16, i16>,
+        pinned_pieces: &HashMap<i16, Direction>,
         color: Color,
-    ) -> Vec<Move> {
-        let mut moves = Vec::new();
-
+        moves: &mut impl MoveSink,
+    ) {
         let (bishop, bishop_list) = match color {
             Color::White => (Piece::WhiteBishop, &self.white_bishop_list),
             Color::Black => (Piece::BlackBishop, &self.black_bishop_list),
         };
 
-        let bishop_possible_rays: SmallVec<[i16; 4]> = smallvec![
-            chess_board.board_width + 1,
-            chess_board.board_width - 1,
-            -chess_board.board_width + 1,
-            -chess_board.board_width - 1,
-        ];
-
-        for &square in bishop_list {
-            let mut bishop_rays = bishop_possible_rays.clone();
-            // If piece is pinned it can only move along pin direction
-            if let Some(pin_direction) = pinned_pieces.get(&square) {
-                bishop_rays.retain(|dir| (*dir == *pin_direction) || (*dir == -*pin_direction));
-            }
+        self.generate_sliding_moves(
+            chess_board,
+            pinned_pieces,
+            (bishop, bishop_list),
+            &DIAGONAL,
+            moves,
+        );
+    }
 
-            for ray in &bishop_rays {
-                let mut position = square + ray;
+    /// Generates the moves of a sliding piece along the rays of its
+    /// directions, keeping pinned pieces on their pin line.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Mutable reference to the chess board
+    /// * `pinned_pieces` - Map of pinned pieces and their pin directions
+    /// * `(piece, squares)` - Sliding piece to move and its squares
+    /// * `piece_directions` - Directions the piece slides in
+    /// * `moves` - Sink the moves are added to
+    fn generate_sliding_moves(
+        &self,
+        chess_board: &mut ChessBoard,
+        pinned_pieces: &HashMap<i16, Direction>,
+        (piece, squares): (Piece, &[i16]),
+        piece_directions: &[Direction],
+        moves: &mut impl MoveSink,
+    ) {
+        let color = piece.get_color();
+
+        for &square in squares {
+            let pin_direction = pinned_pieces.get(&square);
+
+            for &direction in piece_directions {
+                // If piece is pinned it can only move along pin direction
+                if pin_direction.is_some_and(|pin| !direction.is_parallel(*pin)) {
+                    continue;
+                }
 
-                loop {
+                for &position in directions::ray(square, direction) {
                     let target = chess_board.get_piece_on_square(position);
-                    if target.is_empty() {
-                        moves.push(Move::create_move(
-                            chess_board,
-                            square,
-                            position,
-                            bishop,
-                            target,
-                        ));
-                    } else if target.is_opponent(color) {
-                        moves.push(Move::create_move(
-                            chess_board,
-                            square,
-                            position,
-                            bishop,
-                            target,
-                        ));
+                    if target.is_friend(color) {
                         break;
                     }
 
-                    if target.is_sentinel() || target.is_friend(color) {
+                    moves.add(position, || {
+                        Move::create_move(square, position, piece, target)
+                    });
+
+                    // If there is an enemy in this square, the piece can't go further
+                    if !target.is_empty() {
                         break;
                     }
-
-                    position += ray;
                 }
             }
         }
-
-        moves
     }
 
     /// Generates knight moves considering pin constraints.
@@ -526,55 +569,34 @@ impl PieceList {
     /// * `chess_board` - Mutable reference to the chess board
     /// * `pinned_pieces` - Map of pinned pieces and their pin directions
     /// * `color` - Color of the knights to move
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal knight moves
+    /// * `moves` - Sink the knight moves are added to
     fn generate_knight_moves(
-        &mut self,
+        &self,
         chess_board: &mut ChessBoard,
-        pinned_pieces: &HashMap<i16, i16>,
+        pinned_pieces: &HashMap<i16, Direction>,
         color: Color,
-    ) -> Vec<Move> {
-        let mut moves = Vec::new();
-
+        moves: &mut impl MoveSink,
+    ) {
         let (knight, knight_list) = match color {
             Color::White => (Piece::WhiteKnight, &self.white_knight_list),
             Color::Black => (Piece::BlackKnight, &self.black_knight_list),
         };
 
-        let knight_rays: [i16; 8] = [
-            2 * chess_board.board_width + 1,
-            2 * chess_board.board_width - 1,
-            -2 * chess_board.board_width + 1,
-            -2 * chess_board.board_width - 1,
-            chess_board.board_width + 2,
-            chess_board.board_width - 2,
-            -chess_board.board_width + 2,
-            -chess_board.board_width - 2,
-        ];
-
         for &square in knight_list {
             // Knights can't move if pinned (they jump, so any pin makes all moves illegal)
             if pinned_pieces.contains_key(&square) {
                 continue;
             }
 
-            for ray in knight_rays {
-                let target = chess_board.get_piece_on_square(square + ray);
+            for &position in directions::knight_targets(square) {
+                let target = chess_board.get_piece_on_square(position);
                 if target.is_empty() || target.is_opponent(color) {
-                    moves.push(Move::create_move(
-                        chess_board,
-                        square,
-                        square + ray,
-                        knight,
-                        target,
-                    ));
+                    moves.add(position, || {
+                        Move::create_move(square, position, knight, target)
+                    });
                 }
             }
         }
-
-        moves
     }
 
     /// Generates pawn moves considering pin constraints and special rules.
@@ -587,30 +609,24 @@ impl PieceList {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Mutable reference to the chess board
+    /// * `chess_board` - Reference to the chess board
     /// * `pinned_pieces` - Map of pinned pieces and their pin directions
     /// * `color` - Color of the pawns to move
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal pawn moves
+    /// * `moves` - Sink the pawn moves are added to
     fn generate_pawn_moves(
-        &mut self,
-        chess_board: &mut ChessBoard,
-        pinned_pieces: &HashMap<i16, i16>,
+        &self,
+        chess_board: &ChessBoard,
+        pinned_pieces: &HashMap<i16, Direction>,
         color: Color,
-    ) -> Vec<Move> {
-        let mut moves = Vec::new();
-
+        moves: &mut impl MoveSink,
+    ) {
         let (pawn, pawn_list) = match color {
             Color::White => (Piece::WhitePawn, &self.white_pawn_list),
             Color::Black => (Piece::BlackPawn, &self.black_pawn_list),
         };
 
-        let direction: i16 = match color {
-            Color::White => chess_board.board_width,
-            Color::Black => -chess_board.board_width,
-        };
+        let forward = Direction::pawn_push(color);
+        let direction = forward.offset();
 
         let promotion_pieces = match color {
             Color::White => [
@@ -625,311 +641,188 @@ impl PieceList {
                 Piece::BlackBishop,
                 Piece::BlackKnight,
             ],
-        };
+        }
+        .map(Some);
 
         let promotion_rank = match color {
-            Color::White => chess_board.square_rank(chess_board.algebraic_to_internal("e8")),
-            Color::Black => chess_board.square_rank(chess_board.algebraic_to_internal("e1")),
+            Color::White => chess_board.square_rank(E8),
+            Color::Black => chess_board.square_rank(E1),
         };
 
         let double_push_rank = match color {
-            Color::White => chess_board.square_rank(chess_board.algebraic_to_internal("e2")),
-            Color::Black => chess_board.square_rank(chess_board.algebraic_to_internal("e7")),
+            Color::White => chess_board.square_rank(E2),
+            Color::Black => chess_board.square_rank(E7),
         };
 
         for &square in pawn_list {
             let mut move_forward = true;
-            let mut capture_left = true;
-            let mut capture_right = true;
-
-            if let Some(pin_direction) = pinned_pieces.get(&square) {
-                // If the pawn is being pinned on horizontal direction
-                // there's no possible move for this pawn
-                if *pin_direction == 1 || *pin_direction == -1 {
-                    continue;
-                }
-
-                // If the pawn is being pinned on the file, than it can move in the forward direction
-                if (*pin_direction == chess_board.board_width)
-                    || (*pin_direction == -chess_board.board_width)
-                {
-                    capture_left = false;
-                    capture_right = false;
-                }
-
-                // If the pawn is being pinned on the right diagonal, than there's a capture
-                // possibility on the right diagonal
-                if (*pin_direction == chess_board.board_width + 1)
-                    || (*pin_direction == -chess_board.board_width + 1)
-                {
-                    move_forward = false;
-                    capture_left = false;
-                }
-
-                // If the pawn is being pinned on the left diagonal, than there's a capture
-                // possibility on the left diagonal
-                if (*pin_direction == chess_board.board_width - 1)
-                    || (*pin_direction == -chess_board.board_width - 1)
-                {
-                    move_forward = false;
-                    capture_right = false;
-                }
+            let mut capture_east = true;
+            let mut capture_west = true;
+
+            if let Some(&pin_direction) = pinned_pieces.get(&square) {
+                // A pinned pawn can only move along the pin line, which
+                // rules out every move when it is pinned on its rank
+                move_forward = forward.is_parallel(pin_direction);
+                capture_east = (forward + Direction::EAST).is_parallel(pin_direction);
+                capture_west = (forward + Direction::WEST).is_parallel(pin_direction);
             }
 
-            let first_target = chess_board.get_piece_on_square(square + direction);
+            // Pushes and captures all land on the next rank, and promote
+            // together on the last one
+            let one_step = square + direction;
+            let promotions = if chess_board.square_rank(one_step) == promotion_rank {
+                &promotion_pieces[..]
+            } else {
+                &[None][..]
+            };
+
+            let first_target = chess_board.get_piece_on_square(one_step);
             if move_forward && first_target.is_empty() {
-                if chess_board.square_rank(square + direction) != promotion_rank {
-                    let pawn_config = PawnMoveConfig {
-                        promotion: None,
-                        en_passant: false,
-                        en_passant_square: None,
-                    };
-                    moves.push(Move::create_pawn_move(
-                        chess_board,
-                        square,
-                        square + direction,
-                        pawn,
-                        first_target,
-                        pawn_config,
-                    ));
-                } else {
-                    for promotion in promotion_pieces {
-                        let pawn_config = PawnMoveConfig {
-                            promotion: Some(promotion),
-                            en_passant: false,
-                            en_passant_square: None,
-                        };
-                        moves.push(Move::create_pawn_move(
-                            chess_board,
-                            square,
-                            square + direction,
-                            pawn,
-                            first_target,
-                            pawn_config,
-                        ));
-                    }
-                }
+                Self::add_pawn_moves(
+                    moves,
+                    square,
+                    one_step,
+                    pawn,
+                    Piece::EmptySquare,
+                    promotions,
+                );
             }
 
-            let target = chess_board.get_piece_on_square(square + direction + 1);
-            if capture_right && target.is_opponent(color) {
-                if chess_board.square_rank(square + direction + 1) != promotion_rank {
-                    let pawn_config = PawnMoveConfig {
-                        promotion: None,
-                        en_passant: false,
-                        en_passant_square: None,
-                    };
-                    moves.push(Move::create_pawn_move(
-                        chess_board,
-                        square,
-                        square + direction + 1,
-                        pawn,
-                        target,
-                        pawn_config,
-                    ));
-                } else {
-                    for promotion in promotion_pieces {
-                        let pawn_config = PawnMoveConfig {
-                            promotion: Some(promotion),
-                            en_passant: false,
-                            en_passant_square: None,
-                        };
-                        moves.push(Move::create_pawn_move(
-                            chess_board,
-                            square,
-                            square + direction + 1,
-                            pawn,
-                            target,
-                            pawn_config,
-                        ));
-                    }
+            for (can_capture, side) in [
+                (capture_east, Direction::EAST),
+                (capture_west, Direction::WEST),
+            ] {
+                if !can_capture {
+                    continue;
                 }
-            } else if capture_right
-                && (Some(square + direction + 1) == chess_board.get_en_passant_target())
-            {
-                // Remove the pawns to not having blocking a piece that would give put the king in check
-                chess_board.set_piece_on_square(Piece::EmptySquare, square); // Attacker pawn
 
-                let enemy_pawn = chess_board.get_piece_on_square(square + 1);
-                chess_board.set_piece_on_square(Piece::EmptySquare, square + 1); // Captured pawn
-                // If king would be in check, don't add to possible moves
-                if self.is_king_in_check(chess_board, color).is_empty() {
-                    let pawn_config = PawnMoveConfig {
-                        promotion: None,
-                        en_passant: true,
-                        en_passant_square: None,
-                    };
-                    // Move don't let king in check so we add to the possible moves
-                    moves.push(Move::create_pawn_move(
-                        chess_board,
-                        square,
-                        square + direction + 1,
-                        pawn,
-                        target,
-                        pawn_config,
-                    ));
-                }
-                // Restore pawns on the board
-                chess_board.set_piece_on_square(pawn, square); // Attacker pawn
-                chess_board.set_piece_on_square(enemy_pawn, square + 1); // Captured pawn
-            }
+                let to = one_step + side;
+                let target = chess_board.get_piece_on_square(to);
 
-            let target = chess_board.get_piece_on_square(square + direction - 1);
-            if capture_left && target.is_opponent(color) {
-                if chess_board.square_rank(square + direction - 1) != promotion_rank {
-                    let pawn_config = PawnMoveConfig {
-                        promotion: None,
-                        en_passant: false,
-                        en_passant_square: None,
-                    };
-                    moves.push(Move::create_pawn_move(
-                        chess_board,
-                        square,
-                        square + direction - 1,
-                        pawn,
-                        target,
-                        pawn_config,
-                    ));
-                } else {
-                    for promotion in promotion_pieces {
-                        let pawn_config = PawnMoveConfig {
-                            promotion: Some(promotion),
-                            en_passant: false,
-                            en_passant_square: None,
-                        };
-                        moves.push(Move::create_pawn_move(
-                            chess_board,
-                            square,
-                            square + direction - 1,
-                            pawn,
-                            target,
-                            pawn_config,
-                        ));
-                    }
-                }
-            } else if capture_left
-                && (Some(square + direction - 1) == chess_board.get_en_passant_target())
-            {
-                // Remove the pawns to not having blocking a piece that would give put the king in check
-                chess_board.set_piece_on_square(Piece::EmptySquare, square); // Attacker pawn
-
-                let enemy_pawn = chess_board.get_piece_on_square(square - 1);
-                chess_board.set_piece_on_square(Piece::EmptySquare, square - 1); // Captured pawn
-                // If king would be in check, don't add to possible moves
-                if self.is_king_in_check(chess_board, color).is_empty() {
+                if target.is_opponent(color) {
+                    Self::add_pawn_moves(moves, square, to, pawn, target, promotions);
+                } else if Some(to) == chess_board.get_en_passant_target() {
+                    // The captured pawn isn't on the target square, see
+                    // `Move::en_passant_capture`
                     let pawn_config = PawnMoveConfig {
                         promotion: None,
                         en_passant: true,
                         en_passant_square: None,
                     };
-                    // Move don't let king in check so we add to the possible moves
-                    moves.push(Move::create_pawn_move(
-                        chess_board,
+                    let mv = Move::create_pawn_move(
                         square,
-                        square + direction - 1,
+                        to,
                         pawn,
-                        target,
+                        Piece::make(PieceType::Pawn, color.opposite()),
                         pawn_config,
-                    ));
+                    );
+                    if moves.ignores_king_safety()
+                        || self.is_en_passant_legal(chess_board, &mv, color)
+                    {
+                        moves.add_en_passant(mv);
+                    }
                 }
-
-                // Restore pawns on the board
-                chess_board.set_piece_on_square(pawn, square); // Attacker pawn
-                chess_board.set_piece_on_square(enemy_pawn, square - 1); // Captured pawn
             }
 
-            let target = chess_board.get_piece_on_square(square + 2 * direction);
+            // The double push goes through the square behind it, which
+            // becomes the en passant target
+            let two_steps = one_step + direction;
             if move_forward
-                && (chess_board.square_rank(square) == double_push_rank)
+                && chess_board.square_rank(square) == double_push_rank
                 && first_target.is_empty()
-                && target.is_empty()
+                && chess_board.get_piece_on_square(two_steps).is_empty()
             {
                 let pawn_config = PawnMoveConfig {
                     promotion: None,
                     en_passant: false,
-                    en_passant_square: Some(square + direction),
+                    en_passant_square: Some(one_step),
                 };
-                moves.push(Move::create_pawn_move(
-                    chess_board,
-                    square,
-                    square + 2 * direction,
-                    pawn,
-                    target,
-                    pawn_config,
-                ));
+                moves.add(two_steps, || {
+                    Move::create_pawn_move(square, two_steps, pawn, Piece::EmptySquare, pawn_config)
+                });
             }
         }
+    }
 
-        moves
+    /// Adds a pawn move that isn't en passant, once per promotion piece
+    /// when it reaches the last rank.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` - Sink the moves are added to
+    /// * `from` - Square of the pawn
+    /// * `to` - Square the pawn moves to
+    /// * `pawn` - The moving pawn
+    /// * `captured` - Piece on the target square, empty for a push
+    /// * `promotions` - Pieces promoted to, `[None]` off the last rank
+    fn add_pawn_moves(
+        moves: &mut impl MoveSink,
+        from: i16,
+        to: i16,
+        pawn: Piece,
+        captured: Piece,
+        promotions: &[Option<Piece>],
+    ) {
+        for &promotion in promotions {
+            let pawn_config = PawnMoveConfig {
+                promotion,
+                en_passant: false,
+                en_passant_square: None,
+            };
+            moves.add(to, || {
+                Move::create_pawn_move(from, to, pawn, captured, pawn_config)
+            });
+        }
     }
 
     /// Generates castling moves if legal.
     ///
+    /// The king lands on the g-file or the c-file and the rook next to it,
+    /// wherever they start from as in Chess960.
+    ///
     /// # Arguments
     ///
     /// * `chess_board` - Reference to the chess board
     /// * `color` - Color to generate castling moves for
-    ///
-    /// # Returns
-    ///
-    /// Vector of legal castling moves
-    fn generate_castling_moves(&self, chess_board: &ChessBoard, color: Color) -> Vec<Move> {
-        let mut moves = Vec::new();
-
-        let (king_square, king_piece, rook_kingside, rook_queenside) = match color {
-            Color::White => (
-                chess_board.algebraic_to_internal("e1"),
-                Piece::WhiteKing,
-                chess_board.algebraic_to_internal("h1"),
-                chess_board.algebraic_to_internal("a1"),
-            ),
-            Color::Black => (
-                chess_board.algebraic_to_internal("e8"),
-                Piece::BlackKing,
-                chess_board.algebraic_to_internal("h8"),
-                chess_board.algebraic_to_internal("a8"),
-            ),
+    /// * `moves` - Sink the castling moves are added to
+    fn generate_castling_moves(
+        &self,
+        chess_board: &ChessBoard,
+        color: Color,
+        moves: &mut impl MoveSink,
+    ) {
+        let Some(king_square) = self.get_king_square(color) else {
+            return;
         };
-
-        let castling_rights = &chess_board.castling_rights;
+        let king_piece = Piece::make(PieceType::King, color);
 
         // Kingside castling
-        if ((color == Color::White && castling_rights.white_kingside)
-            || (color == Color::Black && castling_rights.black_kingside))
-            && chess_board.can_castle_kingside(color, king_square, rook_kingside)
-        {
-            let king_to = king_square + 2; // g1 or g8
-            let rook_to = king_square + 1; // f1 or f8
-
-            moves.push(Move::create_castling_move(
-                chess_board,
-                king_square,
-                king_to,
-                king_piece,
-                rook_kingside,
-                rook_to,
-            ));
+        let rook_kingside = chess_board.castling_rook_square(color, true);
+        if chess_board.can_castle_kingside(self, color, king_square, rook_kingside) {
+            let king_to = chess_board.on_rank_of(king_square, G1);
+            let rook_to = chess_board.on_rank_of(king_square, F1);
+
+            moves.add(king_to, || {
+                Move::create_castling_move(king_square, king_to, king_piece, rook_kingside, rook_to)
+            });
         }
 
         // Queenside castling
-        if ((color == Color::White && castling_rights.white_queenside)
-            || (color == Color::Black && castling_rights.black_queenside))
-            && chess_board.can_castle_queenside(color, king_square, rook_queenside)
-        {
-            let king_to = king_square - 2; // c1 or c8
-            let rook_to = king_square - 1; // d1 or d8
-
-            moves.push(Move::create_castling_move(
-                chess_board,
-                king_square,
-                king_to,
-                king_piece,
-                rook_queenside,
-                rook_to,
-            ));
+        let rook_queenside = chess_board.castling_rook_square(color, false);
+        if chess_board.can_castle_queenside(self, color, king_square, rook_queenside) {
+            let king_to = chess_board.on_rank_of(king_square, C1);
+            let rook_to = chess_board.on_rank_of(king_square, D1);
+
+            moves.add(king_to, || {
+                Move::create_castling_move(
+                    king_square,
+                    king_to,
+                    king_piece,
+                    rook_queenside,
+                    rook_to,
+                )
+            });
         }
-
-        moves
     }
 
     /// Updates piece lists from the board position.
@@ -957,9 +850,14 @@ impl PieceList {
         self.black_queen_list.clear();
         self.black_king_list.clear();
 
+        self.material = MaterialSignature::default();
+
         for (square, piece) in board_position.iter().enumerate() {
             // Enumerate returns usize but our squares are i16
             let i16_square = square as i16;
+            if piece.is_valid_piece() {
+                self.material.add(*piece);
+            }
             match piece {
                 Piece::WhitePawn => self.white_pawn_list.push(i16_square),
                 Piece::WhiteRook => self.white_rook_list.push(i16_square),
@@ -986,25 +884,14 @@ impl PieceList {
     /// # Arguments
     ///
     /// * `mv` - The move to apply
-    pub fn make_move(&mut self, mv: &Move) {
-        // Remove captured piece first (if any)
-        if mv.captured_piece != Piece::EmptySquare && mv.captured_piece != Piece::SentinelSquare {
-            self.remove_piece(mv.captured_piece, mv.to);
-        }
-
-        // Handle en passant separately (captured pawn is on different square)
-        if mv.en_passant {
-            let capture_square = if mv.piece.is_white() {
-                mv.to - 10 // Todo: think of a better way to pass the board width
-            } else {
-                mv.to + 10
-            };
-            let captured_pawn = if mv.piece.is_white() {
-                Piece::BlackPawn
-            } else {
-                Piece::WhitePawn
-            };
+    /// * `board_width` - Number of squares in a rank of the internal board
+    pub fn make_move(&mut self, mv: &Move, board_width: i16) {
+        // Remove captured piece first (if any), the pawn taken en passant
+        // isn't on the destination square
+        if let Some((capture_square, captured_pawn)) = mv.en_passant_capture(board_width) {
             self.remove_piece(captured_pawn, capture_square);
+        } else if mv.captured_piece.is_valid_piece() {
+            self.remove_piece(mv.captured_piece, mv.to);
         }
 
         // Move the piece
@@ -1028,7 +915,8 @@ impl PieceList {
     /// # Arguments
     ///
     /// * `mv` - The move to undo
-    pub fn unmake_move(&mut self, mv: &Move) {
+    /// * `board_width` - Number of squares in a rank of the internal board
+    pub fn unmake_move(&mut self, mv: &Move, board_width: i16) {
         // 1. Handle castling first
         if let Some(castling) = &mv.castling {
             if !self.remove_piece(castling.rook_piece, castling.rook_to) {
@@ -1038,18 +926,7 @@ impl PieceList {
         }
 
         // 2. Handle en passant
-        if mv.en_passant {
-            let capture_square = if mv.piece.is_white() {
-                mv.to - 10
-            } else {
-                mv.to + 10
-            };
-            let captured_pawn = if mv.piece.is_white() {
-                Piece::BlackPawn
-            } else {
-                Piece::WhitePawn
-            };
-
+        if let Some((capture_square, captured_pawn)) = mv.en_passant_capture(board_width) {
             // Restore en passant capture
             self.add_piece(captured_pawn, capture_square);
         }
@@ -1157,13 +1034,16 @@ impl PieceList {
     ///
     /// * `piece` - Piece to add
     /// * `square` - Square where the piece is located
-    fn add_piece(&mut self, piece: Piece, square: i16) {
+    pub(super) fn add_piece(&mut self, piece: Piece, square: i16) {
         let list = self.get_list_mut(piece);
         if let Some(list) = list {
             // Insert in sorted order for consistency
             match list.binary_search(&square) {
                 Ok(_) => {} // Already exists (shouldn't happen)
-                Err(pos) => list.insert(pos, square),
+                Err(pos) => {
+                    list.insert(pos, square);
+                    self.material.add(piece);
+                }
             }
         }
     }
@@ -1180,12 +1060,13 @@ impl PieceList {
     /// # Returns
     ///
     /// `true` if piece was found and removed, `false` otherwise
-    fn remove_piece(&mut self, piece: Piece, square: i16) -> bool {
+    pub(super) fn remove_piece(&mut self, piece: Piece, square: i16) -> bool {
         let list = self.get_list_mut(piece);
         if let Some(list) = list {
             match list.binary_search(&square) {
                 Ok(pos) => {
                     list.remove(pos);
+                    self.material.remove(piece);
                     return true; // Piece found and removed
                 }
                 Err(_) => {
@@ -1266,6 +1147,11 @@ impl PieceList {
         None
     }
 
+    /// Gets the number of pieces of each kind on the board.
+    pub fn material_signature(&self) -> MaterialSignature {
+        self.material
+    }
+
     /// Calls a closure for every piece on the board.
     ///
     /// # Arguments
@@ -1286,23 +1172,9 @@ impl PieceList {
             &self.black_queen_list,
             &self.black_king_list,
         ];
-        let all_pieces: [Piece; 12] = [
-            Piece::WhitePawn,
-            Piece::WhiteKnight,
-            Piece::WhiteBishop,
-            Piece::WhiteRook,
-            Piece::WhiteQueen,
-            Piece::WhiteKing,
-            Piece::BlackPawn,
-            Piece::BlackKnight,
-            Piece::BlackBishop,
-            Piece::BlackRook,
-            Piece::BlackQueen,
-            Piece::BlackKing,
-        ];
-        for (list, piece) in all_lists.iter().zip(all_pieces.iter()) {
+        for (list, piece) in all_lists.iter().zip(Piece::ALL) {
             for &sq in *list {
-                f(*piece, sq);
+                f(piece, sq);
             }
         }
     }
@@ -1321,24 +1193,14 @@ impl PieceList {
     ///
     /// `true` if the bishop can legally attack the target square
     fn bishop_attack(chess_board: &ChessBoard, from: i16, to: i16) -> bool {
-        let direction = chess_board.get_diagonal_direction(from, to);
-
-        if direction == 0 {
+        let Some(direction) = chess_board.get_diagonal_direction(from, to) else {
             return false;
-        }
-
-        let mut position = from + direction;
-        while position != to {
-            let piece = chess_board.get_piece_on_square(position);
-            if piece.is_empty() {
-                position += direction;
-                continue;
-            } else {
-                return false;
-            }
-        }
+        };
 
-        true
+        directions::ray(from, direction)
+            .iter()
+            .take_while(|&&position| position != to)
+            .all(|&position| chess_board.get_piece_on_square(position).is_empty())
     }
 
     /// Checks if a rook can attack from one square to another.
@@ -1355,25 +1217,15 @@ impl PieceList {
     ///
     /// `true` if the rook can legally attack the target square
     fn rook_attack(chess_board: &ChessBoard, from: i16, to: i16) -> bool {
-        let direction = chess_board.get_rank_or_file_direction(from, to);
-
-        if direction == 0 {
+        let Some(direction) = chess_board.get_rank_or_file_direction(from, to) else {
             return false;
-        }
-
-        let mut position = from + direction;
-        while position != to {
-            let piece = chess_board.get_piece_on_square(position);
-            if piece.is_empty() {
-                position += direction;
-                continue;
-            } else {
-                // Blocked by a piece before reaching destination
-                return false;
-            }
-        }
+        };
 
-        true
+        // Blocked by a piece before reaching destination
+        directions::ray(from, direction)
+            .iter()
+            .take_while(|&&position| position != to)
+            .all(|&position| chess_board.get_piece_on_square(position).is_empty())
     }
 
     /// Checks if a queen can attack from one square to another.
@@ -1412,32 +1264,14 @@ impl PieceList {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the chess board
     /// * `from` - Starting square
     /// * `to` - Target square
     ///
     /// # Returns
     ///
     /// `true` if the king can legally attack the target square
-    fn king_attack(chess_board: &ChessBoard, from: i16, to: i16) -> bool {
-        if from == to {
-            return false;
-        }
-
-        let row1 = chess_board.square_rank(from);
-        let row2 = chess_board.square_rank(to);
-
-        let col1 = chess_board.square_file(from);
-        let col2 = chess_board.square_file(to);
-
-        let row_diff = row1.abs_diff(row2);
-        let col_diff = col1.abs_diff(col2);
-
-        if row_diff > 1 || col_diff > 1 {
-            return false;
-        }
-
-        true
+    fn king_attack(from: i16, to: i16) -> bool {
+        directions::king_targets(from).contains(&to)
     }
 
     /// Checks if a knight can attack from one square to another.
@@ -1446,33 +1280,14 @@ impl PieceList {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the chess board
     /// * `from` - Starting square
     /// * `to` - Target square
     ///
     /// # Returns
     ///
     /// `true` if the knight can legally attack the target square
-    fn knight_attack(chess_board: &ChessBoard, from: i16, to: i16) -> bool {
-        if from == to {
-            return false;
-        }
-
-        let row1 = chess_board.square_rank(from);
-        let row2 = chess_board.square_rank(to);
-
-        let col1 = chess_board.square_file(from);
-        let col2 = chess_board.square_file(to);
-
-        let row_diff = row1.abs_diff(row2);
-        let col_diff = col1.abs_diff(col2);
-
-        if (row_diff == 2 && col_diff == 1) || (row_diff == 1 && col_diff == 2) {
-            return true;
-        }
-
-        // Movement doesn't follow an L-shape
-        false
+    fn knight_attack(from: i16, to: i16) -> bool {
+        directions::knight_targets(from).contains(&to)
     }
 
     /// Checks if a pawn can attack from one square to another.
@@ -1481,7 +1296,6 @@ impl PieceList {
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the chess board
     /// * `from` - Starting square
     /// * `to` - Target square
     /// * `color` - Color of the pawn
@@ -1489,39 +1303,8 @@ impl PieceList {
     /// # Returns
     ///
     /// `true` if the pawn can legally attack the target square
-    fn pawn_attack(chess_board: &ChessBoard, from: i16, to: i16, color: Color) -> bool {
-        if from == to {
-            return false;
-        }
-
-        let row1 = chess_board.square_rank(from);
-        let row2 = chess_board.square_rank(to);
-
-        let col1 = chess_board.square_file(from);
-        let col2 = chess_board.square_file(to);
-
-        let row_diff = row1.abs_diff(row2);
-        let col_diff = col1.abs_diff(col2);
-
-        if col_diff != 1 || row_diff != 1 {
-            // Capture can only happen with a square distance,
-            return false;
-        }
-
-        // Check if pawn direction is right
-        if color == Color::White {
-            // White pawn always goes up
-            if row2 < row1 {
-                return false;
-            }
-        } else {
-            // Black pawn always goes down
-            if row2 > row1 {
-                return false;
-            }
-        }
-
-        true
+    fn pawn_attack(from: i16, to: i16, color: Color) -> bool {
+        directions::pawn_attacks(color, from).contains(&to)
     }
 
     /// Gets the square where the king of the given color is located.
@@ -1533,7 +1316,7 @@ impl PieceList {
     /// # Returns
     ///
     /// Square where the king is located, or `None` if not found
-    fn get_king_square(&self, color: Color) -> Option<i16> {
+    pub(super) fn get_king_square(&self, color: Color) -> Option<i16> {
         if color == Color::White {
             if let Some(king_list) = self.get_list(Piece::WhiteKing)
                 && let Some(king) = king_list.first()
@@ -1563,7 +1346,11 @@ impl PieceList {
     /// # Returns
     ///
     /// HashMap mapping pinned piece squares to their pin directions
-    fn detect_pinned_pieces(&self, chess_board: &ChessBoard, color: Color) -> HashMap<i16, i16> {
+    pub fn detect_pinned_pieces(
+        &self,
+        chess_board: &ChessBoard,
+        color: Color,
+    ) -> HashMap<i16, Direction> {
         let mut pinned_pieces = HashMap::new();
 
         let Some(king_square) = self.get_king_square(color) else {
@@ -1571,18 +1358,9 @@ impl PieceList {
         };
 
         // Check for pins from each direction
-        for direction in &[
-            -1,
-            1, // Horizontal
-            -chess_board.board_width,
-            chess_board.board_width, // Vertical
-            -chess_board.board_width - 1,
-            -chess_board.board_width + 1, // Diagonals
-            chess_board.board_width - 1,
-            chess_board.board_width + 1,
-        ] {
+        for direction in directions::ALL {
             if let Some((pinned_square, pin_direction)) =
-                self.find_pinned_piece_in_direction(chess_board, king_square, *direction, color)
+                self.find_pinned_piece_in_direction(chess_board, king_square, direction, color)
             {
                 pinned_pieces.insert(pinned_square, pin_direction);
             }
@@ -1607,15 +1385,14 @@ impl PieceList {
         &self,
         chess_board: &ChessBoard,
         king_square: i16,
-        direction: i16,
+        direction: Direction,
         color: Color,
-    ) -> Option<(i16, i16)> {
-        let mut current = king_square + direction;
+    ) -> Option<(i16, Direction)> {
         let mut pinned_piece: Option<i16> = None;
 
         // Move away from king until we hit a piece or board edge
-        let mut piece = chess_board.get_piece_on_square(current);
-        while !piece.is_sentinel() {
+        for &current in directions::ray(king_square, direction) {
+            let piece = chess_board.get_piece_on_square(current);
             if !piece.is_empty() {
                 if piece.get_color() == color {
                     // First piece we encounter of our color - could be pinned
@@ -1627,53 +1404,359 @@ impl PieceList {
                     }
                 } else {
                     // Enemy piece - check if it's a slider that can pin
-                    if self.can_piece_pin_in_direction(chess_board, piece, direction) {
+                    if Self::can_piece_pin_in_direction(piece, direction) {
                         return pinned_piece.map(|pin_sq| (pin_sq, direction));
                     } else {
                         return None; // Enemy piece can't pin in this direction
                     }
                 }
             }
-
-            current += direction;
-            piece = chess_board.get_piece_on_square(current);
         }
 
         None
     }
 
+    /// Checks if an en passant capture leaves the king safe.
+    ///
+    /// En passant empties two squares, so the pin detection can't catch every
+    /// case: both pawns may stand between the king and a rook on their rank,
+    /// the captured pawn may be shielding the king, or it may be the piece
+    /// giving check. The position after the capture is tested without
+    /// touching the board, by treating the squares of both pawns as empty and
+    /// the destination square as occupied.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Reference to the chess board before the capture
+    /// * `mv` - The en passant capture
+    /// * `color` - Color of the capturing side
+    ///
+    /// # Returns
+    ///
+    /// `true` if the king isn't attacked after the capture
+    fn is_en_passant_legal(&self, chess_board: &ChessBoard, mv: &Move, color: Color) -> bool {
+        let Some(king_square) = self.get_king_square(color) else {
+            return true;
+        };
+        let Some((captured_square, captured_pawn)) = mv.en_passant_capture(chess_board.board_width)
+        else {
+            return true;
+        };
+
+        // Sliders, seen through the squares vacated by the capture
+        for direction in directions::ALL {
+            for &current in directions::ray(king_square, direction) {
+                let piece = chess_board.get_piece_on_square(current);
+                if current == mv.to {
+                    break;
+                }
+
+                if current != mv.from && current != captured_square && !piece.is_empty() {
+                    if piece.is_opponent(color)
+                        && Self::can_piece_pin_in_direction(piece, direction)
+                    {
+                        return false;
+                    }
+                    break;
+                }
+            }
+        }
+
+        // Knights and pawns, except the captured pawn
+        let knight = match color {
+            Color::White => Piece::BlackKnight,
+            Color::Black => Piece::WhiteKnight,
+        };
+        if self
+            .is_attacked_by_piece(chess_board, king_square, knight, color.opposite())
+            .is_some()
+        {
+            return false;
+        }
+
+        !self.get_list(captured_pawn).is_some_and(|pawns| {
+            pawns.iter().any(|&pawn_square| {
+                pawn_square != captured_square
+                    && Self::pawn_attack(pawn_square, king_square, color.opposite())
+            })
+        })
+    }
+
     /// Checks if a piece can pin in a given direction.
     ///
     /// Only sliding pieces (queen, rook, bishop) can pin.
     ///
     /// # Arguments
     ///
-    /// * `chess_board` - Reference to the chess board
     /// * `piece` - Piece to check
     /// * `direction` - Direction of the potential pin
     ///
     /// # Returns
     ///
     /// `true` if the piece can pin in the given direction
-    fn can_piece_pin_in_direction(
-        &self,
-        chess_board: &ChessBoard,
-        piece: Piece,
-        direction: i16,
-    ) -> bool {
+    fn can_piece_pin_in_direction(piece: Piece, direction: Direction) -> bool {
         match piece.get_type() {
             PieceType::Queen => true, // Queens can pin in any direction
-            PieceType::Rook => {
-                // Rooks can pin horizontally or vertically
-                direction.abs() == 1 || direction.abs() == chess_board.board_width
+            // Rooks can pin horizontally or vertically
+            PieceType::Rook => direction.is_orthogonal(),
+            // Bishops can pin diagonally
+            PieceType::Bishop => direction.is_diagonal(),
+            _ => false, // Other pieces can't pin
+        }
+    }
+
+    /// Checks if a move will give check to the opponent's king.
+    ///
+    /// The test is done before the move is made. Direct checks are found by
+    /// testing the attack from the destination square, and discovered checks
+    /// by looking behind the vacated square for a friendly slider, the same
+    /// way pins are detected. Castling and en passant move two pieces, so they
+    /// must be handled by the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Reference to the chess board before the move
+    /// * `mv` - The move to test
+    ///
+    /// # Returns
+    ///
+    /// `true` if the opponent's king is in check after the move
+    pub fn gives_check(&self, chess_board: &ChessBoard, mv: &Move) -> bool {
+        let color = mv.piece.get_color();
+        let Some(king_square) = self.get_king_square(color.opposite()) else {
+            return false;
+        };
+
+        // Direct check from the destination square. Squares on the path
+        // between `to` and the king are unaffected by the move, except for
+        // `from`, which is empty once the piece has left it. A promoting
+        // pawn checks through its own starting square that way.
+        let moved_piece = mv.promotion.unwrap_or(mv.piece);
+        let line = chess_board.get_rank_or_file_direction(mv.to, king_square);
+        let diagonal = chess_board.get_diagonal_direction(mv.to, king_square);
+        let direct_check = match moved_piece.get_type() {
+            PieceType::Queen => Self::slider_check(chess_board, mv, king_square, line.or(diagonal)),
+            PieceType::Rook => Self::slider_check(chess_board, mv, king_square, line),
+            PieceType::Bishop => Self::slider_check(chess_board, mv, king_square, diagonal),
+            PieceType::Knight => Self::knight_attack(mv.to, king_square),
+            PieceType::Pawn => Self::pawn_attack(mv.to, king_square, color),
+            PieceType::King => false,
+        };
+
+        if direct_check {
+            return true;
+        }
+
+        // Discovered check: the vacated square must be on a line with the king
+        let Some(direction) = chess_board
+            .get_rank_or_file_direction(king_square, mv.from)
+            .or_else(|| chess_board.get_diagonal_direction(king_square, mv.from))
+        else {
+            return false;
+        };
+
+        for &current in directions::ray(king_square, direction) {
+            if current == mv.to {
+                // The moved piece still blocks the line
+                return false;
             }
-            PieceType::Bishop => {
-                // Bishops can pin diagonally
-                direction.abs() == chess_board.board_width - 1
-                    || direction.abs() == chess_board.board_width + 1
+
+            let piece = chess_board.get_piece_on_square(current);
+            if current != mv.from && !piece.is_empty() {
+                return piece.get_color() == color
+                    && Self::can_piece_pin_in_direction(piece, direction);
             }
-            _ => false, // Other pieces can't pin
         }
+
+        false
+    }
+
+    /// Checks if a slider landing on `mv.to` reaches the king along
+    /// `direction`, with `mv.from` already vacated.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Reference to the chess board before the move
+    /// * `mv` - The move that places the slider
+    /// * `king_square` - Square of the enemy king
+    /// * `direction` - Direction from `mv.to` towards the king, if any
+    ///
+    /// # Returns
+    ///
+    /// `true` if no piece stands between the slider and the king
+    fn slider_check(
+        chess_board: &ChessBoard,
+        mv: &Move,
+        king_square: i16,
+        direction: Option<Direction>,
+    ) -> bool {
+        let Some(direction) = direction else {
+            return false;
+        };
+
+        directions::ray(mv.to, direction)
+            .iter()
+            .take_while(|&&position| position != king_square)
+            .all(|&position| {
+                position == mv.from || chess_board.get_piece_on_square(position).is_empty()
+            })
+    }
+
+    /// Checks if a move, other than castling, follows the movement rules of
+    /// its piece in the position.
+    ///
+    /// The piece must stand on the starting square, the captured piece must
+    /// be the enemy piece on the target square, sliders need a clear path and
+    /// the pawn flags (double push, en passant, promotion) must match the
+    /// move. Whether the move leaves its own king in check is not tested.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Reference to the chess board
+    /// * `mv` - The move to test
+    ///
+    /// # Returns
+    ///
+    /// `true` if the move is pseudo-legal
+    pub fn is_pseudo_legal(&self, chess_board: &ChessBoard, mv: &Move) -> bool {
+        let piece = mv.piece;
+        if !piece.is_valid_piece() || chess_board.get_piece_on_square(mv.from) != piece {
+            return false;
+        }
+
+        let color = piece.get_color();
+        let target = chess_board.get_piece_on_square(mv.to);
+        // The destination of an en passant capture is empty
+        let expected = if mv.en_passant {
+            Piece::EmptySquare
+        } else {
+            mv.captured_piece
+        };
+        if target != expected
+            || target.is_sentinel()
+            || target.is_friend(color)
+            || matches!(target, Piece::WhiteKing | Piece::BlackKing)
+        {
+            return false;
+        }
+
+        if piece.get_type() == PieceType::Pawn {
+            return Self::is_pawn_move_pseudo_legal(chess_board, mv, color);
+        }
+
+        if mv.en_passant || mv.en_passant_square.is_some() || mv.promotion.is_some() {
+            return false;
+        }
+
+        match piece.get_type() {
+            PieceType::Knight => Self::knight_attack(mv.from, mv.to),
+            PieceType::Bishop => Self::bishop_attack(chess_board, mv.from, mv.to),
+            PieceType::Rook => Self::rook_attack(chess_board, mv.from, mv.to),
+            PieceType::Queen => Self::queen_attack(chess_board, mv.from, mv.to),
+            PieceType::King => Self::king_attack(mv.from, mv.to),
+            PieceType::Pawn => unreachable!("pawn moves are tested above"),
+        }
+    }
+
+    /// Checks a pawn move for [`is_pseudo_legal`](Self::is_pseudo_legal).
+    fn is_pawn_move_pseudo_legal(chess_board: &ChessBoard, mv: &Move, color: Color) -> bool {
+        let forward = Direction::pawn_push(color).offset();
+        let (start_rank, last_rank) = match color {
+            Color::White => (1, 7),
+            Color::Black => (6, 0),
+        };
+        let from_rank = chess_board.map_to_standard_chess_board(mv.from) / 8;
+        let to_rank = chess_board.map_to_standard_chess_board(mv.to) / 8;
+
+        let promotes = to_rank == last_rank;
+        let promotion_valid = match mv.promotion {
+            Some(promotion) => {
+                promotes
+                    && promotion.is_friend(color)
+                    && !matches!(promotion.get_type(), PieceType::Pawn | PieceType::King)
+            }
+            None => !promotes,
+        };
+        if !promotion_valid {
+            return false;
+        }
+
+        let is_double_push = mv.to == mv.from + 2 * forward;
+        if mv.en_passant_square != is_double_push.then_some(mv.from + forward) {
+            return false;
+        }
+
+        if mv.en_passant {
+            return chess_board.get_en_passant_target() == Some(mv.to)
+                && Self::pawn_attack(mv.from, mv.to, color)
+                && mv
+                    .en_passant_capture(chess_board.board_width)
+                    .is_some_and(|(square, pawn)| {
+                        pawn == mv.captured_piece && chess_board.get_piece_on_square(square) == pawn
+                    });
+        }
+
+        if mv.captured_piece.is_valid_piece() {
+            return Self::pawn_attack(mv.from, mv.to, color);
+        }
+
+        let one_step = mv.from + forward;
+        mv.to == one_step
+            || (is_double_push
+                && from_rank == start_rank
+                && chess_board.get_piece_on_square(one_step).is_empty())
+    }
+
+    /// Computes every square attacked by the pieces of the given color.
+    ///
+    /// # Arguments
+    ///
+    /// * `chess_board` - Reference to the chess board
+    /// * `by_color` - Color of the attacking pieces
+    ///
+    /// # Returns
+    ///
+    /// Bitmask of attacked squares, bit 0 being a1 and bit 63 being h8
+    pub fn attacked_squares(&self, chess_board: &ChessBoard, by_color: Color) -> u64 {
+        let mut attacked = 0u64;
+
+        let mut mark = |square: i16| {
+            attacked |= 1 << chess_board.map_to_standard_chess_board(square);
+        };
+
+        let slide = |from: i16, piece_directions: &[Direction], mark: &mut dyn FnMut(i16)| {
+            for &direction in piece_directions {
+                for &current in directions::ray(from, direction) {
+                    mark(current);
+                    if !chess_board.get_piece_on_square(current).is_empty() {
+                        break;
+                    }
+                }
+            }
+        };
+
+        self.for_each_piece(|piece, square| {
+            if piece.get_color() != by_color {
+                return;
+            }
+
+            match piece.get_type() {
+                PieceType::Pawn => directions::pawn_attacks(by_color, square)
+                    .iter()
+                    .for_each(|&target| mark(target)),
+                PieceType::Knight => directions::knight_targets(square)
+                    .iter()
+                    .for_each(|&target| mark(target)),
+                PieceType::King => directions::king_targets(square)
+                    .iter()
+                    .for_each(|&target| mark(target)),
+                PieceType::Bishop => slide(square, &DIAGONAL, &mut mark),
+                PieceType::Rook => slide(square, &ORTHOGONAL, &mut mark),
+                PieceType::Queen => slide(square, &directions::ALL, &mut mark),
+            }
+        });
+
+        attacked
     }
 
     /// Checks if a square is attacked by any piece of the given color.
@@ -1693,26 +1776,7 @@ impl PieceList {
         square: i16,
         by_color: Color,
     ) -> bool {
-        let attacker_pieces = match by_color {
-            Color::White => [
-                Piece::WhiteQueen,
-                Piece::WhiteRook,
-                Piece::WhiteBishop,
-                Piece::WhiteKnight,
-                Piece::WhitePawn,
-                Piece::WhiteKing,
-            ],
-            Color::Black => [
-                Piece::BlackQueen,
-                Piece::BlackRook,
-                Piece::BlackBishop,
-                Piece::BlackKnight,
-                Piece::BlackPawn,
-                Piece::BlackKing,
-            ],
-        };
-
-        for attack_piece in attacker_pieces {
+        for attack_piece in Piece::of_color(by_color) {
             if self
                 .is_attacked_by_piece(chess_board, square, attack_piece, by_color)
                 .is_some()
@@ -1749,11 +1813,9 @@ impl PieceList {
                     PieceType::Queen => Self::queen_attack(chess_board, piece_square, square),
                     PieceType::Rook => Self::rook_attack(chess_board, piece_square, square),
                     PieceType::Bishop => Self::bishop_attack(chess_board, piece_square, square),
-                    PieceType::Knight => Self::knight_attack(chess_board, piece_square, square),
-                    PieceType::Pawn => {
-                        Self::pawn_attack(chess_board, piece_square, square, by_color)
-                    }
-                    PieceType::King => Self::king_attack(chess_board, piece_square, square),
+                    PieceType::Knight => Self::knight_attack(piece_square, square),
+                    PieceType::Pawn => Self::pawn_attack(piece_square, square, by_color),
+                    PieceType::King => Self::king_attack(piece_square, square),
                 };
 
                 if attacks {
@@ -1782,6 +1844,8 @@ impl Default for PieceList {
             black_bishop_list: Vec::new(),
             black_knight_list: Vec::new(),
             black_pawn_list: Vec::new(),
+
+            material: MaterialSignature::default(),
         }
     }
 }
@@ -1822,29 +1886,29 @@ mod is_square_attacked_tests {
         // Black pawn at d4 should attack e3 and c3
         assert!(game.board.piece_list.is_square_attacked(
             &game.board,
-            game.board.algebraic_to_internal("e3"),
+            game.board.algebraic_to_internal("e3").unwrap(),
             Color::Black
         ));
         assert!(game.board.piece_list.is_square_attacked(
             &game.board,
-            game.board.algebraic_to_internal("c3"),
+            game.board.algebraic_to_internal("c3").unwrap(),
             Color::Black
         ));
         // e4 not attacked
         assert!(!game.board.piece_list.is_square_attacked(
             &game.board,
-            game.board.algebraic_to_internal("e4"),
+            game.board.algebraic_to_internal("e4").unwrap(),
             Color::Black
         ));
         // diagonals behind can't be attacked
         assert!(!game.board.piece_list.is_square_attacked(
             &game.board,
-            game.board.algebraic_to_internal("d5"),
+            game.board.algebraic_to_internal("d5").unwrap(),
             Color::Black
         ));
         assert!(!game.board.piece_list.is_square_attacked(
             &game.board,
-            game.board.algebraic_to_internal("c5"),
+            game.board.algebraic_to_internal("c5").unwrap(),
             Color::Black
         ));
     }
@@ -1875,7 +1939,7 @@ mod is_square_attacked_tests {
         let attacked_squares = ["b4", "b6", "c3", "c7", "e3", "e7", "f4", "f6"];
 
         for &algebraic_square in &attacked_squares {
-            let square = game.board.algebraic_to_internal(algebraic_square);
+            let square = game.board.algebraic_to_internal(algebraic_square).unwrap();
             assert!(
                 game.board
                     .piece_list
@@ -1885,7 +1949,7 @@ mod is_square_attacked_tests {
             );
         }
 
-        let safe_square = game.board.algebraic_to_internal("a1");
+        let safe_square = game.board.algebraic_to_internal("a1").unwrap();
         assert!(
             !game
                 .board
@@ -1922,7 +1986,7 @@ mod is_square_attacked_tests {
 
         // Bishop at d5 should attack diagonals
         for &algebraic_square in &attacked_squares {
-            let square = game.board.algebraic_to_internal(algebraic_square);
+            let square = game.board.algebraic_to_internal(algebraic_square).unwrap();
             assert!(
                 game.board
                     .piece_list
@@ -1932,7 +1996,7 @@ mod is_square_attacked_tests {
             );
         }
 
-        let safe_square = game.board.algebraic_to_internal("d4");
+        let safe_square = game.board.algebraic_to_internal("d4").unwrap();
         assert!(
             !game
                 .board
@@ -1966,7 +2030,7 @@ mod is_square_attacked_tests {
         let attacked_squares = ["b5", "h5", "d6", "d3"];
 
         for &algebraic_square in &attacked_squares {
-            let square = game.board.algebraic_to_internal(algebraic_square);
+            let square = game.board.algebraic_to_internal(algebraic_square).unwrap();
             assert!(
                 game.board
                     .piece_list
@@ -1975,7 +2039,7 @@ mod is_square_attacked_tests {
                 algebraic_square
             );
         }
-        let safe_square = game.board.algebraic_to_internal("e4");
+        let safe_square = game.board.algebraic_to_internal("e4").unwrap();
         assert!(
             !game
                 .board
@@ -2010,7 +2074,7 @@ mod is_square_attacked_tests {
         let attacked_squares = ["a5", "g5", "d6", "b3", "f7", "b3", "c6", "h1"];
 
         for &algebraic_square in &attacked_squares {
-            let square = game.board.algebraic_to_internal(algebraic_square);
+            let square = game.board.algebraic_to_internal(algebraic_square).unwrap();
             assert!(
                 game.board
                     .piece_list
@@ -2020,7 +2084,7 @@ mod is_square_attacked_tests {
             );
         }
 
-        let safe_square = game.board.algebraic_to_internal("g4");
+        let safe_square = game.board.algebraic_to_internal("g4").unwrap();
         assert!(
             !game
                 .board
@@ -2055,7 +2119,7 @@ mod is_square_attacked_tests {
         let attacked_squares = ["c6", "d6", "e6", "c5", "e5", "c4", "d4", "e4"];
 
         for &algebraic_square in &attacked_squares {
-            let square = game.board.algebraic_to_internal(algebraic_square);
+            let square = game.board.algebraic_to_internal(algebraic_square).unwrap();
             assert!(
                 game.board
                     .piece_list
@@ -2065,7 +2129,7 @@ mod is_square_attacked_tests {
             );
         }
 
-        let safe_square = game.board.algebraic_to_internal("e3"); // too far
+        let safe_square = game.board.algebraic_to_internal("e3").unwrap(); // too far
         assert!(
             !game
                 .board
@@ -2097,14 +2161,14 @@ mod is_square_attacked_tests {
         */
 
         // Rook at d5 should not attack squares beyond the pawn at d4
-        let attacked_square = game.board.algebraic_to_internal("d4");
+        let attacked_square = game.board.algebraic_to_internal("d4").unwrap();
         assert!(game.board.piece_list.is_square_attacked(
             &game.board,
             attacked_square,
             Color::White
         ));
 
-        let safe_square = game.board.algebraic_to_internal("d3"); // blocked by pawn
+        let safe_square = game.board.algebraic_to_internal("d3").unwrap(); // blocked by pawn
         assert!(
             !game
                 .board
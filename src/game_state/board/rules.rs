@@ -0,0 +1,184 @@
+//! Rules of chess and its variants.
+//!
+//! The rules that differ between variants are gathered behind the [`Rules`]
+//! trait held by the board: which moves are legal (king safety, capture
+//! obligations), which pieces a capture removes besides the captured one
+//! (explosions) and who wins when the side to move has no legal move.
+//!
+//! Standard chess is always available. The variants live in their own
+//! modules, compiled in with their cargo feature, and are selected with the
+//! `UCI_Variant` option:
+//!
+//! - `antichess` (feature `antichess`): captures are compulsory and the side
+//!   left without a move wins
+//! - `atomic` (feature `atomic`): captures explode the surrounding pieces and
+//!   blowing up the enemy king wins
+//!
+//! Rules are stateless, so the board holds a `&'static` reference to one of
+//! the instances below and the standard search pays no extra cost.
+
+use crate::game_state::board::ChessBoard;
+use crate::game_state::board::moves::{Move, MoveList};
+use crate::game_state::board::piece::Color;
+use crate::game_state::board::score::Score;
+
+#[cfg(feature = "antichess")]
+pub mod antichess;
+#[cfg(feature = "atomic")]
+pub mod atomic;
+
+/// Result of a finished game for the side to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The side to move won
+    Win,
+    /// The side to move lost
+    Loss,
+    /// The game is drawn
+    Draw,
+}
+
+impl Outcome {
+    /// Gets the score of the finished game from the point of view of the
+    /// side to move.
+    pub fn score(self) -> Score {
+        self.score_at(0)
+    }
+
+    /// Gets the score of a game finished some plies below the root of a
+    /// search, from the point of view of the side to move: the nearer mate
+    /// scores higher.
+    ///
+    /// # Arguments
+    ///
+    /// * `ply` - Distance of the position from the root
+    pub fn score_at(self, ply: u32) -> Score {
+        match self {
+            Outcome::Win => Score::mate_in(ply),
+            Outcome::Loss => Score::mated_in(ply),
+            Outcome::Draw => Score::DRAW,
+        }
+    }
+}
+
+/// Rules of a chess variant.
+pub trait Rules: Send + Sync {
+    /// Name of the variant, as given to the `UCI_Variant` option.
+    fn name(&self) -> &'static str;
+
+    /// Generates the legal moves of a color.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - Position to generate the moves in, left unchanged
+    /// * `color` - Color to generate moves for
+    ///
+    /// # Returns
+    ///
+    /// List of legal moves
+    fn generate_moves(&self, board: &mut ChessBoard, color: Color) -> MoveList;
+
+    /// Counts the legal moves of a color, see
+    /// [`generate_moves`](Self::generate_moves).
+    fn count_moves(&self, board: &mut ChessBoard, color: Color) -> usize {
+        self.generate_moves(board, color).len()
+    }
+
+    /// Checks if a color's king is in check.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - Position to look at
+    /// * `color` - Color of the king
+    ///
+    /// # Returns
+    ///
+    /// `true` if the king is attacked, by default
+    fn is_in_check(&self, board: &ChessBoard, color: Color) -> bool {
+        !board.piece_list.is_king_in_check(board, color).is_empty()
+    }
+
+    /// Gets the squares of the pieces removed by a capture besides the
+    /// captured piece.
+    ///
+    /// Only called for captures, on the board where the capture was just
+    /// made. The capturing piece may be removed too.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - Position after the capture
+    /// * `mv` - The capture
+    ///
+    /// # Returns
+    ///
+    /// Internal squares of the removed pieces, empty by default
+    fn exploded_squares(&self, _board: &ChessBoard, _mv: &Move) -> Vec<i16> {
+        Vec::new()
+    }
+
+    /// Decides the game when the side to move has no legal move.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - Position without legal moves
+    /// * `side_to_move` - Color of the player to move
+    ///
+    /// # Returns
+    ///
+    /// The result for the side to move
+    fn outcome_without_moves(&self, board: &ChessBoard, side_to_move: Color) -> Outcome;
+}
+
+/// Rules of standard chess.
+pub struct StandardRules;
+
+impl Rules for StandardRules {
+    fn name(&self) -> &'static str {
+        "chess"
+    }
+
+    fn generate_moves(&self, board: &mut ChessBoard, color: Color) -> MoveList {
+        board.generate_standard_moves(color)
+    }
+
+    fn count_moves(&self, board: &mut ChessBoard, color: Color) -> usize {
+        board.count_standard_moves(color)
+    }
+
+    fn outcome_without_moves(&self, board: &ChessBoard, side_to_move: Color) -> Outcome {
+        if board.is_in_check(side_to_move) {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+}
+
+/// The rules of standard chess, used by new boards.
+pub static STANDARD: StandardRules = StandardRules;
+
+/// Gets every variant compiled in, standard chess first.
+pub fn variants() -> Vec<&'static dyn Rules> {
+    #[allow(unused_mut)]
+    let mut variants: Vec<&'static dyn Rules> = vec![&STANDARD];
+    #[cfg(feature = "antichess")]
+    variants.push(&antichess::ANTICHESS);
+    #[cfg(feature = "atomic")]
+    variants.push(&atomic::ATOMIC);
+    variants
+}
+
+/// Finds a variant by its `UCI_Variant` name.
+///
+/// # Arguments
+///
+/// * `name` - Name of the variant, case insensitive
+///
+/// # Returns
+///
+/// The rules of the variant, or None if it isn't compiled in
+pub fn find_variant(name: &str) -> Option<&'static dyn Rules> {
+    variants()
+        .into_iter()
+        .find(|rules| rules.name().eq_ignore_ascii_case(name))
+}
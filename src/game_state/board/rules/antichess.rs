@@ -0,0 +1,43 @@
+//! Antichess, also known as giveaway or losing chess.
+//!
+//! The king is an ordinary piece: there is no check, it can be captured and
+//! castling is not allowed. Capturing is compulsory, the player choosing
+//! among the captures when there are several, and the player left without a
+//! legal move, usually for having lost every piece, wins.
+//!
+//! Promotion to a king is not supported.
+
+use crate::game_state::board::ChessBoard;
+use crate::game_state::board::moves::MoveList;
+use crate::game_state::board::piece::Color;
+use crate::game_state::board::rules::{Outcome, Rules};
+
+/// Rules of antichess.
+pub struct AntichessRules;
+
+impl Rules for AntichessRules {
+    fn name(&self) -> &'static str {
+        "antichess"
+    }
+
+    fn generate_moves(&self, board: &mut ChessBoard, color: Color) -> MoveList {
+        let mut moves = board.generate_pseudo_legal_moves(color, false);
+
+        if moves.iter().any(|mv| mv.is_capture()) {
+            moves.retain(|mv| mv.is_capture());
+        }
+
+        moves
+    }
+
+    fn is_in_check(&self, _board: &ChessBoard, _color: Color) -> bool {
+        false
+    }
+
+    fn outcome_without_moves(&self, _board: &ChessBoard, _side_to_move: Color) -> Outcome {
+        Outcome::Win
+    }
+}
+
+/// The rules of antichess.
+pub static ANTICHESS: AntichessRules = AntichessRules;
@@ -0,0 +1,95 @@
+//! Atomic chess.
+//!
+//! Every capture is an explosion: the capturing piece, the captured piece and
+//! every piece other than a pawn on the eight surrounding squares are
+//! removed. A king can't capture, since it would blow itself up, and a move
+//! is illegal when it blows up its own king. Blowing up the enemy king wins
+//! at once, even from check.
+//!
+//! Kings standing next to each other are never in check, since neither can
+//! be exploded without the other.
+
+use crate::game_state::board::ChessBoard;
+use crate::game_state::board::directions;
+use crate::game_state::board::moves::{Move, MoveList};
+use crate::game_state::board::piece::{Color, PieceType};
+use crate::game_state::board::rules::{Outcome, Rules};
+
+/// Rules of atomic chess.
+pub struct AtomicRules;
+
+impl AtomicRules {
+    /// Checks that a move doesn't blow up its own king, and that it leaves
+    /// it out of check unless the enemy king is blown up.
+    fn is_legal(board: &mut ChessBoard, mv: &Move, color: Color) -> bool {
+        // Trying the moves isn't part of the search
+        let nodes = board.nodes;
+        board.make_move(mv);
+
+        let own_king = board.piece_list.get_king_square(color);
+        let enemy_king = board.piece_list.get_king_square(color.opposite());
+        let is_legal = own_king.is_some() && (enemy_king.is_none() || !board.is_in_check(color));
+
+        board.unmake_move(mv);
+        board.nodes = nodes;
+        is_legal
+    }
+}
+
+impl Rules for AtomicRules {
+    fn name(&self) -> &'static str {
+        "atomic"
+    }
+
+    fn generate_moves(&self, board: &mut ChessBoard, color: Color) -> MoveList {
+        let mut moves = board.generate_pseudo_legal_moves(color, true);
+
+        moves.retain(|mv| {
+            let king_captures = mv.is_capture() && mv.piece.get_type() == PieceType::King;
+            !king_captures && Self::is_legal(board, mv, color)
+        });
+
+        moves
+    }
+
+    fn is_in_check(&self, board: &ChessBoard, color: Color) -> bool {
+        let (Some(king), Some(enemy_king)) = (
+            board.piece_list.get_king_square(color),
+            board.piece_list.get_king_square(color.opposite()),
+        ) else {
+            return false;
+        };
+
+        let kings_touch = directions::ALL
+            .iter()
+            .any(|&direction| king + direction == enemy_king);
+        !kings_touch && !board.piece_list.is_king_in_check(board, color).is_empty()
+    }
+
+    fn exploded_squares(&self, board: &ChessBoard, mv: &Move) -> Vec<i16> {
+        let mut squares = vec![mv.to];
+
+        for direction in directions::ALL {
+            let square = mv.to + direction;
+            let piece = board.get_piece_on_square(square);
+            if piece.is_valid_piece() && piece.get_type() != PieceType::Pawn {
+                squares.push(square);
+            }
+        }
+
+        squares
+    }
+
+    fn outcome_without_moves(&self, board: &ChessBoard, side_to_move: Color) -> Outcome {
+        if board.piece_list.get_king_square(side_to_move).is_none()
+            || board.is_in_check(side_to_move)
+        {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+}
+
+/// The rules of atomic chess.
+pub static ATOMIC: AtomicRules = AtomicRules;
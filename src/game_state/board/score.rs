@@ -0,0 +1,217 @@
+//! Centipawn and mate scores shared by evaluation, search and the
+//! transposition table.
+//!
+//! Scores are stored as `i32` centipawns, so sums of evaluation terms and
+//! negations of extreme values can't overflow. Mate scores live at the top
+//! of the range: a side that mates in `n` plies scores [`Score::MATE`]` - n`,
+//! and every search score fits in the 16 bits used by the transposition
+//! table.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use crate::game_state::Color;
+
+/// Maximum number of plies a mate score can be away from the root.
+pub const MAX_PLY: i32 = 256;
+
+/// A position score in centipawns, or a mate score.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(i32);
+
+impl Score {
+    /// Score of an even position.
+    pub const ZERO: Score = Score(0);
+
+    /// Score of a drawn position.
+    pub const DRAW: Score = Score(0);
+
+    /// Score of a checkmate on the board, from the point of view of the side
+    /// giving mate.
+    pub const MATE: Score = Score(31_000);
+
+    /// Bound above every reachable score, used for the search window.
+    pub const INFINITE: Score = Score(32_000);
+
+    /// Smallest score that is a mate score.
+    const MATE_BOUND: i32 = Self::MATE.0 - MAX_PLY;
+
+    /// Largest score an evaluation can return without looking like a mate.
+    pub const MAX_EVAL: Score = Score(Self::MATE_BOUND - 1);
+
+    /// Creates a score from centipawns.
+    ///
+    /// # Arguments
+    ///
+    /// * `centipawns` - Score in hundredths of a pawn
+    pub const fn cp(centipawns: i32) -> Self {
+        Score(centipawns)
+    }
+
+    /// Gets the score in centipawns.
+    pub const fn centipawns(self) -> i32 {
+        self.0
+    }
+
+    /// Score of mating the opponent in `plies` half moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `plies` - Number of plies until the mate, capped at [`MAX_PLY`]
+    pub const fn mate_in(plies: u32) -> Self {
+        let plies = if plies > MAX_PLY as u32 {
+            MAX_PLY
+        } else {
+            plies as i32
+        };
+        Score(Self::MATE.0 - plies)
+    }
+
+    /// Score of being mated in `plies` half moves.
+    ///
+    /// # Arguments
+    ///
+    /// * `plies` - Number of plies until the mate, capped at [`MAX_PLY`]
+    pub const fn mated_in(plies: u32) -> Self {
+        Score(-Self::mate_in(plies).0)
+    }
+
+    /// Checks if the score announces a mate, for either side.
+    pub const fn is_mate(self) -> bool {
+        self.0.abs() >= Self::MATE_BOUND
+    }
+
+    /// Gets the number of moves to the mate, negative when the side of the
+    /// score is getting mated.
+    ///
+    /// # Returns
+    ///
+    /// `Some(moves)` for mate scores, `None` otherwise
+    pub fn mate_moves(self) -> Option<i32> {
+        if !self.is_mate() {
+            return None;
+        }
+
+        // Scores past the mate bound (the search window) count as mate now
+        let plies = (Self::MATE.0 - self.0.abs()).max(0);
+        Some(if self.0 > 0 {
+            (plies + 1) / 2
+        } else {
+            -(plies / 2)
+        })
+    }
+
+    /// Formats the score for the `score` field of a UCI `info` line.
+    ///
+    /// # Returns
+    ///
+    /// `"cp <centipawns>"` or `"mate <moves>"`
+    pub fn to_uci_string(self) -> String {
+        match self.mate_moves() {
+            Some(moves) => format!("mate {}", moves),
+            None => format!("cp {}", self.0),
+        }
+    }
+
+    /// Converts between White's point of view and the point of view of a
+    /// side. Black scores are negated, so converting twice is a no-op.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side whose point of view is wanted
+    pub fn relative_to(self, color: Color) -> Self {
+        match color {
+            Color::White => self,
+            Color::Black => -self,
+        }
+    }
+
+    /// Converts a mate score counted from the root of the search to one
+    /// counted from the position it is stored for, as kept in the
+    /// transposition table: the same position can be reached at any ply.
+    ///
+    /// # Arguments
+    ///
+    /// * `ply` - Distance of the position from the root
+    pub fn to_tt(self, ply: u32) -> Self {
+        self.shift_mate(ply as i32)
+    }
+
+    /// Converts a mate score read from the transposition table back to one
+    /// counted from the root of the search, see [`to_tt`](Self::to_tt).
+    ///
+    /// # Arguments
+    ///
+    /// * `ply` - Distance of the position from the root
+    pub fn from_tt(self, ply: u32) -> Self {
+        self.shift_mate(-(ply as i32))
+    }
+
+    /// Moves a mate score `plies` nearer to the mate, leaving other scores
+    /// and the bounds of the search window alone.
+    fn shift_mate(self, plies: i32) -> Self {
+        if !self.is_mate() || self.0.abs() > Self::MATE.0 {
+            return self;
+        }
+
+        let shifted = (self.0.abs() + plies).clamp(Self::MATE_BOUND, Self::MATE.0);
+        Score(shifted * self.0.signum())
+    }
+
+    /// Packs the score into the 16 bits of a transposition table entry.
+    ///
+    /// Search scores are bounded by [`Score::INFINITE`], which fits in an
+    /// `i16`, so the conversion is lossless.
+    pub fn to_i16(self) -> i16 {
+        debug_assert!(self.0.abs() <= Self::INFINITE.0, "score out of range");
+        self.0.clamp(-Self::INFINITE.0, Self::INFINITE.0) as i16
+    }
+}
+
+impl From<i16> for Score {
+    fn from(centipawns: i16) -> Self {
+        Score(centipawns as i32)
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_uci_string())
+    }
+}
+
+impl Neg for Score {
+    type Output = Score;
+
+    fn neg(self) -> Score {
+        Score(-self.0)
+    }
+}
+
+impl Add for Score {
+    type Output = Score;
+
+    fn add(self, rhs: Score) -> Score {
+        Score(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Score {
+    type Output = Score;
+
+    fn sub(self, rhs: Score) -> Score {
+        Score(self.0 - rhs.0)
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, rhs: Score) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Score {
+    fn sub_assign(&mut self, rhs: Score) {
+        self.0 -= rhs.0;
+    }
+}
@@ -13,19 +13,51 @@
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rand::Rng;
 
 use crate::game_state::ChessBoard;
 use crate::game_state::Color;
 use crate::game_state::Move;
+use crate::game_state::board::score::Score;
 
+pub mod clock;
 pub mod minimax_alpha_beta;
+pub mod params;
 pub mod pure_minimax;
 pub mod pure_negamax;
 pub mod quiescence;
+pub mod trace;
 
+pub use clock::SearchClock;
 pub use minimax_alpha_beta::MinimaxAlphaBeta;
+pub use params::SearchParams;
 pub use pure_minimax::PureMinimax;
 pub use pure_negamax::PureNegamax;
+pub use trace::SearchTrace;
+
+/// Time after which the root move being searched is reported with `info
+/// currmove`. Shorter searches would flood the GUI with lines nobody reads.
+const CURRMOVE_DELAY: Duration = Duration::from_secs(3);
+
+/// Largest score loss accepted to play the move learned from earlier games
+/// instead of the best move, see
+/// [`set_learned_move`](ChessBoard::set_learned_move).
+pub const LEARNED_MOVE_MARGIN: Score = Score::cp(30);
+
+/// Receiver of the `info` lines of a running search.
+///
+/// The search only formats the lines, the UCI layer decides where they go;
+/// searches on a board without a reporter stay silent.
+pub type InfoReporter = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Remaining search depth in plies.
+///
+/// Signed, so reductions and the root searching its moves one ply shallower
+/// can take it to zero or below: the search then stops at the horizon
+/// instead of underflowing.
+pub type Depth = i32;
 
 /// Low-level recursive tree search algorithm.
 ///
@@ -50,10 +82,10 @@ pub trait SearchAlgorithm {
     fn tree_search(
         &self,
         board: &mut ChessBoard,
-        depth: u8,
+        depth: Depth,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> i16;
+    ) -> Score;
 
     /// Search for the best move at the root level.
     ///
@@ -64,7 +96,8 @@ pub trait SearchAlgorithm {
     /// # Arguments
     ///
     /// * `board` - Mutable reference to the chess board
-    /// * `depth` - Search depth in plies
+    /// * `depth` - Search depth in plies, the root moves are evaluated
+    ///   at the horizon when it is 1 or less
     /// * `side_to_move` - Color of the player to move
     /// * `stop_flag` - Atomic flag to abort the search early
     ///
@@ -75,31 +108,37 @@ pub trait SearchAlgorithm {
     fn search(
         &self,
         board: &mut ChessBoard,
-        depth: u8,
+        depth: Depth,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> (i16, Option<Move>) {
+    ) -> (Score, Option<Move>) {
+        board.search_side = side_to_move;
+        board.start_seldepth();
         let moves = board.generate_moves(side_to_move);
+        if moves.is_empty() {
+            let score = board
+                .rules()
+                .outcome_without_moves(board, side_to_move)
+                .score_at(0);
+            return (score.relative_to(side_to_move), None);
+        }
         let mut best_move: Option<Move> = None;
-        let mut best_score: Option<i16> = None;
+        let mut best_score: Option<Score> = None;
 
         for mv in moves {
             if stop_flag.load(Ordering::Acquire) {
                 if let Some(score) = best_score {
-                    let white_score = if side_to_move == Color::White {
-                        score
-                    } else {
-                        -score
-                    };
-                    return (white_score, best_move);
+                    return (score.relative_to(side_to_move), best_move);
                 }
-                return (0, None);
+                return (Score::ZERO, None);
             }
 
+            board.trace_enter(&mv, -Score::INFINITE, Score::INFINITE);
             board.make_move(&mv);
             let score =
                 -self.tree_search(board, depth - 1, side_to_move.opposite(), stop_flag.clone());
             board.unmake_move(&mv);
+            board.trace_exit(score);
 
             if best_score.is_none() || score > best_score.unwrap() {
                 best_score = Some(score);
@@ -107,14 +146,9 @@ pub trait SearchAlgorithm {
             }
         }
 
-        let best_score = best_score.unwrap_or(0);
-        let white_score = if side_to_move == Color::White {
-            best_score
-        } else {
-            -best_score
-        };
+        let best_score = best_score.unwrap_or(Score::ZERO);
 
-        (white_score, best_move)
+        (best_score.relative_to(side_to_move), best_move)
     }
 }
 
@@ -139,7 +173,7 @@ pub trait Search {
         board: &mut ChessBoard,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> (i16, Option<Move>);
+    ) -> (Score, Option<Move>);
 }
 
 /// Single-shot search at a fixed depth.
@@ -172,17 +206,306 @@ impl<A: SearchAlgorithm> Search for DepthFirst<A> {
         board: &mut ChessBoard,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> (i16, Option<Move>) {
+    ) -> (Score, Option<Move>) {
         self.algorithm
-            .search(board, self.max_depth, side_to_move, stop_flag)
+            .search(board, Depth::from(self.max_depth), side_to_move, stop_flag)
+    }
+}
+
+/// Progress of a root move in the iteration being searched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootMoveStatus {
+    /// Not searched yet in this iteration
+    Pending,
+    /// Searched to the depth of the iteration, its score can be trusted
+    Searched,
+    /// The search was stopped while searching the move, its score is garbage
+    Interrupted,
+}
+
+/// A move at the root of the search tree with its search statistics.
+///
+/// Root moves are kept across iterative deepening iterations so the moves
+/// can be reordered by the scores of the previous iteration.
+#[derive(Clone, Debug)]
+pub struct RootMove {
+    /// The root move
+    pub mv: Move,
+    /// Side-relative score from the last completed iteration
+    pub score: Score,
+    /// Side-relative score from the iteration before the last one
+    pub previous_score: Score,
+    /// Nodes searched below this move in the last completed iteration
+    pub nodes: u64,
+    /// Progress of the move in the current iteration
+    pub status: RootMoveStatus,
+    /// Side-relative score from the current iteration, only meaningful once
+    /// the move is [`Searched`](RootMoveStatus::Searched)
+    pub iteration_score: Score,
+    /// Nodes searched below this move in the current iteration
+    pub iteration_nodes: u64,
+}
+
+impl RootMove {
+    /// Creates an unsearched root move.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - The root move
+    pub fn new(mv: Move) -> Self {
+        RootMove {
+            mv,
+            score: -Score::INFINITE,
+            previous_score: -Score::INFINITE,
+            nodes: 0,
+            status: RootMoveStatus::Pending,
+            iteration_score: -Score::INFINITE,
+            iteration_nodes: 0,
+        }
+    }
+}
+
+/// The root moves of a search with the progress of the current iteration.
+///
+/// Scores of a move are only committed when the whole iteration completes,
+/// but the moves fully searched in an interrupted iteration are still used
+/// to pick the move to play when the search is stopped.
+#[derive(Clone, Debug)]
+pub struct RootMoves {
+    /// Root moves, best first after each completed iteration
+    moves: Vec<RootMove>,
+    /// Depth of the last completed iteration, 0 before the first one
+    completed_depth: u8,
+}
+
+impl RootMoves {
+    /// Creates the root moves of a position, none of them searched.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` - Legal moves of the position, in the order to search them
+    pub fn new(moves: impl IntoIterator<Item = Move>) -> Self {
+        RootMoves {
+            moves: moves.into_iter().map(RootMove::new).collect(),
+            completed_depth: 0,
+        }
+    }
+
+    /// Gets the root moves, in search order.
+    pub fn moves(&self) -> &[RootMove] {
+        &self.moves
+    }
+
+    /// Gets the number of root moves.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Whether the position has no legal move.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Gets the depth of the last completed iteration, 0 if none completed.
+    pub fn completed_depth(&self) -> u8 {
+        self.completed_depth
+    }
+
+    /// Marks every move as pending for a new iteration.
+    pub fn start_iteration(&mut self) {
+        for root_move in &mut self.moves {
+            root_move.status = RootMoveStatus::Pending;
+        }
+    }
+
+    /// Records the score of a move searched to the depth of the iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the move in [`moves`](Self::moves)
+    /// * `score` - Side-relative score of the move
+    /// * `nodes` - Nodes searched below the move
+    pub fn record(&mut self, index: usize, score: Score, nodes: u64) {
+        let root_move = &mut self.moves[index];
+        root_move.status = RootMoveStatus::Searched;
+        root_move.iteration_score = score;
+        root_move.iteration_nodes = nodes;
+    }
+
+    /// Marks a move whose search was stopped before completing.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the move in [`moves`](Self::moves)
+    pub fn interrupt(&mut self, index: usize) {
+        self.moves[index].status = RootMoveStatus::Interrupted;
+    }
+
+    /// Commits the scores of a completed iteration and sorts the moves by
+    /// score, best first.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Depth of the completed iteration
+    pub fn complete_iteration(&mut self, depth: u8) {
+        for root_move in &mut self.moves {
+            root_move.previous_score = root_move.score;
+            root_move.score = root_move.iteration_score;
+            root_move.nodes = root_move.iteration_nodes;
+        }
+
+        // Stable sort keeps the previous order between equal scores
+        self.moves
+            .sort_by_key(|root_move| std::cmp::Reverse(root_move.score));
+        self.completed_depth = depth;
+    }
+
+    /// Gets the move to play and its side-relative score.
+    ///
+    /// Moves are searched in the order of the previous iteration, so the
+    /// current iteration can only be trusted once the previous best move was
+    /// searched again: the best move fully searched at the new depth is then
+    /// preferred. Otherwise the best move of the last completed iteration is
+    /// kept. Moves whose search was interrupted are never chosen.
+    ///
+    /// # Returns
+    ///
+    /// The best move with its score, None if no move was fully searched at
+    /// any depth
+    pub fn best(&self) -> Option<(Score, &Move)> {
+        let first_searched = self
+            .moves
+            .first()
+            .is_some_and(|root_move| root_move.status == RootMoveStatus::Searched);
+
+        if first_searched || self.completed_depth == 0 {
+            let searched = self
+                .moves
+                .iter()
+                .filter(|root_move| root_move.status == RootMoveStatus::Searched)
+                // The first move wins among equal scores
+                .rev()
+                .max_by_key(|root_move| root_move.iteration_score);
+            if let Some(root_move) = searched {
+                return Some((root_move.iteration_score, &root_move.mv));
+            }
+        }
+
+        (self.completed_depth > 0).then(|| (self.moves[0].score, &self.moves[0].mv))
+    }
+
+    /// Moves a root move to the front, so the next iteration searches it
+    /// first.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the move in [`moves`](Self::moves)
+    pub fn search_first(&mut self, index: usize) {
+        self.moves[..=index].rotate_right(1);
+    }
+
+    /// Gets a root move if the last completed iteration scored it close to
+    /// the best one.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the move in [`moves`](Self::moves)
+    /// * `margin` - Largest score difference with the best move
+    ///
+    /// # Returns
+    ///
+    /// The move with its score, None if it scored lower or no iteration
+    /// completed
+    pub fn pick_if_within(&self, index: usize, margin: Score) -> Option<(Score, &Move)> {
+        let best_score = self.moves.first()?.score;
+        let root_move = &self.moves[index];
+        if self.completed_depth == 0 || root_move.score < best_score - margin {
+            return None;
+        }
+
+        Some((root_move.score, &root_move.mv))
+    }
+
+    /// Picks a random move among the moves scored close to the best one by
+    /// the last completed iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin` - Largest score difference with the best move
+    /// * `rng` - Source of randomness
+    ///
+    /// # Returns
+    ///
+    /// The picked move with its score, None if no iteration completed
+    pub fn pick_within(&self, margin: Score, rng: &mut impl Rng) -> Option<(Score, &Move)> {
+        let best_score = self.moves.first()?.score;
+        if self.completed_depth == 0 {
+            return None;
+        }
+
+        // Moves are sorted best first by the completed iteration
+        let candidates = self
+            .moves
+            .iter()
+            .take_while(|root_move| root_move.score >= best_score - margin)
+            .count();
+        let root_move = &self.moves[rng.random_range(0..candidates)];
+
+        Some((root_move.score, &root_move.mv))
     }
 }
 
+/// Reports the `info` line of the best root move of an iteration.
+///
+/// # Arguments
+///
+/// * `board` - Board searched, for the selective depth and the node count
+/// * `depth` - Depth of the iteration
+/// * `score` - Score of the move, from the side to move's point of view
+/// * `lower_bound` - Whether the score is only a lower bound, the iteration
+///   not being over
+/// * `mv` - Best root move
+fn report_best_move(board: &mut ChessBoard, depth: u8, score: Score, lower_bound: bool, mv: &Move) {
+    // The node count is shared by every thread searching with the clock,
+    // this thread's count is brought up to date
+    board.report_nodes();
+    let nodes = board
+        .clock()
+        .map(|clock| format!(" nodes {} nps {}", clock.nodes(), clock.nps()))
+        .unwrap_or_default();
+    let bound = if lower_bound { " lowerbound" } else { "" };
+    board.report_info(|| {
+        format!(
+            "info depth {} seldepth {} score {}{}{} pv {}",
+            depth,
+            board.seldepth().max(depth.into()),
+            score.to_uci_string(),
+            bound,
+            nodes,
+            board.move_to_uci(mv)
+        )
+    });
+}
+
 /// Iterative deepening search strategy.
 ///
-/// Searches from depth 1 up to `max_depth`, reusing the best move from
-/// the previous iteration as a starting point. Each iteration restarts
-/// the underlying algorithm at the progressively deeper depth.
+/// Searches from depth 1 up to `max_depth`, or the depth limit of the
+/// board's [`SearchClock`] when it is lower, keeping the root move list
+/// between iterations and searching the moves in the order of the scores
+/// found by the previous iteration. The search returns early when the best
+/// move stays far ahead of the others (an "easy move"), and plays forced
+/// moves, the only legal move or an obvious recapture, without searching
+/// unless [`set_instant_moves`](ChessBoard::set_instant_moves) disabled it.
+/// A move learned from earlier games, see
+/// [`set_learned_move`](ChessBoard::set_learned_move), is searched first
+/// and played when it scores within [`LEARNED_MOVE_MARGIN`] of the best
+/// move.
+///
+/// Only the root moves of
+/// [`generate_root_moves`](ChessBoard::generate_root_moves) are searched, so
+/// `go searchmoves` and `go avoidmoves` apply. A search resuming an analysis,
+/// see [`set_resume_depth`](ChessBoard::set_resume_depth), starts deepening
+/// at the depth it was given.
 pub struct IterativeDeepening<A: SearchAlgorithm> {
     max_depth: u8,
     algorithm: A,
@@ -201,6 +524,169 @@ impl<A: SearchAlgorithm> IterativeDeepening<A> {
             algorithm,
         }
     }
+
+    /// Searches every root move to the given depth.
+    ///
+    /// Scores are only committed when the whole iteration completes, so an
+    /// interrupted iteration does not leave half-updated root moves behind.
+    /// The moves fully searched before a stop keep their status, so the
+    /// caller can still use them.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the iteration completed without being stopped
+    fn search_root_moves(
+        &self,
+        board: &mut ChessBoard,
+        root_moves: &mut RootMoves,
+        depth: u8,
+        side_to_move: Color,
+        stop_flag: &Arc<AtomicBool>,
+    ) -> bool {
+        root_moves.start_iteration();
+        // Only the last completed iteration is kept in the trace
+        board.trace_start_iteration();
+        board.start_seldepth();
+        let mut best_score = None;
+
+        for index in 0..root_moves.len() {
+            // The clock is also checked here, so the hard limit is honored
+            // even when the previous move searched fewer nodes than a check
+            if board.clock().is_some_and(SearchClock::check) || stop_flag.load(Ordering::Acquire) {
+                return false;
+            }
+
+            let mv = root_moves.moves()[index].mv.clone();
+            if board
+                .clock()
+                .is_some_and(|clock| clock.elapsed() >= CURRMOVE_DELAY)
+            {
+                board.report_info(|| {
+                    format!(
+                        "info depth {} currmove {} currmovenumber {}",
+                        depth,
+                        board.move_to_uci(&mv),
+                        index + 1
+                    )
+                });
+            }
+
+            let nodes_before = board.nodes();
+            board.trace_enter(&mv, -Score::INFINITE, Score::INFINITE);
+            board.make_move(&mv);
+            let score = -self.algorithm.tree_search(
+                board,
+                Depth::from(depth) - 1,
+                side_to_move.opposite(),
+                stop_flag.clone(),
+            );
+            board.unmake_move(&mv);
+            board.trace_exit(score);
+
+            if stop_flag.load(Ordering::Acquire) {
+                root_moves.interrupt(index);
+                return false;
+            }
+
+            root_moves.record(index, score, board.nodes() - nodes_before);
+
+            // A new best move is reported right away, long iterations would
+            // leave the GUI without news. The moves left can only raise the
+            // score of the iteration
+            if best_score.is_some_and(|best| score > best) {
+                report_best_move(board, depth, score, true, &mv);
+            }
+            best_score = best_score.max(Some(score));
+        }
+
+        root_moves.complete_iteration(depth);
+        board.trace_complete_iteration();
+
+        true
+    }
+
+    /// Picks a move to play when the search was stopped before any root move
+    /// was searched.
+    ///
+    /// The best move stored in the transposition table for the root, left by
+    /// the search of an earlier move, is preferred over the first generated
+    /// move.
+    fn emergency_move(board: &ChessBoard, root_moves: &RootMoves, side_to_move: Color) -> Move {
+        let tt_move = board
+            .transposition_table
+            .retrieve_position(board.hash)
+            .and_then(|position| board.transposition_move(position.best_move, side_to_move));
+
+        tt_move
+            .and_then(|tt_move| {
+                root_moves
+                    .moves()
+                    .iter()
+                    .find(|root_move| root_move.mv.encode(board) == tt_move.encode(board))
+            })
+            .unwrap_or(&root_moves.moves()[0])
+            .mv
+            .clone()
+    }
+
+    /// Finds the move set with
+    /// [`set_learned_move`](ChessBoard::set_learned_move) among the root
+    /// moves.
+    fn learned_move_index(board: &ChessBoard, root_moves: &RootMoves) -> Option<usize> {
+        let encoded = board.learned_move()?.encode(board);
+        root_moves
+            .moves()
+            .iter()
+            .position(|root_move| root_move.mv.encode(board) == encoded)
+    }
+
+    /// Picks a move forced enough to be played without searching.
+    ///
+    /// That is the only legal move or, in timed searches of standard chess,
+    /// the only recapture on the square the opponent just captured on when
+    /// it doesn't lose material and no other capture wins any. The time
+    /// saved on recaptures is kept for the moves that need it.
+    fn instant_move(board: &ChessBoard, root_moves: &[RootMove]) -> Option<Move> {
+        if let [only] = root_moves {
+            return Some(only.mv.clone());
+        }
+
+        let square = board.last_capture()?;
+        if !board.clock().is_some_and(SearchClock::has_time_limit) || !board.is_standard_chess() {
+            return None;
+        }
+
+        let mut captures = root_moves
+            .iter()
+            .map(|root_move| &root_move.mv)
+            .filter(|mv| mv.is_capture());
+        let mut recaptures = captures.clone().filter(|mv| mv.to == square);
+        let recapture = recaptures.next()?;
+        if recaptures.next().is_some() || board.see(recapture) < 0 {
+            return None;
+        }
+
+        // Another capture winning material is worth a search
+        captures
+            .all(|mv| mv.to == square || board.see(mv) <= 0)
+            .then(|| recapture.clone())
+    }
+
+    /// Checks whether the best root move is far enough ahead to stop searching.
+    ///
+    /// The best move must beat the second best by the easy move margin of the
+    /// [`SearchParams`] in the last two iterations.
+    fn is_easy_move(root_moves: &[RootMove], depth: u8, params: &SearchParams) -> bool {
+        if depth < params.easy_move_min_depth || root_moves.len() < 2 {
+            return false;
+        }
+
+        let best = &root_moves[0];
+        let second = &root_moves[1];
+
+        best.score - second.score >= params.easy_move_margin
+            && best.previous_score - second.previous_score >= params.easy_move_margin
+    }
 }
 
 impl<A: SearchAlgorithm> Search for IterativeDeepening<A> {
@@ -209,25 +695,95 @@ impl<A: SearchAlgorithm> Search for IterativeDeepening<A> {
         board: &mut ChessBoard,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> (i16, Option<Move>) {
-        let mut best_move = None;
-        let mut best_score = if side_to_move == Color::White {
-            i16::MIN
-        } else {
-            i16::MAX
-        };
+    ) -> (Score, Option<Move>) {
+        let mut root_moves = RootMoves::new(board.generate_root_moves(side_to_move));
+        board.completed_depth = 0;
+        board.search_side = side_to_move;
+        // Entries of earlier searches become the first to be replaced, unless
+        // this search continues the analysis they come from
+        if board.resume_depth.is_none() {
+            board.transposition_table.new_search();
+        }
 
-        for depth in 1..=self.max_depth {
-            if stop_flag.load(Ordering::Acquire) {
+        // Without legal moves the rules decide the game
+        if root_moves.is_empty() {
+            let score = board
+                .rules()
+                .outcome_without_moves(board, side_to_move)
+                .score_at(0);
+            return (score.relative_to(side_to_move), None);
+        }
+        // A forced move doesn't need to be searched, it is reported as a
+        // depth 1 search scored by the evaluation of the root
+        if board.instant_moves()
+            && let Some(mv) = Self::instant_move(board, root_moves.moves())
+        {
+            let score = board.evaluate_for(side_to_move);
+            board.start_seldepth();
+            board.completed_depth = 1;
+            report_best_move(board, 1, score, false, &mv);
+            return (score.relative_to(side_to_move), Some(mv));
+        }
+
+        // A move learned from earlier games is searched first, so its score
+        // is known even if the first iteration is cut short
+        if let Some(index) = Self::learned_move_index(board, &root_moves) {
+            root_moves.search_first(index);
+        }
+
+        let max_depth = board.clock().map_or(self.max_depth, |clock| {
+            self.max_depth.min(clock.depth_limit())
+        });
+        let first_depth = board
+            .resume_depth
+            .map_or(1, |depth| depth.min(max_depth).max(1));
+        for depth in first_depth..=max_depth {
+            if !self.search_root_moves(board, &mut root_moves, depth, side_to_move, &stop_flag) {
+                break;
+            }
+            board.completed_depth = depth;
+
+            let best = &root_moves.moves()[0];
+            report_best_move(board, depth, best.score, false, &best.mv);
+
+            // Searches without a time limit, `go infinite` and `go ponder`
+            // included, only end when stopped or at their depth
+            if !board.clock().is_some_and(SearchClock::has_time_limit) {
+                continue;
+            }
+
+            if Self::is_easy_move(root_moves.moves(), depth, board.search_params()) {
+                break;
+            }
+
+            // The next iteration would take longer than all the previous
+            // ones and would likely be cut off
+            if board.clock().is_some_and(SearchClock::soft_limit_reached) {
                 break;
             }
-            let (score, mv) = self
-                .algorithm
-                .search(board, depth, side_to_move, stop_flag.clone());
-            best_score = score;
-            best_move = mv.or(best_move);
         }
 
-        (best_score, best_move)
+        // The learned move is played when it is about as good as the best
+        // move. Otherwise, for opening variety, any move close enough to the
+        // best one may be played
+        let learned = Self::learned_move_index(board, &root_moves)
+            .and_then(|index| root_moves.pick_if_within(index, LEARNED_MOVE_MARGIN));
+        let picked = match (learned, board.variety()) {
+            (Some(learned), _) => Some(learned),
+            (None, Some(margin)) => root_moves
+                .pick_within(margin, &mut rand::rng())
+                .or_else(|| root_moves.best()),
+            (None, None) => root_moves.best(),
+        };
+
+        match picked {
+            Some((score, mv)) => (score.relative_to(side_to_move), Some(mv.clone())),
+            // Stopped before any move was searched, usually by a hard
+            // deadline: there must still be a move to play
+            None => (
+                board.evaluate_for(side_to_move).relative_to(side_to_move),
+                Some(Self::emergency_move(board, &root_moves, side_to_move)),
+            ),
+        }
     }
 }
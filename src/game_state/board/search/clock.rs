@@ -0,0 +1,176 @@
+//! Time limits checked from inside the search.
+//!
+//! The search stops itself: the board checks the clock every
+//! [`NODES_PER_TIME_CHECK`] nodes and raises the stop flag once the hard limit
+//! is reached, so time controls are honored within a few milliseconds instead
+//! of depending on a sleeping timer thread. The soft limit is checked between
+//! iterative deepening iterations, since an iteration started past it would
+//! rarely complete.
+//!
+//! The clock also counts the nodes of every thread searching with it. Each
+//! board reports its nodes in batches of [`NODES_PER_TIME_CHECK`] when it
+//! checks the clock, so threads don't contend on the counter at every node.
+//! The total lags behind by less than a batch per thread, which is also how
+//! far a `go nodes` limit can be overshot.
+//!
+//! The depth limit (`go depth`, capped by the `MaxDepth` option) is read by
+//! iterative deepening, which starts no iteration deeper than it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Number of nodes searched between two checks of the clock.
+pub const NODES_PER_TIME_CHECK: u64 = 1024;
+
+/// Marks a limit that is not set.
+const NO_LIMIT: u64 = u64::MAX;
+
+/// Clock of a running search with its soft and hard time limits, its node
+/// budget and its depth limit.
+///
+/// Limits are kept in milliseconds since the search started, in atomics, so
+/// they can be set after the search started, e.g. on a `ponderhit`.
+pub struct SearchClock {
+    /// When the search started
+    start: Instant,
+    /// Elapsed time after which no new iteration is started
+    soft_limit: AtomicU64,
+    /// Elapsed time after which the search is stopped
+    hard_limit: AtomicU64,
+    /// Nodes reported by the threads searching with this clock
+    nodes: AtomicU64,
+    /// Number of nodes after which the search is stopped
+    node_limit: AtomicU64,
+    /// Deepest iteration the search may start, `u8::MAX` for no limit
+    depth_limit: AtomicU8,
+    /// Flag raised to stop the search
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl SearchClock {
+    /// Starts a clock without time limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `stop_flag` - Flag raised when the hard limit is reached
+    pub fn new(stop_flag: Arc<AtomicBool>) -> Self {
+        SearchClock {
+            start: Instant::now(),
+            soft_limit: AtomicU64::new(NO_LIMIT),
+            hard_limit: AtomicU64::new(NO_LIMIT),
+            nodes: AtomicU64::new(0),
+            node_limit: AtomicU64::new(NO_LIMIT),
+            depth_limit: AtomicU8::new(u8::MAX),
+            stop_flag,
+        }
+    }
+
+    /// Gets the time since the search started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Limits the search to `time_to_think` from now.
+    ///
+    /// The hard limit is the whole time to think, the soft limit half of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_to_think` - Time the search may still use
+    pub fn set_time_to_think(&self, time_to_think: Duration) {
+        let now = self.elapsed_millis();
+        let time_to_think = time_to_think.as_millis() as u64;
+
+        self.soft_limit
+            .store(now.saturating_add(time_to_think / 2), Ordering::Release);
+        self.hard_limit
+            .store(now.saturating_add(time_to_think), Ordering::Release);
+    }
+
+    /// Checks if the search is limited in time, rather than only in depth or
+    /// nodes.
+    pub fn has_time_limit(&self) -> bool {
+        self.hard_limit.load(Ordering::Acquire) != NO_LIMIT
+    }
+
+    /// Checks if the search was asked to stop, by `stop` or by one of its
+    /// limits.
+    pub fn is_stopped(&self) -> bool {
+        self.stop_flag.load(Ordering::Acquire)
+    }
+
+    /// Checks if the soft limit has passed and no new iteration should start.
+    pub fn soft_limit_reached(&self) -> bool {
+        self.elapsed_millis() >= self.soft_limit.load(Ordering::Acquire)
+    }
+
+    /// Limits the search to a number of nodes over all its threads (`go
+    /// nodes`).
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Nodes the search may visit
+    pub fn set_node_limit(&self, nodes: u64) {
+        self.node_limit.store(nodes, Ordering::Release);
+    }
+
+    /// Limits the search to a depth in plies (`go depth`).
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Deepest iteration the search may start
+    pub fn set_depth_limit(&self, depth: u8) {
+        self.depth_limit.store(depth, Ordering::Release);
+    }
+
+    /// Gets the deepest iteration the search may start, `u8::MAX` when the
+    /// depth is not limited.
+    pub fn depth_limit(&self) -> u8 {
+        self.depth_limit.load(Ordering::Acquire)
+    }
+
+    /// Adds a batch of nodes searched by one of the threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Nodes searched since the thread last reported
+    pub fn add_nodes(&self, nodes: u64) {
+        self.nodes.fetch_add(nodes, Ordering::Relaxed);
+    }
+
+    /// Gets the nodes reported by all the threads so far.
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Gets the search speed in nodes per second over all the threads.
+    pub fn nps(&self) -> u64 {
+        let seconds = self.elapsed().as_secs_f64();
+        if seconds > 0.0 {
+            (self.nodes() as f64 / seconds) as u64
+        } else {
+            0
+        }
+    }
+
+    /// Stops the search if the hard limit has passed or the node budget is
+    /// spent.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the search was stopped
+    pub fn check(&self) -> bool {
+        if self.elapsed_millis() >= self.hard_limit.load(Ordering::Acquire)
+            || self.nodes() >= self.node_limit.load(Ordering::Acquire)
+        {
+            self.stop_flag.store(true, Ordering::Release);
+            return true;
+        }
+        false
+    }
+
+    fn elapsed_millis(&self) -> u64 {
+        self.elapsed().as_millis() as u64
+    }
+}
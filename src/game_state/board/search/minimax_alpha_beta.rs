@@ -3,6 +3,21 @@
 //! Implements alpha-beta pruning on top of the minimax algorithm using the
 //! negamax formulation. Uses side-relative scoring throughout for compatibility
 //! with the default `search()` implementation.
+//!
+//! At the horizon the search hands over to [`quiescence`], which plays out
+//! the captures before the position is evaluated.
+//!
+//! Positions are first tested with a null move: if passing the turn still
+//! fails high at reduced depth, the position is pruned. The reduction shrinks
+//! with the game phase, as a free move is worth more in the endgame. At high
+//! depth the cutoff is verified by a reduced search without null move, so
+//! zugzwang positions are not pruned by mistake. When the null move fails
+//! low, its refutation is the threat the opponent would play with a free
+//! move, and the moves that parry it are searched before the other quiet
+//! moves.
+//!
+//! Moves giving check and passed pawn pushes to the seventh rank are searched
+//! one ply deeper, so forcing lines are not cut short at the horizon.
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -10,35 +25,118 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use crate::game_state::ChessBoard;
 use crate::game_state::Color;
 use crate::game_state::Move;
-use crate::game_state::board::search::SearchAlgorithm;
+use crate::game_state::board::moves::CompactMove;
+use crate::game_state::board::piece::PieceType;
+use crate::game_state::board::score::Score;
+use crate::game_state::board::search::quiescence::{quiescence, quiescence_with_checks};
+use crate::game_state::board::search::trace::TraceEvent;
+use crate::game_state::board::search::{Depth, SearchAlgorithm};
 use crate::game_state::board::transposition_table::{NodeType, TranspositionTableData};
 
+/// Distance from the root, in plies, from which checks are no longer
+/// extended.
+const CHECK_EXTENSION_MAX_PLY: u32 = 32;
+
 /// Minimax search with alpha-beta pruning and transposition table support.
 ///
 /// Uses the negamax formulation: a single recursive function for both players
 /// with side-relative scoring. Alpha/beta bounds are negated at each recursion
-/// level. Provides transposition table probing and capture-based move ordering.
+/// level. Provides transposition table probing and capture/check move ordering.
 pub struct MinimaxAlphaBeta;
 
 impl SearchAlgorithm for MinimaxAlphaBeta {
     fn tree_search(
         &self,
         board: &mut ChessBoard,
-        depth: u8,
+        depth: Depth,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> i16 {
+    ) -> Score {
         minimax_alpha_beta(
             board,
             depth,
-            i16::MIN + 1,
-            i16::MAX,
+            -Score::INFINITE,
+            Score::INFINITE,
             side_to_move,
             stop_flag,
+            true,
         )
     }
 }
 
+/// A move the opponent would play if the side to move passed, attacking one
+/// of its pieces.
+#[derive(Clone, Copy, Debug)]
+struct Threat {
+    /// Standard square (0-63) of the attacking piece
+    from: usize,
+    /// Standard square (0-63) of the attacked piece
+    to: usize,
+}
+
+impl Threat {
+    /// Gets the threat refuting a null move, from the best move stored in the
+    /// transposition table for the position after the null move.
+    ///
+    /// Only moves that capture a piece of the side to move are threats.
+    fn from_refutation(
+        board: &ChessBoard,
+        refutation: &Move,
+        side_to_move: Color,
+    ) -> Option<Threat> {
+        // The pawn taken en passant isn't on the destination square
+        let attacked = refutation
+            .en_passant_capture(board.board_width)
+            .map_or(refutation.to, |(square, _)| square);
+
+        refutation
+            .captured_piece
+            .is_friend(side_to_move)
+            .then(|| Threat {
+                from: board.map_to_standard_chess_board(refutation.from),
+                to: board.map_to_standard_chess_board(attacked),
+            })
+    }
+
+    /// Checks if a move parries the threat by moving the attacked piece away,
+    /// capturing the attacker or blocking the line between them.
+    fn is_parried_by(&self, board: &ChessBoard, mv: &Move) -> bool {
+        let from = board.map_to_standard_chess_board(mv.from);
+        let to = board.map_to_standard_chess_board(mv.to);
+
+        from == self.to || to == self.from || is_between(to, self.from, self.to)
+    }
+}
+
+/// Checks if a square lies strictly between two squares on a rank, file or
+/// diagonal.
+fn is_between(square: usize, from: usize, to: usize) -> bool {
+    let coordinates = |square: usize| ((square / 8) as i32, (square % 8) as i32);
+    let (from_rank, from_file) = coordinates(from);
+    let (to_rank, to_file) = coordinates(to);
+    let (rank_step, file_step) = (
+        (to_rank - from_rank).signum(),
+        (to_file - from_file).signum(),
+    );
+
+    let aligned = from_rank == to_rank
+        || from_file == to_file
+        || (to_rank - from_rank).abs() == (to_file - from_file).abs();
+    if !aligned || from == to {
+        return false;
+    }
+
+    let (mut rank, mut file) = (from_rank + rank_step, from_file + file_step);
+    while (rank, file) != (to_rank, to_file) {
+        if (rank, file) == coordinates(square) {
+            return true;
+        }
+        rank += rank_step;
+        file += file_step;
+    }
+    false
+}
+
 /// Recursive negamax search with alpha-beta pruning and transposition table.
 ///
 /// Returns a side-relative score (positive = good for `side_to_move`).
@@ -47,90 +145,151 @@ impl SearchAlgorithm for MinimaxAlphaBeta {
 /// # Arguments
 ///
 /// * `board` - Mutable reference to the chess board
-/// * `depth` - Remaining search depth in plies
+/// * `depth` - Remaining search depth in plies, handed over to quiescence
+///   at zero or below
 /// * `alpha` - Lower bound (best score current side can guarantee)
 /// * `beta` - Upper bound (best score opponent can force)
 /// * `side_to_move` - Color of the player to move
 /// * `stop_flag` - Atomic flag to abort the search early
+/// * `null_move_allowed` - Whether a null move may be tried in this position,
+///   false right after a null move and in verification searches
 ///
 /// # Returns
 ///
 /// Side-relative evaluation score
 fn minimax_alpha_beta(
     board: &mut ChessBoard,
-    depth: u8,
-    mut alpha: i16,
-    beta: i16,
+    depth: Depth,
+    mut alpha: Score,
+    beta: Score,
     side_to_move: Color,
     stop_flag: Arc<AtomicBool>,
-) -> i16 {
+    null_move_allowed: bool,
+) -> Score {
+    // A repeated position is a draw. This is checked before probing the
+    // transposition table, whose scores don't depend on the path
+    if board.is_repetition() {
+        board.trace_event(TraceEvent::Repetition);
+        return board.draw_score(side_to_move);
+    }
+    // So is a position where neither side can checkmate
+    if board.has_insufficient_material() {
+        board.trace_event(TraceEvent::InsufficientMaterial);
+        return board.draw_score(side_to_move);
+    }
+
+    // At the horizon the captures are played out, so the position is scored
+    // once it is quiet
+    if depth <= 0 {
+        return if board.quiescence_checks() {
+            quiescence_with_checks(board, alpha, beta, side_to_move)
+        } else {
+            quiescence(board, alpha, beta, side_to_move)
+        };
+    }
+
     let original_alpha = alpha;
     let mut tt_move = None;
 
+    let tt_entry = board.transposition_table.retrieve_position(board.hash);
+    board.count_tt_probe(tt_entry.is_some());
+    if let Some(position) = tt_entry
+        && Depth::from(position.depth) >= depth
     {
-        let tt = &board.transposition_table;
-        if let Some(position) = tt.retrieve_position(board.hash)
-            && position.depth >= depth
+        let score = position.score.from_tt(board.search_ply());
+        let usable = match position.node_type {
+            NodeType::Exact => true,
+            NodeType::UpperBound => score <= alpha,
+            NodeType::LowerBound => score >= beta,
+        };
+        if usable {
+            board.trace_event(TraceEvent::TranspositionHit);
+            return score;
+        }
+        // A colliding entry can hold a move from another position
+        if board
+            .transposition_move(position.best_move, side_to_move)
+            .is_some()
         {
-            match position.node_type {
-                NodeType::Exact => return position.score,
-                NodeType::UpperBound => {
-                    if position.score <= alpha {
-                        return position.score;
-                    }
-                }
-                NodeType::LowerBound => {
-                    if position.score >= beta {
-                        return position.score;
-                    }
-                }
-            }
-            tt_move = Move::decode(position.best_move, board);
+            tt_move = Some(position.best_move);
         }
     }
 
-    if depth == 0 {
-        let perspective = if side_to_move == Color::White { 1 } else { -1 };
-        return board.evaluate() * perspective;
+    let mut threat = None;
+    if null_move_allowed {
+        match null_move_search(board, depth, beta, side_to_move, &stop_flag) {
+            NullMoveResult::Cutoff => {
+                board.trace_event(TraceEvent::NullMoveCutoff);
+                return beta;
+            }
+            NullMoveResult::FailLow(refutation) => threat = refutation,
+            NullMoveResult::Skipped => {}
+        }
     }
 
-    let mut best_move = None;
+    let mut best_move: Option<CompactMove> = None;
     let mut moves = board.generate_moves(side_to_move);
 
-    moves.sort_by(|mv_a, mv_b| {
-        let mv_a_is_capture = mv_a.is_capture();
-        let mv_b_is_capture = mv_b.is_capture();
+    // Without legal moves the rules decide the game
+    if moves.is_empty() {
+        return board
+            .rules()
+            .outcome_without_moves(board, side_to_move)
+            .score_at(board.search_ply());
+    }
 
-        match (mv_a_is_capture, mv_b_is_capture) {
-            (true, false) => std::cmp::Ordering::Greater,
-            (false, true) => std::cmp::Ordering::Less,
-            _ => std::cmp::Ordering::Equal,
-        }
+    // Moves are searched from the back: the move from the transposition
+    // table first, then captures, then moves parrying the threat, then checks
+    moves.sort_by_cached_key(|mv| {
+        let compact = mv.compact(board);
+        (
+            Some(compact.encoded()) == tt_move,
+            compact.is_capture(),
+            threat.is_some_and(|threat: Threat| threat.is_parried_by(board, mv)),
+            board.gives_check(mv),
+        )
     });
 
-    if let Some(mv) = tt_move.clone() {
-        moves.push(mv);
-    }
-
     for mv in moves.into_iter().rev() {
         if stop_flag.load(Ordering::Acquire) {
             return alpha;
         }
 
+        // Checks are searched deeper, so a mating attack is not cut short at
+        // the horizon. Near the ply limit they no longer are, so long checking
+        // sequences can't make the search run away
+        let gives_check = board.search_ply() < CHECK_EXTENSION_MAX_PLY && board.gives_check(&mv);
+
+        board.trace_enter(&mv, alpha, beta);
         board.make_move(&mv);
+        // So is a passed pawn about to promote, so the promotion is not
+        // pushed past the horizon
+        let extension = if gives_check {
+            Depth::from(board.search_params().check_extension)
+        } else if is_passed_pawn_push(board, &mv) {
+            Depth::from(board.search_params().passed_pawn_extension)
+        } else {
+            0
+        };
         let score = -minimax_alpha_beta(
             board,
-            depth - 1,
+            depth - 1 + extension,
             -beta,
             -alpha,
             side_to_move.opposite(),
             stop_flag.clone(),
+            true,
         );
         board.unmake_move(&mv);
 
+        if score >= beta {
+            board.trace_event(TraceEvent::Cutoff);
+        }
+        board.trace_exit(score);
+
         if score > alpha {
             alpha = score;
-            best_move = Some(mv);
+            best_move = Some(mv.compact(board));
         }
 
         if alpha >= beta {
@@ -146,23 +305,119 @@ fn minimax_alpha_beta(
         NodeType::Exact
     };
 
-    let encoded_move = if let Some(mv) = best_move {
-        mv.encode(board)
-    } else {
-        0
-    };
+    let encoded_move = best_move.map_or(0, CompactMove::encoded);
 
     let tt = &board.transposition_table;
     tt.save_position(
         board.hash,
         &TranspositionTableData {
-            depth,
-            score: alpha,
+            // Only depths of 1 or more get here, far below the byte limit
+            depth: u8::try_from(depth).unwrap_or(u8::MAX),
+            score: alpha.to_tt(board.search_ply()),
             node_type,
             best_move: encoded_move,
-            age: 0,
+            age: tt.generation(),
         },
     );
 
     alpha
 }
+
+/// Outcome of the null-move test of a position.
+enum NullMoveResult {
+    /// No null move was tried
+    Skipped,
+    /// Passing still fails high, verified at high depth
+    Cutoff,
+    /// Passing fails low, with the threat found by the opponent's reply
+    FailLow(Option<Threat>),
+}
+
+/// Passes the turn and searches the position at reduced depth with a null
+/// window at `beta`.
+///
+/// The null move is skipped in check, where passing is illegal, when the side
+/// to move only has pawns, where zugzwang is likely, and near mate scores.
+/// A cutoff at the verification depth or deeper is only accepted if a search
+/// of the position itself, reduced the same way and without null move, also
+/// fails high.
+fn null_move_search(
+    board: &mut ChessBoard,
+    depth: Depth,
+    beta: Score,
+    side_to_move: Color,
+    stop_flag: &Arc<AtomicBool>,
+) -> NullMoveResult {
+    let params = *board.search_params();
+    if depth < Depth::from(params.null_move_min_depth)
+        || beta.is_mate()
+        || board.is_in_check(side_to_move)
+        || !board.has_non_pawn_material(side_to_move)
+    {
+        return NullMoveResult::Skipped;
+    }
+
+    let reduction = Depth::from(params.null_move_reduction_at(&board.game_phase()));
+    let reduced_depth = depth - 1 - reduction;
+    let null_window = beta - Score::cp(1);
+
+    board.make_null_move();
+    let score = -minimax_alpha_beta(
+        board,
+        reduced_depth,
+        -beta,
+        -null_window,
+        side_to_move.opposite(),
+        stop_flag.clone(),
+        false,
+    );
+    let threat = board
+        .transposition_table
+        .retrieve_position(board.hash)
+        .and_then(|position| board.transposition_move(position.best_move, side_to_move.opposite()))
+        .and_then(|refutation| Threat::from_refutation(board, &refutation, side_to_move));
+    board.unmake_null_move();
+
+    if score < beta || stop_flag.load(Ordering::Acquire) {
+        return NullMoveResult::FailLow(threat);
+    }
+
+    if depth < Depth::from(params.null_move_verification_depth) {
+        return NullMoveResult::Cutoff;
+    }
+
+    let verified = minimax_alpha_beta(
+        board,
+        depth - reduction,
+        null_window,
+        beta,
+        side_to_move,
+        stop_flag.clone(),
+        false,
+    );
+    if verified >= beta {
+        NullMoveResult::Cutoff
+    } else {
+        NullMoveResult::FailLow(None)
+    }
+}
+
+/// Checks if a move, already made on the board, pushed a passed pawn to its
+/// seventh rank.
+///
+/// Each pawn reaches its seventh rank at most once along a line, so the
+/// extension can't make the search run forever.
+fn is_passed_pawn_push(board: &ChessBoard, mv: &Move) -> bool {
+    if mv.piece.get_type() != PieceType::Pawn || mv.promotion.is_some() {
+        return false;
+    }
+
+    let color = mv.piece.get_color();
+    let to = board.map_to_standard_chess_board(mv.to);
+    let seventh_rank = match color {
+        Color::White => 6,
+        Color::Black => 1,
+    };
+
+    to / 8 == seventh_rank && board.is_passed_pawn(to, color)
+}
@@ -0,0 +1,197 @@
+//! Tunable search parameters.
+//!
+//! The margins and depths used by the search are gathered in
+//! [`SearchParams`], carried by the board like the search clock. With the
+//! `tune` feature enabled every parameter is also exposed as a UCI spin
+//! option named after its [`TunableParam`], so external SPSA tuners can
+//! adjust them between games.
+
+use crate::game_state::board::evaluation::{GamePhase, TOTAL_PHASE};
+use crate::game_state::board::score::Score;
+
+/// Search margins and depths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchParams {
+    /// Margin by which the best root move must beat the second best to be
+    /// considered an easy move
+    pub easy_move_margin: Score,
+    /// Minimum depth before an easy move can end the search early
+    pub easy_move_min_depth: u8,
+    /// Plies added when a passed pawn is pushed to its seventh rank
+    pub passed_pawn_extension: u8,
+    /// Plies added when a move gives check
+    pub check_extension: u8,
+    /// Plies removed from the search after a null move, on top of the ply
+    /// the null move itself uses
+    pub null_move_reduction: u8,
+    /// Minimum depth at which a null move is tried
+    pub null_move_min_depth: u8,
+    /// Minimum depth at which a null-move cutoff is verified by a reduced
+    /// search of the position
+    pub null_move_verification_depth: u8,
+    /// Score the side the search plays for gives up when it repeats the
+    /// position, negative to seek draws
+    pub contempt: Score,
+    /// Material a capture may lose in its static exchange and still be
+    /// searched by quiescence
+    pub qsearch_see_margin: Score,
+}
+
+impl Default for SearchParams {
+    fn default() -> Self {
+        SearchParams {
+            easy_move_margin: Score::cp(200),
+            easy_move_min_depth: 3,
+            passed_pawn_extension: 1,
+            check_extension: 1,
+            null_move_reduction: 2,
+            null_move_min_depth: 3,
+            null_move_verification_depth: 6,
+            contempt: Score::ZERO,
+            qsearch_see_margin: Score::ZERO,
+        }
+    }
+}
+
+/// A search parameter that can be tuned through its UCI option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TunableParam {
+    /// UCI option name
+    pub name: &'static str,
+    /// Smallest accepted value
+    pub min: i32,
+    /// Largest accepted value
+    pub max: i32,
+}
+
+/// Every tunable search parameter, in the order the options are listed.
+pub const TUNABLE_PARAMS: [TunableParam; 9] = [
+    TunableParam {
+        name: "EasyMoveMargin",
+        min: 0,
+        max: 1000,
+    },
+    TunableParam {
+        name: "EasyMoveMinDepth",
+        min: 1,
+        max: 16,
+    },
+    TunableParam {
+        name: "PassedPawnExtension",
+        min: 0,
+        max: 2,
+    },
+    TunableParam {
+        name: "CheckExtension",
+        min: 0,
+        max: 1,
+    },
+    TunableParam {
+        name: "NullMoveReduction",
+        min: 1,
+        max: 4,
+    },
+    TunableParam {
+        name: "NullMoveMinDepth",
+        min: 2,
+        max: 8,
+    },
+    TunableParam {
+        name: "NullMoveVerificationDepth",
+        min: 2,
+        max: 16,
+    },
+    TunableParam {
+        name: "Contempt",
+        min: -100,
+        max: 100,
+    },
+    TunableParam {
+        name: "QsearchSeeMargin",
+        min: 0,
+        max: 1000,
+    },
+];
+
+impl SearchParams {
+    /// Gets the null-move reduction in a game phase.
+    ///
+    /// The full reduction applies with most pieces on the board. Going into
+    /// the endgame, where zugzwang is more likely and a free move is worth
+    /// more, the null move is searched up to a ply deeper, but never with a
+    /// reduction below one ply.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - Game phase of the position searched
+    ///
+    /// # Returns
+    ///
+    /// The plies removed from the search after a null move
+    pub fn null_move_reduction_at(&self, phase: &GamePhase) -> u8 {
+        let middlegame = i32::from(self.null_move_reduction);
+        let endgame = (middlegame - 1).max(1);
+        let total = i32::from(TOTAL_PHASE);
+        let scaled = (middlegame - endgame) * i32::from(phase.value());
+
+        // Rounded, so the full reduction holds down to half the material
+        (endgame + (scaled + total / 2) / total) as u8
+    }
+
+    /// Gets the value of a parameter by its option name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Option name of the parameter
+    ///
+    /// # Returns
+    ///
+    /// The current value, or None for an unknown name
+    pub fn get(&self, name: &str) -> Option<i32> {
+        match name {
+            "EasyMoveMargin" => Some(self.easy_move_margin.centipawns()),
+            "EasyMoveMinDepth" => Some(self.easy_move_min_depth.into()),
+            "PassedPawnExtension" => Some(self.passed_pawn_extension.into()),
+            "CheckExtension" => Some(self.check_extension.into()),
+            "NullMoveReduction" => Some(self.null_move_reduction.into()),
+            "NullMoveMinDepth" => Some(self.null_move_min_depth.into()),
+            "NullMoveVerificationDepth" => Some(self.null_move_verification_depth.into()),
+            "Contempt" => Some(self.contempt.centipawns()),
+            "QsearchSeeMargin" => Some(self.qsearch_see_margin.centipawns()),
+            _ => None,
+        }
+    }
+
+    /// Sets the value of a parameter by its option name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Option name of the parameter
+    /// * `value` - New value, within the parameter's range
+    ///
+    /// # Returns
+    ///
+    /// `true` if the parameter exists and the value is in range
+    pub fn set(&mut self, name: &str, value: i32) -> bool {
+        let Some(param) = TUNABLE_PARAMS.iter().find(|param| param.name == name) else {
+            return false;
+        };
+        if !(param.min..=param.max).contains(&value) {
+            return false;
+        }
+
+        match name {
+            "EasyMoveMargin" => self.easy_move_margin = Score::cp(value),
+            "EasyMoveMinDepth" => self.easy_move_min_depth = value as u8,
+            "PassedPawnExtension" => self.passed_pawn_extension = value as u8,
+            "CheckExtension" => self.check_extension = value as u8,
+            "NullMoveReduction" => self.null_move_reduction = value as u8,
+            "NullMoveMinDepth" => self.null_move_min_depth = value as u8,
+            "NullMoveVerificationDepth" => self.null_move_verification_depth = value as u8,
+            "Contempt" => self.contempt = Score::cp(value),
+            "QsearchSeeMargin" => self.qsearch_see_margin = Score::cp(value),
+            _ => unreachable!("every tunable parameter is handled"),
+        }
+        true
+    }
+}
@@ -9,7 +9,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::game_state::ChessBoard;
 use crate::game_state::Color;
-use crate::game_state::board::search::SearchAlgorithm;
+use crate::game_state::board::score::Score;
+use crate::game_state::board::search::{Depth, SearchAlgorithm};
 
 /// Pure minimax search without any pruning or optimization.
 ///
@@ -21,10 +22,10 @@ impl SearchAlgorithm for PureMinimax {
     fn tree_search(
         &self,
         game: &mut ChessBoard,
-        depth: u8,
+        depth: Depth,
         side_to_move: Color,
         stop_flag: Arc<AtomicBool>,
-    ) -> i16 {
+    ) -> Score {
         pure_minimax(game, depth, side_to_move, stop_flag)
     }
 }
@@ -38,7 +39,8 @@ impl SearchAlgorithm for PureMinimax {
 /// # Arguments
 ///
 /// * `game` - Mutable reference to the chess board
-/// * `depth` - Search depth (number of plies to look ahead)
+/// * `depth` - Search depth (number of plies to look ahead), evaluated at
+///   the horizon at zero or below
 /// * `side_to_move` - Color of the player to move
 /// * `stop_flag` - Flag to abort search early
 ///
@@ -47,20 +49,19 @@ impl SearchAlgorithm for PureMinimax {
 /// Side-relative evaluation score (positive = good for the side to move)
 fn pure_minimax(
     game: &mut ChessBoard,
-    depth: u8,
+    depth: Depth,
     side_to_move: Color,
     stop_flag: Arc<AtomicBool>,
-) -> i16 {
-    if depth == 0 {
-        let perspective = if side_to_move == Color::White { 1 } else { -1 };
-        return game.evaluate() * perspective;
+) -> Score {
+    if depth <= 0 {
+        return game.evaluate_for(side_to_move);
     }
 
     let moves = game.generate_moves(side_to_move);
 
     match side_to_move {
         Color::White => {
-            let mut max_eval = i16::MIN + 1;
+            let mut max_eval = -Score::INFINITE;
 
             for mv in moves {
                 if stop_flag.load(Ordering::Acquire) {
@@ -78,7 +79,7 @@ fn pure_minimax(
             max_eval
         }
         Color::Black => {
-            let mut max_eval = i16::MIN + 1;
+            let mut max_eval = -Score::INFINITE;
 
             for mv in moves {
                 if stop_flag.load(Ordering::Acquire) {
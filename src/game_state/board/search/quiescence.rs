@@ -1,11 +1,32 @@
 //! Quiescence search to stabilize evaluations in tactical positions.
 //!
-//! Extends search beyond the normal depth limit to only consider captures
-//! and other forcing moves, preventing horizon effect problems where
-//! tactical sequences extend beyond the search depth.
+//! Extends search beyond the normal depth limit to only consider captures,
+//! queen promotions and other forcing moves, preventing horizon effect
+//! problems where tactical sequences extend beyond the search depth.
+//!
+//! When the side to move is in check, standing pat is not an option, so all
+//! check evasions are searched instead of captures only. Positions without
+//! legal moves are scored as checkmate or stalemate instead of evaluated.
+//! Quiet checking moves can optionally be searched at the first quiescence
+//! ply to find short tactics that start with a check, see the
+//! `QuiescenceChecks` option.
+//!
+//! Captures and promotions losing material in their static exchange are not
+//! searched, as the side to move would rather stand pat than play them. This
+//! keeps the search from exploding in positions full of captures. Results
+//! are stored in the transposition table at depth 0, below any entry of the
+//! main search, and the best capture stored is searched first when the
+//! position comes back.
 
 use crate::game_state::ChessBoard;
 use crate::game_state::Color;
+use crate::game_state::Move;
+use crate::game_state::board::moves::CompactMove;
+use crate::game_state::board::score::Score;
+use crate::game_state::board::transposition_table::{NodeType, TranspositionTableData};
+
+/// Depth of the transposition table entries stored by quiescence.
+const QUIESCENCE_DEPTH: u8 = 0;
 
 /// Quiescence search to stabilize evaluations in tactical positions.
 ///
@@ -25,38 +46,201 @@ use crate::game_state::Color;
 /// Stabilized evaluation score after considering captures
 pub fn quiescence(
     chess_board: &mut ChessBoard,
-    mut alpha: i16,
-    beta: i16,
+    alpha: Score,
+    beta: Score,
+    side_to_move: Color,
+) -> Score {
+    quiescence_search(chess_board, alpha, beta, side_to_move, 0)
+}
+
+/// Quiescence search that also tries quiet checking moves.
+///
+/// Behaves like [`quiescence`], but at the first quiescence ply non-capturing
+/// moves that give check are searched as well. The replies are searched with
+/// plain [`quiescence`], so checks never chain into an unbounded sequence.
+///
+/// # Arguments
+///
+/// * `chess_board` - Mutable reference to the chess board
+/// * `alpha` - Alpha value for pruning
+/// * `beta` - Beta value for pruning
+/// * `side_to_move` - Color of the player to move
+///
+/// # Returns
+///
+/// Stabilized evaluation score after considering captures and checks
+pub fn quiescence_with_checks(
+    chess_board: &mut ChessBoard,
+    alpha: Score,
+    beta: Score,
+    side_to_move: Color,
+) -> Score {
+    quiescence_search(chess_board, alpha, beta, side_to_move, 1)
+}
+
+/// Recursive quiescence search.
+///
+/// # Arguments
+///
+/// * `chess_board` - Mutable reference to the chess board
+/// * `alpha` - Alpha value for pruning
+/// * `beta` - Beta value for pruning
+/// * `side_to_move` - Color of the player to move
+/// * `check_plies` - Number of plies where quiet checks are still searched
+///
+/// # Returns
+///
+/// Stabilized evaluation score
+fn quiescence_search(
+    chess_board: &mut ChessBoard,
+    mut alpha: Score,
+    beta: Score,
     side_to_move: Color,
-) -> i16 {
-    let stand_pat = chess_board.evaluate();
+    check_plies: u8,
+) -> Score {
+    chess_board.qnodes += 1;
+
+    // Entries only account for captures, nodes searching quiet checks would
+    // miss them
+    let use_table = check_plies == 0;
+    let original_alpha = alpha;
+    let mut tt_move = None;
+    if use_table {
+        let tt_entry = chess_board
+            .transposition_table
+            .retrieve_position(chess_board.hash);
+        chess_board.count_tt_probe(tt_entry.is_some());
+        if let Some(position) = tt_entry {
+            let score = position.score.from_tt(chess_board.search_ply());
+            let usable = match position.node_type {
+                NodeType::Exact => true,
+                NodeType::UpperBound => score <= alpha,
+                NodeType::LowerBound => score >= beta,
+            };
+            if usable {
+                return score;
+            }
+            tt_move = Some(position.best_move);
+        }
+    }
+
+    let in_check = chess_board.is_in_check(side_to_move);
+    let mut moves = chess_board.generate_moves(side_to_move);
+
+    // Without legal moves the game is over, whatever the evaluation says
+    if moves.is_empty() {
+        return chess_board
+            .rules()
+            .outcome_without_moves(chess_board, side_to_move)
+            .score_at(chess_board.search_ply());
+    }
+
+    // Standing pat is only sound when the side to move could pass, which is
+    // not the case while in check.
+    if !in_check {
+        let stand_pat = chess_board.evaluate_for(side_to_move);
 
-    if stand_pat >= beta {
-        return beta;
+        if stand_pat >= beta {
+            if use_table {
+                store(chess_board, beta, NodeType::LowerBound, None);
+            }
+            return beta;
+        }
+
+        if stand_pat > alpha {
+            alpha = stand_pat;
+        }
     }
 
-    if stand_pat > alpha {
-        alpha = stand_pat;
+    // Exchanges are only sound with the standard rules, where captures
+    // are neither compulsory nor explosive
+    let see_pruning = !in_check && chess_board.is_standard_chess();
+    let see_margin = -chess_board.search_params().qsearch_see_margin;
+    let loses_material =
+        |mv: &Move| see_pruning && Score::cp(chess_board.see(mv).into()) < see_margin;
+
+    // In check every legal move is an evasion and must be searched
+    moves.retain(|mv| {
+        in_check
+            || (mv.is_tactical() && !loses_material(mv))
+            || (check_plies > 0 && chess_board.gives_check(mv))
+    });
+
+    if let Some(index) = tt_move.and_then(|tt_move| {
+        moves
+            .iter()
+            .position(|mv| mv.compact(chess_board).encoded() == tt_move)
+    }) {
+        moves[..=index].rotate_right(1);
     }
 
-    let captures = chess_board
-        .generate_moves(side_to_move)
-        .into_iter()
-        .filter(|mv| mv.is_capture())
-        .collect::<Vec<_>>();
+    let next_check_plies = check_plies.saturating_sub(1);
+    let mut best_move = None;
 
-    for mv in captures {
+    for mv in moves {
         chess_board.make_move(&mv);
-        let score = -quiescence(chess_board, -beta, -alpha, side_to_move.opposite());
+        let score = -quiescence_search(
+            chess_board,
+            -beta,
+            -alpha,
+            side_to_move.opposite(),
+            next_check_plies,
+        );
         chess_board.unmake_move(&mv);
 
         if score >= beta {
+            if use_table {
+                store(
+                    chess_board,
+                    beta,
+                    NodeType::LowerBound,
+                    Some(mv.compact(chess_board)),
+                );
+            }
             return beta;
         }
         if score > alpha {
             alpha = score;
+            best_move = Some(mv.compact(chess_board));
         }
     }
 
+    if use_table {
+        let node_type = if alpha > original_alpha {
+            NodeType::Exact
+        } else {
+            NodeType::UpperBound
+        };
+        store(chess_board, alpha, node_type, best_move);
+    }
+
     alpha
 }
+
+/// Stores the result of a quiescence node in the transposition table.
+///
+/// # Arguments
+///
+/// * `chess_board` - Board whose position was searched
+/// * `score` - Side-relative score of the position, mates counted from the
+///   root
+/// * `node_type` - Whether the score is exact or a bound
+/// * `best_move` - Move that raised alpha or failed high, if any
+fn store(
+    chess_board: &ChessBoard,
+    score: Score,
+    node_type: NodeType,
+    best_move: Option<CompactMove>,
+) {
+    let tt = &chess_board.transposition_table;
+    tt.save_position(
+        chess_board.hash,
+        &TranspositionTableData {
+            depth: QUIESCENCE_DEPTH,
+            score: score.to_tt(chess_board.search_ply()),
+            node_type,
+            best_move: best_move.map_or(0, CompactMove::encoded),
+            age: tt.generation(),
+        },
+    );
+}
@@ -0,0 +1,261 @@
+//! Search tree tracing for debugging.
+//!
+//! When a [`SearchTrace`] is attached to the board, the search records the
+//! moves it tries up to a maximum ply, with the alpha-beta window each move was
+//! searched with, the score it got and what happened below it (transposition
+//! table hits, repetitions, beta cutoffs). The recorded tree can be dumped as
+//! indented text or as JSON, so pruning and ordering bugs can be looked at
+//! without adding prints to the search.
+//!
+//! Tracing is enabled from the UCI loop with `debug on`, and the tree of the
+//! last search is printed with the `trace` command.
+
+use crate::game_state::board::score::Score;
+
+/// Default number of plies recorded by a trace.
+pub const DEFAULT_TRACE_DEPTH: usize = 2;
+
+/// Something notable that happened while searching a move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// The position after the move was found in the transposition table
+    TranspositionHit,
+    /// The position after the move repeats an earlier one
+    Repetition,
+    /// Neither side can checkmate after the move
+    InsufficientMaterial,
+    /// The move failed high and the remaining moves were skipped
+    Cutoff,
+    /// Passing the turn after the move still failed high, so the position
+    /// after the move was not searched
+    NullMoveCutoff,
+}
+
+impl TraceEvent {
+    /// Gets the name of the event used in the dumps.
+    pub fn name(self) -> &'static str {
+        match self {
+            TraceEvent::TranspositionHit => "tt hit",
+            TraceEvent::Repetition => "repetition",
+            TraceEvent::InsufficientMaterial => "insufficient material",
+            TraceEvent::Cutoff => "cutoff",
+            TraceEvent::NullMoveCutoff => "null move cutoff",
+        }
+    }
+}
+
+/// A move searched while tracing, with the moves searched below it.
+#[derive(Clone, Debug)]
+pub struct TraceNode {
+    /// Move in UCI notation
+    pub mv: String,
+    /// Lower bound of the window, from the point of view of the side that
+    /// played the move
+    pub alpha: Score,
+    /// Upper bound of the window, from the point of view of the side that
+    /// played the move
+    pub beta: Score,
+    /// Score of the move for the side that played it, `None` if the search
+    /// was interrupted before the move was scored
+    pub score: Option<Score>,
+    /// Events seen while searching the move
+    pub events: Vec<TraceEvent>,
+    /// Moves searched in the position after the move
+    pub children: Vec<TraceNode>,
+}
+
+/// Recorder of the search tree.
+///
+/// The search calls [`enter`](Self::enter) before searching a move and
+/// [`exit`](Self::exit) after it. Moves deeper than the maximum depth are
+/// followed but not recorded, so the calls always stay balanced.
+#[derive(Clone, Debug)]
+pub struct SearchTrace {
+    /// Number of plies recorded
+    max_depth: usize,
+    /// Current ply below the root
+    ply: usize,
+    /// Moves being searched, from the root down
+    stack: Vec<TraceNode>,
+    /// Completed root moves
+    roots: Vec<TraceNode>,
+    /// Root moves of the last completed iteration
+    completed: Option<Vec<TraceNode>>,
+}
+
+impl SearchTrace {
+    /// Creates an empty trace.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_depth` - Number of plies to record, at least one
+    pub fn new(max_depth: usize) -> Self {
+        SearchTrace {
+            max_depth: max_depth.max(1),
+            ply: 0,
+            stack: Vec::new(),
+            roots: Vec::new(),
+            completed: None,
+        }
+    }
+
+    /// Gets the number of plies recorded.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Gets the recorded root moves: those of the last completed iteration,
+    /// or the ones recorded so far if no iteration was completed.
+    pub fn roots(&self) -> &[TraceNode] {
+        self.completed.as_deref().unwrap_or(&self.roots)
+    }
+
+    /// Starts recording a new iteration, keeping the last completed one
+    /// until the new one completes.
+    pub fn start_iteration(&mut self) {
+        self.ply = 0;
+        self.stack.clear();
+        self.roots.clear();
+    }
+
+    /// Marks the moves recorded since [`start_iteration`](Self::start_iteration)
+    /// as a completed iteration.
+    pub fn complete_iteration(&mut self) {
+        self.completed = Some(std::mem::take(&mut self.roots));
+    }
+
+    /// Starts searching a move.
+    ///
+    /// # Arguments
+    ///
+    /// * `mv` - Move in UCI notation
+    /// * `alpha` - Lower bound of the window of the side playing the move
+    /// * `beta` - Upper bound of the window of the side playing the move
+    pub fn enter(&mut self, mv: String, alpha: Score, beta: Score) {
+        self.ply += 1;
+        if self.ply <= self.max_depth {
+            self.stack.push(TraceNode {
+                mv,
+                alpha,
+                beta,
+                score: None,
+                events: Vec::new(),
+                children: Vec::new(),
+            });
+        }
+    }
+
+    /// Records an event on the move being searched.
+    pub fn event(&mut self, event: TraceEvent) {
+        if self.ply <= self.max_depth
+            && let Some(node) = self.stack.last_mut()
+        {
+            node.events.push(event);
+        }
+    }
+
+    /// Finishes searching the move passed to the last [`enter`](Self::enter).
+    ///
+    /// # Arguments
+    ///
+    /// * `score` - Score of the move for the side that played it
+    pub fn exit(&mut self, score: Score) {
+        if self.ply == 0 {
+            return;
+        }
+
+        if self.ply <= self.max_depth
+            && let Some(mut node) = self.stack.pop()
+        {
+            node.score = Some(score);
+            match self.stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => self.roots.push(node),
+            }
+        }
+        self.ply -= 1;
+    }
+
+    /// Dumps the recorded tree as indented text, one move per line.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for node in self.roots() {
+            write_text(node, 0, &mut text);
+        }
+        text
+    }
+
+    /// Dumps the recorded tree as a JSON array of root moves.
+    ///
+    /// Scores and bounds are in centipawns.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        write_json_list(self.roots(), &mut json);
+        json
+    }
+}
+
+/// Formats a window bound, showing the search window limits as infinities.
+fn bound_to_string(bound: Score) -> String {
+    if bound >= Score::INFINITE {
+        "inf".to_string()
+    } else if bound <= -Score::INFINITE {
+        "-inf".to_string()
+    } else {
+        bound.to_uci_string()
+    }
+}
+
+fn write_text(node: &TraceNode, indent: usize, text: &mut String) {
+    let score = node
+        .score
+        .map_or("interrupted".to_string(), Score::to_uci_string);
+
+    text.push_str(&format!(
+        "{:indent$}{} [{}, {}] {}",
+        "",
+        node.mv,
+        bound_to_string(node.alpha),
+        bound_to_string(node.beta),
+        score,
+        indent = indent * 2
+    ));
+    for event in &node.events {
+        text.push_str(&format!(" ({})", event.name()));
+    }
+    text.push('\n');
+
+    for child in &node.children {
+        write_text(child, indent + 1, text);
+    }
+}
+
+fn write_json_list(nodes: &[TraceNode], json: &mut String) {
+    json.push('[');
+    for (index, node) in nodes.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+
+        let score = node
+            .score
+            .map_or("null".to_string(), |score| score.centipawns().to_string());
+        let events: Vec<String> = node
+            .events
+            .iter()
+            .map(|event| format!("\"{}\"", event.name()))
+            .collect();
+
+        json.push_str(&format!(
+            "{{\"move\":\"{}\",\"alpha\":{},\"beta\":{},\"score\":{},\"events\":[{}],\"children\":",
+            node.mv,
+            node.alpha.centipawns(),
+            node.beta.centipawns(),
+            score,
+            events.join(",")
+        ));
+        write_json_list(&node.children, json);
+        json.push('}');
+    }
+    json.push(']');
+}
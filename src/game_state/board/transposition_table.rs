@@ -1,5 +1,15 @@
-use rand::Rng;
-use std::sync::atomic::{AtomicU64, Ordering};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+use crate::game_state::board::piece::Piece;
+use crate::game_state::board::polyglot::{
+    self, CASTLING_OFFSET, EN_PASSANT_OFFSET, RANDOM64, TURN_OFFSET,
+};
+use crate::game_state::board::score::Score;
 
 /// Pre-computed random numbers for Zobrist hashing of chess positions.
 ///
@@ -21,19 +31,61 @@ pub struct Zobrist {
     pub castling_rights: [u64; 4],
     // [file a, file b, ..., file h]
     pub en_passant: [u64; 8],
+    // Whether the keys are the published Polyglot ones
+    polyglot: bool,
 }
 
+/// Magic number starting a hash file, see [`TranspositionTable::save`].
+pub const HASH_FILE_MAGIC: u32 = u32::from_le_bytes(*b"ETTF");
+
+/// Version of the hash file format, bumped when the entry layout changes.
+pub const HASH_FILE_VERSION: u32 = 1;
+
+/// Seed used for the Zobrist keys unless [`ZOBRIST_SEED_VAR`] overrides it.
+pub const DEFAULT_ZOBRIST_SEED: u64 = 0x456e_5275_7374_2121;
+
+/// Environment variable holding a Zobrist seed, in decimal or `0x` hexadecimal.
+pub const ZOBRIST_SEED_VAR: &str = "ENRUST_ZOBRIST_SEED";
+
 impl Zobrist {
-    /// Generates a new Zobrist structure with cryptographically secure random numbers.
+    /// Generates the Zobrist structure from the default seed.
+    ///
+    /// Keys are the same on every run, so hashes can be compared across
+    /// processes and transposition table bugs reproduced. The seed is read
+    /// from the [`ZOBRIST_SEED_VAR`] environment variable when it is set to a
+    /// valid number, and is [`DEFAULT_ZOBRIST_SEED`] otherwise.
     ///
     /// This should be called once and shared across all board instances for consistency.
-    /// Uses `rand::rng()` for random number generation.
+    pub fn new() -> Self {
+        let seed = std::env::var(ZOBRIST_SEED_VAR)
+            .ok()
+            .and_then(|value| parse_seed(&value))
+            .unwrap_or(DEFAULT_ZOBRIST_SEED);
+
+        Self::from_seed(seed)
+    }
+
+    /// Generates the Zobrist structure from a seeded random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed of the generator, equal seeds give equal keys
+    pub fn from_seed(seed: u64) -> Self {
+        Self::from_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Generates a new Zobrist structure with cryptographically secure random numbers.
+    ///
+    /// Keys differ on every call. Uses `rand::rng()` for random number generation.
     ///
     /// # Performance
     /// - Initialization is O(64×12) = 768 random number generations
     /// - Should be done once at program start
-    pub fn new() -> Self {
-        let mut rng = rand::rng();
+    pub fn random() -> Self {
+        Self::from_rng(&mut rand::rng())
+    }
+
+    fn from_rng(rng: &mut impl Rng) -> Self {
         let mut zobrist = Zobrist {
             pieces: [[0; 12]; 64],
             side_to_move: rng.random(),
@@ -48,6 +100,7 @@ impl Zobrist {
                 rng.random(),
                 rng.random(),
             ],
+            polyglot: false,
         };
 
         for square in 0..64 {
@@ -57,6 +110,59 @@ impl Zobrist {
         }
         zobrist
     }
+
+    /// Builds the Zobrist structure from the Polyglot random keys.
+    ///
+    /// Boards hashed with these keys keep a Polyglot compatible key up to date
+    /// incrementally, so book probing and hash comparisons with other engines
+    /// don't need a full board scan. The engine XORs the side key when Black
+    /// is to move while Polyglot does it for White, and it always hashes the
+    /// en passant file; [`ChessBoard::polyglot_key`](super::ChessBoard::polyglot_key)
+    /// corrects both.
+    pub fn polyglot() -> Self {
+        let mut zobrist = Zobrist {
+            pieces: [[0; 12]; 64],
+            side_to_move: RANDOM64[TURN_OFFSET],
+            // Polyglot orders castling as white short, white long, black short, black long
+            castling_rights: [
+                RANDOM64[CASTLING_OFFSET + 1],
+                RANDOM64[CASTLING_OFFSET],
+                RANDOM64[CASTLING_OFFSET + 3],
+                RANDOM64[CASTLING_OFFSET + 2],
+            ],
+            en_passant: std::array::from_fn(|file| RANDOM64[EN_PASSANT_OFFSET + file]),
+            polyglot: true,
+        };
+
+        for square in 0..64 {
+            for piece in Piece::ALL {
+                let kind = polyglot::piece_kind(piece);
+                zobrist.pieces[square][piece as usize] = RANDOM64[64 * kind + square];
+            }
+        }
+        zobrist
+    }
+
+    /// Whether these are the Polyglot keys built by [`Zobrist::polyglot`].
+    pub fn is_polyglot(&self) -> bool {
+        self.polyglot
+    }
+
+    /// Gets a checksum of every key.
+    ///
+    /// Hashes computed with other keys don't match the positions of this
+    /// board, so hash files record the checksum of the keys they were saved
+    /// with.
+    pub fn checksum(&self) -> u64 {
+        self.pieces
+            .iter()
+            .flatten()
+            .chain(&self.castling_rights)
+            .chain(&self.en_passant)
+            .fold(self.side_to_move, |checksum, &key| {
+                checksum.rotate_left(7) ^ key
+            })
+    }
 }
 
 impl Default for Zobrist {
@@ -65,10 +171,22 @@ impl Default for Zobrist {
     }
 }
 
+/// Parses a seed written in decimal or `0x` prefixed hexadecimal.
+fn parse_seed(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
 /// Compact 64-bit representation of transposition table data.
 ///
 /// Bit layout:
-/// - Bits 0-15:   score (i16 stored as u16 using two's complement)
+/// - Bits 0-15:   score (within ±[`Score::INFINITE`], stored as a two's complement i16)
 /// - Bits 16-23:  depth (u8, 0-255 plies)
 /// - Bits 24-25:  node type (2 bits: 0=exact, 1=upper, 2=lower)
 /// - Bits 26-41:  best move (16 bits: from_square 6b, to_square 6b, promotion 4b)
@@ -81,7 +199,7 @@ impl Default for Zobrist {
 /// - Bits 6-11:  to square (0-63)
 /// - Bits 12-15: promotion piece flags (queen=0x1, rook=0x2, bishop=0x4, knight=0x8)
 pub struct TranspositionTableData {
-    pub score: i16,          // Evaluation score
+    pub score: Score,        // Evaluation score
     pub depth: u8,           // Search depth this entry was computed at
     pub node_type: NodeType, // Exact, LowerBound, UpperBound
     pub best_move: u16,      // Best move found in compact form (uci characters)
@@ -97,6 +215,11 @@ pub struct TranspositionTableData {
 /// # Safety
 /// The XOR verification ensures that reads either get complete valid data
 /// or detect corruption through hash mismatch, preventing torn reads.
+///
+/// # Layout
+/// Entries are 16 bytes, aligned so that [`BUCKET_SIZE`] of them fill a
+/// cache line exactly.
+#[repr(C, align(16))]
 pub struct TranspositionEntry {
     pub hash_xor_data: AtomicU64,
     pub data: AtomicU64,
@@ -132,19 +255,58 @@ impl TryFrom<u8> for NodeType {
     }
 }
 
+/// Number of entries sharing a bucket, and a cache line.
+pub const BUCKET_SIZE: usize = 4;
+
+/// Depth an entry loses in the replacement policy for each search it is
+/// older than the current one.
+const AGE_DEPTH_PENALTY: i32 = 8;
+
+/// Entries a position may be stored in, probed together.
+///
+/// A bucket fills one 64-byte cache line, so probing all its entries costs
+/// a single memory access.
+#[repr(C, align(64))]
+struct Bucket {
+    entries: [TranspositionEntry; BUCKET_SIZE],
+}
+
+impl Bucket {
+    fn new() -> Self {
+        Bucket {
+            entries: std::array::from_fn(|_| TranspositionEntry::new()),
+        }
+    }
+}
+
 /// A lock-free transposition table for caching chess position evaluations.
 ///
 /// Stores previously computed search results to avoid re-searching positions.
 /// Uses atomic operations and XOR verification for thread-safe concurrent access.
 ///
 /// # Replacement Policy
-/// Implements a depth-preferred replacement scheme:
-/// 1. Always replace empty slots
-/// 2. For same position: replace if deeper search or same depth but newer
-/// 3. For collisions: replace based on depth, age, and node type priority
+/// The table is N-way associative: a position hashes to a set of `ways`
+/// entries, by default the [`BUCKET_SIZE`] entries of a bucket, and may be
+/// stored in any of them, so a hot position isn't evicted by the first
+/// unrelated position colliding with it. Within the set:
+/// 1. For same position: replace if at least as deep, or stored by an
+///    earlier search
+/// 2. Otherwise take an empty entry
+/// 3. Otherwise replace the least valuable entry, based on depth, age and
+///    node type
+///
+/// # Age
+/// The table counts searches with [`TranspositionTable::new_search`], and
+/// entries record the search that stored them. Entries left by earlier
+/// searches are replaced before deeper entries of the current one.
 pub struct TranspositionTable {
-    entries: Box<[TranspositionEntry]>,
-    size: usize,
+    buckets: Box<[Bucket]>,
+    /// Number of sets of entries a position can hash to
+    sets: usize,
+    /// Entries per set, dividing [`BUCKET_SIZE`]
+    ways: usize,
+    /// Number of the current search, stored as the age of new entries
+    generation: AtomicU8,
 }
 
 impl TranspositionEntry {
@@ -154,9 +316,9 @@ impl TranspositionEntry {
     /// * `data` - Packed 64-bit value containing all entry data
     ///
     /// # Returns
-    /// The evaluation score as i16 (supports negative values via two's complement)
-    fn score(data: u64) -> i16 {
-        ((data & 0xFFFF) as u16) as i16
+    /// The evaluation score (supports negative values via two's complement)
+    fn score(data: u64) -> Score {
+        Score::from((data & 0xFFFF) as u16 as i16)
     }
 
     /// Extracts the depth from packed 64-bit data.
@@ -299,6 +461,32 @@ impl TranspositionEntry {
     fn is_empty(&self) -> bool {
         self.data.load(Ordering::Relaxed) == 0 && self.hash_xor_data.load(Ordering::Relaxed) == 0
     }
+
+    /// Checks that packed data read from a hash file could have been
+    /// stored by the search.
+    ///
+    /// # Arguments
+    /// * `data` - Packed 64-bit value containing all entry data
+    ///
+    /// # Returns
+    /// `true` if the score is within bounds, the node type is known, the
+    /// best move is empty or goes somewhere with at most one promotion, and
+    /// the reserved bits are clear
+    fn is_valid(data: u64) -> bool {
+        let score = Self::score(data);
+        let node_type = ((data >> 24) & 0b11) as u8;
+        let best_move = Self::best_move(data);
+        let from = best_move & 0x3F;
+        let to = (best_move >> 6) & 0x3F;
+        let promotion = best_move >> 12;
+
+        -Score::INFINITE <= score
+            && score <= Score::INFINITE
+            && NodeType::try_from(node_type).is_ok()
+            && (best_move == 0 || from != to)
+            && promotion.count_ones() <= 1
+            && data >> 50 == 0
+    }
 }
 
 impl TranspositionTable {
@@ -308,8 +496,9 @@ impl TranspositionTable {
     /// * `size_mb` - Table size in megabytes
     ///
     /// # Calculation
-    /// Total entries = (size_mb × 1024 × 1024) / size_of::<TranspositionEntry>()
-    /// Each entry is 16 bytes (two u64), so 1MB holds ~65,536 entries
+    /// Total buckets = (size_mb × 1024 × 1024) / size_of::<Bucket>()
+    /// Each bucket is a 64-byte cache line of 4 entries, so 1MB holds
+    /// 16,384 buckets and 65,536 entries
     ///
     /// # Example
     /// ```
@@ -318,15 +507,37 @@ impl TranspositionTable {
     /// let tt = TranspositionTable::new(128); // 128MB table, ~8M entries
     /// ```
     pub fn new(size_mb: usize) -> Self {
-        let entry_size = std::mem::size_of::<TranspositionEntry>();
-        let size = (size_mb * 1024 * 1024) / entry_size;
+        Self::with_ways(size_mb, BUCKET_SIZE)
+    }
+
+    /// Creates a new transposition table storing each position in one of
+    /// `ways` entries.
+    ///
+    /// The memory layout is the same for every number of ways, only the
+    /// entries a position can go to change. One way is a classic
+    /// single-slot table, useful to measure what buckets bring.
+    ///
+    /// # Arguments
+    /// * `size_mb` - Table size in megabytes
+    /// * `ways` - Entries per set: 1, 2 or [`BUCKET_SIZE`]
+    ///
+    /// # Panics
+    /// If `ways` doesn't divide [`BUCKET_SIZE`]
+    pub fn with_ways(size_mb: usize, ways: usize) -> Self {
+        assert!(
+            ways > 0 && BUCKET_SIZE.is_multiple_of(ways),
+            "ways must divide the bucket size"
+        );
+        let bucket_size = std::mem::size_of::<Bucket>();
+        let size = (size_mb * 1024 * 1024) / bucket_size;
 
-        let entries: Vec<TranspositionEntry> =
-            (0..size).map(|_| TranspositionEntry::new()).collect();
+        let buckets: Vec<Bucket> = (0..size).map(|_| Bucket::new()).collect();
 
         Self {
-            entries: entries.into_boxed_slice(),
-            size,
+            buckets: buckets.into_boxed_slice(),
+            sets: size * BUCKET_SIZE / ways,
+            ways,
+            generation: AtomicU8::new(0),
         }
     }
 
@@ -336,13 +547,13 @@ impl TranspositionTable {
     /// * `new_size_mb` - New table size in megabytes
     ///
     /// # Behavior
-    /// - Allocates a new entry array of the specified size
+    /// - Allocates a new bucket array of the specified size
     /// - Initializes all entries to empty state (zero values)
     /// - Discards all previously stored positions (complete cache flush)
     /// - Updates the internal size tracking
     ///
     /// # Memory Calculation
-    /// Uses `size_of::<TranspositionEntry>()` to store raw entries.
+    /// Uses `size_of::<Bucket>()`, a cache line of raw entries.
     ///
     /// # Example
     /// ```
@@ -356,16 +567,165 @@ impl TranspositionTable {
     /// This operation is expensive and should be used sparingly, typically
     /// during engine configuration rather than during search.
     pub fn resize(&mut self, new_size_mb: usize) -> Self {
-        let entry_size = std::mem::size_of::<TranspositionEntry>();
-        let new_size = (new_size_mb * 1024 * 1024) / entry_size;
+        Self::with_ways(new_size_mb, self.ways)
+    }
 
-        let entries: Vec<TranspositionEntry> =
-            (0..new_size).map(|_| TranspositionEntry::new()).collect();
+    /// Starts a new search: entries stored from now on are newer than all
+    /// the entries already in the table.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
 
-        Self {
-            entries: entries.into_boxed_slice(),
-            size: new_size,
+    /// Gets the number of the current search, to store as the age of new
+    /// entries.
+    pub fn generation(&self) -> u8 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Empties every entry of the table, keeping its size.
+    ///
+    /// # Thread Safety
+    /// Entries are cleared one by one with atomic stores, so a search running
+    /// at the same time only sees entries disappear.
+    pub fn clear(&self) {
+        for entry in self.entries() {
+            entry.set_hash_xor_data(0);
+            entry.set_data(0);
+        }
+    }
+
+    /// Asks the CPU to start loading the bucket of a position into the
+    /// cache.
+    ///
+    /// Called as soon as the hash of a child position is known, so the
+    /// bucket is likely cached by the time the search probes it. Does
+    /// nothing on CPUs without a prefetch instruction.
+    ///
+    /// # Arguments
+    /// * `hash` - Zobrist hash of the position about to be probed
+    #[inline]
+    pub fn prefetch(&self, hash: u64) {
+        if self.sets == 0 {
+            return;
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            use std::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+
+            let slots = self.slots(hash);
+            // SAFETY: SSE is part of every x86-64 CPU, and a prefetch
+            // doesn't access memory, it's only a hint
+            unsafe { _mm_prefetch::<_MM_HINT_T0>(slots.as_ptr().cast()) };
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        let _ = hash;
+    }
+
+    /// Iterates over every entry of every bucket.
+    fn entries(&self) -> impl Iterator<Item = &TranspositionEntry> {
+        self.buckets.iter().flat_map(|bucket| bucket.entries.iter())
+    }
+
+    /// Gets the entries a position may be stored in, all in one bucket.
+    fn slots(&self, hash: u64) -> &[TranspositionEntry] {
+        let set = (hash % self.sets as u64) as usize;
+        let first = set * self.ways;
+        let bucket = &self.buckets[first / BUCKET_SIZE];
+        &bucket.entries[first % BUCKET_SIZE..first % BUCKET_SIZE + self.ways]
+    }
+
+    /// Saves every entry of the table to a hash file.
+    ///
+    /// The file starts with [`HASH_FILE_MAGIC`], [`HASH_FILE_VERSION`], the
+    /// [`Zobrist::checksum`] of the keys and the number of entries, followed
+    /// by the hash and packed data of each entry, all little endian. The
+    /// table should not be searched while it is saved.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the hash file, replaced if it exists
+    /// * `keys` - Zobrist keys the entries were hashed with
+    ///
+    /// # Returns
+    /// The number of entries saved
+    pub fn save(&self, path: &Path, keys: &Zobrist) -> io::Result<usize> {
+        let entries: Vec<(u64, u64)> = self
+            .entries()
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let data = entry.get_data();
+                (entry.get_hash_xor_data() ^ data, data)
+            })
+            .collect();
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&HASH_FILE_MAGIC.to_le_bytes())?;
+        writer.write_all(&HASH_FILE_VERSION.to_le_bytes())?;
+        writer.write_all(&keys.checksum().to_le_bytes())?;
+        writer.write_all(&(entries.len() as u64).to_le_bytes())?;
+        for (hash, data) in &entries {
+            writer.write_all(&hash.to_le_bytes())?;
+            writer.write_all(&data.to_le_bytes())?;
+        }
+        writer.flush()?;
+
+        Ok(entries.len())
+    }
+
+    /// Replaces the entries of the table with those of a hash file.
+    ///
+    /// The whole file is checked before the table is touched: a file of
+    /// another version, saved with other Zobrist keys, truncated or holding
+    /// an entry the search could not have stored is rejected and the table
+    /// is left as it was. Entries are stored with the usual replacement
+    /// policy, so a file saved from a larger table may not fit entirely.
+    ///
+    /// # Arguments
+    /// * `path` - Path of a file written by [`TranspositionTable::save`]
+    /// * `keys` - Zobrist keys the table is searched with
+    ///
+    /// # Returns
+    /// The number of entries read from the file
+    pub fn load(&self, path: &Path, keys: &Zobrist) -> io::Result<usize> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        if read_u32(&mut reader)? != HASH_FILE_MAGIC {
+            return Err(invalid_data("not an EnRust hash file".to_string()));
         }
+        let version = read_u32(&mut reader)?;
+        if version != HASH_FILE_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported hash file version {}",
+                version
+            )));
+        }
+        if read_u64(&mut reader)? != keys.checksum() {
+            return Err(invalid_data(
+                "hash file saved with other Zobrist keys".to_string(),
+            ));
+        }
+
+        let count = read_u64(&mut reader)?;
+        let mut entries = Vec::new();
+        for index in 0..count {
+            let hash = read_u64(&mut reader)?;
+            let data = read_u64(&mut reader)?;
+            if !TranspositionEntry::is_valid(data) {
+                return Err(invalid_data(format!("invalid hash file entry {}", index)));
+            }
+            entries.push((hash, data));
+        }
+        if reader.read(&mut [0])? != 0 {
+            return Err(invalid_data(
+                "trailing data after hash file entries".to_string(),
+            ));
+        }
+
+        self.clear();
+        for &(hash, data) in &entries {
+            self.store(hash, data);
+        }
+        Ok(entries.len())
     }
 
     /// Internal method to probe the transposition table for a specific hash.
@@ -378,11 +738,11 @@ impl TranspositionTable {
     /// * `None` - If no entry exists or hash verification fails
     ///
     /// # Algorithm
-    /// 1. Compute index using modulo operation: `hash % table_size`
-    /// 2. Check if entry is non-empty (quick check without verification)
+    /// 1. Compute the set of the position using modulo operation: `hash % set_count`
+    /// 2. For each entry of the set, skip it if empty (quick check without verification)
     /// 3. Load both atomic fields with relaxed ordering
     /// 4. Verify data consistency: `(hash_xor_data ^ data) == hash`
-    /// 5. Return data only if verification passes
+    /// 5. Return the data of the first entry passing the verification
     ///
     /// # XOR Verification
     /// The verification ensures that:
@@ -392,20 +752,19 @@ impl TranspositionTable {
     ///
     /// # Performance
     /// This method is designed for minimal overhead in the hot path of search.
-    /// Uses relaxed memory ordering since XOR verification provides the consistency guarantee.
+    /// The whole set is in one cache line, and uses relaxed memory ordering
+    /// since XOR verification provides the consistency guarantee.
     fn probe(&self, hash: u64) -> Option<u64> {
-        let index = (hash % self.size as u64) as usize;
-
-        if !&self.entries[index].is_empty() {
-            let entry = &self.entries[index];
-            let entry_xor = entry.get_hash_xor_data();
-            let entry_data = entry.get_data();
-            if (entry_xor ^ entry_data) == hash {
-                return Some(entry_data);
-            }
+        if self.sets == 0 {
+            return None;
         }
 
-        None
+        self.slots(hash)
+            .iter()
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| (entry.get_hash_xor_data(), entry.get_data()))
+            .find(|(entry_xor, entry_data)| entry_xor ^ entry_data == hash)
+            .map(|(_, entry_data)| entry_data)
     }
 
     /// Internal method to store an entry in the transposition table.
@@ -415,21 +774,19 @@ impl TranspositionTable {
     /// * `data` - Packed 64-bit data containing evaluation results
     ///
     /// # Replacement Strategy
-    /// Implements a depth-preferential replacement policy with three cases:
+    /// Implements a depth-preferential replacement policy within the set
+    /// of the position:
     ///
-    /// ## 1. Empty Slot
-    /// Always store the new entry when the target slot is empty.
+    /// ## 1. Same Position (Hash Match)
+    /// Replace the entry of the position if:
+    /// - New search depth is at least the existing depth, OR
+    /// - The existing entry was stored by an earlier search
     ///
-    /// ## 2. Same Position (Hash Match)
-    /// Replace existing entry if:
-    /// - New search depth is greater than existing depth, OR
-    /// - Same depth but new entry has equal or newer age
+    /// ## 2. Empty Entry
+    /// Store the new entry in the first empty entry of the set.
     ///
-    /// ## 3. Different Position (Hash Collision)
-    /// Use `should_replace()` heuristic to decide whether to replace:
-    /// - Prefer entries from current search generation (age)
-    /// - Prefer deeper searches
-    /// - Prefer exact scores over bound scores
+    /// ## 3. Full Set
+    /// Replace the entry picked by `victim()`.
     ///
     /// # Memory Ordering
     /// Uses release stores for both fields to ensure proper visibility
@@ -438,84 +795,69 @@ impl TranspositionTable {
     ///
     /// # Thread Safety
     /// This method is lock-free and can be called concurrently from multiple
-    /// threads. Hash collisions are handled gracefully with the replacement policy.
+    /// threads. Two threads storing in the same set may both pick the same
+    /// entry, the last store wins.
     fn store(&self, hash: u64, data: u64) {
-        let index = (hash % self.size as u64) as usize;
-        let hash_xor_data = hash ^ data;
-
-        // Replacement scheme: always replace if:
-        // 1. Empty slot
-        // 2. Different position (hash collision)
-        // 3. Same position but deeper search or newer
-        if !&self.entries[index].is_empty() {
-            let existing = &self.entries[index];
-            // Get atomic values
-            let existing_xor = existing.get_hash_xor_data();
-
-            if existing_xor == hash_xor_data {
+        if self.sets == 0 {
+            return;
+        }
+        let slots = self.slots(hash);
+
+        let same_position = slots.iter().find(|entry| {
+            !entry.is_empty() && entry.get_hash_xor_data() ^ entry.get_data() == hash
+        });
+        let entry = match same_position {
+            Some(existing) => {
                 let existing_data = existing.get_data();
-                // Same position - replace if deeper search or same depth but newer
-                if TranspositionEntry::depth(data) > TranspositionEntry::depth(existing_data)
-                    || (TranspositionEntry::depth(data) == TranspositionEntry::depth(existing_data)
-                        && TranspositionEntry::age(data) >= TranspositionEntry::age(existing_data))
-                {
-                    self.entries[index].set_hash_xor_data(hash_xor_data);
-                    self.entries[index].set_data(data);
-                }
-            } else {
-                // Hash collision - use replacement policy
-                if self.should_replace(existing, data) {
-                    self.entries[index].set_hash_xor_data(hash_xor_data);
-                    self.entries[index].set_data(data);
+                let replace = TranspositionEntry::depth(data)
+                    >= TranspositionEntry::depth(existing_data)
+                    || TranspositionEntry::age(data) != TranspositionEntry::age(existing_data);
+                if !replace {
+                    return;
                 }
+                existing
             }
-        } else {
-            self.entries[index].set_hash_xor_data(hash_xor_data);
-            self.entries[index].set_data(data);
-        }
+            None => slots
+                .iter()
+                .find(|entry| entry.is_empty())
+                .unwrap_or_else(|| Self::victim(slots, TranspositionEntry::age(data))),
+        };
+
+        entry.set_hash_xor_data(hash ^ data);
+        entry.set_data(data);
     }
 
-    /// Internal replacement policy for hash collisions.
+    /// Internal replacement policy for full sets.
     ///
-    /// Determines whether a new entry should replace an existing one when
-    /// different positions hash to the same table index.
+    /// Picks the entry of the set whose loss costs the least when a new
+    /// position has to be stored.
     ///
     /// # Priority Order
-    /// 1. **Age**: Prefer entries from the current search generation
-    /// 2. **Depth**: Prefer entries from deeper searches
-    /// 3. **Node Type**: Prefer exact scores over bound scores
+    /// 1. **Depth and age**: The entry with the lowest depth is replaced,
+    ///    each search since it was stored lowering its depth by
+    ///    `AGE_DEPTH_PENALTY`, so entries left by earlier searches go first
+    /// 2. **Node Type**: Bound scores are replaced before exact scores
     ///
     /// # Arguments
-    /// * `existing` - Reference to the currently stored entry
-    /// * `new_data` - Packed data of the new entry to consider
+    /// * `slots` - Set of the new position, without an empty entry
+    /// * `generation` - Age of the new entry, the current search
     ///
     /// # Returns
-    /// `true` if the new entry should replace the existing one, `false` otherwise
-    ///
-    /// # Note
-    /// This is a simplified replacement policy. More sophisticated engines
-    /// might consider additional factors like search time or node count.
-    fn should_replace(&self, existing: &TranspositionEntry, new_data: u64) -> bool {
-        let existing_data_entry = existing.get_data();
-        // Prefer entries from current search (age)
-        if TranspositionEntry::age(new_data) != TranspositionEntry::age(existing_data_entry) {
-            return TranspositionEntry::age(new_data)
-                == TranspositionEntry::age(existing_data_entry);
-        }
-
-        // Prefer deeper searches
-        if TranspositionEntry::depth(new_data) != TranspositionEntry::depth(existing_data_entry) {
-            return TranspositionEntry::depth(new_data)
-                > TranspositionEntry::depth(existing_data_entry);
-        }
-
-        // As last resort, prefer exact scores over bounds
-        let new_node_type = TranspositionEntry::node_type(new_data);
-        let existing_node_type = TranspositionEntry::node_type(existing_data_entry);
-        matches!(
-            (new_node_type, existing_node_type),
-            (NodeType::Exact, _) | (_, NodeType::UpperBound) | (_, NodeType::LowerBound)
-        )
+    /// The entry to overwrite
+    fn victim(slots: &[TranspositionEntry], generation: u8) -> &TranspositionEntry {
+        slots
+            .iter()
+            .min_by_key(|entry| {
+                let data = entry.get_data();
+                // Ages wrap around, an entry is at most 255 searches old
+                let relative_age = generation.wrapping_sub(TranspositionEntry::age(data));
+                (
+                    TranspositionEntry::depth(data) as i32
+                        - AGE_DEPTH_PENALTY * relative_age as i32,
+                    TranspositionEntry::node_type(data) == NodeType::Exact,
+                )
+            })
+            .expect("sets are not empty")
     }
 
     /// Stores a position evaluation in the transposition table.
@@ -530,7 +872,7 @@ impl TranspositionTable {
         let mut data_u64 = 0;
 
         // Pack Entry into 64 bits
-        data_u64 |= transposition_data.score as u16 as u64;
+        data_u64 |= transposition_data.score.to_i16() as u16 as u64;
         data_u64 |= (transposition_data.depth as u64) << 16;
         data_u64 |= (transposition_data.node_type as u64) << 24;
         data_u64 |= (transposition_data.best_move as u64) << 26;
@@ -566,3 +908,19 @@ impl TranspositionTable {
         None
     }
 }
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
@@ -0,0 +1,252 @@
+//! Polyglot opening book building.
+//!
+//! This module turns PGN databases into Polyglot opening books. Each game is
+//! replayed up to a maximum ply, and for every position the played move is
+//! recorded together with the result of the game from the mover's point of
+//! view. Moves seen in enough games are written as Polyglot entries weighted
+//! by their score (two points per win, one per draw).
+//!
+//! Book files are a sequence of 16-byte big-endian entries sorted by key:
+//! `key: u64`, `move: u16`, `weight: u16`, `learn: u32`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::game_state::GameState;
+use crate::game_state::board::piece::Color;
+use crate::game_state::pgn::{GameResult, PgnGame, parse_pgn};
+
+/// Size in bytes of a Polyglot book entry.
+pub const ENTRY_SIZE: usize = 16;
+
+/// A single Polyglot book entry.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BookEntry {
+    /// Polyglot key of the position
+    pub key: u64,
+    /// Move in Polyglot encoding
+    pub mv: u16,
+    /// Relative weight of the move among the moves of the position
+    pub weight: u16,
+    /// Learning data, unused by the builder
+    pub learn: u32,
+}
+
+impl BookEntry {
+    /// Serializes the entry in the big-endian Polyglot layout.
+    pub fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut bytes = [0u8; ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.mv.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+
+    /// Deserializes an entry from the big-endian Polyglot layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - 16 bytes of a book file
+    pub fn from_bytes(bytes: &[u8; ENTRY_SIZE]) -> Self {
+        BookEntry {
+            key: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+            mv: u16::from_be_bytes(bytes[8..10].try_into().unwrap()),
+            weight: u16::from_be_bytes(bytes[10..12].try_into().unwrap()),
+            learn: u32::from_be_bytes(bytes[12..16].try_into().unwrap()),
+        }
+    }
+}
+
+/// Statistics of a move played in a position.
+#[derive(Default, Clone, Copy)]
+struct MoveStats {
+    /// Games where the move was played
+    games: u32,
+    /// Games won by the side that played the move
+    wins: u32,
+    /// Drawn games
+    draws: u32,
+}
+
+/// Builds a Polyglot book from PGN games.
+pub struct BookBuilder {
+    /// Maximum number of plies recorded from each game
+    max_ply: usize,
+    /// Minimum number of games a move needs to be written to the book
+    min_games: u32,
+    /// Move statistics keyed by (position key, Polyglot move)
+    stats: HashMap<(u64, u16), MoveStats>,
+    /// Game state used to replay the games
+    game_state: GameState,
+}
+
+impl BookBuilder {
+    /// Creates a new book builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_ply` - Maximum number of plies recorded from each game
+    /// * `min_games` - Minimum number of games a move needs to be kept
+    pub fn new(max_ply: usize, min_games: u32) -> Self {
+        BookBuilder {
+            max_ply,
+            min_games,
+            stats: HashMap::new(),
+            game_state: GameState::new(None),
+        }
+    }
+
+    /// Records the moves of a game.
+    ///
+    /// Games with an unknown result are skipped. Replay stops at the first
+    /// move that is not legal, keeping the moves recorded before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - Parsed PGN game
+    ///
+    /// # Returns
+    ///
+    /// `true` if the game was used, `false` if it was skipped
+    pub fn add_game(&mut self, game: &PgnGame) -> bool {
+        if game.result == GameResult::Unknown {
+            return false;
+        }
+
+        match game.tag("FEN") {
+            Some(fen) => {
+                if !self.game_state.set_fen_position(fen) {
+                    return false;
+                }
+            }
+            None => self.game_state.start_position(),
+        }
+
+        for san in game.moves.iter().take(self.max_ply) {
+            let Some(mv) = self.game_state.create_move_from_san(san) else {
+                break;
+            };
+
+            let key = self.game_state.polyglot_key();
+            let board = self.game_state.get_chess_board();
+            let encoded = board.move_to_polyglot(&mv);
+            let uci = board.move_to_uci(&mv);
+
+            let won = matches!(
+                (self.game_state.side_to_move(), game.result),
+                (Color::White, GameResult::WhiteWins) | (Color::Black, GameResult::BlackWins)
+            );
+
+            let stats = self.stats.entry((key, encoded)).or_default();
+            stats.games += 1;
+            if won {
+                stats.wins += 1;
+            } else if game.result == GameResult::Draw {
+                stats.draws += 1;
+            }
+
+            self.game_state.make_move(&uci);
+        }
+
+        true
+    }
+
+    /// Gets the book entries, sorted by key and then by descending weight.
+    ///
+    /// Moves played in fewer than `min_games` games are left out.
+    pub fn entries(&self) -> Vec<BookEntry> {
+        let mut entries: Vec<BookEntry> = self
+            .stats
+            .iter()
+            .filter(|(_, stats)| stats.games >= self.min_games)
+            .map(|(&(key, mv), stats)| BookEntry {
+                key,
+                mv,
+                weight: (2 * stats.wins + stats.draws).min(u16::MAX as u32) as u16,
+                learn: 0,
+            })
+            .collect();
+
+        entries.sort_by(|a, b| {
+            a.key
+                .cmp(&b.key)
+                .then(b.weight.cmp(&a.weight))
+                .then(a.mv.cmp(&b.mv))
+        });
+
+        entries
+    }
+
+    /// Writes the book to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the book file to create
+    ///
+    /// # Returns
+    ///
+    /// Number of entries written
+    pub fn write(&self, path: &Path) -> io::Result<usize> {
+        let entries = self.entries();
+        let bytes: Vec<u8> = entries.iter().flat_map(|entry| entry.to_bytes()).collect();
+        fs::write(path, bytes)?;
+        Ok(entries.len())
+    }
+}
+
+/// Reads every entry of a Polyglot book file.
+///
+/// # Arguments
+///
+/// * `path` - Path of the book file
+///
+/// # Returns
+///
+/// Entries in file order
+pub fn read_book(path: &Path) -> io::Result<Vec<BookEntry>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() % ENTRY_SIZE != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "book size is not a multiple of the entry size",
+        ));
+    }
+
+    Ok(bytes
+        .chunks_exact(ENTRY_SIZE)
+        .map(|chunk| BookEntry::from_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+/// Builds a Polyglot book from a PGN file.
+///
+/// # Arguments
+///
+/// * `pgn_path` - Path of the PGN database
+/// * `book_path` - Path of the book file to create
+/// * `max_ply` - Maximum number of plies recorded from each game
+/// * `min_games` - Minimum number of games a move needs to be kept
+///
+/// # Returns
+///
+/// Tuple with the number of games used and the number of entries written
+pub fn build_book(
+    pgn_path: &Path,
+    book_path: &Path,
+    max_ply: usize,
+    min_games: u32,
+) -> io::Result<(usize, usize)> {
+    let pgn = fs::read_to_string(pgn_path)?;
+    let mut builder = BookBuilder::new(max_ply, min_games);
+
+    let games_used = parse_pgn(&pgn)
+        .iter()
+        .filter(|game| builder.add_game(game))
+        .count();
+
+    let entries = builder.write(book_path)?;
+    Ok((games_used, entries))
+}
@@ -0,0 +1,393 @@
+//! Persistent learning from played games.
+//!
+//! The experience file remembers, for every root position the engine searched,
+//! the move it played, the last search score, and how the games continued
+//! after it. Positions are identified by their Polyglot key, so the file is
+//! independent of the engine's internal Zobrist seeds.
+//!
+//! Moves are recorded while a game is played and written to the file once the
+//! game is over. Several engine instances may share the same file: writers
+//! lock a file next to it, merge their records with the current contents of the
+//! file, and replace it atomically through a temporary file.
+//!
+//! The file is plain text with one entry per line:
+//! `<key in hex> <uci move> <score> <wins> <draws> <losses>`.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::game_state::Color;
+use crate::game_state::Score;
+use crate::game_state::pgn::GameResult;
+
+/// Default name of the experience file.
+pub const DEFAULT_EXPERIENCE_FILE: &str = "enrust.exp";
+
+/// Minimum number of games a move needs before it is preferred.
+const MIN_GAMES: u32 = 2;
+
+/// Score, in centipawns, at which an unfinished game is adjudicated.
+const ADJUDICATION_SCORE: Score = Score::cp(300);
+
+/// How long a writer waits for another writer to release the file.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What the engine learned about a move in a position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExperienceEntry {
+    /// Move in UCI notation
+    pub mv: String,
+    /// Side-relative search score of the last time the move was chosen
+    pub score: Score,
+    /// Games won by the side that played the move
+    pub wins: u32,
+    /// Drawn games
+    pub draws: u32,
+    /// Games lost by the side that played the move
+    pub losses: u32,
+}
+
+impl ExperienceEntry {
+    /// Number of games the move was played in.
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Fraction of the points scored with the move, between 0 and 1.
+    pub fn performance(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+}
+
+/// A root move chosen during the current game.
+#[derive(Clone, Debug)]
+struct PlayedMove {
+    key: u64,
+    mv: String,
+    score: Score,
+    side: Color,
+}
+
+/// Experience collected from previous games, plus the moves of the game in
+/// progress.
+pub struct Experience {
+    /// Whether experience is recorded and used to pick moves
+    enabled: bool,
+    /// Path of the experience file
+    path: PathBuf,
+    /// Known moves for each position, keyed by Polyglot key
+    entries: HashMap<u64, Vec<ExperienceEntry>>,
+    /// Moves chosen in the current game
+    game: Vec<PlayedMove>,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Experience {
+    /// Creates a disabled experience using the default file name.
+    pub fn new() -> Self {
+        Experience {
+            enabled: false,
+            path: PathBuf::from(DEFAULT_EXPERIENCE_FILE),
+            entries: HashMap::new(),
+            game: Vec::new(),
+        }
+    }
+
+    /// Checks whether learning is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables learning.
+    ///
+    /// Enabling learning loads the experience file, so moves learned by other
+    /// engine instances are picked up.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to record and use experience
+    pub fn set_enabled(&mut self, enabled: bool) -> io::Result<()> {
+        self.enabled = enabled;
+        if enabled {
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    /// Gets the path of the experience file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Changes the experience file.
+    ///
+    /// The moves of the game in progress are kept and will be saved to the
+    /// new file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the experience file
+    pub fn set_path(&mut self, path: &Path) -> io::Result<()> {
+        self.path = path.to_path_buf();
+        self.entries.clear();
+        if self.enabled {
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    /// Reloads the experience file. A missing file is an empty experience.
+    pub fn load(&mut self) -> io::Result<()> {
+        self.entries = read_entries(&self.path)?;
+        Ok(())
+    }
+
+    /// Gets the known moves of a position.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Polyglot key of the position
+    pub fn entries(&self, key: u64) -> &[ExperienceEntry] {
+        self.entries.get(&key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Picks a move from experience.
+    ///
+    /// Only moves played in enough games are considered, and the best of them
+    /// must have scored at least half of the points. Ties are broken by the
+    /// last search score. The search still checks the move, see
+    /// [`set_learned_move`](crate::game_state::ChessBoard::set_learned_move).
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Polyglot key of the position
+    ///
+    /// # Returns
+    ///
+    /// `Some(uci)` with the move to prefer, `None` to play the best move
+    pub fn best_move(&self, key: u64) -> Option<&str> {
+        if !self.enabled {
+            return None;
+        }
+
+        self.entries(key)
+            .iter()
+            .filter(|entry| entry.games() >= MIN_GAMES && entry.performance() >= 0.5)
+            .max_by(|a, b| {
+                a.performance()
+                    .total_cmp(&b.performance())
+                    .then(a.score.cmp(&b.score))
+            })
+            .map(|entry| entry.mv.as_str())
+    }
+
+    /// Records a root move chosen in the current game.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Polyglot key of the position
+    /// * `mv` - Move in UCI notation
+    /// * `score` - Search score from the point of view of `side`
+    /// * `side` - Side that played the move
+    pub fn record(&mut self, key: u64, mv: &str, score: Score, side: Color) {
+        if !self.enabled {
+            return;
+        }
+
+        self.game.push(PlayedMove {
+            key,
+            mv: mv.to_string(),
+            score,
+            side,
+        });
+    }
+
+    /// Gets the score of the last move recorded in the current game, from
+    /// White's point of view.
+    pub fn last_score(&self) -> Option<Score> {
+        self.game
+            .last()
+            .map(|played| played.score.relative_to(played.side))
+    }
+
+    /// Ends the current game and saves what was learned from it.
+    ///
+    /// An unknown result is adjudicated from the last search score: a
+    /// clear advantage counts as a win, anything else as a draw.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - Result of the game
+    pub fn finish_game(&mut self, result: GameResult) -> io::Result<()> {
+        if !self.enabled || self.game.is_empty() {
+            self.game.clear();
+            return Ok(());
+        }
+
+        let result = match result {
+            GameResult::Unknown => match self.last_score().unwrap_or(Score::ZERO) {
+                score if score >= ADJUDICATION_SCORE => GameResult::WhiteWins,
+                score if score <= -ADJUDICATION_SCORE => GameResult::BlackWins,
+                _ => GameResult::Draw,
+            },
+            result => result,
+        };
+
+        let game = std::mem::take(&mut self.game);
+        let _lock = FileLock::acquire(&self.path)?;
+
+        // Merge with the file as it is now, another engine may have written
+        // to it since it was loaded
+        let mut entries = read_entries(&self.path)?;
+        for played in &game {
+            let moves = entries.entry(played.key).or_default();
+            let index = match moves.iter().position(|entry| entry.mv == played.mv) {
+                Some(index) => index,
+                None => {
+                    moves.push(ExperienceEntry {
+                        mv: played.mv.clone(),
+                        score: played.score,
+                        wins: 0,
+                        draws: 0,
+                        losses: 0,
+                    });
+                    moves.len() - 1
+                }
+            };
+
+            let entry = &mut moves[index];
+            entry.score = played.score;
+            match (result, played.side) {
+                (GameResult::Draw, _) => entry.draws += 1,
+                (GameResult::WhiteWins, Color::White) | (GameResult::BlackWins, Color::Black) => {
+                    entry.wins += 1
+                }
+                _ => entry.losses += 1,
+            }
+        }
+
+        write_entries(&self.path, &entries)?;
+        self.entries = entries;
+
+        Ok(())
+    }
+}
+
+/// Reads the entries of an experience file, skipping malformed lines.
+fn read_entries(path: &Path) -> io::Result<HashMap<u64, Vec<ExperienceEntry>>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut entries: HashMap<u64, Vec<ExperienceEntry>> = HashMap::new();
+    for line in contents.lines() {
+        if let Some((key, entry)) = parse_line(line) {
+            entries.entry(key).or_default().push(entry);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Parses a line of an experience file.
+fn parse_line(line: &str) -> Option<(u64, ExperienceEntry)> {
+    let mut fields = line.split_whitespace();
+
+    let key = u64::from_str_radix(fields.next()?, 16).ok()?;
+    let entry = ExperienceEntry {
+        mv: fields.next()?.to_string(),
+        score: Score::cp(fields.next()?.parse().ok()?),
+        wins: fields.next()?.parse().ok()?,
+        draws: fields.next()?.parse().ok()?,
+        losses: fields.next()?.parse().ok()?,
+    };
+
+    Some((key, entry))
+}
+
+/// Writes an experience file through a temporary file, so readers never see
+/// a partially written file.
+fn write_entries(path: &Path, entries: &HashMap<u64, Vec<ExperienceEntry>>) -> io::Result<()> {
+    let mut keys: Vec<&u64> = entries.keys().collect();
+    keys.sort();
+
+    let mut contents = String::new();
+    for key in keys {
+        for entry in &entries[key] {
+            contents.push_str(&format!(
+                "{:016x} {} {} {} {} {}\n",
+                key,
+                entry.mv,
+                entry.score.centipawns(),
+                entry.wins,
+                entry.draws,
+                entry.losses
+            ));
+        }
+    }
+
+    let temp_path = sibling_path(path, &format!("tmp{}", std::process::id()));
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)
+}
+
+/// Builds a path next to `path` with an extra extension.
+fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Exclusive lock on an experience file, released when dropped.
+///
+/// The lock is taken by the operating system on a file next to the
+/// experience file. It is released when the process ends, so a writer that
+/// crashed doesn't leave the file locked.
+struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Waits until the lock can be taken.
+    fn acquire(path: &Path) -> io::Result<Self> {
+        let lock_path = sibling_path(path, "lock");
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&lock_path)?;
+        let start = Instant::now();
+
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(FileLock { _file: file }),
+                Err(TryLockError::WouldBlock) => {
+                    if start.elapsed() > LOCK_TIMEOUT {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("experience file is locked: {}", lock_path.display()),
+                        ));
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(TryLockError::Error(err)) => return Err(err),
+            }
+        }
+    }
+}
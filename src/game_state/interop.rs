@@ -0,0 +1,139 @@
+//! Conversions between EnRust and the types of the `shakmaty` crate.
+//!
+//! Compiled with the `interop` feature, so tools built on shakmaty can use
+//! EnRust to search and evaluate their positions without writing the
+//! conversions themselves. Positions cross over as FEN and moves as UCI
+//! notation, the formats both crates read and write, with the castling
+//! notation of Chess960 when the game plays it.
+//!
+//! ```rust
+//! use enrust::game_state::GameState;
+//! use enrust::game_state::interop;
+//! use shakmaty::{Chess, Position};
+//!
+//! let mut game = GameState::with_hash_size(16);
+//! let position = Chess::default();
+//!
+//! assert!(interop::set_position(&mut game, &position).is_ok());
+//! let mv = game.create_move("g1f3").unwrap();
+//! let converted = interop::move_to_shakmaty(&game, &mv).unwrap();
+//! assert!(position.is_legal(converted));
+//! ```
+
+use shakmaty::fen::Fen;
+use shakmaty::uci::UciMove;
+use shakmaty::{CastlingMode, Chess, EnPassantMode, Position};
+
+use crate::game_state::{GameState, Move, SearchConfiguration};
+
+/// Gets the castling notation of the game, Chess960 or standard.
+fn castling_mode(game: &GameState) -> CastlingMode {
+    CastlingMode::from_chess960(game.get_chess_board().is_chess960())
+}
+
+/// Converts the current position of a game to a shakmaty position.
+///
+/// # Arguments
+///
+/// * `game` - Game whose position is converted
+///
+/// # Returns
+///
+/// The position, or an error if shakmaty rejects it
+pub fn to_shakmaty(game: &GameState) -> Result<Chess, String> {
+    let fen = game.to_fen();
+    fen.parse::<Fen>()
+        .map_err(|error| format!("{}: {}", fen, error))?
+        .into_position(castling_mode(game))
+        .map_err(|error| format!("{}: {}", fen, error))
+}
+
+/// Sets up a shakmaty position in a game.
+///
+/// The game history is cleared, as with
+/// [`set_fen_position`](GameState::set_fen_position).
+///
+/// # Arguments
+///
+/// * `game` - Game to set the position in
+/// * `position` - Position to set up
+///
+/// # Returns
+///
+/// An error if EnRust rejects the position
+pub fn set_position<P: Position>(game: &mut GameState, position: &P) -> Result<(), String> {
+    let fen = Fen::from_position(position, EnPassantMode::Legal).to_string();
+    if game.set_fen_position(&fen) {
+        Ok(())
+    } else {
+        Err(format!("position rejected: {}", fen))
+    }
+}
+
+/// Converts a move of the current position of a game to a shakmaty move.
+///
+/// # Arguments
+///
+/// * `game` - Game whose position the move is played in
+/// * `mv` - Move to convert
+///
+/// # Returns
+///
+/// The move, or an error if it isn't legal in the position
+pub fn move_to_shakmaty(game: &GameState, mv: &Move) -> Result<shakmaty::Move, String> {
+    let position = to_shakmaty(game)?;
+    let uci = game.get_chess_board().move_to_uci(mv);
+
+    uci.parse::<UciMove>()
+        .map_err(|error| format!("{}: {}", uci, error))?
+        .to_move(&position)
+        .map_err(|error| format!("{}: {}", uci, error))
+}
+
+/// Converts a shakmaty move to a move of the current position of a game.
+///
+/// # Arguments
+///
+/// * `game` - Game whose position the move is played in
+/// * `mv` - Move to convert
+///
+/// # Returns
+///
+/// The move, or `None` if it doesn't belong to the position
+pub fn move_from_shakmaty(game: &GameState, mv: &shakmaty::Move) -> Option<Move> {
+    let uci = UciMove::from_move(*mv, castling_mode(game));
+    game.create_move(&uci.to_string())
+}
+
+/// Searches a shakmaty position for its best move.
+///
+/// The position replaces the current position of the game, whose options,
+/// tables and evaluation are used for the search.
+///
+/// # Arguments
+///
+/// * `game` - Game searching the position
+/// * `position` - Position to search
+/// * `config` - Time control and limits of the search
+///
+/// # Returns
+///
+/// The best move, `None` if the position has no legal move, or an error
+/// if a conversion failed
+pub fn best_move(
+    game: &mut GameState,
+    position: &Chess,
+    config: &SearchConfiguration,
+) -> Result<Option<shakmaty::Move>, String> {
+    set_position(game, position)?;
+    game.set_time_control(config);
+
+    let Some(uci) = game.search().join().best_move else {
+        return Ok(None);
+    };
+    uci.parse::<UciMove>()
+        .map_err(|error| format!("{}: {}", uci, error))?
+        .to_move(position)
+        .map(Some)
+        .map_err(|error| format!("{}: {}", uci, error))
+}
@@ -0,0 +1,110 @@
+//! Mate-in-N solver for chess problems.
+//!
+//! Composed problems ask for the key, the first move that forces mate in
+//! a given number of moves against any defence. The general search can't
+//! prove that: it prunes, relies on the evaluation and stops at the first
+//! good move. This solver runs an exhaustive and-or search instead. At the
+//! attacker's turn one move must lead to mate, at the defender's turn every
+//! reply must, with no evaluation, pruning or transposition table involved.
+//!
+//! Every root move is checked, so the answer lists all the keys, cooks
+//! included. As in composed problems, the draw rules (repetition, fifty
+//! moves) are ignored.
+
+use crate::game_state::ChessBoard;
+use crate::game_state::Color;
+use crate::game_state::GameState;
+use crate::game_state::board::rules::Outcome;
+
+/// A first move forcing mate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MateKey {
+    /// The move in UCI format
+    pub uci: String,
+    /// The move in SAN
+    pub san: String,
+    /// Fewest moves the mate takes after this key, the key included
+    pub mate_in: u8,
+}
+
+/// Finds every move forcing mate in at most `moves` moves.
+///
+/// # Arguments
+///
+/// * `fen` - Position of the problem, the attacker to move
+/// * `moves` - Moves of the attacker allowed to mate, the key included
+///
+/// # Returns
+///
+/// The keys in move generation order, empty if there is no mate in
+/// `moves`, or None if the FEN is invalid
+pub fn solve_mate(fen: &str, moves: u8) -> Option<Vec<MateKey>> {
+    let mut game = GameState::new(None);
+    if !game.set_fen_position(fen) {
+        return None;
+    }
+
+    let attacker = game.side_to_move();
+    let mut board = game.get_chess_board().clone();
+
+    let mut keys = Vec::new();
+    for mv in board.generate_moves(attacker) {
+        board.make_move(&mv);
+        let mate_in = (1..=moves).find(|&moves| is_mated(&mut board, attacker.opposite(), moves));
+        board.unmake_move(&mv);
+
+        if let Some(mate_in) = mate_in {
+            keys.push(MateKey {
+                uci: board.move_to_uci(&mv),
+                san: board.move_to_san(&mv),
+                mate_in,
+            });
+        }
+    }
+    Some(keys)
+}
+
+/// Checks whether the side to move mates in at most `moves` moves against
+/// any defence.
+fn mates(board: &mut ChessBoard, attacker: Color, moves: u8) -> bool {
+    let mut candidates = board.generate_moves(attacker);
+    if moves == 1 {
+        // Only a check can mate
+        candidates.retain(|mv| board.gives_check(mv));
+    } else {
+        // Checks leave the fewest replies, so they are tried first
+        candidates.sort_by_cached_key(|mv| !board.gives_check(mv));
+    }
+
+    candidates.into_iter().any(|mv| {
+        board.make_move(&mv);
+        let mated = is_mated(board, attacker.opposite(), moves);
+        board.unmake_move(&mv);
+        mated
+    })
+}
+
+/// Checks whether the side to move is mated, now or after every reply
+/// within the attacker's remaining moves.
+///
+/// # Arguments
+///
+/// * `board` - Position after an attacking move
+/// * `defender` - Color of the player to move
+/// * `moves` - Attacker moves allowed, counting the one just played
+fn is_mated(board: &mut ChessBoard, defender: Color, moves: u8) -> bool {
+    let replies = board.generate_moves(defender);
+    if replies.is_empty() {
+        return board.rules().outcome_without_moves(board, defender) == Outcome::Loss;
+    }
+    if moves == 1 {
+        return false;
+    }
+
+    replies.into_iter().all(|mv| {
+        board.make_move(&mv);
+        let mated = mates(board, defender.opposite(), moves - 1);
+        board.unmake_move(&mv);
+        mated
+    })
+}
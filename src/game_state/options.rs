@@ -0,0 +1,221 @@
+//! Engine options shared between the UCI loop and the searches.
+//!
+//! [`EngineOptions`] gathers the configuration that isn't part of the
+//! position: hash size, threads, time and depth limits. Every value is held
+//! in an atomic, so the options are shared through an `Arc` and changed
+//! from any thread with a shared reference, without locking. A search reads
+//! them when it starts, so a change applies from the next `go` command.
+//!
+//! The hash size is the size wanted for the transposition table: setting it
+//! here, for instance from another thread holding the options, makes the
+//! [`GameState`](super::GameState) resize its table before the next search.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering};
+
+use crate::game_state::{DEFAULT_MAX_DEPTH, DEFAULT_MOVE_OVERHEAD};
+
+/// Default size of the transposition table in megabytes.
+pub const DEFAULT_HASH_MB: usize = 256;
+
+/// Configuration of the engine, shared by the UCI loop and the searches.
+#[derive(Debug)]
+pub struct EngineOptions {
+    /// Size of the transposition table in megabytes, zero for no table
+    hash_mb: AtomicUsize,
+    /// Number of search threads
+    threads: AtomicUsize,
+    /// Whether search threads are pinned to CPUs
+    thread_affinity: AtomicBool,
+    /// Time in milliseconds kept aside for each move
+    move_overhead: AtomicU64,
+    /// Nodes searched at most for each move, zero for no cap
+    nodes_per_move: AtomicU64,
+    /// Depth in plies every search is capped at
+    max_depth: AtomicU8,
+    /// Whether time spent pondering counts toward the move's time budget
+    ponder_time_counts: AtomicBool,
+    /// Whether forced moves are played without searching
+    instant_moves: AtomicBool,
+    /// Whether an analysis moving along its principal variation continues
+    /// deepening from the depth reached
+    resume_analysis: AtomicBool,
+    /// Whether the GUI enabled pondering with the `Ponder` option
+    ponder: AtomicBool,
+    /// Whether the engine thinks on the opponent's time when the GUI
+    /// doesn't ponder
+    permanent_brain: AtomicBool,
+    /// Whether quiescence searches quiet checking moves at its first ply
+    quiescence_checks: AtomicBool,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        EngineOptions {
+            hash_mb: AtomicUsize::new(DEFAULT_HASH_MB),
+            threads: AtomicUsize::new(1),
+            thread_affinity: AtomicBool::new(false),
+            move_overhead: AtomicU64::new(DEFAULT_MOVE_OVERHEAD),
+            nodes_per_move: AtomicU64::new(0),
+            max_depth: AtomicU8::new(DEFAULT_MAX_DEPTH),
+            ponder_time_counts: AtomicBool::new(true),
+            instant_moves: AtomicBool::new(true),
+            resume_analysis: AtomicBool::new(true),
+            ponder: AtomicBool::new(false),
+            permanent_brain: AtomicBool::new(false),
+            quiescence_checks: AtomicBool::new(false),
+        }
+    }
+}
+
+impl EngineOptions {
+    /// Creates the options with their default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the size wanted for the transposition table, in megabytes.
+    pub fn hash_mb(&self) -> usize {
+        self.hash_mb.load(Ordering::Relaxed)
+    }
+
+    /// Sets the size of the transposition table, applied by the game
+    /// before its next search.
+    ///
+    /// # Arguments
+    ///
+    /// * `size_mb` - Size in megabytes, zero for no table
+    pub fn set_hash_mb(&self, size_mb: usize) {
+        self.hash_mb.store(size_mb, Ordering::Relaxed);
+    }
+
+    /// Gets the number of search threads.
+    pub fn threads(&self) -> usize {
+        self.threads.load(Ordering::Relaxed)
+    }
+
+    /// Sets the number of search threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads` - Number of threads, at least one
+    pub fn set_threads(&self, threads: usize) {
+        self.threads.store(threads.max(1), Ordering::Relaxed);
+    }
+
+    /// Whether search threads are pinned to CPUs, see
+    /// [`affinity`](super::affinity).
+    pub fn thread_affinity(&self) -> bool {
+        self.thread_affinity.load(Ordering::Relaxed)
+    }
+
+    /// Pins search threads to CPUs or lets them migrate.
+    pub fn set_thread_affinity(&self, enabled: bool) {
+        self.thread_affinity.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Gets the time in milliseconds kept aside for each move.
+    pub fn move_overhead(&self) -> u64 {
+        self.move_overhead.load(Ordering::Relaxed)
+    }
+
+    /// Sets the time in milliseconds kept aside for each move.
+    pub fn set_move_overhead(&self, move_overhead: u64) {
+        self.move_overhead.store(move_overhead, Ordering::Relaxed);
+    }
+
+    /// Gets the cap on the nodes searched for each move, None for no cap.
+    pub fn nodes_per_move(&self) -> Option<u64> {
+        Some(self.nodes_per_move.load(Ordering::Relaxed)).filter(|&nodes| nodes > 0)
+    }
+
+    /// Caps the nodes searched for each move.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Nodes per move, None or zero for no cap
+    pub fn set_nodes_per_move(&self, nodes: Option<u64>) {
+        self.nodes_per_move
+            .store(nodes.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Gets the depth every search is capped at.
+    pub fn max_depth(&self) -> u8 {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    /// Caps the depth of every search.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - Depth in plies, at least 1
+    pub fn set_max_depth(&self, depth: u8) {
+        self.max_depth.store(depth.max(1), Ordering::Relaxed);
+    }
+
+    /// Whether time spent pondering counts toward the move's time budget.
+    pub fn ponder_time_counts(&self) -> bool {
+        self.ponder_time_counts.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether time spent pondering counts toward the move's time
+    /// budget after a `ponderhit`.
+    pub fn set_ponder_time_counts(&self, counts: bool) {
+        self.ponder_time_counts.store(counts, Ordering::Relaxed);
+    }
+
+    /// Whether forced moves are played without searching.
+    pub fn instant_moves(&self) -> bool {
+        self.instant_moves.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether forced moves, the only legal move or an obvious
+    /// recapture, are played without searching.
+    pub fn set_instant_moves(&self, enabled: bool) {
+        self.instant_moves.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether quiescence searches quiet checking moves at its first ply.
+    pub fn quiescence_checks(&self) -> bool {
+        self.quiescence_checks.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether quiescence searches quiet checking moves at its first
+    /// ply.
+    pub fn set_quiescence_checks(&self, enabled: bool) {
+        self.quiescence_checks.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether an analysis moving along its principal variation continues
+    /// deepening from the depth reached.
+    pub fn resume_analysis(&self) -> bool {
+        self.resume_analysis.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether an analysis moving along its principal variation
+    /// continues deepening from the depth reached, instead of from depth 1.
+    pub fn set_resume_analysis(&self, enabled: bool) {
+        self.resume_analysis.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether the GUI enabled pondering with the `Ponder` option.
+    pub fn ponder(&self) -> bool {
+        self.ponder.load(Ordering::Relaxed)
+    }
+
+    /// Records whether the GUI enabled pondering with the `Ponder` option.
+    pub fn set_ponder(&self, enabled: bool) {
+        self.ponder.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether the engine thinks on the opponent's time when the GUI
+    /// doesn't ponder.
+    pub fn permanent_brain(&self) -> bool {
+        self.permanent_brain.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the engine thinks on the opponent's time when the GUI
+    /// doesn't ponder, see [`permanent_brain`](super::permanent_brain).
+    pub fn set_permanent_brain(&self, enabled: bool) {
+        self.permanent_brain.store(enabled, Ordering::Relaxed);
+    }
+}
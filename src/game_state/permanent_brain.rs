@@ -0,0 +1,128 @@
+//! Thinking on the opponent's time without `go ponder`.
+//!
+//! GUIs that don't enable the `Ponder` option never send `go ponder`, so
+//! the engine sits idle while the opponent thinks. With the
+//! `PermanentBrain` option, once `bestmove` is sent the search thread goes
+//! on searching the position after the best move and the expected reply.
+//! Nothing is printed: the only result is the transposition table entries,
+//! which the next search finds when the opponent plays the expected move,
+//! or another reply sharing positions with it.
+//!
+//! The search stops when the next `position`, `go`, `ucinewgame` or `quit`
+//! command arrives. On Linux the thread lowers its scheduling priority, so
+//! it yields the CPU to any other program, the opponent included. The
+//! option is off by default, as thinking on the opponent's time is
+//! forbidden in some tournaments.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::game_state::board::search::{Depth, MinimaxAlphaBeta, SearchAlgorithm};
+use crate::game_state::{ChessBoard, Color};
+
+/// Search thinking on the opponent's time, created before the search whose
+/// best move it continues from.
+pub struct PermanentBrain {
+    /// Copy of the board the search was started on
+    board: ChessBoard,
+    /// Side to move on the board
+    side_to_move: Color,
+    /// Deepest iteration searched
+    max_depth: u8,
+    /// Flag raised when the next command arrives
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl PermanentBrain {
+    /// Creates the search of the position after the moves of a search.
+    ///
+    /// # Arguments
+    ///
+    /// * `board` - Board of the search whose best move is continued from
+    /// * `side_to_move` - Side to move on the board
+    /// * `max_depth` - Deepest iteration searched
+    /// * `stop_flag` - Flag stopping the search, lowered by the caller
+    pub(super) fn new(
+        board: ChessBoard,
+        side_to_move: Color,
+        max_depth: u8,
+        stop_flag: Arc<AtomicBool>,
+    ) -> Self {
+        PermanentBrain {
+            board,
+            side_to_move,
+            max_depth,
+            stop_flag,
+        }
+    }
+
+    /// Searches the position after the best move and the expected reply,
+    /// until stopped or the deepest iteration is over.
+    ///
+    /// Runs on the calling thread, at a lower priority on Linux.
+    ///
+    /// # Arguments
+    ///
+    /// * `best_move` - Move sent with `bestmove`, in UCI format
+    /// * `ponder_move` - Expected reply, in UCI format, if the principal
+    ///   variation has one: without it the replies themselves are searched
+    pub fn think(mut self, best_move: &str, ponder_move: Option<&str>) {
+        lower_current_thread_priority();
+
+        for uci in std::iter::once(best_move).chain(ponder_move) {
+            if self.stop_flag.load(Ordering::Acquire) {
+                return;
+            }
+            let Some(mv) = self.board.from_uci(uci) else {
+                return;
+            };
+            self.board.make_move(&mv);
+            self.side_to_move = self.side_to_move.opposite();
+        }
+
+        for depth in 1..=self.max_depth {
+            if self.stop_flag.load(Ordering::Acquire) {
+                break;
+            }
+            MinimaxAlphaBeta.search(
+                &mut self.board,
+                Depth::from(depth),
+                self.side_to_move,
+                Arc::clone(&self.stop_flag),
+            );
+        }
+    }
+}
+
+/// Lowers the scheduling priority of the calling thread to the lowest
+/// niceness, so it only runs on CPU time nothing else wants.
+///
+/// Only implemented on Linux, where the niceness is set per thread.
+/// Elsewhere the thread keeps its priority.
+fn lower_current_thread_priority() {
+    #[cfg(target_os = "linux")]
+    linux::lower_current_thread_priority();
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::os::raw::{c_int, c_uint};
+
+    /// `which` of `setpriority` for a process, a thread on Linux.
+    const PRIO_PROCESS: c_int = 0;
+
+    /// Lowest priority, the highest niceness.
+    const LOWEST_PRIORITY: c_int = 19;
+
+    unsafe extern "C" {
+        fn setpriority(which: c_int, who: c_uint, prio: c_int) -> c_int;
+    }
+
+    pub(super) fn lower_current_thread_priority() {
+        // SAFETY: no memory is passed, and who 0 is the calling thread. A
+        // failure leaves the priority as it was, which is harmless
+        unsafe {
+            setpriority(PRIO_PROCESS, 0, LOWEST_PRIORITY);
+        }
+    }
+}
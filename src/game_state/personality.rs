@@ -0,0 +1,126 @@
+//! Engine personalities.
+//!
+//! A personality is a preset of evaluation weights and search parameters
+//! giving the engine a recognizable style, for players who want varied
+//! opponents rather than the strongest one. Each preset starts from the
+//! defaults and only moves a few weights:
+//!
+//! - **Aggressive** values space and pieces over pawns, avoids draws and
+//!   prunes less with the null move, so tactics are seen earlier.
+//! - **Solid** values pawns, the bishop pair and piece safety, and verifies
+//!   more null-move cutoffs.
+//! - **Gambit** gives pawns away for the center and development, and avoids
+//!   draws the most.
+
+use crate::game_state::board::evaluation::TaperedScore;
+use crate::game_state::board::evaluation::params::EvalParams;
+use crate::game_state::board::score::Score;
+use crate::game_state::board::search::params::SearchParams;
+
+/// Index of the pawn in [`EvalParams::piece_values`].
+const PAWN: usize = 0;
+/// Index of the knight in [`EvalParams::piece_values`].
+const KNIGHT: usize = 1;
+/// Index of the bishop in [`EvalParams::piece_values`].
+const BISHOP: usize = 2;
+
+/// A playing style, selected with the `Personality` UCI option.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Personality {
+    /// The default weights, for the strongest play
+    #[default]
+    Default,
+    /// Attacking play, avoiding draws
+    Aggressive,
+    /// Careful play, keeping pawns and pieces safe
+    Solid,
+    /// Pawns given away for the initiative
+    Gambit,
+}
+
+impl Personality {
+    /// Every personality, in the order the option lists them.
+    pub const ALL: [Personality; 4] = [
+        Personality::Default,
+        Personality::Aggressive,
+        Personality::Solid,
+        Personality::Gambit,
+    ];
+
+    /// Gets the name of the personality in the UCI option.
+    pub fn name(self) -> &'static str {
+        match self {
+            Personality::Default => "Default",
+            Personality::Aggressive => "Aggressive",
+            Personality::Solid => "Solid",
+            Personality::Gambit => "Gambit",
+        }
+    }
+
+    /// Finds a personality by its name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the personality
+    ///
+    /// # Returns
+    ///
+    /// The personality, or None for an unknown name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|personality| personality.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Gets the evaluation weights of the personality.
+    pub fn eval_params(self) -> EvalParams {
+        let mut params = EvalParams::default();
+        match self {
+            Personality::Default => {}
+            Personality::Aggressive => {
+                params.piece_values[KNIGHT].mg += 20;
+                params.piece_values[BISHOP].mg += 20;
+                params.space_weight = 2;
+                params.center_attack = 8;
+                params.extended_center_attack = 3;
+                params.king_zone_attack = TaperedScore::new(12, 0);
+            }
+            Personality::Solid => {
+                params.piece_values[PAWN] = TaperedScore::new(110, 115);
+                params.bishop_pair = TaperedScore::new(40, 60);
+                params.trapped_bishop = TaperedScore::new(150, 120);
+                params.cornered_knight = TaperedScore::new(70, 40);
+                params.boxed_rook = TaperedScore::new(70, 0);
+                params.weak_square = TaperedScore::new(6, 3);
+            }
+            Personality::Gambit => {
+                // Pawns are only cheap while there is an attack to play for
+                params.piece_values[PAWN].mg = 80;
+                params.space_weight = 2;
+                params.center_pawn = 15;
+                params.center_attack = 9;
+            }
+        }
+        params
+    }
+
+    /// Gets the search parameters of the personality.
+    pub fn search_params(self) -> SearchParams {
+        let mut params = SearchParams::default();
+        match self {
+            Personality::Default => {}
+            Personality::Aggressive => {
+                params.contempt = Score::cp(20);
+                params.null_move_min_depth = 4;
+            }
+            Personality::Solid => {
+                params.null_move_verification_depth = 4;
+            }
+            Personality::Gambit => {
+                params.contempt = Score::cp(40);
+                params.null_move_min_depth = 4;
+            }
+        }
+        params
+    }
+}
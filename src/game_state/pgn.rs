@@ -0,0 +1,366 @@
+//! Portable Game Notation (PGN) parsing and writing.
+//!
+//! This module reads PGN databases into a list of games, each holding its tag
+//! pairs, the mainline moves in Standard Algebraic Notation (SAN), and the
+//! game result. Comments, variations, numeric annotation glyphs and move
+//! numbers are skipped, since only the mainline is needed to replay a game.
+//!
+//! Games played by the engine are written back with a [`PgnWriter`], in the
+//! export format: the Seven Tag Roster first, then the other tags, and the
+//! movetext wrapped to short lines.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tags every exported game starts with, in this order.
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// Longest line of exported movetext.
+const MAX_LINE_LENGTH: usize = 80;
+
+/// Outcome of a game as recorded in its PGN.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameResult {
+    /// White won the game (`1-0`)
+    WhiteWins,
+    /// Black won the game (`0-1`)
+    BlackWins,
+    /// The game was drawn (`1/2-1/2`)
+    Draw,
+    /// The game is unfinished or the result is unknown (`*`)
+    Unknown,
+}
+
+impl GameResult {
+    /// Parses a PGN result token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Result token such as `1-0` or `1/2-1/2`
+    ///
+    /// # Returns
+    ///
+    /// `Some(GameResult)` if the token is a result, `None` otherwise
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unknown),
+            _ => None,
+        }
+    }
+
+    /// Gets the PGN result token, the inverse of [`from_token`](Self::from_token).
+    pub fn to_token(self) -> &'static str {
+        match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Unknown => "*",
+        }
+    }
+}
+
+/// A single game read from a PGN database.
+#[derive(Clone, Debug)]
+pub struct PgnGame {
+    /// Tag pairs in the order they appear, e.g. `("White", "Carlsen")`
+    pub tags: Vec<(String, String)>,
+    /// Mainline moves in SAN
+    pub moves: Vec<String>,
+    /// Result of the game
+    pub result: GameResult,
+}
+
+impl PgnGame {
+    /// Gets the value of a tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Tag name, e.g. `"FEN"`
+    ///
+    /// # Returns
+    ///
+    /// `Some(value)` if the tag is present, `None` otherwise
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parses every game in a PGN database.
+///
+/// Games are separated by their tag sections or by a result token. Games
+/// without any moves are dropped.
+///
+/// # Arguments
+///
+/// * `pgn` - Contents of a PGN file
+///
+/// # Returns
+///
+/// Vector of parsed games
+pub fn parse_pgn(pgn: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut tags = Vec::new();
+    let mut moves = Vec::new();
+
+    let mut chars = pgn.chars().peekable();
+    let mut variation_depth = 0;
+
+    let mut finish_game =
+        |tags: &mut Vec<(String, String)>, moves: &mut Vec<String>, result: GameResult| {
+            if !moves.is_empty() {
+                games.push(PgnGame {
+                    tags: std::mem::take(tags),
+                    moves: std::mem::take(moves),
+                    result,
+                });
+            } else {
+                tags.clear();
+            }
+        };
+
+    while let Some(c) = chars.next() {
+        match c {
+            // Tag pair, a new tag section after moves starts a new game
+            '[' if variation_depth == 0 => {
+                if !moves.is_empty() {
+                    finish_game(&mut tags, &mut moves, GameResult::Unknown);
+                }
+
+                let tag: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if let Some(tag_pair) = parse_tag(&tag) {
+                    tags.push(tag_pair);
+                }
+            }
+            // Comments until the closing brace or the end of the line
+            '{' => {
+                chars.by_ref().find(|&c| c == '}');
+            }
+            ';' => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            // Variations can be nested, only the mainline is kept
+            '(' => variation_depth += 1,
+            ')' => variation_depth = (variation_depth - 1).max(0),
+            c if c.is_whitespace() => {}
+            c => {
+                let mut token = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "{}();[".contains(next) {
+                        break;
+                    }
+                    token.push(next);
+                    chars.next();
+                }
+
+                if variation_depth > 0 {
+                    continue;
+                }
+
+                if let Some(result) = GameResult::from_token(&token) {
+                    finish_game(&mut tags, &mut moves, result);
+                } else if let Some(san) = parse_move_token(&token) {
+                    moves.push(san);
+                }
+            }
+        }
+    }
+
+    finish_game(&mut tags, &mut moves, GameResult::Unknown);
+
+    games
+}
+
+/// Parses the inside of a tag pair, e.g. `White "Carlsen"`.
+fn parse_tag(tag: &str) -> Option<(String, String)> {
+    let (name, value) = tag.trim().split_once(char::is_whitespace)?;
+    let value = value.trim().trim_matches('"').replace("\\\"", "\"");
+    Some((name.to_string(), value))
+}
+
+/// Extracts the SAN move from a movetext token.
+///
+/// Strips move numbers (`12.`, `12...`), annotation glyphs (`$1`) and
+/// suffix annotations (`!`, `?`).
+///
+/// # Returns
+///
+/// `Some(san)` if the token contains a move, `None` otherwise
+fn parse_move_token(token: &str) -> Option<String> {
+    if token.starts_with('$') {
+        return None;
+    }
+
+    // Move numbers may be glued to the move, as in "1.e4"
+    let san = match token.rfind('.') {
+        Some(index) => &token[index + 1..],
+        None => token,
+    };
+    let san = san.trim_end_matches(['!', '?']);
+
+    // A bare number is a move number written without its dots
+    if san.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(san.to_string())
+}
+
+/// Formats a game in PGN export format.
+///
+/// The tags of the Seven Tag Roster come first in their standard order,
+/// `?` standing for the missing ones, followed by the other tags in the
+/// given order. The `Result` tag and the token ending the movetext both
+/// come from `result`, whatever the tags say.
+///
+/// # Arguments
+///
+/// * `tags` - Tag pairs of the game, in any order
+/// * `movetext` - Moves of the game, without the result
+/// * `result` - Result of the game
+///
+/// # Returns
+///
+/// The game followed by an empty line, ready to be appended to a database
+pub fn format_game(tags: &[(String, String)], movetext: &str, result: GameResult) -> String {
+    let tag_value = |name: &str| {
+        tags.iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let roster = SEVEN_TAG_ROSTER.map(|name| {
+        let value = match name {
+            "Result" => Some(result.to_token()),
+            _ => tag_value(name),
+        };
+        let unknown = if name == "Date" { "????.??.??" } else { "?" };
+        (name, value.unwrap_or(unknown))
+    });
+    let others = tags
+        .iter()
+        .filter(|(name, _)| !SEVEN_TAG_ROSTER.contains(&name.as_str()))
+        .map(|(name, value)| (name.as_str(), value.as_str()));
+
+    let mut pgn = String::new();
+    for (name, value) in roster.into_iter().chain(others) {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+    }
+    pgn.push('\n');
+
+    let mut line_length = 0;
+    for token in movetext_tokens(movetext).chain(std::iter::once(result.to_token())) {
+        if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+        pgn.push_str(token);
+        line_length += token.len();
+    }
+    pgn.push_str("\n\n");
+
+    pgn
+}
+
+/// Splits movetext into the tokens kept on one line: moves, move numbers
+/// and whole comments.
+fn movetext_tokens(movetext: &str) -> impl Iterator<Item = &str> {
+    let mut rest = movetext.trim_start();
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = if rest.starts_with('{') {
+            rest.find('}').map_or(rest.len(), |end| end + 1)
+        } else {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+        let (token, after) = rest.split_at(end);
+        rest = after.trim_start();
+        Some(token)
+    })
+}
+
+/// Formats a date as the value of a PGN `Date` tag, e.g. `2025.03.14`.
+pub fn pgn_date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+
+    // Days since 1970-01-01 to a civil date, counting in 400-year eras of
+    // years starting in March so the leap day ends the year
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+/// Writes finished games to a PGN database.
+///
+/// The file is opened in append mode and every game is written at once, so
+/// several game runners can share the writer, or the file from other
+/// processes, without their games getting mixed up.
+pub struct PgnWriter {
+    file: Mutex<File>,
+}
+
+impl PgnWriter {
+    /// Opens a PGN file for writing, keeping the games already in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the PGN file, created if it doesn't exist
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(PgnWriter {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Appends a game to the file, see [`format_game`].
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - Tag pairs of the game
+    /// * `movetext` - Moves of the game, without the result
+    /// * `result` - Result of the game
+    pub fn write_game(
+        &self,
+        tags: &[(String, String)],
+        movetext: &str,
+        result: GameResult,
+    ) -> io::Result<()> {
+        let pgn = format_game(tags, movetext, result);
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(pgn.as_bytes())?;
+        file.flush()
+    }
+}
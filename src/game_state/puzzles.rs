@@ -0,0 +1,180 @@
+//! Puzzle extraction from games.
+//!
+//! Every position of the games is analyzed with the batch analysis. A
+//! position becomes a puzzle when the side to move has a winning move that
+//! the previous move allowed: its score must be above the threshold, and
+//! above the score of the position before by the same threshold. The best
+//! move must also be the only good one, every other move scoring at least
+//! the threshold less.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::game_state::GameState;
+use crate::game_state::analysis::{AnalysisConfig, AnalysisResult};
+use crate::game_state::board::score::Score;
+use crate::game_state::pgn::{PgnGame, parse_pgn};
+
+/// Settings of the puzzle search.
+#[derive(Clone, Debug)]
+pub struct PuzzleConfig {
+    /// Score the solution must reach, and by which it must beat both the
+    /// previous position and the other moves
+    pub threshold: Score,
+    /// Limits of the analysis of each position
+    pub analysis: AnalysisConfig,
+}
+
+impl Default for PuzzleConfig {
+    fn default() -> Self {
+        PuzzleConfig {
+            threshold: Score::cp(200),
+            analysis: AnalysisConfig::default(),
+        }
+    }
+}
+
+/// A position with a single winning move.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    /// Position to solve
+    pub fen: String,
+    /// Solution line in UCI format, starting with the winning move
+    pub solution: Vec<String>,
+    /// Score of the solution from the point of view of the side to move
+    pub score: Score,
+}
+
+/// Looks for puzzles in the positions of games.
+///
+/// Games are replayed until their first illegal move.
+///
+/// # Arguments
+///
+/// * `games` - Parsed PGN games
+/// * `config` - Threshold and analysis limits
+///
+/// # Returns
+///
+/// Puzzles in game order
+pub fn find_puzzles(games: &[PgnGame], config: &PuzzleConfig) -> Vec<Puzzle> {
+    let mut game_state = GameState::new(Some(64));
+
+    let games: Vec<Vec<String>> = games
+        .iter()
+        .map(|game| game_positions(&mut game_state, game))
+        .collect();
+    let positions: Vec<&String> = games.iter().flatten().collect();
+    let mut results = game_state
+        .analyze_batch(&positions, &config.analysis)
+        .into_iter();
+
+    let mut puzzles = Vec::new();
+    for game in &games {
+        let game_results: Vec<AnalysisResult> = results.by_ref().take(game.len()).collect();
+
+        for pair in game_results.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            // The previous score is from the opponent's point of view
+            let swing = current.score + previous.score;
+
+            if current.best_move.is_some()
+                && current.score >= config.threshold
+                && swing >= config.threshold
+                && is_only_winning_move(&mut game_state, current, config)
+            {
+                puzzles.push(Puzzle {
+                    fen: current.fen.clone(),
+                    solution: current.pv.clone(),
+                    score: current.score,
+                });
+            }
+        }
+    }
+
+    puzzles
+}
+
+/// Looks for puzzles in the games of a PGN file.
+///
+/// # Arguments
+///
+/// * `pgn_path` - Path of the PGN database
+/// * `config` - Threshold and analysis limits
+///
+/// # Returns
+///
+/// Puzzles in game order
+pub fn extract_puzzles(pgn_path: &Path, config: &PuzzleConfig) -> io::Result<Vec<Puzzle>> {
+    let pgn = fs::read_to_string(pgn_path)?;
+    Ok(find_puzzles(&parse_pgn(&pgn), config))
+}
+
+/// Replays a game and collects the FEN of the position before each move.
+fn game_positions(game_state: &mut GameState, game: &PgnGame) -> Vec<String> {
+    let started = match game.tag("FEN") {
+        Some(fen) => game_state.set_fen_position(fen),
+        None => {
+            game_state.start_position();
+            true
+        }
+    };
+    if !started {
+        return Vec::new();
+    }
+
+    let mut positions = Vec::new();
+    for san in &game.moves {
+        let Some(mv) = game_state.create_move_from_san(san) else {
+            break;
+        };
+
+        positions.push(game_state.to_fen());
+        let uci = game_state.get_chess_board().move_to_uci(&mv);
+        game_state.make_move(&uci);
+    }
+
+    positions
+}
+
+/// Checks that every move other than the best one scores at least the
+/// threshold less.
+///
+/// The positions after the other moves are analyzed one ply shallower. A
+/// forced move is not a puzzle.
+fn is_only_winning_move(
+    game_state: &mut GameState,
+    analysis: &AnalysisResult,
+    config: &PuzzleConfig,
+) -> bool {
+    game_state.set_fen_position(&analysis.fen);
+    let alternatives: Vec<String> = game_state
+        .generate_moves()
+        .into_iter()
+        .filter(|mv| Some(mv) != analysis.best_move.as_ref())
+        .collect();
+    if alternatives.is_empty() {
+        return false;
+    }
+
+    let replies: Vec<String> = alternatives
+        .iter()
+        .map(|mv| {
+            game_state.set_fen_position(&analysis.fen);
+            game_state.make_move(mv);
+            game_state.to_fen()
+        })
+        .collect();
+
+    let reply_config = AnalysisConfig {
+        depth: config.analysis.depth.saturating_sub(1).max(1),
+        ..config.analysis.clone()
+    };
+
+    game_state
+        .analyze_batch(&replies, &reply_config)
+        .iter()
+        // Reply scores are from the opponent's point of view
+        .all(|reply| analysis.score + reply.score >= config.threshold)
+}
@@ -0,0 +1,59 @@
+//! Readiness of the engine for the `isready` command.
+//!
+//! GUIs send `isready` and wait for `readyok` before starting a game, so
+//! the answer must only come once slow operations are over. Commands are
+//! handled one after the other, so what a command does itself (resizing the
+//! hash table, loading a network, an experience file or evaluation
+//! parameters) is over before a later `isready` is read. Operations running
+//! in the background, such as generating the KPK bitbase at startup, are
+//! started through a [`ReadinessGate`] that `isready` waits on.
+
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+/// Slow operations running in the background, to wait for before answering
+/// `isready`.
+#[derive(Default)]
+pub struct ReadinessGate {
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl ReadinessGate {
+    /// Creates a gate with no operation running.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs a slow operation on its own thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - Operation to run, `isready` waits until it's over
+    pub fn spawn(&self, task: impl FnOnce() + Send + 'static) {
+        let handle = thread::spawn(task);
+        self.tasks.lock().unwrap().push(handle);
+    }
+
+    /// Checks whether every operation is over, without waiting.
+    pub fn is_ready(&self) -> bool {
+        self.tasks
+            .lock()
+            .unwrap()
+            .iter()
+            .all(JoinHandle::is_finished)
+    }
+
+    /// Waits until every operation is over.
+    ///
+    /// # Returns
+    ///
+    /// The number of operations that panicked
+    pub fn wait(&self) -> usize {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        tasks
+            .into_iter()
+            .map(JoinHandle::join)
+            .filter(Result::is_err)
+            .count()
+    }
+}
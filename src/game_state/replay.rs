@@ -0,0 +1,264 @@
+//! Replay of logged UCI sessions.
+//!
+//! A session log records both sides of a conversation between a GUI and the
+//! engine, one line per message:
+//!
+//! ```text
+//! > position startpos moves e2e4
+//! > go depth 3
+//! < info depth 1 score cp 20 nodes 0 nps 0 pv e7e5
+//! < bestmove e7e5
+//! ```
+//!
+//! Lines starting with `>` are commands sent to the engine, lines starting
+//! with `<` are its answers; anything else is a comment. Replaying the log
+//! sends the commands to a fresh engine process in the same order, waiting
+//! for the answers a GUI would wait for (`uciok`, `readyok`, `bestmove`), and
+//! compares the `info` and `bestmove` lines it prints with the recorded ones.
+//! Timings (`time`, `nps`) are ignored, so searches limited by depth or nodes
+//! replay exactly while searches limited by time usually don't.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Longest wait for an answer of the engine before giving up.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Fields of `info` lines that depend on the speed of the machine.
+const TIMING_FIELDS: [&str; 2] = ["time", "nps"];
+
+/// A logged UCI session.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Session {
+    /// Commands sent to the engine, in order
+    pub commands: Vec<String>,
+    /// Lines the engine answered, in order
+    pub answers: Vec<String>,
+}
+
+impl Session {
+    /// Parses a session log, see the [module documentation](self).
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - Contents of the log
+    pub fn parse(log: &str) -> Self {
+        let mut session = Session::default();
+
+        for line in log.lines() {
+            if let Some(command) = line.strip_prefix('>') {
+                session.commands.push(command.trim().to_string());
+            } else if let Some(answer) = line.strip_prefix('<') {
+                session.answers.push(answer.trim().to_string());
+            }
+        }
+
+        session
+    }
+}
+
+/// A difference between the recorded and the replayed answers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    /// Index of the line among the compared lines
+    pub index: usize,
+    /// Recorded line, None if the engine printed more lines
+    pub expected: Option<String>,
+    /// Replayed line, None if the engine printed fewer lines
+    pub actual: Option<String>,
+}
+
+/// Outcome of a replayed session.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayReport {
+    /// Number of `info` and `bestmove` lines compared
+    pub compared: usize,
+    /// Lines that differ
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ReplayReport {
+    /// Whether the engine answered as recorded.
+    pub fn is_match(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Keeps the `info` and `bestmove` lines of the engine's answers, without
+/// their timings.
+///
+/// # Arguments
+///
+/// * `answers` - Lines printed by the engine
+///
+/// # Returns
+///
+/// The lines to compare, in order
+pub fn comparable_lines(answers: &[String]) -> Vec<String> {
+    answers
+        .iter()
+        .filter(|line| line.starts_with("info") || line.starts_with("bestmove"))
+        .map(|line| strip_timings(line))
+        .collect()
+}
+
+/// Removes the [`TIMING_FIELDS`] and their values from an answer.
+fn strip_timings(line: &str) -> String {
+    let mut tokens = line.split_whitespace();
+    let mut kept = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        if TIMING_FIELDS.contains(&token) {
+            tokens.next();
+        } else {
+            kept.push(token);
+        }
+    }
+
+    kept.join(" ")
+}
+
+/// Compares the recorded answers of a session with the replayed ones.
+///
+/// # Arguments
+///
+/// * `expected` - Answers recorded in the log
+/// * `actual` - Answers printed by the replayed engine
+///
+/// # Returns
+///
+/// The compared line count and the lines that differ
+pub fn compare_answers(expected: &[String], actual: &[String]) -> ReplayReport {
+    let expected = comparable_lines(expected);
+    let actual = comparable_lines(actual);
+    let compared = expected.len().max(actual.len());
+
+    let mismatches = (0..compared)
+        .filter_map(|index| {
+            let (expected, actual) = (expected.get(index), actual.get(index));
+            (expected != actual).then(|| Mismatch {
+                index,
+                expected: expected.cloned(),
+                actual: actual.cloned(),
+            })
+        })
+        .collect();
+
+    ReplayReport {
+        compared,
+        mismatches,
+    }
+}
+
+/// Replays a session against an engine process.
+///
+/// The engine is started without arguments, so it speaks UCI. A `quit`
+/// command is sent at the end if the log doesn't have one.
+///
+/// # Arguments
+///
+/// * `engine` - Path of the engine executable
+/// * `session` - Session to replay
+///
+/// # Returns
+///
+/// The comparison of the replayed answers with the recorded ones, or an
+/// error if the engine couldn't be run or stopped answering
+pub fn replay_session(engine: &Path, session: &Session) -> io::Result<ReplayReport> {
+    let mut child = Command::new(engine)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    let (sender, receiver) = mpsc::channel();
+    let reader = thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut answers = Vec::new();
+    let mut searching = false;
+    let mut quit = false;
+
+    for command in &session.commands {
+        writeln!(stdin, "{}", command)?;
+        stdin.flush()?;
+
+        let mut tokens = command.split_whitespace();
+        let awaited = match tokens.next() {
+            Some("uci") => Some("uciok"),
+            Some("isready") => Some("readyok"),
+            Some("go") => {
+                searching = true;
+                // Infinite and ponder searches only end on `stop` or `ponderhit`
+                let open_ended = tokens.any(|token| token == "infinite" || token == "ponder");
+                (!open_ended).then_some("bestmove")
+            }
+            Some("stop") | Some("ponderhit") if searching => Some("bestmove"),
+            Some("quit") => {
+                quit = true;
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(awaited) = awaited {
+            wait_for_answer(&receiver, awaited, &mut answers)?;
+            if awaited == "bestmove" {
+                searching = false;
+            }
+        }
+    }
+
+    if !quit {
+        writeln!(stdin, "quit")?;
+    }
+    drop(stdin);
+    child.wait()?;
+    reader.join().expect("reader thread panicked");
+    answers.extend(receiver.try_iter());
+
+    Ok(compare_answers(&session.answers, &answers))
+}
+
+/// Collects the engine's answers up to the first one starting with
+/// `awaited`.
+fn wait_for_answer(
+    receiver: &Receiver<String>,
+    awaited: &str,
+    answers: &mut Vec<String>,
+) -> io::Result<()> {
+    loop {
+        match receiver.recv_timeout(ANSWER_TIMEOUT) {
+            Ok(line) => {
+                let found = line.starts_with(awaited);
+                answers.push(line);
+                if found {
+                    return Ok(());
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("no '{}' from the engine", awaited),
+                ));
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("engine exited before '{}'", awaited),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Handle on a search running in the background.
+//!
+//! [`GameState::search`](super::GameState::search) starts the search on its
+//! own thread and returns a [`SearchHandle`] at once, so the UCI loop keeps
+//! reading commands while the engine thinks. Programs using the engine as a
+//! library wait for the [`SearchResult`] with [`SearchHandle::join`], or stop
+//! the search early with [`SearchHandle::abort`].
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+
+use crate::game_state::board::score::Score;
+
+/// Outcome of a search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    /// Best move in UCI format, None if there is no legal move
+    pub best_move: Option<String>,
+    /// Expected reply to the best move, the second move of the principal
+    /// variation
+    pub ponder_move: Option<String>,
+    /// Score from the point of view of the side to move
+    pub score: Score,
+    /// Depth of the last completed iteration, 1 for a forced move played
+    /// without searching
+    pub depth: u8,
+    /// Nodes searched
+    pub nodes: u64,
+    /// Principal variation in UCI format, starting with the best move
+    pub pv: Vec<String>,
+}
+
+/// Handle on a search started by [`GameState::search`](super::GameState::search).
+///
+/// Dropping the handle doesn't stop the search: it runs until its limits
+/// are reached or [`GameState::stop_search`](super::GameState::stop_search)
+/// is called.
+pub struct SearchHandle {
+    /// Thread running the search
+    thread: JoinHandle<SearchResult>,
+    /// Stop flag of the search
+    stop_flag: Arc<AtomicBool>,
+}
+
+impl SearchHandle {
+    /// Creates the handle of a search running on a thread.
+    pub(super) fn running(thread: JoinHandle<SearchResult>, stop_flag: Arc<AtomicBool>) -> Self {
+        SearchHandle { thread, stop_flag }
+    }
+
+    /// Waits for the search to finish.
+    ///
+    /// # Returns
+    ///
+    /// The best move found, with its score, depth and principal variation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the search thread panicked.
+    pub fn join(self) -> SearchResult {
+        self.thread.join().expect("search thread panicked")
+    }
+
+    /// Asks the search to stop. It still returns the best move found so far,
+    /// collected with [`join`](Self::join).
+    pub fn abort(&self) {
+        self.stop_flag.store(true, Ordering::Release);
+    }
+
+    /// Checks whether the search is over, so [`join`](Self::join) won't
+    /// block.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+}
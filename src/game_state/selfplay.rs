@@ -0,0 +1,508 @@
+//! Games between two engines, with a clock.
+//!
+//! [`play_game`] plays a game between two [`GameState`]s, the same
+//! configuration for self-play or two different ones for a match. Each
+//! side thinks on its own [`Clock`] time: before every move the remaining
+//! times are passed to the engine to move as a [`SearchConfiguration`], and
+//! the time it actually took is taken off its clock afterwards. A side
+//! running out of time loses the game.
+//!
+//! Games whose result is clear can be ended early with [`Adjudication`]
+//! rules, saving the time of playing them out when testing the engine.
+//!
+//! The clock of the side that moved is recorded with every move and written
+//! as a `%clk` comment in the PGN of the game.
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::game_state::board::evaluation::endgame;
+use crate::game_state::board::rules::Outcome;
+use crate::game_state::eco::Opening;
+use crate::game_state::pgn::{self, GameResult};
+use crate::game_state::{Color, GameState, Score, SearchConfiguration};
+
+/// Standard starting position in FEN.
+const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// Plies without capture or pawn move after which the game is drawn.
+const FIFTY_MOVES_PLIES: u64 = 100;
+
+/// Chess clock of both sides.
+///
+/// Each side starts with the base time and gains the increment after each
+/// of its moves. With a number of moves per control, the base time is added
+/// again every time a side completes that many moves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clock {
+    /// Time given at the start of each control
+    base: Duration,
+    /// Time added after each move
+    increment: Duration,
+    /// Moves of each control, None for sudden death
+    moves_per_control: Option<u32>,
+    /// Time left for White and Black
+    remaining: [Duration; 2],
+    /// Moves played by White and Black
+    moves_played: [u32; 2],
+}
+
+impl Clock {
+    /// Creates a clock with the full base time for both sides.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Time of each control
+    /// * `increment` - Time added after each move
+    /// * `moves_per_control` - Moves to play before the base time is added
+    ///   again, None for sudden death
+    pub fn new(base: Duration, increment: Duration, moves_per_control: Option<u32>) -> Self {
+        Clock {
+            base,
+            increment,
+            moves_per_control: moves_per_control.filter(|&moves| moves > 0),
+            remaining: [base; 2],
+            moves_played: [0; 2],
+        }
+    }
+
+    /// Parses a time control such as `10+0.1`, `40/60` or `300`.
+    ///
+    /// The format is `[moves/]base[+increment]`, with the base time and the
+    /// increment in seconds.
+    ///
+    /// # Returns
+    ///
+    /// The clock, or `None` if the time control is malformed
+    pub fn parse(time_control: &str) -> Option<Self> {
+        let (moves_per_control, time) = match time_control.split_once('/') {
+            Some((moves, time)) => (Some(moves.parse::<u32>().ok()?), time),
+            None => (None, time_control),
+        };
+        let (base, increment) = time.split_once('+').unwrap_or((time, "0"));
+
+        let seconds = |value: &str| {
+            value
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        };
+        let base = seconds(base).filter(|base| !base.is_zero())?;
+
+        Some(Clock::new(base, seconds(increment)?, moves_per_control))
+    }
+
+    /// Gets the time control in the format of the PGN `TimeControl` tag,
+    /// e.g. `40/60` or `10+0.1`, times in seconds.
+    pub fn time_control(&self) -> String {
+        let mut time_control = match self.moves_per_control {
+            Some(moves) => format!("{}/{}", moves, self.base.as_secs_f64()),
+            None => self.base.as_secs_f64().to_string(),
+        };
+        if !self.increment.is_zero() {
+            time_control.push_str(&format!("+{}", self.increment.as_secs_f64()));
+        }
+        time_control
+    }
+
+    /// Gets the time left for a side.
+    pub fn remaining(&self, color: Color) -> Duration {
+        self.remaining[color as usize]
+    }
+
+    /// Gets the number of moves a side has to play before the next control,
+    /// None in sudden death.
+    pub fn moves_to_go(&self, color: Color) -> Option<u32> {
+        self.moves_per_control
+            .map(|moves| moves - self.moves_played[color as usize] % moves)
+    }
+
+    /// Checks if a side ran out of time.
+    pub fn has_flagged(&self, color: Color) -> bool {
+        self.remaining(color).is_zero()
+    }
+
+    /// Gets the search configuration giving the engine the times of the
+    /// clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `side_to_move` - Side about to think, whose moves to go are sent
+    pub fn search_configuration(&self, side_to_move: Color) -> SearchConfiguration {
+        let millis = |duration: Duration| duration.as_millis() as u64;
+
+        SearchConfiguration {
+            wtime: Some(millis(self.remaining(Color::White))),
+            btime: Some(millis(self.remaining(Color::Black))),
+            winc: Some(millis(self.increment)),
+            binc: Some(millis(self.increment)),
+            movestogo: self.moves_to_go(side_to_move).map(u64::from),
+            ..SearchConfiguration::new()
+        }
+    }
+
+    /// Takes the time spent on a move off the clock of a side.
+    ///
+    /// Without a flag fall, the side then gains the increment, and the base
+    /// time of the next control if the move completed one.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - Side that moved
+    /// * `elapsed` - Time spent on the move
+    ///
+    /// # Returns
+    ///
+    /// `false` if the side ran out of time
+    pub fn punch(&mut self, color: Color, elapsed: Duration) -> bool {
+        let side = color as usize;
+        let Some(remaining) = self.remaining[side].checked_sub(elapsed) else {
+            self.remaining[side] = Duration::ZERO;
+            return false;
+        };
+
+        self.remaining[side] = remaining + self.increment;
+        self.moves_played[side] += 1;
+        if self
+            .moves_per_control
+            .is_some_and(|moves| self.moves_played[side].is_multiple_of(moves))
+        {
+            self.remaining[side] += self.base;
+        }
+        true
+    }
+}
+
+/// Formats a clock reading as a PGN `%clk` comment, e.g.
+/// `{[%clk 0:04:59.5]}`.
+pub fn clk_comment(remaining: Duration) -> String {
+    let tenths = remaining.as_millis() / 100;
+    let seconds = tenths / 10;
+
+    format!(
+        "{{[%clk {}:{:02}:{:02}.{}]}}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        tenths % 10
+    )
+}
+
+/// Rules ending a game before checkmate or a draw by the rules.
+///
+/// The score rules look at the scores reported by the engines for their
+/// own moves, so both sides have to agree on the evaluation.
+#[derive(Clone, Debug)]
+pub struct Adjudication {
+    /// Score beyond which the losing side resigns, None to never resign
+    pub resign_score: Option<Score>,
+    /// Consecutive moves of each side the resign score has to be reached
+    pub resign_moves: usize,
+    /// Score within which the game is drawn, None to never adjudicate a draw
+    pub draw_score: Option<Score>,
+    /// Consecutive moves of each side the scores have to stay within the
+    /// draw score
+    pub draw_moves: usize,
+    /// Move number from which draws are adjudicated
+    pub draw_move_number: u64,
+    /// Ends the game as soon as an endgame covered by a bitbase is reached,
+    /// with its exact result, see [`endgame::probe`]
+    pub tablebase: bool,
+}
+
+impl Default for Adjudication {
+    fn default() -> Self {
+        Adjudication {
+            resign_score: None,
+            resign_moves: 3,
+            draw_score: None,
+            draw_moves: 8,
+            draw_move_number: 40,
+            tablebase: false,
+        }
+    }
+}
+
+impl Adjudication {
+    /// Decides the game if the rules allow it.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - Game about to continue, with the current position
+    /// * `moves` - Moves played so far
+    ///
+    /// # Returns
+    ///
+    /// The adjudicated result, or `None` to play on
+    fn adjudicate(&self, game: &GameState, moves: &[PlayedMove]) -> Option<GameResult> {
+        let side_to_move = game.side_to_move();
+
+        if self.tablebase
+            && game.variant() == "chess"
+            && let Some(outcome) = endgame::probe(game.get_chess_board(), side_to_move)
+        {
+            return Some(match (outcome, side_to_move) {
+                (Outcome::Draw, _) => GameResult::Draw,
+                (Outcome::Win, Color::White) | (Outcome::Loss, Color::Black) => {
+                    GameResult::WhiteWins
+                }
+                (Outcome::Win, Color::Black) | (Outcome::Loss, Color::White) => {
+                    GameResult::BlackWins
+                }
+            });
+        }
+
+        // Scores of the last moves from White's point of view, the last move
+        // was played by the other side
+        let white_scores = |plies: usize| {
+            let mut side = side_to_move.opposite();
+            let scores: Vec<Score> = moves
+                .iter()
+                .rev()
+                .take(plies)
+                .map(|played| {
+                    let score = played.score.relative_to(side);
+                    side = side.opposite();
+                    score
+                })
+                .collect();
+            (scores.len() == plies).then_some(scores)
+        };
+
+        if let Some(threshold) = self.resign_score
+            && let Some(scores) = white_scores(2 * self.resign_moves)
+        {
+            if scores.iter().all(|&score| score <= -threshold) {
+                return Some(GameResult::BlackWins);
+            }
+            if scores.iter().all(|&score| score >= threshold) {
+                return Some(GameResult::WhiteWins);
+            }
+        }
+
+        if let Some(threshold) = self.draw_score
+            && game.fullmove_number() > self.draw_move_number
+            && let Some(scores) = white_scores(2 * self.draw_moves)
+            && scores
+                .iter()
+                .all(|&score| -threshold <= score && score <= threshold)
+        {
+            return Some(GameResult::Draw);
+        }
+
+        None
+    }
+}
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Termination {
+    /// Checkmate, stalemate, insufficient material, repetition or fifty-move
+    /// rule
+    Normal,
+    /// A side ran out of time
+    TimeForfeit,
+    /// Ended by the [`Adjudication`] rules
+    Adjudication,
+}
+
+impl Termination {
+    /// Gets the value of the PGN `Termination` tag.
+    pub fn pgn_name(self) -> &'static str {
+        match self {
+            Termination::Normal => "normal",
+            Termination::TimeForfeit => "time forfeit",
+            Termination::Adjudication => "adjudication",
+        }
+    }
+}
+
+/// Move of a played game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayedMove {
+    /// Move in Standard Algebraic Notation
+    pub san: String,
+    /// Move in UCI notation
+    pub uci: String,
+    /// Score reported by the engine, from the point of view of the side
+    /// that moved
+    pub score: Score,
+    /// Time left on the clock of the side that moved
+    pub clock: Duration,
+}
+
+/// Game played by [`play_game`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayedGame {
+    /// Starting position in FEN
+    pub start_fen: String,
+    /// Moves of the game
+    pub moves: Vec<PlayedMove>,
+    /// Result of the game
+    pub result: GameResult,
+    /// How the game ended
+    pub termination: Termination,
+    /// Time control in the format of the PGN `TimeControl` tag
+    pub time_control: String,
+    /// Opening of the game, None if it left the known lines at once
+    pub opening: Option<Opening>,
+}
+
+impl PlayedGame {
+    /// Gets the tag pairs the game knows about: time control, termination,
+    /// starting position and opening. The Seven Tag Roster is left to the
+    /// caller, see [`PgnWriter`](crate::game_state::pgn::PgnWriter).
+    pub fn tags(&self) -> Vec<(String, String)> {
+        let mut tags = vec![
+            ("TimeControl".to_string(), self.time_control.clone()),
+            (
+                "Termination".to_string(),
+                self.termination.pgn_name().to_string(),
+            ),
+        ];
+        if self.start_fen != START_FEN {
+            tags.push(("SetUp".to_string(), "1".to_string()));
+            tags.push(("FEN".to_string(), self.start_fen.clone()));
+        }
+        if let Some(opening) = &self.opening {
+            tags.extend(opening.pgn_tags());
+        }
+        tags
+    }
+
+    /// Gets the game in PGN, with an unknown Seven Tag Roster apart from the
+    /// result.
+    pub fn to_pgn(&self) -> String {
+        pgn::format_game(&self.tags(), &self.movetext(), self.result)
+    }
+
+    /// Gets the moves of the game in PGN movetext, each followed by the
+    /// clock of the side that played it. The result is left out.
+    pub fn movetext(&self) -> String {
+        let fields: Vec<&str> = self.start_fen.split_whitespace().collect();
+        let mut side = match fields.get(1) {
+            Some(&"b") => Color::Black,
+            _ => Color::White,
+        };
+        let mut move_number: u64 = fields.get(5).and_then(|n| n.parse().ok()).unwrap_or(1);
+
+        let mut movetext = String::new();
+        for (index, played) in self.moves.iter().enumerate() {
+            if side == Color::White {
+                write!(movetext, "{}. ", move_number).unwrap();
+            } else if index == 0 {
+                write!(movetext, "{}... ", move_number).unwrap();
+            }
+            write!(movetext, "{} {} ", played.san, clk_comment(played.clock)).unwrap();
+
+            if side == Color::Black {
+                move_number += 1;
+            }
+            side = side.opposite();
+        }
+        movetext.truncate(movetext.trim_end().len());
+        movetext
+    }
+}
+
+/// Plays a game between two engines.
+///
+/// Both engines start from their current position, which must be the same,
+/// and think in turn with the times of the clock. The game ends when the
+/// side to move has no legal move, when neither side can checkmate, on a
+/// threefold repetition, by the fifty-move rule, when a side runs out of time or when the adjudication
+/// rules decide it.
+///
+/// # Arguments
+///
+/// * `white` - Engine playing White
+/// * `black` - Engine playing Black
+/// * `clock` - Clock of the game, with the times at the start
+/// * `adjudication` - Rules ending the game early
+///
+/// # Returns
+///
+/// The moves of the game, with the clock after each one, and its result
+pub fn play_game(
+    white: &mut GameState,
+    black: &mut GameState,
+    mut clock: Clock,
+    adjudication: &Adjudication,
+) -> PlayedGame {
+    let mut game = PlayedGame {
+        start_fen: white.to_fen(),
+        moves: Vec::new(),
+        result: GameResult::Unknown,
+        termination: Termination::Normal,
+        time_control: clock.time_control(),
+        opening: None,
+    };
+
+    loop {
+        let side = white.side_to_move();
+        // Checkmate, stalemate and insufficient material
+        game.result = white.game_result();
+        if game.result != GameResult::Unknown {
+            break;
+        }
+        if white.halfmove_clock() >= FIFTY_MOVES_PLIES || is_threefold_repetition(white) {
+            game.result = GameResult::Draw;
+            break;
+        }
+        if let Some(result) = adjudication.adjudicate(white, &game.moves) {
+            game.result = result;
+            game.termination = Termination::Adjudication;
+            break;
+        }
+
+        let engine = match side {
+            Color::White => &mut *white,
+            Color::Black => &mut *black,
+        };
+        engine.set_time_control(&clock.search_configuration(side));
+        let start = Instant::now();
+        let result = engine.search().join();
+        let elapsed = start.elapsed();
+
+        if !clock.punch(side, elapsed) {
+            game.result = match side {
+                Color::White => GameResult::BlackWins,
+                Color::Black => GameResult::WhiteWins,
+            };
+            game.termination = Termination::TimeForfeit;
+            break;
+        }
+        let uci = result.best_move.expect("the side to move has legal moves");
+
+        let san = white
+            .legal_moves()
+            .into_iter()
+            .find(|record| record.uci == uci)
+            .map_or_else(|| uci.clone(), |record| record.san);
+        white.make_move(&uci);
+        black.make_move(&uci);
+        game.moves.push(PlayedMove {
+            san,
+            uci,
+            score: result.score,
+            clock: clock.remaining(side),
+        });
+    }
+
+    game.opening = white.opening();
+    game
+}
+
+/// Checks if the current position occurred three times with the same side
+/// to move.
+fn is_threefold_repetition(game: &GameState) -> bool {
+    let keys = game.position_keys();
+    let current = keys[keys.len() - 1];
+
+    keys.iter()
+        .rev()
+        .step_by(2)
+        .filter(|&&key| key == current)
+        .count()
+        >= 3
+}
@@ -0,0 +1,232 @@
+//! Tactical test suites.
+//!
+//! A test suite is an EPD file of positions with the move to find (`bm`)
+//! or to avoid (`am`). Each position is searched for a fixed time and the
+//! positions solved are counted, a measure of tactical strength that can be
+//! tracked from release to release with the summary of the run.
+//!
+//! With the `testsuites` feature, Win At Chess (WAC, its first 100
+//! positions) and Bratko-Kopec (BK, 24 positions) are embedded in the
+//! engine. Any other EPD file can be run as well.
+
+use std::time::Duration;
+
+use crate::game_state::GameState;
+use crate::game_state::analysis::AnalysisConfig;
+
+/// Time given to each position by default.
+pub const DEFAULT_SUITE_MOVETIME: Duration = Duration::from_millis(1000);
+
+/// Win At Chess, by Fred Reinfeld.
+#[cfg(feature = "testsuites")]
+const WAC: &str = include_str!("test_suites/wac.epd");
+
+/// Bratko-Kopec test, by Ivan Bratko and Danny Kopec.
+#[cfg(feature = "testsuites")]
+const BK: &str = include_str!("test_suites/bk.epd");
+
+/// A position of a test suite.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpdPosition {
+    /// Identifier of the position, its line number when the EPD has no `id`
+    pub id: String,
+    /// Position as a FEN, with the move counters the EPD leaves out
+    pub fen: String,
+    /// Moves solving the position, in SAN
+    pub best_moves: Vec<String>,
+    /// Moves failing the position, in SAN
+    pub avoid_moves: Vec<String>,
+}
+
+/// Outcome of a position of a suite.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuiteResult {
+    /// Identifier of the position
+    pub id: String,
+    /// Move played by the engine in SAN, None if the FEN was rejected or
+    /// there is no legal move
+    pub played: Option<String>,
+    /// Whether the move is one of the best moves and none of the moves to
+    /// avoid
+    pub solved: bool,
+}
+
+/// Gets the EPD text of an embedded suite.
+///
+/// # Arguments
+///
+/// * `name` - `wac` or `bk`, ignoring case
+///
+/// # Returns
+///
+/// The EPD text, or None for an unknown suite or without the
+/// `testsuites` feature
+pub fn embedded_suite(name: &str) -> Option<&'static str> {
+    #[cfg(feature = "testsuites")]
+    match name.to_ascii_lowercase().as_str() {
+        "wac" => return Some(WAC),
+        "bk" => return Some(BK),
+        _ => {}
+    }
+
+    let _ = name;
+    None
+}
+
+/// Parses the positions of an EPD file.
+///
+/// Lines that are empty, start with `#` or have fewer than the four
+/// position fields are skipped. Only the `bm`, `am` and `id` operations
+/// are read.
+///
+/// # Arguments
+///
+/// * `text` - Content of the EPD file
+///
+/// # Returns
+///
+/// The positions in file order
+pub fn parse_epd(text: &str) -> Vec<EpdPosition> {
+    let mut positions = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.splitn(5, ' ').collect();
+        if fields.len() < 4 {
+            continue;
+        }
+
+        let mut position = EpdPosition {
+            id: (index + 1).to_string(),
+            fen: format!("{} 0 1", fields[..4].join(" ")),
+            best_moves: Vec::new(),
+            avoid_moves: Vec::new(),
+        };
+
+        let operations = fields.get(4).copied().unwrap_or("");
+        for operation in operations.split(';').map(str::trim) {
+            let (opcode, operands) = operation.split_once(' ').unwrap_or((operation, ""));
+            let moves = || operands.split_whitespace().map(str::to_string).collect();
+            match opcode {
+                "bm" => position.best_moves = moves(),
+                "am" => position.avoid_moves = moves(),
+                "id" => position.id = operands.trim().trim_matches('"').to_string(),
+                _ => {}
+            }
+        }
+        positions.push(position);
+    }
+    positions
+}
+
+/// Searches the positions of a suite and checks the moves played.
+///
+/// The positions are searched `config.threads` at a time, and `progress`
+/// is called with each result as soon as its batch is done.
+///
+/// # Arguments
+///
+/// * `game` - Game whose board settings are used for the searches
+/// * `positions` - Positions of the suite
+/// * `config` - Search limits and number of threads
+/// * `progress` - Called with the index and result of each position
+///
+/// # Returns
+///
+/// One result per position, in the same order
+pub fn run_suite(
+    game: &GameState,
+    positions: &[EpdPosition],
+    config: &AnalysisConfig,
+    mut progress: impl FnMut(usize, &SuiteResult),
+) -> Vec<SuiteResult> {
+    let mut results = Vec::with_capacity(positions.len());
+
+    for batch in positions.chunks(config.threads.max(1)) {
+        let fens: Vec<&str> = batch.iter().map(|position| position.fen.as_str()).collect();
+        let analyses = game.analyze_batch(&fens, config);
+
+        for (position, analysis) in batch.iter().zip(analyses) {
+            let result = check_move(position, analysis.best_move.as_deref());
+            progress(results.len(), &result);
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// Checks the move played in a position against its best moves and moves
+/// to avoid.
+fn check_move(position: &EpdPosition, played: Option<&str>) -> SuiteResult {
+    let mut game = GameState::new(None);
+    let record = played
+        .filter(|_| game.set_fen_position(&position.fen))
+        .and_then(|uci| {
+            game.legal_moves()
+                .into_iter()
+                .find(|record| record.uci == uci)
+        });
+
+    let Some(record) = record else {
+        return SuiteResult {
+            id: position.id.clone(),
+            played: None,
+            solved: false,
+        };
+    };
+
+    // The suite's SAN is parsed rather than compared, so check marks and
+    // the way a move is disambiguated don't matter
+    let mut matches = |moves: &[String]| {
+        moves.iter().any(|san| {
+            game.create_move_from_san(san)
+                .is_some_and(|mv| game.get_chess_board().move_to_uci(&mv) == record.uci)
+        })
+    };
+    let solved = (position.best_moves.is_empty() || matches(&position.best_moves))
+        && !matches(&position.avoid_moves);
+
+    SuiteResult {
+        id: position.id.clone(),
+        played: Some(record.san),
+        solved,
+    }
+}
+
+/// Writes the summary of a suite run as a single-line JSON object.
+///
+/// # Arguments
+///
+/// * `suite` - Name of the suite
+/// * `results` - Results of the run
+/// * `config` - Search limits the suite was run with
+pub fn summary_json(suite: &str, results: &[SuiteResult], config: &AnalysisConfig) -> String {
+    let solved = results.iter().filter(|result| result.solved).count();
+    let unsolved: Vec<String> = results
+        .iter()
+        .filter(|result| !result.solved)
+        .map(|result| format!("\"{}\"", escape_json(&result.id)))
+        .collect();
+    let movetime = config.movetime.map_or("null".to_string(), |movetime| {
+        movetime.as_millis().to_string()
+    });
+
+    format!(
+        "{{\"suite\":\"{}\",\"version\":\"{}\",\"movetime\":{},\"depth\":{},\"positions\":{},\"solved\":{},\"unsolved\":[{}]}}",
+        escape_json(suite),
+        env!("CARGO_PKG_VERSION"),
+        movetime,
+        config.depth,
+        results.len(),
+        solved,
+        unsolved.join(",")
+    )
+}
+
+fn escape_json(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
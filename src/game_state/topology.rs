@@ -0,0 +1,87 @@
+//! Detection of the CPU topology, for the automatic thread count.
+//!
+//! `Threads value 0`, and a thread count of 0 on the command line, stand for
+//! one search thread per physical core: the hyperthreads of a core share
+//! its execution units, so a second search thread on the same core adds
+//! little speed and splits the caches.
+//!
+//! The cores are counted from `/proc/cpuinfo` on Linux. Elsewhere, or when
+//! the file doesn't describe the cores, the logical CPUs are counted
+//! instead. Either count is capped by the CPUs the process may run on, as
+//! set by its affinity mask or the quota of its container.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+/// Gets the number of physical cores the engine can use, at least 1.
+pub fn physical_cores() -> usize {
+    let logical = logical_cpus();
+
+    #[cfg(target_os = "linux")]
+    if let Some(cores) = std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|cpuinfo| count_physical_cores(&cpuinfo))
+    {
+        return cores.min(logical);
+    }
+
+    logical
+}
+
+/// Gets the number of logical CPUs the process may run on, at least 1.
+pub fn logical_cpus() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Counts the physical cores described by the contents of `/proc/cpuinfo`.
+///
+/// Each logical CPU has a block of `key : value` lines, and the CPUs of a
+/// core share their `physical id` (the socket) and `core id`.
+///
+/// # Arguments
+///
+/// * `cpuinfo` - Contents of the file
+///
+/// # Returns
+///
+/// The number of distinct cores, None if the blocks have no core ids, as
+/// on some ARM machines
+pub fn count_physical_cores(cpuinfo: &str) -> Option<usize> {
+    let mut cores = Vec::new();
+
+    for block in cpuinfo.split("\n\n") {
+        let field = |name: &str| {
+            block.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == name).then(|| value.trim())
+            })
+        };
+
+        if let Some(core) = field("core id") {
+            let core = (field("physical id").unwrap_or("0"), core);
+            if !cores.contains(&core) {
+                cores.push(core);
+            }
+        }
+    }
+
+    (!cores.is_empty()).then_some(cores.len())
+}
+
+/// Resolves a thread count, where 0 stands for one thread per physical
+/// core.
+///
+/// # Arguments
+///
+/// * `threads` - Thread count asked for
+///
+/// # Returns
+///
+/// The thread count to use, at least 1
+pub fn resolve_threads(threads: usize) -> usize {
+    if threads == 0 {
+        physical_cores()
+    } else {
+        threads
+    }
+}
@@ -4,33 +4,353 @@
 //! chess GUIs and other UCI-compatible interfaces. It handles command
 //! parsing, position setup, search initiation, and response formatting.
 
+use std::path::Path;
+use std::str::FromStr;
 use std::str::SplitWhitespace;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
 
+use crate::game_state::DEFAULT_MAX_DEPTH;
+use crate::game_state::DEFAULT_MOVE_OVERHEAD;
+use crate::game_state::DEFAULT_VARIETY_MOVES;
 use crate::game_state::GameState;
+use crate::game_state::Move;
 use crate::game_state::SearchConfiguration;
+use crate::game_state::board::evaluation::EvaluationTerms;
+use crate::game_state::board::evaluation::nnue::DEFAULT_EVAL_FILE;
+use crate::game_state::board::rules;
+#[cfg(feature = "tune")]
+use crate::game_state::board::search::params::{SearchParams, TUNABLE_PARAMS};
+use crate::game_state::board::search::trace::DEFAULT_TRACE_DEPTH;
+use crate::game_state::experience::DEFAULT_EXPERIENCE_FILE;
+use crate::game_state::options::DEFAULT_HASH_MB;
+use crate::game_state::personality::Personality;
+use crate::game_state::topology;
+use crate::game_state::version;
 
 /// Handles the `uci` command by identifying the engine.
 ///
 /// Responds with engine name and author information as required by the UCI protocol.
 /// This is typically the first command sent by a GUI to initialize communication.
+/// The name carries the version, commit and build date of the binary.
 pub fn handle_uci_command() {
-    println!("id name EnRust");
+    println!("id name {}", version::engine_name());
     println!("id author Mikael Ferraz Aldebrand");
-    println!("option name Threads type spin default 1 min 1 max 1");
-    println!("option name Hash type spin default 256 min 1 max 2048");
+    println!(
+        "option name Threads type spin default 1 min 0 max {}",
+        MAX_THREADS
+    );
+    println!("option name ThreadAffinity type check default false");
+    println!(
+        "option name Hash type spin default {} min 1 max 2048",
+        DEFAULT_HASH_MB
+    );
+    println!("option name Clear Hash type button");
+    println!("option name SaveHashFile type string default <empty>");
+    println!("option name LoadHashFile type string default <empty>");
+    println!("option name Ponder type check default false");
+    println!("option name PonderTimeCounts type check default true");
+    println!("option name InstantMoves type check default true");
+    println!("option name QuiescenceChecks type check default false");
+    println!("option name ResumeAnalysis type check default true");
+    println!("option name PermanentBrain type check default false");
+    println!(
+        "option name TraceDepth type spin default {} min 1 max 16",
+        DEFAULT_TRACE_DEPTH
+    );
+    println!(
+        "option name Move Overhead type spin default {} min 0 max 5000",
+        DEFAULT_MOVE_OVERHEAD
+    );
+    println!(
+        "option name ExperienceFile type string default {}",
+        DEFAULT_EXPERIENCE_FILE
+    );
+    println!(
+        "option name NodesPerMove type spin default 0 min 0 max {}",
+        MAX_NODES_PER_MOVE
+    );
+    println!(
+        "option name MaxDepth type spin default {} min 1 max {}",
+        DEFAULT_MAX_DEPTH, MAX_DEPTH
+    );
+    println!("option name Variety type spin default 0 min 0 max 100");
+    println!(
+        "option name VarietyMoves type spin default {} min 1 max 100",
+        DEFAULT_VARIETY_MOVES
+    );
+    println!("option name LearningEnabled type check default false");
+    println!("option name UseNNUE type check default false");
+    println!(
+        "option name EvalFile type string default {}",
+        DEFAULT_EVAL_FILE
+    );
+    println!("option name EvalParamsFile type string default <empty>");
+    let personalities: Vec<String> = Personality::ALL
+        .iter()
+        .map(|personality| format!("var {}", personality.name()))
+        .collect();
+    println!(
+        "option name Personality type combo default {} {}",
+        Personality::default().name(),
+        personalities.join(" ")
+    );
+    println!("option name Space type check default true");
+    println!("option name CenterControl type check default true");
+    println!("option name KingTropism type check default true");
+    println!("option name WeakSquares type check default true");
+    println!("option name UCI_Chess960 type check default false");
+    // Only offered when a variant is compiled in besides standard chess,
+    // which comes first
+    let variants = rules::variants();
+    if variants.len() > 1 {
+        let names: Vec<String> = variants
+            .iter()
+            .map(|variant| format!("var {}", variant.name()))
+            .collect();
+        println!(
+            "option name UCI_Variant type combo default {} {}",
+            variants[0].name(),
+            names.join(" ")
+        );
+    }
+    #[cfg(feature = "tune")]
+    for param in TUNABLE_PARAMS {
+        let default = SearchParams::default().get(param.name).unwrap_or_default();
+        println!(
+            "option name {} type spin default {} min {} max {}",
+            param.name, default, param.min, param.max
+        );
+    }
     println!("uciok");
 }
 
+/// Handles the `position` command to set up the board.
+///
+/// Accepts `startpos` or `fen <fen>` followed by an optional `moves` list.
+/// The FEN may omit its trailing fields, and an empty move list is fine.
+/// The `current` extension, sent by some tools, plays the moves from the
+/// position the engine is already in instead of setting up a new one.
+/// Problems are reported with `info string` instead of being ignored: an
+/// invalid FEN leaves the previous position in place, and move application
+/// stops at the first illegal move.
+///
+/// # Arguments
+///
+/// * `game_state` - Game state to set up
+/// * `tokens` - Command tokens following the "position" keyword
+pub fn handle_position_command(game_state: &mut GameState, tokens: &mut SplitWhitespace) {
+    match tokens.next() {
+        Some(position @ ("startpos" | "current")) => {
+            if position == "startpos" {
+                game_state.start_position();
+            }
+            if let Some(token) = tokens.next()
+                && token != "moves"
+            {
+                report_error(format!(
+                    "expected 'moves' in position command but found '{}'",
+                    token
+                ));
+                return;
+            }
+        }
+        Some("fen") => {
+            let fen: Vec<&str> = tokens.by_ref().take_while(|&t| t != "moves").collect();
+            if fen.is_empty() {
+                report_error("missing FEN in position command");
+                return;
+            }
+
+            let fen = fen.join(" ");
+            if !game_state.set_fen_position(&fen) {
+                report_error(format!("invalid FEN in position command: '{}'", fen));
+                return;
+            }
+        }
+        Some(token) => {
+            report_error(format!("unknown position type: '{}'", token));
+            return;
+        }
+        None => {
+            report_error("missing position type, expected 'startpos', 'fen' or 'current'");
+            return;
+        }
+    }
+
+    for mv in tokens {
+        if !game_state.is_legal_move(mv) {
+            report_error(format!("illegal move in position command: '{}'", mv));
+            return;
+        }
+        game_state.make_move(mv);
+    }
+}
+
+/// Largest value of the `Threads` option, the search runs on one thread.
+pub const MAX_THREADS: usize = 1;
+
+/// Largest value of the `NodesPerMove` option.
+const MAX_NODES_PER_MOVE: u64 = 100_000_000;
+
+/// Largest value of the `MaxDepth` option.
+const MAX_DEPTH: u8 = 64;
+
+/// Parameters of the `go` command, which end the move list of `searchmoves`
+/// and can't be the value of another parameter.
+const GO_PARAMETERS: [&str; 14] = [
+    "wtime",
+    "btime",
+    "winc",
+    "binc",
+    "movestogo",
+    "depth",
+    "nodes",
+    "movetime",
+    "infinite",
+    "searchmoves",
+    "avoidmoves",
+    "ponder",
+    "mate",
+    "perft",
+];
+
+/// What a `go` command asks for.
+pub enum GoCommand {
+    /// Search with the configuration
+    Search(SearchConfiguration),
+    /// Count the leaf nodes to a depth, a debugging tool
+    Perft(u64),
+}
+
+/// Reports a problem with a command, as `info string error: <message>`.
+///
+/// # Arguments
+///
+/// * `message` - What failed, quoting the offending token
+pub(crate) fn report_error(message: impl std::fmt::Display) {
+    println!("info string error: {}", message);
+}
+
+/// Handles the `tt` console command, not part of UCI, which saves the
+/// transposition table to a file or loads it back: `tt save <path>` or
+/// `tt load <path>`.
+///
+/// # Arguments
+///
+/// * `game_state` - Game whose transposition table is saved or loaded
+/// * `tokens` - Command tokens following the "tt" keyword
+pub fn handle_tt_command(game_state: &GameState, tokens: &mut SplitWhitespace) {
+    let action = tokens.next();
+    let path = tokens.collect::<Vec<&str>>().join(" ");
+
+    match action {
+        Some("save") => save_hash_file(game_state, &path),
+        Some("load") => load_hash_file(game_state, &path),
+        _ => report_error("expected 'tt save <path>' or 'tt load <path>'"),
+    }
+}
+
+/// Saves the transposition table for the `SaveHashFile` option and the `tt
+/// save` command.
+fn save_hash_file(game_state: &GameState, path: &str) {
+    if matches!(path, "" | "<empty>") {
+        report_error("missing hash file path");
+        return;
+    }
+    match game_state.save_hash_file(Path::new(path)) {
+        Ok(entries) => println!("info string Saved {} hash entries to {}", entries, path),
+        Err(err) => report_error(format!("could not save hash file: {}", err)),
+    }
+}
+
+/// Loads the transposition table for the `LoadHashFile` option and the `tt
+/// load` command.
+fn load_hash_file(game_state: &GameState, path: &str) {
+    if matches!(path, "" | "<empty>") {
+        report_error("missing hash file path");
+        return;
+    }
+    match game_state.load_hash_file(Path::new(path)) {
+        Ok(entries) => println!("info string Loaded {} hash entries from {}", entries, path),
+        Err(err) => report_error(format!("could not load hash file: {}", err)),
+    }
+}
+
 /// Handles the `go` command to start a search with specified parameters.
 ///
 /// Parses UCI search parameters and initiates the search process. Supports
-/// all standard UCI time controls, depth limits, and search modes.
+/// all standard UCI time controls, depth limits, and search modes. Invalid
+/// parameters are reported and the search starts with the valid ones.
 ///
 /// # Arguments
 ///
 /// * `game_state` - Current game state and position
 /// * `tokens` - Command tokens following the "go" keyword
 ///
+/// # Returns
+///
+/// For `go infinite` and `go ponder`, the sender that releases the
+/// `bestmove` answer, signaled by `stop` or `ponderhit`. Dropping it
+/// releases the answer too.
+pub fn handle_go_command(
+    game_state: &mut GameState,
+    tokens: &mut SplitWhitespace,
+) -> Option<Sender<()>> {
+    let (command, errors) = parse_go_command(game_state, tokens);
+    for error in errors {
+        report_error(error);
+    }
+
+    let sc = match command {
+        Some(GoCommand::Search(sc)) => sc,
+        Some(GoCommand::Perft(depth)) => {
+            game_state.perft_debug(depth, true);
+            return None;
+        }
+        None => return None,
+    };
+
+    // Apply the search configuration and start the search
+    game_state.set_time_control(&sc);
+
+    // Output the best move once the search is over, without blocking the
+    // commands that may stop it. The thread then thinks on the opponent's
+    // time if the permanent brain is on
+    let search = game_state.search();
+    let brain = game_state.permanent_brain();
+    let waits_for_release = sc.infinite || sc.ponder;
+    let (release, released) = mpsc::channel();
+    thread::spawn(move || {
+        let result = search.join();
+        // `go infinite` and `go ponder` only answer after `stop` or
+        // `ponderhit`, even when they end on their own. A disconnected
+        // sender releases the answer as well
+        if waits_for_release {
+            let _ = released.recv();
+        }
+        println!("bestmove {}", result.best_move.as_deref().unwrap_or("0000"));
+        if let Some(brain) = brain
+            && let Some(best_move) = &result.best_move
+        {
+            brain.think(best_move, result.ponder_move.as_deref());
+        }
+    });
+
+    waits_for_release.then_some(release)
+}
+
+/// Parses the parameters of the `go` command.
+///
+/// A parameter with a missing or invalid value is left out and the parsing
+/// goes on with the next one, so a single bad token doesn't lose the whole
+/// command. Unknown parameters are skipped the same way.
+///
+/// # Arguments
+///
+/// * `game_state` - Current game state, to check the `searchmoves` moves
+/// * `tokens` - Command tokens following the "go" keyword
+///
 /// # Supported Parameters
 ///
 /// - `wtime`, `btime`: Time remaining for white/black
@@ -40,125 +360,134 @@ pub fn handle_uci_command() {
 /// - `movetime`: Fixed time for this move
 /// - `infinite`: Search until stopped
 /// - `searchmoves`: Restrict search to specific moves
+/// - `avoidmoves`: Leave specific moves out of the search, not part of UCI
 /// - `ponder`: Enable pondering mode
 /// - `mate`: Search for mate in N moves
 /// - `perft`: Debugging tool for move generation testing
-pub fn handle_go_command(game_state: &mut GameState, tokens: &mut SplitWhitespace) {
+///
+/// # Returns
+///
+/// The command, None if a `perft` has no valid depth, and one error
+/// message per rejected token
+pub fn parse_go_command(
+    game_state: &mut GameState,
+    tokens: &mut SplitWhitespace,
+) -> (Option<GoCommand>, Vec<String>) {
     let mut sc = SearchConfiguration::new();
+    let mut errors = Vec::new();
 
     // Parse all search parameters following the "go" command
     while let Some(token) = tokens.next() {
         match token {
-            "wtime" => {
-                if let Some(wtime_str) = tokens.next()
-                    && let Ok(wtime) = wtime_str.parse::<u64>()
-                {
-                    sc.wtime = Some(wtime);
-                }
-            }
-            "btime" => {
-                if let Some(btime_str) = tokens.next()
-                    && let Ok(btime) = btime_str.parse::<u64>()
-                {
-                    sc.btime = Some(btime);
-                }
-            }
-            "winc" => {
-                if let Some(winc_str) = tokens.next()
-                    && let Ok(winc) = winc_str.parse::<u64>()
-                {
-                    sc.winc = Some(winc);
-                }
-            }
-            "binc" => {
-                if let Some(binc_str) = tokens.next()
-                    && let Ok(binc) = binc_str.parse::<u64>()
-                {
-                    sc.binc = Some(binc);
-                }
-            }
+            "wtime" => sc.wtime = parse_go_value(token, tokens, &mut errors).or(sc.wtime),
+            "btime" => sc.btime = parse_go_value(token, tokens, &mut errors).or(sc.btime),
+            "winc" => sc.winc = parse_go_value(token, tokens, &mut errors).or(sc.winc),
+            "binc" => sc.binc = parse_go_value(token, tokens, &mut errors).or(sc.binc),
             "movestogo" => {
-                if let Some(movestogo_str) = tokens.next()
-                    && let Ok(movestogo) = movestogo_str.parse::<u64>()
-                {
-                    sc.movestogo = Some(movestogo);
-                }
-            }
-            "depth" => {
-                if let Some(depth_str) = tokens.next()
-                    && let Ok(depth) = depth_str.parse::<u64>()
-                {
-                    sc.depth = Some(depth);
-                }
-            }
-            "nodes" => {
-                if let Some(nodes_str) = tokens.next()
-                    && let Ok(nodes) = nodes_str.parse::<u64>()
-                {
-                    sc.nodes = Some(nodes);
-                }
-            }
-            "movetime" => {
-                if let Some(movetime_str) = tokens.next()
-                    && let Ok(movetime) = movetime_str.parse::<u64>()
-                {
-                    sc.movetime = Some(movetime);
-                }
+                sc.movestogo = parse_go_value(token, tokens, &mut errors).or(sc.movestogo)
             }
+            "depth" => sc.depth = parse_go_value(token, tokens, &mut errors).or(sc.depth),
+            "nodes" => sc.nodes = parse_go_value(token, tokens, &mut errors).or(sc.nodes),
+            "movetime" => sc.movetime = parse_go_value(token, tokens, &mut errors).or(sc.movetime),
             "infinite" => sc.infinite = true,
 
             "searchmoves" => {
-                let mut moves = Vec::new();
-                // Parse moves until encountering a non-move token
-                while let Some(mv) = tokens.clone().next() {
-                    if let Some(parsed) = game_state.create_move(mv) {
-                        moves.push(parsed);
-                        tokens.next(); // advance to next token
-                    } else {
-                        break; // stop at non-move token
-                    }
+                let moves = parse_go_moves(game_state, token, tokens, &mut errors);
+                if moves.is_empty() {
+                    errors.push("no legal move in go searchmoves, searching all moves".to_string());
+                } else {
+                    sc.searchmoves = Some(moves);
                 }
-                sc.searchmoves = Some(moves);
+            }
+
+            // Not a standard UCI parameter: leaves moves out of the search
+            "avoidmoves" => {
+                let moves = parse_go_moves(game_state, token, tokens, &mut errors);
+                sc.avoidmoves.extend(moves);
             }
 
             "ponder" => {
                 sc.ponder = true;
             }
 
-            "mate" => {
-                if let Some(n) = tokens.next().and_then(|t| t.parse::<u32>().ok()) {
-                    sc.mate = Some(n);
-                }
-            }
+            "mate" => sc.mate = parse_go_value(token, tokens, &mut errors).or(sc.mate),
 
             // Not a standard UCI command, but a some debugging tools need this to test the engine
             "perft" => {
-                if let Some(depth_str) = tokens.next()
-                    && let Ok(depth) = depth_str.parse::<u64>()
-                {
-                    game_state.perft_debug(depth, true);
-                }
-                return; // Early return for debugging command
+                let depth = parse_go_value(token, tokens, &mut errors);
+                return (depth.map(GoCommand::Perft), errors);
             }
 
-            _ => {
-                // Ignore unrecognized parameters (UCI protocol requires this)
-            }
+            // Unknown parameters are skipped, as the UCI protocol requires
+            _ => errors.push(format!("unknown go parameter: '{}'", token)),
         }
     }
 
-    // Apply the search configuration and start the search
-    game_state.set_time_control(&sc);
+    (Some(GoCommand::Search(sc)), errors)
+}
+
+/// Parses the moves following `searchmoves` or `avoidmoves`, which run
+/// until the next parameter of the `go` command.
+///
+/// # Returns
+///
+/// The legal moves, after adding an error message to `errors` for each
+/// other one
+fn parse_go_moves(
+    game_state: &mut GameState,
+    parameter: &str,
+    tokens: &mut SplitWhitespace,
+    errors: &mut Vec<String>,
+) -> Vec<Move> {
+    let mut moves = Vec::new();
+    while let Some(mv) = tokens.clone().next()
+        && !GO_PARAMETERS.contains(&mv)
+    {
+        tokens.next();
+        match game_state.create_move(mv) {
+            Some(parsed) if game_state.is_legal_move(mv) => moves.push(parsed),
+            _ => errors.push(format!("illegal move in go {}: '{}'", parameter, mv)),
+        }
+    }
+    moves
+}
 
-    // Output the best move found by the search
-    //println!("bestmove {}", game_state.search());
-    game_state.search();
+/// Parses the value following a parameter of the `go` command.
+///
+/// A following parameter name is not taken as the value, so `go wtime
+/// btime 1000` still reads `btime`.
+///
+/// # Returns
+///
+/// The value, or None after adding an error message to `errors`
+fn parse_go_value<T: FromStr>(
+    parameter: &str,
+    tokens: &mut SplitWhitespace,
+    errors: &mut Vec<String>,
+) -> Option<T> {
+    match tokens.clone().next() {
+        Some(value) if !GO_PARAMETERS.contains(&value) => {
+            tokens.next();
+            let parsed = value.parse().ok();
+            if parsed.is_none() {
+                errors.push(format!(
+                    "invalid value for go {}: '{}' is not a non-negative integer",
+                    parameter, value
+                ));
+            }
+            parsed
+        }
+        _ => {
+            errors.push(format!("missing value for go {}", parameter));
+            None
+        }
+    }
 }
 
 pub fn handle_setoption_command(game_state: &mut GameState, tokens: &mut SplitWhitespace) {
     // Expect "name" token
     if tokens.next() != Some("name") {
-        println!("info string Missing 'name' in setoption command");
+        report_error("missing 'name' in setoption command");
         return;
     }
 
@@ -186,21 +515,184 @@ pub fn handle_setoption_command(game_state: &mut GameState, tokens: &mut SplitWh
                         // Reasonable limits
                         game_state.resize_hash_table(hash_size);
                     } else {
-                        println!(
-                            "info string Hash size {} MB out of range (1-1024)",
-                            hash_size
-                        );
+                        report_error(format!("Hash size {} MB out of range (1-2048)", hash_size));
                     }
                 } else {
-                    println!("info string Invalid Hash value: '{}'", value);
+                    report_error(format!("invalid Hash value: \'{}\'", value));
+                }
+            }
+            "Clear Hash" => game_state.clear_hash_table(),
+            // 0 stands for one thread per physical core
+            "Threads" => match value.parse::<usize>() {
+                Ok(0) => game_state
+                    .options()
+                    .set_threads(topology::physical_cores().min(MAX_THREADS)),
+                Ok(threads) if threads <= MAX_THREADS => game_state.options().set_threads(threads),
+                _ => report_error(format!("invalid Threads value: '{}'", value)),
+            },
+            "ThreadAffinity" => match value.as_str() {
+                "true" | "false" => game_state.set_thread_affinity(value == "true"),
+                _ => report_error(format!("invalid ThreadAffinity value: '{}'", value)),
+            },
+            "SaveHashFile" => save_hash_file(game_state, &value),
+            "LoadHashFile" => load_hash_file(game_state, &value),
+            // The GUI decides when to send `go ponder`, the option only
+            // replaces the search on the opponent's time
+            "Ponder" => match value.as_str() {
+                "true" | "false" => game_state.set_ponder(value == "true"),
+                _ => report_error(format!("invalid Ponder value: '{}'", value)),
+            },
+            "PonderTimeCounts" => match value.as_str() {
+                "true" | "false" => game_state.set_ponder_time_counts(value == "true"),
+                _ => report_error(format!("invalid PonderTimeCounts value: \'{}\'", value)),
+            },
+            "InstantMoves" => match value.as_str() {
+                "true" | "false" => game_state.set_instant_moves(value == "true"),
+                _ => report_error(format!("invalid InstantMoves value: '{}'", value)),
+            },
+            "QuiescenceChecks" => match value.as_str() {
+                "true" | "false" => game_state.set_quiescence_checks(value == "true"),
+                _ => report_error(format!("invalid QuiescenceChecks value: '{}'", value)),
+            },
+            "ResumeAnalysis" => match value.as_str() {
+                "true" | "false" => game_state.set_resume_analysis(value == "true"),
+                _ => report_error(format!("invalid ResumeAnalysis value: '{}'", value)),
+            },
+            "PermanentBrain" => match value.as_str() {
+                "true" | "false" => game_state.set_permanent_brain(value == "true"),
+                _ => report_error(format!("invalid PermanentBrain value: '{}'", value)),
+            },
+            "TraceDepth" => match value.parse::<usize>() {
+                Ok(depth) if (1..=16).contains(&depth) => game_state.set_trace_depth(depth),
+                _ => report_error(format!("invalid TraceDepth value: \'{}\'", value)),
+            },
+            "Move Overhead" => match value.parse::<u64>() {
+                Ok(overhead) if overhead <= 5000 => game_state.set_move_overhead(overhead),
+                _ => report_error(format!("invalid Move Overhead value: \'{}\'", value)),
+            },
+            // Zero removes the cap
+            "NodesPerMove" => match value.parse::<u64>() {
+                Ok(nodes) if nodes <= MAX_NODES_PER_MOVE => {
+                    game_state.set_nodes_per_move((nodes > 0).then_some(nodes))
+                }
+                _ => report_error(format!("invalid NodesPerMove value: '{}'", value)),
+            },
+            "MaxDepth" => match value.parse::<u8>() {
+                Ok(depth) if (1..=MAX_DEPTH).contains(&depth) => game_state.set_max_depth(depth),
+                _ => report_error(format!("invalid MaxDepth value: '{}'", value)),
+            },
+            "Variety" => match value.parse::<i32>() {
+                Ok(margin) if (0..=100).contains(&margin) => {
+                    let (_, moves) = game_state.variety();
+                    game_state.set_variety(margin, moves);
+                }
+                _ => report_error(format!("invalid Variety value: \'{}\'", value)),
+            },
+            "VarietyMoves" => match value.parse::<u64>() {
+                Ok(moves) if (1..=100).contains(&moves) => {
+                    let (margin, _) = game_state.variety();
+                    game_state.set_variety(margin.centipawns(), moves);
+                }
+                _ => report_error(format!("invalid VarietyMoves value: \'{}\'", value)),
+            },
+            "ExperienceFile" => {
+                if value.is_empty() {
+                    report_error("missing ExperienceFile value");
+                } else if let Err(err) = game_state.set_experience_file(Path::new(&value)) {
+                    report_error(format!("could not load experience file: {}", err));
+                }
+            }
+            "LearningEnabled" => match value.as_str() {
+                "true" | "false" => {
+                    if let Err(err) = game_state.set_learning_enabled(value == "true") {
+                        report_error(format!("could not load experience file: {}", err));
+                    }
+                }
+                _ => report_error(format!("invalid LearningEnabled value: \'{}\'", value)),
+            },
+            "UseNNUE" => match value.as_str() {
+                "true" | "false" => {
+                    if let Err(err) = game_state.set_use_nnue(value == "true") {
+                        report_error(format!(
+                            "could not load network: {}, using handcrafted evaluation",
+                            err
+                        ));
+                    }
+                }
+                _ => report_error(format!("invalid UseNNUE value: \'{}\'", value)),
+            },
+            "EvalFile" => {
+                if value.is_empty() {
+                    report_error("missing EvalFile value");
+                } else if let Err(err) = game_state.set_eval_file(Path::new(&value)) {
+                    report_error(format!(
+                        "could not load network: {}, using handcrafted evaluation",
+                        err
+                    ));
+                }
+            }
+            // An empty value goes back to the built-in weights
+            "EvalParamsFile" => {
+                let path = match value.as_str() {
+                    "" | "<empty>" => None,
+                    path => Some(Path::new(path)),
+                };
+                if let Err(err) = game_state.set_eval_params_file(path) {
+                    report_error(format!("could not load evaluation parameters: {}", err));
+                }
+            }
+            "Personality" => match Personality::from_name(&value) {
+                Some(personality) => game_state.set_personality(personality),
+                None => report_error(format!("unknown Personality value: '{}'", value)),
+            },
+            "Space" => match value.as_str() {
+                "true" | "false" => game_state.set_evaluation_terms(EvaluationTerms {
+                    space: value == "true",
+                    ..game_state.evaluation_terms()
+                }),
+                _ => report_error(format!("invalid Space value: \'{}\'", value)),
+            },
+            "CenterControl" => match value.as_str() {
+                "true" | "false" => game_state.set_evaluation_terms(EvaluationTerms {
+                    center_control: value == "true",
+                    ..game_state.evaluation_terms()
+                }),
+                _ => report_error(format!("invalid CenterControl value: \'{}\'", value)),
+            },
+            "KingTropism" => match value.as_str() {
+                "true" | "false" => game_state.set_evaluation_terms(EvaluationTerms {
+                    king_tropism: value == "true",
+                    ..game_state.evaluation_terms()
+                }),
+                _ => report_error(format!("invalid KingTropism value: \'{}\'", value)),
+            },
+            "WeakSquares" => match value.as_str() {
+                "true" | "false" => game_state.set_evaluation_terms(EvaluationTerms {
+                    weak_squares: value == "true",
+                    ..game_state.evaluation_terms()
+                }),
+                _ => report_error(format!("invalid WeakSquares value: \'{}\'", value)),
+            },
+            "UCI_Chess960" => match value.as_str() {
+                "true" | "false" => game_state.set_chess960(value == "true"),
+                _ => report_error(format!("invalid UCI_Chess960 value: \'{}\'", value)),
+            },
+            "UCI_Variant" => {
+                if !game_state.set_variant(&value) {
+                    report_error(format!("unsupported UCI_Variant value: '{}'", value));
                 }
             }
+            #[cfg(feature = "tune")]
+            name if SearchParams::default().get(name).is_some() => match value.parse::<i32>() {
+                Ok(param) if game_state.set_search_param(name, param) => {}
+                _ => report_error(format!("invalid {} value: '{}'", name, value)),
+            },
             _ => {
-                // Ignore unsupported options
-                println!("info string Unsupported option: '{}'", option_name);
+                // Unsupported options are ignored
+                report_error(format!("unsupported option: '{}'", option_name));
             }
         }
     } else {
-        println!("info string Missing option name in setoption command");
+        report_error("missing option name in setoption command");
     }
 }
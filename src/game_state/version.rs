@@ -0,0 +1,82 @@
+//! Identification of the engine binary.
+//!
+//! The name reported to GUIs carries the crate version, the commit and the
+//! date of the build, and `enrust --version` adds the optional features the
+//! binary was compiled with, so a bug report tells exactly which binary it
+//! is about. The commit and the date are recorded by the build script.
+
+use crate::game_state::uci::MAX_THREADS;
+
+/// Version of the crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short hash of the commit the engine was built from, `unknown` outside
+/// of a git checkout.
+pub const GIT_HASH: &str = env!("ENRUST_GIT_HASH");
+
+/// Date of the build, as `YYYY-MM-DD`.
+pub const BUILD_DATE: &str = env!("ENRUST_BUILD_DATE");
+
+/// Gets the name of the engine, as sent with `id name`.
+///
+/// # Returns
+///
+/// `EnRust <version> <git hash> <build date>`
+pub fn engine_name() -> String {
+    format!("EnRust {} {} {}", VERSION, GIT_HASH, BUILD_DATE)
+}
+
+/// Gets the optional features the engine was compiled with.
+///
+/// # Returns
+///
+/// Names of the enabled Cargo features, in the order of the manifest
+pub fn build_features() -> Vec<&'static str> {
+    [
+        ("simd", cfg!(feature = "simd")),
+        ("tune", cfg!(feature = "tune")),
+        ("antichess", cfg!(feature = "antichess")),
+        ("atomic", cfg!(feature = "atomic")),
+        ("testsuites", cfg!(feature = "testsuites")),
+        ("interop", cfg!(feature = "interop")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+/// Gets the instruction set of the NNUE accumulator updates on this
+/// machine: AVX2 with the `simd` feature on a CPU supporting it, scalar
+/// code otherwise.
+pub fn nnue_backend() -> &'static str {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if is_x86_feature_detected!("avx2") {
+        return "avx2";
+    }
+
+    "scalar"
+}
+
+/// Gets the description printed by `enrust --version`.
+///
+/// # Returns
+///
+/// The engine name followed by one `<key>: <value>` line per build detail
+pub fn version_info() -> String {
+    let features = build_features();
+    let features = if features.is_empty() {
+        "none".to_string()
+    } else {
+        features.join(" ")
+    };
+
+    format!(
+        "{}\nfeatures: {}\nnnue: {}\nthreads: {}\ntarget: {}-{}\n",
+        engine_name(),
+        features,
+        nnue_backend(),
+        MAX_THREADS,
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+    )
+}
@@ -32,37 +32,73 @@
 //!
 //! ### Programmatic Usage
 //!
-//! You can use the engine programmatically:
+//! You can use the engine programmatically. Set up a position, list its
+//! legal moves, play them and export the position as FEN:
 //!
 //! ```rust
-//! use enrust::game_state::{GameState, Color, SearchConfiguration};
-//! // Create a game with a transposition table of size 256 MB
-//! let mut game_state = GameState::new(Some(256));
+//! use enrust::game_state::GameState;
+//!
+//! // Create a game with a transposition table of size 16 MB
+//! let mut game_state = GameState::with_hash_size(16);
 //! game_state.start_position();
 //!
-//! // Set up time control
+//! // Generate moves, in UCI format
+//! let moves = game_state.generate_moves();
+//! assert_eq!(moves.len(), 20);
+//! assert!(moves.contains(&"e2e4".to_string()));
+//!
+//! // Or with their SAN and properties
+//! let records = game_state.legal_moves();
+//! assert!(records.iter().any(|record| record.san == "Nf3"));
+//! assert!(records.iter().all(|record| !record.is_check));
+//!
+//! // Play a move and export the position
+//! assert!(game_state.make_move("e2e4"));
+//! assert_eq!(
+//!     game_state.to_fen(),
+//!     "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+//! );
+//! ```
+//!
+//! Evaluate a position statically, or search it for the best move. The
+//! search runs on its own thread and prints its progress as UCI `info`
+//! lines; [`join`](game_state::search_handle::SearchHandle::join) waits
+//! for the result:
+//!
+//! ```rust
+//! use enrust::game_state::{GameState, SearchConfiguration};
+//!
+//! // White's queen can take the queen left hanging on a4
+//! let mut game_state = GameState::with_hash_size(16);
+//! assert!(game_state.set_fen_position("4k3/8/8/8/q7/8/8/3QK3 w - - 0 1"));
+//!
+//! // Both sides have a queen: the static evaluation is about even
+//! assert!(game_state.evaluate_cp().abs() < 100);
+//!
+//! // Search 3 plies deep and wait for the result
 //! let mut config = SearchConfiguration::new();
-//! config.wtime = Some(60000); // 1 minute for white
-//! config.btime = Some(60000); // 1 minute for black
+//! config.depth = Some(3);
 //! game_state.set_time_control(&config);
+//! let result = game_state.search().join();
 //!
-//! // Generate moves
-//! let moves = game_state.generate_moves();
-//! println!("Available moves: {:?}", moves);
-//!
-//! // Search for best move
-//! game_state.search();
+//! assert_eq!(result.best_move.as_deref(), Some("d1a4"));
+//! assert!(result.score.centipawns() > 500);
+//! assert_eq!(result.pv.first(), result.best_move.as_ref());
 //! ```
 //!
+//! Time controls are set the same way, with `wtime`, `btime` and the other
+//! fields of [`SearchConfiguration`](game_state::SearchConfiguration).
+//!
 //! ### As a UCI Engine
 //!
 //! The primary way to use EnRust is as a UCI-compatible chess engine:
 //!
-//! ```rust
+//! ```rust,no_run
 //! use enrust::start_engine;
 //!
 //! fn main() {
-//!     //start_engine();  // Starts the UCI protocol loop
+//!     // Reads UCI commands from stdin until `quit`
+//!     start_engine();
 //! }
 //! ```
 //!
@@ -80,7 +116,7 @@
 //! uci
 //!
 //! // Engine responds:
-//! id name EnRust
+//! id name EnRust 0.1.0 1a2b3c4 2025-06-01
 //! id author Mikael Ferraz Aldebrand
 //! uciok
 //!
@@ -179,9 +215,19 @@
 //! - Uses the SmallVec crate for efficient small vector storage
 //! - UCI protocol specification by Stefan Meyer-Kahlen
 pub mod game_state;
-use crate::game_state::GameState;
+use crate::game_state::{GameState, Score};
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
-use std::time::Instant;
+/// Prints the version of the engine and the details of the build.
+///
+/// Prints the `id name` of the engine, `EnRust <version> <git hash> <build
+/// date>`, followed by the enabled Cargo features, the NNUE instruction set,
+/// the search threads and the target, for bug reports.
+pub fn print_version() {
+    print!("{}", game_state::version::version_info());
+}
 
 /// Starts the chess engine in UCI mode.
 ///
@@ -213,17 +259,550 @@ pub fn start_engine() {
     game_state::uci_main();
 }
 
-pub fn run_benchmark() {
-    // Creates a game object without a transposition table
-    let mut game = GameState::new(None);
-    game.start_position();
+/// Runs the `bench` subcommand.
+///
+/// Usage: `bench [ttSize] [threads] [depth] [fenfile]`
+///
+/// Searches the benchmark positions, or the positions of the FEN file, and
+/// prints the node count of each position followed by the total as
+/// `<nodes> nodes <nps> nps`. The node count doesn't change between runs,
+/// so it can be used to check that a change doesn't alter the search.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `bench` keyword
+pub fn run_benchmark(args: &[String]) {
+    match game_state::bench::BenchConfig::from_args(args) {
+        Ok(config) => print!("{}", game_state::bench::run_bench_with(&config)),
+        Err(error) => {
+            println!("{}", error);
+            println!("usage: bench [ttSize] [threads] [depth] [fenfile]");
+        }
+    }
+}
+
+/// Runs the `analyze` subcommand.
+///
+/// Usage: `analyze --file <positions.txt> [--depth <plies>] [--movetime <ms>] [--threads <count>]`
+///
+/// Searches every FEN of the file, one per line, and prints the best move,
+/// score and principal variation of each position. Empty lines and lines
+/// starting with `#` are skipped.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `analyze` keyword
+pub fn run_analyze_command(args: &[String]) {
+    let usage = "usage: analyze --file <positions.txt> [--depth <plies>] [--movetime <ms>] [--threads <count>]";
+
+    let mut file = None;
+    let mut config = game_state::analysis::AnalysisConfig::default();
+
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        let value = options.next();
+        match (option.as_str(), value) {
+            ("--file", Some(value)) => file = Some(value),
+            (option, Some(value)) if parse_analysis_option(&mut config, option, value) => {}
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    let Some(file) = file else {
+        println!("{}", usage);
+        return;
+    };
+
+    let positions: Vec<String> = match std::fs::read_to_string(file) {
+        Ok(contents) => contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect(),
+        Err(error) => {
+            println!("failed to read {}: {}", file, error);
+            return;
+        }
+    };
+
+    let game = GameState::new(Some(256));
+    for result in game.analyze_batch(&positions, &config) {
+        match result.best_move {
+            Some(best_move) => println!(
+                "{} bestmove {} score {} pv {}",
+                result.fen,
+                best_move,
+                result.score.to_uci_string(),
+                result.pv.join(" ")
+            ),
+            None => println!("{} bestmove (none)", result.fen),
+        }
+    }
+}
+
+/// Runs the `puzzles` subcommand.
+///
+/// Usage: `puzzles <input.pgn> [--threshold <cp>] [--depth <plies>] [--movetime <ms>] [--threads <count>]`
+///
+/// Analyzes every position of the games and prints the positions with a
+/// single winning move, followed by their solution line.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `puzzles` keyword
+pub fn run_puzzles_command(args: &[String]) {
+    let usage = "usage: puzzles <input.pgn> [--threshold <cp>] [--depth <plies>] [--movetime <ms>] [--threads <count>]";
+
+    let Some(pgn_path) = args.first() else {
+        println!("{}", usage);
+        return;
+    };
+    let mut config = game_state::puzzles::PuzzleConfig::default();
+
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        let Some(value) = options.next() else {
+            println!("{}", usage);
+            return;
+        };
+
+        if let ("--threshold", Ok(threshold)) = (option.as_str(), value.parse::<u16>()) {
+            config.threshold = Score::cp(threshold.into());
+        } else if !parse_analysis_option(&mut config.analysis, option, value) {
+            println!("{}", usage);
+            return;
+        }
+    }
+
+    match game_state::puzzles::extract_puzzles(Path::new(pgn_path), &config) {
+        Ok(puzzles) => {
+            for puzzle in puzzles {
+                println!("{} solution {}", puzzle.fen, puzzle.solution.join(" "));
+            }
+        }
+        Err(error) => println!("failed to read {}: {}", pgn_path, error),
+    }
+}
+
+/// Runs the `annotate` subcommand.
+///
+/// Usage: `annotate <input.pgn> <output.pgn> [--movetime <ms>] [--depth <plies>] [--threads <count>]`
+///
+/// Searches every position of the games, one second each by default, and
+/// appends them to the output file as annotated PGN: evaluations, judgements
+/// of the moves that lost score and the best line after them, see
+/// [`game_state::annotate`].
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `annotate` keyword
+pub fn run_annotate_command(args: &[String]) {
+    let usage = "usage: annotate <input.pgn> <output.pgn> [--movetime <ms>] [--depth <plies>] [--threads <count>]";
+
+    let [input, output, args @ ..] = args else {
+        println!("{}", usage);
+        return;
+    };
+    let mut config = game_state::annotate::AnnotateConfig::default();
+
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        match options.next() {
+            Some(value) if parse_analysis_option(&mut config.analysis, option, value) => {}
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    let games = match game_state::annotate::annotate_pgn(Path::new(input), &config) {
+        Ok(games) => games,
+        Err(error) => {
+            println!("failed to read {}: {}", input, error);
+            return;
+        }
+    };
+
+    let written = game_state::pgn::PgnWriter::open(Path::new(output)).and_then(|writer| {
+        games.iter().try_for_each(|game| {
+            writer.write_game(&game.export_tags(), &game.movetext(), game.result)
+        })
+    });
+    match written {
+        Ok(()) => println!("annotated {} games to {}", games.len(), output),
+        Err(error) => println!("failed to write {}: {}", output, error),
+    }
+}
+
+/// Runs the `replay` subcommand.
+///
+/// Usage: `replay <session.log>`
+///
+/// Replays a logged UCI session against a new instance of this engine and
+/// prints the `info` and `bestmove` lines that differ from the recorded ones,
+/// see [`game_state::replay`].
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `replay` keyword
+pub fn run_replay_command(args: &[String]) {
+    let usage = "usage: replay <session.log>";
+
+    let [log_path] = args else {
+        println!("{}", usage);
+        return;
+    };
+
+    let log = match std::fs::read_to_string(log_path) {
+        Ok(log) => log,
+        Err(error) => {
+            println!("failed to read {}: {}", log_path, error);
+            return;
+        }
+    };
+    let session = game_state::replay::Session::parse(&log);
+
+    let report = std::env::current_exe()
+        .and_then(|engine| game_state::replay::replay_session(&engine, &session));
+    match report {
+        Ok(report) => {
+            for mismatch in &report.mismatches {
+                println!(
+                    "line {}: expected '{}', got '{}'",
+                    mismatch.index + 1,
+                    mismatch.expected.as_deref().unwrap_or("(nothing)"),
+                    mismatch.actual.as_deref().unwrap_or("(nothing)")
+                );
+            }
+            println!(
+                "{} lines compared, {} mismatches",
+                report.compared,
+                report.mismatches.len()
+            );
+        }
+        Err(error) => println!("replay failed: {}", error),
+    }
+}
+
+/// Runs the `matesolver` subcommand.
+///
+/// Usage: `matesolver --fen <fen> --in <moves>`
+///
+/// Lists every key forcing mate in at most the given number of moves, see
+/// [`game_state::mate_solver`]. The FEN may be quoted or spread over
+/// several arguments.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `matesolver` keyword
+pub fn run_matesolver_command(args: &[String]) {
+    let usage = "usage: matesolver --fen <fen> --in <moves>";
+
+    let mut fen = Vec::new();
+    let mut moves = None;
+    let mut options = args.iter().peekable();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--fen" => {
+                while let Some(field) = options.next_if(|field| !field.starts_with("--")) {
+                    fen.push(field.as_str());
+                }
+            }
+            "--in" => match options.next().map(|value| value.parse::<u8>()) {
+                Some(Ok(value)) if value > 0 => moves = Some(value),
+                _ => {
+                    println!("{}", usage);
+                    return;
+                }
+            },
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    let (false, Some(moves)) = (fen.is_empty(), moves) else {
+        println!("{}", usage);
+        return;
+    };
+    let fen = fen.join(" ");
+
+    match game_state::mate_solver::solve_mate(&fen, moves) {
+        None => println!("invalid FEN: {}", fen),
+        Some(keys) if keys.is_empty() => println!("no mate in {}", moves),
+        Some(keys) => {
+            for key in &keys {
+                println!("key {} ({}) mate in {}", key.san, key.uci, key.mate_in);
+            }
+            println!("{} keys", keys.len());
+        }
+    }
+}
+
+/// Runs the `suite` subcommand.
+///
+/// Usage: `suite <wac|bk|positions.epd> [--movetime <ms>] [--depth <plies>] [--threads <count>]`
+///
+/// Searches every position of a test suite, one second each by default,
+/// and prints whether it was solved, see [`game_state::test_suites`]. The
+/// last line is a JSON summary to track the solve rate between releases.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `suite` keyword
+pub fn run_suite_command(args: &[String]) {
+    let usage = "usage: suite <wac|bk|positions.epd> [--movetime <ms>] [--depth <plies>] [--threads <count>]";
+
+    let Some((suite, args)) = args.split_first() else {
+        println!("{}", usage);
+        return;
+    };
+
+    // Without a depth limit, the movetime alone stops the search
+    let mut config = game_state::analysis::AnalysisConfig {
+        depth: u8::MAX,
+        movetime: Some(game_state::test_suites::DEFAULT_SUITE_MOVETIME),
+        ..Default::default()
+    };
+
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        match options.next() {
+            Some(value) if parse_analysis_option(&mut config, option, value) => {}
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    let text = match game_state::test_suites::embedded_suite(suite) {
+        Some(text) => text.to_string(),
+        None => match std::fs::read_to_string(suite) {
+            Ok(text) => text,
+            Err(error) => {
+                println!("failed to read {}: {}", suite, error);
+                return;
+            }
+        },
+    };
+
+    let positions = game_state::test_suites::parse_epd(&text);
+    if positions.is_empty() {
+        println!("no positions in {}", suite);
+        return;
+    }
+
+    let game = GameState::new(Some(256));
+    let results =
+        game_state::test_suites::run_suite(&game, &positions, &config, |index, result| {
+            println!(
+                "[{}/{}] {} {} {}",
+                index + 1,
+                positions.len(),
+                result.id,
+                if result.solved { "solved" } else { "unsolved" },
+                result.played.as_deref().unwrap_or("(none)")
+            );
+        });
+
+    let solved = results.iter().filter(|result| result.solved).count();
+    println!("solved {}/{}", solved, results.len());
+    println!(
+        "{}",
+        game_state::test_suites::summary_json(suite, &results, &config)
+    );
+}
 
-    let start_time = Instant::now();
-    let nodes = game.perft_debug(4, false);
-    let time_elapsed = start_time.elapsed();
+/// Applies a `--depth`, `--movetime` or `--threads` command line option.
+///
+/// # Returns
+///
+/// `true` if the option is known and its value valid
+fn parse_analysis_option(
+    config: &mut game_state::analysis::AnalysisConfig,
+    option: &str,
+    value: &str,
+) -> bool {
+    match (option, value.parse::<u64>()) {
+        ("--depth", Ok(depth)) if (1..=u8::MAX as u64).contains(&depth) => {
+            config.depth = depth as u8
+        }
+        ("--movetime", Ok(movetime)) => config.movetime = Some(Duration::from_millis(movetime)),
+        ("--threads", Ok(threads)) => {
+            config.threads = game_state::topology::resolve_threads(threads as usize)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Runs the `book` subcommand.
+///
+/// Usage: `book build <input.pgn> <output.bin> [--depth <plies>] [--min-games <count>]`
+///
+/// Builds a Polyglot opening book from a PGN database. By default the first
+/// 16 plies of every game are recorded and moves need to appear in at least
+/// one game.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `book` keyword
+pub fn run_book_command(args: &[String]) {
+    let usage =
+        "usage: book build <input.pgn> <output.bin> [--depth <plies>] [--min-games <count>]";
+
+    let (Some("build"), Some(pgn_path), Some(book_path)) =
+        (args.first().map(String::as_str), args.get(1), args.get(2))
+    else {
+        println!("{}", usage);
+        return;
+    };
+
+    let mut max_ply = 16;
+    let mut min_games = 1;
+
+    let mut options = args[3..].iter();
+    while let Some(option) = options.next() {
+        let value = options.next().and_then(|value| value.parse::<u32>().ok());
+        match (option.as_str(), value) {
+            ("--depth", Some(depth)) => max_ply = depth as usize,
+            ("--min-games", Some(count)) => min_games = count,
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    match game_state::book::build_book(
+        Path::new(pgn_path),
+        Path::new(book_path),
+        max_ply,
+        min_games,
+    ) {
+        Ok((games, entries)) => println!("{} games, {} book entries written", games, entries),
+        Err(error) => println!("failed to build book: {}", error),
+    }
+}
+
+/// Runs the `selfplay` subcommand.
+///
+/// Usage: `selfplay --pgn <games.pgn> [--games <count>] [--tc <time control>] [--hash <MB>]
+/// [--resign <cp>/<moves>] [--draw <cp>/<moves>] [--tablebase]`
+///
+/// Plays games of the engine against itself and appends them to the PGN
+/// file as they finish, with the clock after every move. The time control
+/// has the format `[moves/]base[+increment]` in seconds and defaults to
+/// `10+0.1`.
+///
+/// Games are adjudicated as lost when both engines report a score beyond
+/// the `--resign` score for the given number of moves each, as drawn when
+/// the scores stay within the `--draw` score for the given number of moves
+/// after move 40, and with their exact result on reaching an endgame of a
+/// bitbase with `--tablebase`.
+///
+/// # Arguments
+///
+/// * `args` - Arguments following the `selfplay` keyword
+pub fn run_selfplay_command(args: &[String]) {
+    let usage = "usage: selfplay --pgn <games.pgn> [--games <count>] [--tc <time control>] [--hash <MB>] [--resign <cp>/<moves>] [--draw <cp>/<moves>] [--tablebase]";
+
+    let mut pgn_path = None;
+    let mut games = 1;
+    let mut clock = game_state::selfplay::Clock::parse("10+0.1");
+    let mut hash = 16;
+    let mut adjudication = game_state::selfplay::Adjudication::default();
+
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        if option == "--tablebase" {
+            adjudication.tablebase = true;
+            continue;
+        }
+
+        let Some(value) = options.next() else {
+            println!("{}", usage);
+            return;
+        };
+        match (option.as_str(), value.parse::<usize>()) {
+            ("--pgn", _) => pgn_path = Some(value),
+            ("--games", Ok(count)) if count > 0 => games = count,
+            ("--tc", _) => clock = game_state::selfplay::Clock::parse(value),
+            ("--hash", Ok(size)) if size > 0 => hash = size,
+            ("--resign", _) if let Some((score, moves)) = parse_score_rule(value) => {
+                adjudication.resign_score = Some(score);
+                adjudication.resign_moves = moves;
+            }
+            ("--draw", _) if let Some((score, moves)) = parse_score_rule(value) => {
+                adjudication.draw_score = Some(score);
+                adjudication.draw_moves = moves;
+            }
+            _ => {
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    let (Some(pgn_path), Some(clock)) = (pgn_path, clock) else {
+        println!("{}", usage);
+        return;
+    };
+    let writer = match game_state::pgn::PgnWriter::open(Path::new(pgn_path)) {
+        Ok(writer) => writer,
+        Err(error) => {
+            println!("failed to open {}: {}", pgn_path, error);
+            return;
+        }
+    };
+    let engine = format!("EnRust {}", env!("CARGO_PKG_VERSION"));
+
+    for round in 1..=games {
+        let mut white = GameState::new(Some(hash));
+        let mut black = GameState::new(Some(hash));
+        white.start_position();
+        black.start_position();
+
+        let game =
+            game_state::selfplay::play_game(&mut white, &mut black, clock.clone(), &adjudication);
+        let mut tags = vec![
+            ("Event".to_string(), "EnRust self-play".to_string()),
+            (
+                "Date".to_string(),
+                game_state::pgn::pgn_date(SystemTime::now()),
+            ),
+            ("Round".to_string(), round.to_string()),
+            ("White".to_string(), engine.clone()),
+            ("Black".to_string(), engine.clone()),
+        ];
+        tags.extend(game.tags());
+        if let Err(error) = writer.write_game(&tags, &game.movetext(), game.result) {
+            println!("failed to write {}: {}", pgn_path, error);
+            return;
+        }
+
+        println!(
+            "game {}: {} ({})",
+            round,
+            game.result.to_token(),
+            game.termination.pgn_name()
+        );
+    }
+}
 
-    // Calculate statistics
-    let nps = nodes as f64 / time_elapsed.as_secs_f64();
+/// Parses an adjudication rule such as `600/3`: a score in centipawns and a
+/// number of moves.
+fn parse_score_rule(rule: &str) -> Option<(Score, usize)> {
+    let (score, moves) = rule.split_once('/')?;
+    let score = score.parse::<u16>().ok()?;
+    let moves = moves.parse::<usize>().ok().filter(|&moves| moves > 0)?;
 
-    println!("{} nodes nps: {:.0}", nodes, nps);
+    Some((Score::cp(score.into()), moves))
 }
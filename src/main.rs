@@ -3,9 +3,36 @@ use std::env;
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    // If first argument is "bench", run benchmark mode
-    if args.len() > 1 && args[1] == "bench" {
-        enrust::run_benchmark();
+    if args.len() > 1 && (args[1] == "--version" || args[1] == "-V") {
+        // Version and build details
+        enrust::print_version();
+    } else if args.len() > 1 && args[1] == "bench" {
+        // Benchmark mode
+        enrust::run_benchmark(&args[2..]);
+    } else if args.len() > 1 && args[1] == "analyze" {
+        // Batch analysis of a file of positions
+        enrust::run_analyze_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "puzzles" {
+        // Puzzle extraction from a PGN database
+        enrust::run_puzzles_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "annotate" {
+        // Annotation of the games of a PGN database
+        enrust::run_annotate_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "replay" {
+        // Regression test against a logged UCI session
+        enrust::run_replay_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "book" {
+        // Opening book tools
+        enrust::run_book_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "selfplay" {
+        // Games of the engine against itself
+        enrust::run_selfplay_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "matesolver" {
+        // Exhaustive mate search for chess problems
+        enrust::run_matesolver_command(&args[2..]);
+    } else if args.len() > 1 && args[1] == "suite" {
+        // Tactical test suites
+        enrust::run_suite_command(&args[2..]);
     } else {
         // Normal engine operation (UCI)
         enrust::start_engine();
@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod affinity_tests {
+    use std::thread;
+
+    use enrust::game_state::GameState;
+    use enrust::game_state::affinity::pin_current_thread;
+    use enrust::game_state::analysis::AnalysisConfig;
+
+    #[test]
+    fn test_pin_current_thread() {
+        // Indices past the number of CPUs wrap around
+        let pinned = thread::spawn(|| pin_current_thread(1000)).join().unwrap();
+        assert_eq!(pinned, cfg!(target_os = "linux"));
+    }
+
+    #[test]
+    fn test_pinned_analysis_finds_the_same_moves() {
+        let positions = [
+            "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+            "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        ];
+        let config = AnalysisConfig {
+            depth: 3,
+            ..Default::default()
+        };
+
+        let mut pinned = GameState::new(Some(1));
+        pinned.set_thread_affinity(true);
+        assert!(pinned.thread_affinity());
+        let unpinned = GameState::new(Some(1));
+
+        assert_eq!(
+            pinned.analyze_batch(&positions, &config),
+            unpinned.analyze_batch(&positions, &config)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod analysis_tests {
+    use enrust::game_state::GameState;
+    use enrust::game_state::analysis::AnalysisConfig;
+
+    const MATE_IN_ONE: &str = "7R/8/8/8/8/1K6/8/1k6 w - - 0 1";
+    const HANGING_QUEEN: &str = "4k3/8/8/8/q7/8/8/3QK3 w - - 0 1";
+    const STALEMATE: &str = "k7/2Q5/1K6/8/8/8/8/8 b - - 0 1";
+
+    fn config(depth: u8, threads: usize) -> AnalysisConfig {
+        AnalysisConfig {
+            depth,
+            threads,
+            ..AnalysisConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_batch_results_follow_input_order() {
+        let game = GameState::new(Some(16));
+        let positions = [MATE_IN_ONE, HANGING_QUEEN, MATE_IN_ONE, HANGING_QUEEN];
+
+        for threads in [1, 3] {
+            let results = game.analyze_batch(&positions, &config(3, threads));
+
+            assert_eq!(results.len(), positions.len());
+            for (result, fen) in results.iter().zip(positions) {
+                assert_eq!(result.fen, fen);
+                let expected = if fen == MATE_IN_ONE { "h8h1" } else { "d1a4" };
+                assert_eq!(result.best_move.as_deref(), Some(expected));
+                assert!(result.nodes > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pv_starts_with_best_move() {
+        let game = GameState::new(Some(16));
+        let results = game.analyze_batch(
+            &["r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"],
+            &config(3, 1),
+        );
+
+        let result = &results[0];
+        assert!(!result.pv.is_empty());
+        assert_eq!(Some(&result.pv[0]), result.best_move.as_ref());
+    }
+
+    #[test]
+    fn test_score_is_from_side_to_move() {
+        let game = GameState::new(Some(16));
+        let results = game.analyze_batch(
+            &[HANGING_QUEEN, "4k3/8/8/8/Q7/8/8/3qK3 b - - 0 1"],
+            &config(2, 1),
+        );
+
+        assert!(results[0].score.centipawns() > 0);
+        assert!(results[1].score.centipawns() > 0);
+    }
+
+    #[test]
+    fn test_unsearchable_positions_have_no_move() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        let results = game.analyze_batch(&["not a fen", STALEMATE], &config(2, 2));
+
+        assert!(results.iter().all(|result| result.best_move.is_none()));
+        assert!(results.iter().all(|result| result.pv.is_empty()));
+        // The game's own position is untouched
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+    }
+}
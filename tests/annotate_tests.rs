@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod annotate_tests {
+    use enrust::game_state::analysis::AnalysisConfig;
+    use enrust::game_state::annotate::{
+        AnnotateConfig, AnnotatedGame, AnnotatedMove, Judgement, annotate_games, format_evaluation,
+    };
+    use enrust::game_state::pgn::{GameResult, parse_pgn};
+    use enrust::game_state::{Color, Score};
+
+    fn config() -> AnnotateConfig {
+        AnnotateConfig {
+            analysis: AnalysisConfig {
+                depth: 3,
+                movetime: None,
+                ..AnalysisConfig::default()
+            },
+            ..AnnotateConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_blunder_allowing_mate_is_marked() {
+        let games = parse_pgn(
+            r#"[Event "Scholar's mate"]
+[Result "1-0"]
+
+1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0
+"#,
+        );
+
+        let annotated = annotate_games(&games, &config());
+        let blunder = &annotated[0].moves[5];
+
+        assert_eq!(blunder.san, "Nf6");
+        assert_eq!(blunder.judgement, Some(Judgement::Blunder));
+        assert_eq!(blunder.evaluation.mate_moves(), Some(1));
+        assert!(!blunder.best_line.is_empty());
+        assert_ne!(blunder.best_line[0], "Nf6");
+
+        let mate = &annotated[0].moves[6];
+        assert_eq!(mate.san, "Qxf7#");
+        assert_eq!(mate.judgement, None);
+        assert!(mate.best_line.is_empty());
+    }
+
+    #[test]
+    fn test_annotated_game_parses_back_to_its_moves() {
+        let games = parse_pgn(
+            r#"[Event "Scholar's mate"]
+[Annotator "Someone"]
+[Result "1-0"]
+
+1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0
+"#,
+        );
+
+        let pgn = annotate_games(&games, &config())[0].to_pgn();
+        assert!(pgn.contains("Nf6?? {#1} (3..."));
+
+        let reparsed = parse_pgn(&pgn);
+        assert_eq!(reparsed[0].moves, games[0].moves);
+        assert_eq!(reparsed[0].result, GameResult::WhiteWins);
+        assert!(reparsed[0].tag("Annotator").unwrap().starts_with("EnRust"));
+    }
+
+    #[test]
+    fn test_mate_on_the_board_has_no_evaluation() {
+        let games = parse_pgn(
+            r#"[Event "Fool's mate"]
+[Result "0-1"]
+
+1. f3 e5 2. g4 Qh4# 0-1
+"#,
+        );
+
+        let annotated = &annotate_games(&games, &config())[0];
+        let mate = annotated.moves[3].evaluation.relative_to(Color::White);
+        assert_eq!(mate.mate_moves(), Some(0));
+
+        let movetext = annotated.movetext();
+        assert!(movetext.ends_with("Qh4#"), "{}", movetext);
+        assert!(!movetext.contains("#-0"), "{}", movetext);
+        // The blunder is answered by the mate, with no evaluation after it
+        assert!(movetext.contains("g4?? {#-1} (2. "), "{}", movetext);
+    }
+
+    #[test]
+    fn test_moves_after_an_illegal_one_are_kept() {
+        let games = parse_pgn(
+            r#"[FEN "6k1/5ppp/8/8/8/8/5PPP/R5K1 b - - 0 30"]
+[Result "*"]
+
+30... h6 31. Ra8+ Kh7 32. Qd8 Kg6 *
+"#,
+        );
+
+        let annotated = &annotate_games(&games, &config())[0];
+
+        assert_eq!(annotated.moves.len(), 3);
+        assert_eq!(annotated.unannotated, vec!["Qd8", "Kg6"]);
+        assert!(annotated.movetext().starts_with("30... h6 {"));
+        assert!(annotated.movetext().ends_with("32. Qd8 Kg6"));
+    }
+
+    #[test]
+    fn test_movetext_numbers_variations() {
+        let good = |san: &str| AnnotatedMove {
+            san: san.to_string(),
+            evaluation: Score::cp(20),
+            best_evaluation: Score::cp(20),
+            loss: Score::ZERO,
+            judgement: None,
+            best_line: Vec::new(),
+        };
+        let game = AnnotatedGame {
+            tags: Vec::new(),
+            start_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+            moves: vec![
+                good("e4"),
+                AnnotatedMove {
+                    san: "f6".to_string(),
+                    evaluation: Score::cp(95),
+                    best_evaluation: Score::cp(25),
+                    loss: Score::cp(70),
+                    judgement: Some(Judgement::Inaccuracy),
+                    best_line: vec!["e5".to_string(), "Nf3".to_string(), "Nc6".to_string()],
+                },
+            ],
+            unannotated: Vec::new(),
+            result: GameResult::Unknown,
+        };
+
+        assert_eq!(
+            game.movetext(),
+            "1. e4 {+0.20} 1... f6?! {+0.95} (1... e5 {+0.25} 2. Nf3 Nc6)"
+        );
+    }
+
+    #[test]
+    fn test_evaluations_and_judgements() {
+        assert_eq!(format_evaluation(Score::cp(35), Color::White), "+0.35");
+        assert_eq!(format_evaluation(Score::cp(-120), Color::Black), "-1.20");
+        assert_eq!(format_evaluation(Score::mate_in(5), Color::White), "#3");
+        assert_eq!(format_evaluation(-Score::mate_in(5), Color::Black), "#-3");
+        assert_eq!(format_evaluation(Score::mated_in(4), Color::White), "#-2");
+
+        let config = AnnotateConfig::default();
+        assert_eq!(Judgement::from_loss(Score::cp(49), &config), None);
+        assert_eq!(
+            Judgement::from_loss(Score::cp(50), &config),
+            Some(Judgement::Inaccuracy)
+        );
+        assert_eq!(
+            Judgement::from_loss(Score::cp(150), &config),
+            Some(Judgement::Mistake)
+        );
+        assert_eq!(
+            Judgement::from_loss(Score::cp(300), &config),
+            Some(Judgement::Blunder)
+        );
+        assert_eq!(Judgement::Blunder.glyph(), "??");
+    }
+}
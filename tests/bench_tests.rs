@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod bench_tests {
+    use std::time::{Duration, Instant};
+
+    use enrust::game_state::bench::{
+        BENCH_HASH_SIZE, BENCH_POSITIONS, BenchConfig, BenchResult, CALIBRATION_TIME,
+        DEFAULT_BENCH_DEPTH, calibrate, calibrated_nps, run_bench, run_bench_with,
+    };
+    use enrust::game_state::topology;
+
+    #[test]
+    fn test_bench_node_count_is_deterministic() {
+        let first = run_bench(2);
+        let second = run_bench(2);
+
+        assert!(first.nodes > 0);
+        assert_eq!(first.nodes, second.nodes);
+    }
+
+    #[test]
+    fn test_calibration_measures_the_speed() {
+        let start = Instant::now();
+        let nps = calibrate(CALIBRATION_TIME);
+
+        assert!(nps > 0);
+        // The searches are stopped at the end of the time, not after a
+        // whole search of a position
+        assert!(start.elapsed() < 2 * CALIBRATION_TIME);
+        // The first measure is kept, whichever ran first
+        assert!(calibrated_nps().is_some_and(|nps| nps > 0));
+    }
+
+    #[test]
+    fn test_bench_output_format() {
+        let result = BenchResult {
+            nodes: 1_500_000,
+            elapsed: Duration::from_millis(1500),
+        };
+
+        assert_eq!(result.to_string(), "1500000 nodes 1000000 nps");
+    }
+
+    #[test]
+    fn test_bench_arguments() {
+        let config = BenchConfig::from_args::<&str>(&[]).unwrap();
+        assert_eq!(config.hash_size, BENCH_HASH_SIZE);
+        assert_eq!(config.threads, 1);
+        assert_eq!(config.depth, DEFAULT_BENCH_DEPTH);
+        assert_eq!(config.positions, BENCH_POSITIONS);
+
+        let config = BenchConfig::from_args(&["64", "4", "6", "default"]).unwrap();
+        assert_eq!((config.hash_size, config.threads, config.depth), (64, 4, 6));
+        assert_eq!(config.positions.len(), BENCH_POSITIONS.len());
+
+        assert_eq!(
+            BenchConfig::from_args(&["0"]),
+            Err("invalid bench ttSize: '0'".to_string())
+        );
+        // 0 threads is one per physical core
+        let config = BenchConfig::from_args(&["16", "0"]).unwrap();
+        assert_eq!(config.threads, topology::physical_cores());
+
+        assert_eq!(
+            BenchConfig::from_args(&["16", "two"]),
+            Err("invalid bench threads: 'two'".to_string())
+        );
+        assert_eq!(
+            BenchConfig::from_args(&["16", "1", "0"]),
+            Err("invalid bench depth: '0'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bench_fen_file() {
+        let path = std::env::temp_dir().join(format!("enrust_bench_{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            "# Endgames\n8/8/4k3/3p4/3P4/4K3/8/8 w - - 0 1\n\n6k1/5p2/6p1/8/7P/8/5PK1/3R4 w - - 0 40\n",
+        )
+        .unwrap();
+        let file = path.to_str().unwrap();
+
+        let config = BenchConfig::from_args(&["16", "1", "2", file]).unwrap();
+        assert_eq!(
+            config.positions,
+            [
+                "8/8/4k3/3p4/3P4/4K3/8/8 w - - 0 1",
+                "6k1/5p2/6p1/8/7P/8/5PK1/3R4 w - - 0 40"
+            ]
+        );
+
+        std::fs::write(&path, "not a fen\n").unwrap();
+        let error = BenchConfig::from_args(&["16", "1", "2", file]).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.starts_with("invalid FEN"), "{}", error);
+    }
+
+    #[test]
+    fn test_bench_threads_keep_the_node_counts() {
+        let single = run_bench_with(&BenchConfig {
+            depth: 2,
+            ..Default::default()
+        });
+        let threaded = run_bench_with(&BenchConfig {
+            depth: 2,
+            threads: 3,
+            ..Default::default()
+        });
+
+        assert_eq!(single.positions, threaded.positions);
+        assert_eq!(single.total.nodes, run_bench(2).nodes);
+        assert_eq!(
+            single.total.nodes,
+            single
+                .positions
+                .iter()
+                .map(|position| position.nodes)
+                .sum::<u64>()
+        );
+
+        // One line per position, the totals last
+        let report = single.to_string();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), BENCH_POSITIONS.len() + 1);
+        assert!(lines[0].starts_with("position 1/10 "));
+        assert!(lines[0].ends_with(BENCH_POSITIONS[0]));
+        assert!(lines[10].starts_with(&format!("{} nodes ", single.total.nodes)));
+    }
+}
@@ -0,0 +1,297 @@
+#[cfg(test)]
+mod book_tests {
+    use std::path::PathBuf;
+
+    use enrust::game_state::GameState;
+    use enrust::game_state::book::{BookBuilder, BookEntry, read_book};
+    use enrust::game_state::pgn::{GameResult, parse_pgn};
+
+    fn setup_game_with_fen(fen: &str) -> GameState {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game
+    }
+
+    fn play(game: &mut GameState, moves: &str) {
+        for mv in moves.split_whitespace() {
+            assert!(game.make_move(mv), "Could not play {}", mv);
+        }
+    }
+
+    fn san_of(fen: &str, uci: &str) -> String {
+        let game = setup_game_with_fen(fen);
+        let mv = game.create_move(uci).expect("valid move");
+        let mut board = game.get_chess_board().clone();
+        board.move_to_san(&mv)
+    }
+
+    fn uci_of_san(fen: &str, san: &str) -> Option<String> {
+        let mut game = setup_game_with_fen(fen);
+        let mv = game.create_move_from_san(san)?;
+        Some(game.get_chess_board().move_to_uci(&mv))
+    }
+
+    fn temp_book_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("enrust_{}_{}.bin", name, std::process::id()))
+    }
+
+    const SAMPLE_PGN: &str = r#"
+[Event "Test 1"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+
+1. e4 e5 2. Nf3 {main line} Nc6 (2... d6 3. d4) 3. Bb5 a6 $1 4. Ba4!? 1-0
+
+[Event "Test 2"]
+[Result "1/2-1/2"]
+
+1.e4 c5 2.Nf3 ; Sicilian
+d6 1/2-1/2
+
+[Event "Test 3"]
+[Result "0-1"]
+
+1. e4 e5 2. Nf3 Nf6 0-1
+
+[Event "Unfinished"]
+[Result "*"]
+
+1. d4 d5 *
+"#;
+
+    #[test]
+    fn test_polyglot_key_reference_positions() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        assert_eq!(game.polyglot_key(), 0x463b96181691fc9c);
+
+        play(&mut game, "e2e4");
+        assert_eq!(game.polyglot_key(), 0x823c9b50fd114196);
+
+        play(&mut game, "d7d5");
+        assert_eq!(game.polyglot_key(), 0x0756b94461c50fb0);
+
+        play(&mut game, "e4e5");
+        assert_eq!(game.polyglot_key(), 0x662fafb965db29d4);
+
+        // En passant on f6 is possible, so the file is hashed
+        play(&mut game, "f7f5");
+        assert_eq!(game.polyglot_key(), 0x22a48b5a8e47ff78);
+
+        play(&mut game, "e1e2");
+        assert_eq!(game.polyglot_key(), 0x652a607ca3f242c1);
+
+        play(&mut game, "e8f7");
+        assert_eq!(game.polyglot_key(), 0x00fdd303c946bdd9);
+    }
+
+    #[test]
+    fn test_polyglot_key_after_en_passant_and_rook_move() {
+        let mut game = GameState::new(None);
+        game.start_position();
+
+        play(&mut game, "a2a4 b7b5 h2h4 b5b4 c2c4");
+        assert_eq!(game.polyglot_key(), 0x3c8123ea7b067637);
+
+        play(&mut game, "b4c3 a1a3");
+        assert_eq!(game.polyglot_key(), 0x5c3f9b829b279560);
+    }
+
+    #[test]
+    fn test_polyglot_key_from_fen_matches_played_moves() {
+        let game =
+            setup_game_with_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        assert_eq!(game.polyglot_key(), 0x22a48b5a8e47ff78);
+    }
+
+    #[test]
+    fn test_incremental_polyglot_keys_match_full_computation() {
+        let mut incremental = GameState::new(None);
+        incremental.set_polyglot_keys(true);
+        incremental.start_position();
+
+        let mut reference = GameState::new(None);
+        reference.start_position();
+        assert_eq!(incremental.polyglot_key(), 0x463b96181691fc9c);
+
+        // Covers double pushes with and without an en passant capture
+        // available, en passant, castling, promotion and lost castling rights
+        let moves = "e2e4 d7d5 e4e5 f7f5 e5f6 g8f6 g1f3 b7b5 f1b5 c7c6 e1g1 c6b5 \
+                     a2a4 b5a4 b1c3 a4a3 d2d4 a3b2 h2h3 b2a1q";
+        for mv in moves.split_whitespace() {
+            play(&mut incremental, mv);
+            play(&mut reference, mv);
+            assert_eq!(
+                incremental.polyglot_key(),
+                reference.polyglot_key(),
+                "after {}",
+                mv
+            );
+        }
+    }
+
+    #[test]
+    fn test_polyglot_keys_survive_new_positions() {
+        let mut game = GameState::new(None);
+        game.set_polyglot_keys(true);
+        game.set_fen_position("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        assert_eq!(game.polyglot_key(), 0x22a48b5a8e47ff78);
+
+        game.set_polyglot_keys(false);
+        assert_eq!(game.polyglot_key(), 0x22a48b5a8e47ff78);
+    }
+
+    #[test]
+    fn test_san_generation() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(san_of(start, "e2e4"), "e4");
+        assert_eq!(san_of(start, "g1f3"), "Nf3");
+
+        // Knights on b1 and f3 can both reach d2
+        let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+        assert_eq!(san_of(fen, "b1d2"), "Nbd2");
+
+        // Rooks on a1 and a5 share the file
+        let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(san_of(fen, "a1a3"), "R1a3");
+
+        assert_eq!(san_of("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5"), "exd5");
+        assert_eq!(san_of("r3k3/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"), "O-O");
+        assert_eq!(san_of("r3k3/8/8/8/8/8/8/4K2R b q - 0 1", "e8c8"), "O-O-O");
+        // The rook lands on d8, giving check to the king on d1
+        assert_eq!(san_of("r3k3/8/8/8/8/8/8/3K4 b q - 0 1", "e8c8"), "O-O-O+");
+        assert_eq!(san_of("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q"), "e8=Q+");
+        assert_eq!(san_of("7k/8/6K1/8/8/8/8/R7 w - - 0 1", "a1a8"), "Ra8#");
+        assert_eq!(san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), "exd6");
+    }
+
+    #[test]
+    fn test_san_parsing() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(uci_of_san(start, "e4").as_deref(), Some("e2e4"));
+        assert_eq!(uci_of_san(start, "Nf3").as_deref(), Some("g1f3"));
+        assert_eq!(uci_of_san(start, "Nf3+!?").as_deref(), Some("g1f3"));
+        assert_eq!(uci_of_san(start, "e5"), None);
+
+        let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+        assert_eq!(uci_of_san(fen, "Nd2"), None, "Ambiguous move");
+        assert_eq!(uci_of_san(fen, "Nbd2").as_deref(), Some("b1d2"));
+        assert_eq!(uci_of_san(fen, "Nfd2").as_deref(), Some("f3d2"));
+
+        let fen = "r3k3/8/8/8/8/8/8/4K2R w K - 0 1";
+        assert_eq!(uci_of_san(fen, "O-O").as_deref(), Some("e1g1"));
+        assert_eq!(uci_of_san(fen, "0-0").as_deref(), Some("e1g1"));
+
+        let fen = "k7/4P3/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(uci_of_san(fen, "e8=Q").as_deref(), Some("e7e8q"));
+        assert_eq!(uci_of_san(fen, "e8N").as_deref(), Some("e7e8n"));
+    }
+
+    #[test]
+    fn test_pgn_parsing() {
+        let games = parse_pgn(SAMPLE_PGN);
+        assert_eq!(games.len(), 4);
+
+        assert_eq!(games[0].tag("White"), Some("Alice"));
+        assert_eq!(games[0].result, GameResult::WhiteWins);
+        assert_eq!(
+            games[0].moves,
+            vec!["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4"]
+        );
+
+        assert_eq!(games[1].moves, vec!["e4", "c5", "Nf3", "d6"]);
+        assert_eq!(games[1].result, GameResult::Draw);
+        assert_eq!(games[2].result, GameResult::BlackWins);
+        assert_eq!(games[3].result, GameResult::Unknown);
+    }
+
+    #[test]
+    fn test_book_entries_weights_and_filters() {
+        let mut builder = BookBuilder::new(4, 2);
+        let used = parse_pgn(SAMPLE_PGN)
+            .iter()
+            .filter(|game| builder.add_game(game))
+            .count();
+        assert_eq!(used, 3, "The unfinished game should be skipped");
+
+        let entries = builder.entries();
+
+        // Only 1. e4, 1... e5 and 2. Nf3 (after 1. e4 e5) appear in two games
+        let mut game = GameState::new(None);
+        game.start_position();
+        let start_key = game.polyglot_key();
+        let e4 = entries
+            .iter()
+            .find(|entry| entry.key == start_key)
+            .expect("1. e4 entry");
+        // White won one game and drew one: 2 + 1 points
+        assert_eq!(e4.weight, 3);
+        assert_eq!(e4.mv, (12 << 6) | 28);
+
+        play(&mut game, "e2e4 e7e5");
+        let nf3 = entries
+            .iter()
+            .find(|entry| entry.key == game.polyglot_key())
+            .expect("2. Nf3 entry");
+        // White won one and lost one
+        assert_eq!(nf3.weight, 2);
+
+        assert!(entries.windows(2).all(|pair| pair[0].key <= pair[1].key));
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_book_depth_limit() {
+        let mut builder = BookBuilder::new(1, 1);
+        for game in parse_pgn(SAMPLE_PGN) {
+            builder.add_game(&game);
+        }
+
+        // Only the first ply of each game is recorded
+        assert_eq!(builder.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_book_file_round_trip() {
+        let mut builder = BookBuilder::new(8, 1);
+        for game in parse_pgn(SAMPLE_PGN) {
+            builder.add_game(&game);
+        }
+
+        let path = temp_book_path("round_trip");
+        let written = builder.write(&path).expect("write book");
+        let entries = read_book(&path).expect("read book");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(written, entries.len());
+        assert_eq!(entries, builder.entries());
+    }
+
+    #[test]
+    fn test_book_entry_layout_is_big_endian() {
+        let entry = BookEntry {
+            key: 0x463b96181691fc9c,
+            mv: 0x031c,
+            weight: 3,
+            learn: 0,
+        };
+        let bytes = entry.to_bytes();
+
+        assert_eq!(
+            bytes[0..8],
+            [0x46, 0x3b, 0x96, 0x18, 0x16, 0x91, 0xfc, 0x9c]
+        );
+        assert_eq!(bytes[8..12], [0x03, 0x1c, 0x00, 0x03]);
+        assert_eq!(BookEntry::from_bytes(&bytes), entry);
+    }
+
+    #[test]
+    fn test_castling_is_encoded_as_king_takes_rook() {
+        let game = setup_game_with_fen("r3k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        let mv = game.create_move("e1g1").unwrap();
+
+        // e1 = 4, h1 = 7
+        assert_eq!(game.get_chess_board().move_to_polyglot(&mv), (4 << 6) | 7);
+    }
+}
@@ -0,0 +1,263 @@
+#[cfg(test)]
+mod check_tests {
+    use enrust::game_state::{ChessBoard, Color, GameState};
+
+    fn setup_game_with_fen(fen: &str) -> GameState {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game
+    }
+
+    fn square_bit(square: &str) -> u64 {
+        let bytes = square.as_bytes();
+        let file = (bytes[0] - b'a') as u64;
+        let rank = (bytes[1] - b'1') as u64;
+        1 << (rank * 8 + file)
+    }
+
+    fn square_names(squares: Vec<usize>) -> Vec<String> {
+        squares.into_iter().map(ChessBoard::square_name).collect()
+    }
+
+    fn gives_check(fen: &str, uci: &str) -> bool {
+        let game = setup_game_with_fen(fen);
+        let mv = game.create_move(uci).expect("valid move");
+        game.get_chess_board().gives_check(&mv)
+    }
+
+    /// Compares `gives_check` against making every legal move and testing for check.
+    fn assert_gives_check_matches_make_move(fen: &str, color: Color) {
+        let game = setup_game_with_fen(fen);
+        let mut board = game.get_chess_board().clone();
+
+        for mv in board.generate_moves(color) {
+            let predicted = board.gives_check(&mv);
+
+            board.make_move(&mv);
+            let actual = board.is_in_check(color.opposite());
+            board.unmake_move(&mv);
+
+            assert_eq!(
+                predicted,
+                actual,
+                "gives_check mismatch for {} in {}",
+                board.move_to_uci(&mv),
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn test_direct_checks() {
+        let fen = "4k3/8/8/8/8/8/8/R3K1NB w - - 0 1";
+
+        // Rook to the e-file
+        assert!(gives_check(fen, "a1a8"));
+        // Knight move that does not reach the king
+        assert!(!gives_check(fen, "g1f3"));
+        // Bishop along the long diagonal does not reach e8
+        assert!(!gives_check(fen, "h1a8"));
+    }
+
+    #[test]
+    fn test_knight_and_pawn_checks() {
+        assert!(gives_check("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1", "e4f6"));
+        assert!(!gives_check("4k3/8/8/8/4N3/8/8/4K3 w - - 0 1", "e4d2"));
+        assert!(gives_check("4k3/8/3P4/8/8/8/8/4K3 w - - 0 1", "d6d7"));
+        assert!(!gives_check("4k3/8/4P3/8/8/8/8/4K3 w - - 0 1", "e6e7"));
+    }
+
+    #[test]
+    fn test_discovered_check() {
+        // Bishop on e4 blocks the rook on e1
+        let fen = "4k3/8/8/8/4B3/8/8/K3R3 w - - 0 1";
+        assert!(gives_check(fen, "e4d5"));
+        assert!(gives_check(fen, "e4b1"));
+
+        // Rook moving along the line keeps blocking
+        let fen = "4k3/8/8/8/4R3/8/8/K3Q3 w - - 0 1";
+        assert!(gives_check(fen, "e4e5"));
+        assert!(gives_check(fen, "e4a4"));
+        let fen = "4k3/8/8/8/4N3/8/8/K3Q3 w - - 0 1";
+        assert!(gives_check(fen, "e4c3"));
+    }
+
+    #[test]
+    fn test_promotion_check() {
+        let fen = "k7/4P3/8/8/8/8/8/4K3 w - - 0 1";
+        assert!(gives_check(fen, "e7e8q"));
+        assert!(gives_check(fen, "e7e8r"));
+        assert!(!gives_check(fen, "e7e8n"));
+        assert!(!gives_check(fen, "e7e8b"));
+    }
+
+    #[test]
+    fn test_promotion_checks_through_the_vacated_square() {
+        // The pawn on b7 no longer blocks the b-file once it promotes
+        let fen = "8/1P6/1k6/8/8/8/8/K7 w - - 0 1";
+        assert!(gives_check(fen, "b7b8q"));
+        assert!(gives_check(fen, "b7b8r"));
+        assert!(!gives_check(fen, "b7b8b"));
+        assert!(!gives_check(fen, "b7b8n"));
+
+        let game = setup_game_with_fen(fen);
+        let mv = game.create_move("b7b8q").expect("valid move");
+        let mut board = game.get_chess_board().clone();
+        assert_eq!(board.move_to_san(&mv), "b8=Q+");
+
+        // Capturing promotion along the diagonal through b7
+        assert!(gives_check("r7/1P6/2k5/8/8/8/8/K7 w - - 0 1", "b7a8q"));
+        assert!(gives_check("r7/1P6/2k5/8/8/8/8/K7 w - - 0 1", "b7a8b"));
+    }
+
+    #[test]
+    fn test_castling_and_en_passant_checks() {
+        // Castling rook lands on f1 and checks the king on f8
+        assert!(gives_check("5k2/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"));
+
+        // En passant capture uncovers the rook on the fifth rank
+        assert!(gives_check("8/8/8/R2pP2k/8/8/8/4K3 w - d6 0 1", "e5d6"));
+    }
+
+    #[test]
+    fn test_gives_check_matches_make_move() {
+        let positions = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                Color::White,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                Color::White,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+                Color::Black,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", Color::White),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 0 1", Color::Black),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                Color::White,
+            ),
+            (
+                "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+                Color::White,
+            ),
+            ("8/1P6/1k6/8/8/8/8/K7 w - - 0 1", Color::White),
+            ("r7/1P6/2k5/8/8/8/8/K7 w - - 0 1", Color::White),
+        ];
+
+        for (fen, color) in positions {
+            assert_gives_check_matches_make_move(fen, color);
+        }
+    }
+
+    #[test]
+    fn test_attacked_squares_start_position() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        let board = game.get_chess_board();
+
+        let white = board.attacked_squares(Color::White);
+        let black = board.attacked_squares(Color::Black);
+
+        // Third rank is fully covered by white pawns, sixth rank by black pawns
+        assert_eq!(white & 0x0000_0000_00FF_0000, 0x0000_0000_00FF_0000);
+        assert_eq!(black & 0x0000_FF00_0000_0000, 0x0000_FF00_0000_0000);
+
+        // Nothing reaches the middle of the board yet
+        assert_eq!(white & 0x0000_00FF_FF00_0000, 0);
+        assert_eq!(black & 0x0000_00FF_FF00_0000, 0);
+
+        // Defended pieces count as attacked, but a1 and h1 are not
+        assert_ne!(white & square_bit("d1"), 0);
+        assert_eq!(white & square_bit("a1"), 0);
+        assert_eq!(white & square_bit("h1"), 0);
+    }
+
+    #[test]
+    fn test_attacked_squares_sliders_stop_at_blockers() {
+        let game = setup_game_with_fen("4k3/8/8/8/3p4/8/8/3RK3 w - - 0 1");
+        let attacked = game.get_chess_board().attacked_squares(Color::White);
+
+        assert_ne!(attacked & square_bit("d4"), 0);
+        assert_eq!(attacked & square_bit("d5"), 0);
+        assert_ne!(attacked & square_bit("a1"), 0);
+        // The king on e1 blocks the rook on the first rank
+        assert_eq!(attacked & square_bit("g1"), 0);
+        assert_ne!(attacked & square_bit("f1"), 0);
+    }
+
+    #[test]
+    fn test_attacked_squares_agree_with_pawn_direction() {
+        let game = setup_game_with_fen("4k3/8/8/8/4p3/8/8/4K3 b - - 0 1");
+        let attacked = game.get_chess_board().attacked_squares(Color::Black);
+
+        assert_ne!(attacked & square_bit("d3"), 0);
+        assert_ne!(attacked & square_bit("f3"), 0);
+        assert_eq!(attacked & square_bit("d5"), 0);
+        assert_eq!(attacked & square_bit("e3"), 0);
+    }
+
+    #[test]
+    fn test_is_square_attacked_agrees_with_attacked_squares() {
+        let game = setup_game_with_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        );
+        let board = game.get_chess_board();
+
+        for color in [Color::White, Color::Black] {
+            let attacked = board.attacked_squares(color);
+            for square in 0..64 {
+                assert_eq!(
+                    board.is_square_attacked(square, color),
+                    attacked & (1 << square) != 0,
+                    "Square {} attacked by {:?}",
+                    square,
+                    color
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_checkers() {
+        let game = setup_game_with_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(game.get_chess_board().checkers(Color::White).is_empty());
+
+        // Knight check
+        let game = setup_game_with_fen("4k3/8/3N4/8/8/8/8/4K3 b - - 0 1");
+        assert_eq!(
+            square_names(game.get_chess_board().checkers(Color::Black)),
+            ["d6"]
+        );
+
+        // Double check by a rook and a bishop
+        let game = setup_game_with_fen("4k3/8/8/8/1b6/8/8/r3K3 w - - 0 1");
+        assert_eq!(
+            square_names(game.get_chess_board().checkers(Color::White)),
+            ["a1", "b4"]
+        );
+    }
+
+    #[test]
+    fn test_pinned_pieces() {
+        // Pinned on the file, the rank and both diagonals
+        let game = setup_game_with_fen("k3r3/8/b7/7b/2P1N3/5Q2/r2BK3/8 w - - 0 1");
+        let board = game.get_chess_board();
+        assert_eq!(
+            square_names(board.pinned(Color::White)),
+            ["d2", "f3", "c4", "e4"]
+        );
+        assert!(board.pinned(Color::Black).is_empty());
+    }
+
+    #[test]
+    fn test_pieces_shielded_twice_are_not_pinned() {
+        // Two pieces between the king and the rook, and a queen behind an
+        // enemy piece
+        let game = setup_game_with_fen("4k3/8/8/8/8/8/r1NBK1pq/8 w - - 0 1");
+        assert!(game.get_chess_board().pinned(Color::White).is_empty());
+    }
+}
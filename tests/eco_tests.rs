@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod eco_tests {
+    use enrust::game_state::GameState;
+
+    fn play(moves: &[&str]) -> GameState {
+        let mut game = GameState::new(None);
+        game.start_position();
+        for mv in moves {
+            assert!(game.make_move(mv), "illegal move {}", mv);
+        }
+        game
+    }
+
+    #[test]
+    fn test_starting_position_has_no_opening() {
+        assert_eq!(play(&[]).opening(), None);
+    }
+
+    #[test]
+    fn test_opening_follows_the_game() {
+        let game = play(&["e2e4"]);
+        assert_eq!(game.opening().unwrap().eco, "B00");
+
+        let game = play(&["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "g8f6"]);
+        let opening = game.opening().unwrap();
+        assert_eq!(opening.eco, "C65");
+        assert_eq!(opening.name, "Ruy Lopez, Berlin Defence");
+    }
+
+    #[test]
+    fn test_transpositions_are_classified() {
+        // Queen's Gambit Declined reached with 1.c4
+        let game = play(&["c2c4", "e7e6", "d2d4", "d7d5"]);
+        assert_eq!(game.opening().unwrap().eco, "D30");
+    }
+
+    #[test]
+    fn test_opening_kept_after_leaving_the_table() {
+        let mut game = play(&["e2e4", "c7c5", "g1f3", "d7d6"]);
+        assert_eq!(game.opening().unwrap().eco, "B50");
+
+        game.make_move("h2h3");
+        game.make_move("a7a6");
+        assert_eq!(game.opening().unwrap().eco, "B50");
+    }
+
+    #[test]
+    fn test_fen_positions_are_classified() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkb1r/1p2pppp/p2p1n2/8/3NP3/2N5/PPP2PPP/R1BQKB1R w KQkq - 0 6");
+        assert_eq!(game.opening().unwrap().eco, "B90");
+
+        game.set_fen_position("8/8/4k3/8/8/4K3/8/8 w - - 0 1");
+        assert_eq!(game.opening(), None);
+    }
+
+    #[test]
+    fn test_pgn_tags() {
+        let tags = play(&["d2d4", "d7d5", "c2c4", "c7c6"])
+            .opening()
+            .unwrap()
+            .pgn_tags();
+
+        assert_eq!(
+            tags,
+            vec![
+                ("ECO".to_string(), "D10".to_string()),
+                ("Opening".to_string(), "Slav Defence".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod endgame_tests {
+    use enrust::game_state::board::evaluation::endgame::{KNOWN_WIN, MaterialSignature};
+    use enrust::game_state::board::evaluation::kpk;
+    use enrust::game_state::{Color, GameState, Piece};
+
+    fn setup_game_with_fen(fen: &str) -> GameState {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game
+    }
+
+    fn evaluate(fen: &str) -> i32 {
+        setup_game_with_fen(fen)
+            .get_chess_board()
+            .evaluate()
+            .centipawns()
+    }
+
+    #[test]
+    fn test_signature_from_code() {
+        let signature = MaterialSignature::from_code("KBNK", Color::Black);
+
+        assert_eq!(signature.count(Piece::BlackKing), 1);
+        assert_eq!(signature.count(Piece::BlackBishop), 1);
+        assert_eq!(signature.count(Piece::BlackKnight), 1);
+        assert_eq!(signature.count(Piece::WhiteKing), 1);
+        assert_eq!(signature.count(Piece::WhiteBishop), 0);
+        assert_eq!(
+            setup_game_with_fen("8/8/8/3k4/8/8/2nb4/6K1 w - - 0 1")
+                .get_chess_board()
+                .material_signature(),
+            signature
+        );
+    }
+
+    #[test]
+    fn test_signature_follows_captures_and_promotions() {
+        let mut game = setup_game_with_fen("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+
+        // bxa8=Q captures the rook and promotes
+        assert!(game.make_move("b7a8q"));
+        let after = game.get_chess_board().material_signature();
+        assert_eq!(after, MaterialSignature::from_code("KQK", Color::White));
+        assert_eq!(
+            after,
+            setup_game_with_fen(&game.to_fen())
+                .get_chess_board()
+                .material_signature()
+        );
+
+        game.undo_last_move();
+        let before = game.get_chess_board().material_signature();
+        assert_eq!(before.count(Piece::WhitePawn), 1);
+        assert_eq!(before.count(Piece::BlackRook), 1);
+        assert_eq!(before.count(Piece::WhiteQueen), 0);
+    }
+
+    #[test]
+    fn test_krk_drives_the_king_to_the_edge() {
+        let center = evaluate("8/8/8/3k4/8/8/8/R3K3 w - - 0 1");
+        let edge = evaluate("3k4/8/8/8/8/8/8/R3K3 w - - 0 1");
+        let corner = evaluate("k7/8/8/8/8/8/8/R3K3 w - - 0 1");
+
+        assert!(center >= KNOWN_WIN.centipawns());
+        assert!(edge > center);
+        assert!(corner > edge);
+
+        // Same endgame for Black
+        assert_eq!(evaluate("r3k3/8/8/8/8/8/8/K7 w - - 0 1"), -corner);
+    }
+
+    #[test]
+    fn test_queen_ending_is_preferred_to_rook_ending() {
+        let queen = evaluate("8/8/8/3k4/8/8/8/Q3K3 w - - 0 1");
+        let rook = evaluate("8/8/8/3k4/8/8/8/R3K3 w - - 0 1");
+
+        assert!(queen > rook);
+    }
+
+    #[test]
+    fn test_evaluation_report_shows_endgame_term() {
+        let game = setup_game_with_fen("8/8/8/3k4/8/8/8/Q3K3 w - - 0 1");
+        let terms = game.get_chess_board().evaluation_breakdown();
+
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].0, "Endgame");
+        assert_eq!(terms[0].1, game.get_chess_board().evaluate());
+    }
+
+    #[test]
+    fn test_kbnk_drives_the_king_to_the_bishop_corner() {
+        // Light squared bishop on f1: a8 and h1 are the mating corners
+        let right_corner = evaluate("k7/8/8/8/8/8/8/4KBN1 w - - 0 1");
+        let wrong_corner = evaluate("7k/8/8/8/8/8/8/4KBN1 w - - 0 1");
+
+        assert!(wrong_corner >= KNOWN_WIN.centipawns());
+        assert!(right_corner > wrong_corner);
+    }
+
+    #[test]
+    fn test_unrecognized_material_keeps_generic_evaluation() {
+        let score = evaluate("8/8/8/3k4/8/8/8/RR2K3 w - - 0 1");
+
+        assert!(score > 0 && score < KNOWN_WIN.centipawns());
+    }
+
+    /// Standard square of a square name like `e4`.
+    fn square(name: &str) -> usize {
+        let bytes = name.as_bytes();
+        ((bytes[1] - b'1') * 8 + (bytes[0] - b'a')) as usize
+    }
+
+    fn kpk_wins(
+        strong: Color,
+        king: &str,
+        pawn: &str,
+        weak_king: &str,
+        side_to_move: Color,
+    ) -> Option<bool> {
+        kpk::probe(
+            strong,
+            square(king),
+            square(pawn),
+            square(weak_king),
+            side_to_move,
+        )
+    }
+
+    #[test]
+    fn test_kpk_known_positions() {
+        // King on the sixth rank in front of the pawn wins whoever moves
+        assert_eq!(
+            kpk_wins(Color::White, "e6", "e5", "e8", Color::White),
+            Some(true)
+        );
+        assert_eq!(
+            kpk_wins(Color::White, "e6", "e5", "e8", Color::Black),
+            Some(true)
+        );
+
+        // The side with the opposition decides
+        assert_eq!(
+            kpk_wins(Color::White, "d5", "d4", "d7", Color::White),
+            Some(false)
+        );
+        assert_eq!(
+            kpk_wins(Color::White, "d5", "d4", "d7", Color::Black),
+            Some(true)
+        );
+
+        // The defending king in front of a rook pawn holds
+        assert_eq!(
+            kpk_wins(Color::White, "b5", "a5", "a8", Color::White),
+            Some(false)
+        );
+        assert_eq!(
+            kpk_wins(Color::White, "b5", "a5", "a8", Color::Black),
+            Some(false)
+        );
+
+        // A pawn out of reach of the defending king promotes
+        assert_eq!(
+            kpk_wins(Color::White, "a1", "h5", "d5", Color::White),
+            Some(true)
+        );
+        assert_eq!(
+            kpk_wins(Color::White, "a1", "h5", "d5", Color::Black),
+            Some(false)
+        );
+
+        // Undefended pawn taken at once
+        assert_eq!(
+            kpk_wins(Color::White, "a1", "e4", "d5", Color::Black),
+            Some(false)
+        );
+
+        // Kings touching can't happen
+        assert_eq!(kpk_wins(Color::White, "e4", "e2", "e5", Color::White), None);
+    }
+
+    #[test]
+    fn test_kpk_mirrors_colors_and_files() {
+        // Same positions as above, mirrored for Black and onto the other wing
+        assert_eq!(
+            kpk_wins(Color::Black, "d4", "d5", "d2", Color::White),
+            Some(true)
+        );
+        assert_eq!(
+            kpk_wins(Color::Black, "d4", "d5", "d2", Color::Black),
+            Some(false)
+        );
+        assert_eq!(
+            kpk_wins(Color::White, "g5", "h5", "h8", Color::Black),
+            Some(false)
+        );
+        assert_eq!(
+            kpk_wins(Color::White, "e5", "e4", "e7", Color::Black),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_kpk_evaluation() {
+        // Won whoever moves
+        let won = evaluate("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1");
+        assert!(won > KNOWN_WIN.centipawns());
+        assert_eq!(evaluate("8/8/8/8/4p3/4k3/8/4K3 w - - 0 1"), -won);
+
+        // Drawn whoever moves
+        assert_eq!(evaluate("k7/8/8/PK6/8/8/8/8 w - - 0 1"), 0);
+
+        // Decided by the side to move: generic evaluation
+        let opposition = evaluate("8/3k4/8/3K4/3P4/8/8/8 w - - 0 1");
+        assert!(opposition > 0 && opposition < KNOWN_WIN.centipawns());
+    }
+
+    #[test]
+    fn test_kpk_evaluation_for_the_side_to_move() {
+        let evaluate_for = |fen: &str, side_to_move: Color| {
+            setup_game_with_fen(fen)
+                .get_chess_board()
+                .evaluate_for(side_to_move)
+                .centipawns()
+        };
+
+        // Won with White to move, drawn with Black to move
+        let fen = "8/8/8/4k3/8/8/4KP2/8 w - - 0 1";
+        assert!(evaluate_for(fen, Color::White) > KNOWN_WIN.centipawns());
+        assert_eq!(evaluate_for(fen, Color::Black), 0);
+    }
+
+    #[test]
+    fn test_kpk_pawn_on_a_back_rank_is_not_probed() {
+        assert_eq!(kpk_wins(Color::White, "a1", "e8", "h8", Color::White), None);
+        assert_eq!(kpk_wins(Color::White, "a1", "e1", "h8", Color::Black), None);
+        assert_eq!(kpk_wins(Color::Black, "a8", "e1", "h1", Color::White), None);
+
+        // Such positions fall back to the generic evaluation
+        assert!(evaluate("k7/8/8/8/8/8/8/KP6 w - - 0 1") > 0);
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod eval_params_tests {
+    use std::path::Path;
+
+    use enrust::game_state::GameState;
+    use enrust::game_state::board::evaluation::TaperedScore;
+    use enrust::game_state::board::evaluation::params::EvalParams;
+
+    const KNIGHT_UP: &str = "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1";
+
+    fn material(game: &GameState) -> i32 {
+        let breakdown = game.get_chess_board().evaluation_breakdown();
+        breakdown
+            .iter()
+            .find(|(name, _)| *name == "Material")
+            .map(|(_, score)| score.centipawns())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_defaults_round_trip_through_toml() {
+        let defaults = EvalParams::default();
+        assert_eq!(EvalParams::parse(&defaults.to_toml()).unwrap(), defaults);
+    }
+
+    #[test]
+    fn test_toml_overrides_some_parameters() {
+        let params = EvalParams::parse(
+            "# Stronger knights\n\
+             [material]\n\
+             knight = [320, 310] # mg, eg\n\
+             \n\
+             [passed_pawns]\n\
+             unstoppable = 650\n",
+        )
+        .unwrap();
+
+        assert_eq!(params.piece_values[1], TaperedScore::new(320, 310));
+        assert_eq!(params.unstoppable_passer, 650);
+        // The rest keeps the defaults
+        let defaults = EvalParams::default();
+        assert_eq!(params.piece_values[0], defaults.piece_values[0]);
+        assert_eq!(params.piece_square_mg, defaults.piece_square_mg);
+    }
+
+    #[test]
+    fn test_json_parameters() {
+        let params = EvalParams::parse(
+            r#"{
+                "material": { "bishop_pair": [40, 60] },
+                "trapped": { "knight": [-10, 0] },
+                "space": { "weight": 2 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(params.bishop_pair, TaperedScore::new(40, 60));
+        assert_eq!(params.cornered_knight, TaperedScore::new(-10, 0));
+        assert_eq!(params.space_weight, 2);
+    }
+
+    #[test]
+    fn test_piece_square_table_spans_lines() {
+        let mut toml = String::from("[piece_square]\nknight_mg = [\n");
+        for rank in 0..8 {
+            toml.push_str(&format!(
+                "    {},\n",
+                [rank; 8].map(|v| v.to_string()).join(", ")
+            ));
+        }
+        toml.push_str("]\n");
+
+        let params = EvalParams::parse(&toml).unwrap();
+        assert_eq!(params.piece_square_mg[1][0], 0);
+        assert_eq!(params.piece_square_mg[1][63], 7);
+    }
+
+    #[test]
+    fn test_invalid_parameters_are_reported() {
+        let unknown = EvalParams::parse("[material]\n\nknigth = [320, 300]\n").unwrap_err();
+        assert!(unknown.to_string().contains("line 3"));
+        assert!(unknown.to_string().contains("knigth"));
+
+        let count = EvalParams::parse("[material]\nknight = 320\n").unwrap_err();
+        assert!(count.to_string().contains("expects 2 values"));
+
+        let range = EvalParams::parse("[space]\nweight = 40000\n").unwrap_err();
+        assert!(range.to_string().contains("out of range"));
+
+        let json = EvalParams::parse("{\n  \"space\": { \"weight\": x }\n}").unwrap_err();
+        assert!(json.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_game_state_loads_parameter_file() {
+        let path =
+            std::env::temp_dir().join(format!("enrust_eval_params_{}.toml", std::process::id()));
+        std::fs::write(&path, "[material]\nknight = [400, 400]\n").unwrap();
+
+        let mut game = GameState::new(None);
+        game.set_fen_position(KNIGHT_UP);
+        let default_material = material(&game);
+
+        game.set_eval_params_file(Some(&path)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(material(&game), default_material + 100);
+
+        // A missing file keeps the weights in use
+        assert!(
+            game.set_eval_params_file(Some(Path::new("missing_params.toml")))
+                .is_err()
+        );
+        assert_eq!(material(&game), default_material + 100);
+
+        game.set_eval_params_file(None).unwrap();
+        assert_eq!(material(&game), default_material);
+        assert_eq!(game.eval_params(), &EvalParams::default());
+    }
+}
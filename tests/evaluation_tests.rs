@@ -0,0 +1,372 @@
+#[cfg(test)]
+mod evaluation_tests {
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::Score;
+    use enrust::game_state::board::evaluation::{EvaluationTerms, TEMPO_BONUS, TOTAL_PHASE};
+
+    fn evaluate(fen: &str) -> i32 {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game.get_chess_board().evaluate().centipawns()
+    }
+
+    fn game_phase(fen: &str) -> i16 {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game.get_chess_board().game_phase().value()
+    }
+
+    #[test]
+    fn test_game_phase_reference_positions() {
+        // All pieces on the board
+        assert_eq!(
+            game_phase("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            TOTAL_PHASE
+        );
+        assert_eq!(
+            game_phase("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"),
+            TOTAL_PHASE
+        );
+
+        // Queens traded: 256 - 2 * 40
+        assert_eq!(
+            game_phase("rnb1kbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNB1KBNR w KQkq - 0 3"),
+            176
+        );
+
+        // Rook and minor piece each: 2 * 20 + 2 * 12
+        assert_eq!(game_phase("4k3/3n1r2/8/8/8/8/2B1R3/4K3 w - - 0 1"), 64);
+
+        // Rook endgame
+        assert_eq!(game_phase("8/8/4k3/8/2r5/8/3R4/4K3 w - - 0 1"), 40);
+
+        // Pawn endgame
+        assert_eq!(game_phase("8/8/4k3/4p3/4P3/8/8/4K3 w - - 0 1"), 0);
+    }
+
+    #[test]
+    fn test_game_phase_is_clamped_after_promotions() {
+        // Queens in place of the queenside pieces, above the starting material
+        assert_eq!(
+            game_phase("qqqqkbnr/pppppppp/8/8/8/8/PPPPPPPP/QQQQKBNR w - - 0 1"),
+            TOTAL_PHASE
+        );
+    }
+
+    #[test]
+    fn test_passed_pawn_detection() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("4k3/8/2p5/8/1P4P1/8/7p/4K3 w - - 0 1");
+        let board = game.get_chess_board();
+
+        // The b4 and c6 pawns stand in front of each other on adjacent files
+        assert!(!board.is_passed_pawn(25, Color::White));
+        assert!(!board.is_passed_pawn(42, Color::Black));
+
+        // Nothing stands in front of g4 and h2
+        assert!(board.is_passed_pawn(30, Color::White));
+        assert!(board.is_passed_pawn(15, Color::Black));
+    }
+
+    #[test]
+    fn test_unstoppable_passed_pawn() {
+        // The king on h8 is outside the square of the a4 pawn
+        assert!(evaluate("7k/8/8/8/P7/8/8/K7 w - - 0 1") > 500);
+        assert!(evaluate("7K/8/8/8/p7/8/8/7k w - - 0 1") < -500);
+
+        // The king on c5 catches the pawn
+        assert!(evaluate("8/8/8/2k5/P7/8/8/K7 w - - 0 1").abs() < 200);
+
+        // A pawn on its starting rank can move two squares
+        assert!(evaluate("7k/8/8/8/8/8/P7/K7 w - - 0 1") > 500);
+        assert!(evaluate("8/5k2/8/8/8/8/P7/K7 w - - 0 1").abs() < 200);
+
+        // The own king in front of the pawn blocks it. The blocked h pawns
+        // keep the position out of the king and pawn bitbase
+        assert!(evaluate("K6k/8/8/8/P7/7p/7P/8 w - - 0 1").abs() < 200);
+
+        // Only kings and pawns: with a knight left it isn't a pure race
+        assert!(evaluate("7k/8/8/8/P7/8/8/K5n1 w - - 0 1") < 500);
+    }
+
+    #[test]
+    fn test_first_pawn_to_promote_wins_the_race() {
+        // Both pawns are unstoppable, the side that needs fewer moves wins
+        assert!(evaluate("7k/8/P7/8/8/8/7p/K7 w - - 0 1") < -500);
+        assert!(evaluate("7k/P7/8/8/8/6p1/8/K7 w - - 0 1") > 500);
+
+        // Equal races cancel out
+        assert!(evaluate("7k/8/P7/8/8/7p/8/K7 w - - 0 1").abs() < 200);
+    }
+
+    #[test]
+    fn test_evaluate_cp_is_side_relative() {
+        let mut white = GameState::new(None);
+        white.set_fen_position("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+        let mut black = GameState::new(None);
+        black.set_fen_position("4k3/8/8/8/8/8/8/3QK3 b - - 0 1");
+
+        assert!(white.evaluate_cp() > 800);
+        assert_eq!(black.evaluate_cp(), -white.evaluate_cp());
+    }
+
+    #[test]
+    fn test_evaluation_breakdown_adds_up() {
+        let mut game = GameState::new(None);
+        game.set_fen_position(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+        );
+        let board = game.get_chess_board();
+
+        let terms = board.evaluation_breakdown();
+        let names: Vec<&str> = terms.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Material",
+                "Piece-square",
+                "Passed pawns",
+                "Trapped pieces",
+                "Space",
+                "Center control",
+                "King tropism",
+                "Weak squares"
+            ]
+        );
+
+        let total: i32 = terms.iter().map(|(_, score)| score.centipawns()).sum();
+        assert_eq!(total, board.evaluate().centipawns());
+        assert_eq!(total + TEMPO_BONUS.centipawns(), game.evaluate_cp());
+    }
+
+    #[test]
+    fn test_evaluation_for_side_to_move() {
+        let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        let board = game.get_chess_board();
+        let white = board.evaluate();
+
+        // Both sides see the same position, each with the tempo bonus
+        assert_eq!(board.evaluate_for(Color::White), white + TEMPO_BONUS);
+        assert_eq!(board.evaluate_for(Color::Black), -white + TEMPO_BONUS);
+
+        // A known draw gets no bonus
+        game.set_fen_position("k7/8/8/PK6/8/8/8/8 w - - 0 1");
+        assert_eq!(
+            game.get_chess_board().evaluate_for(Color::Black),
+            Score::DRAW
+        );
+    }
+
+    #[test]
+    fn test_evaluation_report() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("4k3/8/8/8/8/8/4P3/3QK3 b - - 0 1");
+        let report = game.evaluation_report();
+
+        assert!(report.contains("Material"));
+        assert!(report.contains(&format!(
+            "Evaluation: {} cp (Black to move)",
+            game.evaluate_cp()
+        )));
+    }
+
+    #[test]
+    fn test_evaluation_report_aligns_the_score_column() {
+        let mut game = GameState::new(None);
+        // Trapped bishop, so every term has a row
+        game.set_fen_position("r2qkbnr/Bbp2ppp/1pnp4/4p3/4P3/2N2N2/PPP2PPP/R2QKB1R w KQkq - 0 8");
+        let report = game.evaluation_report();
+
+        let table: Vec<&str> = report
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect();
+        assert!(table.iter().any(|line| line.contains("Trapped pieces")));
+        assert!(table.iter().any(|line| line.contains("Center control")));
+
+        let column = table[0].find('|').expect("header has a separator");
+        for line in &table {
+            assert_eq!(
+                line.find(['|', '+']),
+                Some(column),
+                "misaligned: {:?}",
+                line
+            );
+        }
+    }
+
+    fn term(game: &GameState, name: &str) -> i32 {
+        game.get_chess_board()
+            .evaluation_breakdown()
+            .iter()
+            .find(|(term, _)| *term == name)
+            .map_or(0, |(_, score)| score.centipawns())
+    }
+
+    #[test]
+    fn test_space_rewards_advanced_pawn_chain() {
+        let mut game = GameState::new(None);
+        // White pawns on d5 and e4 gain space behind them
+        game.set_fen_position(
+            "r1bqkb1r/ppp2ppp/2np1n2/3Pp3/4P3/2N2N2/PPP2PPP/R1BQKB1R b KQkq - 0 6",
+        );
+        assert!(term(&game, "Space") > 0);
+
+        // Without minor pieces, space is worth nothing
+        game.set_fen_position("r2qk2r/ppp2ppp/3p4/3Pp3/4P3/8/PPP2PPP/R2QK2R w KQkq - 0 10");
+        assert_eq!(term(&game, "Space"), 0);
+
+        // The term is symmetric
+        game.set_fen_position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(term(&game, "Space"), 0);
+    }
+
+    #[test]
+    fn test_center_control_only_in_the_opening() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppp1ppp/8/4p3/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2");
+        assert!(term(&game, "Center control") > 0);
+
+        // Same pawns with most pieces traded
+        game.set_fen_position("4k3/pppp1ppp/8/4p3/3PP3/8/PPP2PPP/R3K3 b - - 0 20");
+        assert_eq!(term(&game, "Center control"), 0);
+    }
+
+    #[test]
+    fn test_evaluation_terms_can_be_disabled() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppp1ppp/8/4p3/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2");
+        let with_terms = game.evaluate_cp();
+
+        game.set_evaluation_terms(EvaluationTerms {
+            space: false,
+            center_control: false,
+            king_tropism: false,
+            weak_squares: false,
+        });
+        let names: Vec<&str> = game
+            .get_chess_board()
+            .evaluation_breakdown()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Material", "Piece-square", "Passed pawns", "Trapped pieces"]
+        );
+        assert_ne!(game.evaluate_cp(), with_terms);
+    }
+
+    #[test]
+    fn test_attack_map_counts_attackers() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let attacks = game.get_chess_board().attack_map();
+
+        // d2 is defended by the knight, bishop, queen and king, f3 by the
+        // knight and two pawns
+        assert_eq!(attacks.count(Color::White, 11), 4);
+        assert_eq!(attacks.count(Color::White, 21), 3);
+        assert_eq!(attacks.count(Color::Black, 51), 4);
+        assert_eq!(attacks.attacked(Color::White), 0x0000_0000_00ff_ff7e);
+        assert_eq!(attacks.pawn_attacks(Color::White), 0x0000_0000_00ff_0000);
+
+        // Blocked rays stop at the first piece
+        game.set_fen_position("4k3/8/8/8/R2p4/8/8/4K3 w - - 0 1");
+        let attacks = game.get_chess_board().attack_map();
+        assert_eq!(attacks.count(Color::White, 27), 1);
+        assert_eq!(attacks.count(Color::White, 28), 0);
+        assert_eq!(
+            attacks.diagram(Color::Black).lines().nth(7),
+            Some(". . . . . . . .")
+        );
+    }
+
+    #[test]
+    fn test_king_tropism_penalizes_attacks_on_the_king() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(term(&game, "King tropism"), 0);
+
+        // The queen and the bishop aim at the castled black king
+        game.set_fen_position(
+            "r1bq1rk1/ppp2ppp/2np1n2/4p3/2B1P1Q1/2NP4/PPP2PPP/R1B1K2R w KQ - 0 8",
+        );
+        assert!(term(&game, "King tropism") > 0);
+
+        // The other way around, the penalty goes to White
+        game.set_fen_position(
+            "r1b1k2r/ppp2ppp/2np4/2b1p1q1/4P3/2NP1N2/PPP2PPP/R1BQ1RK1 b kq - 0 8",
+        );
+        assert!(term(&game, "King tropism") < 0);
+    }
+
+    #[test]
+    fn test_weak_squares_in_own_half() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(term(&game, "Weak squares"), 0);
+
+        // Without its central pawns, White can't chase the knight from c4,
+        // d3 or g4
+        game.set_fen_position("4k3/pp3ppp/8/4n3/8/8/PP3PPP/4K3 w - - 0 1");
+        assert!(term(&game, "Weak squares") < 0);
+    }
+
+    #[test]
+    fn test_evaluation_report_draws_the_attack_maps() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let report = game.evaluation_report();
+
+        assert!(report.contains("Attacks:  White            Black"));
+        // Rank 3 of both maps
+        assert!(report.contains("2 2 3 2 2 3 2 2  . . . . . . . ."));
+    }
+
+    #[test]
+    fn test_trapped_bishop() {
+        let mut game = GameState::new(None);
+        // Bxa7 b6 shuts the bishop in
+        game.set_fen_position("r2qkbnr/Bbp2ppp/1pnp4/4p3/4P3/2N2N2/PPP2PPP/R2QKB1R w KQkq - 0 8");
+        assert!(term(&game, "Trapped pieces") < 0);
+
+        // Mirrored for Black: the bishop on h2 is shut in by g3
+        game.set_fen_position("rnbqk2r/ppp2ppp/3p1n2/4p3/8/6P1/PPPPPP1b/RNBQKBNR w KQkq - 0 6");
+        assert!(term(&game, "Trapped pieces") > 0);
+
+        // Without the pawn on b6 the bishop can get out
+        game.set_fen_position("r2qkbnr/Bbp2ppp/2np4/4p3/4P3/2N2N2/PPP2PPP/R2QKB1R w KQkq - 0 8");
+        assert_eq!(term(&game, "Trapped pieces"), 0);
+    }
+
+    #[test]
+    fn test_cornered_knight() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("N3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+        assert!(term(&game, "Trapped pieces") < 0);
+
+        game.set_fen_position("4k3/8/8/8/8/8/8/4K2n w - - 0 1");
+        assert!(term(&game, "Trapped pieces") > 0);
+    }
+
+    #[test]
+    fn test_boxed_rook() {
+        let mut game = GameState::new(None);
+        // The king walked to f1 and can't castle anymore
+        game.set_fen_position("r3k2r/pppq1ppp/2n2n2/3pp3/3PP3/2N2N2/PPPQ1PPP/R4K1R w kq - 0 10");
+        assert!(term(&game, "Trapped pieces") < 0);
+
+        // The same squares after castling are fine
+        game.set_fen_position("r3k2r/pppq1ppp/2n2n2/3pp3/3PP3/2N2N2/PPPQ1PPP/R4RK1 w kq - 0 10");
+        assert_eq!(term(&game, "Trapped pieces"), 0);
+
+        // While the king can still castle, the rook isn't boxed in
+        game.set_fen_position("r3k2r/pppq1ppp/2n2n2/3pp3/3PP3/2N2N2/PPPQ1PPP/R3K2R w KQkq - 0 10");
+        assert_eq!(term(&game, "Trapped pieces"), 0);
+    }
+}
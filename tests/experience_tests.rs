@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod experience_tests {
+    use std::path::{Path, PathBuf};
+    use std::thread;
+
+    use enrust::game_state::Color;
+    use enrust::game_state::Score;
+    use enrust::game_state::experience::Experience;
+    use enrust::game_state::pgn::GameResult;
+    use enrust::game_state::{GameState, SearchConfiguration};
+
+    const START_KEY: u64 = 0x463b96181691fc9c;
+
+    fn temp_experience_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("enrust_{}_{}.exp", name, std::process::id()));
+        std::fs::remove_file(&path).ok();
+        path
+    }
+
+    fn setup_experience(path: &Path) -> Experience {
+        let mut experience = Experience::new();
+        experience.set_path(path).unwrap();
+        experience.set_enabled(true).unwrap();
+        experience
+    }
+
+    #[test]
+    fn test_disabled_experience_records_nothing() {
+        let path = temp_experience_path("disabled");
+        let mut experience = Experience::new();
+        experience.set_path(&path).unwrap();
+
+        experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+        experience.finish_game(GameResult::WhiteWins).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(experience.best_move(START_KEY), None);
+    }
+
+    #[test]
+    fn test_results_are_saved_and_reloaded() {
+        let path = temp_experience_path("reload");
+        let mut experience = setup_experience(&path);
+
+        experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+        experience.record(0x823c9b50fd114196, "e7e5", Score::cp(-20), Color::Black);
+        experience.finish_game(GameResult::WhiteWins).unwrap();
+
+        let reloaded = setup_experience(&path);
+        std::fs::remove_file(&path).ok();
+
+        let entry = &reloaded.entries(START_KEY)[0];
+        assert_eq!(entry.mv, "e2e4");
+        assert_eq!(entry.score, Score::cp(30));
+        assert_eq!((entry.wins, entry.draws, entry.losses), (1, 0, 0));
+
+        let entry = &reloaded.entries(0x823c9b50fd114196)[0];
+        assert_eq!((entry.wins, entry.draws, entry.losses), (0, 0, 1));
+    }
+
+    #[test]
+    fn test_best_move_prefers_winning_moves() {
+        let path = temp_experience_path("best_move");
+        let mut experience = setup_experience(&path);
+
+        for result in [GameResult::WhiteWins, GameResult::Draw] {
+            experience.record(START_KEY, "d2d4", Score::cp(20), Color::White);
+            experience.finish_game(result).unwrap();
+        }
+        for _ in 0..2 {
+            experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+            experience.finish_game(GameResult::BlackWins).unwrap();
+        }
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(experience.best_move(START_KEY), Some("d2d4"));
+    }
+
+    #[test]
+    fn test_single_game_is_not_enough_to_replay_a_move() {
+        let path = temp_experience_path("min_games");
+        let mut experience = setup_experience(&path);
+
+        experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+        experience.finish_game(GameResult::WhiteWins).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(experience.best_move(START_KEY), None);
+    }
+
+    #[test]
+    fn test_unknown_result_is_adjudicated_from_last_score() {
+        let path = temp_experience_path("adjudication");
+        let mut experience = setup_experience(&path);
+
+        experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+        // Black thinks it is lost
+        experience.record(0x823c9b50fd114196, "e7e5", Score::cp(-500), Color::Black);
+        assert_eq!(experience.last_score(), Some(Score::cp(500)));
+        experience.finish_game(GameResult::Unknown).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(experience.entries(START_KEY)[0].wins, 1);
+        assert_eq!(experience.entries(0x823c9b50fd114196)[0].losses, 1);
+    }
+
+    #[test]
+    fn test_concurrent_writers_merge_their_games() {
+        let path = temp_experience_path("concurrent");
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    let mut experience = setup_experience(&path);
+                    for _ in 0..5 {
+                        experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+                        experience.finish_game(GameResult::Draw).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let experience = setup_experience(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(experience.entries(START_KEY).len(), 1);
+        assert_eq!(experience.entries(START_KEY)[0].draws, 20);
+    }
+
+    #[test]
+    fn test_lock_left_by_a_crashed_writer_is_not_held() {
+        let path = temp_experience_path("stale_lock");
+        let lock_path = path.with_extension("exp.lock");
+        // Lock file of a writer that crashed while saving
+        std::fs::write(&lock_path, "4194304\n").unwrap();
+
+        let mut experience = setup_experience(&path);
+        experience.record(START_KEY, "e2e4", Score::cp(30), Color::White);
+        let saved = experience.finish_game(GameResult::WhiteWins);
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(&lock_path).ok();
+
+        saved.unwrap();
+    }
+
+    /// Records a move as won in enough games to be preferred.
+    fn learn_winning_move(game: &GameState, key: u64, mv: &str, side: Color) {
+        for _ in 0..2 {
+            let mut experience = game.experience().lock().unwrap();
+            experience.record(key, mv, Score::cp(30), side);
+            experience.finish_game(GameResult::WhiteWins).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_learned_move_is_searched() {
+        let path = temp_experience_path("searched");
+        let mut game = GameState::new(Some(16));
+        game.set_experience_file(&path).unwrap();
+        game.set_learning_enabled(true).unwrap();
+        learn_winning_move(&game, START_KEY, "e2e4", Color::White);
+        std::fs::remove_file(&path).ok();
+        game.start_position();
+        game.set_max_depth(2);
+
+        // The move is searched, and played as it is about as good as any
+        let mut timed = SearchConfiguration::new();
+        timed.movetime = Some(60_000);
+        game.set_time_control(&timed);
+        let result = game.search().join();
+        assert_eq!(result.depth, 2);
+        assert_eq!(result.best_move.as_deref(), Some("e2e4"));
+
+        let mut infinite = SearchConfiguration::new();
+        infinite.infinite = true;
+        game.set_time_control(&infinite);
+        assert_eq!(game.search().join().depth, 2);
+    }
+
+    #[test]
+    fn test_learned_blunder_is_not_played() {
+        let path = temp_experience_path("blunder");
+        let mut game = GameState::new(Some(16));
+        game.set_experience_file(&path).unwrap();
+        game.set_learning_enabled(true).unwrap();
+        // Qd5 hangs the queen to exd5
+        assert!(game.set_fen_position("4k3/8/4p3/8/8/8/8/3QK3 w - - 0 1"));
+        learn_winning_move(&game, game.polyglot_key(), "d1d5", Color::White);
+        std::fs::remove_file(&path).ok();
+        game.set_max_depth(3);
+
+        let mut timed = SearchConfiguration::new();
+        timed.movetime = Some(60_000);
+        game.set_time_control(&timed);
+        let result = game.search().join();
+
+        assert_eq!(result.depth, 3);
+        assert_ne!(result.best_move.as_deref(), Some("d1d5"));
+    }
+
+    #[test]
+    fn test_finish_game_uses_checkmate_result() {
+        let path = temp_experience_path("checkmate");
+        let mut game = GameState::new(None);
+        game.set_experience_file(&path).unwrap();
+        game.set_learning_enabled(true).unwrap();
+
+        game.start_position();
+        let moves = ["f2f3", "e7e5", "g2g4", "d8h4"];
+        for mv in moves {
+            let key = game.polyglot_key();
+            let side = game.side_to_move();
+            game.experience()
+                .lock()
+                .unwrap()
+                .record(key, mv, Score::ZERO, side);
+            assert!(game.make_move(mv));
+        }
+
+        game.finish_game().unwrap();
+
+        let experience = setup_experience(&path);
+        std::fs::remove_file(&path).ok();
+
+        let entry = &experience.entries(START_KEY)[0];
+        assert_eq!(entry.mv, "f2f3");
+        assert_eq!(entry.losses, 1);
+    }
+}
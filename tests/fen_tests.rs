@@ -0,0 +1,306 @@
+#[cfg(test)]
+mod fen_tests {
+    use enrust::game_state::{Color, GameState, uci};
+
+    fn setup_game_with_fen(fen: &str) -> GameState {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game
+    }
+
+    fn sorted_moves(game: &mut GameState) -> Vec<String> {
+        let mut moves = game.generate_moves();
+        moves.sort();
+        moves
+    }
+
+    #[test]
+    fn test_fen_without_clocks_defaults_to_zero_one() {
+        let mut game = GameState::new(None);
+
+        assert!(game.set_fen_position("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq -"));
+        assert_eq!(game.side_to_move(), Color::Black);
+        assert_eq!(game.halfmove_clock(), 0);
+        assert_eq!(game.fullmove_number(), 1);
+    }
+
+    #[test]
+    fn test_fen_without_clocks_matches_full_fen() {
+        let mut partial = setup_game_with_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq -");
+        let mut full = setup_game_with_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1");
+
+        assert_eq!(sorted_moves(&mut partial), sorted_moves(&mut full));
+    }
+
+    #[test]
+    fn test_fen_with_only_placement_and_side() {
+        let mut game = GameState::new(None);
+
+        assert!(game.set_fen_position("4k3/8/8/8/8/8/8/R3K3 w"));
+
+        // Castling rights default to none
+        let moves = game.generate_moves();
+        assert!(!moves.contains(&"e1c1".to_string()));
+    }
+
+    #[test]
+    fn test_fen_clocks_are_stored() {
+        let mut game = GameState::new(None);
+
+        assert!(game.set_fen_position("4k3/8/8/8/8/8/8/4K3 b - - 12 34"));
+        assert_eq!(game.halfmove_clock(), 12);
+        assert_eq!(game.fullmove_number(), 34);
+    }
+
+    #[test]
+    fn test_fen_dash_clears_en_passant() {
+        let mut game = GameState::new(None);
+
+        game.set_fen_position("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        assert!(game.generate_moves().contains(&"e5d6".to_string()));
+
+        game.set_fen_position("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1");
+        assert!(!game.generate_moves().contains(&"e5d6".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_fen_keeps_previous_position() {
+        let mut game = setup_game_with_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        let before = sorted_moves(&mut game);
+
+        assert!(!game.set_fen_position("4k3/8/8/8/8/8/8/4K3 x - - 0 1"));
+        assert!(!game.set_fen_position("4k3/8/8/8/8/8/8/4K3 w KX - 0 1"));
+        assert!(!game.set_fen_position("4k3/8/8/8/8/8/8/4K3 w - z9 0 1"));
+        assert!(!game.set_fen_position("4k3/8/8/8/8/8/8/4K3 w - - a 1"));
+        assert!(!game.set_fen_position("4k3/8/8/4K3 w - - 0 1"));
+        assert!(!game.set_fen_position(""));
+
+        assert_eq!(game.side_to_move(), Color::White);
+        assert_eq!(sorted_moves(&mut game), before);
+    }
+
+    #[test]
+    fn test_clocks_advance_with_moves() {
+        let mut game = GameState::new(None);
+        game.start_position();
+
+        assert!(game.make_move("g1f3"));
+        assert_eq!(game.halfmove_clock(), 1);
+        assert_eq!(game.fullmove_number(), 1);
+
+        assert!(game.make_move("g8f6"));
+        assert_eq!(game.halfmove_clock(), 2);
+        assert_eq!(game.fullmove_number(), 2);
+
+        assert!(game.make_move("e2e4"));
+        assert_eq!(game.halfmove_clock(), 0);
+        assert!(!game.make_move("xyz"));
+    }
+
+    #[test]
+    fn test_position_startpos_with_empty_moves() {
+        let mut game = GameState::new(None);
+        uci::handle_position_command(&mut game, &mut "startpos moves".split_whitespace());
+
+        assert_eq!(game.side_to_move(), Color::White);
+        assert_eq!(game.generate_moves().len(), 20);
+    }
+
+    #[test]
+    fn test_position_startpos_with_stray_whitespace() {
+        let mut game = GameState::new(None);
+        let mut reference = GameState::new(None);
+        reference.start_position();
+        reference.make_move("e2e4");
+        reference.make_move("e7e5");
+
+        uci::handle_position_command(
+            &mut game,
+            &mut "  startpos   moves  e2e4 \t e7e5  ".split_whitespace(),
+        );
+
+        assert_eq!(game.side_to_move(), Color::White);
+        assert_eq!(sorted_moves(&mut game), sorted_moves(&mut reference));
+    }
+
+    #[test]
+    fn test_position_partial_fen_with_moves() {
+        let mut game = GameState::new(None);
+        uci::handle_position_command(
+            &mut game,
+            &mut "fen 4k3/8/8/8/8/8/4P3/4K3 w - moves e2e4".split_whitespace(),
+        );
+
+        assert_eq!(game.side_to_move(), Color::Black);
+        assert_eq!(game.fullmove_number(), 1);
+    }
+
+    #[test]
+    fn test_position_fen_with_empty_moves() {
+        let mut game = GameState::new(None);
+        uci::handle_position_command(
+            &mut game,
+            &mut "fen 4k3/8/8/8/8/8/4P3/4K3 b - - 0 1 moves".split_whitespace(),
+        );
+
+        assert_eq!(game.side_to_move(), Color::Black);
+    }
+
+    #[test]
+    fn test_position_current_continues_from_current_position() {
+        let mut game = GameState::new(None);
+        let mut reference = GameState::new(None);
+        uci::handle_position_command(
+            &mut reference,
+            &mut "startpos moves e2e4 e7e5 g1f3".split_whitespace(),
+        );
+
+        uci::handle_position_command(&mut game, &mut "startpos moves e2e4".split_whitespace());
+        uci::handle_position_command(&mut game, &mut "current moves e7e5 g1f3".split_whitespace());
+
+        assert_eq!(game.to_fen(), reference.to_fen());
+
+        // Without moves the position is kept
+        uci::handle_position_command(&mut game, &mut "current".split_whitespace());
+        assert_eq!(game.to_fen(), reference.to_fen());
+    }
+
+    #[test]
+    fn test_position_stops_at_illegal_move() {
+        let mut game = GameState::new(None);
+        uci::handle_position_command(
+            &mut game,
+            &mut "startpos moves e2e4 e2e4 e7e5".split_whitespace(),
+        );
+
+        // Only the first move is applied
+        assert_eq!(game.side_to_move(), Color::Black);
+        assert!(game.generate_moves().contains(&"e7e5".to_string()));
+    }
+
+    #[test]
+    fn test_to_fen_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 12 40",
+            "r3k3/8/8/8/8/8/8/4K2R b Kq - 3 25",
+        ];
+
+        for fen in fens {
+            assert_eq!(setup_game_with_fen(fen).to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn test_to_fen_after_moves() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        game.make_move("e2e4");
+        game.make_move("g8f6");
+
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2"
+        );
+    }
+
+    #[test]
+    fn test_position_dump() {
+        let game =
+            setup_game_with_fen("rnbqkbnr/ppp1pppp/8/1B1p4/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 2");
+
+        let dump = game.position_dump(false);
+        assert!(dump.contains(" | r | n | b | q | k | b | n | r | 8"));
+        assert!(dump.contains("   a   b   c   d   e   f   g   h"));
+        assert!(
+            dump.contains("Fen: rnbqkbnr/ppp1pppp/8/1B1p4/4P3/8/PPPP1PPP/RNBQK1NR b KQkq - 1 2")
+        );
+        assert!(dump.contains(&format!("Key: {:016X}", game.polyglot_key())));
+        assert!(dump.contains("Checkers: b5"));
+
+        let flipped = game.position_dump(true);
+        assert!(flipped.contains(" | R | N |   | K | Q | B | N | R | 1"));
+        assert!(flipped.contains("   h   g   f   e   d   c   b   a"));
+    }
+
+    #[test]
+    fn test_shredder_and_x_fen_castling_rights() {
+        let standard = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        let expected = sorted_moves(&mut setup_game_with_fen(standard));
+
+        for fen in [
+            "r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w AHah - 0 1",
+            "r3k2r/8/8/8/8/8/8/R3K2R w KAhq - 0 1",
+        ] {
+            let mut game = GameState::new(None);
+            assert!(game.set_fen_position(fen), "Rejected FEN: {}", fen);
+            assert_eq!(game.to_fen(), standard);
+            assert_eq!(sorted_moves(&mut game), expected);
+        }
+
+        // X-FEN letters name the outermost rook
+        let mut game = GameState::new(None);
+        assert!(game.set_fen_position("r3k1r1/8/8/8/8/8/8/R3K2R b Kq - 0 1"));
+        assert_eq!(game.to_fen(), "r3k1r1/8/8/8/8/8/8/R3K2R b Kq - 0 1");
+    }
+
+    #[test]
+    fn test_castling_with_rooks_off_the_corners() {
+        // Chess960 setups, castling with rooks away from the corners
+        for fen in [
+            "1r2k1r1/pppppppp/8/8/8/8/PPPPPPPP/1R2K1R1 w BGbg - 0 1",
+            "1r2k1r1/pppppppp/8/8/8/8/PPPPPPPP/1R2K1R1 w KQkq - 0 1",
+        ] {
+            let mut game = setup_game_with_fen(fen);
+            assert!(game.make_move("e1g1"));
+            assert!(game.make_move("e8c8"));
+            assert_eq!(
+                game.to_fen(),
+                "2kr2r1/pppppppp/8/8/8/8/PPPPPPPP/1R3RK1 w - - 2 2"
+            );
+        }
+
+        // The king may castle onto its own rook's square
+        let mut game = setup_game_with_fen("4k3/8/8/8/8/8/8/5KR1 w G - 0 1");
+        assert!(game.make_move("f1g1"));
+        assert_eq!(game.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+        // Shredder letters need the king to tell the sides apart
+        let mut game = GameState::new(None);
+        assert!(!game.set_fen_position("4k3/8/8/8/8/8/8/R6R w HA - 0 1"));
+    }
+
+    #[test]
+    fn test_chess960_writes_shredder_fen() {
+        let mut game = setup_game_with_fen("r3k3/8/8/8/8/8/8/4K2R b Kq - 3 25");
+        game.set_chess960(true);
+        assert_eq!(game.to_fen(), "r3k3/8/8/8/8/8/8/4K2R b Ha - 3 25");
+
+        // The exported FEN reads back to the same position
+        let fen = game.to_fen();
+        assert!(game.set_fen_position(&fen));
+        assert_eq!(game.to_fen(), fen);
+
+        game.set_chess960(false);
+        assert_eq!(game.to_fen(), "r3k3/8/8/8/8/8/8/4K2R b Kq - 3 25");
+    }
+
+    #[test]
+    fn test_non_ascii_squares_are_rejected() {
+        let mut game = GameState::new(None);
+
+        assert!(!game.set_fen_position("4k3/8/8/8/8/8/8/4K3 w - é 0 1"));
+        uci::handle_position_command(
+            &mut game,
+            &mut "startpos moves e2e4 é7e5 e7e5".split_whitespace(),
+        );
+
+        // The move cut inside a character stops the list like an illegal one
+        assert_eq!(game.side_to_move(), Color::Black);
+        assert!(!game.make_move("e2é4"));
+        assert!(!game.make_move("e7é5"));
+    }
+}
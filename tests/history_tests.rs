@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod history_tests {
+    use enrust::game_state::{Color, GameState};
+
+    fn play(game: &mut GameState, moves: &str) {
+        for mv in moves.split_whitespace() {
+            assert!(game.make_move(mv), "Could not play {}", mv);
+        }
+    }
+
+    #[test]
+    fn test_undo_restores_position_and_clocks() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        play(&mut game, "e2e4 e7e5 g1f3");
+        let before = game.to_fen();
+        play(&mut game, "b8c6");
+
+        assert_eq!(game.undo_last_move(), Some("b8c6".to_string()));
+        assert_eq!(game.to_fen(), before);
+        assert_eq!(game.side_to_move(), Color::Black);
+
+        assert_eq!(game.undo_last_move(), Some("g1f3".to_string()));
+        assert_eq!(game.undo_last_move(), Some("e7e5".to_string()));
+        assert_eq!(game.undo_last_move(), Some("e2e4".to_string()));
+        assert_eq!(
+            game.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+        assert_eq!(game.undo_last_move(), None);
+    }
+
+    #[test]
+    fn test_undo_capture_and_castling() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("r3k2r/8/8/3p4/4P3/8/8/R3K2R w KQkq - 5 20");
+        let start = game.to_fen();
+
+        play(&mut game, "e4d5 e8c8 e1g1");
+        for _ in 0..3 {
+            assert!(game.undo_last_move().is_some());
+        }
+
+        assert_eq!(game.to_fen(), start);
+    }
+
+    #[test]
+    fn test_redo_replays_undone_moves() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        play(&mut game, "d2d4 d7d5");
+        let after = game.to_fen();
+
+        game.undo_last_move();
+        game.undo_last_move();
+
+        assert_eq!(game.redo_move(), Some("d2d4".to_string()));
+        assert_eq!(game.redo_move(), Some("d7d5".to_string()));
+        assert_eq!(game.to_fen(), after);
+        assert_eq!(game.redo_move(), None);
+    }
+
+    #[test]
+    fn test_new_move_or_position_discards_history() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        play(&mut game, "e2e4 e7e5");
+
+        game.undo_last_move();
+        play(&mut game, "c7c5");
+        assert_eq!(game.redo_move(), None);
+
+        game.start_position();
+        assert_eq!(game.undo_last_move(), None);
+    }
+
+    #[test]
+    fn test_moves_played_and_last_move() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        assert!(game.moves_played().is_empty());
+        assert_eq!(game.last_move(), None);
+
+        play(&mut game, "e2e4 c7c5 g1f3");
+
+        assert_eq!(game.moves_played(), vec!["e2e4", "c7c5", "g1f3"]);
+        assert_eq!(game.last_move(), Some("g1f3".to_string()));
+
+        game.undo_last_move();
+        assert_eq!(game.last_move(), Some("c7c5".to_string()));
+    }
+
+    #[test]
+    fn test_position_after_moves() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        play(&mut game, "e2e4 c7c5 g1f3");
+
+        assert_eq!(
+            game.position_after(0),
+            Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string())
+        );
+        assert_eq!(
+            game.position_after(2),
+            Some("rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2".to_string())
+        );
+        assert_eq!(game.position_after(3), Some(game.to_fen()));
+        assert_eq!(game.position_after(4), None);
+
+        // The game itself is left untouched
+        assert_eq!(game.moves_played().len(), 3);
+        assert_eq!(game.side_to_move(), Color::Black);
+    }
+
+    #[test]
+    fn test_position_keys_show_repetitions() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        play(&mut game, "g1f3 g8f6 f3g1 f6g8");
+
+        let keys = game.position_keys();
+
+        assert_eq!(keys.len(), 5);
+        assert_eq!(keys[0], keys[4]);
+        assert_eq!(keys[4], game.polyglot_key());
+        assert_ne!(keys[0], keys[2]);
+    }
+
+    #[test]
+    fn test_make_move_obj_and_unmake_last() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("4k3/8/8/3p4/4P3/8/8/4K3 w - - 7 30");
+        let start = game.to_fen();
+        let key = game.polyglot_key();
+
+        let capture = game.create_move("e4d5").unwrap();
+        assert_eq!(game.make_move_obj(&capture), Ok(()));
+        assert_eq!(game.to_fen(), "4k3/8/8/3P4/8/8/8/4K3 b - - 0 30");
+
+        let taken_back = game.unmake_last().unwrap();
+        assert_eq!(taken_back, capture);
+        assert_eq!(game.to_fen(), start);
+        assert_eq!(game.polyglot_key(), key);
+        assert!(game.unmake_last().is_none());
+    }
+
+    #[test]
+    fn test_moves_not_of_the_position_are_rejected() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        let start = game.to_fen();
+
+        // Black piece while white is to move
+        let black_move = game.create_move("e7e5").unwrap();
+        assert!(game.make_move_obj(&black_move).is_err());
+        assert!(!game.make_move("e7e5"));
+
+        // Move created before the position changed
+        let stale = game.create_move("d2d4").unwrap();
+        play(&mut game, "d2d4 d7d5");
+        let after = game.to_fen();
+        assert!(game.make_move_obj(&stale).is_err());
+
+        assert_eq!(game.to_fen(), after);
+        assert_eq!(game.moves_played(), vec!["d2d4", "d7d5"]);
+        game.unmake_move("d2d4");
+        assert_eq!(game.to_fen(), after);
+        game.unmake_move("d7d5");
+        game.unmake_move("d2d4");
+        assert_eq!(game.to_fen(), start);
+    }
+}
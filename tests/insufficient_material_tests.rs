@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod insufficient_material_tests {
+    use std::time::Duration;
+
+    use enrust::game_state::pgn::GameResult;
+    use enrust::game_state::selfplay::{Adjudication, Clock, Termination, play_game};
+    use enrust::game_state::{GameState, Score, SearchConfiguration};
+
+    fn has_insufficient_material(fen: &str) -> bool {
+        let mut game = GameState::new(None);
+        assert!(game.set_fen_position(fen), "Failed to set FEN: {}", fen);
+        game.get_chess_board().has_insufficient_material()
+    }
+
+    #[test]
+    fn test_bare_kings() {
+        assert!(has_insufficient_material("8/8/4k3/8/8/3K4/8/8 w - - 0 1"));
+    }
+
+    #[test]
+    fn test_knight_against_bare_king() {
+        assert!(has_insufficient_material("8/8/4k3/8/8/3K4/5N2/8 w - - 0 1"));
+        assert!(has_insufficient_material("8/8/4k3/2n5/8/3K4/8/8 b - - 0 1"));
+    }
+
+    #[test]
+    fn test_bishop_against_bare_king() {
+        assert!(has_insufficient_material("8/8/4k3/8/8/3K4/5B2/8 w - - 0 1"));
+        assert!(has_insufficient_material("8/8/4k3/2b5/8/3K4/8/8 b - - 0 1"));
+    }
+
+    #[test]
+    fn test_bishops_on_the_same_color() {
+        // c1 and f8 are both dark squares
+        assert!(has_insufficient_material(
+            "5b2/8/4k3/8/8/3K4/8/2B5 w - - 0 1"
+        ));
+        // c1 is dark, c8 is light: a corner mate is still possible
+        assert!(!has_insufficient_material(
+            "2b5/8/4k3/8/8/3K4/8/2B5 w - - 0 1"
+        ));
+    }
+
+    #[test]
+    fn test_sufficient_material() {
+        // Two knights can't force mate, but mate is possible
+        assert!(!has_insufficient_material(
+            "8/8/4k3/8/8/3K4/4NN2/8 w - - 0 1"
+        ));
+        assert!(!has_insufficient_material(
+            "8/8/4k3/8/8/3K4/4NB2/8 w - - 0 1"
+        ));
+        assert!(!has_insufficient_material(
+            "8/8/4k3/2n5/8/3K4/5B2/8 w - - 0 1"
+        ));
+        assert!(!has_insufficient_material(
+            "8/8/4k3/8/8/3K4/4P3/8 w - - 0 1"
+        ));
+        assert!(!has_insufficient_material(
+            "8/8/4k3/8/8/3K4/4R3/8 w - - 0 1"
+        ));
+        assert!(!has_insufficient_material(
+            "8/8/4k3/8/8/3K4/4Q3/8 w - - 0 1"
+        ));
+    }
+
+    #[test]
+    fn test_game_result_is_a_draw() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("5b2/8/4k3/8/8/3K4/8/2B5 w - - 0 1");
+        assert_eq!(game.game_result(), GameResult::Draw);
+
+        game.set_fen_position("8/8/4k3/8/8/3K4/4R3/8 w - - 0 1");
+        assert_eq!(game.game_result(), GameResult::Unknown);
+    }
+
+    #[test]
+    fn test_search_scores_the_capture_of_the_last_pawn_as_a_draw() {
+        // Bxd4 leaves a bishop each on dark squares, a dead draw
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position("5b2/8/4k3/8/3p4/4B3/8/3K4 w - - 0 1");
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(3);
+        game.set_time_control(&sc);
+
+        let result = game.search().join();
+        assert_eq!(result.best_move.as_deref(), Some("e3d4"));
+        assert_eq!(result.score, Score::DRAW);
+    }
+
+    #[test]
+    fn test_selfplay_ends_without_mating_material() {
+        let fen = "5b2/8/4k3/8/8/3K4/8/2B5 w - - 0 1";
+        let mut white = GameState::new(Some(1));
+        let mut black = GameState::new(Some(1));
+        white.set_fen_position(fen);
+        black.set_fen_position(fen);
+
+        let game = play_game(
+            &mut white,
+            &mut black,
+            Clock::new(Duration::from_secs(1), Duration::ZERO, None),
+            &Adjudication::default(),
+        );
+
+        assert_eq!(game.result, GameResult::Draw);
+        assert_eq!(game.termination, Termination::Normal);
+        assert!(game.moves.is_empty());
+    }
+}
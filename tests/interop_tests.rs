@@ -0,0 +1,93 @@
+#[cfg(all(test, feature = "interop"))]
+mod interop_tests {
+    use enrust::game_state::interop;
+    use enrust::game_state::{GameState, SearchConfiguration};
+    use shakmaty::fen::Fen;
+    use shakmaty::zobrist::Zobrist64;
+    use shakmaty::{CastlingMode, Chess, EnPassantMode, Position};
+
+    fn shakmaty_position(fen: &str) -> Chess {
+        fen.parse::<Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_positions_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 12 40",
+        ];
+
+        for fen in fens {
+            let mut game = GameState::new(None);
+            assert!(interop::set_position(&mut game, &shakmaty_position(fen)).is_ok());
+            assert_eq!(game.to_fen(), fen);
+
+            let position = interop::to_shakmaty(&game).unwrap();
+            assert_eq!(
+                Fen::from_position(&position, EnPassantMode::Legal).to_string(),
+                fen
+            );
+
+            // Both sides hash the position with the Polyglot keys
+            game.set_polyglot_keys(true);
+            let key: Zobrist64 = position.zobrist_hash(EnPassantMode::Legal);
+            assert_eq!(game.polyglot_key(), key.0, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_every_legal_move_converts_both_ways() {
+        let mut game = GameState::new(None);
+        game.set_fen_position(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        );
+        let position = interop::to_shakmaty(&game).unwrap();
+
+        let legal = position.legal_moves();
+        assert_eq!(legal.len(), game.generate_moves().len());
+        for mv in legal {
+            let converted = interop::move_from_shakmaty(&game, &mv).unwrap();
+            assert_eq!(interop::move_to_shakmaty(&game, &converted), Ok(mv));
+        }
+    }
+
+    #[test]
+    fn test_castling_and_promotion_moves() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("4k2r/1P6/8/8/8/8/8/R3K3 w Qk - 0 1");
+
+        for uci in ["e1c1", "b7b8n"] {
+            let mv = game.create_move(uci).unwrap();
+            let converted = interop::move_to_shakmaty(&game, &mv).unwrap();
+            assert_eq!(interop::move_from_shakmaty(&game, &converted), Some(mv));
+        }
+
+        // A move of the wrong side is rejected
+        let black_castling = game.create_move("e8g8").unwrap();
+        assert!(interop::move_to_shakmaty(&game, &black_castling).is_err());
+    }
+
+    #[test]
+    fn test_best_move_of_a_shakmaty_position() {
+        let mut game = GameState::new(Some(16));
+        let mut config = SearchConfiguration::new();
+        config.depth = Some(3);
+
+        // White wins the queen left hanging on a4
+        let position = shakmaty_position("4k3/8/8/8/q7/8/8/3QK3 w - - 0 1");
+        let mv = interop::best_move(&mut game, &position, &config)
+            .unwrap()
+            .unwrap();
+        assert!(position.is_legal(mv));
+        assert_eq!(mv.to().to_string(), "a4");
+
+        // Checkmated, there is no move to play
+        let position = shakmaty_position("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1");
+        assert_eq!(interop::best_move(&mut game, &position, &config), Ok(None));
+    }
+}
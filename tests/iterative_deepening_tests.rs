@@ -0,0 +1,401 @@
+#[cfg(test)]
+mod iterative_deepening_tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::time::Duration;
+
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use enrust::game_state::ChessBoard;
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::Score;
+    use enrust::game_state::board::search::{
+        IterativeDeepening, MinimaxAlphaBeta, RootMoveStatus, RootMoves, Search, SearchClock,
+    };
+
+    fn setup_test_game(fen: &str) -> ChessBoard {
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position(fen);
+        game.get_chess_board().clone()
+    }
+
+    /// Gives the board a clock with plenty of time, as for a timed search.
+    fn set_time_limit(board: &mut ChessBoard, stop_flag: &Arc<AtomicBool>) {
+        let clock = SearchClock::new(stop_flag.clone());
+        clock.set_time_to_think(Duration::from_secs(600));
+        board.set_clock(Some(Arc::new(clock)));
+    }
+
+    #[test]
+    fn test_single_legal_move_is_returned_without_search() {
+        // Kxa2 is the only legal move
+        let mut board = setup_test_game("7k/8/8/8/8/8/q7/K7 w - - 0 1");
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 5);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (_, best_move) = search.search(&mut board, Color::White, stop_flag);
+
+        let best_move = best_move.expect("forced move");
+        assert_eq!(board.move_to_uci(&best_move), "a1a2");
+        assert_eq!(board.nodes(), 0, "A forced move should not be searched");
+    }
+
+    #[test]
+    fn test_forced_move_is_reported_at_depth_1() {
+        let mut board = setup_test_game("7k/8/8/8/8/8/q7/K7 w - - 0 1");
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 5);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        search.search(&mut board, Color::White, stop_flag);
+
+        assert_eq!(board.completed_depth(), 1);
+    }
+
+    #[test]
+    fn test_forced_move_is_searched_without_instant_moves() {
+        let mut board = setup_test_game("7k/8/8/8/8/8/q7/K7 w - - 0 1");
+        board.set_instant_moves(false);
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (_, best_move) = search.search(&mut board, Color::White, stop_flag);
+
+        assert_eq!(board.move_to_uci(&best_move.unwrap()), "a1a2");
+        assert!(board.nodes() > 0);
+        assert_eq!(board.completed_depth(), 3);
+    }
+
+    #[test]
+    fn test_resumed_search_skips_the_shallow_iterations() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let mut fresh = setup_test_game(fen);
+        search.search(&mut fresh, Color::White, stop_flag.clone());
+        let mut resumed = setup_test_game(fen);
+        resumed.set_resume_depth(Some(3));
+        let (_, best_move) = search.search(&mut resumed, Color::White, stop_flag);
+
+        assert!(best_move.is_some());
+        assert_eq!(resumed.completed_depth(), 3);
+        assert!(resumed.nodes() < fresh.nodes());
+    }
+
+    #[test]
+    fn test_easy_move_stops_before_max_depth() {
+        // Capturing the hanging queen is vastly better than anything else
+        let fen = "4k3/8/8/8/q7/8/8/3QK3 w - - 0 1";
+
+        let mut shallow = setup_test_game(fen);
+        let mut deep = setup_test_game(fen);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        set_time_limit(&mut shallow, &stop_flag);
+        set_time_limit(&mut deep, &stop_flag);
+
+        let (_, shallow_move) = IterativeDeepening::new(MinimaxAlphaBeta, 3).search(
+            &mut shallow,
+            Color::White,
+            stop_flag.clone(),
+        );
+        let (_, deep_move) =
+            IterativeDeepening::new(MinimaxAlphaBeta, 6).search(&mut deep, Color::White, stop_flag);
+
+        assert_eq!(shallow.move_to_uci(&shallow_move.unwrap()), "d1a4");
+        assert_eq!(deep.move_to_uci(&deep_move.unwrap()), "d1a4");
+        assert_eq!(
+            shallow.nodes(),
+            deep.nodes(),
+            "Easy move should end the search at the minimum depth"
+        );
+
+        // Without a time limit, as in `go infinite`, the search goes on
+        let mut untimed = setup_test_game(fen);
+        IterativeDeepening::new(MinimaxAlphaBeta, 6).search(
+            &mut untimed,
+            Color::White,
+            Arc::new(AtomicBool::new(false)),
+        );
+        assert_eq!(untimed.completed_depth(), 6);
+    }
+
+    #[test]
+    fn test_easy_move_depth_follows_search_params() {
+        let fen = "4k3/8/8/8/q7/8/8/3QK3 w - - 0 1";
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position(fen);
+        assert!(game.set_search_param("EasyMoveMinDepth", 4));
+
+        let mut shallow = setup_test_game(fen);
+        let mut deep = game.get_chess_board().clone();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        set_time_limit(&mut shallow, &stop_flag);
+        set_time_limit(&mut deep, &stop_flag);
+
+        IterativeDeepening::new(MinimaxAlphaBeta, 3).search(
+            &mut shallow,
+            Color::White,
+            stop_flag.clone(),
+        );
+        let (_, deep_move) =
+            IterativeDeepening::new(MinimaxAlphaBeta, 6).search(&mut deep, Color::White, stop_flag);
+
+        assert_eq!(deep.move_to_uci(&deep_move.unwrap()), "d1a4");
+        assert!(
+            deep.nodes() > shallow.nodes(),
+            "Easy move should wait for the tuned minimum depth"
+        );
+    }
+
+    #[test]
+    fn test_search_params_are_range_checked() {
+        let mut game = GameState::new(None);
+
+        assert!(game.set_search_param("EasyMoveMargin", 350));
+        assert_eq!(game.search_params().easy_move_margin, Score::cp(350));
+
+        assert!(!game.set_search_param("EasyMoveMargin", 5000));
+        assert!(!game.set_search_param("EasyMoveMinDepth", 0));
+        assert!(!game.set_search_param("NoSuchParam", 1));
+        assert_eq!(game.search_params().easy_move_margin, Score::cp(350));
+        assert_eq!(game.search_params().easy_move_min_depth, 3);
+    }
+
+    #[test]
+    fn test_finds_mate_in_one() {
+        let mut board = setup_test_game("7R/8/8/8/8/1K6/8/1k6 w - - 0 1");
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = search.search(&mut board, Color::White, stop_flag);
+
+        assert!(
+            score.is_mate() && score > Score::ZERO,
+            "Should find mate, score: {}",
+            score
+        );
+        let best_move = best_move.unwrap();
+        board.make_move(&best_move);
+        assert!(board.is_checkmate(Color::Black));
+    }
+
+    #[test]
+    fn test_root_without_moves_is_scored_by_the_rules() {
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+
+        // Black is mated, a win for White
+        let mut board = setup_test_game("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1");
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = search.search(&mut board, Color::Black, stop_flag);
+        assert_eq!(score, Score::mate_in(0));
+        assert!(best_move.is_none());
+
+        let mut board = setup_test_game("k7/8/1Q6/8/8/8/8/7K b - - 0 1");
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = search.search(&mut board, Color::Black, stop_flag);
+        assert_eq!(score, Score::DRAW);
+        assert!(best_move.is_none());
+    }
+
+    #[test]
+    fn test_black_score_is_white_centric() {
+        let mut board = setup_test_game("7r/8/8/8/8/1k6/8/1K6 b - - 0 1");
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, _) = search.search(&mut board, Color::Black, stop_flag);
+
+        assert!(
+            score.is_mate() && score < Score::ZERO,
+            "Black mate should be negative, score: {}",
+            score
+        );
+    }
+
+    #[test]
+    fn test_stopped_search_still_returns_a_legal_move() {
+        let mut board = setup_test_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 5);
+        let stop_flag = Arc::new(AtomicBool::new(true));
+        let (_, best_move) = search.search(&mut board, Color::White, stop_flag);
+
+        let moves = board.generate_moves(Color::White);
+        assert!(moves.contains(&best_move.expect("fallback move")));
+    }
+
+    #[test]
+    fn test_stopped_search_returns_a_searched_move() {
+        let mut board =
+            setup_test_game("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let stopper = {
+            let stop_flag = Arc::clone(&stop_flag);
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                stop_flag.store(true, std::sync::atomic::Ordering::Release);
+            })
+        };
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, u8::MAX);
+        let (score, best_move) = search.search(&mut board, Color::White, stop_flag);
+        stopper.join().unwrap();
+
+        let moves = board.generate_moves(Color::White);
+        assert!(moves.contains(&best_move.expect("best move")));
+        assert!(score > -Score::INFINITE && score < Score::INFINITE);
+    }
+
+    #[test]
+    fn test_stopped_search_falls_back_to_the_transposition_move() {
+        let mut board = setup_test_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        // Searching the previous move leaves a best move in the table for
+        // the position two plies later
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        let (_, best_move) =
+            search.search(&mut board, Color::White, Arc::new(AtomicBool::new(false)));
+        let line = board.principal_variation(&best_move.unwrap(), Color::White, 3);
+        assert_eq!(line.len(), 3);
+        board.make_move(&line[0]);
+        board.make_move(&line[1]);
+
+        let first_move = board.generate_moves(Color::White)[0].clone();
+        assert_ne!(line[2], first_move);
+
+        let stop_flag = Arc::new(AtomicBool::new(true));
+        let (_, emergency_move) = search.search(&mut board, Color::White, stop_flag);
+        assert_eq!(emergency_move, Some(line[2].clone()));
+    }
+
+    #[test]
+    fn test_root_moves_prefer_moves_searched_deeper() {
+        let mut board = setup_test_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut root_moves = RootMoves::new(board.generate_moves(Color::White));
+        let first = root_moves.moves()[0].mv.clone();
+        let second = root_moves.moves()[1].mv.clone();
+
+        root_moves.start_iteration();
+        for index in 0..root_moves.len() {
+            root_moves.record(index, Score::cp(-(index as i32)), 1);
+        }
+        root_moves.complete_iteration(1);
+        assert_eq!(root_moves.best(), Some((Score::ZERO, &first)));
+
+        // Stopped after the first two moves: the second one is better
+        root_moves.start_iteration();
+        root_moves.record(0, Score::cp(10), 1);
+        root_moves.record(1, Score::cp(30), 1);
+        root_moves.interrupt(2);
+        assert_eq!(root_moves.moves()[2].status, RootMoveStatus::Interrupted);
+        assert_eq!(root_moves.best(), Some((Score::cp(30), &second)));
+    }
+
+    #[test]
+    fn test_root_moves_keep_completed_iteration_until_best_is_searched() {
+        let mut board = setup_test_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut root_moves = RootMoves::new(board.generate_moves(Color::White));
+        let first = root_moves.moves()[0].mv.clone();
+
+        // Nothing searched yet
+        assert_eq!(root_moves.best(), None);
+
+        // An interrupted move is never played
+        root_moves.start_iteration();
+        root_moves.interrupt(0);
+        assert_eq!(root_moves.best(), None);
+
+        root_moves.start_iteration();
+        for index in 0..root_moves.len() {
+            root_moves.record(index, Score::cp(20 - index as i32), 1);
+        }
+        root_moves.complete_iteration(1);
+        assert_eq!(root_moves.completed_depth(), 1);
+
+        // The previous best move was interrupted in the next iteration
+        root_moves.start_iteration();
+        root_moves.interrupt(0);
+        assert_eq!(root_moves.best(), Some((Score::cp(20), &first)));
+    }
+
+    #[test]
+    fn test_root_moves_pick_within_margin() {
+        let mut board = setup_test_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut root_moves = RootMoves::new(board.generate_moves(Color::White));
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // Only a completed iteration is trusted
+        assert_eq!(root_moves.pick_within(Score::cp(5), &mut rng), None);
+
+        root_moves.start_iteration();
+        for index in 0..root_moves.len() {
+            root_moves.record(index, Score::cp(20 - 3 * index as i32), 1);
+        }
+        root_moves.complete_iteration(1);
+
+        // The three moves within 6 centipawns of the best are all played
+        let mut picked = [false; 3];
+        for _ in 0..100 {
+            let (score, mv) = root_moves.pick_within(Score::cp(6), &mut rng).unwrap();
+            let index = root_moves
+                .moves()
+                .iter()
+                .position(|root_move| &root_move.mv == mv);
+            let index = index.expect("a root move");
+            assert!(index < 3, "{:?} is not within the margin", score);
+            picked[index] = true;
+        }
+        assert_eq!(picked, [true; 3]);
+
+        // Without margin the best move is played
+        let best = root_moves.moves()[0].mv.clone();
+        assert_eq!(
+            root_moves.pick_within(Score::ZERO, &mut rng),
+            Some((Score::cp(20), &best))
+        );
+    }
+
+    #[test]
+    fn test_variety_plays_moves_close_to_the_best() {
+        // Only Qxa4 keeps the material balance, every other move loses
+        // the queen
+        let fen = "4k3/8/8/8/q7/8/8/3QK3 w - - 0 1";
+        let mut board = setup_test_game(fen);
+        board.set_variety(Some(Score::cp(50)));
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        for _ in 0..5 {
+            let stop_flag = Arc::new(AtomicBool::new(false));
+            let (_, best_move) = search.search(&mut board, Color::White, stop_flag);
+            assert_eq!(board.move_to_uci(&best_move.unwrap()), "d1a4");
+        }
+    }
+
+    #[test]
+    fn test_no_legal_moves_returns_none() {
+        let mut board = setup_test_game("k7/8/1Q6/8/8/8/8/7K b - - 0 1");
+
+        let search = IterativeDeepening::new(MinimaxAlphaBeta, 3);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (_, best_move) = search.search(&mut board, Color::Black, stop_flag);
+
+        assert!(best_move.is_none());
+    }
+
+    #[test]
+    fn test_seldepth_counts_extensions() {
+        // e6-e7 is extended, so the last iteration goes one ply deeper
+        let mut board = setup_test_game("4k3/8/8/4P3/8/8/8/4K3 w - - 0 1");
+
+        IterativeDeepening::new(MinimaxAlphaBeta, 3).search(
+            &mut board,
+            Color::White,
+            Arc::new(AtomicBool::new(false)),
+        );
+
+        assert_eq!(board.completed_depth(), 3);
+        assert!(board.seldepth() > 3, "seldepth: {}", board.seldepth());
+    }
+}
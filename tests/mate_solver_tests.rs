@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod mate_solver_tests {
+    use enrust::game_state::mate_solver::{MateKey, solve_mate};
+
+    fn key_moves(keys: &[MateKey]) -> Vec<&str> {
+        keys.iter().map(|key| key.uci.as_str()).collect()
+    }
+
+    #[test]
+    fn test_mate_in_one_lists_every_key() {
+        // Back rank: both rooks mate on the eighth rank
+        let keys = solve_mate("6k1/5ppp/8/8/8/8/8/R3R1K1 w - - 0 1", 1).unwrap();
+        assert_eq!(key_moves(&keys), ["a1a8", "e1e8"]);
+        assert!(keys.iter().all(|key| key.mate_in == 1));
+        assert_eq!(keys[0].san, "Ra8#");
+    }
+
+    #[test]
+    fn test_quiet_key() {
+        // 1. Kc7 Ka7 2. Ra1#
+        let fen = "k7/8/2K5/8/8/8/8/1R6 w - - 0 1";
+        assert_eq!(solve_mate(fen, 1).unwrap(), []);
+        let keys = solve_mate(fen, 2).unwrap();
+        assert_eq!(key_moves(&keys), ["c6c7"]);
+        assert_eq!(keys[0].mate_in, 2);
+
+        // 1. Kf7 Kh7 2. Rh1#, for Black too
+        let keys = solve_mate("6r1/8/8/8/8/5k2/8/7K b - - 0 1", 2).unwrap();
+        assert_eq!(key_moves(&keys), ["f3f2"]);
+    }
+
+    #[test]
+    fn test_shorter_mates_are_keys() {
+        let keys = solve_mate("k7/8/1K6/8/8/8/8/2Q5 w - - 0 1", 2).unwrap();
+        let mate_in_one: Vec<&str> = keys
+            .iter()
+            .filter(|key| key.mate_in == 1)
+            .map(|key| key.san.as_str())
+            .collect();
+        assert_eq!(mate_in_one, ["Qc8#"]);
+        assert!(keys.len() > 1);
+    }
+
+    #[test]
+    fn test_stalemate_is_not_mate() {
+        // 1. Qc7 leaves the king without moves, but not in check
+        let keys = solve_mate("k7/8/1K6/8/8/8/8/2Q5 w - - 0 1", 2).unwrap();
+        assert!(!key_moves(&keys).contains(&"c1c7"));
+    }
+
+    #[test]
+    fn test_invalid_fen() {
+        assert_eq!(solve_mate("not a fen", 2), None);
+    }
+}
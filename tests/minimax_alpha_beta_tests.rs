@@ -6,7 +6,9 @@ mod minimax_alpha_beta_tests {
     use enrust::game_state::ChessBoard;
     use enrust::game_state::Color;
     use enrust::game_state::GameState;
-    use enrust::game_state::board::search::{MinimaxAlphaBeta, SearchAlgorithm};
+    use enrust::game_state::Score;
+    use enrust::game_state::board::search::quiescence::quiescence;
+    use enrust::game_state::board::search::{MinimaxAlphaBeta, SearchAlgorithm, SearchParams};
 
     fn setup_test_game(fen: &str) -> ChessBoard {
         let mut game = GameState::new(Some(256));
@@ -31,7 +33,7 @@ mod minimax_alpha_beta_tests {
 
         // Score should be reasonable (not extreme values)
         assert!(
-            score.abs() < 1000,
+            score.centipawns().abs() < 1000,
             "Score should be reasonable, got: {}",
             score
         );
@@ -47,7 +49,7 @@ mod minimax_alpha_beta_tests {
 
         // Should find checkmate
         assert!(
-            score > 10000,
+            score.is_mate() && score > Score::ZERO,
             "Should find winning position, score: {}",
             score
         );
@@ -69,7 +71,7 @@ mod minimax_alpha_beta_tests {
 
         // Should find checkmate (negative score from black's perspective)
         assert!(
-            score < -10000,
+            score.is_mate() && score < Score::ZERO,
             "Should find winning position for black, score: {}",
             score
         );
@@ -92,7 +94,7 @@ mod minimax_alpha_beta_tests {
         // Should recognize stalemate (score from black's perspective ≈ 0)
         // PST values may give slight non-zero score, so allow a small range
         assert!(
-            score.abs() < 100,
+            score.centipawns().abs() < 100,
             "Should recognize stalemate, got score: {}",
             score
         );
@@ -118,7 +120,7 @@ mod minimax_alpha_beta_tests {
 
         // Score should reflect material advantage
         assert!(
-            score > 800,
+            score > Score::cp(800),
             "Should have significant advantage after capture, score: {}",
             score
         );
@@ -126,8 +128,10 @@ mod minimax_alpha_beta_tests {
 
     #[test]
     fn test_minimax_promotion() {
-        // White pawn can promote to queen
-        let mut game = setup_test_game("k7/3P4/8/8/8/8/8/K7 w - - 0 1");
+        // White pawn can promote to queen, and only now: after a waiting
+        // move Ke7 stops it. Unstoppable, the promotion could be left to
+        // quiescence
+        let mut game = setup_test_game("5k2/3P4/8/8/8/8/8/K7 w - - 0 1");
 
         let stop_flag = Arc::new(AtomicBool::new(false));
         let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 2, Color::White, stop_flag);
@@ -146,7 +150,7 @@ mod minimax_alpha_beta_tests {
 
         // Score should reflect queen advantage (adjusted for PST)
         assert!(
-            score >= 800,
+            score >= Score::cp(800),
             "Should have queen advantage, score: {}",
             score
         );
@@ -171,7 +175,46 @@ mod minimax_alpha_beta_tests {
         game.unmake_move(&best_move);
 
         // Score should not be extremely negative
-        assert!(score > -10000, "Should avoid checkmate, score: {}", score);
+        assert!(
+            score > Score::cp(-10000),
+            "Should avoid checkmate, score: {}",
+            score
+        );
+    }
+
+    #[test]
+    fn test_minimax_quiescence_checks_at_the_horizon() {
+        // Kg8 is forced and Ra8# follows, a quiet move beyond depth 1
+        let fen = "7k/8/6K1/8/8/8/8/R7 b - - 0 1";
+
+        let mut board = setup_test_game(fen);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, _) = MinimaxAlphaBeta.search(&mut board, 1, Color::Black, stop_flag);
+        assert!(!score.is_mate(), "Plain quiescence can't see the mate");
+
+        let mut board = setup_test_game(fen);
+        board.set_quiescence_checks(true);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, _) = MinimaxAlphaBeta.search(&mut board, 1, Color::Black, stop_flag);
+        assert!(score.is_mate(), "Ra8# should be found, score: {}", score);
+    }
+
+    #[test]
+    fn test_check_extension_finds_mate_past_the_horizon() {
+        // Qg8+ Rxg8 Nf7# needs three plies, one more than searched
+        let fen = "r6k/6pp/7N/8/8/1Q6/8/6K1 w - - 0 1";
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let mut game = setup_test_game(fen);
+        let mut params = *game.search_params();
+        params.check_extension = 0;
+        game.set_search_params(params);
+        let score = MinimaxAlphaBeta.tree_search(&mut game, 2, Color::White, stop_flag.clone());
+        assert!(!score.is_mate(), "score: {}", score);
+
+        let mut game = setup_test_game(fen);
+        let score = MinimaxAlphaBeta.tree_search(&mut game, 2, Color::White, stop_flag);
+        assert_eq!(score, Score::mate_in(3));
     }
 
     #[test]
@@ -206,9 +249,9 @@ mod minimax_alpha_beta_tests {
         );
 
         // Scores should be reasonable
-        assert!(score_depth_1.abs() < 1000);
-        assert!(score_depth_2.abs() < 1000);
-        assert!(score_depth_3.abs() < 1000);
+        assert!(score_depth_1.centipawns().abs() < 1000);
+        assert!(score_depth_2.centipawns().abs() < 1000);
+        assert!(score_depth_3.centipawns().abs() < 1000);
     }
 
     #[test]
@@ -226,7 +269,7 @@ mod minimax_alpha_beta_tests {
 
         // Scores should be approximately opposite (white positive, black negative)
         assert!(
-            (score_white + score_black).abs() < 50,
+            (score_white + score_black).centipawns().abs() < 50,
             "Symmetric position should have opposite scores: white={}, black={}",
             score_white,
             score_black
@@ -235,15 +278,15 @@ mod minimax_alpha_beta_tests {
 
     #[test]
     fn test_minimax_material_advantage() {
-        // White has extra queen
-        let mut game = setup_test_game("k7/8/8/8/8/8/1Q6/K7 w - - 0 1");
+        // White has extra queen, pawns keep it out of the known endgames
+        let mut game = setup_test_game("k7/7p/8/8/8/8/1Q5P/K7 w - - 0 1");
 
         let stop_flag = Arc::new(AtomicBool::new(false));
         let (score, _) = MinimaxAlphaBeta.search(&mut game, 1, Color::White, stop_flag);
 
         // Should show significant advantage (around +900 for queen)
         assert!(
-            score > 800 && score < 1000,
+            score > Score::cp(800) && score < Score::cp(1000),
             "Should show queen advantage, got: {}",
             score
         );
@@ -270,7 +313,7 @@ mod minimax_alpha_beta_tests {
 
             // Score should be near 0 for equal position (PST gives slight offset)
             assert!(
-                score.abs() < 100,
+                score.centipawns().abs() < 100,
                 "Depth {}: Kings only should be near equal, got: {}",
                 depth,
                 score
@@ -287,9 +330,270 @@ mod minimax_alpha_beta_tests {
         let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 3, Color::Black, stop_flag);
 
         assert!(
-            score > 10000,
+            score.is_mate() && score > Score::ZERO,
             "Black is being mated, score should be very high for white"
         );
         assert!(best_move.is_some(), "There's two forced moves for black");
     }
+
+    #[test]
+    fn test_repetition_detection() {
+        let mut game = GameState::new(None);
+        game.start_position();
+
+        for mv in ["g1f3", "g8f6", "f3g1"] {
+            assert!(game.make_move(mv));
+            assert!(!game.get_chess_board().is_repetition());
+        }
+
+        assert!(game.make_move("f6g8"));
+        assert!(game.get_chess_board().is_repetition());
+
+        // A pawn move makes every earlier position unreachable
+        assert!(game.make_move("e2e3"));
+        for mv in ["e7e6", "g1f3", "g8f6", "f3g1"] {
+            assert!(game.make_move(mv));
+            assert!(!game.get_chess_board().is_repetition());
+        }
+        assert!(game.make_move("f6g8"));
+        assert!(game.get_chess_board().is_repetition());
+    }
+
+    #[test]
+    fn test_perpetual_check_is_a_draw() {
+        // White is a rook and a queen down, but Qh6+ and Qg6+ check forever
+        let mut game = setup_test_game("q4r1k/8/8/8/8/4Q3/8/6K1 w - - 0 1");
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 5, Color::White, stop_flag);
+
+        assert_eq!(score, Score::DRAW, "Perpetual check should be a draw");
+        assert_eq!(best_move.unwrap().to_uci(&game), "e3h6");
+
+        // Same position with colors reversed
+        let mut game = setup_test_game("6k1/8/4q3/8/8/8/8/Q4R1K b - - 0 1");
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 5, Color::Black, stop_flag);
+
+        assert_eq!(score, Score::DRAW, "Perpetual check should be a draw");
+        assert_eq!(best_move.unwrap().to_uci(&game), "e6h3");
+    }
+
+    #[test]
+    fn test_pawn_races() {
+        // The king on h8 is outside the square, pushing the pawn wins
+        let mut game = setup_test_game("7k/8/8/8/P7/8/8/K7 w - - 0 1");
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 3, Color::White, stop_flag);
+
+        assert!(
+            score > Score::cp(500),
+            "Pawn race should be won, score: {}",
+            score
+        );
+        assert_eq!(best_move.unwrap().to_uci(&game), "a4a5");
+
+        // The black king has to step into the square of the pawn
+        let mut game = setup_test_game("8/8/4k3/8/P7/8/8/K7 b - - 0 1");
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 3, Color::Black, stop_flag);
+
+        assert!(
+            score < Score::cp(300),
+            "Pawn race should be defended, score: {}",
+            score
+        );
+        let best_move = best_move.unwrap().to_uci(&game);
+        assert!(
+            ["e6d5", "e6d6", "e6d7", "e6e5"].contains(&best_move.as_str()),
+            "King should move toward the pawn, played {}",
+            best_move
+        );
+    }
+
+    #[test]
+    fn test_null_move_passes_the_turn() {
+        let fen = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2";
+        let mut game = GameState::new(None);
+        game.set_polyglot_keys(true);
+        game.set_fen_position(fen);
+        let mut board = game.get_chess_board().clone();
+        let key = board.polyglot_key(Color::White);
+
+        // Same pieces with the other side to move and no en passant target
+        board.make_null_move();
+        let mut passed = GameState::new(None);
+        passed.set_polyglot_keys(true);
+        passed.set_fen_position("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2");
+        assert_eq!(
+            board.to_fen(Color::Black, 0, 2),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2"
+        );
+        assert_eq!(
+            board.polyglot_key(Color::Black),
+            passed.get_chess_board().polyglot_key(Color::Black)
+        );
+
+        board.unmake_null_move();
+        assert_eq!(board.to_fen(Color::White, 0, 2), fen);
+        assert_eq!(board.polyglot_key(Color::White), key);
+    }
+
+    #[test]
+    fn test_null_move_breaks_repetition() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        for mv in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+            assert!(game.make_move(mv));
+        }
+        let mut board = game.get_chess_board().clone();
+        assert!(board.is_repetition());
+
+        // Passing twice returns to the same position, but not by moves
+        board.make_null_move();
+        board.make_null_move();
+        assert!(!board.is_repetition());
+    }
+
+    #[test]
+    fn test_non_pawn_material() {
+        let board = setup_test_game("4k3/pppp4/8/8/8/8/PPPP4/4K1N1 w - - 0 1");
+
+        assert!(board.has_non_pawn_material(Color::White));
+        assert!(!board.has_non_pawn_material(Color::Black));
+    }
+
+    #[test]
+    fn test_no_null_move_with_only_pawns() {
+        // Passing would often be the best move in pawn endings, so the search
+        // must be the same as with null move turned off
+        let fen = "8/8/8/1k6/8/1PK5/8/8 w - - 0 1";
+        let search_nodes = |null_move_min_depth: u8| {
+            let mut game = setup_test_game(fen);
+            let mut params = *game.search_params();
+            params.null_move_min_depth = null_move_min_depth;
+            game.set_search_params(params);
+
+            let stop_flag = Arc::new(AtomicBool::new(false));
+            MinimaxAlphaBeta.search(&mut game, 6, Color::White, stop_flag);
+            game.nodes()
+        };
+
+        assert_eq!(search_nodes(3), search_nodes(u8::MAX));
+    }
+
+    #[test]
+    fn test_null_move_reduction_follows_game_phase() {
+        let reduction = |fen: &str, null_move_reduction: u8| {
+            let params = SearchParams {
+                null_move_reduction,
+                ..SearchParams::default()
+            };
+            params.null_move_reduction_at(&setup_test_game(fen).game_phase())
+        };
+
+        let opening = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let rook_ending = "8/8/4k3/8/2r5/8/3R4/4K3 w - - 0 1";
+        let pawn_ending = "8/8/4k3/4p3/4P3/8/8/4K3 w - - 0 1";
+
+        assert_eq!(reduction(opening, 2), 2);
+        assert_eq!(reduction(rook_ending, 2), 1);
+        assert_eq!(reduction(pawn_ending, 2), 1);
+        // Never below a ply
+        assert_eq!(reduction(pawn_ending, 1), 1);
+        assert_eq!(reduction(opening, 4), 4);
+        assert_eq!(reduction(pawn_ending, 4), 3);
+    }
+
+    #[test]
+    fn test_null_move_verification_finds_mate() {
+        let mut game =
+            setup_test_game("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4");
+        // Verify every null-move cutoff
+        let mut params = *game.search_params();
+        params.null_move_verification_depth = params.null_move_min_depth;
+        game.set_search_params(params);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut game, 4, Color::White, stop_flag);
+
+        assert!(score.is_mate() && score > Score::ZERO, "score: {}", score);
+        assert_eq!(best_move.unwrap().to_uci(&game), "h5f7");
+    }
+
+    #[test]
+    fn test_minimax_shallow_depths() {
+        // Checkmate and stalemate have no move to return at any depth
+        for fen in [
+            "k7/1Q6/1K6/8/8/8/8/8 b - - 0 1",
+            "k7/8/1Q6/8/8/8/8/7K b - - 0 1",
+        ] {
+            let mut game = setup_test_game(fen);
+            for depth in [0, 1] {
+                let stop_flag = Arc::new(AtomicBool::new(false));
+                let (_, best_move) =
+                    MinimaxAlphaBeta.search(&mut game, depth, Color::Black, stop_flag);
+                assert!(best_move.is_none(), "{} at depth {}", fen, depth);
+            }
+        }
+
+        // Depth 0 searches the root moves at the horizon, like depth 1
+        let mut game = setup_test_game("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1");
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) =
+            MinimaxAlphaBeta.search(&mut game, 0, Color::White, stop_flag.clone());
+        assert_eq!(best_move.unwrap().to_uci(&game), "e4d5");
+        assert_eq!(
+            score,
+            MinimaxAlphaBeta
+                .search(&mut game, 1, Color::White, stop_flag.clone())
+                .0
+        );
+
+        // Below the horizon the captures are played out: exd5 wins the queen
+        let quiet = quiescence(&mut game, -Score::INFINITE, Score::INFINITE, Color::White);
+        assert!(quiet > game.evaluate_for(Color::White));
+        for depth in [-3, -1, 0] {
+            assert_eq!(
+                MinimaxAlphaBeta.tree_search(&mut game, depth, Color::White, stop_flag.clone()),
+                quiet
+            );
+        }
+    }
+
+    #[test]
+    fn test_horizon_captures_are_played_out() {
+        // Qxd5 looks like it wins a pawn at the horizon, but exd5 wins the
+        // queen back
+        let mut game = setup_test_game("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1");
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (_, best_move) = MinimaxAlphaBeta.search(&mut game, 1, Color::White, stop_flag);
+
+        assert_ne!(best_move.unwrap().to_uci(&game), "d1d5");
+    }
+
+    #[test]
+    fn test_nearer_mate_scores_higher() {
+        // Rh1 mates at once, Rc8 Ka1 Rc1 mates a move later
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position("7R/8/8/8/8/1K6/8/1k6 w - - 0 1");
+        let mut board = game.get_chess_board().clone();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut board, 3, Color::White, stop_flag);
+
+        assert_eq!(best_move.unwrap().to_uci(&board), "h8h1");
+        assert_eq!(score, Score::mate_in(1));
+
+        // The table keeps the distance of the mate from each position: a
+        // second search doesn't mistake the longer mate for the shorter one
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = MinimaxAlphaBeta.search(&mut board, 3, Color::White, stop_flag);
+        assert_eq!(best_move.unwrap().to_uci(&board), "h8h1");
+        assert_eq!(score, Score::mate_in(1));
+    }
 }
@@ -1,5 +1,9 @@
 mod move_generator {
+    use enrust::game_state::Color;
     use enrust::game_state::GameState;
+    use enrust::game_state::Move;
+    use enrust::game_state::PieceType;
+    use enrust::game_state::board::directions::{self, Direction, KNIGHT_JUMPS};
 
     fn setup_game_with_fen(fen: &str) -> GameState {
         let mut game = GameState::new(None);
@@ -33,4 +37,280 @@ mod move_generator {
             );
         }
     }
+
+    #[test]
+    fn test_copy_make_matches_make_move() {
+        let game = setup_game_with_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        );
+        let mut board = game.get_chess_board().clone();
+        let original_key = board.polyglot_key(Color::White);
+
+        for mv in board.generate_moves(Color::White) {
+            let mut copy = board.copy_make(&mv);
+
+            board.make_move(&mv);
+            assert_eq!(
+                copy.polyglot_key(Color::Black),
+                board.polyglot_key(Color::Black)
+            );
+            assert_eq!(
+                copy.generate_moves(Color::Black),
+                board.generate_moves(Color::Black)
+            );
+            board.unmake_move(&mv);
+
+            // The source board is never modified
+            assert_eq!(board.polyglot_key(Color::White), original_key);
+        }
+    }
+
+    #[test]
+    fn test_compact_move_round_trip() {
+        let positions = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            // En passant and promotions with and without captures
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N w - - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ];
+
+        for fen in positions {
+            let game = setup_game_with_fen(fen);
+            let mut board = game.get_chess_board().clone();
+
+            for mv in board.generate_moves(Color::White) {
+                let compact = mv.compact(&board);
+                assert_eq!(Move::from_compact(compact, &board), Some(mv.clone()));
+
+                assert_eq!(compact.is_capture(), mv.is_capture() || mv.en_passant);
+                assert_eq!(compact.is_en_passant(), mv.en_passant);
+                assert_eq!(compact.is_castling(), mv.castling.is_some());
+                assert_eq!(compact.is_double_push(), mv.en_passant_square.is_some());
+                assert_eq!(compact.is_promotion(), mv.promotion.is_some());
+                assert_eq!(compact.encoded(), mv.encode(&board));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unmake_restores_state_from_board() {
+        let game =
+            setup_game_with_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        let mut board = game.get_chess_board().clone();
+        let original_key = board.polyglot_key(Color::White);
+        let original_moves = board.generate_moves(Color::White);
+
+        for mv in original_moves.clone() {
+            board.make_move(&mv);
+            board.unmake_move(&mv);
+
+            assert_eq!(board.polyglot_key(Color::White), original_key);
+            assert_eq!(board.generate_moves(Color::White), original_moves);
+        }
+    }
+
+    #[test]
+    fn test_legal_moves_records() {
+        // Promotion with capture and check, en passant, castling and a rook check
+        let mut game = setup_game_with_fen("1n2k3/P7/8/3pP3/8/8/8/4K2R w K d6 0 1");
+
+        let records = game.legal_moves();
+        let uci_moves: Vec<String> = records.iter().map(|record| record.uci.clone()).collect();
+        let mut generated = game.generate_moves();
+        generated.sort();
+        let mut sorted = uci_moves.clone();
+        sorted.sort();
+        assert_eq!(sorted, generated);
+
+        let find = |uci: &str| records.iter().find(|record| record.uci == uci).unwrap();
+
+        let promotion = find("a7b8q");
+        assert_eq!(promotion.san, "axb8=Q+");
+        assert_eq!(promotion.piece, PieceType::Pawn);
+        assert!(promotion.is_capture && promotion.is_check && promotion.is_promotion);
+
+        let en_passant = find("e5d6");
+        assert_eq!(en_passant.san, "exd6");
+        assert!(en_passant.is_capture && !en_passant.is_check && !en_passant.is_promotion);
+
+        let castling = find("e1g1");
+        assert_eq!(castling.san, "O-O");
+        assert_eq!(castling.piece, PieceType::King);
+        assert!(!castling.is_capture && !castling.is_check);
+
+        let rook_check = find("h1h8");
+        assert_eq!(rook_check.san, "Rh8+");
+        assert_eq!(rook_check.piece, PieceType::Rook);
+        assert!(rook_check.is_check && !rook_check.is_capture);
+    }
+
+    fn assert_counts_match(board: &mut enrust::game_state::ChessBoard, color: Color, depth: u8) {
+        let moves = board.generate_moves(color);
+        assert_eq!(board.count_legal_moves(color), moves.len());
+
+        if depth > 1 {
+            for mv in moves {
+                board.make_move(&mv);
+                assert_counts_match(board, color.opposite(), depth - 1);
+                board.unmake_move(&mv);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_legal_moves_matches_generation() {
+        let positions = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                Color::White,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                Color::White,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", Color::White),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                Color::White,
+            ),
+            // En passant as the only way out of check
+            ("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1", Color::Black),
+            // Double check
+            ("4k3/8/8/8/7b/3n4/8/4K3 w - - 0 1", Color::White),
+        ];
+
+        for (fen, color) in positions {
+            let game = setup_game_with_fen(fen);
+            let mut board = game.get_chess_board().clone();
+            assert_counts_match(&mut board, color, 3);
+        }
+    }
+
+    #[test]
+    fn test_count_legal_moves_in_mate_and_stalemate() {
+        let mate = setup_game_with_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1");
+        let mut board = mate.get_chess_board().clone();
+        assert_eq!(board.count_legal_moves(Color::Black), 0);
+        assert!(board.is_checkmate(Color::Black));
+
+        let stalemate = setup_game_with_fen("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1");
+        let mut board = stalemate.get_chess_board().clone();
+        assert_eq!(board.count_legal_moves(Color::Black), 0);
+        assert!(!board.is_checkmate(Color::Black));
+    }
+
+    #[test]
+    fn test_transposition_moves_are_validated() {
+        let positions = [
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                Color::White,
+            ),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                Color::White,
+            ),
+            (
+                "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+                Color::Black,
+            ),
+            ("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1", Color::Black),
+            (
+                "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+                Color::White,
+            ),
+        ];
+
+        for (fen, color) in positions {
+            let game = setup_game_with_fen(fen);
+            let mut board = game.get_chess_board().clone();
+            let mut expected: Vec<u16> = board
+                .generate_moves(color)
+                .iter()
+                .map(|mv| mv.encode(&board))
+                .collect();
+            expected.sort_unstable();
+
+            // Try every from/to pair with every promotion code, as a
+            // colliding table entry could hold anything
+            let mut accepted = Vec::new();
+            for promotion in [0u16, 1, 2, 4, 8] {
+                for squares in 0..4096u16 {
+                    let encoded = promotion << 12 | squares;
+                    let Some(mv) = board.transposition_move(encoded, color) else {
+                        continue;
+                    };
+
+                    board.make_move(&mv);
+                    if !board.is_in_check(color) {
+                        accepted.push(encoded);
+                    }
+                    board.unmake_move(&mv);
+                }
+            }
+            accepted.sort_unstable();
+
+            assert_eq!(accepted, expected, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_rays_stop_at_the_edge_of_the_board() {
+        // a1 and d4 on the 12x10 board
+        let a1 = 21;
+        let d4 = 54;
+
+        assert_eq!(
+            directions::ray(a1, Direction::NORTH),
+            &[31, 41, 51, 61, 71, 81, 91]
+        );
+        assert_eq!(directions::ray(a1, Direction::NORTH_EAST).len(), 7);
+        assert!(directions::ray(a1, Direction::WEST).is_empty());
+        assert!(directions::ray(a1, Direction::SOUTH_EAST).is_empty());
+
+        let queen_squares: usize = directions::ALL
+            .iter()
+            .map(|&direction| directions::ray(d4, direction).len())
+            .sum();
+        assert_eq!(queen_squares, 27);
+
+        // Rays only go through the chess board
+        for direction in directions::ALL {
+            assert!(
+                directions::ray(d4, direction)
+                    .iter()
+                    .all(|&square| directions::is_on_board(square))
+            );
+        }
+        assert_eq!(
+            KNIGHT_JUMPS
+                .iter()
+                .filter(|&&jump| directions::is_on_board(a1 + jump))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_step_tables_leave_out_sentinels() {
+        // a1, h8 and e4 on the 12x10 board
+        let a1 = 21;
+        let h8 = 98;
+        let e4 = 55;
+
+        let mut knight = directions::knight_targets(a1).to_vec();
+        knight.sort();
+        assert_eq!(knight, vec![33, 42]);
+        assert_eq!(directions::knight_targets(e4).len(), 8);
+
+        assert_eq!(directions::king_targets(a1).len(), 3);
+        assert_eq!(directions::king_targets(h8).len(), 3);
+        assert_eq!(directions::king_targets(e4).len(), 8);
+
+        assert_eq!(directions::pawn_attacks(Color::White, a1), &[32]);
+        assert!(directions::pawn_attacks(Color::White, h8).is_empty());
+        let mut black = directions::pawn_attacks(Color::Black, e4).to_vec();
+        black.sort();
+        assert_eq!(black, vec![44, 46]);
+    }
 }
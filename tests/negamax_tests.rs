@@ -6,6 +6,7 @@ mod negamax_tests {
     use enrust::game_state::ChessBoard;
     use enrust::game_state::Color;
     use enrust::game_state::GameState;
+    use enrust::game_state::Score;
     use enrust::game_state::board::search::{PureNegamax, SearchAlgorithm};
 
     fn setup_test_game(fen: &str) -> ChessBoard {
@@ -31,7 +32,7 @@ mod negamax_tests {
 
         // Score should be reasonable (not extreme values)
         assert!(
-            score.abs() < 1000,
+            score.centipawns().abs() < 1000,
             "Score should be reasonable, got: {}",
             score
         );
@@ -47,7 +48,7 @@ mod negamax_tests {
 
         // Should find checkmate
         assert!(
-            score > 10000,
+            score.is_mate() && score > Score::ZERO,
             "Should find winning position, score: {}",
             score
         );
@@ -69,7 +70,7 @@ mod negamax_tests {
 
         // Should find checkmate (negative score from black's perspective)
         assert!(
-            score < -10000,
+            score.is_mate() && score < Score::ZERO,
             "Should find winning position for black, score: {}",
             score
         );
@@ -91,7 +92,7 @@ mod negamax_tests {
 
         // Should recognize stalemate (score from black's perspective ≈ 0)
         assert!(
-            score.abs() < 100,
+            score.centipawns().abs() < 100,
             "Should recognize stalemate, got score: {}",
             score
         );
@@ -117,7 +118,7 @@ mod negamax_tests {
 
         // Score should reflect material advantage
         assert!(
-            score > 800,
+            score > Score::cp(800),
             "Should have significant advantage after capture, score: {}",
             score
         );
@@ -145,7 +146,7 @@ mod negamax_tests {
 
         // Score should reflect queen advantage (adjusted for PST)
         assert!(
-            score >= 800,
+            score >= Score::cp(800),
             "Should have queen advantage, score: {}",
             score
         );
@@ -170,7 +171,11 @@ mod negamax_tests {
         game.unmake_move(&best_move);
 
         // Score should not be extremely negative
-        assert!(score > -10000, "Should avoid checkmate, score: {}", score);
+        assert!(
+            score > Score::cp(-10000),
+            "Should avoid checkmate, score: {}",
+            score
+        );
     }
 
     #[test]
@@ -205,9 +210,9 @@ mod negamax_tests {
         );
 
         // Scores should be reasonable
-        assert!(score_depth_1.abs() < 1000);
-        assert!(score_depth_2.abs() < 1000);
-        assert!(score_depth_3.abs() < 1000);
+        assert!(score_depth_1.centipawns().abs() < 1000);
+        assert!(score_depth_2.centipawns().abs() < 1000);
+        assert!(score_depth_3.centipawns().abs() < 1000);
     }
 
     #[test]
@@ -223,7 +228,7 @@ mod negamax_tests {
 
         // Scores should be approximately opposite (white positive, black negative)
         assert!(
-            (score_white + score_black).abs() < 50,
+            (score_white + score_black).centipawns().abs() < 50,
             "Symmetric position should have opposite scores: white={}, black={}",
             score_white,
             score_black
@@ -232,15 +237,15 @@ mod negamax_tests {
 
     #[test]
     fn test_negamax_material_advantage() {
-        // White has extra queen
-        let mut game = setup_test_game("k7/8/8/8/8/8/1Q6/K7 w - - 0 1");
+        // White has extra queen, pawns keep it out of the known endgames
+        let mut game = setup_test_game("k7/7p/8/8/8/8/1Q5P/K7 w - - 0 1");
 
         let stop_flag = Arc::new(AtomicBool::new(false));
         let (score, _) = PureNegamax.search(&mut game, 1, Color::White, stop_flag);
 
         // Should show significant advantage (around +900 for queen)
         assert!(
-            score > 800 && score < 1000,
+            score > Score::cp(800) && score < Score::cp(1000),
             "Should show queen advantage, got: {}",
             score
         );
@@ -267,7 +272,7 @@ mod negamax_tests {
 
             // Score should be near 0 for equal position (PST gives slight offset)
             assert!(
-                score.abs() < 100,
+                score.centipawns().abs() < 100,
                 "Depth {}: Kings only should be near equal, got: {}",
                 depth,
                 score
@@ -284,9 +289,46 @@ mod negamax_tests {
         let (score, best_move) = PureNegamax.search(&mut game, 3, Color::Black, stop_flag);
 
         assert!(
-            score > 10000,
+            score.is_mate() && score > Score::ZERO,
             "Black is being mated, score should be very high for white"
         );
         assert!(best_move.is_some(), "There's two forced moves for black");
     }
+
+    #[test]
+    fn test_negamax_shallow_depths() {
+        // Checkmate and stalemate have no move to return at any depth
+        for fen in [
+            "k7/1Q6/1K6/8/8/8/8/8 b - - 0 1",
+            "k7/8/1Q6/8/8/8/8/7K b - - 0 1",
+        ] {
+            let mut game = setup_test_game(fen);
+            for depth in [0, 1] {
+                let stop_flag = Arc::new(AtomicBool::new(false));
+                let (_, best_move) = PureNegamax.search(&mut game, depth, Color::Black, stop_flag);
+                assert!(best_move.is_none(), "{} at depth {}", fen, depth);
+            }
+        }
+
+        // Depth 0 searches the root moves at the horizon, like depth 1
+        let mut game = setup_test_game("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1");
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (score, best_move) = PureNegamax.search(&mut game, 0, Color::White, stop_flag.clone());
+        assert_eq!(best_move.unwrap().to_uci(&game), "e4d5");
+        assert_eq!(
+            score,
+            PureNegamax
+                .search(&mut game, 1, Color::White, stop_flag.clone())
+                .0
+        );
+
+        // Below the horizon the position is evaluated
+        let evaluation = game.evaluate_for(Color::White);
+        for depth in [-3, -1, 0] {
+            assert_eq!(
+                PureNegamax.tree_search(&mut game, depth, Color::White, stop_flag.clone()),
+                evaluation
+            );
+        }
+    }
 }
@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod nnue_tests {
+    use std::sync::Arc;
+
+    use enrust::game_state::ChessBoard;
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::board::evaluation::nnue::{FEATURES, Network, simd};
+
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const PROMOTIONS: &str = "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1";
+
+    /// Builds a small network with deterministic pseudo-random weights.
+    fn test_network() -> Network {
+        let hidden_size = 8;
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |range: i64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            ((seed % (2 * range as u64 + 1)) as i64 - range) as i16
+        };
+
+        let feature_weights = (0..FEATURES * hidden_size).map(|_| next(20)).collect();
+        let feature_biases = (0..hidden_size).map(|_| next(50) + 100).collect();
+        let output_weights = (0..2 * hidden_size).map(|_| next(60)).collect();
+
+        Network::new(
+            hidden_size,
+            feature_weights,
+            feature_biases,
+            output_weights,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn setup_test_game(fen: &str, network: &Arc<Network>) -> ChessBoard {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        let mut board = game.get_chess_board().clone();
+        board.set_network(Some(network.clone()));
+        board
+    }
+
+    /// Recomputes the accumulator of the board from scratch.
+    fn refreshed(board: &ChessBoard, network: &Arc<Network>) -> ChessBoard {
+        let mut fresh = board.clone();
+        fresh.set_network(Some(network.clone()));
+        fresh
+    }
+
+    fn assert_incremental_matches_refresh(
+        board: &mut ChessBoard,
+        network: &Arc<Network>,
+        color: Color,
+        depth: u8,
+    ) {
+        if depth == 0 {
+            return;
+        }
+
+        for mv in board.generate_moves(color) {
+            let before = board.nnue().unwrap().accumulator().clone();
+
+            board.make_move(&mv);
+            assert_eq!(
+                board.nnue().unwrap().accumulator(),
+                refreshed(board, network).nnue().unwrap().accumulator(),
+                "Accumulator mismatch after {}",
+                board.move_to_uci(&mv)
+            );
+            assert_incremental_matches_refresh(board, network, color.opposite(), depth - 1);
+            board.unmake_move(&mv);
+
+            assert_eq!(board.nnue().unwrap().accumulator(), &before);
+        }
+    }
+
+    #[test]
+    fn test_incremental_updates_match_full_refresh() {
+        let network = Arc::new(test_network());
+
+        // Castling, en passant, captures and king moves
+        let mut board = setup_test_game(KIWIPETE, &network);
+        assert_incremental_matches_refresh(&mut board, &network, Color::White, 2);
+
+        // Promotions with and without captures
+        let mut board = setup_test_game(PROMOTIONS, &network);
+        assert_incremental_matches_refresh(&mut board, &network, Color::Black, 2);
+    }
+
+    #[test]
+    fn test_mirrored_position_swaps_perspectives() {
+        let network = Arc::new(test_network());
+
+        let board = setup_test_game("4k3/3ppp2/8/8/8/8/2N5/4K3 w - - 0 1", &network);
+        let mirrored = setup_test_game("4k3/2n5/8/8/8/8/3PPP2/4K3 b - - 0 1", &network);
+
+        let accumulator = board.nnue().unwrap().accumulator();
+        let mirrored_accumulator = mirrored.nnue().unwrap().accumulator();
+        assert_eq!(accumulator.white, mirrored_accumulator.black);
+        assert_eq!(accumulator.black, mirrored_accumulator.white);
+    }
+
+    #[test]
+    fn test_network_file_round_trip() {
+        let network = test_network();
+        let bytes = network.to_bytes();
+
+        let loaded = Network::from_bytes(&bytes).expect("valid network");
+        assert_eq!(loaded.hidden_size(), 8);
+        assert_eq!(loaded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_invalid_network_files_are_rejected() {
+        let bytes = test_network().to_bytes();
+
+        assert!(Network::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Network::from_bytes(b"NOPE").is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(Network::from_bytes(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_missing_network_falls_back_to_handcrafted_eval() {
+        let mut game = GameState::new(None);
+        game.start_position();
+        let handcrafted = game.get_chess_board().evaluate();
+
+        game.set_eval_file(&std::env::temp_dir().join("enrust_missing.nnue"))
+            .unwrap();
+        assert!(game.set_use_nnue(true).is_err());
+
+        assert!(!game.is_nnue_active());
+        assert_eq!(game.get_chess_board().evaluate(), handcrafted);
+    }
+
+    #[test]
+    fn test_loaded_network_is_used_for_evaluation() {
+        let network = test_network();
+        let path = std::env::temp_dir().join(format!("enrust_{}.nnue", std::process::id()));
+        std::fs::write(&path, network.to_bytes()).unwrap();
+
+        let mut game = GameState::new(None);
+        game.set_eval_file(&path).unwrap();
+        game.set_use_nnue(true).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        game.set_fen_position(KIWIPETE);
+        assert!(game.is_nnue_active());
+
+        let expected = setup_test_game(KIWIPETE, &Arc::new(network)).evaluate();
+        assert_eq!(game.get_chess_board().evaluate(), expected);
+
+        game.set_use_nnue(false).unwrap();
+        assert!(!game.is_nnue_active());
+    }
+
+    #[test]
+    fn test_vectorized_helpers_match_scalar() {
+        // Odd length to exercise the scalar tail, extreme values to check wrapping
+        let values: Vec<i16> = (0..37)
+            .map(|i| match i % 4 {
+                0 => i16::MAX - i,
+                1 => i16::MIN + i,
+                2 => i * 13 - 200,
+                _ => i * 7,
+            })
+            .collect();
+        let weights: Vec<i16> = (0..37).map(|i| (i * 31 % 97) - 48).collect();
+
+        let mut expected = values.clone();
+        let mut actual = values.clone();
+        simd::scalar::add_weights(&mut expected, &weights);
+        simd::add_weights(&mut actual, &weights);
+        assert_eq!(actual, expected);
+
+        simd::scalar::sub_weights(&mut expected, &weights);
+        simd::sub_weights(&mut actual, &weights);
+        assert_eq!(actual, values);
+        assert_eq!(expected, values);
+
+        assert_eq!(
+            simd::clipped_dot(&values, &weights),
+            simd::scalar::clipped_dot(&values, &weights)
+        );
+    }
+}
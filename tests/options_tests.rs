@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod options_tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use enrust::game_state::board::transposition_table::{TranspositionTable, Zobrist};
+    use enrust::game_state::options::{DEFAULT_HASH_MB, EngineOptions};
+    use enrust::game_state::topology;
+    use enrust::game_state::uci::{MAX_THREADS, handle_setoption_command};
+    use enrust::game_state::{
+        DEFAULT_MAX_DEPTH, DEFAULT_MOVE_OVERHEAD, GameState, SearchConfiguration,
+    };
+
+    fn nodes_limited(nodes: u64) -> SearchConfiguration {
+        let mut sc = SearchConfiguration::new();
+        sc.nodes = Some(nodes);
+        sc
+    }
+
+    #[test]
+    fn test_default_options() {
+        let options = EngineOptions::new();
+
+        assert_eq!(options.hash_mb(), DEFAULT_HASH_MB);
+        assert_eq!(options.threads(), 1);
+        assert!(!options.thread_affinity());
+        assert_eq!(options.move_overhead(), DEFAULT_MOVE_OVERHEAD);
+        assert_eq!(options.nodes_per_move(), None);
+        assert_eq!(options.max_depth(), DEFAULT_MAX_DEPTH);
+        assert!(options.ponder_time_counts());
+        assert!(options.instant_moves());
+        assert!(options.resume_analysis());
+        assert!(!options.ponder());
+        assert!(!options.permanent_brain());
+        assert!(!options.quiescence_checks());
+    }
+
+    #[test]
+    fn test_limits_are_clamped() {
+        let options = EngineOptions::new();
+
+        options.set_threads(0);
+        options.set_max_depth(0);
+        options.set_nodes_per_move(Some(0));
+
+        assert_eq!(options.threads(), 1);
+        assert_eq!(options.max_depth(), 1);
+        assert_eq!(options.nodes_per_move(), None);
+    }
+
+    #[test]
+    fn test_games_sharing_options_see_every_change() {
+        let options = Arc::new(EngineOptions::new());
+        let mut game = GameState::with_options(Arc::clone(&options));
+        let other = GameState::with_options(Arc::clone(&options));
+
+        game.set_max_depth(7);
+        game.set_nodes_per_move(Some(1_000));
+        let writer = Arc::clone(&options);
+        thread::spawn(move || writer.set_thread_affinity(true))
+            .join()
+            .unwrap();
+
+        assert_eq!(other.max_depth(), 7);
+        assert_eq!(other.nodes_per_move(), Some(1_000));
+        assert!(game.thread_affinity() && other.thread_affinity());
+        assert!(Arc::ptr_eq(game.options(), other.options()));
+    }
+
+    #[test]
+    fn test_hash_size_change_applies_at_the_next_search() {
+        let options = Arc::new(EngineOptions::new());
+        options.set_hash_mb(1);
+        let mut game = GameState::with_options(Arc::clone(&options));
+        game.start_position();
+        assert_eq!(game.hash_size_mb(), 1);
+
+        // Changed from another thread while the game is idle
+        let writer = Arc::clone(&options);
+        thread::spawn(move || writer.set_hash_mb(4)).join().unwrap();
+        assert_eq!(game.hash_size_mb(), 1);
+
+        game.set_time_control(&nodes_limited(1_000));
+        game.search().join();
+
+        assert_eq!(game.hash_size_mb(), 4);
+    }
+
+    #[test]
+    fn test_resize_hash_table_updates_the_options() {
+        let mut game = GameState::new(Some(1));
+        assert_eq!(game.options().hash_mb(), 1);
+
+        game.resize_hash_table(2);
+
+        assert_eq!(game.hash_size_mb(), 2);
+        assert_eq!(game.options().hash_mb(), 2);
+    }
+
+    #[test]
+    fn test_setoption_threads() {
+        let mut game = GameState::new(Some(1));
+
+        handle_setoption_command(&mut game, &mut "name Threads value 1".split_whitespace());
+        assert_eq!(game.options().threads(), 1);
+
+        // Out of range values are reported and ignored
+        let too_many = format!("name Threads value {}", MAX_THREADS + 1);
+        handle_setoption_command(&mut game, &mut too_many.split_whitespace());
+        assert_eq!(game.options().threads(), 1);
+
+        // 0 is a thread per physical core, as far as the search allows
+        handle_setoption_command(&mut game, &mut "name Threads value 0".split_whitespace());
+        assert_eq!(
+            game.options().threads(),
+            topology::physical_cores().min(MAX_THREADS)
+        );
+    }
+
+    #[test]
+    fn test_setoption_quiescence_checks() {
+        let mut game = GameState::new(Some(1));
+
+        handle_setoption_command(
+            &mut game,
+            &mut "name QuiescenceChecks value true".split_whitespace(),
+        );
+        assert!(game.quiescence_checks());
+
+        // Invalid values are reported and ignored
+        handle_setoption_command(
+            &mut game,
+            &mut "name QuiescenceChecks value maybe".split_whitespace(),
+        );
+        assert!(game.quiescence_checks());
+    }
+
+    #[test]
+    fn test_constructors_create_their_own_tables() {
+        assert_eq!(GameState::with_hash_size(2).hash_size_mb(), 2);
+        assert_eq!(GameState::new(None).hash_size_mb(), 0);
+        assert_eq!(GameState::default().hash_size_mb(), DEFAULT_HASH_MB);
+        assert_eq!(GameState::default().max_depth(), DEFAULT_MAX_DEPTH);
+    }
+
+    #[test]
+    fn test_games_can_share_a_transposition_table() {
+        let options = Arc::new(EngineOptions::new());
+        options.set_hash_mb(1);
+        let keys = Arc::new(Zobrist::polyglot());
+        let table = Arc::new(TranspositionTable::new(1));
+        let mut first = GameState::with_shared_tables(
+            Arc::clone(&options),
+            Arc::clone(&keys),
+            Arc::clone(&table),
+        );
+        let mut second =
+            GameState::with_shared_tables(options, Arc::clone(&keys), Arc::clone(&table));
+        first.start_position();
+        second.set_fen_position("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+
+        first.set_time_control(&nodes_limited(1_000));
+        let first_move = first.search().join().best_move.unwrap();
+        second.set_time_control(&nodes_limited(1_000));
+        let second_move = second.search().join().best_move.unwrap();
+
+        // The table is keyed by the board hash, which the Polyglot key
+        // flips the side key of: both searches filled the shared table
+        assert!(first.make_move(&first_move.to_string()));
+        assert!(second.make_move(&second_move.to_string()));
+        for game in [&first, &second] {
+            let hash = game.polyglot_key() ^ keys.side_to_move;
+            assert!(table.retrieve_position(hash).is_some());
+        }
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod pawn_tests {
-    use enrust::game_state::GameState;
+    use enrust::game_state::board::piece::Piece;
+    use enrust::game_state::{Color, GameState};
 
     fn setup_game_with_fen(fen: &str) -> GameState {
         let mut game = GameState::new(None);
@@ -66,11 +67,48 @@ mod pawn_tests {
         assert!(moves.contains(&"e2e3".to_string()));
         assert!(moves.contains(&"e2e4".to_string()));
     }
+
+    #[test]
+    fn test_double_push_of_both_colors() {
+        for (fen, color, double_push, en_passant) in [
+            (
+                "4k3/8/8/3p4/8/8/4P3/4K3 w - - 0 1",
+                Color::White,
+                "e2e4",
+                "e3",
+            ),
+            (
+                "4k3/4p3/8/8/3P4/8/8/4K3 b - - 0 1",
+                Color::Black,
+                "e7e5",
+                "e6",
+            ),
+        ] {
+            let game = setup_game_with_fen(fen);
+            let mut board = game.get_chess_board().clone();
+
+            let moves = board.generate_moves(color);
+            let mv = moves
+                .iter()
+                .find(|mv| mv.to_uci(&board) == double_push)
+                .expect("the pawn can push two squares");
+
+            // Nothing is captured on the far square, the square passed is
+            // the en passant target
+            assert_eq!(mv.captured_piece, Piece::EmptySquare);
+            assert!(!mv.is_capture());
+            assert_eq!(mv.promotion, None);
+            assert!(!mv.en_passant);
+            let mut game = setup_game_with_fen(fen);
+            assert!(game.make_move(double_push));
+            assert_eq!(game.to_fen().split(' ').nth(3), Some(en_passant));
+        }
+    }
 }
 
 #[cfg(test)]
 mod promotion_tests {
-    use enrust::game_state::GameState;
+    use enrust::game_state::{Color, GameState};
 
     fn setup_game_with_fen(fen: &str) -> GameState {
         let mut game = GameState::new(None);
@@ -181,6 +219,29 @@ mod promotion_tests {
         assert_eq!(pawn_moves.len(), 1); // single push
         assert!(pawn_moves.iter().all(|mv| mv.len() < 5));
     }
+
+    #[test]
+    fn test_promotions_and_captures_are_tactical() {
+        let game = setup_game_with_fen("1n2k3/P7/8/3p4/4P3/8/8/4K3 w - - 0 1");
+        let mut board = game.get_chess_board().clone();
+        let moves = board.generate_moves(Color::White);
+        let find = |uci: &str| {
+            moves
+                .iter()
+                .find(|mv| mv.to_uci(&board) == uci)
+                .expect("the move is legal")
+        };
+
+        assert!(find("a7a8q").is_queen_promotion());
+        assert!(find("a7a8q").is_tactical());
+        assert!(find("a7b8q").is_tactical());
+        assert!(find("e4d5").is_tactical());
+
+        // Underpromotions and quiet moves are left to the main search
+        assert!(!find("a7a8n").is_queen_promotion());
+        assert!(!find("a7a8n").is_tactical());
+        assert!(!find("e4e5").is_tactical());
+    }
 }
 
 #[cfg(test)]
@@ -252,7 +313,8 @@ mod blocked_pawn_tests {
 
 #[cfg(test)]
 mod en_passant_tests {
-    use enrust::game_state::GameState;
+    use enrust::game_state::board::piece::Piece;
+    use enrust::game_state::{ChessBoard, Color, GameState};
 
     fn setup_game_with_fen(fen: &str) -> GameState {
         let mut game = GameState::new(None);
@@ -260,6 +322,191 @@ mod en_passant_tests {
         game
     }
 
+    /// Gets the sorted moves of both sides, generated from the piece lists.
+    fn all_moves(board: &mut ChessBoard) -> Vec<String> {
+        let mut moves = Vec::new();
+        for color in [Color::White, Color::Black] {
+            for mv in board.generate_moves(color) {
+                moves.push(board.move_to_uci(&mv));
+            }
+        }
+        moves.sort();
+        moves
+    }
+
+    /// Checks that the board and its piece lists agree with a board set up
+    /// from scratch.
+    fn assert_same_position(board: &mut ChessBoard, expected: &mut ChessBoard, side: Color) {
+        assert_eq!(board.polyglot_key(side), expected.polyglot_key(side));
+        assert_eq!(all_moves(board), all_moves(expected));
+        assert_eq!(board.evaluate(), expected.evaluate());
+    }
+
+    fn assert_en_passant_round_trip(fen: &str, uci: &str, fen_after: &str) {
+        let game = setup_game_with_fen(fen);
+        let side = game.side_to_move();
+        let mut board = game.get_chess_board().clone();
+        let mut before = board.clone();
+        let mut after = setup_game_with_fen(fen_after).get_chess_board().clone();
+
+        let mv = board.from_uci(uci).expect("valid move");
+        assert!(mv.en_passant, "{} is not an en passant capture", uci);
+
+        board.make_move(&mv);
+        assert_same_position(&mut board, &mut after, side.opposite());
+
+        board.unmake_move(&mv);
+        assert_same_position(&mut board, &mut before, side);
+    }
+
+    #[test]
+    fn test_en_passant_is_a_capture() {
+        let mut game = setup_game_with_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 5");
+        let mut board = game.get_chess_board().clone();
+
+        let generated = board
+            .generate_moves(Color::White)
+            .into_iter()
+            .find(|mv| mv.en_passant)
+            .expect("e5d6 is legal");
+        let parsed = board.from_uci("e5d6").expect("valid move");
+        assert_eq!(generated, parsed);
+
+        // The pawn taken is carried by the move, beside its destination
+        assert!(parsed.is_capture());
+        assert!(parsed.is_tactical());
+        assert_eq!(parsed.captured_piece, Piece::BlackPawn);
+        assert_eq!(board.see(&parsed), 100);
+        assert!(board.move_to_san(&parsed).contains('x'));
+
+        // A capture resets the halfmove clock
+        assert!(game.make_move("e5d6"));
+        assert_eq!(game.halfmove_clock(), 0);
+    }
+
+    #[test]
+    fn test_en_passant_from_either_adjacent_file() {
+        // Pawns on both sides of the pushed pawn may take it en passant
+        let game = setup_game_with_fen("4k3/8/8/2PpP3/8/8/8/4K3 w - d6 0 1");
+        let board = game.get_chess_board();
+        for uci in ["c5d6", "e5d6"] {
+            let mv = board.from_uci(uci).expect("valid move");
+            assert!(mv.en_passant, "{} is not an en passant capture", uci);
+            assert_eq!(mv.captured_piece, Piece::BlackPawn);
+        }
+
+        // Same for Black
+        let game = setup_game_with_fen("4k3/8/8/8/2pPp3/8/8/4K3 b - d3 0 1");
+        let board = game.get_chess_board();
+        for uci in ["c4d3", "e4d3"] {
+            let mv = board.from_uci(uci).expect("valid move");
+            assert!(mv.en_passant, "{} is not an en passant capture", uci);
+            assert_eq!(mv.captured_piece, Piece::WhitePawn);
+        }
+
+        // Only a pawn can take en passant
+        let game = setup_game_with_fen("4k3/8/8/3pP3/4N3/8/8/4K3 w - d6 0 1");
+        let mv = game.get_chess_board().from_uci("e4d6");
+        assert!(mv.is_some_and(|mv| !mv.en_passant));
+        let mv = game.get_chess_board().from_uci("e5e6").expect("valid move");
+        assert!(!mv.en_passant);
+    }
+
+    #[test]
+    fn test_en_passant_evades_pawn_check() {
+        // The pawn that was just pushed gives check and can be taken en passant
+        let mut game = setup_game_with_fen("8/8/8/4k3/3Pp3/8/8/4K3 b - d3 0 1");
+
+        let moves = game.generate_moves();
+        assert!(moves.contains(&"e4d3".to_string()));
+        assert!(!moves.contains(&"e4e3".to_string()));
+    }
+
+    #[test]
+    fn test_en_passant_exposing_king_on_rank() {
+        // Both pawns leave the rank between the king and the rook
+        let mut game = setup_game_with_fen("8/8/8/K2pP2r/8/8/8/4k3 w - d6 0 1");
+
+        let moves = game.generate_moves();
+        assert!(!moves.contains(&"e5d6".to_string()));
+        assert!(moves.contains(&"e5e6".to_string()));
+    }
+
+    #[test]
+    fn test_en_passant_exposing_king_on_diagonal() {
+        // The captured pawn shields the king from the bishop
+        let mut game = setup_game_with_fen("8/5b2/8/3pP3/8/1K6/8/7k w - d6 0 1");
+
+        let moves = game.generate_moves();
+        assert!(!moves.contains(&"e5d6".to_string()));
+        assert!(moves.contains(&"e5e6".to_string()));
+    }
+
+    #[test]
+    fn test_en_passant_along_pin() {
+        // The pinned pawn may still capture towards the pinning bishop
+        let mut game = setup_game_with_fen("4k3/6b1/8/4Pp2/8/8/1K6/8 w - f6 0 1");
+
+        let moves = game.generate_moves();
+        assert!(moves.contains(&"e5f6".to_string()));
+        assert!(!moves.contains(&"e5e6".to_string()));
+    }
+
+    #[test]
+    fn test_pawn_pinned_on_diagonal_behind_it() {
+        // The bishop pins the pawn from behind: capturing on the other
+        // diagonal would leave the king in check
+        let mut game = setup_game_with_fen("4k3/8/2K1p3/3P4/4b3/8/8/8 w - - 0 1");
+        let moves = game.generate_moves();
+        assert!(!moves.contains(&"d5e6".to_string()));
+        assert!(!moves.contains(&"d5d6".to_string()));
+
+        // Same for Black
+        let mut game = setup_game_with_fen("4k3/8/8/4B3/3p4/2k1P3/8/8 b - - 0 1");
+        let moves = game.generate_moves();
+        assert!(!moves.contains(&"d4e3".to_string()));
+        assert!(!moves.contains(&"d4d3".to_string()));
+    }
+
+    #[test]
+    fn test_en_passant_make_unmake_consistency() {
+        // White capturing towards both sides
+        assert_en_passant_round_trip(
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1",
+            "e5d6",
+            "4k3/8/3P4/8/8/8/8/4K3 b - - 0 1",
+        );
+        assert_en_passant_round_trip(
+            "4k3/8/8/4Pp2/8/8/8/4K3 w - f6 0 1",
+            "e5f6",
+            "4k3/8/5P2/8/8/8/8/4K3 b - - 0 1",
+        );
+
+        // Black capturing towards both sides
+        assert_en_passant_round_trip(
+            "4k3/8/8/8/3Pp3/8/8/4K3 b - d3 0 1",
+            "e4d3",
+            "4k3/8/8/8/8/3p4/8/4K3 w - - 0 1",
+        );
+        assert_en_passant_round_trip(
+            "4k3/8/8/8/4pP2/8/8/4K3 b - f3 0 1",
+            "e4f3",
+            "4k3/8/8/8/8/5p2/8/4K3 w - - 0 1",
+        );
+
+        // Captures next to the edges of the board
+        assert_en_passant_round_trip(
+            "4k3/8/8/Pp6/8/8/8/4K3 w - b6 0 1",
+            "a5b6",
+            "4k3/8/1P6/8/8/8/8/4K3 b - - 0 1",
+        );
+        assert_en_passant_round_trip(
+            "4k3/8/8/8/6Pp/8/8/4K3 b - g3 0 1",
+            "h4g3",
+            "4k3/8/8/8/8/6p1/8/4K3 w - - 0 1",
+        );
+    }
+
     #[test]
     fn test_en_passant_capture() {
         let mut game = setup_game_with_fen("8/8/8/3pP3/8/8/8/8 w - d6 0 1");
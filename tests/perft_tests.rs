@@ -22,7 +22,6 @@ mod perft_tests {
         run_perft_test(fen, 2, 400);
         run_perft_test(fen, 3, 8902);
         run_perft_test(fen, 4, 197281);
-        // run_perft_test(fen, 5, 4865609);  // Deep test for later
     }
 
     #[test]
@@ -32,7 +31,6 @@ mod perft_tests {
         run_perft_test(fen, 1, 48);
         run_perft_test(fen, 2, 2039);
         run_perft_test(fen, 3, 97862);
-        // run_perft_test(fen, 4, 4085603);
     }
 
     #[test]
@@ -43,7 +41,6 @@ mod perft_tests {
         run_perft_test(fen, 2, 191);
         run_perft_test(fen, 3, 2812);
         run_perft_test(fen, 4, 43238);
-        // run_perft_test(fen, 5, 674624);
     }
 
     #[test]
@@ -53,7 +50,15 @@ mod perft_tests {
         run_perft_test(fen, 1, 6);
         run_perft_test(fen, 2, 264);
         run_perft_test(fen, 3, 9467);
-        // run_perft_test(fen, 4, 422333);
+    }
+
+    #[test]
+    fn test_perft_position_4_mirrored() {
+        // Same as position 4 with colors swapped, must give the same counts
+        let fen = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+        run_perft_test(fen, 1, 6);
+        run_perft_test(fen, 2, 264);
+        run_perft_test(fen, 3, 9467);
     }
 
     #[test]
@@ -63,7 +68,15 @@ mod perft_tests {
         run_perft_test(fen, 1, 44);
         run_perft_test(fen, 2, 1486);
         run_perft_test(fen, 3, 62379);
-        // run_perft_test(fen, 4, 2103487);
+    }
+
+    #[test]
+    fn test_perft_position_6() {
+        // Quiet middlegame position with pins on both sides
+        let fen = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+        run_perft_test(fen, 1, 46);
+        run_perft_test(fen, 2, 2079);
+        run_perft_test(fen, 3, 89890);
     }
 
     #[test]
@@ -74,6 +87,32 @@ mod perft_tests {
         run_perft_test(fen, 2, 568);
     }
 
+    #[test]
+    fn test_perft_chess960() {
+        // Chess960 positions, with castling rights in Shredder-FEN
+        let fen = "nnrbkqbr/pppppppp/8/8/8/8/PPPPPPPP/NNRBKQBR w HChc - 0 1";
+        run_perft_test(fen, 1, 19);
+        run_perft_test(fen, 2, 361);
+        run_perft_test(fen, 3, 7710);
+        run_perft_test(fen, 4, 163799);
+
+        let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        run_perft_test(fen, 1, 21);
+        run_perft_test(fen, 2, 528);
+        run_perft_test(fen, 3, 12189);
+
+        let fen = "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9";
+        run_perft_test(fen, 1, 21);
+        run_perft_test(fen, 2, 807);
+        run_perft_test(fen, 3, 18002);
+
+        // The king castles in place, and the rook leaves a queen's ray open
+        let fen = "k7/8/8/8/8/8/8/qRK4R w BH - 0 1";
+        run_perft_test(fen, 1, 16);
+        run_perft_test(fen, 2, 213);
+        run_perft_test(fen, 3, 3921);
+    }
+
     #[test]
     fn test_perft_promotion() {
         // Position from: http://www.rocechess.ch/perft.html
@@ -83,4 +122,174 @@ mod perft_tests {
         run_perft_test(fen, 2, 496);
         run_perft_test(fen, 3, 9483);
     }
+
+    #[test]
+    fn test_perft_edge_cases() {
+        // Stalemating yourself
+        run_perft_test("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6, 2217);
+        // Stalemate and checkmate in the tree
+        run_perft_test("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4, 23527);
+    }
+
+    /// Swaps the colors of a position: the board is flipped upside down,
+    /// White's pieces become Black's and the other side is to move.
+    fn mirror(fen: &str) -> String {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        let swap_case = |text: &str| -> String {
+            text.chars()
+                .map(|c| {
+                    if c.is_ascii_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    }
+                })
+                .collect()
+        };
+
+        let placement: Vec<&str> = fields[0].split('/').rev().collect();
+        let side = if fields[1] == "w" { "b" } else { "w" };
+        let mut castling: Vec<char> = swap_case(fields[2]).chars().collect();
+        castling.sort_by_key(|c| "KQkq-".find(*c));
+        let en_passant = match fields[3].as_bytes() {
+            [file, b'3'] => format!("{}6", *file as char),
+            [file, b'6'] => format!("{}3", *file as char),
+            _ => "-".to_string(),
+        };
+
+        format!(
+            "{} {} {} {} {}",
+            swap_case(&placement.join("/")),
+            side,
+            castling.into_iter().collect::<String>(),
+            en_passant,
+            fields[4..].join(" ")
+        )
+    }
+
+    #[test]
+    fn test_mirror() {
+        assert_eq!(
+            mirror("r3k2r/8/8/2Pp4/8/8/8/R3K2R w Kq d6 0 1"),
+            "r3k2r/8/8/8/2pP4/8/8/R3K2R b Qk d3 0 1"
+        );
+    }
+
+    #[test]
+    fn test_perft_pawn_moves_of_both_colors() {
+        let positions = [
+            // Double pushes of both sides from the start position
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                3,
+                8902,
+            ),
+            // Double pushes answered by en passant captures
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4, 43238),
+            // Promotions with capture
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                3,
+                9467,
+            ),
+            ("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1", 3, 9483),
+        ];
+
+        for (fen, depth, expected_nodes) in positions {
+            run_perft_test(fen, depth, expected_nodes);
+            run_perft_test(&mirror(fen), depth, expected_nodes);
+        }
+    }
+
+    #[test]
+    fn test_perft_en_passant_is_color_symmetric() {
+        let positions = [
+            // En passant capture giving check
+            "8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1",
+            // En passant captures exposing the king
+            "3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1",
+            "8/5bk1/8/2Pp4/8/1K6/8/8 w - d6 0 1",
+            // Double pushes next to enemy pawns, with captures promoting
+            "4k3/1p1p1p2/2P1P3/8/3p1p2/8/2P1P1p1/4K2R w K - 0 1",
+        ];
+
+        for fen in positions {
+            let mut game = GameState::new(None);
+            assert!(game.set_fen_position(fen), "Failed to set FEN: {}", fen);
+            let nodes = game.perft_debug(4, false);
+
+            run_perft_test(&mirror(fen), 4, nodes);
+        }
+    }
+
+    // Deep perft runs, too slow for every test run:
+    // `cargo test --release --test perft_tests -- --ignored`
+
+    #[test]
+    #[ignore]
+    fn test_perft_deep_standard_positions() {
+        let positions = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                5,
+                4865609,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                4,
+                4085603,
+            ),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                4,
+                422333,
+            ),
+            (
+                "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+                4,
+                2103487,
+            ),
+            (
+                "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+                4,
+                3894594,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 5, 674624),
+        ];
+
+        for (fen, depth, expected_nodes) in positions {
+            run_perft_test(fen, depth, expected_nodes);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_perft_deep_edge_cases() {
+        let positions = [
+            // En passant capture giving check
+            ("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6, 1440467),
+            // En passant captures exposing the king
+            ("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6, 1134888),
+            ("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6, 1015133),
+            ("8/5bk1/8/2Pp4/8/1K6/8/8 w - d6 0 1", 6, 824064),
+            // Castling giving check
+            ("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6, 661072),
+            ("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6, 803711),
+            // Castling rights lost by captures, castling prevented by attacks
+            ("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4, 1274206),
+            ("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4, 1720476),
+            // Promoting out of check, discovered check
+            ("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6, 3821001),
+            ("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5, 1004658),
+            // Promotions giving check
+            ("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6, 217342),
+            ("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6, 92683),
+            // Stalemate and checkmate
+            ("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7, 567584),
+        ];
+
+        for (fen, depth, expected_nodes) in positions {
+            run_perft_test(fen, depth, expected_nodes);
+        }
+    }
 }
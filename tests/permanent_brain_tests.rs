@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod permanent_brain_tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use enrust::game_state::{GameState, SearchConfiguration};
+
+    fn game_with_permanent_brain() -> GameState {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        game.set_permanent_brain(true);
+        game
+    }
+
+    fn saved_entries(game: &GameState) -> usize {
+        let path = std::env::temp_dir().join(format!(
+            "enrust_permanent_brain_{}_{:?}.hash",
+            std::process::id(),
+            thread::current().id()
+        ));
+        let entries = game.save_hash_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        entries
+    }
+
+    #[test]
+    fn test_permanent_brain_is_off_by_default() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        assert!(game.permanent_brain().is_none());
+
+        game.set_permanent_brain(true);
+        assert!(game.permanent_brain().is_some());
+    }
+
+    #[test]
+    fn test_pondering_replaces_the_permanent_brain() {
+        let mut game = game_with_permanent_brain();
+        game.set_ponder(true);
+        assert!(game.permanent_brain().is_none());
+
+        // Nor does an analysis think further once it is over
+        let mut game = game_with_permanent_brain();
+        let mut sc = SearchConfiguration::new();
+        sc.infinite = true;
+        game.set_time_control(&sc);
+        assert!(game.permanent_brain().is_none());
+    }
+
+    #[test]
+    fn test_permanent_brain_fills_the_transposition_table() {
+        let mut game = game_with_permanent_brain();
+        game.set_max_depth(3);
+        assert_eq!(saved_entries(&game), 0);
+
+        let brain = game.permanent_brain().unwrap();
+        brain.think("e2e4", Some("e7e5"));
+
+        assert!(saved_entries(&game) > 0);
+    }
+
+    #[test]
+    fn test_permanent_brain_stops_with_the_next_command() {
+        let mut game = game_with_permanent_brain();
+        game.set_max_depth(64);
+
+        let brain = game.permanent_brain().unwrap();
+        let thinking = thread::spawn(move || brain.think("e2e4", None));
+        thread::sleep(Duration::from_millis(50));
+        assert!(!thinking.is_finished());
+
+        game.stop_permanent_brain();
+        thinking.join().unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod personality_tests {
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::Score;
+    use enrust::game_state::board::evaluation::params::EvalParams;
+    use enrust::game_state::board::search::params::SearchParams;
+    use enrust::game_state::personality::Personality;
+
+    #[test]
+    fn test_personality_names() {
+        for personality in Personality::ALL {
+            assert_eq!(
+                Personality::from_name(personality.name()),
+                Some(personality)
+            );
+        }
+        assert_eq!(
+            Personality::from_name("aggressive"),
+            Some(Personality::Aggressive)
+        );
+        assert_eq!(Personality::from_name("Reckless"), None);
+    }
+
+    #[test]
+    fn test_default_personality_keeps_the_defaults() {
+        assert_eq!(Personality::Default.eval_params(), EvalParams::default());
+        assert_eq!(
+            Personality::Default.search_params(),
+            SearchParams::default()
+        );
+
+        // Every other personality changes something
+        for personality in &Personality::ALL[1..] {
+            assert_ne!(personality.eval_params(), EvalParams::default());
+            assert_ne!(personality.search_params(), SearchParams::default());
+        }
+    }
+
+    #[test]
+    fn test_set_personality() {
+        let mut game = GameState::new(None);
+        game.set_fen_position("rnbqkbnr/pppp1ppp/8/4p3/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2");
+        let default_eval = game.evaluate_cp();
+
+        game.set_personality(Personality::Gambit);
+        assert_eq!(game.personality(), Personality::Gambit);
+        assert_eq!(game.eval_params(), &Personality::Gambit.eval_params());
+        assert_eq!(game.search_params().contempt, Score::cp(40));
+        assert_ne!(game.evaluate_cp(), default_eval);
+
+        game.set_personality(Personality::Default);
+        assert_eq!(game.evaluate_cp(), default_eval);
+        assert_eq!(game.search_params(), &SearchParams::default());
+    }
+
+    #[test]
+    fn test_contempt_scores_draws_against_the_search_side() {
+        let mut game = GameState::new(None);
+        assert_eq!(game.get_chess_board().draw_score(Color::White), Score::DRAW);
+
+        game.set_personality(Personality::Aggressive);
+        let board = game.get_chess_board();
+        // No search ran yet, so the board plays for White
+        assert_eq!(board.draw_score(Color::White), Score::cp(-20));
+        assert_eq!(board.draw_score(Color::Black), Score::cp(20));
+    }
+}
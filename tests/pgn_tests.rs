@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod pgn_tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use enrust::game_state::pgn::{GameResult, PgnWriter, format_game, parse_pgn, pgn_date};
+
+    fn tag(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_format_game_orders_the_seven_tag_roster() {
+        let tags = [
+            tag("Termination", "normal"),
+            tag("White", "EnRust"),
+            tag("Result", "0-1"),
+            tag("Event", "Test \"quoted\""),
+        ];
+        let pgn = format_game(&tags, "1. e4 e5", GameResult::WhiteWins);
+
+        let lines: Vec<&str> = pgn.lines().collect();
+        assert_eq!(
+            lines[..9],
+            [
+                "[Event \"Test \\\"quoted\\\"\"]",
+                "[Site \"?\"]",
+                "[Date \"????.??.??\"]",
+                "[Round \"?\"]",
+                "[White \"EnRust\"]",
+                "[Black \"?\"]",
+                "[Result \"1-0\"]",
+                "[Termination \"normal\"]",
+                "",
+            ]
+        );
+        assert_eq!(lines[9], "1. e4 e5 1-0");
+        assert!(pgn.ends_with("1-0\n\n"));
+    }
+
+    #[test]
+    fn test_format_game_wraps_movetext() {
+        let movetext = "1. Nf3 {[%clk 0:00:09.9]} Nf6 {[%clk 0:00:09.9]} ".repeat(10);
+        let pgn = format_game(&[], &movetext, GameResult::Draw);
+
+        let movetext_lines: Vec<&str> = pgn.lines().skip(8).filter(|l| !l.is_empty()).collect();
+        assert!(movetext_lines.len() > 1);
+        assert!(movetext_lines.iter().all(|line| line.len() <= 80));
+        assert!(movetext_lines.last().unwrap().ends_with("1/2-1/2"));
+        // Comments aren't split
+        assert!(
+            movetext_lines
+                .iter()
+                .all(|line| line.matches('{').count() == line.matches('}').count())
+        );
+    }
+
+    #[test]
+    fn test_pgn_date() {
+        assert_eq!(pgn_date(UNIX_EPOCH), "1970.01.01");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(11_016 * 86_400);
+        assert_eq!(pgn_date(leap_day), "2000.02.29");
+        let later = UNIX_EPOCH + Duration::from_secs(20_161 * 86_400 + 3600);
+        assert_eq!(pgn_date(later), "2025.03.14");
+    }
+
+    #[test]
+    fn test_writer_appends_games() {
+        let path =
+            std::env::temp_dir().join(format!("enrust_pgn_writer_{}.pgn", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        PgnWriter::open(&path)
+            .unwrap()
+            .write_game(
+                &[tag("Round", "1")],
+                "1. f3 e5 2. g4 Qh4#",
+                GameResult::BlackWins,
+            )
+            .unwrap();
+        // A new writer keeps the games already in the file
+        let writer = PgnWriter::open(&path).unwrap();
+        writer
+            .write_game(&[tag("Round", "2")], "1. e4 e5", GameResult::Draw)
+            .unwrap();
+
+        let games = parse_pgn(&std::fs::read_to_string(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("Round"), Some("1"));
+        assert_eq!(games[0].result, GameResult::BlackWins);
+        assert_eq!(games[0].moves, ["f3", "e5", "g4", "Qh4#"]);
+        assert_eq!(games[1].tag("Round"), Some("2"));
+        assert_eq!(games[1].result, GameResult::Draw);
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod piece_tests {
+    use enrust::game_state::{Color, Piece, PieceType};
+
+    #[test]
+    fn test_piece_char_round_trip() {
+        for piece in Piece::ALL {
+            assert_eq!(Piece::from_char(piece.to_char()), Some(piece));
+            assert_eq!(piece.print_piece(), piece.to_char().to_string());
+        }
+
+        assert_eq!(Piece::from_char('x'), None);
+        assert_eq!(Piece::from_char('1'), None);
+        assert_eq!(Piece::EmptySquare.to_char(), '.');
+        assert_eq!(Piece::SentinelSquare.to_char(), 'X');
+    }
+
+    #[test]
+    fn test_make_matches_type_and_color() {
+        for color in [Color::White, Color::Black] {
+            for piece_type in PieceType::ALL {
+                let piece = Piece::make(piece_type, color);
+
+                assert_eq!(piece.get_type(), piece_type);
+                assert_eq!(piece.get_color(), color);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pieces_of_color() {
+        let black: Vec<Piece> = Piece::of_color(Color::Black).collect();
+
+        assert_eq!(black.len(), 6);
+        assert!(black.iter().all(|piece| piece.get_color() == Color::Black));
+        assert_eq!(black[0], Piece::BlackPawn);
+        assert_eq!(black[5], Piece::BlackKing);
+    }
+
+    #[test]
+    fn test_piece_values() {
+        assert_eq!(Piece::WhitePawn.value(), 100);
+        assert_eq!(Piece::BlackQueen.value(), PieceType::Queen.value());
+        assert!(PieceType::Rook.value() > PieceType::Bishop.value());
+        assert_eq!(PieceType::from_char('R'), Some(PieceType::Rook));
+        assert_eq!(PieceType::Knight.to_char(), 'n');
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod puzzle_tests {
+    use enrust::game_state::analysis::AnalysisConfig;
+    use enrust::game_state::pgn::parse_pgn;
+    use enrust::game_state::puzzles::{PuzzleConfig, find_puzzles};
+
+    fn config() -> PuzzleConfig {
+        PuzzleConfig {
+            analysis: AnalysisConfig {
+                depth: 3,
+                ..AnalysisConfig::default()
+            },
+            ..PuzzleConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_mate_after_blunder_is_a_puzzle() {
+        let games = parse_pgn(
+            r#"[Event "Blackburne Shilling Gambit"]
+[Result "0-1"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bc4 Nd4 4. Nxe5 Qg5 5. Nxf7 Qxg2 6. Rf1 Qxe4+ 7. Be2 Nf3# 0-1
+"#,
+        );
+
+        let puzzles = find_puzzles(&games, &config());
+        let mate = puzzles
+            .iter()
+            .find(|puzzle| {
+                puzzle.fen == "r1b1kbnr/pppp1Npp/8/8/3nq3/8/PPPPBP1P/RNBQKR2 b Qkq - 1 7"
+            })
+            .expect("the mate in one is found");
+
+        assert_eq!(mate.solution, vec!["d4f3".to_string()]);
+        assert!(mate.score.is_mate());
+    }
+
+    #[test]
+    fn test_quiet_game_has_no_puzzles() {
+        let games = parse_pgn(
+            r#"[Event "Quiet"]
+[Result "*"]
+
+1. d4 d5 2. c4 e6 3. Nc3 Nf6 4. Nf3 Be7 *
+"#,
+        );
+
+        assert!(find_puzzles(&games, &config()).is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod quiescence_tests {
+    use enrust::game_state::ChessBoard;
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::Score;
+    use enrust::game_state::board::search::params::SearchParams;
+    use enrust::game_state::board::search::quiescence::{quiescence, quiescence_with_checks};
+
+    fn setup_test_game(fen: &str) -> ChessBoard {
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        game.get_chess_board().clone()
+    }
+
+    #[test]
+    fn test_quiescence_does_not_stand_pat_in_check() {
+        // White is a queen up but is back-rank mated
+        let mut board = setup_test_game("7k/8/8/1Q6/8/8/6PP/r6K w - - 0 1");
+        let stand_pat = board.evaluate();
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        assert!(
+            stand_pat > Score::ZERO,
+            "White should look better statically"
+        );
+        assert!(
+            score.is_mate() && score < Score::ZERO,
+            "Mated side should not stand pat, score: {}",
+            score
+        );
+    }
+
+    #[test]
+    fn test_quiescence_searches_evasions() {
+        // Stand pat would count the checking queen, but the only evasion captures it
+        let mut board = setup_test_game("7k/8/8/8/8/8/q7/K7 w - - 0 1");
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        // Kxa2 is the only legal move and wins the queen
+        assert!(
+            score > Score::cp(-100),
+            "King should capture the queen, score: {}",
+            score
+        );
+    }
+
+    #[test]
+    fn test_quiescence_with_checks_finds_quiet_mate() {
+        // Ra8# is a quiet move, so plain quiescence only sees the stand pat
+        let mut board = setup_test_game("7k/8/6K1/8/8/8/8/R7 w - - 0 1");
+
+        let plain = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+        let with_checks =
+            quiescence_with_checks(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        assert_eq!(plain, board.evaluate());
+        assert!(
+            with_checks.is_mate() && with_checks > Score::ZERO,
+            "Quiet check should find the mate, score: {}",
+            with_checks
+        );
+    }
+
+    #[test]
+    fn test_quiescence_searches_queen_promotions() {
+        // a8=Q is quiet, but the king is too far to take the new queen
+        let mut board = setup_test_game("8/P6k/8/8/8/8/8/K7 w - - 0 1");
+        let stand_pat = board.evaluate_for(Color::White);
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        assert!(
+            score > stand_pat + Score::cp(500),
+            "The promotion should be searched, score: {}",
+            score
+        );
+    }
+
+    #[test]
+    fn test_quiescence_skips_promotions_losing_the_queen() {
+        // The rook takes a new queen on a8
+        let mut board = setup_test_game("r7/P6k/8/8/8/8/8/K7 w - - 0 1");
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        assert_eq!(score, board.evaluate_for(Color::White));
+        assert_eq!(board.qnodes(), 1);
+    }
+
+    #[test]
+    fn test_quiescence_quiet_position_is_stand_pat() {
+        let mut board = setup_test_game("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let stand_pat = board.evaluate_for(Color::White);
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        assert_eq!(score, stand_pat);
+    }
+
+    #[test]
+    fn test_quiescence_stands_pat_for_black() {
+        let mut board =
+            setup_test_game("rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2");
+        let stand_pat = board.evaluate_for(Color::Black);
+        assert!(stand_pat < Score::ZERO);
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::Black);
+
+        assert_eq!(score, stand_pat);
+    }
+
+    #[test]
+    fn test_quiescence_scores_are_color_symmetric() {
+        // The same captures, once for White and once for Black
+        let cases = [
+            (
+                "4k3/8/4p3/3p4/4P3/8/8/3QK3 w - - 0 1",
+                "3qk3/8/8/4p3/3P4/4P3/8/4K3 b - - 0 1",
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                "r3k2r/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b KQkq - 0 1",
+            ),
+        ];
+
+        for (white_fen, black_fen) in cases {
+            let mut white = setup_test_game(white_fen);
+            let mut black = setup_test_game(black_fen);
+
+            assert_eq!(
+                quiescence(&mut white, -Score::INFINITE, Score::INFINITE, Color::White),
+                quiescence(&mut black, -Score::INFINITE, Score::INFINITE, Color::Black),
+                "{}",
+                white_fen
+            );
+        }
+    }
+
+    #[test]
+    fn test_quiescence_scores_stalemate_as_draw() {
+        // Black has no legal moves but isn't in check
+        let mut board = setup_test_game("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert!(
+            board.evaluate() > Score::cp(500),
+            "White should look winning statically"
+        );
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::Black);
+
+        assert_eq!(score, Score::DRAW, "Stalemate should be a draw");
+    }
+
+    #[test]
+    fn test_quiescence_scores_checkmate() {
+        // Black is mated, the static evaluation only sees equal material
+        let mut board =
+            setup_test_game("r1bqkb1r/pppp1Qpp/2n2n2/4p3/2B1P3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 4");
+
+        let score = quiescence(&mut board, Score::cp(-1000), Score::cp(1000), Color::Black);
+        assert!(
+            score.is_mate() && score < Score::ZERO,
+            "Mated side should lose, score: {}",
+            score
+        );
+
+        // Found from the capture that gives mate as well
+        let mut board =
+            setup_test_game("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4");
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+        assert!(
+            score.is_mate() && score > Score::ZERO,
+            "Qxf7 should mate, score: {}",
+            score
+        );
+    }
+
+    /// Searches a position with quiescence, losing captures searched too or
+    /// not.
+    ///
+    /// # Returns
+    ///
+    /// The score and the number of quiescence nodes
+    fn quiescence_nodes(fen: &str, prune_losing_captures: bool) -> (Score, u64) {
+        let mut board = setup_test_game(fen);
+        if !prune_losing_captures {
+            let mut params = SearchParams::default();
+            assert!(params.set("QsearchSeeMargin", 1000));
+            board.set_search_params(params);
+        }
+
+        let score = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+        (score, board.qnodes())
+    }
+
+    #[test]
+    fn test_quiescence_skips_captures_losing_material() {
+        // Qxd5 exd5 loses the queen, standing pat is better
+        let fen = "4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1";
+
+        let (pruned_score, pruned_nodes) = quiescence_nodes(fen, true);
+        let (full_score, full_nodes) = quiescence_nodes(fen, false);
+
+        assert_eq!(pruned_nodes, 1, "Qxd5 should not be searched");
+        assert!(full_nodes > pruned_nodes);
+        assert_eq!(pruned_score, full_score);
+    }
+
+    #[test]
+    fn test_see_pruning_reduces_quiescence_nodes() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+        let (_, pruned_nodes) = quiescence_nodes(fen, true);
+        let (_, full_nodes) = quiescence_nodes(fen, false);
+
+        assert!(
+            pruned_nodes * 2 < full_nodes,
+            "SEE pruning should at least halve the quiescence nodes: {} vs {}",
+            pruned_nodes,
+            full_nodes
+        );
+    }
+
+    #[test]
+    fn test_quiescence_reuses_transposition_table_entries() {
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        );
+        let mut board = game.get_chess_board().clone();
+
+        let first = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+        let qnodes = board.qnodes();
+        let hits = board.tt_hits();
+        let second = quiescence(&mut board, -Score::INFINITE, Score::INFINITE, Color::White);
+
+        assert_eq!(first, second);
+        assert_eq!(
+            board.qnodes(),
+            qnodes + 1,
+            "The stored score should be reused"
+        );
+        assert_eq!(board.tt_hits(), hits + 1);
+    }
+}
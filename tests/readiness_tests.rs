@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod readiness_tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use enrust::game_state::readiness::ReadinessGate;
+
+    #[test]
+    fn test_ready_without_operations() {
+        let gate = ReadinessGate::new();
+        assert!(gate.is_ready());
+        assert_eq!(gate.wait(), 0);
+    }
+
+    #[test]
+    fn test_wait_for_background_operations() {
+        let gate = ReadinessGate::new();
+        let done = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel::<()>();
+
+        let task_done = Arc::clone(&done);
+        gate.spawn(move || {
+            // Held until the test lets the operation finish
+            receiver.recv().ok();
+            thread::sleep(Duration::from_millis(20));
+            task_done.store(true, Ordering::Release);
+        });
+        assert!(!gate.is_ready());
+
+        sender.send(()).unwrap();
+        assert_eq!(gate.wait(), 0);
+        assert!(done.load(Ordering::Acquire));
+        assert!(gate.is_ready());
+    }
+
+    #[test]
+    fn test_wait_counts_failed_operations() {
+        let gate = ReadinessGate::new();
+        gate.spawn(|| panic!("initialization failed"));
+        gate.spawn(|| {});
+
+        assert_eq!(gate.wait(), 1);
+        assert!(gate.is_ready());
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod replay_tests {
+    use std::path::Path;
+
+    use enrust::game_state::replay::{Session, comparable_lines, compare_answers, replay_session};
+
+    fn engine() -> &'static Path {
+        Path::new(env!("CARGO_BIN_EXE_enrust"))
+    }
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_session_log() {
+        let log = "\
+# Session from a GUI
+> uci
+< id name EnRust
+< uciok
+> position startpos moves e2e4
+>go nodes 2000
+< bestmove g8f6
+";
+        let session = Session::parse(log);
+
+        assert_eq!(
+            session.commands,
+            lines(&["uci", "position startpos moves e2e4", "go nodes 2000"])
+        );
+        assert_eq!(
+            session.answers,
+            lines(&["id name EnRust", "uciok", "bestmove g8f6"])
+        );
+    }
+
+    #[test]
+    fn test_comparable_lines_ignore_timings_and_other_answers() {
+        let answers = lines(&[
+            "readyok",
+            "info depth 3 score cp 33 nodes 4096 nps 73403 time 55 pv g8f6",
+            "bestmove g8f6 ponder d2d4",
+        ]);
+
+        assert_eq!(
+            comparable_lines(&answers),
+            lines(&[
+                "info depth 3 score cp 33 nodes 4096 pv g8f6",
+                "bestmove g8f6 ponder d2d4"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_compare_answers_reports_differing_and_missing_lines() {
+        let expected = lines(&["info depth 1 score cp 4 nps 10 pv g8f6", "bestmove g8f6"]);
+        let actual = lines(&[
+            "info depth 1 score cp 4 nps 20 pv g8f6",
+            "bestmove e7e5",
+            "info string extra",
+        ]);
+
+        let report = compare_answers(&expected, &actual);
+
+        assert_eq!(report.compared, 3);
+        assert_eq!(report.mismatches.len(), 2);
+        assert_eq!(report.mismatches[0].index, 1);
+        assert_eq!(
+            report.mismatches[0].expected.as_deref(),
+            Some("bestmove g8f6")
+        );
+        assert_eq!(
+            report.mismatches[0].actual.as_deref(),
+            Some("bestmove e7e5")
+        );
+        assert_eq!(report.mismatches[1].expected, None);
+        assert_eq!(
+            report.mismatches[1].actual.as_deref(),
+            Some("info string extra")
+        );
+    }
+
+    #[test]
+    fn test_replay_matches_recorded_session() {
+        let commands = lines(&[
+            "uci",
+            "isready",
+            "position startpos moves e2e4",
+            "go nodes 2000",
+            "position startpos moves e2e4 g8f6 e4e5",
+            "go nodes 2000",
+        ]);
+
+        // Record the session by replaying it without answers
+        let empty = Session {
+            commands: commands.clone(),
+            answers: Vec::new(),
+        };
+        let recording = replay_session(engine(), &empty).expect("engine runs");
+        let answers: Vec<String> = recording
+            .mismatches
+            .into_iter()
+            .filter_map(|mismatch| mismatch.actual)
+            .collect();
+        assert_eq!(
+            answers
+                .iter()
+                .filter(|line| line.starts_with("bestmove"))
+                .count(),
+            2
+        );
+
+        let session = Session { commands, answers };
+        let report = replay_session(engine(), &session).expect("engine runs");
+
+        assert!(report.is_match(), "{:?}", report.mismatches);
+        assert_eq!(report.compared, session.answers.len());
+    }
+
+    #[test]
+    fn test_replay_reports_changed_bestmove() {
+        let log = "\
+> position startpos moves e2e4
+> go nodes 2000
+< bestmove a7a6
+";
+        let report = replay_session(engine(), &Session::parse(log)).expect("engine runs");
+
+        assert!(!report.is_match());
+        assert!(report.mismatches.iter().any(|mismatch| {
+            mismatch
+                .actual
+                .as_deref()
+                .is_some_and(|line| line.starts_with("bestmove"))
+        }));
+    }
+}
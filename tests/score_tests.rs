@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod score_tests {
+    use enrust::game_state::Color;
+    use enrust::game_state::Score;
+
+    #[test]
+    fn test_centipawn_scores_are_not_mate() {
+        assert!(!Score::cp(950).is_mate());
+        assert!(!Score::MAX_EVAL.is_mate());
+        assert!(!(-Score::MAX_EVAL).is_mate());
+        assert_eq!(Score::cp(950).mate_moves(), None);
+    }
+
+    #[test]
+    fn test_mate_scores() {
+        assert!(Score::MATE.is_mate());
+        assert!(Score::mate_in(3).is_mate());
+        assert!(Score::mated_in(4).is_mate());
+        assert!(Score::mate_in(1) > Score::mate_in(3));
+        assert!(Score::mated_in(2) < Score::mated_in(4));
+        assert_eq!(-Score::mate_in(5), Score::mated_in(5));
+
+        // Mate in 3 plies is mate in 2 moves, mated in 4 plies is 2 moves
+        assert_eq!(Score::mate_in(3).mate_moves(), Some(2));
+        assert_eq!(Score::mated_in(4).mate_moves(), Some(-2));
+    }
+
+    #[test]
+    fn test_uci_strings() {
+        assert_eq!(Score::cp(35).to_uci_string(), "cp 35");
+        assert_eq!(Score::cp(-120).to_uci_string(), "cp -120");
+        assert_eq!(Score::mate_in(1).to_uci_string(), "mate 1");
+        assert_eq!(Score::mated_in(2).to_uci_string(), "mate -1");
+    }
+
+    #[test]
+    fn test_relative_to_side() {
+        let score = Score::cp(80);
+        assert_eq!(score.relative_to(Color::White), score);
+        assert_eq!(score.relative_to(Color::Black), Score::cp(-80));
+        assert_eq!(
+            score.relative_to(Color::Black).relative_to(Color::Black),
+            score
+        );
+    }
+
+    #[test]
+    fn test_search_scores_fit_in_transposition_table() {
+        for score in [
+            -Score::INFINITE,
+            Score::mated_in(0),
+            Score::cp(-1234),
+            Score::mate_in(7),
+            Score::INFINITE,
+        ] {
+            assert_eq!(Score::from(score.to_i16()), score);
+        }
+    }
+
+    #[test]
+    fn test_mates_are_stored_from_the_position() {
+        // Mate found 5 plies from the root, in 2 plies from the position
+        assert_eq!(Score::mate_in(5).to_tt(3), Score::mate_in(2));
+        assert_eq!(Score::mated_in(5).to_tt(3), Score::mated_in(2));
+        assert_eq!(Score::mate_in(2).from_tt(3), Score::mate_in(5));
+        assert_eq!(Score::mated_in(2).from_tt(3), Score::mated_in(5));
+
+        // Other scores and the window bounds are left alone
+        for score in [Score::cp(-1234), Score::INFINITE, -Score::INFINITE] {
+            assert_eq!(score.to_tt(3), score);
+            assert_eq!(score.from_tt(3), score);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+#[cfg(test)]
+mod search_handle_tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use enrust::game_state::board::search::clock::NODES_PER_TIME_CHECK;
+    use enrust::game_state::search_handle::SearchResult;
+    use enrust::game_state::{GameState, Score, SearchConfiguration};
+
+    fn nodes_limited(nodes: u64) -> SearchConfiguration {
+        let mut sc = SearchConfiguration::new();
+        sc.nodes = Some(nodes);
+        sc
+    }
+
+    #[test]
+    fn test_join_returns_the_search_result() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        game.set_time_control(&nodes_limited(5_000));
+
+        let result = game.search().join();
+
+        let best_move = result.best_move.expect("the start position has moves");
+        assert!(game.generate_moves().contains(&best_move));
+        assert_eq!(result.pv.first(), Some(&best_move));
+        assert_eq!(result.ponder_move, result.pv.get(1).cloned());
+        assert!(result.depth >= 1);
+        assert!(result.nodes > 0);
+    }
+
+    #[test]
+    fn test_result_without_legal_moves() {
+        let mut game = GameState::new(Some(1));
+        // Fool's mate, White is mated
+        game.set_fen_position("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+
+        let handle = game.search();
+        let result = handle.join();
+
+        assert_eq!(result.best_move, None);
+        assert!(result.pv.is_empty());
+        assert!(result.score < -Score::cp(10_000));
+    }
+
+    #[test]
+    fn test_abort_stops_an_infinite_search() {
+        let mut game = GameState::new(Some(1));
+        game.start_position();
+        let mut sc = SearchConfiguration::new();
+        sc.infinite = true;
+        game.set_time_control(&sc);
+
+        let handle = game.search();
+        thread::sleep(Duration::from_millis(50));
+        handle.abort();
+
+        let result = handle.join();
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn test_is_finished_after_join_is_possible() {
+        let mut game = GameState::new(Some(1));
+        // A single legal move is played without searching
+        game.set_fen_position("k7/8/8/8/8/8/1q6/K7 w - - 0 1");
+
+        let handle = game.search();
+        while !handle.is_finished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(handle.join().best_move, Some("a1b2".to_string()));
+    }
+
+    /// Game where black just took the knight on c3 with the bishop, which
+    /// only the b2 pawn can take back.
+    fn game_after_capture(fen: &str) -> GameState {
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position(fen);
+        assert!(game.make_move("e5c3"));
+        game
+    }
+
+    fn timed(time_left: u64) -> SearchConfiguration {
+        let mut sc = SearchConfiguration::new();
+        sc.wtime = Some(time_left);
+        sc.btime = Some(time_left);
+        sc
+    }
+
+    #[test]
+    fn test_recapture_is_played_without_searching() {
+        let mut game = game_after_capture("4k3/8/8/4b3/8/2N5/1P6/6K1 b - - 0 1");
+        game.set_time_control(&timed(60_000));
+
+        let result = game.search().join();
+
+        assert_eq!(result.best_move, Some("b2c3".to_string()));
+        assert_eq!(result.depth, 1);
+        assert_eq!(result.nodes, 0);
+    }
+
+    #[test]
+    fn test_recapture_is_searched_when_not_forced() {
+        // Without a time limit there is no time to save
+        let mut game = game_after_capture("4k3/8/8/4b3/8/2N5/1P6/6K1 b - - 0 1");
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(3);
+        game.set_time_control(&sc);
+        assert_eq!(game.search().join().depth, 3);
+
+        // Taking the undefended rook wins material as well
+        let mut game = game_after_capture("4k3/8/8/4b3/8/r1N5/1P6/6K1 b - - 0 1");
+        game.set_time_control(&timed(60_000));
+        assert!(game.search().join().depth > 1);
+
+        // The option turns instant moves off
+        let mut game = game_after_capture("4k3/8/8/4b3/8/2N5/1P6/6K1 b - - 0 1");
+        game.set_time_control(&timed(60_000));
+        game.set_instant_moves(false);
+        assert!(!game.instant_moves());
+        assert!(game.search().join().depth > 1);
+    }
+
+    #[test]
+    fn test_forced_move_is_searched_when_pondering_or_analyzing() {
+        let mut ponder = timed(60_000);
+        ponder.ponder = true;
+        let mut infinite = SearchConfiguration::new();
+        infinite.infinite = true;
+
+        // Kxg2 is the only legal move, but the search waits for the GUI
+        for sc in [ponder, infinite] {
+            let mut game = GameState::new(Some(16));
+            game.set_fen_position("7k/8/8/8/8/8/6q1/7K w - - 0 1");
+            game.set_max_depth(3);
+            game.set_time_control(&sc);
+
+            let result = game.search().join();
+            assert_eq!(result.best_move, Some("h1g2".to_string()));
+            assert_eq!(result.depth, 3);
+        }
+    }
+
+    /// Depth 3 search of a position where Qxa4 wins the hanging queen.
+    fn hanging_queen(searchmoves: Option<&[&str]>, avoidmoves: &[&str]) -> SearchResult {
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position("4k3/8/8/8/q7/8/8/3QK3 w - - 0 1");
+
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(3);
+        sc.searchmoves = searchmoves.map(|moves| {
+            moves
+                .iter()
+                .map(|mv| game.create_move(mv).unwrap())
+                .collect()
+        });
+        sc.avoidmoves = avoidmoves
+            .iter()
+            .map(|mv| game.create_move(mv).unwrap())
+            .collect();
+        game.set_time_control(&sc);
+
+        game.search().join()
+    }
+
+    #[test]
+    fn test_avoided_moves_are_not_played() {
+        assert_eq!(hanging_queen(None, &[]).best_move.as_deref(), Some("d1a4"));
+
+        let result = hanging_queen(None, &["d1a4"]);
+        assert!(result.best_move.is_some_and(|mv| mv != "d1a4"));
+        // The queen is still attacked, so the best other move is worse
+        assert!(result.score < hanging_queen(None, &[]).score);
+    }
+
+    #[test]
+    fn test_search_is_restricted_to_searchmoves() {
+        let result = hanging_queen(Some(&["e1e2", "e1f2"]), &["e1f2"]);
+        assert_eq!(result.best_move.as_deref(), Some("e1e2"));
+
+        // Leaving out every allowed move searches them all
+        let result = hanging_queen(Some(&["d1a4"]), &["d1a4"]);
+        assert_eq!(result.best_move.as_deref(), Some("d1a4"));
+    }
+
+    /// Analyzes the start position until the depth cap stops the search.
+    fn analyzed_start_position() -> (GameState, SearchResult) {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        game.set_max_depth(4);
+        let mut sc = SearchConfiguration::new();
+        sc.infinite = true;
+        game.set_time_control(&sc);
+
+        let result = game.search().join();
+        assert_eq!(result.depth, 4);
+        (game, result)
+    }
+
+    #[test]
+    fn test_analysis_resumes_along_the_principal_variation() {
+        let (mut game, result) = analyzed_start_position();
+        assert_eq!(game.resume_depth(), None, "The position didn't change");
+
+        game.make_move(&result.pv[0]);
+        game.set_resume_analysis(false);
+        assert_eq!(game.resume_depth(), Some(1));
+        game.set_resume_analysis(true);
+        assert_eq!(game.resume_depth(), Some(3));
+
+        let resumed = game.search().join();
+        assert_eq!(resumed.depth, 4);
+        // The resumed analysis can be resumed in turn
+        game.make_move(&resumed.pv[0]);
+        assert_eq!(game.resume_depth(), Some(3));
+    }
+
+    #[test]
+    fn test_analysis_restarts_off_the_principal_variation() {
+        let (mut game, result) = analyzed_start_position();
+        let other = game
+            .generate_moves()
+            .into_iter()
+            .find(|mv| *mv != result.pv[0])
+            .unwrap();
+        game.make_move(&other);
+        assert_eq!(game.resume_depth(), None);
+
+        // Only analyses are resumed
+        let (mut game, result) = analyzed_start_position();
+        game.make_move(&result.pv[0]);
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(4);
+        game.set_time_control(&sc);
+        assert_eq!(game.resume_depth(), None);
+    }
+
+    #[test]
+    fn test_nodes_per_move_caps_every_search() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        game.set_nodes_per_move(Some(2 * NODES_PER_TIME_CHECK));
+        assert_eq!(game.nodes_per_move(), Some(2 * NODES_PER_TIME_CHECK));
+        let capped = game.search().join();
+
+        // The count is checked in batches, so the search may go a batch over
+        assert!(capped.nodes <= 3 * NODES_PER_TIME_CHECK);
+        // Depth 5 takes tens of thousands of nodes
+        assert!(capped.depth < 5);
+        assert!(capped.best_move.is_some());
+    }
+
+    #[test]
+    fn test_lower_go_nodes_limit_wins_over_nodes_per_move() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        game.set_nodes_per_move(Some(1_000_000));
+        game.set_time_control(&nodes_limited(2 * NODES_PER_TIME_CHECK));
+
+        let result = game.search().join();
+        assert!(result.nodes <= 3 * NODES_PER_TIME_CHECK);
+    }
+
+    #[test]
+    fn test_go_depth_limits_the_search() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(2);
+        game.set_time_control(&sc);
+
+        assert_eq!(game.search().join().depth, 2);
+    }
+
+    #[test]
+    fn test_max_depth_caps_go_depth() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        game.set_max_depth(3);
+        assert_eq!(game.max_depth(), 3);
+
+        // Without `go depth` the search goes to the cap
+        assert_eq!(game.search().join().depth, 3);
+
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(10);
+        game.set_time_control(&sc);
+        assert_eq!(game.search().join().depth, 3);
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod search_trace_tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::Score;
+    use enrust::game_state::board::search::trace::{TraceEvent, TraceNode};
+    use enrust::game_state::board::search::{
+        IterativeDeepening, MinimaxAlphaBeta, Search, SearchTrace,
+    };
+
+    fn traced_search(fen: &str, depth: u8, trace_depth: usize) -> SearchTrace {
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position(fen);
+        let mut board = game.get_chess_board().clone();
+        board.set_trace(Some(SearchTrace::new(trace_depth)));
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        IterativeDeepening::new(MinimaxAlphaBeta, depth).search(
+            &mut board,
+            game.side_to_move(),
+            stop_flag,
+        );
+
+        board.take_trace().expect("trace is kept on the board")
+    }
+
+    fn max_depth(nodes: &[TraceNode]) -> usize {
+        nodes
+            .iter()
+            .map(|node| 1 + max_depth(&node.children))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn has_event(nodes: &[TraceNode], event: TraceEvent) -> bool {
+        nodes
+            .iter()
+            .any(|node| node.events.contains(&event) || has_event(&node.children, event))
+    }
+
+    #[test]
+    fn test_moves_beyond_the_trace_depth_are_not_recorded() {
+        let mut trace = SearchTrace::new(1);
+
+        trace.enter("e2e4".to_string(), -Score::INFINITE, Score::INFINITE);
+        trace.enter("e7e5".to_string(), -Score::INFINITE, Score::INFINITE);
+        trace.event(TraceEvent::Cutoff);
+        trace.exit(Score::cp(-20));
+        trace.event(TraceEvent::TranspositionHit);
+        trace.exit(Score::cp(20));
+
+        assert_eq!(trace.roots().len(), 1);
+        let root = &trace.roots()[0];
+        assert_eq!(root.mv, "e2e4");
+        assert_eq!(root.score, Some(Score::cp(20)));
+        assert_eq!(root.events, vec![TraceEvent::TranspositionHit]);
+        assert!(root.children.is_empty());
+    }
+
+    #[test]
+    fn test_search_records_last_iteration() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+        let trace = traced_search(fen, 3, 3);
+
+        let mut game = GameState::new(None);
+        game.set_fen_position(fen);
+        let legal_moves = game.get_chess_board().clone().generate_moves(Color::White);
+
+        // Every root move of the last iteration, three plies deep
+        assert_eq!(trace.roots().len(), legal_moves.len());
+        assert_eq!(max_depth(trace.roots()), 3);
+        assert!(trace.roots().iter().all(|node| node.score.is_some()));
+        assert!(has_event(trace.roots(), TraceEvent::Cutoff));
+    }
+
+    #[test]
+    fn test_null_move_cutoffs_are_recorded() {
+        let trace = traced_search("r3k3/ppp2n2/8/8/8/8/PPP2N2/R3K3 w - - 0 1", 5, 2);
+
+        assert!(has_event(trace.roots(), TraceEvent::NullMoveCutoff));
+    }
+
+    #[test]
+    fn test_trace_dumps() {
+        let trace = traced_search("7k/8/6K1/8/8/8/8/R7 w - - 0 1", 2, 2);
+
+        let text = trace.to_text();
+        assert!(
+            text.lines()
+                .any(|line| line.starts_with("a1a8 [-inf, inf] mate"))
+        );
+        assert!(text.lines().any(|line| line.starts_with("  ")));
+
+        let json = trace.to_json();
+        assert!(json.starts_with("[{\"move\":"));
+        assert!(json.ends_with("}]"));
+        assert_eq!(json.matches('[').count(), json.matches(']').count());
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+    }
+}
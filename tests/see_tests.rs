@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod see_tests {
+    use enrust::game_state::GameState;
+
+    fn see(fen: &str, uci_move: &str) -> i16 {
+        let mut game = GameState::new(None);
+        assert!(game.set_fen_position(fen), "Failed to set FEN: {}", fen);
+        let board = game.get_chess_board();
+        let mv = board
+            .from_uci(uci_move)
+            .unwrap_or_else(|| panic!("{} is not a move", uci_move));
+        board.see(&mv)
+    }
+
+    #[test]
+    fn test_undefended_capture_wins_the_piece() {
+        assert_eq!(see("4k3/8/8/3p4/8/8/8/3RK3 w - - 0 1", "d1d5"), 100);
+    }
+
+    #[test]
+    fn test_capture_of_a_defended_piece() {
+        // dxe5 dxe5 trades a pawn for a knight
+        assert_eq!(see("4k3/8/3p4/4n3/3P4/8/8/4K3 w - - 0 1", "d4e5"), 200);
+        // Qxd5 exd5 gives the queen for a pawn
+        assert_eq!(see("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", "d1d5"), -800);
+    }
+
+    #[test]
+    fn test_sliders_behind_the_capture_join_the_exchange() {
+        // Rxe7 Rxe7 Rxe7, the second rook is only seen once the first moved
+        assert_eq!(see("4r1k1/4r3/8/8/8/8/4R3/4R1K1 w - - 0 1", "e2e7"), 500);
+        assert_eq!(see("4r1k1/4r3/8/8/8/8/4R3/6K1 w - - 0 1", "e2e7"), 0);
+    }
+
+    #[test]
+    fn test_king_does_not_take_a_defended_piece() {
+        // The bishop defends the queen on f7, so Kxf7 is illegal
+        assert_eq!(see("4k3/5p2/8/8/2B5/5Q2/8/4K3 w - - 0 1", "f3f7"), 100);
+        assert_eq!(see("4k3/5p2/8/8/8/5Q2/8/4K3 w - - 0 1", "f3f7"), -800);
+    }
+
+    #[test]
+    fn test_en_passant_and_promotion() {
+        assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+        // cxd8=Q Kxd8 wins the rook for the pawn
+        assert_eq!(see("3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1", "c7d8q"), 400);
+        assert_eq!(see("1r2k3/2P5/8/8/8/8/8/4K3 w - - 0 1", "c7c8q"), -100);
+    }
+
+    #[test]
+    fn test_quiet_move_to_an_attacked_square() {
+        assert_eq!(see("4k3/8/8/8/2p5/8/8/2N1K3 w - - 0 1", "c1e2"), 0);
+        assert_eq!(see("4k3/8/8/8/2p5/8/8/2N1K3 w - - 0 1", "c1d3"), -300);
+    }
+}
@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod selfplay_tests {
+    use std::time::Duration;
+
+    use enrust::game_state::pgn::GameResult;
+    use enrust::game_state::selfplay::{Adjudication, Clock, Termination, clk_comment, play_game};
+    use enrust::game_state::{Color, GameState, Score};
+
+    fn seconds(seconds: f64) -> Duration {
+        Duration::from_secs_f64(seconds)
+    }
+
+    #[test]
+    fn test_parse_time_controls() {
+        assert_eq!(
+            Clock::parse("10+0.1"),
+            Some(Clock::new(seconds(10.0), seconds(0.1), None))
+        );
+        assert_eq!(
+            Clock::parse("40/60"),
+            Some(Clock::new(seconds(60.0), Duration::ZERO, Some(40)))
+        );
+        assert_eq!(Clock::parse("0+1"), None);
+        assert_eq!(Clock::parse("x/60"), None);
+        assert_eq!(Clock::parse("60+"), None);
+    }
+
+    #[test]
+    fn test_clock_adds_increment_and_next_control() {
+        let mut clock = Clock::new(seconds(60.0), seconds(1.0), Some(2));
+        assert_eq!(clock.moves_to_go(Color::White), Some(2));
+
+        assert!(clock.punch(Color::White, seconds(10.0)));
+        assert_eq!(clock.remaining(Color::White), seconds(51.0));
+        assert_eq!(clock.remaining(Color::Black), seconds(60.0));
+        assert_eq!(clock.moves_to_go(Color::White), Some(1));
+
+        // The second move completes the control
+        assert!(clock.punch(Color::White, seconds(1.0)));
+        assert_eq!(clock.remaining(Color::White), seconds(111.0));
+        assert_eq!(clock.moves_to_go(Color::White), Some(2));
+    }
+
+    #[test]
+    fn test_flag_fall() {
+        let mut clock = Clock::new(seconds(1.0), seconds(5.0), None);
+
+        assert!(!clock.punch(Color::Black, seconds(1.5)));
+        assert!(clock.has_flagged(Color::Black));
+        assert!(!clock.has_flagged(Color::White));
+    }
+
+    #[test]
+    fn test_search_configuration_follows_the_clock() {
+        let mut clock = Clock::new(seconds(60.0), seconds(0.5), Some(40));
+        clock.punch(Color::White, seconds(2.0));
+        let config = clock.search_configuration(Color::Black);
+
+        assert_eq!(config.wtime, Some(58_500));
+        assert_eq!(config.btime, Some(60_000));
+        assert_eq!(config.winc, Some(500));
+        assert_eq!(config.binc, Some(500));
+        assert_eq!(config.movestogo, Some(40));
+    }
+
+    #[test]
+    fn test_clk_comment() {
+        assert_eq!(clk_comment(seconds(299.55)), "{[%clk 0:04:59.5]}");
+        assert_eq!(clk_comment(seconds(3723.0)), "{[%clk 1:02:03.0]}");
+    }
+
+    #[test]
+    fn test_game_ends_with_mate_and_records_clocks() {
+        // Mate in one for White: Qh5xf7#
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+        let mut white = GameState::new(Some(1));
+        let mut black = GameState::new(Some(1));
+        white.set_fen_position(fen);
+        black.set_fen_position(fen);
+
+        let game = play_game(
+            &mut white,
+            &mut black,
+            Clock::new(seconds(5.0), Duration::ZERO, None),
+            &Adjudication::default(),
+        );
+
+        assert_eq!(game.result, GameResult::WhiteWins);
+        assert_eq!(game.termination, Termination::Normal);
+        assert_eq!(game.moves.len(), 1);
+        assert_eq!(game.moves[0].san, "Qxf7#");
+        assert!(game.moves[0].clock < seconds(5.0));
+
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[Result \"1-0\"]"));
+        assert!(pgn.contains(&format!("[FEN \"{}\"]", fen)));
+        assert!(pgn.contains("4. Qxf7# {[%clk 0:00:0"));
+        assert!(pgn.trim_end().ends_with("1-0"));
+        assert!(pgn.contains("[TimeControl \"5\"]"));
+        assert!(pgn.contains("[Termination \"normal\"]"));
+    }
+
+    #[test]
+    fn test_time_control_tag() {
+        assert_eq!(Clock::parse("10+0.1").unwrap().time_control(), "10+0.1");
+        assert_eq!(Clock::parse("40/60").unwrap().time_control(), "40/60");
+        assert_eq!(Clock::parse("0.5+1").unwrap().time_control(), "0.5+1");
+    }
+
+    fn play_from(
+        fen: &str,
+        adjudication: &Adjudication,
+    ) -> enrust::game_state::selfplay::PlayedGame {
+        let mut white = GameState::new(Some(1));
+        let mut black = GameState::new(Some(1));
+        white.set_fen_position(fen);
+        black.set_fen_position(fen);
+
+        play_game(
+            &mut white,
+            &mut black,
+            Clock::new(seconds(1.0), Duration::ZERO, None),
+            adjudication,
+        )
+    }
+
+    #[test]
+    fn test_tablebase_adjudication() {
+        let adjudication = Adjudication {
+            tablebase: true,
+            ..Adjudication::default()
+        };
+
+        let won = play_from("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", &adjudication);
+        assert_eq!(won.result, GameResult::WhiteWins);
+        assert_eq!(won.termination, Termination::Adjudication);
+        assert!(won.moves.is_empty());
+
+        let drawn = play_from("k7/8/8/PK6/8/8/8/8 w - - 0 1", &adjudication);
+        assert_eq!(drawn.result, GameResult::Draw);
+        assert_eq!(drawn.termination, Termination::Adjudication);
+    }
+
+    #[test]
+    fn test_resign_adjudication() {
+        let adjudication = Adjudication {
+            resign_score: Some(Score::cp(600)),
+            resign_moves: 1,
+            ..Adjudication::default()
+        };
+
+        // Queen against bare king: both engines agree White is winning
+        let game = play_from("8/8/8/3k4/8/8/8/Q3K3 b - - 0 1", &adjudication);
+        assert_eq!(game.result, GameResult::WhiteWins);
+        assert_eq!(game.termination, Termination::Adjudication);
+        assert_eq!(game.moves.len(), 2);
+        assert!(game.to_pgn().contains("[Termination \"adjudication\"]"));
+    }
+
+    #[test]
+    fn test_draw_adjudication_waits_for_move_number() {
+        let fen = "4k3/8/8/p7/P7/8/8/4K3 w - - 0 50";
+        let adjudication = Adjudication {
+            draw_score: Some(Score::cp(100)),
+            draw_moves: 1,
+            ..Adjudication::default()
+        };
+
+        let game = play_from(fen, &adjudication);
+        assert_eq!(game.result, GameResult::Draw);
+        assert_eq!(game.termination, Termination::Adjudication);
+        assert_eq!(game.moves.len(), 2);
+
+        // Not before move 52 when draws are adjudicated after move 51
+        let later = Adjudication {
+            draw_move_number: 51,
+            ..adjudication
+        };
+        let game = play_from(fen, &later);
+        assert_eq!(game.result, GameResult::Draw);
+        assert_eq!(game.termination, Termination::Adjudication);
+        assert_eq!(game.moves.len(), 4);
+    }
+}
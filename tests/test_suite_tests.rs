@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod test_suite_tests {
+    use enrust::game_state::GameState;
+    use enrust::game_state::analysis::AnalysisConfig;
+    use enrust::game_state::test_suites::{SuiteResult, parse_epd, run_suite, summary_json};
+
+    #[test]
+    fn test_parse_epd() {
+        let text = "# Comment\n\
+                    \n\
+                    6k1/5ppp/8/8/8/8/8/R3R1K1 w - - bm Ra8# Re8#; id \"back rank\";\n\
+                    k7/8/1K6/8/8/8/8/2Q5 w - - am Qc8+; c0 \"ignored\";\n\
+                    8/8/8 w\n";
+        let positions = parse_epd(text);
+
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].id, "back rank");
+        assert_eq!(positions[0].fen, "6k1/5ppp/8/8/8/8/8/R3R1K1 w - - 0 1");
+        assert_eq!(positions[0].best_moves, ["Ra8#", "Re8#"]);
+        assert!(positions[0].avoid_moves.is_empty());
+
+        // Without an id, the line number identifies the position
+        assert_eq!(positions[1].id, "4");
+        assert!(positions[1].best_moves.is_empty());
+        assert_eq!(positions[1].avoid_moves, ["Qc8+"]);
+    }
+
+    #[test]
+    fn test_run_suite() {
+        let positions = parse_epd(
+            "6k1/5ppp/8/8/8/8/8/R3R1K1 w - - bm Ra8 Re8; id \"solved\";\n\
+             k7/8/1K6/8/8/8/8/2Q5 w - - am Qc8+; id \"avoided\";\n\
+             8/8/8/8/8/8/8/8 w - - bm Ke2; id \"invalid\";\n",
+        );
+        let config = AnalysisConfig {
+            depth: 3,
+            ..Default::default()
+        };
+
+        let game = GameState::new(Some(16));
+        let mut reported = Vec::new();
+        let results = run_suite(&game, &positions, &config, |index, result| {
+            reported.push((index, result.clone()))
+        });
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].solved);
+        assert!(matches!(
+            results[0].played.as_deref(),
+            Some("Ra8#" | "Re8#")
+        ));
+
+        // The check mark of the suite's SAN doesn't matter
+        assert_eq!(results[1].played.as_deref(), Some("Qc8#"));
+        assert!(!results[1].solved);
+
+        assert_eq!(results[2].played, None);
+        assert!(!results[2].solved);
+
+        let indices: Vec<usize> = reported.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(reported[1].1, results[1]);
+    }
+
+    #[test]
+    fn test_summary_json() {
+        let results = [
+            SuiteResult {
+                id: "WAC.001".to_string(),
+                played: Some("Qg6".to_string()),
+                solved: true,
+            },
+            SuiteResult {
+                id: "WAC.002".to_string(),
+                played: Some("c3".to_string()),
+                solved: false,
+            },
+        ];
+        let config = AnalysisConfig {
+            depth: 20,
+            movetime: Some(std::time::Duration::from_millis(500)),
+            threads: 1,
+        };
+
+        assert_eq!(
+            summary_json("wac", &results, &config),
+            format!(
+                "{{\"suite\":\"wac\",\"version\":\"{}\",\"movetime\":500,\"depth\":20,\"positions\":2,\"solved\":1,\"unsolved\":[\"WAC.002\"]}}",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+
+    #[cfg(feature = "testsuites")]
+    #[test]
+    fn test_embedded_suites_are_valid() {
+        use enrust::game_state::test_suites::embedded_suite;
+
+        assert_eq!(embedded_suite("nope"), None);
+        for name in ["wac", "BK"] {
+            let positions = parse_epd(embedded_suite(name).unwrap());
+            assert!(!positions.is_empty());
+
+            for position in positions {
+                let mut game = GameState::new(None);
+                assert!(game.set_fen_position(&position.fen), "{}", position.id);
+                assert!(!position.best_moves.is_empty(), "{}", position.id);
+                for san in position.best_moves.iter().chain(&position.avoid_moves) {
+                    assert!(
+                        game.create_move_from_san(san).is_some(),
+                        "{} {}",
+                        position.id,
+                        san
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,313 @@
+#[cfg(test)]
+mod time_management_tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    use enrust::game_state::Color;
+    use enrust::game_state::GameState;
+    use enrust::game_state::SearchConfiguration;
+    use enrust::game_state::board::evaluation::{GamePhase, TOTAL_PHASE};
+    use enrust::game_state::board::search::clock::NODES_PER_TIME_CHECK;
+    use enrust::game_state::board::search::{
+        IterativeDeepening, MinimaxAlphaBeta, Search, SearchClock,
+    };
+
+    /// Phase halfway between middlegame and endgame, which keeps the plain
+    /// time allocation.
+    fn half_phase() -> GamePhase {
+        GamePhase::new(TOTAL_PHASE / 2)
+    }
+
+    fn ponder_configuration() -> SearchConfiguration {
+        let mut sc = SearchConfiguration::new();
+        sc.wtime = Some(60_000);
+        sc.btime = Some(60_000);
+        sc.movestogo = Some(30);
+        sc.ponder = true;
+        sc.move_overhead = 0;
+        sc
+    }
+
+    /// Sudden death clock for White with the given time left and increment.
+    fn sudden_death(time_left: u64, increment: u64) -> SearchConfiguration {
+        let mut sc = SearchConfiguration::new();
+        sc.wtime = Some(time_left);
+        sc.winc = Some(increment);
+        sc.move_overhead = 50;
+        sc
+    }
+
+    #[test]
+    fn test_ponder_time_counts_toward_budget() {
+        let sc = ponder_configuration();
+        let pondered = Duration::from_millis(500);
+
+        // 60 seconds over 30 moves
+        assert_eq!(
+            sc.time_after_ponderhit(Color::White, half_phase(), pondered, true),
+            Some(Duration::from_millis(1500))
+        );
+
+        // Pondering longer than the budget leaves no time
+        assert_eq!(
+            sc.time_after_ponderhit(Color::White, half_phase(), Duration::from_secs(5), true),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_ponder_time_excluded_from_budget() {
+        let sc = ponder_configuration();
+        let pondered = Duration::from_millis(500);
+
+        assert_eq!(
+            sc.time_after_ponderhit(Color::Black, half_phase(), pondered, false),
+            Some(Duration::from_millis(2000))
+        );
+    }
+
+    #[test]
+    fn test_infinite_ponder_search_has_no_budget() {
+        let mut sc = ponder_configuration();
+        sc.infinite = true;
+
+        assert_eq!(
+            sc.time_after_ponderhit(Color::White, half_phase(), Duration::from_millis(500), true),
+            None
+        );
+    }
+
+    #[test]
+    fn test_middlegames_get_more_time_than_endgames() {
+        let mut sc = SearchConfiguration::new();
+        sc.wtime = Some(60_000);
+        sc.movestogo = Some(30);
+        sc.move_overhead = 0;
+
+        let middlegame = GamePhase::new(TOTAL_PHASE);
+        let endgame = GamePhase::new(0);
+
+        assert_eq!(
+            sc.time_for_move(Color::White, middlegame),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(2000))
+        );
+        assert_eq!(
+            sc.time_for_move(Color::White, endgame),
+            Some(Duration::from_millis(1500))
+        );
+
+        // A fixed move time is always used as is
+        sc.movetime = Some(1000);
+        assert_eq!(
+            sc.time_for_move(Color::White, endgame),
+            Some(Duration::from_millis(1000))
+        );
+    }
+
+    #[test]
+    fn test_sudden_death_splits_remaining_time() {
+        // 60 seconds minus the overhead over 20 moves
+        let sc = sudden_death(60_050, 0);
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(3000))
+        );
+
+        // `movestogo 0` is sudden death too, not a division by zero
+        let mut sc = sudden_death(60_050, 0);
+        sc.movestogo = Some(0);
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(3000))
+        );
+    }
+
+    #[test]
+    fn test_last_move_before_time_control_keeps_a_margin() {
+        let mut sc = sudden_death(10_050, 0);
+        sc.movestogo = Some(1);
+
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(8000))
+        );
+    }
+
+    #[test]
+    fn test_increment_never_exceeds_the_clock() {
+        // 100 ms left but 2 seconds of increment: only the clock can be used
+        let sc = sudden_death(100, 2000);
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(40))
+        );
+
+        // With time on the clock most of the increment is spent
+        let sc = sudden_death(10_050, 2000);
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(2500))
+        );
+    }
+
+    #[test]
+    fn test_nearly_flagged_clock_gets_minimum_time() {
+        for time_left in [0, 30, 50, 60] {
+            let sc = sudden_death(time_left, 0);
+            assert_eq!(
+                sc.time_for_move(Color::White, half_phase()),
+                Some(Duration::from_millis(10)),
+                "{} ms left",
+                time_left
+            );
+        }
+    }
+
+    #[test]
+    fn test_move_time_keeps_the_overhead_aside() {
+        let mut sc = sudden_death(60_000, 0);
+        sc.movetime = Some(1000);
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(950))
+        );
+
+        sc.movetime = Some(20);
+        assert_eq!(
+            sc.time_for_move(Color::White, half_phase()),
+            Some(Duration::from_millis(10))
+        );
+    }
+
+    #[test]
+    fn test_clock_limits() {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let clock = SearchClock::new(stop_flag.clone());
+
+        // Without limits the search never stops by itself
+        assert!(!clock.soft_limit_reached());
+        assert!(!clock.check());
+
+        clock.set_time_to_think(Duration::ZERO);
+        assert!(clock.soft_limit_reached());
+        assert!(clock.check());
+        assert!(stop_flag.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn test_search_stops_itself_at_the_hard_limit() {
+        let mut game = GameState::new(Some(16));
+        // A position full of captures: the time runs out inside an iteration
+        // rather than between two, where the soft limit would end the search
+        game.set_fen_position(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        );
+        let mut board = game.get_chess_board().clone();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let clock = Arc::new(SearchClock::new(stop_flag.clone()));
+        clock.set_time_to_think(Duration::from_millis(30));
+        board.set_clock(Some(clock));
+
+        // Far too deep to complete, only the clock can end the search
+        let start = Instant::now();
+        let (_, best_move) = IterativeDeepening::new(MinimaxAlphaBeta, 20).search(
+            &mut board,
+            Color::White,
+            stop_flag.clone(),
+        );
+
+        assert!(best_move.is_some());
+        assert!(stop_flag.load(Ordering::Acquire));
+        assert!(
+            start.elapsed() < Duration::from_millis(250),
+            "Search overshot its time: {:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn test_short_movetime_still_plays_a_move() {
+        let mut game = GameState::new(Some(16));
+        game.set_fen_position(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        );
+        let mut board = game.get_chess_board().clone();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let clock = Arc::new(SearchClock::new(stop_flag.clone()));
+        clock.set_time_to_think(Duration::from_millis(10));
+        board.set_clock(Some(clock));
+
+        let start = Instant::now();
+        let (_, best_move) = IterativeDeepening::new(MinimaxAlphaBeta, u8::MAX).search(
+            &mut board,
+            Color::White,
+            stop_flag,
+        );
+
+        let moves = board.generate_moves(Color::White);
+        assert!(moves.contains(&best_move.expect("move played on time")));
+        assert!(
+            start.elapsed() < Duration::from_millis(150),
+            "Search overshot its time: {:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn test_clock_counts_nodes_of_every_thread() {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let clock = Arc::new(SearchClock::new(stop_flag.clone()));
+        clock.set_node_limit(4000);
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let clock = Arc::clone(&clock);
+                std::thread::spawn(move || clock.add_nodes(NODES_PER_TIME_CHECK))
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(clock.nodes(), 4 * NODES_PER_TIME_CHECK);
+        assert!(clock.check());
+        assert!(stop_flag.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn test_search_stops_at_the_node_limit() {
+        let mut game = GameState::new(Some(16));
+        game.start_position();
+        let mut board = game.get_chess_board().clone();
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let clock = Arc::new(SearchClock::new(stop_flag.clone()));
+        clock.set_node_limit(20_000);
+        board.set_clock(Some(Arc::clone(&clock)));
+
+        let nodes_before = board.nodes();
+        let (_, best_move) = IterativeDeepening::new(MinimaxAlphaBeta, u8::MAX).search(
+            &mut board,
+            Color::White,
+            stop_flag.clone(),
+        );
+        let nodes = board.nodes() - nodes_before;
+
+        assert!(best_move.is_some());
+        assert!(stop_flag.load(Ordering::Acquire));
+        // The limit is overshot by less than one batch of nodes
+        assert!(clock.nodes() >= 20_000);
+        assert!(
+            nodes < 20_000 + 2 * NODES_PER_TIME_CHECK,
+            "Searched {} nodes",
+            nodes
+        );
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod topology_tests {
+    use enrust::game_state::topology::{
+        count_physical_cores, logical_cpus, physical_cores, resolve_threads,
+    };
+
+    /// One `/proc/cpuinfo` block of a logical CPU.
+    fn cpu(processor: usize, socket: usize, core: usize) -> String {
+        format!(
+            "processor\t: {}\nmodel name\t: Test CPU\nphysical id\t: {}\ncore id\t\t: {}\n",
+            processor, socket, core
+        )
+    }
+
+    #[test]
+    fn test_hyperthreads_share_their_core() {
+        // Two cores with two hyperthreads each
+        let cpuinfo = [cpu(0, 0, 0), cpu(1, 0, 1), cpu(2, 0, 0), cpu(3, 0, 1)].join("\n");
+        assert_eq!(count_physical_cores(&cpuinfo), Some(2));
+    }
+
+    #[test]
+    fn test_cores_are_counted_per_socket() {
+        // Core ids start over on the second socket
+        let cpuinfo = [cpu(0, 0, 0), cpu(1, 0, 1), cpu(2, 1, 0), cpu(3, 1, 1)].join("\n");
+        assert_eq!(count_physical_cores(&cpuinfo), Some(4));
+    }
+
+    #[test]
+    fn test_cpuinfo_without_cores() {
+        let cpuinfo = "processor\t: 0\nBogoMIPS\t: 48.00\n\nprocessor\t: 1\nBogoMIPS\t: 48.00\n";
+        assert_eq!(count_physical_cores(cpuinfo), None);
+        assert_eq!(count_physical_cores(""), None);
+    }
+
+    #[test]
+    fn test_thread_counts() {
+        assert!((1..=logical_cpus()).contains(&physical_cores()));
+        assert_eq!(resolve_threads(0), physical_cores());
+        assert_eq!(resolve_threads(3), 3);
+    }
+}
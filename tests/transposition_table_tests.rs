@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod basic_tests {
+    use enrust::game_state::Score;
     use enrust::game_state::board::transposition_table::{
         NodeType, TranspositionTable, TranspositionTableData,
     };
@@ -10,7 +11,7 @@ mod basic_tests {
 
         let hash = 0x123456789ABCDEF0;
         let data = TranspositionTableData {
-            score: 150,
+            score: Score::cp(150),
             depth: 8,
             node_type: NodeType::Exact,
             best_move: 0x1543,
@@ -21,7 +22,7 @@ mod basic_tests {
         tt.save_position(hash, &data);
 
         let retrieved = tt.retrieve_position(hash).unwrap();
-        assert_eq!(retrieved.score, 150);
+        assert_eq!(retrieved.score, Score::cp(150));
         assert_eq!(retrieved.depth, 8);
         assert_eq!(retrieved.node_type, NodeType::Exact);
         assert_eq!(retrieved.best_move, 0x1543);
@@ -35,7 +36,7 @@ mod basic_tests {
         let hash2 = 0x123456789ABCDEF1; // Different hash
 
         let data1 = TranspositionTableData {
-            score: 100,
+            score: Score::cp(100),
             depth: 6,
             node_type: NodeType::Exact,
             best_move: 0x1543,
@@ -43,7 +44,7 @@ mod basic_tests {
         };
 
         let data2 = TranspositionTableData {
-            score: -50,
+            score: Score::cp(-50),
             depth: 4,
             node_type: NodeType::UpperBound,
             best_move: 0x2543,
@@ -58,8 +59,8 @@ mod basic_tests {
         let retrieved1 = tt.retrieve_position(hash1).unwrap();
         let retrieved2 = tt.retrieve_position(hash2).unwrap();
 
-        assert_eq!(retrieved1.score, 100);
-        assert_eq!(retrieved2.score, -50);
+        assert_eq!(retrieved1.score, Score::cp(100));
+        assert_eq!(retrieved2.score, Score::cp(-50));
     }
 
     #[test]
@@ -72,6 +73,29 @@ mod basic_tests {
         assert!(tt.retrieve_position(hash).is_none());
     }
 
+    #[test]
+    fn test_clear_empties_the_table() {
+        let tt = TranspositionTable::new(4);
+
+        let hashes = [0x123456789ABCDEF0, 0x0FEDCBA987654321];
+        for hash in hashes {
+            let data = TranspositionTableData {
+                score: Score::cp(42),
+                depth: 3,
+                node_type: NodeType::LowerBound,
+                best_move: 0x0E1C,
+                age: 0,
+            };
+            tt.save_position(hash, &data);
+        }
+
+        tt.clear();
+
+        for hash in hashes {
+            assert!(tt.retrieve_position(hash).is_none());
+        }
+    }
+
     #[test]
     fn test_overwrite_behavior() {
         let tt = TranspositionTable::new(4);
@@ -79,7 +103,7 @@ mod basic_tests {
         let hash = 0x123456789ABCDEF0;
 
         let data1 = TranspositionTableData {
-            score: 100,
+            score: Score::cp(100),
             depth: 6,
             node_type: NodeType::Exact,
             best_move: 0x1543,
@@ -87,7 +111,7 @@ mod basic_tests {
         };
 
         let data2 = TranspositionTableData {
-            score: 200,
+            score: Score::cp(200),
             depth: 8,
             node_type: NodeType::UpperBound,
             best_move: 0x2543,
@@ -96,16 +120,17 @@ mod basic_tests {
 
         // Store first entry
         tt.save_position(hash, &data1);
-        assert_eq!(tt.retrieve_position(hash).unwrap().score, 100);
+        assert_eq!(tt.retrieve_position(hash).unwrap().score, Score::cp(100));
 
         // Overwrite with second entry
         tt.save_position(hash, &data2);
-        assert_eq!(tt.retrieve_position(hash).unwrap().score, 200);
+        assert_eq!(tt.retrieve_position(hash).unwrap().score, Score::cp(200));
     }
 }
 
 #[cfg(test)]
 mod xor_verification_tests {
+    use enrust::game_state::Score;
     use enrust::game_state::board::transposition_table::{
         NodeType, TranspositionTable, TranspositionTableData,
     };
@@ -118,7 +143,7 @@ mod xor_verification_tests {
         let hash2 = 0xFEDCBA9876543210; // Different hash
 
         let data = TranspositionTableData {
-            score: 150,
+            score: Score::cp(150),
             depth: 8,
             node_type: NodeType::Exact,
             best_move: 0x1543,
@@ -141,7 +166,7 @@ mod xor_verification_tests {
 
         let hash = 0x123456789ABCDEF0;
         let data = TranspositionTableData {
-            score: 150,
+            score: Score::cp(150),
             depth: 8,
             node_type: NodeType::Exact,
             best_move: 0x1543,
@@ -156,7 +181,7 @@ mod xor_verification_tests {
             let result = tt.retrieve_position(hash);
             assert!(result.is_some());
             let retrieved = result.unwrap();
-            assert_eq!(retrieved.score, 150);
+            assert_eq!(retrieved.score, Score::cp(150));
             assert_eq!(retrieved.depth, 8);
         }
     }
@@ -166,6 +191,7 @@ mod concurrent_thread_access_tests {
     use std::sync::Arc;
     use std::thread;
 
+    use enrust::game_state::Score;
     use enrust::game_state::board::transposition_table::{
         NodeType, TranspositionTable, TranspositionTableData,
     };
@@ -182,7 +208,7 @@ mod concurrent_thread_access_tests {
                 for i in 0..1000 {
                     let hash: u64 = (thread_id * 10000) + i;
                     let data = TranspositionTableData {
-                        score: (thread_id * 100 + i) as i16,
+                        score: Score::cp((thread_id * 100 + i) as i32),
                         depth: (i % 20) as u8,
                         node_type: NodeType::try_from(i as u8 % 3).unwrap(),
                         best_move: (i * 10) as u16,
@@ -202,7 +228,10 @@ mod concurrent_thread_access_tests {
                     );
 
                     let retrieved_data = retrieved.unwrap();
-                    assert_eq!(retrieved_data.score, (thread_id * 100 + i) as i16);
+                    assert_eq!(
+                        retrieved_data.score,
+                        Score::cp((thread_id * 100 + i) as i32)
+                    );
                     assert_eq!(retrieved_data.depth, (i % 20) as u8);
                 }
             });
@@ -222,7 +251,7 @@ mod concurrent_thread_access_tests {
         for i in 0..1000 {
             let hash = i * 100;
             let data = TranspositionTableData {
-                score: i as i16,
+                score: Score::cp(i as i32),
                 depth: (i % 10) as u8,
                 node_type: NodeType::Exact,
                 best_move: (i * 5) as u16,
@@ -240,7 +269,7 @@ mod concurrent_thread_access_tests {
                 for i in 0..500 {
                     let hash = (i * 100) % 1000;
                     if let Some(entry) = tt_clone.retrieve_position(hash) {
-                        assert_eq!(entry.score, (hash / 100) as i16);
+                        assert_eq!(entry.score, Score::cp((hash / 100) as i32));
                     }
                 }
             });
@@ -254,7 +283,7 @@ mod concurrent_thread_access_tests {
                 for i in 0..500 {
                     let hash = 1000 + (thread_id * 1000) + i;
                     let data = TranspositionTableData {
-                        score: -(i as i16),
+                        score: Score::cp(-(i as i32)),
                         depth: (i % 15) as u8,
                         node_type: NodeType::LowerBound,
                         best_move: (i * 3) as u16,
@@ -273,11 +302,13 @@ mod concurrent_thread_access_tests {
 }
 
 mod transposition_logic_tests {
+    use enrust::game_state::Color;
     use enrust::game_state::GameState;
     use enrust::game_state::Move;
     use enrust::game_state::Piece;
+    use enrust::game_state::Score;
     use enrust::game_state::board::transposition_table::{
-        NodeType, TranspositionTable, TranspositionTableData,
+        BUCKET_SIZE, NodeType, TranspositionEntry, TranspositionTable, TranspositionTableData,
     };
 
     #[test]
@@ -288,7 +319,7 @@ mod transposition_logic_tests {
 
         // Lower depth entry
         let shallow_data = TranspositionTableData {
-            score: 100,
+            score: Score::cp(100),
             depth: 4,
             node_type: NodeType::Exact,
             best_move: 0x1543,
@@ -297,7 +328,7 @@ mod transposition_logic_tests {
 
         // Higher depth entry - should be considered better
         let deep_data = TranspositionTableData {
-            score: 120, // Different score to verify it actually gets replaced
+            score: Score::cp(120), // Different score to verify it actually gets replaced
             depth: 8,
             node_type: NodeType::Exact,
             best_move: 0x2543,
@@ -307,13 +338,50 @@ mod transposition_logic_tests {
         // Store shallow entry first
         tt.save_position(hash, &shallow_data);
         assert_eq!(tt.retrieve_position(hash).unwrap().depth, 4);
-        assert_eq!(tt.retrieve_position(hash).unwrap().score, 100);
+        assert_eq!(tt.retrieve_position(hash).unwrap().score, Score::cp(100));
 
         // Replace previous entry with deeper search entry
         tt.save_position(hash, &deep_data);
         let retrieved = tt.retrieve_position(hash).unwrap();
         assert_eq!(retrieved.depth, 8);
-        assert_eq!(retrieved.score, 120);
+        assert_eq!(retrieved.score, Score::cp(120));
+    }
+
+    #[test]
+    fn test_entries_fill_cache_lines() {
+        assert_eq!(std::mem::size_of::<TranspositionEntry>(), 16);
+        assert_eq!(std::mem::align_of::<TranspositionEntry>(), 16);
+        assert_eq!(BUCKET_SIZE * std::mem::size_of::<TranspositionEntry>(), 64);
+    }
+
+    #[test]
+    fn test_colliding_positions_share_a_bucket() {
+        // 1 MB holds 16,384 buckets, so these hashes share the first one
+        let tt = TranspositionTable::new(1);
+        let colliding = |index: u64| index * 16_384;
+        let data = |depth: u8| TranspositionTableData {
+            score: Score::cp(depth as i32),
+            depth,
+            node_type: NodeType::LowerBound,
+            best_move: 0,
+            age: 0,
+        };
+
+        for index in 1..=BUCKET_SIZE as u64 {
+            tt.save_position(colliding(index), &data(10 + index as u8));
+        }
+        for index in 1..=BUCKET_SIZE as u64 {
+            assert_eq!(
+                tt.retrieve_position(colliding(index)).unwrap().depth,
+                10 + index as u8
+            );
+        }
+
+        // A full bucket gives up its shallowest entry
+        tt.save_position(colliding(10), &data(5));
+        assert!(tt.retrieve_position(colliding(1)).is_none());
+        assert_eq!(tt.retrieve_position(colliding(10)).unwrap().depth, 5);
+        assert!(tt.retrieve_position(colliding(2)).is_some());
     }
 
     #[test]
@@ -322,7 +390,7 @@ mod transposition_logic_tests {
 
         // Test that packing and unpacking TranspositionTableData works correctly
         let original = TranspositionTableData {
-            score: -32768,              // Minimum i16
+            score: -Score::INFINITE,    // Lowest search score
             depth: 255,                 // Maximum u8
             node_type: NodeType::Exact, // Maximum 2-bit value
             best_move: 0xFFFF,          // Maximum u16
@@ -341,7 +409,7 @@ mod transposition_logic_tests {
 
         // Test positive score
         let positive = TranspositionTableData {
-            score: 32767, // Maximum i16
+            score: Score::INFINITE, // Highest search score
             depth: 128,
             node_type: NodeType::LowerBound,
             best_move: 0x1234,
@@ -389,8 +457,6 @@ mod transposition_logic_tests {
                 castling: None,
                 en_passant: false,
                 en_passant_square: None,
-                previous_en_passant: None,
-                previous_castling_rights: None,
             };
 
             let packed = original_move.encode(game.get_chess_board());
@@ -402,14 +468,38 @@ mod transposition_logic_tests {
         }
     }
 
+    #[test]
+    fn test_decoded_moves_make_and_unmake() {
+        // Castling, en passant and a double push change the irreversible
+        // state, which the board saves rather than the move
+        let game = setup_game_with_fen("r3k2r/8/8/3pP3/8/8/3P4/R3K2R w KQkq d6 0 1");
+        let mut board = game.get_chess_board().clone();
+        let key = board.polyglot_key(Color::White);
+        let moves = board.generate_moves(Color::White);
+
+        for uci in ["e1g1", "e1c1", "e5d6", "d2d4", "a1a8"] {
+            let generated = board.from_uci(uci).expect("legal move");
+            let decoded = Move::decode(generated.encode(&board), &board).expect("decodable move");
+            assert_eq!(decoded, generated);
+
+            board.make_move(&decoded);
+            board.unmake_move(&decoded);
+            assert_eq!(board.polyglot_key(Color::White), key, "{}", uci);
+            assert_eq!(board.generate_moves(Color::White), moves, "{}", uci);
+        }
+    }
+
     #[test]
     fn test_score_packing_roundtrip_extreme_values() {
         let tt = TranspositionTable::new(4);
 
         // Test extreme values and zero
         let extreme_cases = vec![
-            -32768, // i16::MIN
-            -16384, 0, 16383, 32767, // i16::MAX
+            -Score::INFINITE,
+            Score::cp(-16384),
+            Score::ZERO,
+            Score::cp(16383),
+            Score::INFINITE,
         ];
 
         for &score in &extreme_cases {
@@ -421,9 +511,9 @@ mod transposition_logic_tests {
                 age: 15,
             };
 
-            tt.save_position(score as u64, &data);
+            tt.save_position(score.centipawns() as u64, &data);
 
-            let unpacked = tt.retrieve_position(score as u64).unwrap();
+            let unpacked = tt.retrieve_position(score.centipawns() as u64).unwrap();
 
             assert_eq!(
                 unpacked.score, data.score,
@@ -437,3 +527,313 @@ mod transposition_logic_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod zobrist_seed_tests {
+    use enrust::game_state::board::transposition_table::{
+        DEFAULT_ZOBRIST_SEED, ZOBRIST_SEED_VAR, Zobrist,
+    };
+
+    fn same_keys(a: &Zobrist, b: &Zobrist) -> bool {
+        a.pieces == b.pieces
+            && a.side_to_move == b.side_to_move
+            && a.castling_rights == b.castling_rights
+            && a.en_passant == b.en_passant
+    }
+
+    #[test]
+    fn test_equal_seeds_give_equal_keys() {
+        assert!(same_keys(&Zobrist::from_seed(42), &Zobrist::from_seed(42)));
+        assert!(!same_keys(&Zobrist::from_seed(42), &Zobrist::from_seed(43)));
+    }
+
+    #[test]
+    fn test_default_keys_use_the_default_seed() {
+        if std::env::var_os(ZOBRIST_SEED_VAR).is_some() {
+            return;
+        }
+
+        assert!(same_keys(
+            &Zobrist::new(),
+            &Zobrist::from_seed(DEFAULT_ZOBRIST_SEED)
+        ));
+    }
+
+    #[test]
+    fn test_random_keys_differ_from_seeded_keys() {
+        assert!(!same_keys(
+            &Zobrist::random(),
+            &Zobrist::from_seed(DEFAULT_ZOBRIST_SEED)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod hash_file_tests {
+    use std::fs;
+    use std::io::ErrorKind;
+    use std::path::PathBuf;
+
+    use enrust::game_state::board::transposition_table::{
+        HASH_FILE_MAGIC, HASH_FILE_VERSION, NodeType, TranspositionTable, TranspositionTableData,
+        Zobrist,
+    };
+    use enrust::game_state::{GameState, Score, SearchConfiguration};
+
+    fn temp_hash_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("enrust_{}_{}.hash", name, std::process::id()))
+    }
+
+    fn entry(score: i32, depth: u8) -> TranspositionTableData {
+        TranspositionTableData {
+            score: Score::cp(score),
+            depth,
+            node_type: NodeType::LowerBound,
+            best_move: 0x1543,
+            age: 3,
+        }
+    }
+
+    fn header(version: u32, keys: &Zobrist, count: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&HASH_FILE_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&keys.checksum().to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let path = temp_hash_path("roundtrip");
+        let keys = Zobrist::from_seed(1);
+        let tt = TranspositionTable::new(1);
+        tt.save_position(0x1111, &entry(-250, 7));
+        tt.save_position(0x2222, &entry(40, 3));
+        assert_eq!(tt.save(&path, &keys).unwrap(), 2);
+
+        // The table loaded into may have another size
+        let loaded = TranspositionTable::new(2);
+        loaded.save_position(0x3333, &entry(10, 1));
+        assert_eq!(loaded.load(&path, &keys).unwrap(), 2);
+        fs::remove_file(&path).unwrap();
+
+        let first = loaded.retrieve_position(0x1111).unwrap();
+        assert_eq!(first.score, Score::cp(-250));
+        assert_eq!(first.depth, 7);
+        assert_eq!(first.node_type, NodeType::LowerBound);
+        assert_eq!(first.best_move, 0x1543);
+        assert_eq!(first.age, 3);
+        assert_eq!(loaded.retrieve_position(0x2222).unwrap().depth, 3);
+        // Loading replaces the previous entries
+        assert!(loaded.retrieve_position(0x3333).is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_other_keys_and_versions() {
+        let path = temp_hash_path("keys");
+        let keys = Zobrist::from_seed(1);
+        let tt = TranspositionTable::new(1);
+        tt.save_position(0x1111, &entry(20, 4));
+        tt.save(&path, &keys).unwrap();
+
+        let error = tt.load(&path, &Zobrist::from_seed(2)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        fs::write(&path, header(HASH_FILE_VERSION + 1, &keys, 0)).unwrap();
+        assert_eq!(
+            tt.load(&path, &keys).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        fs::write(&path, b"not a hash file").unwrap();
+        assert_eq!(
+            tt.load(&path, &keys).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        fs::remove_file(&path).unwrap();
+
+        // Rejected files leave the table untouched
+        assert_eq!(tt.retrieve_position(0x1111).unwrap().depth, 4);
+    }
+
+    #[test]
+    fn test_load_rejects_insane_entries() {
+        let path = temp_hash_path("insane");
+        let keys = Zobrist::from_seed(1);
+        let tt = TranspositionTable::new(1);
+
+        // Node type 3 doesn't exist
+        let mut bytes = header(HASH_FILE_VERSION, &keys, 1);
+        bytes.extend_from_slice(&0x1111u64.to_le_bytes());
+        bytes.extend_from_slice(&(3u64 << 24).to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(
+            tt.load(&path, &keys).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // Fewer entries than announced
+        fs::write(&path, header(HASH_FILE_VERSION, &keys, 2)).unwrap();
+        assert!(tt.load(&path, &keys).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_game_resumes_analysis_from_a_hash_file() {
+        let path = temp_hash_path("game");
+        let mut game = GameState::new(Some(1));
+        game.start_position();
+        let mut sc = SearchConfiguration::new();
+        sc.depth = Some(3);
+        game.set_time_control(&sc);
+        let searched = game.search().join();
+        assert!(game.save_hash_file(&path).unwrap() > 0);
+
+        let mut resumed = GameState::new(Some(1));
+        resumed.start_position();
+        resumed.set_time_control(&sc);
+        assert!(resumed.load_hash_file(&path).unwrap() > 0);
+        fs::remove_file(&path).unwrap();
+
+        // The loaded entries spare most of the search
+        let result = resumed.search().join();
+        assert!(result.nodes < searched.nodes);
+    }
+}
+
+mod replacement_policy_tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    use enrust::game_state::board::search::{IterativeDeepening, MinimaxAlphaBeta, Search};
+    use enrust::game_state::board::transposition_table::{
+        BUCKET_SIZE, NodeType, TranspositionTable, TranspositionTableData,
+    };
+    use enrust::game_state::{Color, GameState, Score};
+
+    /// Hashes sharing the first set of a 1 MB table with the given ways.
+    fn colliding(ways: usize, index: u64) -> u64 {
+        index * (16_384 * BUCKET_SIZE / ways) as u64
+    }
+
+    fn data(depth: u8, age: u8) -> TranspositionTableData {
+        TranspositionTableData {
+            score: Score::cp(depth as i32),
+            depth,
+            node_type: NodeType::LowerBound,
+            best_move: 0,
+            age,
+        }
+    }
+
+    /// Searches a pawn endgame full of transpositions, Fine #70, to a fixed
+    /// depth with a table too small for it.
+    ///
+    /// # Returns
+    ///
+    /// The share of the table lookups that found their position
+    fn hit_rate(ways: usize) -> f64 {
+        let mut game = GameState::new(Some(1));
+        game.set_fen_position("8/k7/3p4/p2P1p2/P2P1P2/8/8/K7 w - - 0 1");
+        let mut board = game.get_chess_board().clone();
+        board.set_transposition_table(Arc::new(TranspositionTable::with_ways(1, ways)));
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        IterativeDeepening::new(MinimaxAlphaBeta, 19).search(&mut board, Color::White, stop_flag);
+
+        board.tt_hits() as f64 / board.tt_probes() as f64
+    }
+
+    #[test]
+    fn test_buckets_raise_the_hit_rate_of_a_full_table() {
+        let single_slot = hit_rate(1);
+        let buckets = hit_rate(BUCKET_SIZE);
+        assert!(
+            buckets > single_slot,
+            "{BUCKET_SIZE}-way hit rate {buckets:.3} is not above the single-slot {single_slot:.3}"
+        );
+    }
+
+    #[test]
+    fn test_single_way_replaces_on_every_collision() {
+        let tt = TranspositionTable::with_ways(1, 1);
+        tt.save_position(colliding(1, 1), &data(20, 0));
+        tt.save_position(colliding(1, 2), &data(1, 0));
+
+        assert!(tt.retrieve_position(colliding(1, 1)).is_none());
+        assert_eq!(tt.retrieve_position(colliding(1, 2)).unwrap().depth, 1);
+    }
+
+    #[test]
+    fn test_two_ways_keep_two_colliding_positions() {
+        let tt = TranspositionTable::with_ways(1, 2);
+        tt.save_position(colliding(2, 1), &data(20, 0));
+        tt.save_position(colliding(2, 2), &data(10, 0));
+        assert!(tt.retrieve_position(colliding(2, 1)).is_some());
+        assert!(tt.retrieve_position(colliding(2, 2)).is_some());
+
+        tt.save_position(colliding(2, 3), &data(1, 0));
+        assert!(tt.retrieve_position(colliding(2, 1)).is_some());
+        assert!(tt.retrieve_position(colliding(2, 2)).is_none());
+        assert!(tt.retrieve_position(colliding(2, 3)).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "ways must divide the bucket size")]
+    fn test_ways_must_divide_the_bucket_size() {
+        TranspositionTable::with_ways(1, 3);
+    }
+
+    #[test]
+    fn test_entries_of_earlier_searches_are_replaced_first() {
+        let tt = TranspositionTable::new(1);
+        let ways = BUCKET_SIZE;
+        for index in 1..=BUCKET_SIZE as u64 {
+            tt.save_position(
+                colliding(ways, index),
+                &data(10 + index as u8, tt.generation()),
+            );
+        }
+
+        // Two searches later, a shallow entry of the current search is worth
+        // more than the deep entries left behind
+        tt.new_search();
+        tt.new_search();
+        tt.save_position(colliding(ways, 10), &data(4, tt.generation()));
+        assert!(tt.retrieve_position(colliding(ways, 1)).is_none());
+        tt.save_position(colliding(ways, 11), &data(3, tt.generation()));
+        assert!(tt.retrieve_position(colliding(ways, 2)).is_none());
+
+        // Among entries of the current search the shallowest goes
+        tt.save_position(colliding(ways, 12), &data(2, tt.generation()));
+        tt.save_position(colliding(ways, 13), &data(1, tt.generation()));
+        tt.save_position(colliding(ways, 14), &data(5, tt.generation()));
+        assert!(tt.retrieve_position(colliding(ways, 13)).is_none());
+        assert_eq!(tt.retrieve_position(colliding(ways, 14)).unwrap().depth, 5);
+        assert_eq!(tt.retrieve_position(colliding(ways, 10)).unwrap().depth, 4);
+    }
+
+    #[test]
+    fn test_entries_of_earlier_searches_are_overwritten_by_their_position() {
+        let tt = TranspositionTable::new(1);
+        let hash = colliding(BUCKET_SIZE, 1);
+        tt.save_position(hash, &data(12, tt.generation()));
+        tt.save_position(hash, &data(6, tt.generation()));
+        assert_eq!(tt.retrieve_position(hash).unwrap().depth, 12);
+
+        tt.new_search();
+        tt.save_position(hash, &data(6, tt.generation()));
+        assert_eq!(tt.retrieve_position(hash).unwrap().depth, 6);
+    }
+
+    #[test]
+    fn test_generation_wraps_around() {
+        let tt = TranspositionTable::new(1);
+        let start = tt.generation();
+        for _ in 0..256 {
+            tt.new_search();
+        }
+        assert_eq!(tt.generation(), start);
+    }
+}
@@ -0,0 +1,308 @@
+#[cfg(test)]
+mod uci_tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use enrust::game_state::GameState;
+    use enrust::game_state::SearchConfiguration;
+    use enrust::game_state::UciSession;
+    use enrust::game_state::replay::{Session, replay_session};
+    use enrust::game_state::uci::{GoCommand, parse_go_command};
+
+    /// Sends commands to the engine and checks whether it answers with a
+    /// best move within a second, long after the search is over. The
+    /// search is then stopped.
+    fn answers_before_stop(commands: &[&str]) -> bool {
+        let mut engine = Command::new(env!("CARGO_BIN_EXE_enrust"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("engine runs");
+        let mut stdin = engine.stdin.take().expect("stdin is piped");
+        let stdout = engine.stdout.take().expect("stdout is piped");
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for command in commands {
+            writeln!(stdin, "{}", command).unwrap();
+        }
+        thread::sleep(Duration::from_secs(1));
+        let answered = receiver.try_iter().any(|line| line.starts_with("bestmove"));
+
+        writeln!(stdin, "stop").unwrap();
+        let stopped = answered
+            || std::iter::from_fn(|| receiver.recv_timeout(Duration::from_secs(10)).ok())
+                .any(|line| line.starts_with("bestmove"));
+        writeln!(stdin, "quit").unwrap();
+        engine.wait().unwrap();
+
+        assert!(stopped, "no best move after stop");
+        answered
+    }
+
+    fn parse_search(game: &mut GameState, command: &str) -> (SearchConfiguration, Vec<String>) {
+        match parse_go_command(game, &mut command.split_whitespace()) {
+            (Some(GoCommand::Search(sc)), errors) => (sc, errors),
+            _ => panic!("'{}' is not a search", command),
+        }
+    }
+
+    #[test]
+    fn test_valid_go_command() {
+        let mut game = GameState::new(None);
+        let (sc, errors) = parse_search(
+            &mut game,
+            "wtime 60000 btime 50000 winc 1000 binc 500 movestogo 20",
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(sc.wtime, Some(60000));
+        assert_eq!(sc.btime, Some(50000));
+        assert_eq!(sc.winc, Some(1000));
+        assert_eq!(sc.binc, Some(500));
+        assert_eq!(sc.movestogo, Some(20));
+    }
+
+    #[test]
+    fn test_invalid_value_keeps_the_other_parameters() {
+        let mut game = GameState::new(None);
+        let (sc, errors) = parse_search(&mut game, "wtime abc btime 1000 depth -3 movetime 500");
+
+        assert_eq!(sc.wtime, None);
+        assert_eq!(sc.btime, Some(1000));
+        assert_eq!(sc.depth, None);
+        assert_eq!(sc.movetime, Some(500));
+        assert_eq!(
+            errors,
+            [
+                "invalid value for go wtime: 'abc' is not a non-negative integer",
+                "invalid value for go depth: '-3' is not a non-negative integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_value_does_not_take_the_next_parameter() {
+        let mut game = GameState::new(None);
+        let (sc, errors) = parse_search(&mut game, "wtime btime 1000 nodes");
+
+        assert_eq!(sc.wtime, None);
+        assert_eq!(sc.btime, Some(1000));
+        assert_eq!(sc.nodes, None);
+        assert_eq!(
+            errors,
+            ["missing value for go wtime", "missing value for go nodes"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_go_parameter() {
+        let mut game = GameState::new(None);
+        let (sc, errors) = parse_search(&mut game, "fast depth 4 infinite");
+
+        assert_eq!(sc.depth, Some(4));
+        assert!(sc.infinite);
+        assert_eq!(errors, ["unknown go parameter: 'fast'"]);
+    }
+
+    #[test]
+    fn test_searchmoves_reports_illegal_moves() {
+        let mut game = GameState::new(None);
+        game.start_position();
+
+        let (sc, errors) = parse_search(&mut game, "searchmoves e2e4 e2e5 d2d4 depth 3");
+        assert_eq!(sc.searchmoves.map(|moves| moves.len()), Some(2));
+        assert_eq!(sc.depth, Some(3));
+        assert_eq!(errors, ["illegal move in go searchmoves: 'e2e5'"]);
+
+        // Without a legal move the restriction is dropped
+        let (sc, errors) = parse_search(&mut game, "searchmoves a1a8");
+        assert!(sc.searchmoves.is_none());
+        assert_eq!(
+            errors,
+            [
+                "illegal move in go searchmoves: 'a1a8'",
+                "no legal move in go searchmoves, searching all moves",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_avoidmoves_lists_the_moves_to_leave_out() {
+        let mut game = GameState::new(None);
+        game.start_position();
+
+        let (sc, errors) = parse_search(&mut game, "avoidmoves e2e4 d2d5 searchmoves d2d4 g1f3");
+        let avoided: Vec<_> = sc
+            .avoidmoves
+            .iter()
+            .map(|mv| game.get_chess_board().move_to_uci(mv))
+            .collect();
+        assert_eq!(avoided, ["e2e4"]);
+        assert_eq!(sc.searchmoves.map(|moves| moves.len()), Some(2));
+        assert_eq!(errors, ["illegal move in go avoidmoves: 'd2d5'"]);
+    }
+
+    #[test]
+    fn test_go_perft() {
+        let mut game = GameState::new(None);
+
+        let (command, errors) = parse_go_command(&mut game, &mut "perft 3".split_whitespace());
+        assert!(matches!(command, Some(GoCommand::Perft(3))));
+        assert!(errors.is_empty());
+
+        let (command, errors) = parse_go_command(&mut game, &mut "perft x".split_whitespace());
+        assert!(command.is_none());
+        assert_eq!(
+            errors,
+            ["invalid value for go perft: 'x' is not a non-negative integer"]
+        );
+    }
+
+    #[test]
+    fn test_search_info_reports_seldepth_and_new_best_moves() {
+        let session = Session {
+            commands: vec!["position startpos".to_string(), "go depth 3".to_string()],
+            answers: Vec::new(),
+        };
+        let report =
+            replay_session(Path::new(env!("CARGO_BIN_EXE_enrust")), &session).expect("engine runs");
+        let info: Vec<String> = report
+            .mismatches
+            .into_iter()
+            .filter_map(|mismatch| mismatch.actual)
+            .filter(|line| line.contains(" score "))
+            .collect();
+
+        assert!(!info.is_empty());
+        for line in &info {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            assert_eq!(tokens[..2], ["info", "depth"], "{}", line);
+            assert_eq!(tokens[3], "seldepth", "{}", line);
+            assert!(tokens[4].parse::<u8>().unwrap() >= tokens[2].parse::<u8>().unwrap());
+            // The nodes of the first depths are counted before a full batch
+            let nodes = tokens.iter().position(|&token| token == "nodes").unwrap();
+            assert!(tokens[nodes + 1].parse::<u64>().unwrap() > 0, "{}", line);
+        }
+        // A better move found during an iteration is only a lower bound, the
+        // iteration ends with the exact score
+        let last_bound = info
+            .iter()
+            .rposition(|line| line.contains(" lowerbound "))
+            .expect("the first iteration finds better moves than the first");
+        assert!(last_bound + 1 < info.len());
+        assert!(!info.last().unwrap().contains("bound"));
+    }
+
+    #[test]
+    fn test_go_infinite_answers_only_after_stop() {
+        // The best move is far ahead, and the depth limit quickly reached
+        assert!(!answers_before_stop(&[
+            "setoption name MaxDepth value 2",
+            "position fen 4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1",
+            "go infinite",
+        ]));
+    }
+
+    #[test]
+    fn test_forced_move_waits_for_stop_when_pondering_or_analyzing() {
+        for go in ["go ponder wtime 60000 btime 60000", "go infinite"] {
+            assert!(
+                !answers_before_stop(&["position fen 7k/8/8/8/8/8/6q1/7K w - - 0 1", go]),
+                "{}",
+                go
+            );
+        }
+    }
+
+    #[test]
+    fn test_session_starts_at_the_starting_position() {
+        let session = UciSession::new(GameState::new(Some(1)));
+
+        assert_eq!(
+            session.game_state().to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+        assert!(
+            session
+                .game_state()
+                .position_dump(false)
+                .contains("Fen: rnbqkbnr/")
+        );
+    }
+
+    #[test]
+    fn test_chess960_castling_is_played_as_king_takes_rook() {
+        let mut session = UciSession::new(GameState::new(Some(1)));
+        assert!(session.handle_command("setoption name UCI_Chess960 value true"));
+
+        let fen = "nnrbkqbr/pppppppp/8/8/8/8/PPPPPPPP/NNRBKQBR w HChc - 0 1";
+        assert!(session.handle_command(&format!("position fen {}", fen)));
+        assert_eq!(session.game_state().to_fen(), fen);
+
+        let fen = "1r2k1r1/pppppppp/8/8/8/8/PPPPPPPP/1R2K1R1 w GBgb - 0 1";
+        let mut game = GameState::new(Some(1));
+        game.set_chess960(true);
+        assert!(game.set_fen_position(fen));
+        let moves = game.generate_moves();
+        assert!(moves.contains(&"e1g1".to_string()));
+        assert!(moves.contains(&"e1b1".to_string()));
+
+        assert!(session.handle_command(&format!("position fen {} moves e1g1 e8b8", fen)));
+        assert_eq!(
+            session.game_state().to_fen(),
+            "2kr2r1/pppppppp/8/8/8/8/PPPPPPPP/1R3RK1 w - - 2 2"
+        );
+    }
+
+    #[test]
+    fn test_chess960_search_finds_a_move() {
+        let session = Session {
+            commands: vec![
+                "setoption name UCI_Chess960 value true".to_string(),
+                "position fen nnrbkqbr/pppppppp/8/8/8/8/PPPPPPPP/NNRBKQBR w HChc - 0 1".to_string(),
+                "go depth 3".to_string(),
+            ],
+            answers: Vec::new(),
+        };
+        let report =
+            replay_session(Path::new(env!("CARGO_BIN_EXE_enrust")), &session).expect("engine runs");
+        let best_move = report
+            .mismatches
+            .into_iter()
+            .filter_map(|mismatch| mismatch.actual)
+            .find(|line| line.starts_with("bestmove"))
+            .expect("the search answers");
+
+        assert_ne!(best_move, "bestmove 0000");
+    }
+
+    #[test]
+    fn test_session_handles_commands_until_quit() {
+        let mut session = UciSession::new(GameState::new(Some(1)));
+
+        assert!(session.handle_command("position startpos moves e2e4\n"));
+        assert!(session.handle_command("position fen 4k3/8/8/8/8/8/8/4K3 w - é 0 1"));
+        assert!(session.handle_command("setoption name Threads value ü"));
+        assert!(session.handle_command("flip"));
+        assert!(session.handle_command(""));
+        assert!(session.handle_command("ééé"));
+        assert_eq!(
+            session.game_state().to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        assert!(!session.handle_command("quit"));
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod variant_tests {
+    use enrust::game_state::Score;
+    use enrust::game_state::analysis::{AnalysisConfig, AnalysisResult};
+    use enrust::game_state::{Color, GameState};
+
+    fn setup_variant(variant: &str, fen: &str) -> GameState {
+        let mut game = GameState::new(Some(16));
+        assert!(
+            game.set_variant(variant),
+            "Variant not compiled: {}",
+            variant
+        );
+        assert!(game.set_fen_position(fen), "Failed to set FEN: {}", fen);
+        game
+    }
+
+    fn sorted_moves(game: &mut GameState) -> Vec<String> {
+        let mut moves = game.generate_moves();
+        moves.sort();
+        moves
+    }
+
+    fn analyze(game: &GameState, fen: &str, depth: u8) -> AnalysisResult {
+        let config = AnalysisConfig {
+            depth,
+            ..AnalysisConfig::default()
+        };
+        game.analyze_batch(&[fen], &config).remove(0)
+    }
+
+    #[test]
+    fn test_standard_chess_is_the_default() {
+        let mut game = GameState::new(Some(16));
+        assert_eq!(game.variant(), "chess");
+        assert!(game.set_variant("Chess"));
+        assert!(!game.set_variant("crazyhouse"));
+        assert_eq!(game.variant(), "chess");
+    }
+
+    #[test]
+    fn test_standard_rules_keep_the_king_safe() {
+        let mut game = setup_variant("chess", "4k3/8/8/8/8/8/8/R3K1r1 w Q - 0 1");
+        assert!(game.get_chess_board().is_in_check(Color::White));
+        assert_eq!(sorted_moves(&mut game), vec!["e1d2", "e1e2", "e1f2"]);
+    }
+
+    #[test]
+    fn test_stalemate_scores_as_draw() {
+        let game = GameState::new(Some(16));
+        let fen = "7k/8/6Q1/8/8/8/8/K7 b - - 0 1";
+        let result = analyze(&game, fen, 3);
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, Score::DRAW);
+    }
+
+    #[cfg(feature = "antichess")]
+    mod antichess {
+        use super::*;
+
+        const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+
+        #[test]
+        fn test_antichess_perft() {
+            let mut game = setup_variant("antichess", START);
+            assert_eq!(game.perft_debug(1, false), 20);
+            assert_eq!(game.perft_debug(2, false), 400);
+            assert_eq!(game.perft_debug(3, false), 8067);
+            assert_eq!(game.perft_debug(4, false), 153299);
+        }
+
+        #[test]
+        fn test_captures_are_compulsory() {
+            let mut game = setup_variant("antichess", "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+            assert_eq!(sorted_moves(&mut game), vec!["e4d5"]);
+
+            // En passant captures count as well
+            let mut game = setup_variant("antichess", "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+            assert_eq!(sorted_moves(&mut game), vec!["e5d6"]);
+        }
+
+        #[test]
+        fn test_king_is_an_ordinary_piece() {
+            let mut game = setup_variant("antichess", "4k3/8/8/8/8/8/8/R3K1r1 w Q - 0 1");
+            // There is no check, so the king may stay attacked, and no castling
+            assert!(!game.get_chess_board().is_in_check(Color::White));
+            let moves = game.generate_moves();
+            assert!(moves.contains(&"a1a2".to_string()));
+            assert!(!moves.contains(&"e1c1".to_string()));
+        }
+
+        #[test]
+        fn test_bare_kings_are_not_a_draw() {
+            let game = setup_variant("antichess", "8/8/4k3/8/8/3K4/8/8 w - - 0 1");
+            assert!(!game.get_chess_board().has_insufficient_material());
+        }
+
+        #[test]
+        fn test_side_without_moves_wins() {
+            let game = setup_variant("antichess", "8/8/8/8/8/8/8/8 w - - 0 1");
+            let result = analyze(&game, "8/8/8/8/8/8/8/8 w - - 0 1", 3);
+            assert_eq!(result.best_move, None);
+            assert_eq!(result.score, Score::mate_in(0));
+        }
+    }
+
+    #[cfg(feature = "atomic")]
+    mod atomic {
+        use super::*;
+
+        const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        #[test]
+        fn test_atomic_perft() {
+            let mut game = setup_variant("atomic", START);
+            assert_eq!(game.perft_debug(1, false), 20);
+            assert_eq!(game.perft_debug(2, false), 400);
+            assert_eq!(game.perft_debug(3, false), 8902);
+            assert_eq!(game.perft_debug(4, false), 197326);
+        }
+
+        #[test]
+        fn test_capture_explodes_surrounding_pieces() {
+            let mut game = setup_variant("atomic", "4k3/8/2nqb3/2Pp4/8/8/3R4/4K3 w - - 0 1");
+            assert!(game.make_move("d2d5"));
+
+            let expected = "4k3/8/8/2P5/8/8/8/4K3 b - - 0 1";
+            assert_eq!(game.to_fen(), expected);
+
+            // The hash must match the one of the position set from scratch
+            let key = game.polyglot_key();
+            assert!(game.set_fen_position(expected));
+            assert_eq!(game.polyglot_key(), key);
+        }
+
+        #[test]
+        fn test_en_passant_explodes_around_its_destination() {
+            let mut game = setup_variant("atomic", "4k3/2n5/8/3pP3/8/8/8/4K3 w - d6 0 1");
+            assert!(game.make_move("e5d6"));
+
+            // Both pawns and the knight next to d6 are gone
+            let expected = "4k3/8/8/8/8/8/8/4K3 b - - 0 1";
+            assert_eq!(game.to_fen(), expected);
+
+            let key = game.polyglot_key();
+            assert!(game.set_fen_position(expected));
+            assert_eq!(game.polyglot_key(), key);
+        }
+
+        #[test]
+        fn test_king_cannot_capture() {
+            let mut game = setup_variant("atomic", "4k3/8/8/8/8/8/4p3/4K3 w - - 0 1");
+            assert_eq!(sorted_moves(&mut game), vec!["e1d2", "e1f2"]);
+        }
+
+        #[test]
+        fn test_touching_kings_are_never_in_check() {
+            let mut game = setup_variant("atomic", "8/8/8/8/8/8/3k4/r2K4 w - - 0 1");
+            assert!(!game.get_chess_board().is_in_check(Color::White));
+            assert!(game.generate_moves().contains(&"d1e1".to_string()));
+        }
+
+        #[test]
+        fn test_exploding_the_king_wins() {
+            let game = setup_variant("atomic", "4k3/4p3/8/8/8/8/4R3/4K3 w - - 0 1");
+            let result = analyze(&game, "4k3/4p3/8/8/8/8/4R3/4K3 w - - 0 1", 2);
+            assert_eq!(result.best_move.as_deref(), Some("e2e7"));
+            assert!(result.score.is_mate());
+        }
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod version_tests {
+    use std::process::Command;
+
+    use enrust::game_state::version::{
+        BUILD_DATE, GIT_HASH, VERSION, build_features, engine_name, version_info,
+    };
+
+    #[test]
+    fn test_engine_name_identifies_the_build() {
+        assert_eq!(
+            engine_name(),
+            format!("EnRust {} {} {}", VERSION, GIT_HASH, BUILD_DATE)
+        );
+        assert_eq!(VERSION, env!("CARGO_PKG_VERSION"));
+        assert!(!GIT_HASH.is_empty() && !GIT_HASH.contains(char::is_whitespace));
+    }
+
+    #[test]
+    fn test_build_date_is_a_calendar_date() {
+        let parts: Vec<u32> = BUILD_DATE
+            .split('-')
+            .map(|part| part.parse().expect("numeric date"))
+            .collect();
+
+        assert_eq!(BUILD_DATE.len(), 10, "{}", BUILD_DATE);
+        assert!(parts[0] >= 2025);
+        assert!((1..=12).contains(&parts[1]));
+        assert!((1..=31).contains(&parts[2]));
+    }
+
+    #[test]
+    fn test_build_features_follow_the_cargo_features() {
+        let features = build_features();
+
+        assert_eq!(features.contains(&"tune"), cfg!(feature = "tune"));
+        assert_eq!(features.contains(&"simd"), cfg!(feature = "simd"));
+        assert_eq!(features.contains(&"antichess"), cfg!(feature = "antichess"));
+        assert_eq!(features.contains(&"interop"), cfg!(feature = "interop"));
+    }
+
+    #[test]
+    fn test_version_flag_prints_the_build_details() {
+        let output = Command::new(env!("CARGO_BIN_EXE_enrust"))
+            .arg("--version")
+            .output()
+            .expect("engine should run");
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(stdout, version_info());
+        let mut lines = stdout.lines();
+        assert_eq!(lines.next(), Some(engine_name().as_str()));
+        assert!(lines.next().unwrap().starts_with("features: "));
+        assert!(lines.next().unwrap().starts_with("nnue: "));
+    }
+}